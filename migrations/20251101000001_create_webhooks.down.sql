@@ -0,0 +1,13 @@
+-- Drop triggers first
+DROP TRIGGER IF EXISTS update_event_outbox_updated_at;
+DROP TRIGGER IF EXISTS update_webhooks_updated_at;
+
+-- Drop indexes
+DROP INDEX IF EXISTS idx_event_outbox_status_next;
+DROP INDEX IF EXISTS idx_event_outbox_webhook_id;
+DROP INDEX IF EXISTS idx_webhooks_user_active;
+DROP INDEX IF EXISTS idx_webhooks_user_id;
+
+-- Drop Event_Outbox and Webhooks tables
+DROP TABLE IF EXISTS event_outbox;
+DROP TABLE IF EXISTS webhooks;
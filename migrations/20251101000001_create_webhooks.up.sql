@@ -0,0 +1,67 @@
+CREATE TABLE IF NOT EXISTS webhooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    
+    -- Comma-separated list of subscribed event types, or '*' for all events
+    -- Example: 'transaction.created,transaction.deleted'
+    event_types TEXT NOT NULL DEFAULT '*',
+    
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_webhooks_updated_at
+    AFTER UPDATE ON webhooks
+    FOR EACH ROW
+BEGIN
+    UPDATE webhooks 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_webhooks_user_id ON webhooks(user_id);
+CREATE INDEX IF NOT EXISTS idx_webhooks_user_active ON webhooks(user_id, is_active);
+
+CREATE TABLE IF NOT EXISTS event_outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    -- One outbox row per (event, subscribed webhook), written in the same
+    -- DB transaction as the domain change that produced the event
+    webhook_id INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    
+    -- Delivery state: pending (waiting for dispatch or retry), delivered, failed (gave up)
+    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    last_response_status INTEGER,
+    delivered_at TIMESTAMP,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_event_outbox_updated_at
+    AFTER UPDATE ON event_outbox
+    FOR EACH ROW
+BEGIN
+    UPDATE event_outbox 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_event_outbox_webhook_id ON event_outbox(webhook_id);
+CREATE INDEX IF NOT EXISTS idx_event_outbox_status_next 
+    ON event_outbox(status, next_attempt_at);
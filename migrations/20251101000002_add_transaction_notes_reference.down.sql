@@ -0,0 +1,6 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transactions_reference;
+
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN reference;
+ALTER TABLE transactions DROP COLUMN notes;
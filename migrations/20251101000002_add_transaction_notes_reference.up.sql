@@ -0,0 +1,5 @@
+-- Long-form memo and bank reference (check number, bank reference) for transactions
+ALTER TABLE transactions ADD COLUMN notes TEXT;
+ALTER TABLE transactions ADD COLUMN reference TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_reference ON transactions(reference);
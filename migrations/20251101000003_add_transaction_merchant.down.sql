@@ -0,0 +1,7 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transactions_merchant;
+
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN merchant_country;
+ALTER TABLE transactions DROP COLUMN merchant_city;
+ALTER TABLE transactions DROP COLUMN merchant;
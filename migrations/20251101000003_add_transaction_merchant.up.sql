@@ -0,0 +1,6 @@
+-- Merchant metadata for transactions (normalized payee name and location)
+ALTER TABLE transactions ADD COLUMN merchant TEXT;
+ALTER TABLE transactions ADD COLUMN merchant_city TEXT;
+ALTER TABLE transactions ADD COLUMN merchant_country TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_merchant ON transactions(merchant);
@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_recurring_transactions_classification;
+
+-- Drop columns
+ALTER TABLE recurring_transactions DROP COLUMN classification;
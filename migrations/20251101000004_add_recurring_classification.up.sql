@@ -0,0 +1,7 @@
+-- Fixed (rent, subscriptions) vs variable (utilities, groceries) recurring items,
+-- used by forecasts to separate committed obligations from discretionary spending
+ALTER TABLE recurring_transactions ADD COLUMN classification TEXT NOT NULL DEFAULT 'fixed'
+    CHECK(classification IN ('fixed', 'variable'));
+
+CREATE INDEX IF NOT EXISTS idx_recurring_transactions_classification 
+    ON recurring_transactions(classification);
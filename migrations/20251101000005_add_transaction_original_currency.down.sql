@@ -0,0 +1,7 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transactions_original_currency;
+
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN exchange_rate;
+ALTER TABLE transactions DROP COLUMN original_currency;
+ALTER TABLE transactions DROP COLUMN original_amount;
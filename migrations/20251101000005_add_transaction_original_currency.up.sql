@@ -0,0 +1,8 @@
+-- Original (foreign) amount of a transaction, e.g. a 50 EUR purchase on a CAD card.
+-- amount stays the posted amount in the account currency; exchange_rate is the
+-- applied rate (account currency per unit of original currency).
+ALTER TABLE transactions ADD COLUMN original_amount REAL;
+ALTER TABLE transactions ADD COLUMN original_currency TEXT;
+ALTER TABLE transactions ADD COLUMN exchange_rate REAL CHECK(exchange_rate IS NULL OR exchange_rate > 0);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_original_currency ON transactions(original_currency);
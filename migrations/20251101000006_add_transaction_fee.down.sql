@@ -0,0 +1,6 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transactions_fee_category_id;
+
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN fee_category_id;
+ALTER TABLE transactions DROP COLUMN fee;
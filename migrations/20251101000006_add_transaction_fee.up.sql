@@ -0,0 +1,7 @@
+-- Bank/FX fees charged on a transaction or transfer, tracked apart from the amount.
+-- The fee is always debited from the account; fee_category_id lets it show up
+-- as its own category in analytics (e.g. "Bank Fees").
+ALTER TABLE transactions ADD COLUMN fee REAL NOT NULL DEFAULT 0.0 CHECK(fee >= 0);
+ALTER TABLE transactions ADD COLUMN fee_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_fee_category_id ON transactions(fee_category_id);
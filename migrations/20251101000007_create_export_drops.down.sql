@@ -0,0 +1,6 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_export_drops_created_at;
+DROP INDEX IF EXISTS idx_export_drops_directory;
+
+-- Drop table
+DROP TABLE IF EXISTS export_drops;
@@ -0,0 +1,19 @@
+-- Scheduled export drops: one row per file written by the export daemon.
+-- last_transaction_id is the watermark, so every drop only contains
+-- transactions created since the previous drop into the same directory.
+CREATE TABLE IF NOT EXISTS export_drops (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    directory TEXT NOT NULL,
+    format TEXT NOT NULL CHECK(format IN ('csv', 'json')),
+    schedule TEXT NOT NULL CHECK(schedule IN ('daily', 'weekly')),
+    
+    file_path TEXT NOT NULL,
+    row_count INTEGER NOT NULL DEFAULT 0,
+    last_transaction_id INTEGER NOT NULL DEFAULT 0,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_export_drops_directory ON export_drops(directory, format);
+CREATE INDEX IF NOT EXISTS idx_export_drops_created_at ON export_drops(created_at);
@@ -0,0 +1,6 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_accounts_user_order;
+
+-- Drop columns
+ALTER TABLE accounts DROP COLUMN is_favorite;
+ALTER TABLE accounts DROP COLUMN sort_order;
@@ -0,0 +1,5 @@
+-- User-defined account ordering: favorites first, then by sort_order
+ALTER TABLE accounts ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE accounts ADD COLUMN is_favorite BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_user_order ON accounts(user_id, is_favorite, sort_order);
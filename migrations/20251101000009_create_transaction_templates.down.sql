@@ -0,0 +1,9 @@
+-- Drop trigger and indexes
+DROP TRIGGER IF EXISTS update_transaction_templates_updated_at;
+DROP INDEX IF EXISTS idx_transaction_templates_user_id;
+
+-- Drop table
+DROP TABLE IF EXISTS transaction_templates;
+
+-- Drop columns
+ALTER TABLE accounts DROP COLUMN default_category_id;
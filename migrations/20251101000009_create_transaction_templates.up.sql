@@ -0,0 +1,36 @@
+-- Default category applied to new transactions on an account when no split is given
+ALTER TABLE accounts ADD COLUMN default_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL;
+
+-- Saved transaction templates for frequent entries, e.g. "Coffee - $4.50 - Dining - Card A"
+CREATE TABLE IF NOT EXISTS transaction_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    
+    account_id INTEGER NOT NULL,
+    category_id INTEGER,
+    amount REAL NOT NULL CHECK(amount > 0),
+    transaction_type TEXT NOT NULL DEFAULT 'expense' CHECK(transaction_type IN ('income', 'expense', 'transfer')),
+    description TEXT,
+    merchant TEXT,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_transaction_templates_updated_at
+    AFTER UPDATE ON transaction_templates
+    FOR EACH ROW
+BEGIN
+    UPDATE transaction_templates 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_transaction_templates_user_id ON transaction_templates(user_id);
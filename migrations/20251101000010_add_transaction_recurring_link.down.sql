@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transactions_recurring_transaction_id;
+
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN recurring_transaction_id;
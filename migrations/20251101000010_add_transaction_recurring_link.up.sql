@@ -0,0 +1,5 @@
+-- Link transactions generated by the recurring processor back to their schedule,
+-- so recurring executions can be told apart from manual entries (activity feed)
+ALTER TABLE transactions ADD COLUMN recurring_transaction_id INTEGER REFERENCES recurring_transactions(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_recurring_transaction_id ON transactions(recurring_transaction_id);
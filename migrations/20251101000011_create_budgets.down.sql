@@ -0,0 +1,6 @@
+-- Drop trigger and indexes
+DROP TRIGGER IF EXISTS update_budgets_updated_at;
+DROP INDEX IF EXISTS idx_budgets_user_id;
+
+-- Drop table
+DROP TABLE IF EXISTS budgets;
@@ -0,0 +1,29 @@
+-- Monthly spending limit per category; actual spend is computed from
+-- expense splits (and fees booked to the category) in each calendar month
+CREATE TABLE IF NOT EXISTS budgets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    category_id INTEGER NOT NULL,
+    monthly_limit REAL NOT NULL CHECK(monthly_limit > 0),
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    
+    UNIQUE(user_id, category_id)
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_budgets_updated_at
+    AFTER UPDATE ON budgets
+    FOR EACH ROW
+BEGIN
+    UPDATE budgets 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_budgets_user_id ON budgets(user_id);
@@ -0,0 +1,55 @@
+-- no-transaction
+-- Allow investment and cash accounts. SQLite cannot alter a CHECK constraint,
+-- so the table is rebuilt; foreign keys are switched off for the swap so the
+-- drop does not cascade into transactions, recurring rules and templates.
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TABLE accounts_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    account_type TEXT NOT NULL CHECK(account_type IN ('checking', 'savings', 'credit_card', 'investment', 'cash')),
+    bank_name TEXT,
+    currency TEXT NOT NULL DEFAULT 'CAD',
+    
+    initial_balance REAL NOT NULL DEFAULT 0.0,
+    current_balance REAL NOT NULL DEFAULT 0.0,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    is_favorite BOOLEAN NOT NULL DEFAULT 0,
+    default_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+INSERT INTO accounts_new (id, user_id, name, account_type, bank_name, currency, initial_balance, current_balance,
+                          created_at, updated_at, sort_order, is_favorite, default_category_id)
+SELECT id, user_id, name, account_type, bank_name, currency, initial_balance, current_balance,
+       created_at, updated_at, sort_order, is_favorite, default_category_id
+FROM accounts;
+
+DROP TABLE accounts;
+ALTER TABLE accounts_new RENAME TO accounts;
+
+CREATE TRIGGER IF NOT EXISTS update_accounts_updated_at
+    AFTER UPDATE ON accounts
+    FOR EACH ROW
+BEGIN
+    UPDATE accounts 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_user_id ON accounts(user_id);
+CREATE INDEX IF NOT EXISTS idx_accounts_type ON accounts(account_type);
+CREATE INDEX IF NOT EXISTS idx_accounts_currency ON accounts(currency);
+CREATE INDEX IF NOT EXISTS idx_accounts_user_order ON accounts(user_id, is_favorite, sort_order);
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
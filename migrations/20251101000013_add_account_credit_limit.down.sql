@@ -0,0 +1,2 @@
+-- Drop columns
+ALTER TABLE accounts DROP COLUMN credit_limit;
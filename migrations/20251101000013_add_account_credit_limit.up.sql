@@ -0,0 +1,2 @@
+-- Optional credit limit for credit card accounts, used to report utilization
+ALTER TABLE accounts ADD COLUMN credit_limit REAL CHECK(credit_limit IS NULL OR credit_limit > 0);
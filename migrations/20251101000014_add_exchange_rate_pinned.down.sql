@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_exchange_rates_pinned_pair;
+
+-- Drop columns
+ALTER TABLE exchange_rates DROP COLUMN pinned;
@@ -0,0 +1,6 @@
+-- A pinned manual rate is used for its pair instead of newer quotes until it is unpinned.
+-- At most one rate per pair can be pinned.
+ALTER TABLE exchange_rates ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT 0 CHECK(pinned = 0 OR source = 'manual');
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_exchange_rates_pinned_pair
+    ON exchange_rates(from_currency, to_currency) WHERE pinned = 1;
@@ -0,0 +1,4 @@
+-- Currency a recurring item is billed in (e.g. a USD subscription on a CAD card).
+-- NULL means the account currency; otherwise each occurrence is converted at the
+-- rate of its date and the original amount is kept on the transaction.
+ALTER TABLE recurring_transactions ADD COLUMN currency TEXT;
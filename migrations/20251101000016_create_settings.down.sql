@@ -0,0 +1,5 @@
+-- Drop triggers first
+DROP TRIGGER IF EXISTS update_settings_updated_at;
+
+-- Drop Settings table
+DROP TABLE IF EXISTS settings;
@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS settings (
+    user_id INTEGER NOT NULL,
+    
+    -- Setting name and its value as text, e.g. 'default_screen' = 'transactions'
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    PRIMARY KEY (user_id, key),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_settings_updated_at
+    AFTER UPDATE ON settings
+    FOR EACH ROW
+BEGIN
+    UPDATE settings 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE user_id = NEW.user_id AND key = NEW.key;
+END;
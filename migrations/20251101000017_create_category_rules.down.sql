@@ -0,0 +1,6 @@
+-- Drop trigger and indexes
+DROP TRIGGER IF EXISTS update_category_rules_updated_at;
+DROP INDEX IF EXISTS idx_category_rules_user_id;
+
+-- Drop table
+DROP TABLE IF EXISTS category_rules;
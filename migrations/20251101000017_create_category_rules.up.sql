@@ -0,0 +1,29 @@
+-- Categorization rules: an imported transaction whose description contains
+-- the pattern (case-insensitive) is suggested the rule's category
+CREATE TABLE IF NOT EXISTS category_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    pattern TEXT NOT NULL CHECK(length(trim(pattern)) > 0),
+    category_id INTEGER NOT NULL,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    
+    UNIQUE(user_id, pattern)
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_category_rules_updated_at
+    AFTER UPDATE ON category_rules
+    FOR EACH ROW
+BEGIN
+    UPDATE category_rules 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_category_rules_user_id ON category_rules(user_id);
@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_scrape_runs_started_at;
+
+-- Drop table
+DROP TABLE IF EXISTS scrape_runs;
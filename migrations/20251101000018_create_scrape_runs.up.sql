@@ -0,0 +1,22 @@
+-- One row per exchange-rate scrape run, kept so failed refreshes are visible
+CREATE TABLE IF NOT EXISTS scrape_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    provider TEXT NOT NULL,
+    -- Comma-separated base currencies requested, e.g. 'CAD,USD,EUR,GBP'
+    currencies TEXT NOT NULL,
+    
+    started_at TIMESTAMP NOT NULL,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    rates_saved INTEGER NOT NULL DEFAULT 0,
+    currencies_skipped INTEGER NOT NULL DEFAULT 0,
+    
+    -- success: every currency fetched; partial: some failed; failed: none fetched
+    status TEXT NOT NULL CHECK(status IN ('success', 'partial', 'failed')),
+    -- One "CODE: message" line per failed currency
+    errors TEXT,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_scrape_runs_started_at ON scrape_runs(started_at);
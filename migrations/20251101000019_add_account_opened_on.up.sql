@@ -0,0 +1,12 @@
+-- Date the account was opened: initial_balance is the balance on that day and
+-- no transaction may predate it. Existing accounts are backfilled with their
+-- creation date, or their first transaction if that is earlier.
+ALTER TABLE accounts ADD COLUMN opened_on DATE;
+
+UPDATE accounts SET opened_on = MIN(
+    date(created_at),
+    COALESCE(
+        (SELECT MIN(date(transaction_date)) FROM transactions WHERE account_id = accounts.id),
+        date(created_at)
+    )
+);
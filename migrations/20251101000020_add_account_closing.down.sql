@@ -0,0 +1,3 @@
+-- Drop columns
+ALTER TABLE accounts DROP COLUMN closed_on;
+ALTER TABLE accounts DROP COLUMN is_archived;
@@ -0,0 +1,4 @@
+-- Closed accounts are archived: their balance was moved out in a closing
+-- transfer on closed_on and they take no new transactions.
+ALTER TABLE accounts ADD COLUMN is_archived BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE accounts ADD COLUMN closed_on DATE;
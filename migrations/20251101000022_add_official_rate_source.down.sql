@@ -0,0 +1,33 @@
+-- Drop rows
+DELETE FROM exchange_rates WHERE source = 'official';
+
+-- Rebuild table without 'official'
+CREATE TABLE exchange_rates_old (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    from_currency TEXT NOT NULL,
+    to_currency TEXT NOT NULL,
+    rate REAL NOT NULL,
+    rate_date TIMESTAMP NOT NULL,
+    source TEXT NOT NULL CHECK(source IN ('api', 'bank', 'manual', 'scraper')),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    pinned BOOLEAN NOT NULL DEFAULT 0 CHECK(pinned = 0 OR source = 'manual'),
+    UNIQUE(from_currency, to_currency, rate_date)
+);
+
+INSERT INTO exchange_rates_old SELECT id, from_currency, to_currency, rate, rate_date, source, created_at, updated_at, pinned FROM exchange_rates;
+DROP TABLE exchange_rates;
+ALTER TABLE exchange_rates_old RENAME TO exchange_rates;
+
+CREATE TRIGGER IF NOT EXISTS update_exchange_rates_updated_at
+    AFTER UPDATE ON exchange_rates
+    FOR EACH ROW
+BEGIN
+    UPDATE exchange_rates SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_currencies ON exchange_rates(from_currency, to_currency);
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_date ON exchange_rates(rate_date);
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_currencies_date ON exchange_rates(from_currency, to_currency, rate_date DESC);
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_source ON exchange_rates(source);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_exchange_rates_pinned_pair ON exchange_rates(from_currency, to_currency) WHERE pinned = 1;
@@ -0,0 +1,3 @@
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN base_currency;
+ALTER TABLE transactions DROP COLUMN base_amount;
@@ -0,0 +1,6 @@
+-- Value of a transaction in its owner's base currency (base_currency setting),
+-- converted at the rate of the transaction date when it was recorded, so
+-- historical figures don't move with today's rates. NULL when the user has no
+-- base currency, the account already uses it, or no rate was known.
+ALTER TABLE transactions ADD COLUMN base_amount REAL;
+ALTER TABLE transactions ADD COLUMN base_currency TEXT;
@@ -0,0 +1,2 @@
+-- Drop columns
+ALTER TABLE categories DROP COLUMN bucket;
@@ -0,0 +1,2 @@
+-- 50/30/20-style bucket of a category; NULL leaves it out of the bucket split
+ALTER TABLE categories ADD COLUMN bucket TEXT CHECK(bucket IS NULL OR bucket IN ('needs', 'wants', 'savings'));
@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transactions_linked;
+
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN linked_transaction_id;
@@ -0,0 +1,5 @@
+-- The other leg of a transfer between two accounts: the debit on the source
+-- account and the credit on the destination point at each other.
+ALTER TABLE transactions ADD COLUMN linked_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_linked ON transactions(linked_transaction_id);
@@ -0,0 +1,11 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_exchange_rates_rate_source;
+
+-- Drop columns
+ALTER TABLE exchange_rates DROP COLUMN rate_source_id;
+
+-- Drop triggers
+DROP TRIGGER IF EXISTS update_rate_sources_updated_at;
+
+-- Drop tables
+DROP TABLE IF EXISTS rate_sources;
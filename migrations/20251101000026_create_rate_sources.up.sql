@@ -0,0 +1,39 @@
+-- Named providers behind exchange rates. exchange_rates.source stays the kind
+-- of source used for priorities ('scraper', 'official', ...); rate_source_id
+-- says which provider of that kind a rate came from.
+CREATE TABLE IF NOT EXISTS rate_sources (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE, -- e.g. "x-rates.com"
+    kind TEXT NOT NULL CHECK(kind IN ('api', 'bank', 'manual', 'scraper', 'official')),
+    url TEXT,
+    reliability_notes TEXT,
+
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER IF NOT EXISTS update_rate_sources_updated_at
+    AFTER UPDATE ON rate_sources
+    FOR EACH ROW
+BEGIN
+    UPDATE rate_sources
+    SET updated_at = CURRENT_TIMESTAMP
+    WHERE id = NEW.id;
+END;
+
+INSERT INTO rate_sources (name, kind, url, reliability_notes) VALUES
+    ('x-rates.com', 'scraper', 'https://www.x-rates.com/table/',
+     'Mid-market rates scraped from HTML tables; no published update schedule'),
+    ('bankofcanada.ca', 'official', 'https://www.bankofcanada.ca/valet/',
+     'Daily reference rates against CAD, published once per business day'),
+    ('Manual entry', 'manual', NULL,
+     'Entered by hand; only as accurate as the person who typed it');
+
+ALTER TABLE exchange_rates ADD COLUMN rate_source_id INTEGER REFERENCES rate_sources(id);
+
+-- The stored kinds each had a single provider so far
+UPDATE exchange_rates
+SET rate_source_id = (SELECT id FROM rate_sources WHERE rate_sources.kind = exchange_rates.source)
+WHERE source IN ('scraper', 'official', 'manual');
+
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_rate_source ON exchange_rates(rate_source_id);
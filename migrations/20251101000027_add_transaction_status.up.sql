@@ -0,0 +1,5 @@
+-- Pending transactions are recorded but not yet posted: they stay out of
+-- current_balance until cleared. Void ones are kept for the record only.
+ALTER TABLE transactions ADD COLUMN status TEXT NOT NULL DEFAULT 'cleared' CHECK(status IN ('pending', 'cleared', 'void'));
+
+CREATE INDEX IF NOT EXISTS idx_transactions_status ON transactions(status);
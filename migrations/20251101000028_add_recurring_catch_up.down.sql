@@ -0,0 +1,2 @@
+-- Drop columns
+ALTER TABLE recurring_transactions DROP COLUMN catch_up;
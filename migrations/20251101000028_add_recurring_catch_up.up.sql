@@ -0,0 +1,4 @@
+-- What processing does with occurrences missed while the app wasn't running:
+-- 'all' posts every one of them, 'latest' posts only the most recent and
+-- skips ahead
+ALTER TABLE recurring_transactions ADD COLUMN catch_up TEXT NOT NULL DEFAULT 'all' CHECK(catch_up IN ('all', 'latest'));
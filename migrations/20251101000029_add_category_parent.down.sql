@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_categories_parent;
+
+-- Drop columns
+ALTER TABLE categories DROP COLUMN parent_category_id;
@@ -0,0 +1,5 @@
+-- Sub-categories, e.g. Food > Groceries and Food > Restaurants. Nesting is one
+-- level deep; deleting a parent turns its sub-categories into top-level ones.
+ALTER TABLE categories ADD COLUMN parent_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_category_id);
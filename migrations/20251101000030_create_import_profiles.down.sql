@@ -0,0 +1,6 @@
+-- Drop trigger and indexes
+DROP TRIGGER IF EXISTS update_import_profiles_updated_at;
+DROP INDEX IF EXISTS idx_import_profiles_user_id;
+
+-- Drop table
+DROP TABLE IF EXISTS import_profiles;
@@ -0,0 +1,41 @@
+-- Saved CSV mappings per bank, so next month's statement imports with the
+-- same columns, date format and sign convention into the same account
+CREATE TABLE IF NOT EXISTS import_profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL CHECK(length(trim(name)) > 0), -- e.g. "Chase checking"
+    account_id INTEGER, -- Default account to import into
+    
+    -- Header names to read each field from; NULL uses the built-in aliases
+    date_column TEXT,
+    description_column TEXT,
+    amount_column TEXT,
+    debit_column TEXT,
+    credit_column TEXT,
+    reference_column TEXT,
+    date_format TEXT, -- chrono format; NULL detects it per file
+    
+    -- How a signed amount column marks expenses
+    amount_sign TEXT NOT NULL DEFAULT 'negative_expenses' CHECK(amount_sign IN ('negative_expenses', 'positive_expenses')),
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE SET NULL,
+    
+    UNIQUE(user_id, name)
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_import_profiles_updated_at
+    AFTER UPDATE ON import_profiles
+    FOR EACH ROW
+BEGIN
+    UPDATE import_profiles 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_import_profiles_user_id ON import_profiles(user_id);
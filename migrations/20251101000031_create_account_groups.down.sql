@@ -0,0 +1,12 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_accounts_account_group_id;
+
+-- Drop columns
+ALTER TABLE accounts DROP COLUMN account_group_id;
+
+-- Drop trigger and indexes
+DROP TRIGGER IF EXISTS update_account_groups_updated_at;
+DROP INDEX IF EXISTS idx_account_groups_user_id;
+
+-- Drop table
+DROP TABLE IF EXISTS account_groups;
@@ -0,0 +1,32 @@
+-- User-defined account groups ("Liquid", "Retirement", "Joint") for grouped
+-- balances and net worth. An account is in at most one group.
+CREATE TABLE IF NOT EXISTS account_groups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL CHECK(length(trim(name)) > 0),
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    
+    UNIQUE(user_id, name)
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_account_groups_updated_at
+    AFTER UPDATE ON account_groups
+    FOR EACH ROW
+BEGIN
+    UPDATE account_groups 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_account_groups_user_id ON account_groups(user_id);
+
+-- Deleting a group leaves its accounts ungrouped
+ALTER TABLE accounts ADD COLUMN account_group_id INTEGER REFERENCES account_groups(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_account_group_id ON accounts(account_group_id);
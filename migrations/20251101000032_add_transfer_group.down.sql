@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transactions_transfer_group;
+
+-- Drop columns
+ALTER TABLE transactions DROP COLUMN transfer_group_id;
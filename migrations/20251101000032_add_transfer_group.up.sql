@@ -0,0 +1,11 @@
+-- Both legs of a transfer between two of a user's accounts share a
+-- transfer_group_id, the id of the debit leg. Internal transfers are left out
+-- of income and expense analytics.
+ALTER TABLE transactions ADD COLUMN transfer_group_id INTEGER;
+
+-- Existing transfers already point at each other through linked_transaction_id
+UPDATE transactions
+SET transfer_group_id = MIN(id, linked_transaction_id)
+WHERE linked_transaction_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_transfer_group ON transactions(transfer_group_id);
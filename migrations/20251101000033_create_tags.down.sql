@@ -0,0 +1,7 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_transaction_tags_tag_id;
+DROP INDEX IF EXISTS idx_tags_user_id;
+
+-- Drop tables
+DROP TABLE IF EXISTS transaction_tags;
+DROP TABLE IF EXISTS tags;
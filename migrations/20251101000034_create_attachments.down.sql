@@ -0,0 +1,5 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_attachments_transaction_id;
+
+-- Drop table
+DROP TABLE IF EXISTS attachments;
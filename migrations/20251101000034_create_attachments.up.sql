@@ -0,0 +1,17 @@
+-- Files (receipt images, PDFs) attached to transactions. The files live on
+-- disk under ATTACHMENT_DIR; storage_path is relative to it.
+CREATE TABLE IF NOT EXISTS attachments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    transaction_id INTEGER NOT NULL,
+    filename TEXT NOT NULL,     -- As uploaded, for downloads
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL CHECK(size_bytes > 0),
+    storage_path TEXT NOT NULL,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_transaction_id ON attachments(transaction_id);
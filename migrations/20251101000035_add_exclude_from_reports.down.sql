@@ -0,0 +1,3 @@
+-- Drop columns
+ALTER TABLE categories DROP COLUMN exclude_from_reports;
+ALTER TABLE transactions DROP COLUMN exclude_from_reports;
@@ -0,0 +1,5 @@
+-- Transactions (and every transaction filed under a category) flagged
+-- exclude_from_reports still move balances but are left out of income,
+-- spending and budget analytics: reimbursements, one-off anomalies and the like.
+ALTER TABLE transactions ADD COLUMN exclude_from_reports BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE categories ADD COLUMN exclude_from_reports BOOLEAN NOT NULL DEFAULT 0;
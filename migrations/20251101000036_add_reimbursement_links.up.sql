@@ -0,0 +1,5 @@
+-- An incoming reimbursement points at the expense it pays back. Category
+-- analytics net the expense against its reimbursements.
+ALTER TABLE transactions ADD COLUMN reimburses_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_reimburses ON transactions(reimburses_transaction_id);
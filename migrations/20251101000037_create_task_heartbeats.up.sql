@@ -0,0 +1,12 @@
+-- One row per background task of `serve`, updated after every run so other
+-- clients of the database (the TUI) can tell whether the tasks are healthy
+CREATE TABLE IF NOT EXISTS task_heartbeats (
+    task TEXT PRIMARY KEY,
+    
+    -- Expected time between runs; a task this far behind twice over is stalled
+    interval_seconds INTEGER NOT NULL,
+    last_run_at TIMESTAMP NOT NULL,
+    last_success_at TIMESTAMP,
+    -- Error of the latest run, NULL when it succeeded
+    last_error TEXT
+);
@@ -0,0 +1,6 @@
+-- Detach rates fetched from the provider, then drop it
+UPDATE exchange_rates
+SET rate_source_id = NULL
+WHERE rate_source_id = (SELECT id FROM rate_sources WHERE name = 'frankfurter.app');
+
+DELETE FROM rate_sources WHERE name = 'frankfurter.app';
@@ -0,0 +1,5 @@
+-- Second provider for fetched rates, used as a fallback for x-rates.com or
+-- picked with FX_PROVIDER=frankfurter
+INSERT OR IGNORE INTO rate_sources (name, kind, url, reliability_notes) VALUES
+    ('frankfurter.app', 'api', 'https://api.frankfurter.app/',
+     'European Central Bank reference rates, published once per business day around 16:00 CET');
@@ -0,0 +1,2 @@
+-- Drop table
+DROP TABLE IF EXISTS exchange_rates_archive;
@@ -0,0 +1,19 @@
+-- Intra-day exchange rates replaced by their day's close when the rates are
+-- compacted (see rate_compaction.rs). Same columns as exchange_rates, minus
+-- pinned (pinned rates are never archived), plus when the row moved here.
+CREATE TABLE IF NOT EXISTS exchange_rates_archive (
+    id INTEGER PRIMARY KEY, -- Id the rate had in exchange_rates
+    
+    from_currency TEXT NOT NULL,
+    to_currency TEXT NOT NULL,
+    rate REAL NOT NULL,
+    rate_date TIMESTAMP NOT NULL,
+    source TEXT NOT NULL,
+    rate_source_id INTEGER,
+    
+    created_at TIMESTAMP NOT NULL,
+    archived_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_archive_currencies_date
+    ON exchange_rates_archive(from_currency, to_currency, rate_date);
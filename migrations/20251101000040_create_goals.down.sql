@@ -0,0 +1,12 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_goal_accounts_account_id;
+
+-- Drop tables
+DROP TABLE IF EXISTS goal_accounts;
+
+-- Drop trigger and indexes
+DROP TRIGGER IF EXISTS update_goals_updated_at;
+DROP INDEX IF EXISTS idx_goals_user_id;
+
+-- Drop table
+DROP TABLE IF EXISTS goals;
@@ -0,0 +1,40 @@
+-- Savings goals: a target amount saved up in one or more linked accounts,
+-- optionally by a target date. Progress is the linked accounts' balance.
+CREATE TABLE IF NOT EXISTS goals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL CHECK(length(trim(name)) > 0),
+    target_amount REAL NOT NULL CHECK(target_amount > 0),
+    currency TEXT NOT NULL,
+    target_date DATE,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_goals_updated_at
+    AFTER UPDATE ON goals
+    FOR EACH ROW
+BEGIN
+    UPDATE goals 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_goals_user_id ON goals(user_id);
+
+-- Accounts the goal is saved in
+CREATE TABLE IF NOT EXISTS goal_accounts (
+    goal_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    
+    PRIMARY KEY (goal_id, account_id),
+    FOREIGN KEY (goal_id) REFERENCES goals(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goal_accounts_account_id ON goal_accounts(account_id);
@@ -0,0 +1,68 @@
+-- no-transaction
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+-- Drop indexes
+DROP INDEX IF EXISTS idx_loans_recurring_transaction_id;
+
+-- Drop table
+DROP TABLE IF EXISTS loans;
+
+-- Restore the account types; loan accounts become credit card accounts,
+-- which are liabilities too, as the table is rebuilt without them.
+CREATE TABLE accounts_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    account_type TEXT NOT NULL CHECK(account_type IN ('checking', 'savings', 'credit_card', 'investment', 'cash')),
+    bank_name TEXT,
+    currency TEXT NOT NULL DEFAULT 'CAD',
+    
+    initial_balance REAL NOT NULL DEFAULT 0.0,
+    current_balance REAL NOT NULL DEFAULT 0.0,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    is_favorite BOOLEAN NOT NULL DEFAULT 0,
+    default_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+    credit_limit REAL CHECK(credit_limit IS NULL OR credit_limit > 0),
+    opened_on DATE,
+    is_archived BOOLEAN NOT NULL DEFAULT 0,
+    closed_on DATE,
+    account_group_id INTEGER REFERENCES account_groups(id) ON DELETE SET NULL,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+INSERT INTO accounts_new (id, user_id, name, account_type, bank_name, currency, initial_balance, current_balance,
+                          created_at, updated_at, sort_order, is_favorite, default_category_id, credit_limit,
+                          opened_on, is_archived, closed_on, account_group_id)
+SELECT id, user_id, name, CASE account_type WHEN 'loan' THEN 'credit_card' ELSE account_type END, bank_name, currency, initial_balance, current_balance,
+       created_at, updated_at, sort_order, is_favorite, default_category_id, credit_limit,
+       opened_on, is_archived, closed_on, account_group_id
+FROM accounts;
+
+DROP TABLE accounts;
+ALTER TABLE accounts_new RENAME TO accounts;
+
+CREATE TRIGGER IF NOT EXISTS update_accounts_updated_at
+    AFTER UPDATE ON accounts
+    FOR EACH ROW
+BEGIN
+    UPDATE accounts 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_user_id ON accounts(user_id);
+CREATE INDEX IF NOT EXISTS idx_accounts_type ON accounts(account_type);
+CREATE INDEX IF NOT EXISTS idx_accounts_currency ON accounts(currency);
+CREATE INDEX IF NOT EXISTS idx_accounts_user_order ON accounts(user_id, is_favorite, sort_order);
+CREATE INDEX IF NOT EXISTS idx_accounts_account_group_id ON accounts(account_group_id);
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
@@ -0,0 +1,88 @@
+-- no-transaction
+-- Allow loan accounts; the table is rebuilt with foreign keys off, as in
+-- 20251101000012_extend_account_types.
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TABLE accounts_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    account_type TEXT NOT NULL CHECK(account_type IN ('checking', 'savings', 'credit_card', 'investment', 'cash', 'loan')),
+    bank_name TEXT,
+    currency TEXT NOT NULL DEFAULT 'CAD',
+    
+    initial_balance REAL NOT NULL DEFAULT 0.0,
+    current_balance REAL NOT NULL DEFAULT 0.0,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    is_favorite BOOLEAN NOT NULL DEFAULT 0,
+    default_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+    credit_limit REAL CHECK(credit_limit IS NULL OR credit_limit > 0),
+    opened_on DATE,
+    is_archived BOOLEAN NOT NULL DEFAULT 0,
+    closed_on DATE,
+    account_group_id INTEGER REFERENCES account_groups(id) ON DELETE SET NULL,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+INSERT INTO accounts_new (id, user_id, name, account_type, bank_name, currency, initial_balance, current_balance,
+                          created_at, updated_at, sort_order, is_favorite, default_category_id, credit_limit,
+                          opened_on, is_archived, closed_on, account_group_id)
+SELECT id, user_id, name, account_type, bank_name, currency, initial_balance, current_balance,
+       created_at, updated_at, sort_order, is_favorite, default_category_id, credit_limit,
+       opened_on, is_archived, closed_on, account_group_id
+FROM accounts;
+
+DROP TABLE accounts;
+ALTER TABLE accounts_new RENAME TO accounts;
+
+CREATE TRIGGER IF NOT EXISTS update_accounts_updated_at
+    AFTER UPDATE ON accounts
+    FOR EACH ROW
+BEGIN
+    UPDATE accounts 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_user_id ON accounts(user_id);
+CREATE INDEX IF NOT EXISTS idx_accounts_type ON accounts(account_type);
+CREATE INDEX IF NOT EXISTS idx_accounts_currency ON accounts(currency);
+CREATE INDEX IF NOT EXISTS idx_accounts_user_order ON accounts(user_id, is_favorite, sort_order);
+CREATE INDEX IF NOT EXISTS idx_accounts_account_group_id ON accounts(account_group_id);
+
+-- Terms of a loan account, and the recurring payment that pays it off
+CREATE TABLE IF NOT EXISTS loans (
+    account_id INTEGER PRIMARY KEY,
+    
+    principal REAL NOT NULL CHECK(principal > 0),
+    -- Nominal yearly rate in percent, compounded monthly
+    annual_rate REAL NOT NULL CHECK(annual_rate >= 0),
+    term_months INTEGER NOT NULL CHECK(term_months > 0),
+    first_payment_date DATE NOT NULL,
+    
+    payment_account_id INTEGER NOT NULL,
+    recurring_transaction_id INTEGER,
+    principal_category_id INTEGER NOT NULL,
+    interest_category_id INTEGER NOT NULL,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (payment_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (recurring_transaction_id) REFERENCES recurring_transactions(id) ON DELETE SET NULL,
+    FOREIGN KEY (principal_category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    FOREIGN KEY (interest_category_id) REFERENCES categories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_loans_recurring_transaction_id ON loans(recurring_transaction_id);
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
@@ -0,0 +1,8 @@
+-- Drop tables
+DROP TABLE IF EXISTS security_prices;
+
+-- Drop trigger
+DROP TRIGGER IF EXISTS update_holdings_updated_at;
+
+-- Drop table
+DROP TABLE IF EXISTS holdings;
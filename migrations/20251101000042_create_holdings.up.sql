@@ -0,0 +1,42 @@
+-- Investment holdings: a position in one security held in an investment
+-- account. Cost basis is the total paid for the position, in the account's
+-- currency; quotes are taken to be in it as well.
+CREATE TABLE IF NOT EXISTS holdings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    account_id INTEGER NOT NULL,
+    ticker TEXT NOT NULL CHECK(length(trim(ticker)) > 0), -- Upper case, e.g. "AAPL" or "VFV.TO"
+    quantity REAL NOT NULL CHECK(quantity > 0),
+    cost_basis REAL NOT NULL DEFAULT 0 CHECK(cost_basis >= 0),
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    UNIQUE(account_id, ticker)
+);
+
+-- Create trigger to automatically update updated_at on row modification
+CREATE TRIGGER IF NOT EXISTS update_holdings_updated_at
+    AFTER UPDATE ON holdings
+    FOR EACH ROW
+BEGIN
+    UPDATE holdings 
+    SET updated_at = CURRENT_TIMESTAMP 
+    WHERE id = NEW.id;
+END;
+
+-- Security quotes fetched by update_prices and the PRICE_CRON job (see
+-- prices.rs); holdings are valued at the latest one of their ticker
+CREATE TABLE IF NOT EXISTS security_prices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    ticker TEXT NOT NULL,
+    price REAL NOT NULL CHECK(price > 0),
+    price_date DATE NOT NULL, -- Trading day the quote is from
+    source TEXT NOT NULL,
+    
+    fetched_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    UNIQUE(ticker, price_date)
+);
@@ -0,0 +1,4 @@
+-- Session tokens are now signed JWTs checked without a lookup, so the
+-- sessions table is no longer used.
+DROP INDEX IF EXISTS idx_sessions_user_id;
+DROP TABLE IF EXISTS sessions;
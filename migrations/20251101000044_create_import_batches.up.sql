@@ -0,0 +1,16 @@
+-- One row per committed statement import, for the activity feed; the
+-- imported transactions themselves are ordinary rows in transactions
+CREATE TABLE IF NOT EXISTS import_batches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    user_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    row_count INTEGER NOT NULL,
+    
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_import_batches_user_id ON import_batches(user_id);
@@ -0,0 +1,95 @@
+// account_groups.rs
+// User-defined account groups ("Liquid", "Retirement", "Joint"). Each account
+// is in at most one group, so group balances add up to the net worth;
+// net_worth::group_balances does the aggregation.
+
+use sqlx::SqlitePool;
+
+use crate::models::{Account, AccountGroup};
+
+/// Why `account` can't join `group`, if anything
+pub fn validate_member(group: &AccountGroup, account: &Account) -> Result<(), String> {
+    if group.user_id != account.user_id {
+        return Err("Account belongs to another user".to_string());
+    }
+    Ok(())
+}
+
+/// Create the user's group with this name, or return it if it exists
+pub async fn create_group(pool: &SqlitePool, user_id: i64, name: &str) -> Result<AccountGroup, sqlx::Error> {
+    let name = name.trim();
+    sqlx::query("INSERT OR IGNORE INTO account_groups (user_id, name) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(name)
+        .execute(pool)
+        .await?;
+
+    sqlx::query_as::<_, AccountGroup>("SELECT * FROM account_groups WHERE user_id = ? AND name = ?")
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn groups_for_user(pool: &SqlitePool, user_id: i64) -> Result<Vec<AccountGroup>, sqlx::Error> {
+    sqlx::query_as::<_, AccountGroup>("SELECT * FROM account_groups WHERE user_id = ? ORDER BY name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Move the account into a group, or out of its group with None.
+/// Call validate_member first.
+pub async fn set_account_group(
+    pool: &SqlitePool,
+    account_id: i64,
+    group_id: Option<i64>,
+) -> Result<Account, sqlx::Error> {
+    sqlx::query_as::<_, Account>(
+        "UPDATE accounts SET account_group_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? RETURNING *",
+    )
+    .bind(group_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_worth;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_group_balances_split_net_worth() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).balance(1200.0).insert(&pool).await;
+        let savings = testing::account(user.id).name("Savings").balance(800.0).insert(&pool).await;
+        let rrsp = testing::account(user.id).name("RRSP").balance(5000.0).insert(&pool).await;
+        testing::account(user.id).name("Wallet").balance(40.0).insert(&pool).await;
+
+        let liquid = create_group(&pool, user.id, "Liquid").await.unwrap();
+        let retirement = create_group(&pool, user.id, " Retirement ").await.unwrap();
+        assert_eq!(create_group(&pool, user.id, "Liquid").await.unwrap().id, liquid.id);
+        for (account, group) in [(&checking, &liquid), (&savings, &liquid), (&rrsp, &retirement)] {
+            validate_member(group, account).unwrap();
+            set_account_group(&pool, account.id, Some(group.id)).await.unwrap();
+        }
+        let stranger = testing::user().insert(&pool).await;
+        let foreign = testing::account(stranger.id).insert(&pool).await;
+        assert!(validate_member(&liquid, &foreign).is_err());
+
+        let groups = net_worth::group_balances(&pool, user.id, None).await.unwrap();
+        let summary: Vec<(&str, i64, f64)> = groups
+            .iter()
+            .map(|g| (g.group_name.as_str(), g.account_count, g.totals[0].net_worth))
+            .collect();
+        assert_eq!(summary, [("Liquid", 2, 2000.0), ("Retirement", 1, 5000.0), ("Ungrouped", 1, 40.0)]);
+
+        // The account-type view still adds up across groups
+        let worth = net_worth::net_worth(&pool, user.id, None).await.unwrap();
+        assert_eq!(worth.totals[0].net_worth, 7040.0);
+        assert_eq!(worth.by_group.len(), 3);
+    }
+}
@@ -0,0 +1,336 @@
+// accounts.rs
+// Closing accounts: the remaining balance moves to another account in a final
+// transfer, then the account is archived and its recurring transactions paused.
+// Changing an account's currency once it has history: everything recorded in
+// the old currency is converted, keeping the old amounts as the originals.
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::models::{Account, AccountClosure, Transaction};
+use crate::webhooks;
+
+/// Balances smaller than this (rounding leftovers) count as zero when closing
+const ZERO_BALANCE: f64 = 0.005;
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Why the account can't be closed into `destination`, if anything.
+/// A destination is only needed while there is money (or debt) left.
+pub fn validate_closing(account: &Account, destination: Option<&Account>) -> Result<(), String> {
+    if account.is_archived {
+        return Err("Account is already closed".to_string());
+    }
+    match destination {
+        None if account.current_balance.abs() >= ZERO_BALANCE => Err(format!(
+            "Account has a balance of {:.2} {}; give a destination_account_id for the remainder",
+            account.current_balance, account.currency
+        )),
+        None => Ok(()),
+        Some(d) if d.id == account.id => {
+            Err("Destination must be a different account".to_string())
+        }
+        Some(d) if d.user_id != account.user_id => {
+            Err("Destination account belongs to another user".to_string())
+        }
+        Some(d) if d.is_archived => Err("Destination account is closed".to_string()),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Move the remaining balance to `destination` and archive the account, in one
+/// DB transaction. The paying side books a "transfer" and the receiving side an
+/// "income" of the same amount; `rate` converts into the destination currency.
+/// Call validate_closing first.
+pub async fn close_account(
+    pool: &SqlitePool,
+    account: &Account,
+    destination: Option<&Account>,
+    rate: f64,
+) -> Result<AccountClosure, sqlx::Error> {
+    let now = Utc::now();
+    let balance = round_cents(account.current_balance);
+    let mut tx = pool.begin().await?;
+    let mut transaction_ids = Vec::new();
+
+    if let Some(destination) = destination.filter(|_| balance.abs() >= ZERO_BALANCE) {
+        let converted = round_cents(balance.abs() * rate);
+        // A positive balance flows out to the destination; a debt is paid off from it
+        let legs = if balance > 0.0 {
+            [
+                (account.id, "transfer", balance, format!("Closing transfer to {}", destination.name)),
+                (destination.id, "income", converted, format!("Closing transfer from {}", account.name)),
+            ]
+        } else {
+            [
+                (destination.id, "transfer", converted, format!("Closing transfer to {}", account.name)),
+                (account.id, "income", -balance, format!("Closing transfer from {}", destination.name)),
+            ]
+        };
+
+        for (account_id, transaction_type, amount, description) in legs {
+            let transaction_id = sqlx::query(
+                "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(account_id)
+            .bind(amount)
+            .bind(transaction_type)
+            .bind(&description)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+
+            // Both legs are grouped under the paying leg's id, like a transfer
+            let group_id = transaction_ids.first().copied().unwrap_or(transaction_id);
+            sqlx::query("UPDATE transactions SET transfer_group_id = ? WHERE id = ?")
+                .bind(group_id)
+                .bind(transaction_id)
+                .execute(&mut *tx)
+                .await?;
+
+            let balance_change = if transaction_type == "income" { amount } else { -amount };
+            sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+                .bind(balance_change)
+                .bind(account_id)
+                .execute(&mut *tx)
+                .await?;
+
+            let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+                .bind(transaction_id)
+                .fetch_one(&mut *tx)
+                .await?;
+            let payload = serde_json::to_value(&transaction).unwrap_or_default();
+            webhooks::enqueue_event(&mut tx, account.user_id, "transaction.created", &payload).await?;
+            transaction_ids.push(transaction_id);
+        }
+    }
+
+    // Nothing recurs into a closed account
+    sqlx::query("UPDATE recurring_transactions SET is_active = 0 WHERE account_id = ?")
+        .bind(account.id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Leftover rounding dust is written off with the archive
+    sqlx::query(
+        "UPDATE accounts SET current_balance = 0, is_archived = 1, closed_on = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(now.date_naive())
+    .bind(account.id)
+    .execute(&mut *tx)
+    .await?;
+
+    let closed = sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+        .bind(account.id)
+        .fetch_one(&mut *tx)
+        .await?;
+    let payload = serde_json::to_value(&closed).unwrap_or_default();
+    webhooks::enqueue_event(&mut tx, account.user_id, "account.closed", &payload).await?;
+
+    tx.commit().await?;
+
+    Ok(AccountClosure {
+        account: closed,
+        transferred: if transaction_ids.is_empty() { 0.0 } else { balance },
+        transaction_ids,
+    })
+}
+
+/// Number of transactions booked on the account, which a currency change
+/// would re-label
+pub async fn transaction_count(pool: &SqlitePool, account_id: i64) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM transactions WHERE account_id = ?")
+        .bind(account_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Switch the account to `currency`, converting its balances, credit limit,
+/// transactions (with their splits and fees), templates and recurring
+/// transactions billed in the account currency at `rate` (new currency per old unit), in one DB transaction.
+/// Each transaction keeps what it was booked at as original_amount and
+/// original_currency with the applied rate; ones already foreign keep their
+/// original and have the rate carried over to the new currency.
+pub async fn convert_currency(
+    pool: &SqlitePool,
+    account: &Account,
+    currency: &str,
+    rate: f64,
+) -> Result<Account, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE accounts SET currency = ?,
+             initial_balance = ROUND(initial_balance * ?, 2),
+             current_balance = ROUND(current_balance * ?, 2),
+             credit_limit = ROUND(credit_limit * ?, 2),
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?",
+    )
+    .bind(currency)
+    .bind(rate)
+    .bind(rate)
+    .bind(rate)
+    .bind(account.id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Splits first, while the transactions still hold the old amounts
+    sqlx::query(
+        "UPDATE transaction_categories SET amount = ROUND(amount * ?, 2)
+         WHERE transaction_id IN (SELECT id FROM transactions WHERE account_id = ?)",
+    )
+    .bind(rate)
+    .bind(account.id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Every right-hand side sees the row as it was before the update
+    sqlx::query(
+        "UPDATE transactions SET
+             original_amount = COALESCE(original_amount, amount),
+             original_currency = COALESCE(original_currency, ?),
+             exchange_rate = COALESCE(exchange_rate, 1.0) * ?,
+             amount = ROUND(amount * ?, 2),
+             fee = ROUND(fee * ?, 2)
+         WHERE account_id = ?",
+    )
+    .bind(&account.currency)
+    .bind(rate)
+    .bind(rate)
+    .bind(rate)
+    .bind(account.id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE transaction_templates SET amount = ROUND(amount * ?, 2) WHERE account_id = ?")
+        .bind(rate)
+        .bind(account.id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Recurring transactions billed in their own currency keep it, unless
+    // that's now the account's
+    sqlx::query(
+        "UPDATE recurring_transactions SET amount = ROUND(amount * ?, 2)
+         WHERE account_id = ? AND currency IS NULL",
+    )
+    .bind(rate)
+    .bind(account.id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE recurring_transactions SET currency = NULL WHERE account_id = ? AND currency = ?")
+        .bind(account.id)
+        .bind(currency)
+        .execute(&mut *tx)
+        .await?;
+
+    let converted = sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+        .bind(account.id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_close_account_moves_balance_and_archives() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let savings = testing::account(user.id).name("Savings").balance(250.0).insert(&pool).await;
+        let checking = testing::account(user.id).balance(10.0).insert(&pool).await;
+
+        assert!(validate_closing(&savings, None).is_err());
+        assert!(validate_closing(&savings, Some(&savings)).is_err());
+        validate_closing(&savings, Some(&checking)).unwrap();
+
+        let closure = close_account(&pool, &savings, Some(&checking), 1.0).await.unwrap();
+        assert!(closure.account.is_archived);
+        assert_eq!(closure.account.closed_on, Some(Utc::now().date_naive()));
+        assert_eq!((closure.account.current_balance, closure.transferred), (0.0, 250.0));
+        assert_eq!(closure.transaction_ids.len(), 2);
+        let groups: Vec<Option<i64>> = sqlx::query_scalar("SELECT transfer_group_id FROM transactions ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(groups, vec![Some(closure.transaction_ids[0]); 2]);
+
+        let balance: f64 = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(checking.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balance, 260.0);
+        assert_eq!(
+            validate_closing(&closure.account, None).unwrap_err(),
+            "Account is already closed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_close_account_pays_off_debt_from_destination() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let card = testing::account(user.id).account_type("credit_card").balance(-40.0).insert(&pool).await;
+        let checking = testing::account(user.id).currency("EUR").balance(100.0).insert(&pool).await;
+
+        let closure = close_account(&pool, &card, Some(&checking), 0.5).await.unwrap();
+        assert_eq!(closure.transferred, -40.0);
+
+        let balance: f64 = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(checking.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balance, 80.0);
+    }
+
+    #[tokio::test]
+    async fn test_convert_currency_keeps_history_as_originals() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let lunch = testing::txn(account.id).amount(20.0).category(groceries.id).insert(&pool).await;
+        let recurring = testing::recurring(account.id).amount(50.0).insert(&pool).await;
+        sqlx::query("UPDATE accounts SET current_balance = 80.0 WHERE id = ?")
+            .bind(account.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(transaction_count(&pool, account.id).await.unwrap(), 1);
+
+        let converted = convert_currency(&pool, &account, "EUR", 0.9).await.unwrap();
+        assert_eq!(converted.currency, "EUR");
+        assert_eq!((converted.initial_balance, converted.current_balance), (90.0, 72.0));
+
+        let lunch = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+            .bind(lunch.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(lunch.amount, 18.0);
+        assert_eq!((lunch.original_amount, lunch.original_currency.as_deref()), (Some(20.0), Some("USD")));
+        assert_eq!(lunch.exchange_rate, Some(0.9));
+        let split: f64 = sqlx::query_scalar("SELECT amount FROM transaction_categories WHERE transaction_id = ?")
+            .bind(lunch.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(split, 18.0);
+        let recurring: f64 = sqlx::query_scalar("SELECT amount FROM recurring_transactions WHERE id = ?")
+            .bind(recurring.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(recurring, 45.0);
+    }
+}
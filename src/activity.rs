@@ -0,0 +1,146 @@
+// activity.rs
+// Recent-activity feed: one chronological list across transactions,
+// recurring executions, imports, exchange-rate refreshes, export drops and alerts
+
+use sqlx::SqlitePool;
+
+use crate::models::ActivityItem;
+
+pub const DEFAULT_LIMIT: i64 = 50;
+pub const MAX_LIMIT: i64 = 500;
+
+/// Most recent activity for a user, newest first.
+///
+/// Timestamps are normalized with datetime() because rows written by SQLite
+/// defaults and by sqlx bindings use different text formats.
+/// Exchange rates are shared by all users, so every user sees every refresh
+/// (one entry per source and minute). Export drops hold all users' new
+/// transactions; a user sees the drops with some of theirs, counting only
+/// those. Alerts are webhook deliveries that gave up.
+pub async fn recent_activity(
+    pool: &SqlitePool,
+    user_id: i64,
+    limit: i64,
+) -> Result<Vec<ActivityItem>, sqlx::Error> {
+    sqlx::query_as::<_, ActivityItem>(
+        "SELECT kind, occurred_at, title, detail, amount, reference_id FROM (
+             SELECT CASE WHEN t.recurring_transaction_id IS NOT NULL THEN 'recurring' ELSE 'transaction' END as kind,
+                    datetime(t.created_at) as occurred_at,
+                    COALESCE(t.description, t.merchant, t.transaction_type) as title,
+                    a.name as detail,
+                    CASE WHEN t.transaction_type = 'income' THEN ABS(t.amount) ELSE -ABS(t.amount) END as amount,
+                    t.id as reference_id
+             FROM transactions t
+             INNER JOIN accounts a ON t.account_id = a.id
+             WHERE a.user_id = ?
+             UNION ALL
+             SELECT 'import' as kind,
+                    datetime(b.created_at) as occurred_at,
+                    'Imported ' || b.row_count || ' transactions' as title,
+                    a.name as detail,
+                    NULL as amount,
+                    b.id as reference_id
+             FROM import_batches b
+             INNER JOIN accounts a ON b.account_id = a.id
+             WHERE b.user_id = ?
+             UNION ALL
+             SELECT 'rate_refresh' as kind,
+                    MAX(datetime(created_at)) as occurred_at,
+                    COUNT(*) || ' exchange rates saved' as title,
+                    source || ': ' || GROUP_CONCAT(DISTINCT from_currency) as detail,
+                    NULL as amount,
+                    MAX(id) as reference_id
+             FROM exchange_rates
+             GROUP BY source, strftime('%Y-%m-%d %H:%M', created_at)
+             UNION ALL
+             SELECT 'export' as kind,
+                    datetime(d.created_at) as occurred_at,
+                    'Export drop: ' || COUNT(*) || ' transactions (' || d.format || ')' as title,
+                    d.file_path as detail,
+                    NULL as amount,
+                    d.id as reference_id
+             FROM export_drops d
+             INNER JOIN transactions t ON t.id <= d.last_transaction_id
+                 AND t.id > COALESCE((SELECT MAX(p.last_transaction_id) FROM export_drops p
+                                      WHERE p.directory = d.directory AND p.format = d.format AND p.id < d.id), 0)
+             INNER JOIN accounts a ON t.account_id = a.id
+             WHERE a.user_id = ?
+             GROUP BY d.id
+             UNION ALL
+             SELECT 'alert' as kind,
+                    datetime(o.updated_at) as occurred_at,
+                    'Webhook delivery failed: ' || o.event_type as title,
+                    o.last_error as detail,
+                    NULL as amount,
+                    o.id as reference_id
+             FROM event_outbox o
+             INNER JOIN webhooks w ON o.webhook_id = w.id
+             WHERE w.user_id = ? AND o.status = 'failed'
+         )
+         ORDER BY occurred_at DESC, reference_id DESC
+         LIMIT ?",
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind(user_id)
+    .bind(user_id)
+    .bind(clamp_limit(limit))
+    .fetch_all(pool)
+    .await
+}
+
+/// Keep feed requests between 1 and MAX_LIMIT entries.
+pub fn clamp_limit(limit: i64) -> i64 {
+    limit.clamp(1, MAX_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import;
+    use crate::models::ImportRow;
+    use crate::testing;
+
+    #[test]
+    fn test_clamp_limit() {
+        assert_eq!(clamp_limit(50), 50);
+        assert_eq!(clamp_limit(0), 1);
+        assert_eq!(clamp_limit(10_000), MAX_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_imports_and_export_drops_are_per_user() {
+        let pool = testing::pool().await;
+        let alice = testing::user().insert(&pool).await;
+        let bob = testing::user().insert(&pool).await;
+        let checking = testing::account(alice.id).name("Checking").insert(&pool).await;
+        let bobs = testing::account(bob.id).insert(&pool).await;
+        testing::txn(bobs.id).insert(&pool).await;
+        let rows: Vec<ImportRow> = serde_json::from_value(serde_json::json!([
+            { "transaction_date": "2025-05-01T00:00:00Z", "description": "Coffee", "amount": 4.5, "transaction_type": "expense" },
+            { "transaction_date": "2025-05-02T00:00:00Z", "description": "Payroll", "amount": 50.0, "transaction_type": "income" }
+        ]))
+        .unwrap();
+        let imported = import::commit_import(&pool, alice.id, checking.id, &rows).await.unwrap();
+        let last_id = *imported.transaction_ids.last().unwrap();
+        sqlx::query(
+            "INSERT INTO export_drops (directory, format, schedule, file_path, row_count, last_transaction_id)
+             VALUES ('/drops', 'csv', 'daily', '/drops/transactions.csv', 3, ?)",
+        )
+        .bind(last_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let feed = recent_activity(&pool, alice.id, 50).await.unwrap();
+        let import = feed.iter().find(|item| item.kind == "import").unwrap();
+        assert_eq!((import.title.as_str(), import.detail.as_deref()), ("Imported 2 transactions", Some("Checking")));
+        let export = feed.iter().find(|item| item.kind == "export").unwrap();
+        assert_eq!(export.title, "Export drop: 2 transactions (csv)");
+
+        let feed = recent_activity(&pool, bob.id, 50).await.unwrap();
+        assert!(!feed.iter().any(|item| item.kind == "import"));
+        let export = feed.iter().find(|item| item.kind == "export").unwrap();
+        assert_eq!(export.title, "Export drop: 1 transactions (csv)");
+    }
+}
@@ -1,8 +1,58 @@
 ﻿use crate::models::*;
+use crate::account_groups;
+use crate::accounts;
+use crate::activity;
+use crate::attachments;
+use crate::auth;
+use crate::buckets;
+use crate::budgets;
+use crate::categories;
+use crate::charts;
+use crate::credit;
+use crate::currencies;
+use crate::encryption;
+use crate::exchange_scraper;
+use crate::exclusions;
+use crate::flows;
+use crate::forecast;
+use crate::fx;
+use crate::goals;
+use crate::holdings;
+use crate::import;
+use crate::input;
+use crate::ledger::{self, LedgerFormat};
+use crate::loans;
+use crate::merchants;
+use crate::net_worth;
+use crate::patterns;
+use crate::qif;
+use crate::rate_compaction;
+use crate::rate_providers::RateProviders;
+use crate::rate_sources;
+use crate::receipts;
+use crate::reconcile;
 use crate::recurring;
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
-use chrono::Utc;
+use crate::reimbursements;
+use crate::restore;
+use crate::rules;
+use crate::settings;
+use crate::statements;
+use crate::tags;
+use crate::transaction_status;
+use crate::transfers;
+use crate::webhooks;
+use crate::xlsx;
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::error::PayloadError;
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::middleware::Next;
+use actix_web::{delete, get, post, put, web, FromRequest, HttpMessage, HttpRequest, HttpResponse, Responder};
+use chrono::{NaiveDate, Utc};
+use futures::future::{ready, Ready};
+use futures::{SinkExt, StreamExt};
 use sqlx::SqlitePool;
+use std::collections::HashMap;
 
 // ============================================================================
 // User Endpoints
@@ -75,7 +125,10 @@ async fn create_user(
         return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
     }
 
-    let password_hash = format!("$argon2id$v=19$m=19456,t=2,p=1${}", user_data.password);
+    let password_hash = match auth::hash_password(&user_data.password) {
+        Ok(hash) => hash,
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e)),
+    };
 
     let result = sqlx::query("INSERT INTO users (username, email, password_hash) VALUES (?, ?, ?)")
         .bind(&user_data.username)
@@ -108,30 +161,37 @@ async fn update_user(
     update_data: web::Json<UpdateUser>,
 ) -> impl Responder {
     let id = id.into_inner();
-
-    let mut updates = Vec::new();
-    let mut query = String::from("UPDATE users SET ");
-
-    if let Some(username) = &update_data.username {
-        updates.push(format!("username = '{}'", username));
-    }
-    if let Some(email) = &update_data.email {
-        updates.push(format!("email = '{}'", email));
-    }
-    if let Some(password) = &update_data.password {
-        let hash = format!("$argon2id$v=19$m=19456,t=2,p=1${}", password);
-        updates.push(format!("password_hash = '{}'", hash));
+    if let Err(e) = update_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
     }
 
-    if updates.is_empty() {
+    let password_hash = match &update_data.password {
+        Some(password) if password.len() < 8 => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Password must be at least 8 characters".into()));
+        }
+        Some(password) => match auth::hash_password(password) {
+            Ok(hash) => Some(hash),
+            Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e)),
+        },
+        None => None,
+    };
+
+    if update_data.username.is_none() && update_data.email.is_none() && password_hash.is_none() {
         return HttpResponse::BadRequest()
             .json(ApiResponse::<()>::error("No fields to update".into()));
     }
 
-    query.push_str(&updates.join(", "));
-    query.push_str(&format!(", updated_at = datetime('now') WHERE id = {}", id));
-
-    let result = sqlx::query(&query).execute(pool.get_ref()).await;
+    let result = sqlx::query(
+        "UPDATE users SET username = COALESCE(?, username), email = COALESCE(?, email),
+         password_hash = COALESCE(?, password_hash), updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(&update_data.username)
+    .bind(&update_data.email)
+    .bind(&password_hash)
+    .bind(id)
+    .execute(pool.get_ref())
+    .await;
 
     match result {
         Ok(_) => {
@@ -170,6 +230,346 @@ async fn delete_user(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Re
     }
 }
 
+async fn user_exists(pool: &SqlitePool, user_id: i64) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+/// GET /users/{id}/settings - Get a user's preferences
+#[get("/users/{id}/settings")]
+async fn get_user_settings(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let id = id.into_inner();
+
+    match user_exists(pool.get_ref(), id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    }
+
+    match settings::user_settings(pool.get_ref(), id).await {
+        Ok(user_settings) => HttpResponse::Ok().json(ApiResponse::success(user_settings)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /users/{id}/settings - Update a user's preferences
+#[put("/users/{id}/settings")]
+async fn update_user_settings(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    update_data: web::Json<UpdateUserSettings>,
+) -> impl Responder {
+    let id = id.into_inner();
+
+    if let Err(e) = update_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    match user_exists(pool.get_ref(), id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    }
+
+    // The default account has to be one of the user's own
+    if let Some(account_id) = update_data.default_account_id.filter(|a| *a != 0) {
+        match resource_owner(pool.get_ref(), "accounts", account_id).await {
+            Ok(Some(owner)) if owner == id => {}
+            Ok(_) => {
+                return HttpResponse::UnprocessableEntity().json(ApiResponse::<()>::error(format!(
+                    "Account {} doesn't belong to this user",
+                    account_id
+                )))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
+    }
+
+    match settings::update_user_settings(pool.get_ref(), id, &update_data).await {
+        Ok(user_settings) => HttpResponse::Ok().json(ApiResponse::success(user_settings)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Auth Endpoints
+// ============================================================================
+
+/// POST /auth/login - Check a username (or email) and password and start a session
+#[post("/auth/login")]
+async fn login(pool: web::Data<SqlitePool>, credentials: web::Json<LoginRequest>) -> impl Responder {
+    let user = match auth::authenticate(pool.get_ref(), &credentials.username, &credentials.password).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return HttpResponse::Unauthorized()
+                .json(ApiResponse::<()>::error("Invalid username or password".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    let (token, expires_at) = auth::create_session(user.id);
+    HttpResponse::Ok().json(ApiResponse::success(LoginSession {
+        token,
+        expires_at,
+        user,
+    }))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// GET /auth/session - The user signed in with the "Authorization: Bearer" token
+#[get("/auth/session")]
+async fn get_session(pool: web::Data<SqlitePool>, req: HttpRequest) -> impl Responder {
+    let Some(token) = bearer_token(req.headers()) else {
+        return HttpResponse::Unauthorized()
+            .json(ApiResponse::<()>::error("Authorization: Bearer <token> header is required".into()));
+    };
+
+    match auth::session_user(pool.get_ref(), token).await {
+        Ok(Some(user)) => HttpResponse::Ok().json(ApiResponse::success(user)),
+        Ok(None) => HttpResponse::Unauthorized()
+            .json(ApiResponse::<()>::error("Session is invalid or has expired".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// The user signed in with the request's bearer token, set by require_session
+#[derive(Debug, Clone, Copy)]
+pub struct AuthUser {
+    pub user_id: i64,
+}
+
+/// Pool for the analytics, report and export endpoints: the read-only pool
+/// when the server registered one (web::Data<ReadPool>), else the main pool
+#[derive(Debug, Clone)]
+pub struct ReadPool(pub SqlitePool);
+
+impl ReadPool {
+    /// Same accessor as web::Data, so handlers read the same either way
+    pub fn get_ref(&self) -> &SqlitePool {
+        &self.0
+    }
+}
+
+impl FromRequest for ReadPool {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<ReadPool, actix_web::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let pool = match req.app_data::<web::Data<ReadPool>>() {
+            Some(reads) => Some(reads.get_ref().clone()),
+            None => req.app_data::<web::Data<SqlitePool>>().map(|pool| ReadPool(pool.get_ref().clone())),
+        };
+        ready(pool.ok_or_else(|| actix_web::error::ErrorInternalServerError("No database pool configured")))
+    }
+}
+
+/// Resources only their owner may see or change
+const USER_SCOPED: [&str; 20] = [
+    "users",
+    "accounts",
+    "account-groups",
+    "categories",
+    "goals",
+    "holdings",
+    "transactions",
+    "recurring-transactions",
+    "transfers",
+    "tags",
+    "budgets",
+    "templates",
+    "category-rules",
+    "import",
+    "import-profiles", // /import/profiles/{id}
+    "activity",
+    "analytics",
+    "reports",
+    "export",
+    "webhooks",
+];
+
+/// Owner of the resource with this id, or None when it doesn't exist
+async fn resource_owner(pool: &SqlitePool, resource: &str, id: i64) -> Result<Option<i64>, sqlx::Error> {
+    let query = match resource {
+        "users" => "SELECT id FROM users WHERE id = ?",
+        "accounts" => "SELECT user_id FROM accounts WHERE id = ?",
+        "account-groups" => "SELECT user_id FROM account_groups WHERE id = ?",
+        "categories" => "SELECT user_id FROM categories WHERE id = ?",
+        "goals" => "SELECT user_id FROM goals WHERE id = ?",
+        "holdings" => "SELECT a.user_id FROM holdings h JOIN accounts a ON h.account_id = a.id WHERE h.id = ?",
+        "tags" => "SELECT user_id FROM tags WHERE id = ?",
+        "budgets" => "SELECT user_id FROM budgets WHERE id = ?",
+        "templates" => "SELECT user_id FROM transaction_templates WHERE id = ?",
+        "category-rules" => "SELECT user_id FROM category_rules WHERE id = ?",
+        "import-profiles" => "SELECT user_id FROM import_profiles WHERE id = ?",
+        "webhooks" => "SELECT user_id FROM webhooks WHERE id = ?",
+        "transactions" => {
+            "SELECT a.user_id FROM transactions t JOIN accounts a ON t.account_id = a.id WHERE t.id = ?"
+        }
+        "recurring-transactions" => {
+            "SELECT a.user_id FROM recurring_transactions r JOIN accounts a ON r.account_id = a.id WHERE r.id = ?"
+        }
+        _ => return Ok(None),
+    };
+    sqlx::query_scalar(query).bind(id).fetch_optional(pool).await
+}
+
+fn forbidden() -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<()>::error("Belongs to another user".into()))
+}
+
+/// 422 when one of the categories a transaction refers to isn't the user's
+async fn check_category_owner(pool: &SqlitePool, user_id: i64, category_ids: &[i64]) -> Option<HttpResponse> {
+    match categories::first_foreign(pool, user_id, category_ids).await {
+        Ok(None) => None,
+        Ok(Some(category_id)) => Some(HttpResponse::UnprocessableEntity().json(ApiResponse::<()>::error(format!(
+            "Category {} doesn't belong to this user",
+            category_id
+        )))),
+        Err(e) => Some(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// Middleware for the user-scoped resources: requires a valid session token,
+/// rejects /{resource}/{id} requests for another user's rows with 403 and
+/// passes the user on to the handlers as AuthUser. A user_id in the query
+/// string must be the signed-in user's (403 otherwise); when it's missing
+/// the signed-in user's is filled in, so no listing spans other users.
+/// POST /users (sign-up) stays open.
+pub async fn require_session(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let mut segments = req.path().trim_start_matches('/').split('/').peekable();
+    let mut resource = segments.next().unwrap_or_default().to_string();
+    if resource == "import" && segments.peek() == Some(&"profiles") {
+        segments.next();
+        resource = "import-profiles".to_string();
+    }
+    let sign_up = resource == "users" && req.method() == actix_web::http::Method::POST;
+    if !USER_SCOPED.contains(&resource.as_str()) || sign_up {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    }
+    let id = segments.next().and_then(|s| s.parse::<i64>().ok());
+
+    let reject = |req: ServiceRequest, response: HttpResponse| Ok(req.into_response(response));
+    let Some(pool) = req.app_data::<web::Data<SqlitePool>>().cloned() else {
+        return reject(req, HttpResponse::InternalServerError().finish());
+    };
+    let Some(token) = bearer_token(req.headers()) else {
+        return reject(
+            req,
+            HttpResponse::Unauthorized()
+                .json(ApiResponse::<()>::error("Authorization: Bearer <token> header is required".into())),
+        );
+    };
+
+    let user = match auth::session_user(pool.get_ref(), token).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return reject(
+                req,
+                HttpResponse::Unauthorized()
+                    .json(ApiResponse::<()>::error("Session is invalid or has expired".into())),
+            )
+        }
+        Err(e) => {
+            return reject(req, HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())))
+        }
+    };
+
+    let query_user = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.get("user_id").cloned());
+    match query_user.map(|id| id.trim().parse::<i64>()) {
+        Some(Ok(query_user)) if query_user != user.id => return reject(req, forbidden()),
+        Some(_) => {}
+        None => {
+            let query = match req.query_string() {
+                "" => format!("user_id={}", user.id),
+                query => format!("{}&user_id={}", query, user.id),
+            };
+            match format!("{}?{}", req.path(), query).parse::<actix_web::http::Uri>() {
+                Ok(uri) => {
+                    req.match_info_mut().get_mut().update(&uri);
+                    req.head_mut().uri = uri;
+                }
+                Err(e) => {
+                    return reject(req, HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
+                }
+            }
+        }
+    }
+
+    // Missing rows fall through so the handler answers 404
+    if let Some(id) = id {
+        match resource_owner(pool.get_ref(), &resource, id).await {
+            Ok(Some(owner)) if owner != user.id => return reject(req, forbidden()),
+            Ok(_) => {}
+            Err(e) => {
+                return reject(req, HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())))
+            }
+        }
+    }
+
+    req.extensions_mut().insert(AuthUser { user_id: user.id });
+    Ok(next.call(req).await?.map_into_boxed_body())
+}
+
+/// Middleware capping request bodies at input::body_limit for the path: 413
+/// up front when Content-Length is over it, else when the streamed body is.
+pub async fn limit_body(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let limit = input::body_limit(req.path());
+    let length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if length.is_some_and(|length| length > limit) {
+        return Ok(req.into_response(
+            HttpResponse::PayloadTooLarge()
+                .json(ApiResponse::<()>::error(format!("Request body must be at most {} bytes", limit))),
+        ));
+    }
+
+    let mut received = 0;
+    let payload = req.take_payload().map(move |chunk| {
+        let chunk = chunk?;
+        received += chunk.len();
+        if received > limit {
+            Err(PayloadError::Overflow)
+        } else {
+            Ok(chunk)
+        }
+    });
+    req.set_payload(Payload::Stream { payload: Box::pin(payload) });
+    Ok(next.call(req).await?.map_into_boxed_body())
+}
+
 // ============================================================================
 // Account Endpoints
 // ============================================================================
@@ -178,19 +578,22 @@ async fn delete_user(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Re
 #[get("/accounts")]
 async fn get_accounts(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     query: web::Query<PaginationParams>,
 ) -> impl Responder {
     let offset = (query.page - 1) * query.page_size;
 
     let accounts = sqlx::query_as::<_, Account>(
-        "SELECT * FROM accounts ORDER BY created_at DESC LIMIT ? OFFSET ?",
+        "SELECT * FROM accounts WHERE user_id = ? ORDER BY is_favorite DESC, sort_order ASC, created_at DESC LIMIT ? OFFSET ?",
     )
+    .bind(auth_user.user_id)
     .bind(query.page_size)
     .bind(offset)
     .fetch_all(pool.get_ref())
     .await;
 
-    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM accounts")
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM accounts WHERE user_id = ?")
+        .bind(auth_user.user_id)
         .fetch_one(pool.get_ref())
         .await
         .unwrap_or(0);
@@ -229,21 +632,198 @@ async fn get_account(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Re
     }
 }
 
+/// GET /accounts/{id}/stats - Projected balance, credit limit, available credit and utilization of an account
+#[get("/accounts/{id}/stats")]
+async fn get_account_stats(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let id = id.into_inner();
+
+    let account = sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    match account {
+        Ok(Some(account)) => match forecast::projected_balance(pool.get_ref(), &account, Utc::now()).await {
+            Ok(projected) => HttpResponse::Ok().json(ApiResponse::success(credit::account_stats(
+                &account,
+                projected,
+                credit::utilization_threshold(),
+            ))),
+            Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+        },
+        Ok(None) => {
+            HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /accounts/{id}/amortization - Payment schedule of a loan account
+#[get("/accounts/{id}/amortization")]
+async fn get_amortization(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    match loans::loan_for_account(pool.get_ref(), id.into_inner()).await {
+        Ok(Some(loan)) => HttpResponse::Ok().json(ApiResponse::success(loans::amortization(loan))),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error("No loan terms for this account".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /accounts/{id}/loan - Set the terms of a loan account and schedule its monthly payment
+#[post("/accounts/{id}/loan")]
+async fn create_loan(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    data: web::Json<CreateLoan>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    let load = |id: i64| {
+        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool.get_ref())
+    };
+    let (account, payment_account) = match (load(id.into_inner()).await, load(data.payment_account_id).await) {
+        (Ok(Some(account)), Ok(Some(payment_account))) => (account, payment_account),
+        (Ok(None), _) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))
+        }
+        (_, Ok(None)) => {
+            return HttpResponse::UnprocessableEntity().json(ApiResponse::<()>::error(format!(
+                "Account {} not found",
+                data.payment_account_id
+            )))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+    if let Err(e) = loans::validate_accounts(&account, &payment_account) {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse::<()>::error(e));
+    }
+    match loans::loan_for_account(pool.get_ref(), account.id).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return HttpResponse::Conflict()
+                .json(ApiResponse::<()>::error("The account already has loan terms".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    }
+
+    match loans::create_loan(pool.get_ref(), &account, &data).await {
+        Ok(loan) => HttpResponse::Created().json(ApiResponse::success(loans::amortization(loan))),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// Investment account `id`, or the response when it can't hold securities
+async fn investment_account(pool: &SqlitePool, id: i64) -> Result<Account, HttpResponse> {
+    let account = sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await;
+    match account {
+        Ok(Some(account)) => match holdings::validate_account(&account) {
+            Ok(()) => Ok(account),
+            Err(e) => Err(HttpResponse::UnprocessableEntity().json(ApiResponse::<()>::error(e))),
+        },
+        Ok(None) => Err(HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))),
+        Err(e) => Err(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))),
+    }
+}
+
+/// GET /accounts/{id}/holdings - Holdings of an investment account with market value, gain/loss and allocation
+#[get("/accounts/{id}/holdings")]
+async fn get_holdings(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let account = match investment_account(pool.get_ref(), id.into_inner()).await {
+        Ok(account) => account,
+        Err(response) => return response,
+    };
+    match holdings::account_holdings(pool.get_ref(), &account).await {
+        Ok(valued) => HttpResponse::Ok().json(ApiResponse::success(valued)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /accounts/{id}/holdings - Add a holding to an investment account
+#[post("/accounts/{id}/holdings")]
+async fn create_holding(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    data: web::Json<CreateHolding>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    let account = match investment_account(pool.get_ref(), id.into_inner()).await {
+        Ok(account) => account,
+        Err(response) => return response,
+    };
+    match holdings::create_holding(pool.get_ref(), account.id, &data).await {
+        Ok(holding) => HttpResponse::Created().json(ApiResponse::success(holding)),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => HttpResponse::Conflict().json(
+            ApiResponse::<()>::error(format!("The account already holds {}", holdings::normalize_ticker(&data.ticker))),
+        ),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /holdings/{id} - Change a holding's quantity or cost basis
+#[put("/holdings/{id}")]
+async fn update_holding(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    data: web::Json<UpdateHolding>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    match holdings::update_holding(pool.get_ref(), id.into_inner(), &data).await {
+        Ok(Some(holding)) => HttpResponse::Ok().json(ApiResponse::success(holding)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Holding not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /holdings/{id} - Delete a holding
+#[delete("/holdings/{id}")]
+async fn delete_holding(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let result = sqlx::query("DELETE FROM holdings WHERE id = ?")
+        .bind(id.into_inner())
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Holding deleted successfully"))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Holding not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
 /// POST /accounts - Create new account
 #[post("/accounts")]
 async fn create_account(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     account_data: web::Json<CreateAccount>,
 ) -> impl Responder {
     if let Err(e) = account_data.validate() {
         return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
     }
+    if account_data.user_id != auth_user.user_id {
+        return forbidden();
+    }
 
     let currency = account_data.currency.as_deref().unwrap_or("USD");
     let initial_balance = account_data.initial_balance.unwrap_or(0.0);
+    let opened_on = account_data.opened_on.unwrap_or_else(|| Utc::now().date_naive());
 
     let result = sqlx::query(
-        "INSERT INTO accounts (user_id, name, account_type, bank_name, currency, initial_balance, current_balance) VALUES (?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO accounts (user_id, name, account_type, bank_name, currency, initial_balance, current_balance, default_category_id, credit_limit, opened_on) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(account_data.user_id)
     .bind(&account_data.name)
@@ -252,6 +832,9 @@ async fn create_account(
     .bind(currency)
     .bind(initial_balance)
     .bind(initial_balance)
+    .bind(account_data.default_category_id)
+    .bind(account_data.credit_limit)
+    .bind(opened_on)
     .execute(pool.get_ref())
     .await;
 
@@ -269,41 +852,248 @@ async fn create_account(
     }
 }
 
-/// PUT /accounts/{id} - Update account
-#[put("/accounts/{id}")]
-async fn update_account(
+/// PUT /accounts/reorder - Set the order of a user's accounts
+#[put("/accounts/reorder")]
+async fn reorder_accounts(
     pool: web::Data<SqlitePool>,
-    id: web::Path<i64>,
-    update_data: web::Json<UpdateAccount>,
+    auth_user: web::ReqData<AuthUser>,
+    order: web::Json<ReorderAccounts>,
 ) -> impl Responder {
-    let id = id.into_inner();
-    let mut updates = Vec::new();
-
-    if let Some(name) = &update_data.name {
-        updates.push(format!("name = '{}'", name));
+    if order.user_id != auth_user.user_id {
+        return forbidden();
     }
-    if let Some(account_type) = &update_data.account_type {
-        updates.push(format!("account_type = '{}'", account_type));
+
+    let owned: Vec<i64> = match sqlx::query_scalar("SELECT id FROM accounts WHERE user_id = ?")
+        .bind(order.user_id)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    if let Some(id) = order.account_ids.iter().find(|id| !owned.contains(id)) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+            "Account {} does not belong to user {}",
+            id, order.user_id
+        )));
     }
-    if let Some(bank_name) = &update_data.bank_name {
-        updates.push(format!("bank_name = '{}'", bank_name));
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    // Accounts left out of the list keep their relative order after the listed ones
+    for (position, id) in order.account_ids.iter().enumerate() {
+        if let Err(e) = sqlx::query("UPDATE accounts SET sort_order = ? WHERE id = ?")
+            .bind(position as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+        {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()));
+        }
     }
-    if let Some(currency) = &update_data.currency {
-        updates.push(format!("currency = '{}'", currency));
+    if !order.account_ids.is_empty() {
+        let listed = order
+            .account_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let query = format!(
+            "UPDATE accounts SET sort_order = sort_order + {} WHERE user_id = {} AND id NOT IN ({})",
+            order.account_ids.len(),
+            order.user_id,
+            listed
+        );
+        if let Err(e) = sqlx::query(&query).execute(&mut *tx).await {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()));
+        }
     }
 
-    if updates.is_empty() {
-        return HttpResponse::BadRequest()
-            .json(ApiResponse::<()>::error("No fields to update".into()));
+    if let Err(e) = tx.commit().await {
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
     }
 
-    let query = format!(
-        "UPDATE accounts SET {}, updated_at = datetime('now') WHERE id = {}",
-        updates.join(", "),
-        id
-    );
+    let accounts = sqlx::query_as::<_, Account>(
+        "SELECT * FROM accounts WHERE user_id = ? ORDER BY is_favorite DESC, sort_order ASC, created_at DESC",
+    )
+    .bind(order.user_id)
+    .fetch_all(pool.get_ref())
+    .await;
 
-    let result = sqlx::query(&query).execute(pool.get_ref()).await;
+    match accounts {
+        Ok(accounts) => HttpResponse::Ok().json(ApiResponse::success(accounts)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /accounts/{id} - Update account (?currency_mode=convert&rate=0.9 to convert a currency change)
+#[put("/accounts/{id}")]
+async fn update_account(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    query: web::Query<UpdateAccountQuery>,
+    update_data: web::Json<UpdateAccount>,
+) -> impl Responder {
+    let id = id.into_inner();
+    if let Err(e) = update_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    if query.rate.is_some_and(|rate| rate <= 0.0) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Rate must be positive".into()));
+    }
+    let mut updates = Vec::new();
+    // Set when the currency change converts the account rather than re-labelling it
+    let mut conversion = None;
+
+    if let Some(name) = &update_data.name {
+        updates.push(format!("name = '{}'", name));
+    }
+    if let Some(account_type) = &update_data.account_type {
+        updates.push(format!("account_type = '{}'", account_type));
+    }
+    if let Some(bank_name) = &update_data.bank_name {
+        updates.push(format!("bank_name = '{}'", bank_name));
+    }
+    if let Some(currency) = &update_data.currency {
+        let account = match sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool.get_ref())
+            .await
+        {
+            Ok(Some(account)) => account,
+            Ok(None) => {
+                return HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        };
+        let currency = currency.trim().to_uppercase();
+        // Without history there's nothing to re-label, so the block mode lets it through
+        let transactions = match accounts::transaction_count(pool.get_ref(), id).await {
+            Ok(count) => count,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        };
+
+        let changed = currency != account.currency;
+        if changed && query.currency_mode == CurrencyChangeMode::Convert {
+            let rate = match query.rate {
+                Some(rate) => rate,
+                None => match fx::conversion_rate(pool.get_ref(), &account.currency, &currency, None).await {
+                    Ok(Some(rate)) => rate,
+                    Ok(None) => {
+                        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                            "No exchange rate from {} to {}; give one with ?rate=",
+                            account.currency, currency
+                        )))
+                    }
+                    Err(e) => {
+                        return HttpResponse::InternalServerError()
+                            .json(ApiResponse::<()>::error(e.to_string()))
+                    }
+                },
+            };
+            conversion = Some((account, currency, rate));
+        } else if changed && transactions > 0 {
+            return HttpResponse::Conflict().json(ApiResponse::<()>::error(format!(
+                "Account has {} transactions in {}; pass ?currency_mode=convert to convert them to {}",
+                transactions, account.currency, currency
+            )));
+        } else {
+            updates.push(format!("currency = '{}'", currency));
+        }
+    }
+    if let Some(sort_order) = update_data.sort_order {
+        updates.push(format!("sort_order = {}", sort_order));
+    }
+    if let Some(is_favorite) = update_data.is_favorite {
+        updates.push(format!("is_favorite = {}", is_favorite as i64));
+    }
+    if let Some(default_category_id) = update_data.default_category_id {
+        // 0 clears the default category
+        if default_category_id == 0 {
+            updates.push("default_category_id = NULL".to_string());
+        } else {
+            updates.push(format!("default_category_id = {}", default_category_id));
+        }
+    }
+    if let Some(credit_limit) = update_data.credit_limit {
+        // 0 clears the credit limit
+        if credit_limit == 0.0 {
+            updates.push("credit_limit = NULL".to_string());
+        } else if credit_limit < 0.0 {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Credit limit must be positive".into()));
+        } else {
+            updates.push(format!("credit_limit = {}", credit_limit));
+        }
+    }
+    if let Some(opened_on) = update_data.opened_on {
+        // The initial balance can't be dated after transactions it already includes
+        let first: Option<NaiveDate> = match sqlx::query_scalar(
+            "SELECT MIN(date(transaction_date)) FROM transactions WHERE account_id = ?",
+        )
+        .bind(id)
+        .fetch_one(pool.get_ref())
+        .await
+        {
+            Ok(first) => first,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        };
+        if opened_on > Utc::now().date_naive() {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Opening date cannot be in the future".into()));
+        }
+        if let Some(first) = first.filter(|first| opened_on > *first) {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                "Opening date cannot be after the first transaction ({})",
+                first
+            )));
+        }
+        updates.push(format!("opened_on = '{}'", opened_on));
+    }
+
+    if updates.is_empty() && conversion.is_none() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("No fields to update".into()));
+    }
+
+    // Convert first, so a credit limit given alongside is in the new currency
+    if let Some((account, currency, rate)) = &conversion {
+        if let Err(e) = accounts::convert_currency(pool.get_ref(), account, currency, *rate).await {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
+        }
+    }
+
+    let result = if updates.is_empty() {
+        Ok(Default::default())
+    } else {
+        let query = format!(
+            "UPDATE accounts SET {}, updated_at = datetime('now') WHERE id = {}",
+            updates.join(", "),
+            id
+        );
+        sqlx::query(&query).execute(pool.get_ref()).await
+    };
 
     match result {
         Ok(_) => {
@@ -340,6 +1130,207 @@ async fn delete_account(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl
     }
 }
 
+/// POST /accounts/{id}/close - Move the remaining balance to another account and archive
+#[post("/accounts/{id}/close")]
+async fn close_account(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    data: web::Json<CloseAccount>,
+) -> impl Responder {
+    let load = |id: i64| {
+        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool.get_ref())
+    };
+
+    let account = match load(id.into_inner()).await {
+        Ok(Some(account)) => account,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+    let destination = match data.destination_account_id {
+        Some(destination_id) => match load(destination_id).await {
+            Ok(Some(destination)) => Some(destination),
+            Ok(None) => {
+                return HttpResponse::NotFound()
+                    .json(ApiResponse::<()>::error("Destination account not found".into()))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        },
+        None => None,
+    };
+
+    if let Err(e) = accounts::validate_closing(&account, destination.as_ref()) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let rate = match &destination {
+        Some(d) => match fx::conversion_rate(pool.get_ref(), &account.currency, &d.currency, None).await {
+            Ok(Some(rate)) => rate,
+            Ok(None) => {
+                return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                    "No exchange rate from {} to {}",
+                    account.currency, d.currency
+                )))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        },
+        None => 1.0,
+    };
+
+    match accounts::close_account(pool.get_ref(), &account, destination.as_ref(), rate).await {
+        Ok(closure) => HttpResponse::Ok().json(ApiResponse::success(closure)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /accounts/{id}/reconcile - Recompute the balance from the transactions (?fix=true to correct it)
+#[post("/accounts/{id}/reconcile")]
+async fn reconcile_account(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    query: web::Query<ReconcileQuery>,
+) -> impl Responder {
+    match reconcile::reconcile(pool.get_ref(), Some(id.into_inner()), query.fix).await {
+        Ok(mut results) if !results.is_empty() => {
+            HttpResponse::Ok().json(ApiResponse::success(results.remove(0)))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Account Group Endpoints
+// ============================================================================
+
+/// GET /account-groups - List the user's account groups
+#[get("/account-groups")]
+async fn get_account_groups(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+) -> impl Responder {
+    match account_groups::groups_for_user(pool.get_ref(), auth_user.user_id).await {
+        Ok(groups) => HttpResponse::Ok().json(ApiResponse::success(groups)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /account-groups - Create an account group (returns the existing one with that name)
+#[post("/account-groups")]
+async fn create_account_group(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    group_data: web::Json<CreateAccountGroup>,
+) -> impl Responder {
+    if group_data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    if let Err(e) = group_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    match account_groups::create_group(pool.get_ref(), group_data.user_id, &group_data.name).await {
+        Ok(group) => HttpResponse::Created().json(ApiResponse::success(group)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /account-groups/{id} - Delete an account group, leaving its accounts ungrouped
+#[delete("/account-groups/{id}")]
+async fn delete_account_group(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let result = sqlx::query("DELETE FROM account_groups WHERE id = ?")
+        .bind(id.into_inner())
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Account group deleted successfully"))
+        }
+        Ok(_) => HttpResponse::NotFound()
+            .json(ApiResponse::<()>::error("Account group not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /account-groups/{id}/accounts - Move an account into the group
+#[post("/account-groups/{id}/accounts")]
+async fn add_account_group_member(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    member: web::Json<AddAccountGroupMember>,
+) -> impl Responder {
+    let group = sqlx::query_as::<_, AccountGroup>("SELECT * FROM account_groups WHERE id = ?")
+        .bind(id.into_inner())
+        .fetch_optional(pool.get_ref())
+        .await;
+    let account = sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+        .bind(member.account_id)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    let (group, account) = match (group, account) {
+        (Ok(Some(group)), Ok(Some(account))) => (group, account),
+        (Ok(None), _) => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Account group not found".into()))
+        }
+        (_, Ok(None)) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+    if let Err(e) = account_groups::validate_member(&group, &account) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    match account_groups::set_account_group(pool.get_ref(), account.id, Some(group.id)).await {
+        Ok(account) => HttpResponse::Ok().json(ApiResponse::success(account)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /account-groups/{id}/accounts/{account_id} - Take an account out of the group
+#[delete("/account-groups/{id}/accounts/{account_id}")]
+async fn remove_account_group_member(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<(i64, i64)>,
+) -> impl Responder {
+    let (group_id, account_id) = path.into_inner();
+
+    let result = sqlx::query(
+        "UPDATE accounts SET account_group_id = NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ? AND account_group_id = ?",
+    )
+    .bind(account_id)
+    .bind(group_id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Account removed from group"))
+        }
+        Ok(_) => HttpResponse::NotFound()
+            .json(ApiResponse::<()>::error("Account is not in this group".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
 // ============================================================================
 // Category Endpoints
 // ============================================================================
@@ -348,18 +1339,22 @@ async fn delete_account(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl
 #[get("/categories")]
 async fn get_categories(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     query: web::Query<PaginationParams>,
 ) -> impl Responder {
     let offset = (query.page - 1) * query.page_size;
 
-    let categories =
-        sqlx::query_as::<_, Category>("SELECT * FROM categories ORDER BY name LIMIT ? OFFSET ?")
-            .bind(query.page_size)
-            .bind(offset)
-            .fetch_all(pool.get_ref())
-            .await;
+    let categories = sqlx::query_as::<_, Category>(
+        "SELECT * FROM categories WHERE user_id = ? ORDER BY name LIMIT ? OFFSET ?",
+    )
+    .bind(auth_user.user_id)
+    .bind(query.page_size)
+    .bind(offset)
+    .fetch_all(pool.get_ref())
+    .await;
 
-    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM categories")
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM categories WHERE user_id = ?")
+        .bind(auth_user.user_id)
         .fetch_one(pool.get_ref())
         .await
         .unwrap_or(0);
@@ -398,17 +1393,68 @@ async fn get_category(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl R
     }
 }
 
+/// The 400 (or 500) to answer with when `parent_id` can't be the parent of
+/// `category_id` (None for a new category)
+async fn category_parent_error(
+    pool: &SqlitePool,
+    user_id: i64,
+    category_id: Option<i64>,
+    parent_id: i64,
+) -> Option<HttpResponse> {
+    let parent = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+        .bind(parent_id)
+        .fetch_optional(pool)
+        .await;
+    let has_sub_categories = match category_id {
+        Some(id) => categories::has_sub_categories(pool, id).await,
+        None => Ok(false),
+    };
+    match (parent, has_sub_categories) {
+        (Ok(Some(parent)), Ok(has_sub_categories)) => {
+            categories::validate_parent(user_id, category_id, &parent, has_sub_categories)
+                .err()
+                .map(|e| HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)))
+        }
+        (Ok(None), _) => Some(
+            HttpResponse::BadRequest().json(ApiResponse::<()>::error("Parent category not found".into())),
+        ),
+        (Err(e), _) | (_, Err(e)) => {
+            Some(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())))
+        }
+    }
+}
+
 /// POST /categories - Create new category
 #[post("/categories")]
 async fn create_category(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     category_data: web::Json<CreateCategory>,
 ) -> impl Responder {
-    let result = sqlx::query("INSERT INTO categories (user_id, name) VALUES (?, ?)")
-        .bind(category_data.user_id)
-        .bind(&category_data.name)
-        .execute(pool.get_ref())
-        .await;
+    if category_data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    if let Err(e) = category_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    if let Some(Err(e)) = category_data.bucket.as_deref().map(buckets::validate_bucket) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    if let Some(parent_id) = category_data.parent_category_id {
+        if let Some(response) = category_parent_error(pool.get_ref(), auth_user.user_id, None, parent_id).await {
+            return response;
+        }
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO categories (user_id, name, bucket, parent_category_id) VALUES (?, ?, ?, ?)",
+    )
+    .bind(category_data.user_id)
+    .bind(&category_data.name)
+    .bind(&category_data.bucket)
+    .bind(category_data.parent_category_id)
+    .execute(pool.get_ref())
+    .await;
 
     match result {
         Ok(result) => {
@@ -428,36 +1474,63 @@ async fn create_category(
 #[put("/categories/{id}")]
 async fn update_category(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     id: web::Path<i64>,
     update_data: web::Json<UpdateCategory>,
 ) -> impl Responder {
     let id = id.into_inner();
+    if let Err(e) = update_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
 
-    if let Some(name) = &update_data.name {
-        let result = sqlx::query(
-            "UPDATE categories SET name = ?, updated_at = datetime('now') WHERE id = ?",
-        )
-        .bind(name)
-        .bind(id)
-        .execute(pool.get_ref())
-        .await;
+    if update_data.name.is_none()
+        && update_data.bucket.is_none()
+        && update_data.parent_category_id.is_none()
+        && update_data.exclude_from_reports.is_none()
+    {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("No fields to update".into()));
+    }
+    let bucket = update_data.bucket.as_deref().map(str::trim);
+    if let Some(Err(e)) = bucket.filter(|b| !b.is_empty()).map(buckets::validate_bucket) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    if let Some(parent_id) = update_data.parent_category_id.filter(|p| *p != 0) {
+        if let Some(response) = category_parent_error(pool.get_ref(), auth_user.user_id, Some(id), parent_id).await {
+            return response;
+        }
+    }
 
-        match result {
-            Ok(_) => {
-                let category =
-                    sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
-                        .bind(id)
-                        .fetch_one(pool.get_ref())
-                        .await
-                        .unwrap();
-                HttpResponse::Ok().json(ApiResponse::success(category))
-            }
-            Err(e) => {
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
-            }
+    // An empty bucket clears it and a parent of 0 moves the category back to
+    // the top level; missing ones are left as is
+    let result = sqlx::query(
+        "UPDATE categories SET name = COALESCE(?, name),
+             bucket = CASE WHEN ? THEN NULLIF(?, '') ELSE bucket END,
+             parent_category_id = CASE WHEN ? THEN NULLIF(?, 0) ELSE parent_category_id END,
+             exclude_from_reports = COALESCE(?, exclude_from_reports),
+             updated_at = datetime('now')
+         WHERE id = ?",
+    )
+    .bind(&update_data.name)
+    .bind(bucket.is_some())
+    .bind(bucket)
+    .bind(update_data.parent_category_id.is_some())
+    .bind(update_data.parent_category_id)
+    .bind(update_data.exclude_from_reports)
+    .bind(id)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => {
+            let category = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+                .bind(id)
+                .fetch_one(pool.get_ref())
+                .await
+                .unwrap();
+            HttpResponse::Ok().json(ApiResponse::success(category))
         }
-    } else {
-        HttpResponse::BadRequest().json(ApiResponse::<()>::error("No name provided".into()))
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
 }
 
@@ -491,11 +1564,20 @@ async fn delete_category(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> imp
 #[get("/transactions")]
 async fn get_transactions(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     query: web::Query<TransactionFilter>,
 ) -> impl Responder {
     let offset = (query.page - 1) * query.page_size;
+    if query.include_running_balance && query.account_id.is_none() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+            "include_running_balance requires account_id".into(),
+        ));
+    }
 
-    let mut where_clauses = Vec::new();
+    let mut where_clauses = vec![format!(
+        "account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+        auth_user.user_id
+    )];
 
     if let Some(account_id) = query.account_id {
         where_clauses.push(format!("account_id = {}", account_id));
@@ -503,12 +1585,45 @@ async fn get_transactions(
     if let Some(ref txn_type) = query.transaction_type {
         where_clauses.push(format!("transaction_type = '{}'", txn_type));
     }
+    if let Some(ref status) = query.status {
+        if let Err(e) = transaction_status::validate_status(status) {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+        }
+        where_clauses.push(format!("status = '{}'", status));
+    }
+    if let Some(transfer_group_id) = query.transfer_group_id {
+        where_clauses.push(format!("transfer_group_id = {}", transfer_group_id));
+    }
+    for name in query.tag.iter().flat_map(|t| t.split(',')) {
+        let name = match tags::normalize_tag(name) {
+            Ok(name) => name,
+            Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+        };
+        where_clauses.push(format!(
+            "id IN (SELECT tt.transaction_id FROM transaction_tags tt JOIN tags g ON g.id = tt.tag_id WHERE g.name = '{}')",
+            name
+        ));
+    }
+    if let Some(ref start_date) = query.start_date {
+        where_clauses.push(format!("datetime(transaction_date) >= datetime('{}')", start_date.to_rfc3339()));
+    }
+    if let Some(ref end_date) = query.end_date {
+        where_clauses.push(format!("datetime(transaction_date) <= datetime('{}')", end_date.to_rfc3339()));
+    }
+    if let Some(ref period) = query.period {
+        let Some((start, end)) = budgets::resolve_period(period, Utc::now()) else {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+                "period must be this_month, last_month, this_quarter, last_quarter, ytd or YYYY-MM".into(),
+            ));
+        };
+        where_clauses.push(format!(
+            "datetime(transaction_date) >= datetime('{}') AND datetime(transaction_date) < datetime('{}')",
+            start.to_rfc3339(),
+            end.to_rfc3339()
+        ));
+    }
 
-    let where_sql = if where_clauses.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", where_clauses.join(" AND "))
-    };
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
 
     let query_sql = format!(
         "SELECT * FROM transactions {} ORDER BY transaction_date DESC LIMIT {} OFFSET {}",
@@ -525,18 +1640,42 @@ async fn get_transactions(
         .await
         .unwrap_or(0);
 
-    match transactions {
-        Ok(transactions) => {
-            let response = PaginatedResponse {
-                items: transactions,
-                total,
-                page: query.page,
-                page_size: query.page_size,
-                total_pages: (total + query.page_size - 1) / query.page_size,
-            };
-            HttpResponse::Ok().json(ApiResponse::success(response))
+    let transactions = match transactions {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
         }
-        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    };
+    let total_pages = (total + query.page_size - 1) / query.page_size;
+
+    match query.account_id.filter(|_| query.include_running_balance) {
+        Some(account_id) => match reconcile::running_balances(pool.get_ref(), account_id).await {
+            Ok(balances) => {
+                let items = transactions
+                    .into_iter()
+                    .map(|transaction| TransactionWithRunningBalance {
+                        running_balance: balances.get(&transaction.id).copied().unwrap_or_default(),
+                        transaction,
+                    })
+                    .collect();
+                HttpResponse::Ok().json(ApiResponse::success(PaginatedResponse {
+                    items,
+                    total,
+                    page: query.page,
+                    page_size: query.page_size,
+                    total_pages,
+                }))
+            }
+            Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+        },
+        None => HttpResponse::Ok().json(ApiResponse::success(PaginatedResponse {
+            items: transactions,
+            total,
+            page: query.page,
+            page_size: query.page_size,
+            total_pages,
+        })),
     }
 }
 
@@ -579,6 +1718,7 @@ async fn get_transaction(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> imp
                 .collect();
 
             let response = TransactionWithCategories {
+                tags: tags::tags_for_transaction(pool.get_ref(), id).await.unwrap_or_default(),
                 transaction,
                 categories,
             };
@@ -596,6 +1736,7 @@ async fn get_transaction(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> imp
 #[post("/transactions")]
 async fn create_transaction(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     txn_data: web::Json<CreateTransaction>,
 ) -> impl Responder {
     if let Err(e) = txn_data.validate() {
@@ -603,55 +1744,209 @@ async fn create_transaction(
     }
 
     let txn_date = txn_data.transaction_date.unwrap_or_else(Utc::now);
+    let merchant = txn_data
+        .merchant
+        .as_deref()
+        .and_then(merchants::normalize_merchant);
+
+    // Owner of the account, used to route webhook events, its default category,
+    // its opening date and whether it was closed
+    let account: Option<(i64, Option<i64>, Option<NaiveDate>, bool)> = sqlx::query_as(
+        "SELECT user_id, default_category_id, opened_on, is_archived FROM accounts WHERE id = ?",
+    )
+    .bind(txn_data.account_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .unwrap_or(None);
+
+    let (user_id, default_category_id, opened_on, is_archived) = match account {
+        Some(account) => account,
+        None => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Account not found".into()))
+        }
+    };
+    if user_id != auth_user.user_id {
+        return forbidden();
+    }
+    let category_ids: Vec<i64> =
+        txn_data.categories.iter().map(|c| c.category_id).chain(txn_data.fee_category_id).collect();
+    if let Some(response) = check_category_owner(pool.get_ref(), user_id, &category_ids).await {
+        return response;
+    }
+    if is_archived {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("Account is closed".into()));
+    }
+    if let Some(opened_on) = opened_on.filter(|d| txn_date.date_naive() < *d) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+            "Transaction date is before the account was opened ({})",
+            opened_on
+        )));
+    }
+
+    let (base_amount, base_currency) =
+        match fx::base_amount_snapshot(pool.get_ref(), txn_data.account_id, txn_data.amount, txn_date).await {
+            Ok(snapshot) => snapshot.unzip(),
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
 
+    let status = txn_data.status.as_deref().unwrap_or("cleared");
     let result = sqlx::query(
-        "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date) VALUES (?, ?, ?, ?, ?)"
+        "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, notes, reference, merchant, merchant_city, merchant_country, original_amount, original_currency, exchange_rate, fee, fee_category_id, base_amount, base_currency, status) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     )
     .bind(txn_data.account_id)
     .bind(txn_data.amount)
     .bind(&txn_data.transaction_type)
     .bind(&txn_data.description)
     .bind(txn_date)
-    .execute(pool.get_ref())
+    .bind(&txn_data.notes)
+    .bind(&txn_data.reference)
+    .bind(&merchant)
+    .bind(&txn_data.merchant_city)
+    .bind(&txn_data.merchant_country)
+    .bind(txn_data.original_amount)
+    .bind(txn_data.original_currency.as_deref().map(|c| c.to_uppercase()))
+    .bind(txn_data.applied_rate())
+    .bind(txn_data.fee.unwrap_or(0.0))
+    .bind(txn_data.fee_category_id)
+    .bind(base_amount)
+    .bind(base_currency)
+    .bind(status)
+    .execute(&mut *tx)
     .await;
 
     match result {
         Ok(result) => {
             let transaction_id = result.last_insert_rowid();
 
-            for cat_amount in &txn_data.categories {
-                let _ = sqlx::query(
+            // Unsplit income/expenses fall back to the account's default category
+            let default_split = match default_category_id {
+                Some(category_id)
+                    if txn_data.categories.is_empty() && txn_data.transaction_type != "transfer" =>
+                {
+                    vec![CategoryAmount {
+                        category_id,
+                        amount: txn_data.amount,
+                    }]
+                }
+                _ => Vec::new(),
+            };
+
+            // A failed split or balance update drops tx, rolling the insert back
+            for cat_amount in txn_data.categories.iter().chain(&default_split) {
+                if let Err(e) = sqlx::query(
                     "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)"
                 )
                 .bind(transaction_id)
                 .bind(cat_amount.category_id)
                 .bind(cat_amount.amount)
-                .execute(pool.get_ref())
-                .await;
+                .execute(&mut *tx)
+                .await
+                {
+                    return HttpResponse::InternalServerError()
+                        .json(ApiResponse::<()>::error(e.to_string()));
+                }
             }
 
-            let balance_change = if txn_data.transaction_type == "income" {
-                txn_data.amount
+            // Fees are always debited on top of the amount; pending and void
+            // transactions leave the balance alone until they clear
+            let balance_change = if status == "cleared" {
+                transaction_status::balance_effect(
+                    &txn_data.transaction_type,
+                    txn_data.amount,
+                    txn_data.fee.unwrap_or(0.0),
+                )
             } else {
-                -txn_data.amount.abs()
+                0.0
             };
 
-            let _ = sqlx::query(
+            if let Err(e) = sqlx::query(
                 "UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?",
             )
             .bind(balance_change)
             .bind(txn_data.account_id)
-            .execute(pool.get_ref())
-            .await;
+            .execute(&mut *tx)
+            .await
+            {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()));
+            }
 
             let transaction =
-                sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+                match sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
                     .bind(transaction_id)
-                    .fetch_one(pool.get_ref())
+                    .fetch_one(&mut *tx)
                     .await
-                    .unwrap();
+                {
+                    Ok(transaction) => transaction,
+                    Err(e) => {
+                        return HttpResponse::InternalServerError()
+                            .json(ApiResponse::<()>::error(e.to_string()))
+                    }
+                };
+
+            // Outbox row is written in the same DB transaction as the insert
+            let payload = serde_json::to_value(&transaction).unwrap_or_default();
+            let mut enqueued = webhooks::enqueue_event(
+                &mut tx,
+                user_id,
+                "transaction.created",
+                &payload,
+            )
+            .await;
+
+            // Alert once when this transaction pushes card utilization over the threshold
+            let limit: Option<(f64, Option<f64>)> =
+                sqlx::query_as("SELECT current_balance, credit_limit FROM accounts WHERE id = ?")
+                    .bind(txn_data.account_id)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .unwrap_or(None);
+            if let (Ok(_), Some((balance, Some(credit_limit)))) = (&enqueued, limit) {
+                let threshold = credit::utilization_threshold();
+                let before = credit::utilization_percent(balance - balance_change, Some(credit_limit));
+                let after = credit::utilization_percent(balance, Some(credit_limit));
+                if credit::crossed_threshold(before, after, threshold) {
+                    let alert = serde_json::json!({
+                        "account_id": txn_data.account_id,
+                        "transaction_id": transaction_id,
+                        "current_balance": balance,
+                        "credit_limit": credit_limit,
+                        "utilization_percent": after,
+                        "threshold": threshold,
+                    });
+                    enqueued = webhooks::enqueue_event(
+                        &mut tx,
+                        user_id,
+                        "account.credit_utilization_high",
+                        &alert,
+                    )
+                    .await;
+                }
+            }
+
+            let committed = match enqueued {
+                Ok(_) => tx.commit().await,
+                Err(e) => Err(e),
+            };
 
-            HttpResponse::Created().json(ApiResponse::success(transaction))
+            match committed {
+                Ok(_) => HttpResponse::Created().json(ApiResponse::success(transaction)),
+                Err(e) => HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string())),
+            }
         }
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
@@ -661,45 +1956,315 @@ async fn create_transaction(
 #[put("/transactions/{id}")]
 async fn update_transaction(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     id: web::Path<i64>,
     update_data: web::Json<UpdateTransaction>,
 ) -> impl Responder {
     let id = id.into_inner();
-    let mut updates = Vec::new();
-
-    if let Some(amount) = update_data.amount {
-        updates.push(format!("amount = {}", amount));
-    }
-    if let Some(ref txn_type) = update_data.transaction_type {
-        updates.push(format!("transaction_type = '{}'", txn_type));
+    if let Err(e) = update_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
     }
-    if let Some(ref desc) = update_data.description {
-        updates.push(format!("description = '{}'", desc));
+    let category_ids: Vec<i64> = update_data
+        .categories
+        .iter()
+        .flatten()
+        .map(|c| c.category_id)
+        .chain(update_data.fee_category_id.filter(|id| *id != 0))
+        .collect();
+    if let Some(response) = check_category_owner(pool.get_ref(), auth_user.user_id, &category_ids).await {
+        return response;
     }
-
-    if updates.is_empty() {
+    if update_data.is_empty() {
         return HttpResponse::BadRequest()
             .json(ApiResponse::<()>::error("No fields to update".into()));
     }
+    if let Some(ref currency) = update_data.original_currency {
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Original currency must be a 3-letter code".into()));
+        }
+    }
+    if update_data.exchange_rate.is_some_and(|rate| rate <= 0.0) {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("Exchange rate must be positive".into()));
+    }
+    if update_data.fee.is_some_and(|fee| fee < 0.0) {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("Fee must not be negative".into()));
+    }
 
-    let query = format!(
-        "UPDATE transactions SET {}, updated_at = datetime('now') WHERE id = {}",
-        updates.join(", "),
-        id
-    );
+    let existing = match sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool.get_ref())
+        .await
+    {
+        Ok(Some(transaction)) => transaction,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Transaction not found".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
 
-    let result = sqlx::query(&query).execute(pool.get_ref()).await;
+    // The splits, new or kept, have to add up to the new amount
+    let amount = update_data.amount.unwrap_or(existing.amount);
+    let splits = match &update_data.categories {
+        Some(categories) => categories.clone(),
+        None if amount == existing.amount => Vec::new(),
+        None => match sqlx::query_as::<_, (i64, f64)>(
+            "SELECT category_id, amount FROM transaction_categories WHERE transaction_id = ?",
+        )
+        .bind(id)
+        .fetch_all(pool.get_ref())
+        .await
+        {
+            Ok(rows) => rows
+                .into_iter()
+                .map(|(category_id, amount)| CategoryAmount { category_id, amount })
+                .collect(),
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            }
+        },
+    };
+    if let Err(e) = (UpdateTransactionCategories { categories: splits }).validate(amount) {
+        return HttpResponse::UnprocessableEntity().json(ApiResponse::<()>::error(e));
+    }
+
+    // A new amount or date is valued again in the owner's base currency
+    let rebase = update_data.amount.is_some() || update_data.transaction_date.is_some();
+    let (base_amount, base_currency) = if rebase {
+        let date = update_data.transaction_date.unwrap_or(existing.transaction_date);
+        match fx::base_amount_snapshot(pool.get_ref(), existing.account_id, amount, date).await {
+            Ok(snapshot) => snapshot.unzip(),
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
+    } else {
+        (None, None)
+    };
+
+    // An empty description or merchant, or fee category 0, is cleared; an
+    // absent one is kept
+    let merchant = update_data.merchant.as_deref().map(merchants::normalize_merchant);
+
+    let result: Result<Transaction, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+        let updated = sqlx::query_as::<_, Transaction>(
+            "UPDATE transactions SET
+                 amount = COALESCE(?, amount),
+                 transaction_type = COALESCE(?, transaction_type),
+                 description = CASE WHEN ? THEN NULLIF(?, '') ELSE description END,
+                 transaction_date = COALESCE(?, transaction_date),
+                 notes = COALESCE(?, notes),
+                 reference = COALESCE(?, reference),
+                 merchant = CASE WHEN ? THEN ? ELSE merchant END,
+                 merchant_city = COALESCE(?, merchant_city),
+                 merchant_country = COALESCE(?, merchant_country),
+                 original_amount = COALESCE(?, original_amount),
+                 original_currency = COALESCE(?, original_currency),
+                 exchange_rate = COALESCE(?, exchange_rate),
+                 fee = COALESCE(?, fee),
+                 fee_category_id = CASE WHEN ? THEN NULLIF(?, 0) ELSE fee_category_id END,
+                 exclude_from_reports = COALESCE(?, exclude_from_reports),
+                 base_amount = CASE WHEN ? THEN ? ELSE base_amount END,
+                 base_currency = CASE WHEN ? THEN ? ELSE base_currency END,
+                 updated_at = datetime('now')
+             WHERE id = ?
+             RETURNING *",
+        )
+        .bind(update_data.amount)
+        .bind(&update_data.transaction_type)
+        .bind(update_data.description.is_some())
+        .bind(&update_data.description)
+        .bind(update_data.transaction_date)
+        .bind(&update_data.notes)
+        .bind(&update_data.reference)
+        .bind(merchant.is_some())
+        .bind(merchant.flatten())
+        .bind(&update_data.merchant_city)
+        .bind(&update_data.merchant_country)
+        .bind(update_data.original_amount)
+        .bind(update_data.original_currency.as_ref().map(|c| c.to_uppercase()))
+        .bind(update_data.exchange_rate)
+        .bind(update_data.fee)
+        .bind(update_data.fee_category_id.is_some())
+        .bind(update_data.fee_category_id)
+        .bind(update_data.exclude_from_reports)
+        .bind(rebase)
+        .bind(base_amount)
+        .bind(rebase)
+        .bind(base_currency)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Some(categories) = &update_data.categories {
+            sqlx::query("DELETE FROM transaction_categories WHERE transaction_id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            for split in categories {
+                sqlx::query(
+                    "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)",
+                )
+                .bind(id)
+                .bind(split.category_id)
+                .bind(split.amount)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        // Amount, type and fee all feed the posted balance
+        let change = transaction_status::posted_effect(&updated) - transaction_status::posted_effect(&existing);
+        if change != 0.0 {
+            sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+                .bind(change)
+                .bind(existing.account_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(updated)
+    }
+    .await;
 
     match result {
-        Ok(_) => {
-            let transaction =
-                sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
-                    .bind(id)
-                    .fetch_one(pool.get_ref())
-                    .await
-                    .unwrap();
-            HttpResponse::Ok().json(ApiResponse::success(transaction))
+        Ok(transaction) => HttpResponse::Ok().json(ApiResponse::success(transaction)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /transactions/{id}/categories - Replace the category splits of a transaction
+#[put("/transactions/{id}/categories")]
+async fn update_transaction_categories(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    id: web::Path<i64>,
+    data: web::Json<UpdateTransactionCategories>,
+) -> impl Responder {
+    let id = id.into_inner();
+
+    let transaction = match sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool.get_ref())
+        .await
+    {
+        Ok(Some(transaction)) => transaction,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Transaction not found".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    if let Err(e) = data.validate(transaction.amount) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    let category_ids: Vec<i64> = data.categories.iter().map(|c| c.category_id).collect();
+    if let Some(response) = check_category_owner(pool.get_ref(), auth_user.user_id, &category_ids).await {
+        return response;
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    let mut categories = Vec::new();
+    for split in &data.categories {
+        match sqlx::query_scalar::<_, String>("SELECT name FROM categories WHERE id = ?")
+            .bind(split.category_id)
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(category_name) => categories.push(TransactionCategoryDetail {
+                category_id: split.category_id,
+                category_name,
+                amount: split.amount,
+            }),
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM transaction_categories WHERE transaction_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+    {
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
+    }
+    for split in &data.categories {
+        if let Err(e) = sqlx::query(
+            "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)",
+        )
+        .bind(id)
+        .bind(split.category_id)
+        .bind(split.amount)
+        .execute(&mut *tx)
+        .await
+        {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
+    }
+    match tags::tags_for_transaction(pool.get_ref(), id).await {
+        Ok(tags) => HttpResponse::Ok().json(ApiResponse::success(TransactionWithCategories {
+            transaction,
+            categories,
+            tags,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /transactions/{id}/status - Mark a transaction pending, cleared or void
+#[put("/transactions/{id}/status")]
+async fn update_transaction_status(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    data: web::Json<UpdateTransactionStatus>,
+) -> impl Responder {
+    if let Err(e) = transaction_status::validate_status(&data.status) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let transaction = match sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+        .bind(id.into_inner())
+        .fetch_optional(pool.get_ref())
+        .await
+    {
+        Ok(Some(transaction)) => transaction,
+        Ok(None) => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Transaction not found".into()))
         }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    match transaction_status::set_status(pool.get_ref(), &transaction, &data.status).await {
+        Ok(transaction) => HttpResponse::Ok().json(ApiResponse::success(transaction)),
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
 }
@@ -727,43 +2292,83 @@ async fn delete_transaction(pool: web::Data<SqlitePool>, id: web::Path<i64>) ->
         }
     };
 
-    // 2. Compute the reverse balance change
-    let balance_change = if txn.transaction_type == "income" {
-        // Creation: +amount  → Deletion: -amount
-        -txn.amount
-    } else {
-        // Creation: -amount.abs() → Deletion: +amount.abs()
-        txn.amount.abs()
+    // 2. Compute the reverse balance change (nothing was posted unless it cleared)
+    let balance_change = -transaction_status::posted_effect(&txn);
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
     };
 
     // 3. Delete any related transaction_categories rows (if you have them)
     if let Err(e) = sqlx::query("DELETE FROM transaction_categories WHERE transaction_id = ?")
         .bind(id)
-        .execute(pool.get_ref())
+        .execute(&mut *tx)
         .await
     {
         return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
     }
 
+    // Attachment rows cascade with the transaction; their files go after the commit
+    let attachment_dir = attachments::storage_dir();
+    let attachment_files = attachments::stored_files(pool.get_ref(), &attachment_dir, id)
+        .await
+        .unwrap_or_default();
+
     // 4. Delete the transaction itself
     let result = sqlx::query("DELETE FROM transactions WHERE id = ?")
         .bind(id)
-        .execute(pool.get_ref())
+        .execute(&mut *tx)
         .await;
 
     match result {
         Ok(result) => {
             if result.rows_affected() > 0 {
                 // 5. Apply the balance update to the account
-                let _ = sqlx::query(
+                if let Err(e) = sqlx::query(
                     "UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?",
                 )
                 .bind(balance_change)
                 .bind(txn.account_id)
-                .execute(pool.get_ref())
-                .await;
-
-                HttpResponse::Ok().json(ApiResponse::success("Transaction deleted successfully"))
+                .execute(&mut *tx)
+                .await
+                {
+                    return HttpResponse::InternalServerError()
+                        .json(ApiResponse::<()>::error(e.to_string()));
+                }
+
+                // 6. Record the event in the outbox within the same DB transaction
+                let user_id: Option<i64> =
+                    sqlx::query_scalar("SELECT user_id FROM accounts WHERE id = ?")
+                        .bind(txn.account_id)
+                        .fetch_optional(&mut *tx)
+                        .await
+                        .unwrap_or(None);
+
+                if let Some(user_id) = user_id {
+                    let payload = serde_json::to_value(&txn).unwrap_or_default();
+                    if let Err(e) =
+                        webhooks::enqueue_event(&mut tx, user_id, "transaction.deleted", &payload)
+                            .await
+                    {
+                        return HttpResponse::InternalServerError()
+                            .json(ApiResponse::<()>::error(e.to_string()));
+                    }
+                }
+
+                match tx.commit().await {
+                    Ok(_) => {
+                        for file in attachment_files {
+                            let _ = std::fs::remove_file(file);
+                        }
+                        HttpResponse::Ok().json(ApiResponse::success("Transaction deleted successfully"))
+                    }
+                    Err(e) => HttpResponse::InternalServerError()
+                        .json(ApiResponse::<()>::error(e.to_string())),
+                }
             } else {
                 // Shouldn’t really happen since we already fetched it,
                 // but keep the check for safety.
@@ -776,42 +2381,447 @@ async fn delete_transaction(pool: web::Data<SqlitePool>, id: web::Path<i64>) ->
 }
 
 // ============================================================================
-// Exchange Rate Endpoints
+// Tag Endpoints
 // ============================================================================
 
-/// GET /exchange-rates - List exchange rates with filters
-#[get("/exchange-rates")]
-async fn get_exchange_rates(
+/// GET /tags - List the user's tags
+#[get("/tags")]
+async fn get_tags(pool: web::Data<SqlitePool>, auth_user: web::ReqData<AuthUser>) -> impl Responder {
+    match tags::tags_for_user(pool.get_ref(), auth_user.user_id).await {
+        Ok(tags) => HttpResponse::Ok().json(ApiResponse::success(tags)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /tags - Create a tag (returns the existing one with that name)
+#[post("/tags")]
+async fn create_tag(
     pool: web::Data<SqlitePool>,
-    query: web::Query<ExchangeRateFilter>,
+    auth_user: web::ReqData<AuthUser>,
+    tag_data: web::Json<CreateTag>,
 ) -> impl Responder {
-    let offset = (query.page - 1) * query.page_size as i64;
-
-    let mut where_clauses = Vec::new();
-
-    if let Some(ref from) = query.from_currency {
-        where_clauses.push(format!("from_currency = '{}'", from));
-    }
-    if let Some(ref to) = query.to_currency {
-        where_clauses.push(format!("to_currency LIKE '%{}%'", to));
-    }
-    if let Some(ref source) = query.source {
-        where_clauses.push(format!("source = '{}'", source));
-    }
-    if let Some(date) = query.date {
-        where_clauses.push(format!("DATE(rate_date) = '{}'", date.format("%Y-%m-%d")));
+    if tag_data.user_id != auth_user.user_id {
+        return forbidden();
     }
-
-    let where_sql = if where_clauses.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", where_clauses.join(" AND "))
+    let name = match tags::normalize_tag(&tag_data.name) {
+        Ok(name) => name,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
     };
 
-    let query_sql = format!(
-        "SELECT * FROM exchange_rates {} ORDER BY rate_date DESC, from_currency, to_currency LIMIT {} OFFSET {}",
-        where_sql, query.page_size, offset
-    );
+    match tags::create_tag(pool.get_ref(), tag_data.user_id, &name).await {
+        Ok(tag) => HttpResponse::Created().json(ApiResponse::success(tag)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /tags/{id} - Delete a tag, taking it off every transaction
+#[delete("/tags/{id}")]
+async fn delete_tag(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let result = sqlx::query("DELETE FROM tags WHERE id = ?")
+        .bind(id.into_inner())
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Tag deleted successfully"))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Tag not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /transactions/{id}/tags - Tag a transaction by name, creating the tag if needed
+#[post("/transactions/{id}/tags")]
+async fn add_transaction_tag(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    id: web::Path<i64>,
+    tag_data: web::Json<AddTransactionTag>,
+) -> impl Responder {
+    let id = id.into_inner();
+    let name = match tags::normalize_tag(&tag_data.name) {
+        Ok(name) => name,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+    };
+    // The session middleware has already turned away other users' transactions
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM transactions WHERE id = ?)")
+        .bind(id)
+        .fetch_one(pool.get_ref())
+        .await;
+    match exists {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Transaction not found".into()))
+        }
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+
+    let tagged = match tags::create_tag(pool.get_ref(), auth_user.user_id, &name).await {
+        Ok(tag) => tags::add_tag(pool.get_ref(), id, tag.id).await,
+        Err(e) => Err(e),
+    };
+    match tagged {
+        Ok(()) => match tags::tags_for_transaction(pool.get_ref(), id).await {
+            Ok(tags) => HttpResponse::Ok().json(ApiResponse::success(tags)),
+            Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+        },
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /transactions/{id}/tags/{tag_id} - Take a tag off a transaction
+#[delete("/transactions/{id}/tags/{tag_id}")]
+async fn remove_transaction_tag(pool: web::Data<SqlitePool>, path: web::Path<(i64, i64)>) -> impl Responder {
+    let (id, tag_id) = path.into_inner();
+    match tags::remove_tag(pool.get_ref(), id, tag_id).await {
+        Ok(true) => match tags::tags_for_transaction(pool.get_ref(), id).await {
+            Ok(tags) => HttpResponse::Ok().json(ApiResponse::success(tags)),
+            Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+        },
+        Ok(false) => HttpResponse::NotFound()
+            .json(ApiResponse::<()>::error("Transaction doesn't have that tag".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Attachment Endpoints
+// ============================================================================
+
+/// POST /transactions/{id}/attachments - Attach receipt images or PDFs (multipart/form-data)
+#[post("/transactions/{id}/attachments")]
+async fn upload_attachments(
+    pool: web::Data<SqlitePool>,
+    req: HttpRequest,
+    id: web::Path<i64>,
+    body: web::Bytes,
+) -> impl Responder {
+    let id = id.into_inner();
+    let content_type = req
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let files = match attachments::parse_multipart(content_type, &body) {
+        Ok(files) if files.is_empty() => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Upload has no files".into()))
+        }
+        Ok(files) => files,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+    };
+    if let Some(e) = files.iter().find_map(|file| attachments::validate_upload(file).err()) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    // The session middleware has already turned away other users' transactions
+    let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM transactions WHERE id = ?)")
+        .bind(id)
+        .fetch_one(pool.get_ref())
+        .await;
+    match exists {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Transaction not found".into()))
+        }
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+
+    let dir = attachments::storage_dir();
+    let mut saved = Vec::new();
+    for file in &files {
+        match attachments::save_attachment(pool.get_ref(), &dir, id, file).await {
+            Ok(attachment) => saved.push(attachment),
+            Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+        }
+    }
+    HttpResponse::Created().json(ApiResponse::success(saved))
+}
+
+/// GET /transactions/{id}/attachments - List a transaction's attachments
+#[get("/transactions/{id}/attachments")]
+async fn get_attachments(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    match attachments::attachments_for_transaction(pool.get_ref(), id.into_inner()).await {
+        Ok(attachments) => HttpResponse::Ok().json(ApiResponse::success(attachments)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+async fn find_attachment(pool: &SqlitePool, transaction_id: i64, attachment_id: i64) -> Result<Option<Attachment>, sqlx::Error> {
+    sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE id = ? AND transaction_id = ?")
+        .bind(attachment_id)
+        .bind(transaction_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// GET /transactions/{id}/attachments/{attachment_id} - Download an attachment
+#[get("/transactions/{id}/attachments/{attachment_id}")]
+async fn download_attachment(pool: web::Data<SqlitePool>, path: web::Path<(i64, i64)>) -> impl Responder {
+    let (id, attachment_id) = path.into_inner();
+    let attachment = match find_attachment(pool.get_ref(), id, attachment_id).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("Attachment not found".into())),
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    };
+    match attachments::read_attachment(&attachments::storage_dir(), &attachment) {
+        Ok(data) => HttpResponse::Ok()
+            .content_type(attachment.content_type.as_str())
+            .insert_header(("Content-Disposition", format!("inline; filename=\"{}\"", attachment.filename)))
+            .body(data),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /transactions/{id}/attachments/{attachment_id} - Remove an attachment
+#[delete("/transactions/{id}/attachments/{attachment_id}")]
+async fn delete_attachment(pool: web::Data<SqlitePool>, path: web::Path<(i64, i64)>) -> impl Responder {
+    let (id, attachment_id) = path.into_inner();
+    let attachment = match find_attachment(pool.get_ref(), id, attachment_id).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("Attachment not found".into())),
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    };
+    match attachments::delete_attachment(pool.get_ref(), &attachments::storage_dir(), &attachment).await {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::success("Attachment deleted successfully")),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Reimbursement Endpoints
+// ============================================================================
+
+/// POST /transactions/{id}/link-reimbursement - Link a later income as paying back this expense
+#[post("/transactions/{id}/link-reimbursement")]
+async fn link_reimbursement(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    id: web::Path<i64>,
+    link_data: web::Json<LinkReimbursement>,
+) -> impl Responder {
+    let id = id.into_inner();
+    // The session middleware has checked the expense; the reimbursement is
+    // looked up among the user's own transactions
+    let expense = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool.get_ref())
+        .await;
+    let reimbursement = sqlx::query_as::<_, Transaction>(
+        "SELECT t.* FROM transactions t JOIN accounts a ON a.id = t.account_id WHERE t.id = ? AND a.user_id = ?",
+    )
+    .bind(link_data.reimbursement_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(pool.get_ref())
+    .await;
+    let (expense, reimbursement) = match (expense, reimbursement) {
+        (Ok(Some(expense)), Ok(Some(reimbursement))) => (expense, reimbursement),
+        (Ok(None), _) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Transaction not found".into()))
+        }
+        (_, Ok(None)) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Reimbursement not found".into()))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+    if let Err(e) = reimbursements::validate_link(&expense, &reimbursement) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let linked = match reimbursements::link(pool.get_ref(), expense.id, reimbursement.id).await {
+        Ok(()) => reimbursements::reimbursed_expense(pool.get_ref(), expense).await,
+        Err(e) => Err(e),
+    };
+    match linked {
+        Ok(linked) => HttpResponse::Ok().json(ApiResponse::success(linked)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /transactions/{id}/link-reimbursement/{reimbursement_id} - Unlink a reimbursement
+#[delete("/transactions/{id}/link-reimbursement/{reimbursement_id}")]
+async fn unlink_reimbursement(pool: web::Data<SqlitePool>, path: web::Path<(i64, i64)>) -> impl Responder {
+    let (id, reimbursement_id) = path.into_inner();
+    match reimbursements::unlink(pool.get_ref(), id, reimbursement_id).await {
+        Ok(true) => {
+            let expense = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+                .bind(id)
+                .fetch_one(pool.get_ref())
+                .await;
+            match expense {
+                Ok(expense) => match reimbursements::reimbursed_expense(pool.get_ref(), expense).await {
+                    Ok(linked) => HttpResponse::Ok().json(ApiResponse::success(linked)),
+                    Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+                },
+                Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+            }
+        }
+        Ok(false) => HttpResponse::NotFound()
+            .json(ApiResponse::<()>::error("That transaction doesn't reimburse this expense".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Transfer Endpoints
+// ============================================================================
+
+/// POST /transactions/receipt - Read an uploaded receipt into a draft expense to confirm
+#[post("/transactions/receipt")]
+async fn parse_receipt(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    query: web::Query<ReceiptQuery>,
+    body: web::Bytes,
+) -> impl Responder {
+    let parser = match receipts::parser_from_env() {
+        Ok(Some(parser)) => parser,
+        Ok(None) => {
+            return HttpResponse::ServiceUnavailable()
+                .json(ApiResponse::<()>::error("Receipt parsing is turned off".into()))
+        }
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e)),
+    };
+    if body.is_empty() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("Receipt upload is empty".into()));
+    }
+    if let Some(account_id) = query.account_id {
+        match user_owns_account(pool.get_ref(), auth_user.user_id, account_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::NotFound()
+                    .json(ApiResponse::<()>::error("Account not found".into()))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
+    }
+
+    let fields = match parser.parse(&body) {
+        Ok(fields) => fields,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+    };
+
+    match receipts::draft(pool.get_ref(), auth_user.user_id, query.account_id, fields, parser.name()).await {
+        Ok(draft) => HttpResponse::Ok().json(ApiResponse::success(draft)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /transfers - Transfers between the user's accounts, both legs of each, newest first
+#[get("/transfers")]
+async fn get_transfers(pool: web::Data<SqlitePool>, auth_user: web::ReqData<AuthUser>) -> impl Responder {
+    match transfers::transfers_for_user(pool.get_ref(), auth_user.user_id).await {
+        Ok(transfers) => HttpResponse::Ok().json(ApiResponse::success(transfers)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /transfers - Move money between two of the user's accounts
+#[post("/transfers")]
+async fn create_transfer(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<CreateTransfer>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let load = |id: i64| {
+        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool.get_ref())
+    };
+    let mut accounts = Vec::new();
+    for (id, label) in [(data.from_account_id, "Source"), (data.to_account_id, "Destination")] {
+        match load(id).await {
+            Ok(Some(account)) if account.user_id != auth_user.user_id => return forbidden(),
+            Ok(Some(account)) => accounts.push(account),
+            Ok(None) => {
+                return HttpResponse::NotFound()
+                    .json(ApiResponse::<()>::error(format!("{} account not found", label)))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
+    }
+    let (from, to) = (&accounts[0], &accounts[1]);
+
+    let date = data.transaction_date.unwrap_or_else(Utc::now);
+    if let Err(e) = transfers::validate_transfer(from, to, date) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let rate = match fx::conversion_rate(pool.get_ref(), &from.currency, &to.currency, None).await {
+        Ok(Some(rate)) => rate,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                "No exchange rate from {} to {}",
+                from.currency, to.currency
+            )))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    match transfers::create_transfer(pool.get_ref(), from, to, &data, rate).await {
+        Ok(transfer) => HttpResponse::Created().json(ApiResponse::success(transfer)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Exchange Rate Endpoints
+// ============================================================================
+
+/// GET /exchange-rates - List exchange rates with filters
+#[get("/exchange-rates")]
+async fn get_exchange_rates(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<ExchangeRateFilter>,
+) -> impl Responder {
+    let offset = (query.page - 1) * query.page_size;
+
+    let mut where_clauses = Vec::new();
+
+    if let Some(ref from) = query.from_currency {
+        where_clauses.push(format!("from_currency = '{}'", from));
+    }
+    if let Some(ref to) = query.to_currency {
+        where_clauses.push(format!("to_currency LIKE '%{}%'", to));
+    }
+    if let Some(ref source) = query.source {
+        where_clauses.push(format!("source = '{}'", source));
+    }
+    if let Some(ref provider) = query.provider {
+        where_clauses.push(format!(
+            "rate_source_id IN (SELECT id FROM rate_sources WHERE name = '{}')",
+            provider.replace('\'', "''")
+        ));
+    }
+    if let Some(date) = query.date {
+        where_clauses.push(format!("DATE(rate_date) = '{}'", date.format("%Y-%m-%d")));
+    }
+
+    let where_sql = if where_clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", where_clauses.join(" AND "))
+    };
+
+    let query_sql = format!(
+        "SELECT * FROM exchange_rates {} ORDER BY rate_date DESC, from_currency, to_currency LIMIT {} OFFSET {}",
+        where_sql, query.page_size, offset
+    );
 
     let rates = sqlx::query_as::<_, ExchangeRate>(&query_sql)
         .fetch_all(pool.get_ref())
@@ -867,10 +2877,10 @@ async fn get_latest_rates(
 
     let latest_date = latest_date.unwrap();
 
-    // Get all rates for that date
+    // Get all rates for that date plus pinned ones, one per pair
     let rates = sqlx::query_as::<_, ExchangeRate>(
         "SELECT * FROM exchange_rates 
-         WHERE from_currency = ? AND DATE(rate_date) = ?
+         WHERE from_currency = ? AND (DATE(rate_date) = ? OR pinned = 1)
          ORDER BY to_currency",
     )
     .bind(&from_currency)
@@ -879,93 +2889,249 @@ async fn get_latest_rates(
     .await;
 
     match rates {
-        Ok(rates) => HttpResponse::Ok().json(ApiResponse::success(rates)),
+        Ok(rates) => HttpResponse::Ok().json(ApiResponse::success(fx::preferred_per_pair(
+            rates,
+            &fx::source_priority(),
+        ))),
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
 }
 
-/// GET /exchange-rates/convert - Convert amount between currencies
-#[get("/exchange-rates/convert")]
-async fn convert_currency(
+/// GET /exchange-rates/scrape-preview - Scrape rates and diff them against the db without saving
+#[get("/exchange-rates/scrape-preview")]
+async fn preview_scraped_rates(
     pool: web::Data<SqlitePool>,
-    query: web::Query<CurrencyConversion>,
+    query: web::Query<ScrapePreviewQuery>,
 ) -> impl Responder {
-    // Get the latest rate
-    let rate: Option<f64> = sqlx::query_scalar(
-        "SELECT rate FROM exchange_rates 
-         WHERE from_currency = ? AND to_currency LIKE ?
-         ORDER BY rate_date DESC 
-         LIMIT 1",
-    )
-    .bind(&query.from_currency)
-    .bind(format!("%({})%", &query.to_currency))
-    .fetch_optional(pool.get_ref())
-    .await
-    .unwrap_or(None);
+    let currencies: Vec<String> = match query.currencies.as_deref() {
+        Some(list) => list
+            .split(',')
+            .map(|c| c.trim().to_uppercase())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        None => match exchange_scraper::base_currencies(pool.get_ref()).await {
+            Ok(currencies) => currencies,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        },
+    };
 
-    match rate {
-        Some(rate) => {
-            let converted_amount = query.amount * rate;
-            let result = ConversionResult {
-                from_currency: query.from_currency.clone(),
-                to_currency: query.to_currency.clone(),
-                amount: query.amount,
-                rate,
-                converted_amount,
-            };
-            HttpResponse::Ok().json(ApiResponse::success(result))
+    if currencies.is_empty() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("currencies cannot be empty".into()));
+    }
+
+    let providers = match RateProviders::configured(None, false) {
+        Ok(providers) => providers,
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e)),
+    };
+    let mut diffs = Vec::new();
+
+    for currency in &currencies {
+        let rates = match providers.fetch(currency).await {
+            Ok(fetched) => fetched.rates,
+            Err(e) => {
+                return HttpResponse::BadGateway().json(ApiResponse::<()>::error(format!(
+                    "Scraping {} failed: {}",
+                    currency, e
+                )))
+            }
+        };
+        match exchange_scraper::diff_against_database(pool.get_ref(), &rates).await {
+            Ok(currency_diffs) => diffs.extend(currency_diffs),
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
         }
-        None => HttpResponse::NotFound().json(ApiResponse::<()>::error(format!(
-            "No exchange rate found from {} to {}",
-            query.from_currency, query.to_currency
-        ))),
     }
-}
 
-/// GET /exchange-rates/{id} - Get exchange rate by ID
-#[get("/exchange-rates/{id}")]
-async fn get_exchange_rate(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
-    let id = id.into_inner();
+    HttpResponse::Ok().json(ApiResponse::success(diffs))
+}
 
-    let rate = sqlx::query_as::<_, ExchangeRate>("SELECT * FROM exchange_rates WHERE id = ?")
-        .bind(id)
-        .fetch_optional(pool.get_ref())
-        .await;
+/// GET /exchange-rates/scrape-runs - Recent scraper runs with their metrics and errors
+#[get("/exchange-rates/scrape-runs")]
+async fn get_scrape_runs(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<ScrapeRunQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20);
+    if limit <= 0 {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("limit must be positive".into()));
+    }
 
-    match rate {
-        Ok(Some(rate)) => HttpResponse::Ok().json(ApiResponse::success(rate)),
-        Ok(None) => HttpResponse::NotFound()
-            .json(ApiResponse::<()>::error("Exchange rate not found".into())),
+    match exchange_scraper::recent_scrape_runs(pool.get_ref(), limit).await {
+        Ok(runs) => HttpResponse::Ok().json(ApiResponse::success(runs)),
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
 }
 
-/// POST /exchange-rates - Create new exchange rate
-#[post("/exchange-rates")]
-async fn create_exchange_rate(
+/// GET /exchange-rates/stale - Currency pairs whose rate is older than the stale threshold
+#[get("/exchange-rates/stale")]
+async fn get_stale_rates(
     pool: web::Data<SqlitePool>,
-    rate_data: web::Json<CreateExchangeRate>,
+    query: web::Query<StaleRateQuery>,
 ) -> impl Responder {
-    let rate_date = rate_data.rate_date.unwrap_or_else(Utc::now);
-    let source = rate_data.source.as_deref().unwrap_or("manual");
+    let max_age_days = query.days.unwrap_or_else(fx::stale_after_days);
+    if max_age_days < 0 {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("days cannot be negative".into()));
+    }
 
-    let result = sqlx::query(
-        "INSERT INTO exchange_rates (from_currency, to_currency, rate, rate_date, source) 
-         VALUES (?, ?, ?, ?, ?)",
-    )
+    match fx::stale_rates(pool.get_ref(), Utc::now(), max_age_days).await {
+        Ok(rates) => HttpResponse::Ok().json(ApiResponse::success(rates)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /exchange-rates/storage-stats - Row counts of the rate tables and how many rates compaction would archive
+#[get("/exchange-rates/storage-stats")]
+async fn get_rate_storage_stats(pool: web::Data<SqlitePool>) -> impl Responder {
+    match rate_compaction::storage_stats(pool.get_ref(), Utc::now()).await {
+        Ok(stats) => HttpResponse::Ok().json(ApiResponse::success(stats)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /exchange-rates/convert - Convert amount between currencies
+#[get("/exchange-rates/convert")]
+async fn convert_currency(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<CurrencyConversion>,
+) -> impl Responder {
+    let default_priority = match query.purpose.as_deref() {
+        None => fx::source_priority(),
+        Some("tax") => fx::tax_source_priority(),
+        Some(other) => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                "Unknown purpose '{}' (expected tax)",
+                other
+            )))
+        }
+    };
+    let priority = match query.sources.as_deref() {
+        Some(sources) => match fx::parse_priority(sources) {
+            Ok(priority) => priority,
+            Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+        },
+        None => default_priority,
+    };
+
+    // Latest rate, or the one closest to the given day, from the most trusted
+    // source quoting the pair that day
+    let result = match query.date {
+        Some(date) => {
+            fx::convert_near(pool.get_ref(), &query.from_currency, &query.to_currency, query.amount, date, &priority)
+                .await
+        }
+        None => fx::convert(pool.get_ref(), &query.from_currency, &query.to_currency, query.amount, None, &priority)
+            .await,
+    }
+    .unwrap_or(None);
+
+    match result {
+        Some(result) => HttpResponse::Ok().json(ApiResponse::success(result)),
+        None => HttpResponse::NotFound().json(ApiResponse::<()>::error(format!(
+            "No exchange rate found from {} to {}",
+            query.from_currency, query.to_currency
+        ))),
+    }
+}
+
+/// GET /currencies - Currencies of the user's accounts plus those with rates, with display names
+#[get("/currencies")]
+async fn get_currencies(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<CurrencyQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+
+    match currencies::currencies_in_use(pool.get_ref(), user_id).await {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /exchange-rates/{id} - Get exchange rate by ID
+#[get("/exchange-rates/{id}")]
+async fn get_exchange_rate(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let id = id.into_inner();
+
+    let rate = sqlx::query_as::<_, ExchangeRate>("SELECT * FROM exchange_rates WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    match rate {
+        Ok(Some(rate)) => HttpResponse::Ok().json(ApiResponse::success(rate)),
+        Ok(None) => HttpResponse::NotFound()
+            .json(ApiResponse::<()>::error("Exchange rate not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /exchange-rates - Create new exchange rate
+#[post("/exchange-rates")]
+async fn create_exchange_rate(
+    pool: web::Data<SqlitePool>,
+    rate_data: web::Json<CreateExchangeRate>,
+) -> impl Responder {
+    let rate_date = rate_data.rate_date.unwrap_or_else(Utc::now);
+    let pinned = rate_data.pinned.unwrap_or(false);
+    let (source, rate_source_id) = match rate_sources::resolve(
+        pool.get_ref(),
+        rate_data.source.as_deref(),
+        rate_data.rate_source_id,
+    )
+    .await
+    {
+        Ok(Ok(resolved)) => resolved,
+        Ok(Err(e)) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    if pinned && source != "manual" {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("Only manual rates can be pinned".into()));
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO exchange_rates (from_currency, to_currency, rate, rate_date, source, rate_source_id) 
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
     .bind(&rate_data.from_currency)
     .bind(&rate_data.to_currency)
     .bind(rate_data.rate)
     .bind(rate_date)
-    .bind(source)
+    .bind(&source)
+    .bind(rate_source_id)
     .execute(pool.get_ref())
     .await;
 
     match result {
         Ok(result) => {
+            let rate_id = result.last_insert_rowid();
+            if pinned {
+                if let Err(e) = fx::set_pinned(pool.get_ref(), rate_id, true).await {
+                    return HttpResponse::InternalServerError()
+                        .json(ApiResponse::<()>::error(e.to_string()));
+                }
+            }
+
             let rate =
                 sqlx::query_as::<_, ExchangeRate>("SELECT * FROM exchange_rates WHERE id = ?")
-                    .bind(result.last_insert_rowid())
+                    .bind(rate_id)
                     .fetch_one(pool.get_ref())
                     .await
                     .unwrap();
@@ -989,22 +3155,68 @@ async fn update_exchange_rate(
     if let Some(rate) = update_data.rate {
         updates.push(format!("rate = {}", rate));
     }
-    if let Some(ref source) = update_data.source {
-        updates.push(format!("source = '{}'", source));
+    if update_data.source.is_some() || update_data.rate_source_id.is_some() {
+        let resolved = rate_sources::resolve(
+            pool.get_ref(),
+            update_data.source.as_deref(),
+            update_data.rate_source_id,
+        )
+        .await;
+        match resolved {
+            Ok(Ok((source, rate_source_id))) => {
+                updates.push(format!("source = '{}'", source));
+                updates.push(format!(
+                    "rate_source_id = {}",
+                    rate_source_id.map_or("NULL".to_string(), |id| id.to_string())
+                ));
+            }
+            Ok(Err(e)) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
     }
 
-    if updates.is_empty() {
+    if updates.is_empty() && update_data.pinned.is_none() {
         return HttpResponse::BadRequest()
             .json(ApiResponse::<()>::error("No fields to update".into()));
     }
 
-    let query = format!(
-        "UPDATE exchange_rates SET {}, updated_at = datetime('now') WHERE id = {}",
-        updates.join(", "),
-        id
-    );
+    let result = if updates.is_empty() {
+        Ok(())
+    } else {
+        let query = format!(
+            "UPDATE exchange_rates SET {}, updated_at = datetime('now') WHERE id = {}",
+            updates.join(", "),
+            id
+        );
+        sqlx::query(&query).execute(pool.get_ref()).await.map(|_| ())
+    };
 
-    let result = sqlx::query(&query).execute(pool.get_ref()).await;
+    // Pinned after the other fields so a rate can be switched to manual and pinned at once
+    let result = match (result, update_data.pinned) {
+        (Ok(()), Some(pinned)) => {
+            let source: Option<String> =
+                sqlx::query_scalar("SELECT source FROM exchange_rates WHERE id = ?")
+                    .bind(id)
+                    .fetch_optional(pool.get_ref())
+                    .await
+                    .unwrap_or(None);
+            match source.as_deref() {
+                None => {
+                    return HttpResponse::NotFound()
+                        .json(ApiResponse::<()>::error("Exchange rate not found".into()))
+                }
+                Some(source) if pinned && source != "manual" => {
+                    return HttpResponse::BadRequest()
+                        .json(ApiResponse::<()>::error("Only manual rates can be pinned".into()))
+                }
+                Some(_) => fx::set_pinned(pool.get_ref(), id, pinned).await,
+            }
+        }
+        (result, _) => result,
+    };
 
     match result {
         Ok(_) => {
@@ -1083,6 +3295,185 @@ async fn delete_rates_bulk(
     }
 }
 
+// ============================================================================
+// Rate Source Endpoints
+// ============================================================================
+
+/// GET /rate-sources - List rate providers (optionally of one kind)
+#[get("/rate-sources")]
+async fn get_rate_sources(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<RateSourceFilter>,
+) -> impl Responder {
+    let sources = match query.kind {
+        Some(ref kind) => {
+            sqlx::query_as::<_, RateSource>("SELECT * FROM rate_sources WHERE kind = ? ORDER BY name")
+                .bind(kind)
+                .fetch_all(pool.get_ref())
+                .await
+        }
+        None => {
+            sqlx::query_as::<_, RateSource>("SELECT * FROM rate_sources ORDER BY kind, name")
+                .fetch_all(pool.get_ref())
+                .await
+        }
+    };
+
+    match sources {
+        Ok(sources) => HttpResponse::Ok().json(ApiResponse::success(sources)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /rate-sources/{id} - Get rate provider by ID
+#[get("/rate-sources/{id}")]
+async fn get_rate_source(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    match rate_sources::find(pool.get_ref(), id.into_inner()).await {
+        Ok(Some(source)) => HttpResponse::Ok().json(ApiResponse::success(source)),
+        Ok(None) => {
+            HttpResponse::NotFound().json(ApiResponse::<()>::error("Rate source not found".into()))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /rate-sources - Create new rate provider
+#[post("/rate-sources")]
+async fn create_rate_source(
+    pool: web::Data<SqlitePool>,
+    data: web::Json<CreateRateSource>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO rate_sources (name, kind, url, reliability_notes) VALUES (?, ?, ?, ?)",
+    )
+    .bind(data.name.trim())
+    .bind(&data.kind)
+    .bind(&data.url)
+    .bind(&data.reliability_notes)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) => match rate_sources::find(pool.get_ref(), result.last_insert_rowid()).await {
+            Ok(source) => HttpResponse::Created().json(ApiResponse::success(source)),
+            Err(e) => {
+                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            }
+        },
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error(format!(
+                "Rate source '{}' already exists",
+                data.name.trim()
+            ))),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /rate-sources/{id} - Update rate provider
+#[put("/rate-sources/{id}")]
+async fn update_rate_source(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    update_data: web::Json<UpdateRateSource>,
+) -> impl Responder {
+    let id = id.into_inner();
+    let mut updates = Vec::new();
+
+    if let Some(ref name) = update_data.name {
+        if name.trim().is_empty() {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Rate source name cannot be empty".into()));
+        }
+        updates.push(format!("name = '{}'", name.trim().replace('\'', "''")));
+    }
+    if let Some(ref kind) = update_data.kind {
+        if ExchangeRateSource::from_str(kind).is_none() {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error(format!("Invalid kind '{}'", kind)));
+        }
+        // The rates keep their own source, which would no longer match
+        match rate_sources::rates_using(pool.get_ref(), id).await {
+            Ok(0) => updates.push(format!("kind = '{}'", kind)),
+            Ok(count) => {
+                return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                    "Cannot change the kind of a source used by {} exchange rate(s)",
+                    count
+                )))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
+    }
+    if let Some(ref url) = update_data.url {
+        updates.push(format!("url = '{}'", url.replace('\'', "''")));
+    }
+    if let Some(ref notes) = update_data.reliability_notes {
+        updates.push(format!("reliability_notes = '{}'", notes.replace('\'', "''")));
+    }
+
+    if updates.is_empty() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("No fields to update".into()));
+    }
+
+    let query = format!("UPDATE rate_sources SET {} WHERE id = {}", updates.join(", "), id);
+
+    match sqlx::query(&query).execute(pool.get_ref()).await {
+        Ok(result) if result.rows_affected() == 0 => {
+            HttpResponse::NotFound().json(ApiResponse::<()>::error("Rate source not found".into()))
+        }
+        Ok(_) => match rate_sources::find(pool.get_ref(), id).await {
+            Ok(source) => HttpResponse::Ok().json(ApiResponse::success(source)),
+            Err(e) => {
+                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            }
+        },
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("A rate source with that name already exists".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /rate-sources/{id} - Delete a rate provider no rates refer to
+#[delete("/rate-sources/{id}")]
+async fn delete_rate_source(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let id = id.into_inner();
+
+    match rate_sources::rates_using(pool.get_ref(), id).await {
+        Ok(0) => {}
+        Ok(count) => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                "Rate source is used by {} exchange rate(s)",
+                count
+            )))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    }
+
+    let result = sqlx::query("DELETE FROM rate_sources WHERE id = ?")
+        .bind(id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Rate source deleted successfully"))
+        }
+        Ok(_) => {
+            HttpResponse::NotFound().json(ApiResponse::<()>::error("Rate source not found".into()))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
 // ============================================================================
 // Recurring Transaction Endpoints
 // ============================================================================
@@ -1091,11 +3482,15 @@ async fn delete_rates_bulk(
 #[get("/recurring-transactions")]
 async fn get_recurring_transactions(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     query: web::Query<RecurringTransactionFilter>,
 ) -> impl Responder {
     let offset = (query.page - 1) * query.page_size;
 
-    let mut where_clauses = Vec::new();
+    let mut where_clauses = vec![format!(
+        "account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+        auth_user.user_id
+    )];
 
     if let Some(account_id) = query.account_id {
         where_clauses.push(format!("account_id = {}", account_id));
@@ -1107,11 +3502,7 @@ async fn get_recurring_transactions(
         where_clauses.push(format!("frequency = '{}'", frequency));
     }
 
-    let where_sql = if where_clauses.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", where_clauses.join(" AND "))
-    };
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
 
     let query_sql = format!(
         "SELECT * FROM recurring_transactions {} ORDER BY next_occurrence ASC LIMIT {} OFFSET {}",
@@ -1169,14 +3560,31 @@ async fn get_recurring_transaction(
 #[post("/recurring-transactions")]
 async fn create_recurring_transaction(
     pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
     data: web::Json<CreateRecurringTransaction>,
 ) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    match resource_owner(pool.get_ref(), "accounts", data.account_id).await {
+        Ok(Some(owner)) if owner == auth_user.user_id => {}
+        Ok(Some(_)) => return forbidden(),
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    }
+
     let next_occurrence = data.start_date;
+    let classification = data.classification.as_deref().unwrap_or("fixed");
+    let catch_up = data.catch_up.as_deref().unwrap_or("all");
 
     let result = sqlx::query(
         "INSERT INTO recurring_transactions 
-         (account_id, category_id, amount, transaction_type, description, frequency, start_date, end_date, next_occurrence, is_active) 
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 1)",
+         (account_id, category_id, amount, transaction_type, description, frequency, start_date, end_date, next_occurrence, is_active, classification, currency, catch_up) 
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?, ?)",
     )
     .bind(data.account_id)
     .bind(data.category_id)
@@ -1187,6 +3595,9 @@ async fn create_recurring_transaction(
     .bind(data.start_date)
     .bind(data.end_date)
     .bind(next_occurrence)
+    .bind(classification)
+    .bind(data.currency.as_deref().map(str::trim).filter(|c| !c.is_empty()).map(str::to_uppercase))
+    .bind(catch_up)
     .execute(pool.get_ref())
     .await;
 
@@ -1214,6 +3625,9 @@ async fn update_recurring_transaction(
     update_data: web::Json<UpdateRecurringTransaction>,
 ) -> impl Responder {
     let id = id.into_inner();
+    if let Err(e) = update_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
     let mut updates = Vec::new();
 
     if let Some(category_id) = update_data.category_id {
@@ -1234,6 +3648,33 @@ async fn update_recurring_transaction(
     if let Some(is_active) = update_data.is_active {
         updates.push(format!("is_active = {}", if is_active { 1 } else { 0 }));
     }
+    if let Some(ref classification) = update_data.classification {
+        if RecurringClassification::from_str(classification).is_none() {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+                "Classification must be 'fixed' or 'variable'".into(),
+            ));
+        }
+        updates.push(format!("classification = '{}'", classification));
+    }
+    if let Some(ref catch_up) = update_data.catch_up {
+        if CatchUpPolicy::from_str(catch_up).is_none() {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+                "Catch-up policy must be 'all' or 'latest'".into(),
+            ));
+        }
+        updates.push(format!("catch_up = '{}'", catch_up));
+    }
+    if let Some(ref currency) = update_data.currency {
+        // Empty string goes back to the account currency
+        if currency.trim().is_empty() {
+            updates.push("currency = NULL".to_string());
+        } else if currency.trim().len() != 3 {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Currency must be a 3-letter code".into()));
+        } else {
+            updates.push(format!("currency = '{}'", currency.trim().to_uppercase().replace('\'', "''")));
+        }
+    }
 
     if updates.is_empty() {
         return HttpResponse::BadRequest()
@@ -1293,7 +3734,7 @@ async fn delete_recurring_transaction(
 /// POST /recurring-transactions/process - Process due recurring transactions
 #[post("/recurring-transactions/process")]
 async fn process_recurring_transactions(pool: web::Data<SqlitePool>) -> impl Responder {
-    match recurring::process_due_recurring(pool.get_ref()).await {
+    match recurring::process_due_recurring(pool.get_ref(), Utc::now()).await {
         Ok(result) => HttpResponse::Ok().json(ApiResponse::success(format!(
             "Processed {} recurring transactions, created {} new transactions",
             result.due, result.created
@@ -1303,73 +3744,1608 @@ async fn process_recurring_transactions(pool: web::Data<SqlitePool>) -> impl Res
 }
 
 // ============================================================================
-// Analytics & Insights Endpoints
+// Transaction Template Endpoints
 // ============================================================================
 
-/// GET /analytics/spending-by-category - Get spending breakdown by category
-#[get("/analytics/spending-by-category")]
-async fn get_spending_by_category(
+/// GET /templates - List transaction templates (optionally for one user)
+#[get("/templates")]
+async fn get_templates(
     pool: web::Data<SqlitePool>,
-    query: web::Query<AnalyticsFilter>,
+    query: web::Query<TemplateFilter>,
 ) -> impl Responder {
-    let mut where_clauses = vec!["t.transaction_type = 'expense'".to_string()];
+    let templates = match query.user_id {
+        Some(user_id) => {
+            sqlx::query_as::<_, TransactionTemplate>(
+                "SELECT * FROM transaction_templates WHERE user_id = ? ORDER BY name",
+            )
+            .bind(user_id)
+            .fetch_all(pool.get_ref())
+            .await
+        }
+        None => {
+            sqlx::query_as::<_, TransactionTemplate>(
+                "SELECT * FROM transaction_templates ORDER BY user_id, name",
+            )
+            .fetch_all(pool.get_ref())
+            .await
+        }
+    };
 
-    if let Some(user_id) = query.user_id {
-        where_clauses.push(format!(
-            "t.account_id IN (SELECT id FROM accounts WHERE user_id = {})",
-            user_id
-        ));
+    match templates {
+        Ok(templates) => HttpResponse::Ok().json(ApiResponse::success(templates)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
-    if let Some(ref start_date) = query.start_date {
-        where_clauses.push(format!("t.transaction_date >= '{}'", start_date));
+}
+
+/// POST /templates - Create new transaction template
+#[post("/templates")]
+async fn create_template(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<CreateTransactionTemplate>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
     }
-    if let Some(ref end_date) = query.end_date {
-        where_clauses.push(format!("t.transaction_date <= '{}'", end_date));
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
     }
 
-    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
+    let owner: Option<i64> = sqlx::query_scalar("SELECT user_id FROM accounts WHERE id = ?")
+        .bind(data.account_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .unwrap_or(None);
 
-    let query_sql = format!(
-        "SELECT c.id as category_id, c.name as category_name, 
-                SUM(ABS(tc.amount)) as total_amount, COUNT(DISTINCT t.id) as transaction_count
-         FROM transactions t
-         JOIN transaction_categories tc ON t.id = tc.transaction_id
-         JOIN categories c ON tc.category_id = c.id
-         {}
-         GROUP BY c.id, c.name
-         ORDER BY total_amount DESC",
-        where_sql
-    );
+    match owner {
+        Some(owner) if owner == data.user_id => {}
+        Some(_) => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                "Account {} does not belong to user {}",
+                data.account_id, data.user_id
+            )))
+        }
+        None => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Account not found".into()))
+        }
+    }
+
+    let transaction_type = data.transaction_type.as_deref().unwrap_or("expense");
+    let merchant = data.merchant.as_deref().and_then(merchants::normalize_merchant);
+
+    let result = sqlx::query(
+        "INSERT INTO transaction_templates 
+         (user_id, name, account_id, category_id, amount, transaction_type, description, merchant) 
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(data.user_id)
+    .bind(data.name.trim())
+    .bind(data.account_id)
+    .bind(data.category_id)
+    .bind(data.amount)
+    .bind(transaction_type)
+    .bind(&data.description)
+    .bind(&merchant)
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(result) => {
+            let template = sqlx::query_as::<_, TransactionTemplate>(
+                "SELECT * FROM transaction_templates WHERE id = ?",
+            )
+            .bind(result.last_insert_rowid())
+            .fetch_one(pool.get_ref())
+            .await
+            .unwrap();
+
+            HttpResponse::Created().json(ApiResponse::success(template))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /templates/{id} - Update transaction template
+#[put("/templates/{id}")]
+async fn update_template(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    update_data: web::Json<UpdateTransactionTemplate>,
+) -> impl Responder {
+    let id = id.into_inner();
+    if let Err(e) = update_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    let mut updates = Vec::new();
+
+    if let Some(ref name) = update_data.name {
+        if name.trim().is_empty() {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Template name cannot be empty".into()));
+        }
+        updates.push(format!("name = '{}'", name.trim().replace('\'', "''")));
+    }
+    if let Some(account_id) = update_data.account_id {
+        updates.push(format!("account_id = {}", account_id));
+    }
+    if let Some(category_id) = update_data.category_id {
+        // 0 clears the category
+        if category_id == 0 {
+            updates.push("category_id = NULL".to_string());
+        } else {
+            updates.push(format!("category_id = {}", category_id));
+        }
+    }
+    if let Some(amount) = update_data.amount {
+        if amount <= 0.0 {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Template amount must be positive".into()));
+        }
+        updates.push(format!("amount = {}", amount));
+    }
+    if let Some(ref txn_type) = update_data.transaction_type {
+        if !["income", "expense", "transfer"].contains(&txn_type.as_str()) {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Invalid transaction type".into()));
+        }
+        updates.push(format!("transaction_type = '{}'", txn_type));
+    }
+    if let Some(ref desc) = update_data.description {
+        updates.push(format!("description = '{}'", desc.replace('\'', "''")));
+    }
+    if let Some(ref merchant) = update_data.merchant {
+        match merchants::normalize_merchant(merchant) {
+            Some(merchant) => {
+                updates.push(format!("merchant = '{}'", merchant.replace('\'', "''")))
+            }
+            None => updates.push("merchant = NULL".to_string()),
+        }
+    }
+
+    if updates.is_empty() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("No fields to update".into()));
+    }
+
+    let query = format!(
+        "UPDATE transaction_templates SET {}, updated_at = datetime('now') WHERE id = {}",
+        updates.join(", "),
+        id
+    );
+
+    let result = sqlx::query(&query).execute(pool.get_ref()).await;
+
+    match result {
+        Ok(result) if result.rows_affected() == 0 => {
+            HttpResponse::NotFound().json(ApiResponse::<()>::error("Template not found".into()))
+        }
+        Ok(_) => {
+            let template = sqlx::query_as::<_, TransactionTemplate>(
+                "SELECT * FROM transaction_templates WHERE id = ?",
+            )
+            .bind(id)
+            .fetch_one(pool.get_ref())
+            .await
+            .unwrap();
+            HttpResponse::Ok().json(ApiResponse::success(template))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /templates/{id} - Delete transaction template
+#[delete("/templates/{id}")]
+async fn delete_template(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let id = id.into_inner();
+
+    let result = sqlx::query("DELETE FROM transaction_templates WHERE id = ?")
+        .bind(id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                HttpResponse::Ok().json(ApiResponse::success("Template deleted successfully"))
+            } else {
+                HttpResponse::NotFound().json(ApiResponse::<()>::error("Template not found".into()))
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Budget Endpoints
+// ============================================================================
+
+/// GET /budgets - List budgets (optionally for one user)
+#[get("/budgets")]
+async fn get_budgets(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<BudgetFilter>,
+) -> impl Responder {
+    let budgets = match query.user_id {
+        Some(user_id) => {
+            sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE user_id = ? ORDER BY id")
+                .bind(user_id)
+                .fetch_all(pool.get_ref())
+                .await
+        }
+        None => {
+            sqlx::query_as::<_, Budget>("SELECT * FROM budgets ORDER BY id")
+                .fetch_all(pool.get_ref())
+                .await
+        }
+    };
+
+    match budgets {
+        Ok(budgets) => HttpResponse::Ok().json(ApiResponse::success(budgets)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /budgets - Set the monthly limit for a category
+#[post("/budgets")]
+async fn create_budget(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<CreateBudget>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    // One budget per category: setting it again replaces the limit
+    match budgets::set_budget(pool.get_ref(), data.user_id, data.category_id, data.monthly_limit).await {
+        Ok(budget) => HttpResponse::Created().json(ApiResponse::success(budget)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /budgets/{id} - Get budget by ID
+#[get("/budgets/{id}")]
+async fn get_budget(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let budget = sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE id = ?")
+        .bind(id.into_inner())
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    match budget {
+        Ok(Some(budget)) => HttpResponse::Ok().json(ApiResponse::success(budget)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Budget not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /budgets/{id} - Change a budget's monthly limit
+#[put("/budgets/{id}")]
+async fn update_budget(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    data: web::Json<UpdateBudget>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let id = id.into_inner();
+    let result = sqlx::query("UPDATE budgets SET monthly_limit = ? WHERE id = ?")
+        .bind(data.monthly_limit)
+        .bind(id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            let budget = sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE id = ?")
+                .bind(id)
+                .fetch_one(pool.get_ref())
+                .await
+                .unwrap();
+            HttpResponse::Ok().json(ApiResponse::success(budget))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Budget not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /budgets/{id} - Remove a category's budget
+#[delete("/budgets/{id}")]
+async fn delete_budget(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let result = sqlx::query("DELETE FROM budgets WHERE id = ?")
+        .bind(id.into_inner())
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Budget deleted successfully"))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Budget not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Goal Endpoints
+// ============================================================================
+
+/// 422 when a linked account doesn't exist or can't hold a goal of this
+/// user in `currency`
+async fn check_goal_accounts(
+    pool: &SqlitePool,
+    user_id: i64,
+    currency: &str,
+    account_ids: &[i64],
+) -> Option<HttpResponse> {
+    let (accounts, missing) = match goals::find_accounts(pool, account_ids).await {
+        Ok(found) => found,
+        Err(e) => return Some(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))),
+    };
+    let problem = match missing.first() {
+        Some(id) => Err(format!("Account {} not found", id)),
+        None => goals::validate_accounts(user_id, currency, &accounts),
+    };
+    problem.err().map(|e| HttpResponse::UnprocessableEntity().json(ApiResponse::<()>::error(e)))
+}
+
+/// Progress of the goal just written, as the goal endpoints return it
+async fn goal_response(pool: &SqlitePool, goal: Goal, created: bool) -> HttpResponse {
+    match goals::goal_progress(pool, goal, Utc::now().date_naive()).await {
+        Ok(progress) if created => HttpResponse::Created().json(ApiResponse::success(progress)),
+        Ok(progress) => HttpResponse::Ok().json(ApiResponse::success(progress)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /goals - List the user's goals with their progress
+#[get("/goals")]
+async fn get_goals(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+) -> impl Responder {
+    match goals::goals_progress(pool.get_ref(), auth_user.user_id, Utc::now().date_naive()).await {
+        Ok(progress) => HttpResponse::Ok().json(ApiResponse::success(progress)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /goals - Create a goal saved up in the linked accounts
+#[post("/goals")]
+async fn create_goal(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<CreateGoal>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    // The first linked account's currency unless one is given
+    let currency = match &data.currency {
+        Some(currency) => currency.to_uppercase(),
+        None => match data.account_ids.first() {
+            Some(&id) => sqlx::query_scalar("SELECT currency FROM accounts WHERE id = ?")
+                .bind(id)
+                .fetch_optional(pool.get_ref())
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "USD".to_string()),
+            None => "USD".to_string(),
+        },
+    };
+    if let Some(response) = check_goal_accounts(pool.get_ref(), data.user_id, &currency, &data.account_ids).await {
+        return response;
+    }
+
+    match goals::create_goal(pool.get_ref(), &data, &currency).await {
+        Ok(goal) => goal_response(pool.get_ref(), goal, true).await,
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /goals/{id} - Get a goal with its progress
+#[get("/goals/{id}")]
+async fn get_goal(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let goal = sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = ?")
+        .bind(id.into_inner())
+        .fetch_optional(pool.get_ref())
+        .await;
+
+    match goal {
+        Ok(Some(goal)) => goal_response(pool.get_ref(), goal, false).await,
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Goal not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// PUT /goals/{id} - Change a goal's name, target or linked accounts
+#[put("/goals/{id}")]
+async fn update_goal(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    data: web::Json<UpdateGoal>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    let id = id.into_inner();
+    if let Some(account_ids) = &data.account_ids {
+        let goal = sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool.get_ref())
+            .await;
+        match goal {
+            Ok(Some(goal)) => {
+                if let Some(response) =
+                    check_goal_accounts(pool.get_ref(), goal.user_id, &goal.currency, account_ids).await
+                {
+                    return response;
+                }
+            }
+            Ok(None) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("Goal not found".into())),
+            Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+        }
+    }
+
+    match goals::update_goal(pool.get_ref(), id, &data).await {
+        Ok(Some(goal)) => goal_response(pool.get_ref(), goal, false).await,
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Goal not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /goals/{id} - Delete a goal; its accounts are left as they are
+#[delete("/goals/{id}")]
+async fn delete_goal(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let result = sqlx::query("DELETE FROM goals WHERE id = ?")
+        .bind(id.into_inner())
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Goal deleted successfully"))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Goal not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /reports/budget-variance - Budget vs. actual per category for a month (JSON or CSV)
+#[get("/reports/budget-variance")]
+async fn get_budget_variance(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<BudgetVarianceQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+    let period = query
+        .period
+        .clone()
+        .unwrap_or_else(|| Utc::now().format("%Y-%m").to_string());
+
+    if budgets::parse_period(&period).is_none() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("period must be formatted as YYYY-MM".into()));
+    }
+
+    let rows = match budgets::budget_variance(pool.get_ref(), user_id, &period).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => HttpResponse::Ok().json(ApiResponse::success(rows)),
+        "csv" => export_file(
+            &req,
+            query.encrypt,
+            "text/csv",
+            &format!("budget_variance_{}.csv", period),
+            budgets::render_csv(&rows),
+        ),
+        other => HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+            "Unsupported format '{}', expected json or csv",
+            other
+        ))),
+    }
+}
+
+/// GET /reports/charts/spending.svg - Category spending for a month as an SVG bar or pie chart
+#[get("/reports/charts/spending.svg")]
+async fn get_spending_chart(
+    pool: ReadPool,
+    query: web::Query<SpendingChartQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+    let period = query
+        .period
+        .clone()
+        .unwrap_or_else(|| Utc::now().format("%Y-%m").to_string());
+
+    let Some((start, end)) = budgets::parse_period(&period) else {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("period must be formatted as YYYY-MM".into()));
+    };
+
+    let render = match query.chart.as_deref().unwrap_or("bar") {
+        "bar" => charts::bar_chart_svg,
+        "pie" => charts::pie_chart_svg,
+        other => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                "Unsupported chart '{}', expected bar or pie",
+                other
+            )))
+        }
+    };
+
+    match charts::category_spending(pool.get_ref(), user_id, start, end).await {
+        Ok(spending) => HttpResponse::Ok()
+            .content_type("image/svg+xml")
+            .body(render(&format!("Spending by category, {}", period), &spending)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Import & Category Rule Endpoints
+// ============================================================================
+
+/// GET /category-rules - List category rules (optionally for one user)
+#[get("/category-rules")]
+async fn get_category_rules(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<CategoryRuleFilter>,
+) -> impl Responder {
+    let category_rules = match query.user_id {
+        Some(user_id) => rules::rules_for_user(pool.get_ref(), user_id).await,
+        None => {
+            sqlx::query_as::<_, CategoryRule>("SELECT * FROM category_rules ORDER BY id")
+                .fetch_all(pool.get_ref())
+                .await
+        }
+    };
+
+    match category_rules {
+        Ok(category_rules) => HttpResponse::Ok().json(ApiResponse::success(category_rules)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /category-rules - Suggest a category for descriptions containing a pattern
+#[post("/category-rules")]
+async fn create_category_rule(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<CreateCategoryRule>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+
+    match rules::create_rule(pool.get_ref(), data.user_id, &data.pattern, data.category_id).await {
+        Ok(rule) => HttpResponse::Created().json(ApiResponse::success(rule)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /category-rules/{id} - Delete a category rule
+#[delete("/category-rules/{id}")]
+async fn delete_category_rule(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let result = sqlx::query("DELETE FROM category_rules WHERE id = ?")
+        .bind(id.into_inner())
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Category rule deleted successfully"))
+        }
+        Ok(_) => HttpResponse::NotFound()
+            .json(ApiResponse::<()>::error("Category rule not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /transactions/recategorize - Move every transaction matching a payee/description pattern,
+/// date range or old category to another category
+#[post("/transactions/recategorize")]
+async fn recategorize_transactions(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<RecategorizeRequest>,
+) -> impl Responder {
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+
+    let owned: Result<i64, sqlx::Error> =
+        sqlx::query_scalar("SELECT COUNT(*) FROM categories WHERE id = ? AND user_id = ?")
+            .bind(data.to_category_id)
+            .bind(data.user_id)
+            .fetch_one(pool.get_ref())
+            .await;
+    match owned {
+        Ok(0) => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+                "to_category_id does not belong to the user".into(),
+            ))
+        }
+        Ok(_) => {}
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    }
+
+    match rules::recategorize(pool.get_ref(), &data).await {
+        Ok(updated) => HttpResponse::Ok().json(ApiResponse::success(RecategorizeResult { updated })),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+async fn user_owns_account(pool: &SqlitePool, user_id: i64, account_id: i64) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM accounts WHERE id = ? AND user_id = ?")
+        .bind(account_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+/// Account and column mapping an import reads the statement with: the saved
+/// profile's when the request names one (its own account_id still wins),
+/// else the request's. The account must belong to the user.
+async fn import_target(
+    pool: &SqlitePool,
+    user_id: i64,
+    account_id: Option<i64>,
+    mapping: &CsvColumnMapping,
+    profile_id: Option<i64>,
+) -> Result<(i64, CsvColumnMapping), HttpResponse> {
+    let internal_error = |e: sqlx::Error| {
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+    };
+    let (account_id, mapping) = match profile_id {
+        Some(profile_id) => {
+            let profile = sqlx::query_as::<_, ImportProfile>(
+                "SELECT * FROM import_profiles WHERE id = ? AND user_id = ?",
+            )
+            .bind(profile_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(internal_error)?
+            .ok_or_else(|| {
+                HttpResponse::NotFound().json(ApiResponse::<()>::error("Import profile not found".into()))
+            })?;
+            (account_id.or(profile.account_id), import::profile_mapping(&profile))
+        }
+        None => (account_id, mapping.clone()),
+    };
+    let account_id = account_id.ok_or_else(|| {
+        HttpResponse::BadRequest().json(ApiResponse::<()>::error("account_id is required".into()))
+    })?;
+
+    match user_owns_account(pool, user_id, account_id).await {
+        Ok(true) => Ok((account_id, mapping)),
+        Ok(false) => Err(HttpResponse::NotFound().json(ApiResponse::<()>::error("Account not found".into()))),
+        Err(e) => Err(internal_error(e)),
+    }
+}
+
+/// GET /import/profiles - List saved import mappings (optionally for one user)
+#[get("/import/profiles")]
+async fn get_import_profiles(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<ImportProfileFilter>,
+) -> impl Responder {
+    let profiles = match query.user_id {
+        Some(user_id) => import::profiles_for_user(pool.get_ref(), user_id).await,
+        None => {
+            sqlx::query_as::<_, ImportProfile>("SELECT * FROM import_profiles ORDER BY id")
+                .fetch_all(pool.get_ref())
+                .await
+        }
+    };
+
+    match profiles {
+        Ok(profiles) => HttpResponse::Ok().json(ApiResponse::success(profiles)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /import/profiles - Save a named CSV mapping and default account for a bank
+#[post("/import/profiles")]
+async fn create_import_profile(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<CreateImportProfile>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    if let Err(e) = data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    if let Some(account_id) = data.account_id {
+        match user_owns_account(pool.get_ref(), data.user_id, account_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::NotFound()
+                    .json(ApiResponse::<()>::error("Account not found".into()))
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        }
+    }
+
+    match import::save_profile(pool.get_ref(), &data).await {
+        Ok(profile) => HttpResponse::Created().json(ApiResponse::success(profile)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /import/profiles/{id} - Delete an import profile
+#[delete("/import/profiles/{id}")]
+async fn delete_import_profile(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let result = sqlx::query("DELETE FROM import_profiles WHERE id = ?")
+        .bind(id.into_inner())
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => {
+            HttpResponse::Ok().json(ApiResponse::success("Import profile deleted successfully"))
+        }
+        Ok(_) => HttpResponse::NotFound()
+            .json(ApiResponse::<()>::error("Import profile not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /import/preview - Parse a CSV/OFX statement and suggest a category per row
+#[post("/import/preview")]
+async fn preview_import(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<ImportPreviewRequest>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    let (account_id, mapping) = match import_target(
+        pool.get_ref(),
+        data.user_id,
+        data.account_id,
+        &data.mapping,
+        data.profile_id,
+    )
+    .await
+    {
+        Ok(target) => target,
+        Err(response) => return response,
+    };
+
+    let parsed = match data.format.as_deref().unwrap_or("csv") {
+        "csv" => import::parse_csv(&data.content, &mapping).map(|(rows, _)| rows),
+        "ofx" => import::parse_ofx(&data.content),
+        other => Err(format!("Unsupported format '{}', expected csv or ofx", other)),
+    };
+    let mut rows = match parsed {
+        Ok(rows) => rows,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+    };
+
+    if let Err(e) = import::suggest_categories(pool.get_ref(), data.user_id, &mut rows).await {
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
+    }
+    match import::mark_duplicates(pool.get_ref(), account_id, &mut rows).await {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::success(rows)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /import/commit - Import reviewed preview rows, learning rules from corrections
+#[post("/import/commit")]
+async fn commit_import(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<ImportCommitRequest>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    if data.rows.is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("No rows to import".into()));
+    }
+
+    match user_owns_account(pool.get_ref(), data.user_id, data.account_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Account not found".into()))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    }
+
+    let opened_on = match import::account_opened_on(pool.get_ref(), data.account_id).await {
+        Ok(opened_on) => opened_on,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+    if let Some(row) = data.rows.iter().find(|r| import::before_opening(r, opened_on)) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+            "Row '{}' on {} is before the account was opened",
+            row.description,
+            row.transaction_date.date_naive()
+        )));
+    }
+
+    match import::commit_import(pool.get_ref(), data.user_id, data.account_id, &data.rows).await {
+        Ok(result) => HttpResponse::Created().json(ApiResponse::success(result)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /import/summary - Recreate the accounts, categories, transactions and recurring
+/// transactions of a financial_summary.json export for a user
+#[post("/import/summary")]
+async fn import_summary(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<SummaryImportQuery>,
+    file: web::Json<restore::SummaryFile>,
+) -> impl Responder {
+    let Some(user_id) = query.user_id.or(file.user_id) else {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("user_id is required for files without one".into()));
+    };
+    if let Err(e) = restore::validate(&file) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    match user_exists(pool.get_ref(), user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found".into())),
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+    match restore::conflicting_accounts(pool.get_ref(), user_id, &file).await {
+        Ok(names) if names.is_empty() => {}
+        Ok(names) => {
+            return HttpResponse::Conflict().json(ApiResponse::<()>::error(format!(
+                "The user already has accounts named {}",
+                names.join(", ")
+            )))
+        }
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+
+    match restore::restore(pool.get_ref(), user_id, &file).await {
+        Ok(result) => HttpResponse::Created().json(ApiResponse::success(result)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /import/transactions/csv - Import a CSV statement in one step, skipping duplicates
+#[post("/import/transactions/csv")]
+async fn import_transactions_csv(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    data: web::Json<CsvImportRequest>,
+) -> impl Responder {
+    if data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+    let (account_id, mapping) = match import_target(
+        pool.get_ref(),
+        data.user_id,
+        data.account_id,
+        &data.mapping,
+        data.profile_id,
+    )
+    .await
+    {
+        Ok(target) => target,
+        Err(response) => return response,
+    };
+
+    let (rows, date_format) = match import::parse_csv(&data.content, &mapping) {
+        Ok(parsed) => parsed,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+    };
+
+    match import::import_csv(pool.get_ref(), data.user_id, account_id, rows, date_format).await {
+        Ok(result) => HttpResponse::Created().json(ApiResponse::success(result)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Analytics & Insights Endpoints
+// ============================================================================
+
+/// Spending rows with sub-categories folded into their parents when the
+/// filter asks for rollup=true
+async fn rolled_up(
+    pool: &SqlitePool,
+    filter: &AnalyticsFilter,
+    spending: Vec<CategorySpendingSummary>,
+) -> Result<Vec<CategorySpendingSummary>, sqlx::Error> {
+    if filter.rollup != Some(true) {
+        return Ok(spending);
+    }
+    let all = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE ? IS NULL OR user_id = ?")
+        .bind(filter.user_id)
+        .bind(filter.user_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(categories::roll_up(spending, &all))
+}
+
+/// GET /analytics/spending-by-category - Get spending breakdown by category
+#[get("/analytics/spending-by-category")]
+async fn get_spending_by_category(
+    pool: ReadPool,
+    query: web::Query<AnalyticsFilter>,
+) -> impl Responder {
+    let mut scope_clauses = vec![exclusions::COUNTED.to_string()];
+
+    if let Some(user_id) = query.user_id {
+        scope_clauses.push(format!(
+            "t.account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+            user_id
+        ));
+    }
+    if let Some(ref start_date) = query.start_date {
+        scope_clauses.push(format!("t.transaction_date >= '{}'", start_date));
+    }
+    if let Some(ref end_date) = query.end_date {
+        scope_clauses.push(format!("t.transaction_date <= '{}'", end_date));
+    }
+
+    let mut expense_clauses = scope_clauses.clone();
+    expense_clauses.push("t.transaction_type = 'expense'".to_string());
+    // Fees count on every transaction type, including transfers
+    let mut fee_clauses = scope_clauses;
+    fee_clauses.push("t.fee > 0".to_string());
+
+    // Fees without a fee category are grouped under a synthetic "Fees" row (id 0)
+    let query_sql = format!(
+        "SELECT category_id, category_name,
+                SUM(total_amount) as total_amount, SUM(transaction_count) as transaction_count
+         FROM (
+             SELECT c.id as category_id, c.name as category_name,
+                    SUM(ABS(tc.amount) * {share}) as total_amount, COUNT(DISTINCT t.id) as transaction_count
+             FROM transactions t
+             JOIN transaction_categories tc ON t.id = tc.transaction_id
+             JOIN categories c ON tc.category_id = c.id
+             WHERE {}
+             GROUP BY c.id, c.name
+             UNION ALL
+             SELECT COALESCE(c.id, 0) as category_id, COALESCE(c.name, 'Fees') as category_name,
+                    SUM(t.fee) as total_amount, COUNT(*) as transaction_count
+             FROM transactions t
+             LEFT JOIN categories c ON t.fee_category_id = c.id
+             WHERE {}
+             GROUP BY COALESCE(c.id, 0), COALESCE(c.name, 'Fees')
+         )
+         GROUP BY category_id, category_name
+         ORDER BY total_amount DESC",
+        expense_clauses.join(" AND "),
+        fee_clauses.join(" AND "),
+        share = reimbursements::UNREIMBURSED_SHARE
+    );
+
+    let results = match sqlx::query_as::<_, CategorySpendingSummary>(&query_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(data) => rolled_up(pool.get_ref(), &query, data).await,
+        Err(e) => Err(e),
+    };
+
+    match results {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/monthly-summary - Get monthly income/expense summary
+#[get("/analytics/monthly-summary")]
+async fn get_monthly_summary(
+    pool: ReadPool,
+    query: web::Query<AnalyticsFilter>,
+) -> impl Responder {
+    // Transfers between the user's own accounts are neither income nor spending
+    let mut where_clauses = vec!["transfer_group_id IS NULL".to_string(), exclusions::COUNTED.to_string()];
+
+    if let Some(user_id) = query.user_id {
+        where_clauses.push(format!(
+            "account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+            user_id
+        ));
+    }
+    if let Some(ref start_date) = query.start_date {
+        where_clauses.push(format!("transaction_date >= '{}'", start_date));
+    }
+    if let Some(ref end_date) = query.end_date {
+        where_clauses.push(format!("transaction_date <= '{}'", end_date));
+    }
+
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
+
+    let query_sql = format!(
+        "SELECT strftime('%Y-%m', transaction_date) as month,
+                SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0.0 END) as total_income,
+                SUM(CASE WHEN transaction_type = 'expense' THEN ABS(amount) ELSE 0.0 END) as total_expense,
+                SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE -ABS(amount) END) as net_change,
+                COUNT(*) as transaction_count
+         FROM transactions t
+         {}
+         GROUP BY strftime('%Y-%m', transaction_date)
+         ORDER BY month DESC
+         LIMIT 12",
+        where_sql
+    );
+
+    let results = sqlx::query_as::<_, MonthlySummary>(&query_sql)
+        .fetch_all(pool.get_ref())
+        .await;
+
+    match results {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/spending-comparison - Compare spending between periods
+#[get("/analytics/spending-comparison")]
+async fn get_spending_comparison(
+    pool: ReadPool,
+    query: web::Query<SpendingComparisonQuery>,
+) -> impl Responder {
+    let mut user_filter = String::new();
+    if let Some(user_id) = query.user_id {
+        user_filter = format!(
+            "AND account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+            user_id
+        );
+    }
+
+    // Get current period spending
+    let current_sql = format!(
+        "SELECT SUM(ABS(amount)) as total
+         FROM transactions t
+         WHERE transaction_type = 'expense' AND {}
+         AND transaction_date >= ? AND transaction_date <= ?
+         {}",
+        exclusions::COUNTED, user_filter
+    );
+
+    let current_total: Option<f64> = sqlx::query_scalar(&current_sql)
+        .bind(query.current_start)
+        .bind(query.current_end)
+        .fetch_optional(pool.get_ref())
+        .await
+        .unwrap_or(None);
+
+    // Get previous period spending
+    let previous_sql = format!(
+        "SELECT SUM(ABS(amount)) as total
+         FROM transactions t
+         WHERE transaction_type = 'expense' AND {}
+         AND transaction_date >= ? AND transaction_date <= ?
+         {}",
+        exclusions::COUNTED, user_filter
+    );
+
+    let previous_total: Option<f64> = sqlx::query_scalar(&previous_sql)
+        .bind(query.previous_start)
+        .bind(query.previous_end)
+        .fetch_optional(pool.get_ref())
+        .await
+        .unwrap_or(None);
+
+    let current = current_total.unwrap_or(0.0);
+    let previous = previous_total.unwrap_or(0.0);
+    let change_amount = current - previous;
+    let change_percentage = if previous > 0.0 {
+        (change_amount / previous) * 100.0
+    } else {
+        0.0
+    };
+
+    let comparison = SpendingComparison {
+        current_period_total: current,
+        previous_period_total: previous,
+        change_amount,
+        change_percentage,
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(comparison))
+}
+
+/// GET /analytics/top-categories - Get top spending categories
+#[get("/analytics/top-categories")]
+async fn get_top_categories(
+    pool: ReadPool,
+    query: web::Query<AnalyticsFilter>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(5);
+    let mut where_clauses = vec!["t.transaction_type = 'expense'".to_string(), exclusions::COUNTED.to_string()];
+
+    if let Some(user_id) = query.user_id {
+        where_clauses.push(format!(
+            "t.account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+            user_id
+        ));
+    }
+    if let Some(ref start_date) = query.start_date {
+        where_clauses.push(format!("t.transaction_date >= '{}'", start_date));
+    }
+    if let Some(ref end_date) = query.end_date {
+        where_clauses.push(format!("t.transaction_date <= '{}'", end_date));
+    }
+
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
+    // Rolled-up parents are only ranked once every sub-category is in
+    let limit_sql = if query.rollup == Some(true) {
+        String::new()
+    } else {
+        format!("LIMIT {}", limit)
+    };
+
+    let query_sql = format!(
+        "SELECT c.id as category_id, c.name as category_name,
+                SUM(ABS(tc.amount) * {share}) as total_amount, COUNT(DISTINCT t.id) as transaction_count
+         FROM transactions t
+         JOIN transaction_categories tc ON t.id = tc.transaction_id
+         JOIN categories c ON tc.category_id = c.id
+         {}
+         GROUP BY c.id, c.name
+         ORDER BY total_amount DESC
+         {}",
+        where_sql,
+        limit_sql,
+        share = reimbursements::UNREIMBURSED_SHARE
+    );
+
+    let results = match sqlx::query_as::<_, CategorySpendingSummary>(&query_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(data) => rolled_up(pool.get_ref(), &query, data).await.map(|mut rows| {
+            rows.truncate(limit.max(0) as usize);
+            rows
+        }),
+        Err(e) => Err(e),
+    };
+
+    match results {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/spending-by-merchant - Get spending breakdown by merchant (payee leaderboard)
+#[get("/analytics/spending-by-merchant")]
+async fn get_spending_by_merchant(
+    pool: ReadPool,
+    query: web::Query<AnalyticsFilter>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20);
+    let mut where_clauses = vec![
+        "transaction_type = 'expense'".to_string(),
+        "merchant IS NOT NULL".to_string(),
+        exclusions::COUNTED.to_string(),
+    ];
+
+    if let Some(user_id) = query.user_id {
+        where_clauses.push(format!(
+            "account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+            user_id
+        ));
+    }
+    if let Some(ref start_date) = query.start_date {
+        where_clauses.push(format!("transaction_date >= '{}'", start_date));
+    }
+    if let Some(ref end_date) = query.end_date {
+        where_clauses.push(format!("transaction_date <= '{}'", end_date));
+    }
+
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
+
+    // One representative city/country per merchant
+    let query_sql = format!(
+        "SELECT merchant,
+                MAX(merchant_city) as merchant_city,
+                MAX(merchant_country) as merchant_country,
+                SUM(ABS(amount)) as total_amount,
+                COUNT(*) as transaction_count
+         FROM transactions t
+         {}
+         GROUP BY merchant
+         ORDER BY total_amount DESC
+         LIMIT {}",
+        where_sql, limit
+    );
+
+    let results = sqlx::query_as::<_, MerchantSpendingSummary>(&query_sql)
+        .fetch_all(pool.get_ref())
+        .await;
+
+    match results {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/patterns - Get spending by weekday and hour of day
+#[get("/analytics/patterns")]
+async fn get_spending_patterns(
+    pool: ReadPool,
+    query: web::Query<PatternFilter>,
+) -> impl Responder {
+    let offset = query.utc_offset_minutes.unwrap_or(0);
+    if offset.abs() > 14 * 60 {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+            "utc_offset_minutes must be between -840 and 840".into(),
+        ));
+    }
+
+    match patterns::spending_patterns(
+        pool.get_ref(),
+        query.user_id,
+        query.start_date,
+        query.end_date,
+        offset,
+    )
+    .await
+    {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/flows - Income source -> account -> category flows (Sankey nodes and edges)
+#[get("/analytics/flows")]
+async fn get_cash_flows(
+    pool: ReadPool,
+    query: web::Query<FlowQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+    let period = query
+        .period
+        .clone()
+        .unwrap_or_else(|| Utc::now().format("%Y-%m").to_string());
+
+    if budgets::parse_period(&period).is_none() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("period must be formatted as YYYY-MM".into()));
+    }
+
+    match flows::cash_flows(pool.get_ref(), user_id, &period).await {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/spending-by-currency - Get spending breakdown by original currency
+#[get("/analytics/spending-by-currency")]
+async fn get_spending_by_currency(
+    pool: ReadPool,
+    query: web::Query<AnalyticsFilter>,
+) -> impl Responder {
+    let mut where_clauses = vec!["t.transaction_type = 'expense'".to_string(), exclusions::COUNTED.to_string()];
+
+    if let Some(user_id) = query.user_id {
+        where_clauses.push(format!("a.user_id = {}", user_id));
+    }
+    if let Some(ref start_date) = query.start_date {
+        where_clauses.push(format!("t.transaction_date >= '{}'", start_date));
+    }
+    if let Some(ref end_date) = query.end_date {
+        where_clauses.push(format!("t.transaction_date <= '{}'", end_date));
+    }
+
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
+
+    // Domestic spending has no original amount, so it counts in the account currency
+    let query_sql = format!(
+        "SELECT COALESCE(t.original_currency, a.currency) as currency,
+                a.currency as account_currency,
+                SUM(ABS(COALESCE(t.original_amount, t.amount))) as total_original_amount,
+                SUM(ABS(t.amount)) as total_posted_amount,
+                COUNT(*) as transaction_count
+         FROM transactions t
+         JOIN accounts a ON t.account_id = a.id
+         {}
+         GROUP BY COALESCE(t.original_currency, a.currency), a.currency
+         ORDER BY total_posted_amount DESC",
+        where_sql
+    );
+
+    let results = sqlx::query_as::<_, CurrencySpendingSummary>(&query_sql)
+        .fetch_all(pool.get_ref())
+        .await;
+
+    match results {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/balances - Balances grouped by account type and currency, optionally as of a date
+#[get("/analytics/balances")]
+async fn get_account_type_balances(
+    pool: ReadPool,
+    query: web::Query<NetWorthQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+
+    match net_worth::account_type_balances(pool.get_ref(), user_id, query.as_of).await {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/balances/groups - Assets minus liabilities per account group and currency, optionally as of a date
+#[get("/analytics/balances/groups")]
+async fn get_account_group_balances(
+    pool: ReadPool,
+    query: web::Query<NetWorthQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+
+    match net_worth::group_balances(pool.get_ref(), user_id, query.as_of).await {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/net-worth - Assets minus liabilities per currency, with the account-type and account-group breakdowns
+#[get("/analytics/net-worth")]
+async fn get_net_worth(
+    pool: ReadPool,
+    query: web::Query<NetWorthQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+
+    match net_worth::net_worth(pool.get_ref(), user_id, query.as_of).await {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/discretionary-budget - Projected income minus fixed obligations for the rest of the month
+#[get("/analytics/discretionary-budget")]
+async fn get_discretionary_budget(
+    pool: ReadPool,
+    query: web::Query<AnalyticsFilter>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+
+    match forecast::discretionary_budget(pool.get_ref(), user_id, Utc::now()).await {
+        Ok(budget) => HttpResponse::Ok().json(ApiResponse::success(budget)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
 
-    let results = sqlx::query_as::<_, CategorySpendingSummary>(&query_sql)
-        .fetch_all(pool.get_ref())
-        .await;
+/// GET /analytics/forecast - Income, expenses and month-end balance of each account for the coming months
+#[get("/analytics/forecast")]
+async fn get_cash_flow_forecast(
+    pool: ReadPool,
+    query: web::Query<ForecastQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+    let months = query.months.unwrap_or(6);
+    if !(1..=forecast::MAX_FORECAST_MONTHS).contains(&months) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+            "months must be between 1 and {}",
+            forecast::MAX_FORECAST_MONTHS
+        )));
+    }
 
-    match results {
-        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+    match forecast::cash_flow_forecast(pool.get_ref(), user_id, months, Utc::now()).await {
+        Ok(forecast) => HttpResponse::Ok().json(ApiResponse::success(forecast)),
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
 }
 
-/// GET /analytics/monthly-summary - Get monthly income/expense summary
-#[get("/analytics/monthly-summary")]
-async fn get_monthly_summary(
-    pool: web::Data<SqlitePool>,
+/// GET /analytics/buckets - Needs/wants/savings split of a month's spending vs. the target allocation
+#[get("/analytics/buckets")]
+async fn get_bucket_split(
+    pool: ReadPool,
+    query: web::Query<BucketQuery>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+    let period = query
+        .period
+        .clone()
+        .unwrap_or_else(|| Utc::now().format("%Y-%m").to_string());
+
+    let Some((start, end)) = budgets::parse_period(&period) else {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("period must be formatted as YYYY-MM".into()));
+    };
+
+    let targets = match query.targets.as_deref() {
+        Some(targets) => match buckets::parse_targets(targets) {
+            Ok(targets) => targets,
+            Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
+        },
+        None => match buckets::target_allocation(pool.get_ref(), user_id).await {
+            Ok(targets) => targets,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(e.to_string()))
+            }
+        },
+    };
+
+    match buckets::bucket_split(pool.get_ref(), user_id, &period, start, end, targets).await {
+        Ok(split) => HttpResponse::Ok().json(ApiResponse::success(split)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /analytics/safe-to-spend - Amount that can be spent per day for the rest of the month
+#[get("/analytics/safe-to-spend")]
+async fn get_safe_to_spend(
+    pool: ReadPool,
     query: web::Query<AnalyticsFilter>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+
+    match forecast::safe_to_spend(pool.get_ref(), user_id, Utc::now()).await {
+        Ok(result) => HttpResponse::Ok().json(ApiResponse::success(result)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Activity Feed Endpoints
+// ============================================================================
+
+/// GET /activity - Recent transactions, recurring executions, imports, rate refreshes, export drops and alerts
+#[get("/activity")]
+async fn get_activity(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<ActivityFilter>,
+) -> impl Responder {
+    let user_id = match query.user_id {
+        Some(user_id) => user_id,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("user_id is required".into()))
+        }
+    };
+    let limit = query.limit.unwrap_or(activity::DEFAULT_LIMIT);
+
+    match activity::recent_activity(pool.get_ref(), user_id, limit).await {
+        Ok(items) => HttpResponse::Ok().json(ApiResponse::success(items)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Data Export Endpoints
+// ============================================================================
+
+/// GET /export/transactions/csv - Export transactions as CSV
+#[get("/export/transactions/csv")]
+async fn export_transactions_csv(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<ExportFilter>,
 ) -> impl Responder {
     let mut where_clauses = Vec::new();
 
     if let Some(user_id) = query.user_id {
         where_clauses.push(format!(
-            "account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+            "t.account_id IN (SELECT id FROM accounts WHERE user_id = {})",
             user_id
         ));
     }
     if let Some(ref start_date) = query.start_date {
-        where_clauses.push(format!("transaction_date >= '{}'", start_date));
+        where_clauses.push(format!("t.transaction_date >= '{}'", start_date));
     }
     if let Some(ref end_date) = query.end_date {
-        where_clauses.push(format!("transaction_date <= '{}'", end_date));
+        where_clauses.push(format!("t.transaction_date <= '{}'", end_date));
+    }
+    if let Some(account_id) = query.account_id {
+        where_clauses.push(format!("t.account_id = {}", account_id));
+    }
+    if let Some(category_id) = query.category_id {
+        where_clauses.push(format!(
+            "t.id IN (SELECT transaction_id FROM transaction_categories WHERE category_id = {})",
+            category_id
+        ));
     }
 
     let where_sql = if where_clauses.is_empty() {
@@ -1379,175 +5355,381 @@ async fn get_monthly_summary(
     };
 
     let query_sql = format!(
-        "SELECT strftime('%Y-%m', transaction_date) as month,
-                SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0 END) as total_income,
-                SUM(CASE WHEN transaction_type = 'expense' THEN ABS(amount) ELSE 0 END) as total_expense,
-                SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE -ABS(amount) END) as net_change,
-                COUNT(*) as transaction_count
-         FROM transactions
+        "SELECT t.id, t.account_id, a.name as account_name, t.amount, t.transaction_type,
+                t.description, t.transaction_date, a.currency, t.notes, t.reference
+         FROM transactions t
+         JOIN accounts a ON t.account_id = a.id
          {}
-         GROUP BY strftime('%Y-%m', transaction_date)
-         ORDER BY month DESC
-         LIMIT 12",
+         ORDER BY t.transaction_date DESC",
         where_sql
     );
 
-    let results = sqlx::query_as::<_, MonthlySummary>(&query_sql)
+    let rows = sqlx::query(&query_sql)
         .fetch_all(pool.get_ref())
         .await;
 
-    match results {
-        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
+    match rows {
+        Ok(rows) => {
+            use sqlx::Row;
+            let mut csv = String::from("id,account_id,account_name,amount,type,description,date,currency,notes,reference\n");
+
+            for row in rows {
+                let id: i64 = row.get("id");
+                let account_id: i64 = row.get("account_id");
+                let account_name: String = row.get("account_name");
+                let amount: f64 = row.get("amount");
+                let txn_type: String = row.get("transaction_type");
+                let description: Option<String> = row.get("description");
+                let date: chrono::DateTime<Utc> = row.get("transaction_date");
+                let currency: String = row.get("currency");
+                let notes: Option<String> = row.get("notes");
+                let reference: Option<String> = row.get("reference");
+
+                csv.push_str(&format!(
+                    "{},{},\"{}\",{:.2},{},\"{}\",{},{},\"{}\",\"{}\"\n",
+                    id,
+                    account_id,
+                    account_name.replace("\"", "\"\""),
+                    amount,
+                    txn_type,
+                    description.unwrap_or_default().replace("\"", "\"\""),
+                    date.format("%Y-%m-%d %H:%M:%S"),
+                    currency,
+                    notes.unwrap_or_default().replace("\"", "\"\""),
+                    reference.unwrap_or_default().replace("\"", "\"\"")
+                ));
+            }
+
+            export_file(&req, query.encrypt, "text/csv", "transactions.csv", csv)
+        }
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
 }
 
-/// GET /analytics/spending-comparison - Compare spending between periods
-#[get("/analytics/spending-comparison")]
-async fn get_spending_comparison(
-    pool: web::Data<SqlitePool>,
-    query: web::Query<SpendingComparisonQuery>,
+/// GET /export/transactions/ofx - Export transactions as OFX (one statement per account)
+#[get("/export/transactions/ofx")]
+async fn export_transactions_ofx(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<ExportFilter>,
 ) -> impl Responder {
-    let mut user_filter = String::new();
+    let mut where_clauses = Vec::new();
+
     if let Some(user_id) = query.user_id {
-        user_filter = format!(
-            "AND account_id IN (SELECT id FROM accounts WHERE user_id = {})",
-            user_id
-        );
+        where_clauses.push(format!("user_id = {}", user_id));
+    }
+    if let Some(account_id) = query.account_id {
+        where_clauses.push(format!("id = {}", account_id));
     }
 
-    // Get current period spending
-    let current_sql = format!(
-        "SELECT SUM(ABS(amount)) as total
-         FROM transactions
-         WHERE transaction_type = 'expense'
-         AND transaction_date >= ? AND transaction_date <= ?
-         {}",
-        user_filter
-    );
+    let where_sql = if where_clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", where_clauses.join(" AND "))
+    };
 
-    let current_total: Option<f64> = sqlx::query_scalar(&current_sql)
-        .bind(&query.current_start)
-        .bind(&query.current_end)
-        .fetch_optional(pool.get_ref())
+    let accounts_sql = format!("SELECT * FROM accounts {} ORDER BY id", where_sql);
+    let accounts = match sqlx::query_as::<_, Account>(&accounts_sql)
+        .fetch_all(pool.get_ref())
         .await
-        .unwrap_or(None);
+    {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
 
-    // Get previous period spending
-    let previous_sql = format!(
-        "SELECT SUM(ABS(amount)) as total
-         FROM transactions
-         WHERE transaction_type = 'expense'
-         AND transaction_date >= ? AND transaction_date <= ?
-         {}",
-        user_filter
+    let mut date_clauses = Vec::new();
+    if let Some(ref start_date) = query.start_date {
+        date_clauses.push(format!("AND transaction_date >= '{}'", start_date));
+    }
+    if let Some(ref end_date) = query.end_date {
+        date_clauses.push(format!("AND transaction_date <= '{}'", end_date));
+    }
+    let date_sql = date_clauses.join(" ");
+
+    let now = Utc::now().format("%Y%m%d%H%M%S").to_string();
+    let mut ofx = String::from(
+        "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\nSECURITY:NONE\nENCODING:USASCII\nCHARSET:1252\nCOMPRESSION:NONE\nOLDFILEUID:NONE\nNEWFILEUID:NONE\n\n",
     );
+    ofx.push_str("<OFX>\n<SIGNONMSGSRSV1>\n<SONRS>\n<STATUS>\n<CODE>0\n<SEVERITY>INFO\n</STATUS>\n");
+    ofx.push_str(&format!("<DTSERVER>{}\n<LANGUAGE>ENG\n</SONRS>\n</SIGNONMSGSRSV1>\n", now));
+    ofx.push_str("<BANKMSGSRSV1>\n");
 
-    let previous_total: Option<f64> = sqlx::query_scalar(&previous_sql)
-        .bind(&query.previous_start)
-        .bind(&query.previous_end)
-        .fetch_optional(pool.get_ref())
-        .await
-        .unwrap_or(None);
+    for account in &accounts {
+        let txn_sql = format!(
+            "SELECT * FROM transactions WHERE account_id = ? {} ORDER BY transaction_date",
+            date_sql
+        );
+        let transactions = sqlx::query_as::<_, Transaction>(&txn_sql)
+            .bind(account.id)
+            .fetch_all(pool.get_ref())
+            .await
+            .unwrap_or_default();
 
-    let current = current_total.unwrap_or(0.0);
-    let previous = previous_total.unwrap_or(0.0);
-    let change_amount = current - previous;
-    let change_percentage = if previous > 0.0 {
-        (change_amount / previous) * 100.0
-    } else {
-        0.0
-    };
+        let acct_type = match account.account_type.as_str() {
+            "savings" => "SAVINGS",
+            "credit_card" | "loan" => "CREDITLINE",
+            _ => "CHECKING",
+        };
 
-    let comparison = SpendingComparison {
-        current_period_total: current,
-        previous_period_total: previous,
-        change_amount,
-        change_percentage,
-    };
+        ofx.push_str(&format!(
+            "<STMTTRNRS>\n<TRNUID>{}\n<STATUS>\n<CODE>0\n<SEVERITY>INFO\n</STATUS>\n<STMTRS>\n<CURDEF>{}\n",
+            account.id, account.currency
+        ));
+        ofx.push_str(&format!(
+            "<BANKACCTFROM>\n<BANKID>{}\n<ACCTID>{}\n<ACCTTYPE>{}\n</BANKACCTFROM>\n",
+            ofx_escape(account.bank_name.as_deref().unwrap_or("")),
+            account.id,
+            acct_type
+        ));
+        ofx.push_str(&format!("<BANKTRANLIST>\n<DTSTART>{}\n<DTEND>{}\n",
+            transactions.first().map(|t| t.transaction_date.format("%Y%m%d").to_string()).unwrap_or_else(|| now[..8].to_string()),
+            transactions.last().map(|t| t.transaction_date.format("%Y%m%d").to_string()).unwrap_or_else(|| now[..8].to_string()),
+        ));
 
-    HttpResponse::Ok().json(ApiResponse::success(comparison))
+        for t in &transactions {
+            let (trn_type, signed_amount) = if t.transaction_type == "income" {
+                ("CREDIT", t.amount.abs())
+            } else {
+                ("DEBIT", -t.amount.abs())
+            };
+
+            ofx.push_str(&format!(
+                "<STMTTRN>\n<TRNTYPE>{}\n<DTPOSTED>{}\n<TRNAMT>{:.2}\n<FITID>{}\n",
+                trn_type,
+                t.transaction_date.format("%Y%m%d%H%M%S"),
+                signed_amount,
+                t.id
+            ));
+            if let Some(ref reference) = t.reference {
+                ofx.push_str(&format!("<CHECKNUM>{}\n", ofx_escape(reference)));
+            }
+            ofx.push_str(&format!(
+                "<NAME>{}\n",
+                ofx_escape(t.description.as_deref().unwrap_or(""))
+            ));
+            if let Some(ref notes) = t.notes {
+                ofx.push_str(&format!("<MEMO>{}\n", ofx_escape(notes)));
+            }
+            ofx.push_str("</STMTTRN>\n");
+        }
+
+        ofx.push_str("</BANKTRANLIST>\n");
+        ofx.push_str(&format!(
+            "<LEDGERBAL>\n<BALAMT>{:.2}\n<DTASOF>{}\n</LEDGERBAL>\n</STMTRS>\n</STMTTRNRS>\n",
+            account.current_balance, now
+        ));
+    }
+
+    ofx.push_str("</BANKMSGSRSV1>\n</OFX>\n");
+
+    export_file(&req, query.encrypt, "application/x-ofx", "transactions.ofx", ofx)
 }
 
-/// GET /analytics/top-categories - Get top spending categories
-#[get("/analytics/top-categories")]
-async fn get_top_categories(
-    pool: web::Data<SqlitePool>,
-    query: web::Query<AnalyticsFilter>,
+/// GET /export/transactions/qif - Export accounts, categories and transactions as QIF (GnuCash, Quicken)
+#[get("/export/transactions/qif")]
+async fn export_transactions_qif(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<ExportFilter>,
 ) -> impl Responder {
-    let limit = query.limit.unwrap_or(5);
-    let mut where_clauses = vec!["t.transaction_type = 'expense'".to_string()];
-
+    let mut account_clauses = Vec::new();
     if let Some(user_id) = query.user_id {
-        where_clauses.push(format!(
-            "t.account_id IN (SELECT id FROM accounts WHERE user_id = {})",
-            user_id
-        ));
+        account_clauses.push(format!("user_id = {}", user_id));
+    }
+    if let Some(account_id) = query.account_id {
+        account_clauses.push(format!("id = {}", account_id));
     }
+    let account_sql = if account_clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", account_clauses.join(" AND "))
+    };
+
+    let accounts_sql = format!("SELECT * FROM accounts {} ORDER BY sort_order, id", account_sql);
+    let accounts = match sqlx::query_as::<_, Account>(&accounts_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    let categories_sql = match query.user_id {
+        Some(user_id) => format!("SELECT * FROM categories WHERE user_id = {} ORDER BY id", user_id),
+        None => "SELECT * FROM categories ORDER BY id".to_string(),
+    };
+    let categories = match sqlx::query_as::<_, Category>(&categories_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(categories) => categories,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    let mut where_clauses = vec![format!("account_id IN (SELECT id FROM accounts {})", account_sql)];
     if let Some(ref start_date) = query.start_date {
-        where_clauses.push(format!("t.transaction_date >= '{}'", start_date));
+        where_clauses.push(format!("transaction_date >= '{}'", start_date));
     }
     if let Some(ref end_date) = query.end_date {
-        where_clauses.push(format!("t.transaction_date <= '{}'", end_date));
+        where_clauses.push(format!("transaction_date <= '{}'", end_date));
     }
+    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
+
+    let txn_sql = format!("SELECT * FROM transactions {} ORDER BY transaction_date, id", where_sql);
+    let transactions = match sqlx::query_as::<_, Transaction>(&txn_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    let splits_sql = format!(
+        "SELECT * FROM transaction_categories WHERE transaction_id IN (SELECT id FROM transactions {})",
+        where_sql
+    );
+    let splits = match sqlx::query_as::<_, TransactionCategory>(&splits_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(splits) => splits,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
 
-    let where_sql = format!("WHERE {}", where_clauses.join(" AND "));
+    let body = qif::render(&accounts, &transactions, &splits, &categories);
+    export_file(&req, query.encrypt, "application/qif", "transactions.qif", body)
+}
 
-    let query_sql = format!(
-        "SELECT c.id as category_id, c.name as category_name,
-                SUM(ABS(tc.amount)) as total_amount, COUNT(DISTINCT t.id) as transaction_count
-         FROM transactions t
-         JOIN transaction_categories tc ON t.id = tc.transaction_id
-         JOIN categories c ON tc.category_id = c.id
-         {}
-         GROUP BY c.id, c.name
-         ORDER BY total_amount DESC
-         LIMIT {}",
-        where_sql, limit
-    );
+/// Header carrying the passphrase for encrypted exports, so it stays out of URLs and access logs
+const EXPORT_PASSPHRASE_HEADER: &str = "X-Export-Passphrase";
+
+/// Build an export download; with `encrypt=true` the file is wrapped in an AES-256
+/// encrypted zip using the passphrase from the X-Export-Passphrase header
+fn export_file(
+    req: &HttpRequest,
+    encrypt: Option<bool>,
+    content_type: &str,
+    filename: &str,
+    body: impl Into<Vec<u8>>,
+) -> HttpResponse {
+    let body: Vec<u8> = body.into();
+    if !encrypt.unwrap_or(false) {
+        return HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+            .body(body);
+    }
 
-    let results = sqlx::query_as::<_, CategorySpendingSummary>(&query_sql)
-        .fetch_all(pool.get_ref())
-        .await;
+    let passphrase = match req
+        .headers()
+        .get(EXPORT_PASSPHRASE_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(passphrase) => passphrase,
+        None => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error(format!(
+                "{} header is required for encrypted exports",
+                EXPORT_PASSPHRASE_HEADER
+            )))
+        }
+    };
 
-    match results {
-        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(data)),
-        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    match encryption::encrypt(filename, &body, passphrase) {
+        Ok(encrypted) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}.{}\"", filename, encryption::FILE_EXTENSION),
+            ))
+            .body(encrypted),
+        Err(e) => HttpResponse::BadRequest().json(ApiResponse::<()>::error(e)),
     }
 }
 
-// ============================================================================
-// Data Export Endpoints
-// ============================================================================
+/// Escape a value for an OFX (SGML) element and keep it on one line
+fn ofx_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(['\r', '\n'], " ")
+}
 
-/// GET /export/transactions/csv - Export transactions as CSV
-#[get("/export/transactions/csv")]
-async fn export_transactions_csv(
-    pool: web::Data<SqlitePool>,
-    query: web::Query<ExportFilter>,
+/// GET /export/ledger - Export accounts and transactions as beancount or ledger-cli plaintext
+#[get("/export/ledger")]
+async fn export_ledger(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<LedgerExportFilter>,
 ) -> impl Responder {
-    let mut where_clauses = Vec::new();
+    let format = match query.format.as_deref() {
+        None => LedgerFormat::Beancount,
+        Some(f) => match LedgerFormat::from_str(f) {
+            Some(format) => format,
+            None => {
+                return HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+                    "format must be 'beancount' or 'ledger'".into(),
+                ))
+            }
+        },
+    };
+
+    let user_sql = match query.user_id {
+        Some(user_id) => format!("WHERE user_id = {}", user_id),
+        None => String::new(),
+    };
+
+    let accounts_sql = format!("SELECT * FROM accounts {} ORDER BY id", user_sql);
+    let accounts = match sqlx::query_as::<_, Account>(&accounts_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
 
+    let categories_sql = format!("SELECT * FROM categories {} ORDER BY id", user_sql);
+    let categories = match sqlx::query_as::<_, Category>(&categories_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(categories) => categories,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    let mut where_clauses = Vec::new();
     if let Some(user_id) = query.user_id {
         where_clauses.push(format!(
-            "t.account_id IN (SELECT id FROM accounts WHERE user_id = {})",
+            "account_id IN (SELECT id FROM accounts WHERE user_id = {})",
             user_id
         ));
     }
     if let Some(ref start_date) = query.start_date {
-        where_clauses.push(format!("t.transaction_date >= '{}'", start_date));
+        where_clauses.push(format!("transaction_date >= '{}'", start_date));
     }
     if let Some(ref end_date) = query.end_date {
-        where_clauses.push(format!("t.transaction_date <= '{}'", end_date));
-    }
-    if let Some(account_id) = query.account_id {
-        where_clauses.push(format!("t.account_id = {}", account_id));
-    }
-    if let Some(category_id) = query.category_id {
-        where_clauses.push(format!(
-            "t.id IN (SELECT transaction_id FROM transaction_categories WHERE category_id = {})",
-            category_id
-        ));
+        where_clauses.push(format!("transaction_date <= '{}'", end_date));
     }
 
     let where_sql = if where_clauses.is_empty() {
@@ -1556,63 +5738,44 @@ async fn export_transactions_csv(
         format!("WHERE {}", where_clauses.join(" AND "))
     };
 
-    let query_sql = format!(
-        "SELECT t.id, t.account_id, a.name as account_name, t.amount, t.transaction_type,
-                t.description, t.transaction_date, a.currency
-         FROM transactions t
-         JOIN accounts a ON t.account_id = a.id
-         {}
-         ORDER BY t.transaction_date DESC",
+    let txn_sql = format!(
+        "SELECT * FROM transactions {} ORDER BY transaction_date, id",
         where_sql
     );
-
-    let rows = sqlx::query(&query_sql)
+    let transactions = match sqlx::query_as::<_, Transaction>(&txn_sql)
         .fetch_all(pool.get_ref())
-        .await;
-
-    match rows {
-        Ok(rows) => {
-            use sqlx::Row;
-            let mut csv = String::from("id,account_id,account_name,amount,type,description,date,currency\n");
+        .await
+    {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
 
-            for row in rows {
-                let id: i64 = row.get("id");
-                let account_id: i64 = row.get("account_id");
-                let account_name: String = row.get("account_name");
-                let amount: f64 = row.get("amount");
-                let txn_type: String = row.get("transaction_type");
-                let description: Option<String> = row.get("description");
-                let date: chrono::DateTime<Utc> = row.get("transaction_date");
-                let currency: String = row.get("currency");
+    let splits_sql = format!(
+        "SELECT * FROM transaction_categories WHERE transaction_id IN (SELECT id FROM transactions {})",
+        where_sql
+    );
+    let splits = match sqlx::query_as::<_, TransactionCategory>(&splits_sql)
+        .fetch_all(pool.get_ref())
+        .await
+    {
+        Ok(splits) => splits,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
 
-                csv.push_str(&format!(
-                    "{},{},\"{}\",{:.2},{},\"{}\",{},{}\n",
-                    id,
-                    account_id,
-                    account_name.replace("\"", "\"\""),
-                    amount,
-                    txn_type,
-                    description.unwrap_or_default().replace("\"", "\"\""),
-                    date.format("%Y-%m-%d %H:%M:%S"),
-                    currency
-                ));
-            }
+    let body = ledger::render(format, &accounts, &transactions, &splits, &categories);
 
-            HttpResponse::Ok()
-                .content_type("text/csv")
-                .insert_header(("Content-Disposition", "attachment; filename=\"transactions.csv\""))
-                .body(csv)
-        }
-        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
-    }
+    let filename = format!("transactions.{}", format.file_extension());
+    export_file(&req, query.encrypt, "text/plain; charset=utf-8", &filename, body)
 }
 
-/// GET /export/transactions/json - Export transactions as JSON
-#[get("/export/transactions/json")]
-async fn export_transactions_json(
-    pool: web::Data<SqlitePool>,
-    query: web::Query<ExportFilter>,
-) -> impl Responder {
+/// The transactions an export filter selects, newest first
+fn transaction_export_sql(query: &ExportFilter) -> String {
     let mut where_clauses = Vec::new();
 
     if let Some(user_id) = query.user_id {
@@ -1637,31 +5800,81 @@ async fn export_transactions_json(
         format!("WHERE {}", where_clauses.join(" AND "))
     };
 
-    let query_sql = format!(
+    format!(
         "SELECT * FROM transactions {} ORDER BY transaction_date DESC",
         where_sql
-    );
+    )
+}
 
-    let transactions = sqlx::query_as::<_, Transaction>(&query_sql)
+/// GET /export/transactions/json - Export transactions as JSON
+#[get("/export/transactions/json")]
+async fn export_transactions_json(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<ExportFilter>,
+) -> impl Responder {
+    let transactions = sqlx::query_as::<_, Transaction>(&transaction_export_sql(&query))
         .fetch_all(pool.get_ref())
         .await;
 
     match transactions {
         Ok(data) => {
             let json = serde_json::to_string_pretty(&data).unwrap_or_default();
-            HttpResponse::Ok()
-                .content_type("application/json")
-                .insert_header(("Content-Disposition", "attachment; filename=\"transactions.json\""))
-                .body(json)
+            export_file(&req, query.encrypt, "application/json", "transactions.json", json)
         }
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
 }
 
+/// GET /export/transactions/jsonl - Export transactions as JSON Lines, one
+/// object per line, streamed as the rows are read
+#[get("/export/transactions/jsonl")]
+async fn export_transactions_jsonl(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<ExportFilter>,
+) -> impl Responder {
+    let sql = transaction_export_sql(&query);
+
+    // Encryption needs the whole file
+    if query.encrypt.unwrap_or(false) {
+        return match sqlx::query_as::<_, Transaction>(&sql).fetch_all(pool.get_ref()).await {
+            Ok(data) => {
+                let lines: String = data
+                    .iter()
+                    .map(|t| serde_json::to_string(t).unwrap_or_default() + "\n")
+                    .collect();
+                export_file(&req, query.encrypt, "application/x-ndjson", "transactions.jsonl", lines)
+            }
+            Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+        };
+    }
+
+    let (mut lines, body) = futures::channel::mpsc::channel::<Result<web::Bytes, sqlx::Error>>(64);
+    let pool = pool.get_ref().clone();
+    tokio::spawn(async move {
+        let mut rows = sqlx::query_as::<_, Transaction>(&sql).fetch(&pool);
+        while let Some(row) = rows.next().await {
+            // A failed read cuts the response short rather than ending it cleanly
+            let line = row.map(|t| web::Bytes::from(serde_json::to_string(&t).unwrap_or_default() + "\n"));
+            let failed = line.is_err();
+            if lines.send(line).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .insert_header(("Content-Disposition", "attachment; filename=\"transactions.jsonl\""))
+        .streaming(body)
+}
+
 /// GET /export/accounts/csv - Export accounts as CSV
 #[get("/export/accounts/csv")]
 async fn export_accounts_csv(
-    pool: web::Data<SqlitePool>,
+    req: HttpRequest,
+    pool: ReadPool,
     query: web::Query<ExportFilter>,
 ) -> impl Responder {
     let mut where_clauses = Vec::new();
@@ -1704,10 +5917,7 @@ async fn export_accounts_csv(
                 ));
             }
 
-            HttpResponse::Ok()
-                .content_type("text/csv")
-                .insert_header(("Content-Disposition", "attachment; filename=\"accounts.csv\""))
-                .body(csv)
+            export_file(&req, query.encrypt, "text/csv", "accounts.csv", csv)
         }
         Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
     }
@@ -1716,7 +5926,8 @@ async fn export_accounts_csv(
 /// GET /export/summary/json - Export complete financial summary as JSON
 #[get("/export/summary/json")]
 async fn export_summary_json(
-    pool: web::Data<SqlitePool>,
+    req: HttpRequest,
+    pool: ReadPool,
     query: web::Query<ExportFilter>,
 ) -> impl Responder {
     let user_filter = if let Some(user_id) = query.user_id {
@@ -1756,18 +5967,219 @@ async fn export_summary_json(
         Vec::new()
     };
 
+    // Splits and recurring transactions, so the file can be restored with POST /import/summary
+    let accounts_sql = format!("SELECT id FROM accounts {}", user_filter);
+    let splits_sql = format!(
+        "SELECT * FROM transaction_categories WHERE transaction_id IN
+             (SELECT id FROM transactions WHERE account_id IN ({})) ORDER BY id",
+        accounts_sql
+    );
+    let transaction_categories = sqlx::query_as::<_, TransactionCategory>(&splits_sql)
+        .fetch_all(pool.get_ref())
+        .await
+        .unwrap_or_default();
+    let recurring_sql = format!(
+        "SELECT * FROM recurring_transactions WHERE account_id IN ({}) ORDER BY id",
+        accounts_sql
+    );
+    let recurring_transactions = sqlx::query_as::<_, RecurringTransaction>(&recurring_sql)
+        .fetch_all(pool.get_ref())
+        .await
+        .unwrap_or_default();
+
     let summary = FinancialExportSummary {
         export_date: Utc::now(),
         accounts,
         categories,
         transactions,
+        transaction_categories,
+        recurring_transactions,
+    };
+
+    let json = serde_json::to_string_pretty(&summary).unwrap_or_default();
+    export_file(&req, query.encrypt, "application/json", "financial_summary.json", json)
+}
+
+/// GET /export/statement/pdf - Export a month's statement of every account as a PDF
+#[get("/export/statement/pdf")]
+async fn export_statement_pdf(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<StatementQuery>,
+) -> impl Responder {
+    let Some(month) = statements::parse_month(&query.month) else {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("month must be YYYY-MM".into()));
+    };
+
+    match statements::statement(pool.get_ref(), query.user_id, month).await {
+        Ok(Some(statement)) => export_file(
+            &req,
+            query.encrypt,
+            "application/pdf",
+            &format!("statement_{}.pdf", month.format("%Y-%m")),
+            statements::render(&statement),
+        ),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found".into())),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /export/summary/xlsx - Export the financial summary as an Excel workbook
+#[get("/export/summary/xlsx")]
+async fn export_summary_xlsx(
+    req: HttpRequest,
+    pool: ReadPool,
+    query: web::Query<ExportFilter>,
+) -> impl Responder {
+    let user_filter = if let Some(user_id) = query.user_id {
+        format!("WHERE user_id = {}", user_id)
+    } else {
+        String::new()
     };
 
-    let json = serde_json::to_string_pretty(&summary).unwrap_or_default();
-    HttpResponse::Ok()
-        .content_type("application/json")
-        .insert_header(("Content-Disposition", "attachment; filename=\"financial_summary.json\""))
-        .body(json)
+    let result = async {
+        let accounts = sqlx::query_as::<_, Account>(&format!("SELECT * FROM accounts {} ORDER BY sort_order, id", user_filter))
+            .fetch_all(pool.get_ref())
+            .await?;
+        let categories = sqlx::query_as::<_, Category>(&format!("SELECT * FROM categories {} ORDER BY name", user_filter))
+            .fetch_all(pool.get_ref())
+            .await?;
+        let transactions = sqlx::query_as::<_, Transaction>(&format!(
+            "SELECT * FROM transactions WHERE account_id IN (SELECT id FROM accounts {}) ORDER BY transaction_date DESC, id DESC",
+            user_filter
+        ))
+        .fetch_all(pool.get_ref())
+        .await?;
+        let transaction_categories = xlsx::transaction_categories(pool.get_ref(), query.user_id).await?;
+        let monthly = xlsx::monthly_summary(pool.get_ref(), query.user_id, None).await?;
+        Ok::<_, sqlx::Error>(xlsx::summary_sheets(&accounts, &transactions, &categories, &transaction_categories, &monthly))
+    }
+    .await;
+
+    match result {
+        Ok(sheets) => export_file(&req, query.encrypt, xlsx::CONTENT_TYPE, "financial_summary.xlsx", xlsx::workbook(&sheets)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+// ============================================================================
+// Webhook Endpoints
+// ============================================================================
+
+/// GET /webhooks - List the signed-in user's webhooks
+#[get("/webhooks")]
+async fn get_webhooks(pool: web::Data<SqlitePool>, auth_user: web::ReqData<AuthUser>) -> impl Responder {
+    let webhooks = sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE user_id = ? ORDER BY id")
+        .bind(auth_user.user_id)
+        .fetch_all(pool.get_ref())
+        .await;
+
+    match webhooks {
+        Ok(webhooks) => HttpResponse::Ok().json(ApiResponse::success(webhooks)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// POST /webhooks - Register a new webhook
+#[post("/webhooks")]
+async fn create_webhook(
+    pool: web::Data<SqlitePool>,
+    auth_user: web::ReqData<AuthUser>,
+    webhook_data: web::Json<CreateWebhook>,
+) -> impl Responder {
+    if let Err(e) = webhook_data.validate() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e));
+    }
+    if webhook_data.user_id != auth_user.user_id {
+        return forbidden();
+    }
+
+    let event_types = webhook_data.event_types.as_deref().unwrap_or("*");
+
+    let result = sqlx::query("INSERT INTO webhooks (user_id, url, event_types) VALUES (?, ?, ?)")
+        .bind(webhook_data.user_id)
+        .bind(&webhook_data.url)
+        .bind(event_types)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) => {
+            let webhook = sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks WHERE id = ?")
+                .bind(result.last_insert_rowid())
+                .fetch_one(pool.get_ref())
+                .await
+                .unwrap();
+
+            HttpResponse::Created().json(ApiResponse::success(webhook))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// DELETE /webhooks/{id} - Delete webhook (and its outbox rows)
+#[delete("/webhooks/{id}")]
+async fn delete_webhook(pool: web::Data<SqlitePool>, id: web::Path<i64>) -> impl Responder {
+    let id = id.into_inner();
+
+    let result = sqlx::query("DELETE FROM webhooks WHERE id = ?")
+        .bind(id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                HttpResponse::Ok().json(ApiResponse::success("Webhook deleted successfully"))
+            } else {
+                HttpResponse::NotFound().json(ApiResponse::<()>::error("Webhook not found".into()))
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
+}
+
+/// GET /webhooks/{id}/deliveries - Inspect outbox deliveries for a webhook
+#[get("/webhooks/{id}/deliveries")]
+async fn get_webhook_deliveries(
+    pool: web::Data<SqlitePool>,
+    id: web::Path<i64>,
+    query: web::Query<PaginationParams>,
+) -> impl Responder {
+    let id = id.into_inner();
+    let offset = (query.page - 1) * query.page_size;
+
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM webhooks WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .unwrap_or(None);
+
+    if exists.is_none() {
+        return HttpResponse::NotFound().json(ApiResponse::<()>::error("Webhook not found".into()));
+    }
+
+    let deliveries = webhooks::list_deliveries(pool.get_ref(), id, query.page_size, offset).await;
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM event_outbox WHERE webhook_id = ?")
+        .bind(id)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or(0);
+
+    match deliveries {
+        Ok(deliveries) => {
+            let response = PaginatedResponse {
+                items: deliveries,
+                total,
+                page: query.page,
+                page_size: query.page_size,
+                total_pages: (total + query.page_size - 1) / query.page_size,
+            };
+            HttpResponse::Ok().json(ApiResponse::success(response))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string())),
+    }
 }
 
 // ============================================================================
@@ -1775,34 +6187,85 @@ async fn export_summary_json(
 // ============================================================================
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(get_users)
+    // limit_body holds each endpoint to its own limit; the extractors only
+    // have to admit the largest (their 256 kB / 2 MB defaults are too small
+    // for receipt images and statement imports)
+    cfg.app_data(web::PayloadConfig::new(input::UPLOAD_BODY_BYTES))
+        .app_data(web::JsonConfig::default().limit(input::IMPORT_BODY_BYTES));
+    cfg.service(login)
+        .service(get_session)
+        .service(get_users)
         .service(get_user)
         .service(create_user)
         .service(update_user)
         .service(delete_user)
+        .service(get_user_settings)
+        .service(update_user_settings)
         .service(get_accounts)
         .service(get_account)
+        .service(get_account_stats)
+        .service(get_amortization)
+        .service(create_loan)
+        .service(get_holdings)
+        .service(create_holding)
+        .service(update_holding)
+        .service(delete_holding)
         .service(create_account)
+        .service(reorder_accounts) // before update_account so "reorder" isn't parsed as an {id}
         .service(update_account)
         .service(delete_account)
+        .service(close_account)
+        .service(reconcile_account)
+        .service(get_account_groups)
+        .service(create_account_group)
+        .service(delete_account_group)
+        .service(add_account_group_member)
+        .service(remove_account_group_member)
         .service(get_categories)
         .service(get_category)
         .service(create_category)
         .service(update_category)
         .service(delete_category)
         .service(get_transactions)
+        .service(recategorize_transactions) // before get_transaction so "recategorize" isn't parsed as an {id}
         .service(get_transaction)
         .service(create_transaction)
         .service(update_transaction)
+        .service(update_transaction_categories)
+        .service(update_transaction_status)
+        .service(add_transaction_tag)
+        .service(remove_transaction_tag)
+        .service(upload_attachments)
+        .service(get_attachments)
+        .service(download_attachment)
+        .service(delete_attachment)
+        .service(link_reimbursement)
+        .service(unlink_reimbursement)
         .service(delete_transaction)
+        .service(parse_receipt)
+        .service(get_tags)
+        .service(create_tag)
+        .service(delete_tag)
+        .service(get_transfers)
+        .service(create_transfer)
         .service(get_exchange_rates)
         .service(get_latest_rates)
         .service(convert_currency)
+        .service(get_currencies)
+        .service(preview_scraped_rates)
+        .service(get_scrape_runs)
+        .service(get_stale_rates)
+        .service(get_rate_storage_stats)
         .service(create_exchange_rate)
         .service(update_exchange_rate)
         .service(delete_rates_bulk)
         .service(delete_exchange_rate)
         .service(get_exchange_rate)
+        .service(get_rate_sources)
+        .service(get_rate_source)
+        .service(create_rate_source)
+        .service(update_rate_source)
+        .service(delete_rate_source)
         // Recurring transactions
         .service(get_recurring_transactions)
         .service(get_recurring_transaction)
@@ -1810,14 +6273,1969 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         .service(update_recurring_transaction)
         .service(delete_recurring_transaction)
         .service(process_recurring_transactions)
+        // Transaction templates
+        .service(get_templates)
+        .service(create_template)
+        .service(update_template)
+        .service(delete_template)
+        // Budgets
+        .service(get_budgets)
+        .service(create_budget)
+        .service(get_budget)
+        .service(update_budget)
+        .service(delete_budget)
+        .service(get_budget_variance)
+        .service(get_goals)
+        .service(create_goal)
+        .service(get_goal)
+        .service(update_goal)
+        .service(delete_goal)
+        .service(get_spending_chart)
+        // Import & category rules
+        .service(get_category_rules)
+        .service(create_category_rule)
+        .service(delete_category_rule)
+        .service(get_import_profiles)
+        .service(create_import_profile)
+        .service(delete_import_profile)
+        .service(preview_import)
+        .service(commit_import)
+        .service(import_transactions_csv)
+        .service(import_summary)
         // Analytics
         .service(get_spending_by_category)
         .service(get_monthly_summary)
         .service(get_spending_comparison)
         .service(get_top_categories)
+        .service(get_spending_by_merchant)
+        .service(get_spending_by_currency)
+        .service(get_spending_patterns)
+        .service(get_cash_flows)
+        .service(get_account_type_balances)
+        .service(get_account_group_balances)
+        .service(get_net_worth)
+        .service(get_discretionary_budget)
+        .service(get_cash_flow_forecast)
+        .service(get_bucket_split)
+        .service(get_safe_to_spend)
+        // Activity feed
+        .service(get_activity)
         // Export
         .service(export_transactions_csv)
         .service(export_transactions_json)
+        .service(export_transactions_jsonl)
+        .service(export_transactions_ofx)
+        .service(export_transactions_qif)
+        .service(export_ledger)
         .service(export_accounts_csv)
-        .service(export_summary_json);
+        .service(export_summary_json)
+        .service(export_summary_xlsx)
+        .service(export_statement_pdf)
+        // Webhooks
+        .service(get_webhooks)
+        .service(create_webhook)
+        .service(delete_webhook)
+        .service(get_webhook_deliveries);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use actix_web::{test, App};
+    use serde_json::{json, Value};
+
+    async fn balance(pool: &SqlitePool, account_id: i64) -> f64 {
+        sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(account_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// "Authorization: Bearer" header of a new session for the user
+    fn bearer(user_id: i64) -> (&'static str, String) {
+        let (token, _) = auth::create_session(user_id);
+        ("Authorization", format!("Bearer {}", token))
+    }
+
+    #[actix_web::test]
+    async fn test_create_and_delete_transaction_updates_balance() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/transactions")
+            .insert_header(auth.clone())
+            .set_json(json!({
+                "account_id": account.id,
+                "amount": 30.0,
+                "transaction_type": "expense",
+                "description": "Weekly shop",
+                "categories": [{ "category_id": groceries.id, "amount": 30.0 }],
+                "fee": 1.5
+            }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["success"], true);
+        let transaction_id = resp["data"]["id"].as_i64().unwrap();
+        assert_eq!(balance(&pool, account.id).await, 68.5);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/transactions/{}", transaction_id))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["categories"][0]["category_name"], "Groceries");
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/transactions/{}", transaction_id))
+            .insert_header(auth.clone())
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+        assert_eq!(balance(&pool, account.id).await, 100.0);
+    }
+
+    #[actix_web::test]
+    async fn test_create_transaction_rejects_mismatched_splits() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/transactions")
+            .insert_header(auth.clone())
+            .set_json(json!({
+                "account_id": account.id,
+                "amount": 30.0,
+                "transaction_type": "expense",
+                "categories": [{ "category_id": groceries.id, "amount": 20.0 }]
+            }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(balance(&pool, account.id).await, 0.0);
+    }
+
+    #[actix_web::test]
+    async fn test_failed_split_rolls_back_the_transaction() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        // No such category: rejected up front now, and nothing may be left behind either way
+        let req = test::TestRequest::post()
+            .uri("/transactions")
+            .insert_header(auth.clone())
+            .set_json(json!({
+                "account_id": account.id,
+                "amount": 30.0,
+                "transaction_type": "expense",
+                "categories": [{ "category_id": 9999, "amount": 30.0 }]
+            }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(balance(&pool, account.id).await, 100.0);
+    }
+
+    #[actix_web::test]
+    async fn test_create_transaction_rejects_dates_before_opening() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let opened_on = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let account = testing::account(user.id).opened_on(opened_on).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        for (date, status) in [("2025-05-31T23:00:00Z", 400), ("2025-06-01T08:00:00Z", 201)] {
+            let req = test::TestRequest::post()
+                .uri("/transactions")
+                .insert_header(auth.clone())
+                .set_json(json!({
+                    "account_id": account.id,
+                    "amount": 10.0,
+                    "transaction_type": "expense",
+                    "transaction_date": date,
+                    "categories": []
+                }))
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), status);
+        }
+
+        // An earlier opening date is fine, a later one would orphan the transaction
+        for (opened_on, status) in [("2025-05-01", 200), ("2025-06-02", 400)] {
+            let req = test::TestRequest::put()
+                .uri(&format!("/accounts/{}", account.id))
+                .insert_header(auth.clone())
+                .set_json(json!({ "opened_on": opened_on }))
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), status);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_pending_transaction_posts_when_cleared() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/transactions")
+            .insert_header(auth.clone())
+            .set_json(json!({
+                "account_id": account.id,
+                "amount": 25.0,
+                "transaction_type": "expense",
+                "categories": [],
+                "status": "pending"
+            }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let id = resp["data"]["id"].as_i64().unwrap();
+        assert_eq!(resp["data"]["status"], "pending");
+        assert_eq!(balance(&pool, account.id).await, 100.0);
+
+        let req = test::TestRequest::get()
+            .uri("/transactions?status=pending")
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["total"], 1);
+
+        for (status, code, expected) in [("posted", 400, 100.0), ("cleared", 200, 75.0), ("void", 200, 100.0)] {
+            let req = test::TestRequest::put()
+                .uri(&format!("/transactions/{}/status", id))
+                .insert_header(auth.clone())
+                .set_json(json!({ "status": status }))
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), code);
+            assert_eq!(balance(&pool, account.id).await, expected);
+        }
+
+        // Deleting the void transaction leaves the balance alone
+        let req = test::TestRequest::delete()
+            .uri(&format!("/transactions/{}", id))
+            .insert_header(auth)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        assert_eq!(balance(&pool, account.id).await, 100.0);
+    }
+
+    #[actix_web::test]
+    async fn test_transactions_with_running_balance() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let first = Utc::now() - chrono::Duration::days(2);
+        testing::txn(account.id).amount(40.0).date(first).insert(&pool).await;
+        testing::txn(account.id).amount(15.0).income().insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/transactions?account_id={}&include_running_balance=true", account.id))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let balances: Vec<f64> = resp["data"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["running_balance"].as_f64().unwrap())
+            .collect();
+        assert_eq!(balances, [75.0, 60.0]); // Newest first
+
+        let req = test::TestRequest::get()
+            .uri("/transactions?include_running_balance=true")
+            .insert_header(auth)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_transactions_filtered_by_date_range() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let now = Utc::now();
+        let (this_month, _) = budgets::resolve_period("this_month", now).unwrap();
+        let recent = testing::txn(account.id).amount(10.0).date(this_month).insert(&pool).await;
+        let old = testing::txn(account.id).amount(20.0).date(this_month - chrono::Duration::days(40)).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let ids = |resp: Value| -> Vec<i64> {
+            resp["data"]["items"].as_array().unwrap().iter().map(|t| t["id"].as_i64().unwrap()).collect()
+        };
+        let start = (this_month - chrono::Duration::days(1)).format("%Y-%m-%dT%H:%M:%SZ");
+        for (uri, expected) in [
+            ("/transactions?period=this_month".to_string(), vec![recent.id]),
+            (format!("/transactions?start_date={}", start), vec![recent.id]),
+            (format!("/transactions?end_date={}", start), vec![old.id]),
+        ] {
+            let req = test::TestRequest::get().uri(&uri).insert_header(auth.clone()).to_request();
+            assert_eq!(ids(test::call_and_read_body_json(&app, req).await), expected, "{}", uri);
+        }
+
+        let req = test::TestRequest::get()
+            .uri("/transactions?period=someday")
+            .insert_header(auth)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_tag_transactions_and_filter_by_tag() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let hotel = testing::txn(account.id).amount(300.0).insert(&pool).await;
+        let taxi = testing::txn(account.id).amount(40.0).insert(&pool).await;
+        testing::txn(account.id).amount(12.0).insert(&pool).await;
+        let stranger = testing::user().insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let tag = |transaction_id: i64, name: &str| {
+            test::TestRequest::post()
+                .uri(&format!("/transactions/{}/tags", transaction_id))
+                .insert_header(auth.clone())
+                .set_json(json!({ "name": name }))
+                .to_request()
+        };
+        test::call_service(&app, tag(hotel.id, "#Vacation2024")).await;
+        let resp: Value = test::call_and_read_body_json(&app, tag(taxi.id, "vacation2024")).await;
+        assert_eq!(resp["data"], json!(["vacation2024"]));
+        let resp: Value = test::call_and_read_body_json(&app, tag(taxi.id, "reimbursable")).await;
+        assert_eq!(resp["data"], json!(["reimbursable", "vacation2024"]));
+        assert_eq!(test::call_service(&app, tag(taxi.id, "two words")).await.status(), 400);
+
+        let list = |query: &str| {
+            test::TestRequest::get()
+                .uri(&format!("/transactions?{}", query))
+                .insert_header(auth.clone())
+                .to_request()
+        };
+        let resp: Value = test::call_and_read_body_json(&app, list("tag=vacation2024")).await;
+        assert_eq!(resp["data"]["total"], 2);
+        let resp: Value = test::call_and_read_body_json(&app, list("tag=vacation2024,reimbursable")).await;
+        assert_eq!(resp["data"]["items"][0]["id"], taxi.id);
+        assert_eq!(resp["data"]["total"], 1);
+
+        let req = test::TestRequest::get().uri("/tags").insert_header(auth.clone()).to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let tags = resp["data"].as_array().unwrap();
+        assert_eq!(tags.len(), 2);
+        let reimbursable_id = tags[0]["id"].as_i64().unwrap();
+
+        // Someone else can't tag the transaction or delete the tag
+        let req = test::TestRequest::post()
+            .uri(&format!("/transactions/{}/tags", taxi.id))
+            .insert_header(bearer(stranger.id))
+            .set_json(json!({ "name": "mine" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/tags/{}", reimbursable_id))
+            .insert_header(bearer(stranger.id))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/transactions/{}/tags/{}", taxi.id, reimbursable_id))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"], json!(["vacation2024"]));
+        let req = test::TestRequest::get()
+            .uri(&format!("/transactions/{}", taxi.id))
+            .insert_header(auth)
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["tags"], json!(["vacation2024"]));
+    }
+
+    #[actix_web::test]
+    async fn test_link_reimbursement_nets_category_spending() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let travel = testing::category(&pool, user.id, "Work travel").await;
+        let now = Utc::now();
+        let hotel = testing::txn(account.id).amount(400.0).category(travel.id).date(now - chrono::Duration::days(2)).insert(&pool).await;
+        let payback = testing::txn(account.id).amount(400.0).income().insert(&pool).await;
+        let stranger = testing::user().insert(&pool).await;
+        let foreign_account = testing::account(stranger.id).insert(&pool).await;
+        let foreign = testing::txn(foreign_account.id).amount(400.0).income().insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let link = |expense_id: i64, reimbursement_id: i64| {
+            test::TestRequest::post()
+                .uri(&format!("/transactions/{}/link-reimbursement", expense_id))
+                .insert_header(auth.clone())
+                .set_json(json!({ "reimbursement_id": reimbursement_id }))
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, link(hotel.id, foreign.id)).await.status(), 404);
+        assert_eq!(test::call_service(&app, link(payback.id, hotel.id)).await.status(), 400);
+        let resp: Value = test::call_and_read_body_json(&app, link(hotel.id, payback.id)).await;
+        assert_eq!(resp["data"]["net_amount"], 0.0);
+        assert_eq!(resp["data"]["reimbursements"][0]["id"], payback.id);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/analytics/spending-by-category?user_id={}", user.id))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"][0]["total_amount"], 0.0);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/transactions/{}/link-reimbursement/{}", hotel.id, payback.id))
+            .insert_header(auth)
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["net_amount"], 400.0);
+    }
+
+    #[actix_web::test]
+    async fn test_attachment_uploads_are_validated_and_scoped() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let transaction = testing::txn(account.id).insert(&pool).await;
+        let stranger = testing::user().insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let upload = |auth: (&'static str, String), filename: &str, content_type: &str| {
+            let body = format!(
+                "--b0und\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\nhello\r\n--b0und--\r\n",
+                filename, content_type
+            );
+            test::TestRequest::post()
+                .uri(&format!("/transactions/{}/attachments", transaction.id))
+                .insert_header(auth)
+                .insert_header(("Content-Type", "multipart/form-data; boundary=b0und"))
+                .set_payload(body)
+                .to_request()
+        };
+        let resp = test::call_service(&app, upload(auth.clone(), "notes.txt", "text/plain")).await;
+        assert_eq!(resp.status(), 400);
+        let req = test::TestRequest::post()
+            .uri(&format!("/transactions/{}/attachments", transaction.id))
+            .insert_header(auth.clone())
+            .set_json(json!({ "file": "receipt.jpg" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+        let stranger_auth = bearer(stranger.id);
+        let resp = test::call_service(&app, upload(stranger_auth.clone(), "receipt.jpg", "image/jpeg")).await;
+        assert_eq!(resp.status(), 403);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/transactions/{}/attachments", transaction.id))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"], json!([]));
+        let req = test::TestRequest::get()
+            .uri(&format!("/transactions/{}/attachments/1", transaction.id))
+            .insert_header(auth)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_account_group_membership() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).balance(300.0).insert(&pool).await;
+        testing::account(user.id).balance(25.0).insert(&pool).await;
+        let stranger = testing::user().insert(&pool).await;
+        let foreign = testing::account(stranger.id).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/account-groups")
+            .insert_header(auth.clone())
+            .set_json(json!({ "user_id": user.id, "name": "Liquid" }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let group_id = resp["data"]["id"].as_i64().unwrap();
+
+        let add = |account_id: i64| {
+            test::TestRequest::post()
+                .uri(&format!("/account-groups/{}/accounts", group_id))
+                .insert_header(auth.clone())
+                .set_json(json!({ "account_id": account_id }))
+                .to_request()
+        };
+        let resp: Value = test::call_and_read_body_json(&app, add(checking.id)).await;
+        assert_eq!(resp["data"]["account_group_id"], group_id);
+        assert_eq!(test::call_service(&app, add(foreign.id)).await.status(), 400);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/analytics/balances/groups?user_id={}", user.id))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let groups = resp["data"].as_array().unwrap();
+        assert_eq!((groups[0]["group_name"].as_str(), groups[0]["totals"][0]["net_worth"].as_f64()), (Some("Liquid"), Some(300.0)));
+        assert_eq!(groups[1]["group_name"], "Ungrouped");
+
+        // Another user's token can't touch the group
+        let req = test::TestRequest::delete()
+            .uri(&format!("/account-groups/{}/accounts/{}", group_id, checking.id))
+            .insert_header(bearer(stranger.id))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/account-groups/{}/accounts/{}", group_id, checking.id))
+            .insert_header(auth)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
+
+    #[actix_web::test]
+    async fn test_sub_category_spending_rolls_up() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let food = testing::category(&pool, user.id, "Food").await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let create = |name: &str, parent: i64| {
+            test::TestRequest::post()
+                .uri("/categories")
+                .insert_header(auth.clone())
+                .set_json(json!({ "user_id": user.id, "name": name, "parent_category_id": parent }))
+                .to_request()
+        };
+        let resp: Value = test::call_and_read_body_json(&app, create("Groceries", food.id)).await;
+        let groceries = resp["data"]["id"].as_i64().unwrap();
+        assert_eq!(resp["data"]["parent_category_id"], food.id);
+        // Nesting is one level deep
+        assert_eq!(test::call_service(&app, create("Produce", groceries)).await.status(), 400);
+
+        testing::txn(account.id).amount(40.0).category(groceries).insert(&pool).await;
+        testing::txn(account.id).amount(15.0).category(food.id).insert(&pool).await;
+
+        let totals = |resp: Value| -> Vec<(String, f64)> {
+            resp["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| (r["category_name"].as_str().unwrap().to_string(), r["total_amount"].as_f64().unwrap()))
+                .collect()
+        };
+        let uri = format!("/analytics/spending-by-category?user_id={}", user.id);
+        let req = test::TestRequest::get().uri(&uri).insert_header(auth.clone()).to_request();
+        assert_eq!(
+            totals(test::call_and_read_body_json(&app, req).await),
+            [("Groceries".to_string(), 40.0), ("Food".to_string(), 15.0)]
+        );
+        let req = test::TestRequest::get()
+            .uri(&format!("{}&rollup=true", uri))
+            .insert_header(auth)
+            .to_request();
+        assert_eq!(totals(test::call_and_read_body_json(&app, req).await), [("Food".to_string(), 55.0)]);
+    }
+
+    #[actix_web::test]
+    async fn test_update_transaction_categories_replaces_splits() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let household = testing::category(&pool, user.id, "Household").await;
+        let stranger = testing::user().insert(&pool).await;
+        let foreign = testing::category(&pool, stranger.id, "Other").await;
+        let txn = testing::txn(account.id).amount(50.0).category(dining.id).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+        let put = |categories: Value| {
+            test::TestRequest::put()
+                .uri(&format!("/transactions/{}/categories", txn.id))
+                .insert_header(auth.clone())
+                .set_json(json!({ "categories": categories }))
+                .to_request()
+        };
+        let splits = || async {
+            sqlx::query_as::<_, (i64, f64)>(
+                "SELECT category_id, amount FROM transaction_categories WHERE transaction_id = ? ORDER BY category_id",
+            )
+            .bind(txn.id)
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        };
+
+        let req = put(json!([
+            { "category_id": groceries.id, "amount": 30.0 },
+            { "category_id": household.id, "amount": 20.0 }
+        ]));
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["categories"][1]["category_name"], "Household");
+        assert_eq!(splits().await, vec![(groceries.id, 30.0), (household.id, 20.0)]);
+
+        // Wrong total or someone else's category: rejected, splits unchanged
+        let req = put(json!([{ "category_id": dining.id, "amount": 40.0 }]));
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+        let req = put(json!([
+            { "category_id": dining.id, "amount": 25.0 },
+            { "category_id": foreign.id, "amount": 25.0 }
+        ]));
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+        assert_eq!(splits().await.len(), 2);
+
+        let req = put(json!([]));
+        assert!(test::call_service(&app, req).await.status().is_success());
+        assert!(splits().await.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_get_transactions_filters() {
+        let pool = testing::pool().await;
+        let user = testing::user().username("alice").insert(&pool).await;
+        let checking = testing::account(user.id).insert(&pool).await;
+        let card = testing::account(user.id)
+            .name("Visa")
+            .account_type("credit_card")
+            .insert(&pool)
+            .await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        let yesterday = Utc::now() - chrono::Duration::days(1);
+
+        testing::txn(checking.id).income().amount(1000.0).description("Salary").insert(&pool).await;
+        testing::txn(checking.id).category(dining.id).date(yesterday).insert(&pool).await;
+        testing::txn(card.id).category(dining.id).insert(&pool).await;
+
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+        let list = |uri: String| {
+            let (app, auth) = (&app, &auth);
+            async move {
+                let req = test::TestRequest::get().uri(&uri).insert_header(auth.clone()).to_request();
+                let resp: Value = test::call_and_read_body_json(app, req).await;
+                resp["data"].clone()
+            }
+        };
+
+        // Newest first
+        let page = list(format!("/transactions?account_id={}", checking.id)).await;
+        assert_eq!(page["total"], 2);
+        assert_eq!(page["items"][0]["description"], "Salary");
+
+        let page = list("/transactions?transaction_type=expense&page_size=1".to_string()).await;
+        assert_eq!((page["total"].as_i64(), page["total_pages"].as_i64()), (Some(2), Some(2)));
+    }
+
+    #[actix_web::test]
+    async fn test_user_scoped_resources_require_own_session() {
+        let pool = testing::pool().await;
+        let alice = testing::user().insert(&pool).await;
+        let bob = testing::user().insert(&pool).await;
+        let checking = testing::account(alice.id).insert(&pool).await;
+        let bobs = testing::account(bob.id).name("Bob's").insert(&pool).await;
+        let txn = testing::txn(bobs.id).insert(&pool).await;
+        let auth = bearer(alice.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+        let get = |uri: &str| test::TestRequest::get().uri(uri).insert_header(auth.clone()).to_request();
+
+        let req = test::TestRequest::get().uri("/accounts").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+        let req = test::TestRequest::get()
+            .uri("/accounts")
+            .insert_header(("Authorization", "Bearer nope"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+        let resp: Value = test::call_and_read_body_json(&app, get("/accounts")).await;
+        assert_eq!(resp["data"]["total"], 1);
+        assert_eq!(resp["data"]["items"][0]["id"], checking.id);
+        let resp: Value = test::call_and_read_body_json(&app, get("/transactions")).await;
+        assert_eq!(resp["data"]["total"], 0);
+
+        assert_eq!(test::call_service(&app, get(&format!("/accounts/{}", bobs.id))).await.status(), 403);
+        assert_eq!(test::call_service(&app, get(&format!("/transactions/{}", txn.id))).await.status(), 403);
+        assert_eq!(test::call_service(&app, get("/accounts/999")).await.status(), 404);
+
+        let req = test::TestRequest::post()
+            .uri("/transactions")
+            .insert_header(auth.clone())
+            .set_json(json!({
+                "account_id": bobs.id,
+                "amount": 5.0,
+                "transaction_type": "expense",
+                "categories": []
+            }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = test::TestRequest::post()
+            .uri("/categories")
+            .insert_header(auth.clone())
+            .set_json(json!({ "user_id": bob.id, "name": "Sneaky" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        assert_eq!(balance(&pool, bobs.id).await, 0.0);
+    }
+
+    #[actix_web::test]
+    async fn test_user_id_routes_are_limited_to_the_session_user() {
+        let pool = testing::pool().await;
+        let alice = testing::user().insert(&pool).await;
+        let bob = testing::user().insert(&pool).await;
+        let checking = testing::account(alice.id).insert(&pool).await;
+        let bobs = testing::account(bob.id).insert(&pool).await;
+        let groceries = testing::category(&pool, bob.id, "Groceries").await;
+        testing::txn(checking.id).amount(10.0).insert(&pool).await;
+        testing::txn(bobs.id).amount(20.0).insert(&pool).await;
+        let auth = bearer(alice.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+        let get = |uri: String| test::TestRequest::get().uri(&uri).insert_header(auth.clone()).to_request();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/export/summary/json?user_id={}", bob.id))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+        for uri in [
+            format!("/export/summary/json?user_id={}", bob.id),
+            format!("/analytics/net-worth?user_id={}", bob.id),
+            format!("/users/{}", bob.id),
+            format!("/users/{}/settings", bob.id),
+        ] {
+            assert_eq!(test::call_service(&app, get(uri)).await.status(), 403);
+        }
+
+        // Without a user_id the export is the signed-in user's only
+        let body = test::call_and_read_body(&app, get("/export/transactions/json".to_string())).await;
+        let exported: Vec<Transaction> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(exported.iter().map(|t| t.account_id).collect::<Vec<_>>(), [checking.id]);
+
+        let req = test::TestRequest::post()
+            .uri("/budgets")
+            .insert_header(auth.clone())
+            .set_json(json!({ "user_id": bob.id, "category_id": groceries.id, "monthly_limit": 1.0 }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+
+        // Values are bound, not spliced into the SQL
+        let req = test::TestRequest::put()
+            .uri(&format!("/users/{}", alice.id))
+            .insert_header(auth.clone())
+            .set_json(json!({ "username": "o'brien" }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["username"], "o'brien");
+
+        // Signing up needs no session
+        let req = test::TestRequest::post()
+            .uri("/users")
+            .set_json(json!({ "username": "carol", "email": "carol@example.com", "password": "password123" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    #[actix_web::test]
+    async fn test_webhooks_are_limited_to_the_session_user() {
+        let pool = testing::pool().await;
+        let alice = testing::user().insert(&pool).await;
+        let bob = testing::user().insert(&pool).await;
+        let bobs: i64 =
+            sqlx::query_scalar("INSERT INTO webhooks (user_id, url, event_types) VALUES (?, ?, '*') RETURNING id")
+                .bind(bob.id)
+                .bind("https://bob.example.com/hook")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let auth = bearer(alice.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/webhooks").insert_header(auth.clone()).to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"], json!([]));
+
+        let req = test::TestRequest::post()
+            .uri("/webhooks")
+            .insert_header(auth.clone())
+            .set_json(json!({ "user_id": bob.id, "url": "https://alice.example.com/hook" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = test::TestRequest::get()
+            .uri(&format!("/webhooks/{}/deliveries", bobs))
+            .insert_header(auth.clone())
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/webhooks/{}", bobs))
+            .insert_header(auth.clone())
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+
+        let req = test::TestRequest::post()
+            .uri("/webhooks")
+            .insert_header(auth.clone())
+            .set_json(json!({ "user_id": alice.id, "url": "https://alice.example.com/hook" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+        let req = test::TestRequest::get().uri("/webhooks").insert_header(auth).to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"].as_array().unwrap().len(), 1);
+        assert_eq!(resp["data"][0]["user_id"], alice.id);
+    }
+
+    #[actix_web::test]
+    async fn test_close_account_requires_destination_for_remainder() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let savings = testing::account(user.id).name("Savings").balance(75.0).insert(&pool).await;
+        let checking = testing::account(user.id).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let close = |body: Value| {
+            test::TestRequest::post()
+                .uri(&format!("/accounts/{}/close", savings.id))
+                .insert_header(auth.clone())
+                .set_json(body)
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, close(json!({}))).await.status(), 400);
+
+        let resp: Value =
+            test::call_and_read_body_json(&app, close(json!({ "destination_account_id": checking.id }))).await;
+        assert_eq!(resp["data"]["account"]["is_archived"], true);
+        assert_eq!(resp["data"]["transferred"], 75.0);
+        assert_eq!(balance(&pool, checking.id).await, 75.0);
+
+        // Closed accounts take no new transactions
+        let req = test::TestRequest::post()
+            .uri("/transactions")
+            .insert_header(auth.clone())
+            .set_json(json!({
+                "account_id": savings.id,
+                "amount": 5.0,
+                "transaction_type": "income",
+                "categories": []
+            }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_transfer_needs_own_accounts_and_a_rate() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let bob = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let euros = testing::account(user.id).currency("EUR").insert(&pool).await;
+        let bobs = testing::account(bob.id).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let transfer = |to: i64| {
+            test::TestRequest::post()
+                .uri("/transfers")
+                .insert_header(auth.clone())
+                .set_json(json!({ "from_account_id": checking.id, "to_account_id": to, "amount": 40.0 }))
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, transfer(bobs.id)).await.status(), 403);
+        assert_eq!(test::call_service(&app, transfer(euros.id)).await.status(), 400);
+
+        testing::rate(&pool, "EUR", "USD", 1.25, Utc::now()).await;
+        let resp = test::call_service(&app, transfer(euros.id)).await;
+        assert_eq!(resp.status(), 201);
+        let resp: Value = test::read_body_json(resp).await;
+        assert_eq!(resp["data"]["exchange_rate"], 0.8);
+        assert_eq!(resp["data"]["to_transaction"]["linked_transaction_id"], resp["data"]["from_transaction"]["id"]);
+        assert_eq!((balance(&pool, checking.id).await, balance(&pool, euros.id).await), (60.0, 32.0));
+        let group_id = resp["data"]["from_transaction"]["transfer_group_id"].as_i64().unwrap();
+
+        let req = test::TestRequest::get().uri("/transfers").insert_header(auth.clone()).to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"].as_array().unwrap().len(), 1);
+        assert_eq!(resp["data"][0]["to_transaction"]["account_id"], euros.id);
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/transactions?transfer_group_id={}", group_id))
+            .insert_header(auth.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["total"], 2);
+
+        // Moving money between accounts is neither income nor spending
+        let req = test::TestRequest::get()
+            .uri(&format!("/analytics/monthly-summary?user_id={}", user.id))
+            .insert_header(auth)
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert!(resp["data"].as_array().unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_rate_sources_crud_and_provider_filter() {
+        let pool = testing::pool().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/rate-sources")
+            .set_json(json!({ "name": "ECB", "kind": "official", "url": "https://www.ecb.europa.eu" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let ecb: Value = test::read_body_json(resp).await;
+        let ecb_id = ecb["data"]["id"].as_i64().unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/rate-sources")
+            .set_json(json!({ "name": "ECB", "kind": "scraper" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        // The provider sets the kind; a contradicting source is rejected
+        let rate = |body: Value| test::TestRequest::post().uri("/exchange-rates").set_json(body).to_request();
+        let body = json!({ "from_currency": "EUR", "to_currency": "USD", "rate": 1.08, "rate_source_id": ecb_id });
+        let resp: Value = test::read_body_json(test::call_service(&app, rate(body)).await).await;
+        assert_eq!(resp["data"]["source"], "official");
+        let body = json!({ "from_currency": "EUR", "to_currency": "JPY", "rate": 160.0, "source": "manual", "rate_source_id": ecb_id });
+        assert_eq!(test::call_service(&app, rate(body)).await.status(), 400);
+        let body = json!({ "from_currency": "EUR", "to_currency": "GBP", "rate": 0.85, "source": "official" });
+        test::call_service(&app, rate(body)).await;
+
+        let req = test::TestRequest::get().uri("/exchange-rates?provider=ECB").to_request();
+        let resp: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(resp["data"]["total"], 1);
+        assert_eq!(resp["data"]["items"][0]["to_currency"], "USD");
+
+        let req = test::TestRequest::delete().uri(&format!("/rate-sources/{}", ecb_id)).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+        let req = test::TestRequest::put()
+            .uri(&format!("/rate-sources/{}", ecb_id))
+            .set_json(json!({ "reliability_notes": "Published around 16:00 CET" }))
+            .to_request();
+        let resp: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(resp["data"]["reliability_notes"], "Published around 16:00 CET");
+
+        let req = test::TestRequest::get().uri("/rate-sources?kind=official").to_request();
+        let resp: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(resp["data"].as_array().unwrap().len(), 2);
+    }
+
+    #[actix_web::test]
+    async fn test_login_issues_session_for_hashed_password() {
+        let pool = testing::pool().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/users")
+            .set_json(json!({ "username": "dana", "email": "dana@example.com", "password": "hunter2hunter2" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+        let attempt = |password: &str| {
+            test::TestRequest::post()
+                .uri("/auth/login")
+                .set_json(json!({ "username": "dana", "password": password }))
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, attempt("wrong password")).await.status(), 401);
+
+        let resp: Value = test::call_and_read_body_json(&app, attempt("hunter2hunter2")).await;
+        let token = resp["data"]["token"].as_str().unwrap().to_string();
+        assert_eq!(resp["data"]["user"]["username"], "dana");
+
+        let req = test::TestRequest::get()
+            .uri("/auth/session")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["username"], "dana");
+    }
+
+    #[actix_web::test]
+    async fn test_budget_update_and_delete_by_id() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        let budget = budgets::set_budget(&pool, user.id, dining.id, 200.0).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+        let auth = bearer(user.id);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/budgets/{}", budget.id))
+            .insert_header(auth.clone())
+            .set_json(json!({ "monthly_limit": 250.0 }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["monthly_limit"], 250.0);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/budgets/{}", budget.id))
+            .insert_header(auth.clone())
+            .set_json(json!({ "monthly_limit": 0.0 }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/budgets/{}", budget.id))
+            .insert_header(auth.clone())
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/budgets/{}", budget.id))
+            .insert_header(auth.clone())
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_export_summary_xlsx_is_a_workbook() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        testing::txn(account.id).amount(20.0).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/export/summary/xlsx?user_id={}", user.id))
+            .insert_header(bearer(user.id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), xlsx::CONTENT_TYPE);
+        let body = test::read_body(resp).await;
+        assert!(body.starts_with(b"PK\x03\x04"));
+    }
+
+    #[actix_web::test]
+    async fn test_oversized_and_garbled_input_is_rejected_or_cleaned() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .wrap(actix_web::middleware::from_fn(limit_body))
+                .configure(configure_routes),
+        )
+        .await;
+        let expense = |description: String| {
+            test::TestRequest::post()
+                .uri("/transactions")
+                .insert_header(auth.clone())
+                .set_json(json!({
+                    "account_id": account.id,
+                    "amount": 5.0,
+                    "transaction_type": "expense",
+                    "categories": [],
+                    "description": description
+                }))
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, expense("x".repeat(input::JSON_BODY_BYTES))).await;
+        assert_eq!(resp.status(), 413);
+        let resp = test::call_service(&app, expense("x".repeat(input::MAX_DESCRIPTION_LEN + 1))).await;
+        assert_eq!(resp.status(), 400);
+
+        let resp: Value = test::call_and_read_body_json(&app, expense("Coffee\u{1b}[2J\r\nshop".to_string())).await;
+        assert_eq!(resp["data"]["description"], "Coffee[2J  shop");
+    }
+
+    #[actix_web::test]
+    async fn test_export_statement_pdf() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        testing::txn(account.id).amount(20.0).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let month = Utc::now().format("%Y-%m");
+        let req = test::TestRequest::get()
+            .uri(&format!("/export/statement/pdf?user_id={}&month={}", user.id, month))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "application/pdf");
+        assert!(test::read_body(resp).await.starts_with(b"%PDF-"));
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/export/statement/pdf?user_id={}&month=May", user.id))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_currency_change_blocks_or_converts_history() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(200.0).insert(&pool).await;
+        let empty = testing::account(user.id).name("Spare").balance(10.0).insert(&pool).await;
+        testing::txn(account.id).amount(40.0).insert(&pool).await;
+        testing::rate(&pool, "USD", "EUR", 0.5, Utc::now()).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let put = |id: i64, query: &str| {
+            test::TestRequest::put()
+                .uri(&format!("/accounts/{}{}", id, query))
+                .insert_header(auth.clone())
+                .set_json(json!({ "currency": "EUR" }))
+                .to_request()
+        };
+        // History would be re-labelled, so the default mode refuses
+        assert_eq!(test::call_service(&app, put(account.id, "")).await.status(), 409);
+        let resp: Value = test::call_and_read_body_json(&app, put(empty.id, "")).await;
+        assert_eq!((resp["data"]["currency"].as_str(), resp["data"]["current_balance"].as_f64()), (Some("EUR"), Some(10.0)));
+
+        let resp: Value = test::call_and_read_body_json(&app, put(account.id, "?currency_mode=convert")).await;
+        assert_eq!(resp["data"]["currency"], "EUR");
+        assert_eq!(resp["data"]["initial_balance"], 100.0);
+        let (amount, original, currency, rate): (f64, f64, String, f64) = sqlx::query_as(
+            "SELECT amount, original_amount, original_currency, exchange_rate FROM transactions WHERE account_id = ?",
+        )
+        .bind(account.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((amount, original, currency.as_str(), rate), (20.0, 40.0, "USD", 0.5));
+
+        // A given rate needs no quote; without either the change is refused
+        let req = test::TestRequest::put()
+            .uri(&format!("/accounts/{}?currency_mode=convert&rate=2.5", account.id))
+            .insert_header(auth.clone())
+            .set_json(json!({ "currency": "GBP" }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["initial_balance"], 250.0);
+        let req = test::TestRequest::put()
+            .uri(&format!("/accounts/{}?currency_mode=convert", account.id))
+            .insert_header(auth.clone())
+            .set_json(json!({ "currency": "JPY" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_export_transactions_qif() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let other = testing::user().username("other").insert(&pool).await;
+        let account = testing::account(user.id).name("Everyday").account_type("credit_card").insert(&pool).await;
+        let theirs = testing::account(other.id).name("Theirs").insert(&pool).await;
+        let rent = testing::category(&pool, user.id, "Rent").await;
+        testing::txn(account.id).amount(900.0).category(rent.id).description("May rent").insert(&pool).await;
+        testing::txn(theirs.id).amount(5.0).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/export/transactions/qif?user_id={}", user.id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "application/qif");
+        let body = test::read_body(resp).await;
+        let qif = std::str::from_utf8(&body).unwrap();
+        assert!(qif.contains("!Account\nNEveryday\nTCCard\n^\n!Type:CCard\n"));
+        assert!(qif.contains("T-900.00\nC*\nPMay rent\nLRent\n^\n"));
+        assert!(!qif.contains("Theirs"));
+    }
+
+    #[actix_web::test]
+    async fn test_import_summary_restores_an_export() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let other = testing::user().username("other").insert(&pool).await;
+        let account = testing::account(user.id).name("Everyday").balance(250.0).insert(&pool).await;
+        let rent = testing::category(&pool, user.id, "Rent").await;
+        testing::category(&pool, other.id, "Rent").await;
+        testing::txn(account.id).amount(900.0).category(rent.id).description("May rent").insert(&pool).await;
+        testing::recurring(account.id).amount(900.0).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/export/summary/json?user_id={}", user.id))
+            .to_request();
+        let summary: Value = test::call_and_read_body_json(&app, req).await;
+        let import = |user_id: i64| {
+            test::TestRequest::post()
+                .uri(&format!("/import/summary?user_id={}", user_id))
+                .set_json(&summary)
+                .to_request()
+        };
+        let resp: Value = test::call_and_read_body_json(&app, import(other.id)).await;
+        assert_eq!(resp["data"]["accounts"], 1);
+        assert_eq!(resp["data"]["transactions"], 1);
+        assert_eq!(resp["data"]["recurring_transactions"], 1);
+        // Their own "Rent" is reused rather than duplicated
+        assert_eq!((resp["data"]["categories_created"].as_u64(), resp["data"]["categories_matched"].as_u64()), (Some(0), Some(1)));
+
+        let (balance, category): (f64, String) = sqlx::query_as(
+            "SELECT a.current_balance, c.name FROM accounts a
+             JOIN transactions t ON t.account_id = a.id
+             JOIN transaction_categories tc ON tc.transaction_id = t.id
+             JOIN categories c ON c.id = tc.category_id
+             WHERE a.user_id = ?",
+        )
+        .bind(other.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((balance, category.as_str()), (250.0, "Rent"));
+
+        // A second restore would duplicate the account
+        assert_eq!(test::call_service(&app, import(other.id)).await.status(), 409);
+        assert_eq!(test::call_service(&app, import(9999)).await.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_transactions_only_use_own_accounts_and_categories() {
+        let pool = testing::pool().await;
+        let user = testing::user().username("u1").insert(&pool).await;
+        let other = testing::user().username("u2").insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let others_account = testing::account(other.id).insert(&pool).await;
+        let food = testing::category(&pool, user.id, "Food").await;
+        let others_food = testing::category(&pool, other.id, "Food").await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let create = |account_id: i64, category_id: i64, fee_category_id: Option<i64>| {
+            test::TestRequest::post()
+                .uri("/transactions")
+                .insert_header(auth.clone())
+                .set_json(json!({
+                    "account_id": account_id,
+                    "amount": 10.0,
+                    "transaction_type": "expense",
+                    "categories": [{ "category_id": category_id, "amount": 10.0 }],
+                    "fee": 1.0,
+                    "fee_category_id": fee_category_id
+                }))
+                .to_request()
+        };
+        let status = |req| async { test::call_service(&app, req).await.status().as_u16() };
+        assert_eq!(status(create(others_account.id, food.id, None)).await, 403);
+        assert_eq!(status(create(account.id, others_food.id, None)).await, 422);
+        assert_eq!(status(create(account.id, food.id, Some(others_food.id))).await, 422);
+        assert_eq!(status(create(account.id, 999, None)).await, 422);
+        assert_eq!(balance(&pool, account.id).await, 0.0);
+
+        let req = create(account.id, food.id, Some(food.id));
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let transaction_id = resp["data"]["id"].as_i64().unwrap();
+        let req = test::TestRequest::put()
+            .uri(&format!("/transactions/{}", transaction_id))
+            .insert_header(auth.clone())
+            .set_json(json!({ "fee_category_id": others_food.id }))
+            .to_request();
+        assert_eq!(status(req).await, 422);
+    }
+
+    #[actix_web::test]
+    async fn test_convert_at_rate_closest_to_date() {
+        let pool = testing::pool().await;
+        let march = "2025-03-10T12:00:00Z".parse::<chrono::DateTime<Utc>>().unwrap();
+        testing::rate(&pool, "USD", "EUR", 0.9, march).await;
+        testing::rate(&pool, "USD", "EUR", 0.5, march + chrono::Duration::days(30)).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let convert = |date: &str| {
+            test::TestRequest::get()
+                .uri(&format!("/exchange-rates/convert?from_currency=USD&to_currency=EUR&amount=100{}", date))
+                .to_request()
+        };
+        let resp: Value = test::call_and_read_body_json(&app, convert("")).await;
+        assert_eq!(resp["data"]["converted_amount"], 50.0);
+        // Closest quote, even when it's after the day
+        let resp: Value = test::call_and_read_body_json(&app, convert("&date=2025-03-01")).await;
+        assert_eq!(resp["data"]["converted_amount"], 90.0);
+        let resp: Value = test::call_and_read_body_json(&app, convert("&date=2025-04-01")).await;
+        assert_eq!(resp["data"]["converted_amount"], 50.0);
+        assert_eq!(test::call_service(&app, convert("&date=March")).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_rate_storage_stats() {
+        let pool = testing::pool().await;
+        let march = "2025-03-10T12:00:00Z".parse::<chrono::DateTime<Utc>>().unwrap();
+        testing::rate(&pool, "USD", "EUR", 0.9, march).await;
+        testing::rate(&pool, "USD", "EUR", 0.8, march + chrono::Duration::hours(1)).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/exchange-rates/storage-stats").to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["rates"], 2);
+        assert_eq!(resp["data"]["archived_rates"], 0);
+        assert_eq!(resp["data"]["compactable_rates"], 1);
+    }
+
+    #[actix_web::test]
+    async fn test_settings_default_account_must_be_own() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let other = testing::user().username("other").insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let others_account = testing::account(other.id).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let put = |body: Value| {
+            test::TestRequest::put()
+                .uri(&format!("/users/{}/settings", user.id))
+                .set_json(body)
+                .to_request()
+        };
+        let resp = test::call_service(&app, put(json!({ "default_account_id": others_account.id }))).await;
+        assert_eq!(resp.status(), 422);
+        let resp = test::call_service(&app, put(json!({ "week_start": "someday" }))).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = put(json!({ "default_account_id": account.id, "date_format": "us", "week_start": "Sunday" }));
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["default_account_id"], account.id);
+        assert_eq!(resp["data"]["date_format"], "us");
+        assert_eq!(resp["data"]["week_start"], "sunday");
+    }
+
+    #[actix_web::test]
+    async fn test_budget_variance_projects_month_end_spend() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        budgets::set_budget(&pool, user.id, groceries.id, 600.0).await.unwrap();
+        testing::txn(account.id).amount(240.0).category(groceries.id).insert(&pool).await;
+        let march = "2025-03-10T12:00:00Z".parse::<chrono::DateTime<Utc>>().unwrap();
+        testing::txn(account.id).amount(90.0).category(groceries.id).date(march).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/reports/budget-variance?user_id={}", user.id))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let row = &resp["data"][0];
+        assert_eq!(row["actual"], 240.0);
+        assert!(row["projected"].as_f64().unwrap() >= 240.0);
+        assert!(row["projected_status"].is_string());
+
+        // A finished month projects what was spent
+        let req = test::TestRequest::get()
+            .uri(&format!("/reports/budget-variance?user_id={}&period=2025-03", user.id))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"][0]["projected"], 90.0);
+        assert_eq!(resp["data"][0]["projected_status"], "under");
+    }
+
+    #[actix_web::test]
+    async fn test_goal_crud_checks_linked_accounts() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let savings = testing::account(user.id).name("Savings").balance(400.0).insert(&pool).await;
+        let euros = testing::account(user.id).name("Euro").currency("EUR").insert(&pool).await;
+        let bearer = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/goals")
+            .insert_header(bearer.clone())
+            .set_json(json!({ "user_id": user.id, "name": "Trip", "target_amount": 1000.0,
+                              "account_ids": [savings.id, euros.id] }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+        let req = test::TestRequest::post()
+            .uri("/goals")
+            .insert_header(bearer.clone())
+            .set_json(json!({ "user_id": user.id, "name": "Trip", "target_amount": 1000.0,
+                              "target_date": "2030-06-01", "account_ids": [savings.id] }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let goal = &resp["data"];
+        assert_eq!((goal["currency"].as_str(), goal["saved"].as_f64()), (Some("USD"), Some(400.0)));
+        assert_eq!(goal["percent"], 40.0);
+        assert_eq!(goal["on_track"], false);
+        let id = goal["id"].as_i64().unwrap();
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/goals/{}", id))
+            .insert_header(bearer.clone())
+            .set_json(json!({ "target_amount": 500.0 }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["remaining"], 100.0);
+        assert_eq!(resp["data"]["account_ids"], json!([savings.id]));
+
+        let req = test::TestRequest::get().uri("/goals").insert_header(bearer.clone()).to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"].as_array().unwrap().len(), 1);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/goals/{}", id))
+            .insert_header(bearer)
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+    }
+
+    #[actix_web::test]
+    async fn test_loan_terms_and_amortization_schedule() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).insert(&pool).await;
+        let mortgage = testing::account(user.id).name("Mortgage").account_type("loan").insert(&pool).await;
+        let stranger = testing::user().insert(&pool).await;
+        let theirs = testing::account(stranger.id).insert(&pool).await;
+        let bearer = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let amortization = |id: i64| {
+            test::TestRequest::get()
+                .uri(&format!("/accounts/{}/amortization", id))
+                .insert_header(bearer.clone())
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, amortization(mortgage.id)).await.status(), 404);
+
+        let terms = |id: i64, payment_account_id: i64| {
+            test::TestRequest::post()
+                .uri(&format!("/accounts/{}/loan", id))
+                .insert_header(bearer.clone())
+                .set_json(json!({ "principal": 2400.0, "annual_rate": 0.0, "term_months": 24,
+                                  "first_payment_date": "2026-01-15", "payment_account_id": payment_account_id }))
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, terms(mortgage.id, theirs.id)).await.status(), 422);
+        assert_eq!(test::call_service(&app, terms(checking.id, mortgage.id)).await.status(), 422);
+        let resp = test::call_service(&app, terms(mortgage.id, checking.id)).await;
+        assert_eq!(resp.status(), 201);
+        assert_eq!(test::call_service(&app, terms(mortgage.id, checking.id)).await.status(), 409);
+
+        let resp: Value = test::call_and_read_body_json(&app, amortization(mortgage.id)).await;
+        let plan = &resp["data"];
+        assert_eq!((plan["monthly_payment"].as_f64(), plan["total_interest"].as_f64()), (Some(100.0), Some(0.0)));
+        assert_eq!(plan["schedule"].as_array().unwrap().len(), 24);
+        assert_eq!(plan["schedule"][23]["date"], "2027-12-15");
+        assert_eq!(plan["schedule"][23]["balance"], 0.0);
+
+        let recurring_id = plan["loan"]["recurring_transaction_id"].as_i64().unwrap();
+        let recurring = sqlx::query_as::<_, RecurringTransaction>("SELECT * FROM recurring_transactions WHERE id = ?")
+            .bind(recurring_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((recurring.account_id, recurring.amount), (checking.id, 100.0));
+        assert_eq!(recurring.end_date, "2027-12-15T00:00:00Z".parse::<chrono::DateTime<Utc>>().ok());
+        let owed: f64 = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(mortgage.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(owed, -2400.0);
+    }
+
+    #[actix_web::test]
+    async fn test_holdings_of_investment_account() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).insert(&pool).await;
+        let brokerage = testing::account(user.id).name("Brokerage").account_type("investment").insert(&pool).await;
+        let stranger = testing::user().insert(&pool).await;
+        let stranger_bearer = bearer(stranger.id);
+        let bearer = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let add = |account_id: i64, body: Value| {
+            test::TestRequest::post()
+                .uri(&format!("/accounts/{}/holdings", account_id))
+                .insert_header(bearer.clone())
+                .set_json(body)
+                .to_request()
+        };
+        let msft = json!({ "ticker": "msft", "quantity": 5.0, "cost_basis": 1500.0 });
+        assert_eq!(test::call_service(&app, add(checking.id, msft.clone())).await.status(), 422);
+        let bad = json!({ "ticker": "MS FT", "quantity": 5.0 });
+        assert_eq!(test::call_service(&app, add(brokerage.id, bad)).await.status(), 400);
+        let resp: Value = test::call_and_read_body_json(&app, add(brokerage.id, msft.clone())).await;
+        assert_eq!(resp["data"]["ticker"], "MSFT");
+        let id = resp["data"]["id"].as_i64().unwrap();
+        assert_eq!(test::call_service(&app, add(brokerage.id, msft)).await.status(), 409);
+        let bond = json!({ "ticker": "BND", "quantity": 10.0, "cost_basis": 500.0 });
+        assert_eq!(test::call_service(&app, add(brokerage.id, bond)).await.status(), 201);
+        holdings::save_price(&pool, "MSFT", 400.0, NaiveDate::from_ymd_opt(2025, 6, 3).unwrap(), "stooq.com")
+            .await
+            .unwrap();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/accounts/{}/holdings", brokerage.id))
+            .insert_header(bearer.clone())
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        let valued = &resp["data"];
+        assert_eq!((valued["market_value"].as_f64(), valued["gain"].as_f64()), (Some(2500.0), Some(500.0)));
+        assert_eq!(valued["holdings"][0]["ticker"], "MSFT");
+        assert_eq!(valued["holdings"][0]["allocation"], 80.0);
+        assert_eq!(valued["holdings"][1]["price"], Value::Null);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/holdings/{}", id))
+            .insert_header(bearer.clone())
+            .set_json(json!({ "quantity": 6.0 }))
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!((resp["data"]["quantity"].as_f64(), resp["data"]["cost_basis"].as_f64()), (Some(6.0), Some(1500.0)));
+
+        // Someone else's holdings are off limits
+        let req = test::TestRequest::delete()
+            .uri(&format!("/holdings/{}", id))
+            .insert_header(stranger_bearer)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 403);
+        let req = test::TestRequest::delete()
+            .uri(&format!("/holdings/{}", id))
+            .insert_header(bearer)
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+    }
+
+    #[actix_web::test]
+    async fn test_export_transactions_as_json_lines() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let other = testing::user().username("other").insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let theirs = testing::account(other.id).insert(&pool).await;
+        let yesterday = Utc::now() - chrono::Duration::days(1);
+        testing::txn(account.id).amount(12.5).date(yesterday - chrono::Duration::days(1)).insert(&pool).await;
+        testing::txn(account.id).amount(3000.0).income().date(yesterday).insert(&pool).await;
+        testing::txn(theirs.id).amount(5.0).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/export/transactions/jsonl?user_id={}", user.id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "application/x-ndjson");
+        let body = test::read_body(resp).await;
+        let lines: Vec<Value> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        // Newest first, one object per line
+        let amounts: Vec<f64> = lines.iter().map(|t| t["amount"].as_f64().unwrap()).collect();
+        assert_eq!(amounts, vec![3000.0, 12.5]);
+        assert!(body.ends_with(b"\n"));
+    }
+
+    #[actix_web::test]
+    async fn test_cash_flow_forecast() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).name("Everyday").balance(500.0).insert(&pool).await;
+        testing::account(user.id).name("Old").insert(&pool).await;
+        sqlx::query("UPDATE accounts SET is_archived = 1 WHERE name = 'Old'").execute(&pool).await.unwrap();
+        // Mid-month, the second month from now
+        let payday = forecast::end_of_month(Utc::now()) + chrono::Months::new(1) + chrono::Duration::days(14);
+        testing::recurring(account.id).amount(2000.0).income().due(payday).insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let get = |uri: String| test::TestRequest::get().uri(&uri).to_request();
+        let resp: Value =
+            test::call_and_read_body_json(&app, get(format!("/analytics/forecast?user_id={}&months=3", user.id))).await;
+        let accounts = resp["data"]["accounts"].as_array().unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0]["account_name"], "Everyday");
+        let months = accounts[0]["months"].as_array().unwrap();
+        assert_eq!(months.len(), 3);
+        // The salary lands in the second month and carries into the third
+        let balances: Vec<f64> = months.iter().map(|m| m["ending_balance"].as_f64().unwrap()).collect();
+        assert_eq!(balances, vec![500.0, 2500.0, 4500.0]);
+
+        let resp = test::call_service(&app, get(format!("/analytics/forecast?user_id={}&months=0", user.id))).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_update_transaction_reposts_balance() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let lunch = testing::txn(account.id).amount(20.0).insert(&pool).await;
+        let draft = testing::txn(account.id).amount(50.0).pending().insert(&pool).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+        let balance = || async {
+            sqlx::query_scalar::<_, f64>("SELECT current_balance FROM accounts WHERE id = ?")
+                .bind(account.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+        let update = |id: i64, body: Value| {
+            test::TestRequest::put()
+                .uri(&format!("/transactions/{}", id))
+                .insert_header(auth.clone())
+                .set_json(body)
+                .to_request()
+        };
+
+        // A fee added to a cleared expense comes off the balance on top of it
+        let resp: Value = test::call_and_read_body_json(&app, update(lunch.id, json!({ "fee": 1.5 }))).await;
+        assert_eq!(resp["data"]["fee"], 1.5);
+        assert_eq!(balance().await, 98.5);
+        let req = update(lunch.id, json!({ "amount": 25.0, "description": "Lunch o'clock" }));
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["description"], "Lunch o'clock");
+        assert_eq!(balance().await, 93.5);
+        // Turning it into income swaps -26.5 for +23.5
+        test::call_service(&app, update(lunch.id, json!({ "transaction_type": "income" }))).await;
+        assert_eq!(balance().await, 143.5);
+
+        // Pending transactions have not posted, so editing them leaves it alone
+        test::call_service(&app, update(draft.id, json!({ "fee": 3.0 }))).await;
+        assert_eq!(balance().await, 143.5);
+
+        assert_eq!(test::call_service(&app, update(lunch.id, json!({}))).await.status(), 400);
+        let req = update(lunch.id, json!({ "transaction_type": "gift" }));
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+        assert_eq!(test::call_service(&app, update(9999, json!({ "fee": 1.0 }))).await.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_update_transaction_date_splits_and_cleared_fields() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).currency("USD").insert(&pool).await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let txn = testing::txn(account.id).amount(40.0).description("Dinner").category(dining.id).insert(&pool).await;
+        settings::set(&pool, user.id, settings::BASE_CURRENCY, Some("EUR")).await.unwrap();
+        testing::rate(&pool, "USD", "EUR", 0.5, "2025-01-01T00:00:00Z".parse().unwrap()).await;
+        let auth = bearer(user.id);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .wrap(actix_web::middleware::from_fn(require_session))
+                .configure(configure_routes),
+        )
+        .await;
+        let update = |body: Value| {
+            test::TestRequest::put()
+                .uri(&format!("/transactions/{}", txn.id))
+                .insert_header(auth.clone())
+                .set_json(body)
+                .to_request()
+        };
+
+        let req = update(json!({ "transaction_date": "2025-03-01T12:00:00Z" }));
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["data"]["transaction_date"], "2025-03-01T12:00:00Z");
+        assert_eq!((resp["data"]["base_amount"].as_f64(), resp["data"]["base_currency"].as_str()), (Some(20.0), Some("EUR")));
+
+        // The 40.00 split would no longer add up, unless it's replaced too
+        assert_eq!(test::call_service(&app, update(json!({ "amount": 50.0 }))).await.status(), 422);
+        let req = update(json!({
+            "amount": 50.0,
+            "categories": [{ "category_id": dining.id, "amount": 30.0 }, { "category_id": groceries.id, "amount": 20.0 }]
+        }));
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!((resp["data"]["amount"].as_f64(), resp["data"]["base_amount"].as_f64()), (Some(50.0), Some(25.0)));
+        let splits: Vec<(i64, f64)> = sqlx::query_as(
+            "SELECT category_id, amount FROM transaction_categories WHERE transaction_id = ? ORDER BY amount DESC",
+        )
+        .bind(txn.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(splits, [(dining.id, 30.0), (groceries.id, 20.0)]);
+
+        // Empty text and fee category 0 clear the field; absent ones are kept
+        let req = update(json!({ "fee": 1.0, "fee_category_id": groceries.id }));
+        test::call_service(&app, req).await;
+        let req = update(json!({ "description": "", "fee_category_id": 0 }));
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert!(resp["data"]["description"].is_null());
+        assert!(resp["data"]["fee_category_id"].is_null());
+        assert_eq!(resp["data"]["fee"], 1.0);
+    }
+
+    #[actix_web::test]
+    async fn test_encrypted_export_is_an_aes_zip() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        testing::txn(account.id).amount(42.0).description("Groceries").insert(&pool).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .configure(configure_routes),
+        )
+        .await;
+        let uri = format!("/export/transactions/csv?user_id={}&encrypt=true", user.id);
+
+        let req = test::TestRequest::get().uri(&uri).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+        let req = test::TestRequest::get()
+            .uri(&uri)
+            .insert_header((EXPORT_PASSPHRASE_HEADER, "correct horse"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "application/zip");
+        assert!(resp.headers().get("content-disposition").unwrap().to_str().unwrap().contains("transactions.csv.zip"));
+        let body = test::read_body(resp).await;
+        assert!(!body.windows(9).any(|w| w == b"Groceries"));
+        let (name, csv) = encryption::decrypt(&body, "correct horse").unwrap();
+        assert_eq!(name, "transactions.csv");
+        assert!(String::from_utf8(csv).unwrap().contains("Groceries"));
+    }
 }
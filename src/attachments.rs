@@ -0,0 +1,293 @@
+// attachments.rs
+// Receipt images and PDFs attached to transactions. Uploads arrive as
+// multipart/form-data; each file is written under ATTACHMENT_DIR (default
+// "attachments") as <transaction id>/<attachment id>.<ext>, with its original
+// name and type kept in the attachments table.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::Attachment;
+
+type AttachmentError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Types accepted besides image/*
+const DOCUMENT_TYPES: [&str; 1] = ["application/pdf"];
+
+/// A file part of a multipart upload
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadedFile {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Directory the files are stored in, from ATTACHMENT_DIR
+pub fn storage_dir() -> PathBuf {
+    env::var("ATTACHMENT_DIR")
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("attachments"))
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|i| i + from)
+}
+
+/// Value of `key="value"` (or unquoted) in a header such as Content-Disposition
+fn header_param(header: &str, key: &str) -> Option<String> {
+    header.split(';').find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        (name.trim().eq_ignore_ascii_case(key)).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// The file parts of a multipart/form-data body; other form fields are skipped
+pub fn parse_multipart(content_type: &str, body: &[u8]) -> Result<Vec<UploadedFile>, String> {
+    if !content_type.trim().to_ascii_lowercase().starts_with("multipart/form-data") {
+        return Err("Upload must be multipart/form-data".to_string());
+    }
+    let boundary = header_param(content_type, "boundary")
+        .filter(|b| !b.is_empty())
+        .ok_or("multipart/form-data upload has no boundary")?;
+    let delimiter = format!("--{}", boundary).into_bytes();
+
+    let mut files = Vec::new();
+    let mut start = find(body, &delimiter, 0).ok_or("Upload has no parts")? + delimiter.len();
+    // Each part runs from after a delimiter line to the CRLF before the next one
+    while !body[start..].starts_with(b"--") {
+        let headers_start = start + 2;
+        let headers_end = find(body, b"\r\n\r\n", headers_start).ok_or("Malformed part headers")?;
+        let next = find(body, &delimiter, headers_end).ok_or("Upload ends in the middle of a part")?;
+        let data = &body[headers_end + 4..next.saturating_sub(2).max(headers_end + 4)];
+
+        let headers = String::from_utf8_lossy(&body[headers_start..headers_end]);
+        let mut disposition = None;
+        let mut part_type = None;
+        for line in headers.lines() {
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-disposition") {
+                    disposition = Some(value.to_string());
+                } else if name.trim().eq_ignore_ascii_case("content-type") {
+                    part_type = Some(value.trim().to_ascii_lowercase());
+                }
+            }
+        }
+        if let Some(filename) = disposition.as_deref().and_then(|d| header_param(d, "filename")) {
+            files.push(UploadedFile {
+                content_type: part_type.unwrap_or_else(|| guess_content_type(&filename).to_string()),
+                filename,
+                data: data.to_vec(),
+            });
+        }
+        start = next + delimiter.len();
+    }
+    Ok(files)
+}
+
+fn guess_content_type(filename: &str) -> &'static str {
+    match extension(filename).as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+fn extension(filename: &str) -> String {
+    Path::new(filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .filter(|e| e.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_default()
+}
+
+/// Why the file can't be attached, if anything
+pub fn validate_upload(file: &UploadedFile) -> Result<(), String> {
+    if file.data.is_empty() {
+        return Err(format!("'{}' is empty", file.filename));
+    }
+    if !file.content_type.starts_with("image/") && !DOCUMENT_TYPES.contains(&file.content_type.as_str()) {
+        return Err(format!(
+            "'{}' is {} (expected an image or a PDF)",
+            file.filename, file.content_type
+        ));
+    }
+    Ok(())
+}
+
+/// The uploaded name without any directory part or characters that would
+/// break a Content-Disposition header
+fn clean_filename(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = name.chars().filter(|c| !c.is_control() && *c != '"').collect();
+    if name.trim().is_empty() {
+        "attachment".to_string()
+    } else {
+        name.trim().to_string()
+    }
+}
+
+/// Write the file under `dir` and record it. Call validate_upload first.
+pub async fn save_attachment(
+    pool: &SqlitePool,
+    dir: &Path,
+    transaction_id: i64,
+    file: &UploadedFile,
+) -> Result<Attachment, AttachmentError> {
+    let mut tx = pool.begin().await?;
+    let id = sqlx::query(
+        "INSERT INTO attachments (transaction_id, filename, content_type, size_bytes, storage_path) VALUES (?, ?, ?, ?, '')",
+    )
+    .bind(transaction_id)
+    .bind(clean_filename(&file.filename))
+    .bind(&file.content_type)
+    .bind(file.data.len() as i64)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    let storage_path = match extension(&file.filename).as_str() {
+        "" => format!("{}/{}", transaction_id, id),
+        ext => format!("{}/{}.{}", transaction_id, id, ext),
+    };
+    let path = dir.join(&storage_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, &file.data)?;
+
+    let attachment = sqlx::query_as::<_, Attachment>("UPDATE attachments SET storage_path = ? WHERE id = ? RETURNING *")
+        .bind(&storage_path)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if let Err(e) = tx.commit().await {
+        let _ = fs::remove_file(&path);
+        return Err(e.into());
+    }
+    Ok(attachment)
+}
+
+pub async fn attachments_for_transaction(pool: &SqlitePool, transaction_id: i64) -> Result<Vec<Attachment>, sqlx::Error> {
+    sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE transaction_id = ? ORDER BY id")
+        .bind(transaction_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Number of attachments of each of the user's transactions that has any
+pub async fn attachment_counts(pool: &SqlitePool, user_id: i64) -> Result<HashMap<i64, i64>, sqlx::Error> {
+    let rows: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT f.transaction_id, COUNT(*) FROM attachments f
+         JOIN transactions t ON t.id = f.transaction_id
+         JOIN accounts a ON a.id = t.account_id
+         WHERE a.user_id = ?
+         GROUP BY f.transaction_id",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+pub fn read_attachment(dir: &Path, attachment: &Attachment) -> std::io::Result<Vec<u8>> {
+    fs::read(dir.join(&attachment.storage_path))
+}
+
+/// Files of the transaction's attachments, to remove once the transaction is
+/// deleted (the rows go with it)
+pub async fn stored_files(pool: &SqlitePool, dir: &Path, transaction_id: i64) -> Result<Vec<PathBuf>, sqlx::Error> {
+    let paths: Vec<String> = sqlx::query_scalar("SELECT storage_path FROM attachments WHERE transaction_id = ?")
+        .bind(transaction_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(paths.into_iter().map(|p| dir.join(p)).collect())
+}
+
+/// Remove the attachment's row and its file
+pub async fn delete_attachment(pool: &SqlitePool, dir: &Path, attachment: &Attachment) -> Result<(), AttachmentError> {
+    sqlx::query("DELETE FROM attachments WHERE id = ?")
+        .bind(attachment.id)
+        .execute(pool)
+        .await?;
+    match fs::remove_file(dir.join(&attachment.storage_path)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn multipart(parts: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, filename, data) in parts {
+            body.extend_from_slice(b"--XyZ\r\n");
+            match filename {
+                Some(filename) => body.extend_from_slice(
+                    format!("Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\r\n", name, filename).as_bytes(),
+                ),
+                None => body.extend_from_slice(format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", name).as_bytes()),
+            }
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(b"--XyZ--\r\n");
+        body
+    }
+
+    #[test]
+    fn test_parse_multipart_keeps_file_parts() {
+        let body = multipart(&[
+            ("note", None, b"lunch"),
+            ("file", Some("receipt.JPG"), b"\xff\xd8\r\n--not-a-boundary\xff\xd9"),
+            ("file", Some("../../invoice.pdf"), b"%PDF-1.4"),
+        ]);
+        let files = parse_multipart("multipart/form-data; boundary=XyZ", &body).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!((files[0].content_type.as_str(), files[0].data.as_slice()), ("image/jpeg", &b"\xff\xd8\r\n--not-a-boundary\xff\xd9"[..]));
+        assert_eq!(files[1].content_type, "application/pdf");
+        assert_eq!(clean_filename(&files[1].filename), "invoice.pdf");
+
+        assert!(parse_multipart("application/json", &body).is_err());
+        let text = UploadedFile { filename: "notes.txt".into(), content_type: "text/plain".into(), data: b"hi".to_vec() };
+        assert!(validate_upload(&text).is_err());
+        assert!(validate_upload(&files[0]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_attachments_are_stored_and_removed() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let transaction = testing::txn(account.id).insert(&pool).await;
+        let dir = env::temp_dir().join(format!("pft-attachments-{}-{}", std::process::id(), transaction.id));
+
+        let file = UploadedFile { filename: "receipt.png".into(), content_type: "image/png".into(), data: b"\x89PNG".to_vec() };
+        let attachment = save_attachment(&pool, &dir, transaction.id, &file).await.unwrap();
+        assert_eq!((attachment.size_bytes, attachment.storage_path.as_str()), (4, format!("{}/{}.png", transaction.id, attachment.id).as_str()));
+        assert_eq!(read_attachment(&dir, &attachment).unwrap(), b"\x89PNG");
+        assert_eq!(attachment_counts(&pool, user.id).await.unwrap()[&transaction.id], 1);
+        assert_eq!(stored_files(&pool, &dir, transaction.id).await.unwrap(), vec![dir.join(&attachment.storage_path)]);
+
+        delete_attachment(&pool, &dir, &attachment).await.unwrap();
+        assert!(attachments_for_transaction(&pool, transaction.id).await.unwrap().is_empty());
+        assert!(read_attachment(&dir, &attachment).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,180 @@
+// auth.rs
+// Password hashing (argon2id) and login sessions (HS256 JWTs)
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::env;
+use std::sync::OnceLock;
+
+use crate::models::User;
+
+/// Password given to seeded users and to users added from the TUI
+pub const DEFAULT_PASSWORD: &str = "password123";
+
+/// How long a session token from POST /auth/login stays valid
+pub const SESSION_DAYS: i64 = 30;
+
+/// Whether the TUI asks for the user's password on login (TUI_REQUIRE_PASSWORD=true)
+pub fn tui_requires_password() -> bool {
+    env::var("TUI_REQUIRE_PASSWORD")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "System random number generator failed".to_string())?;
+    Ok(bytes)
+}
+
+/// PHC-format argon2id hash with a random salt
+pub fn hash_password(password: &str) -> Result<String, String> {
+    let salt = SaltString::encode_b64(&random_bytes::<16>()?)
+        .map_err(|e| format!("Failed to encode salt: {}", e))?;
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash password: {}", e))
+}
+
+/// False for a wrong password or a hash that doesn't parse (such as the
+/// placeholders stored before passwords were hashed).
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
+/// Key that signs session tokens: JWT_SECRET, else a random one for this
+/// process, so sessions end when the server restarts
+fn jwt_key() -> &'static hmac::Key {
+    static KEY: OnceLock<hmac::Key> = OnceLock::new();
+    KEY.get_or_init(|| match env::var("JWT_SECRET") {
+        Ok(secret) if !secret.trim().is_empty() => hmac::Key::new(hmac::HMAC_SHA256, secret.trim().as_bytes()),
+        _ => hmac::Key::generate(hmac::HMAC_SHA256, &SystemRandom::new())
+            .expect("System random number generator failed"),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    typ: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    iat: i64,
+    exp: i64,
+}
+
+fn sign_token(key: &hmac::Key, user_id: i64, issued_at: DateTime<Utc>, expires_at: DateTime<Utc>) -> String {
+    let header = JwtHeader { alg: "HS256".to_string(), typ: Some("JWT".to_string()) };
+    let claims = Claims { sub: user_id.to_string(), iat: issued_at.timestamp(), exp: expires_at.timestamp() };
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header).unwrap_or_default()),
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap_or_default())
+    );
+    let signature = hmac::sign(key, signing_input.as_bytes());
+    format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature.as_ref()))
+}
+
+/// User id of a token signed with `key` that is HS256 and unexpired at `now`
+fn verify_token(key: &hmac::Key, token: &str, now: DateTime<Utc>) -> Option<i64> {
+    let (signing_input, signature) = token.rsplit_once('.')?;
+    let (header, claims) = signing_input.split_once('.')?;
+    hmac::verify(key, signing_input.as_bytes(), &URL_SAFE_NO_PAD.decode(signature).ok()?).ok()?;
+
+    let header: JwtHeader = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+    let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).ok()?).ok()?;
+    if header.alg != "HS256" || claims.exp <= now.timestamp() {
+        return None;
+    }
+    claims.sub.parse().ok()
+}
+
+/// The user with this username (or email) and password
+pub async fn authenticate(
+    pool: &SqlitePool,
+    username: &str,
+    password: &str,
+) -> Result<Option<User>, sqlx::Error> {
+    let user = sqlx::query_as::<_, User>(
+        "SELECT id, username, email, password_hash, created_at, updated_at FROM users WHERE username = ? OR email = ?",
+    )
+    .bind(username)
+    .bind(username)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(user.filter(|u| verify_password(password, &u.password_hash)))
+}
+
+/// Issue a session token for the user: a JWT signed with jwt_key
+pub fn create_session(user_id: i64) -> (String, DateTime<Utc>) {
+    let now = Utc::now();
+    let expires_at = now + Duration::days(SESSION_DAYS);
+    (sign_token(jwt_key(), user_id, now, expires_at), expires_at)
+}
+
+/// User of a valid, unexpired session token
+pub async fn session_user(pool: &SqlitePool, token: &str) -> Result<Option<User>, sqlx::Error> {
+    let Some(user_id) = verify_token(jwt_key(), token, Utc::now()) else {
+        return Ok(None);
+    };
+    sqlx::query_as::<_, User>(
+        "SELECT id, username, email, password_hash, created_at, updated_at FROM users WHERE id = ?",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_password() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert_ne!(hash, hash_password("correct horse").unwrap()); // random salt
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("wrong horse", &hash));
+        assert!(!verify_password("password123", "$argon2id$v=19$m=19456,t=2,p=1$defaulthash"));
+    }
+
+    #[test]
+    fn test_session_tokens_are_signed_jwts() {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"test secret");
+        let now = Utc::now();
+        let token = sign_token(&key, 7, now, now + Duration::days(1));
+        assert_eq!(token.split('.').count(), 3);
+        assert_eq!(verify_token(&key, &token, now), Some(7));
+
+        // Expired, signed with another key, or with the claims swapped out
+        assert_eq!(verify_token(&key, &token, now + Duration::days(2)), None);
+        assert_eq!(verify_token(&hmac::Key::new(hmac::HMAC_SHA256, b"other"), &token, now), None);
+        let forged = sign_token(&key, 8, now, now + Duration::days(1));
+        let (header, rest) = token.split_once('.').unwrap();
+        let (_, signature) = rest.split_once('.').unwrap();
+        let claims = forged.split('.').nth(1).unwrap();
+        assert_eq!(verify_token(&key, &format!("{}.{}.{}", header, claims, signature), now), None);
+
+        // An unsigned "alg": "none" token is refused
+        let none = URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#);
+        assert_eq!(verify_token(&key, &format!("{}.{}.", none, claims), now), None);
+        assert_eq!(verify_token(&key, "not a token", now), None);
+    }
+}
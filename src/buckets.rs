@@ -0,0 +1,158 @@
+// buckets.rs
+// 50/30/20-style allocation: categories are tagged needs, wants or savings and
+// a period's spending split is compared with the user's target shares
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::charts;
+use crate::models::{BucketAllocation, BucketSplit};
+use crate::settings;
+
+pub const BUCKETS: [&str; 3] = ["needs", "wants", "savings"];
+
+/// Used when the bucket_targets setting is unset
+pub const DEFAULT_TARGETS: [f64; 3] = [50.0, 30.0, 20.0];
+
+pub fn validate_bucket(bucket: &str) -> Result<(), String> {
+    if BUCKETS.contains(&bucket) {
+        Ok(())
+    } else {
+        Err(format!("Unknown bucket '{}' (expected needs, wants or savings)", bucket))
+    }
+}
+
+/// Parse "needs/wants/savings" percentages such as "50/30/20" (commas work too).
+/// They must add up to 100.
+pub fn parse_targets(spec: &str) -> Result<[f64; 3], String> {
+    let parts: Vec<f64> = spec
+        .split(['/', ','])
+        .map(|p| p.trim().trim_end_matches('%').parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid bucket targets '{}' (expected e.g. 50/30/20)", spec))?;
+    let [needs, wants, savings] = parts[..] else {
+        return Err("Bucket targets need three shares: needs/wants/savings".to_string());
+    };
+    if parts.iter().any(|p| *p < 0.0) || (needs + wants + savings - 100.0).abs() > 0.01 {
+        return Err("Bucket targets must be non-negative and add up to 100".to_string());
+    }
+    Ok([needs, wants, savings])
+}
+
+/// The user's bucket_targets setting; invalid or missing values fall back to 50/30/20.
+pub async fn target_allocation(pool: &SqlitePool, user_id: i64) -> Result<[f64; 3], sqlx::Error> {
+    let targets = settings::user_settings(pool, user_id).await?.bucket_targets;
+    Ok(targets
+        .and_then(|t| parse_targets(&t).ok())
+        .unwrap_or(DEFAULT_TARGETS))
+}
+
+/// Split (bucket, amount) spending rows into buckets; rows without a bucket
+/// count as unassigned and are left out of the shares.
+pub fn allocate(period: &str, spending: &[(Option<String>, f64)], targets: [f64; 3]) -> BucketSplit {
+    let mut amounts = [0.0; 3];
+    let mut unassigned = 0.0;
+    for (bucket, amount) in spending {
+        match bucket.as_deref().and_then(|b| BUCKETS.iter().position(|x| *x == b)) {
+            Some(i) => amounts[i] += amount,
+            None => unassigned += amount,
+        }
+    }
+
+    let total: f64 = amounts.iter().sum();
+    let buckets = BUCKETS
+        .iter()
+        .zip(amounts.iter().zip(targets))
+        .map(|(bucket, (amount, target_percent))| {
+            let percent = if total > 0.0 { amount / total * 100.0 } else { 0.0 };
+            BucketAllocation {
+                bucket: bucket.to_string(),
+                amount: *amount,
+                percent,
+                target_percent,
+                difference: percent - target_percent,
+            }
+        })
+        .collect();
+
+    BucketSplit {
+        period: period.to_string(),
+        buckets,
+        total,
+        unassigned,
+    }
+}
+
+/// Expense splits and fees of the user in [start, end), split by bucket.
+pub async fn bucket_split(
+    pool: &SqlitePool,
+    user_id: i64,
+    period: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    targets: [f64; 3],
+) -> Result<BucketSplit, sqlx::Error> {
+    let buckets: HashMap<i64, String> =
+        sqlx::query_as::<_, (i64, String)>("SELECT id, bucket FROM categories WHERE user_id = ? AND bucket IS NOT NULL")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    let spending: Vec<(Option<String>, f64)> = charts::category_spending(pool, user_id, start, end)
+        .await?
+        .into_iter()
+        .map(|s| (buckets.get(&s.category_id).cloned(), s.total_amount))
+        .collect();
+
+    Ok(allocate(period, &spending, targets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budgets;
+    use crate::testing;
+
+    #[test]
+    fn test_parse_targets() {
+        assert_eq!(parse_targets("50/30/20").unwrap(), [50.0, 30.0, 20.0]);
+        assert_eq!(parse_targets(" 60%, 25%, 15% ").unwrap(), [60.0, 25.0, 15.0]);
+        assert!(parse_targets("50/30").is_err());
+        assert!(parse_targets("50/30/30").is_err());
+        assert!(parse_targets("needs").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bucket_split_compares_spending_with_targets() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let rent = testing::category(&pool, user.id, "Rent").await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        let misc = testing::category(&pool, user.id, "Misc").await;
+        for (category, bucket) in [(rent.id, "needs"), (dining.id, "wants")] {
+            sqlx::query("UPDATE categories SET bucket = ? WHERE id = ?")
+                .bind(bucket)
+                .bind(category)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        testing::txn(account.id).amount(600.0).category(rent.id).insert(&pool).await;
+        testing::txn(account.id).amount(400.0).category(dining.id).insert(&pool).await;
+        testing::txn(account.id).amount(50.0).category(misc.id).insert(&pool).await;
+
+        let period = Utc::now().format("%Y-%m").to_string();
+        let (start, end) = budgets::parse_period(&period).unwrap();
+        let targets = target_allocation(&pool, user.id).await.unwrap();
+        let split = bucket_split(&pool, user.id, &period, start, end, targets).await.unwrap();
+
+        assert_eq!((split.total, split.unassigned), (1000.0, 50.0));
+        let needs = &split.buckets[0];
+        assert_eq!((needs.percent, needs.target_percent, needs.difference), (60.0, 50.0, 10.0));
+        assert_eq!(split.buckets[2].amount, 0.0);
+    }
+}
@@ -0,0 +1,322 @@
+// budgets.rs
+// Budget vs. actual variance per category for a calendar month
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::exclusions;
+use crate::models::{Budget, BudgetVariance};
+use crate::reimbursements;
+
+/// Share of the budget at which a category is flagged as "near" its limit
+const NEAR_THRESHOLD: f64 = 0.9;
+
+#[derive(FromRow)]
+struct BudgetActual {
+    budget_id: i64,
+    category_id: i64,
+    category_name: String,
+    monthly_limit: f64,
+    actual: f64,
+}
+
+/// How a month's budgets held up overall
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetAdherence {
+    pub under: usize,
+    pub near: usize,
+    pub over: usize,
+    pub total_budgeted: f64,
+    pub total_actual: f64,
+}
+
+impl BudgetAdherence {
+    /// Share of budgets kept (not over the limit), in percent
+    pub fn kept_percent(&self) -> f64 {
+        let budgets = self.under + self.near + self.over;
+        if budgets == 0 {
+            return 100.0;
+        }
+        (self.under + self.near) as f64 / budgets as f64 * 100.0
+    }
+}
+
+/// Parse a "YYYY-MM" period into its [start, end) range.
+pub fn parse_period(period: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = NaiveDate::parse_from_str(&format!("{}-01", period.trim()), "%Y-%m-%d").ok()?;
+    let end = start.checked_add_months(chrono::Months::new(1))?;
+    Some((
+        Utc.from_utc_datetime(&start.and_hms_opt(0, 0, 0)?),
+        Utc.from_utc_datetime(&end.and_hms_opt(0, 0, 0)?),
+    ))
+}
+
+/// Like parse_period, but also takes the "this_month", "last_month",
+/// "this_quarter", "last_quarter" and "ytd" (this year up to today)
+/// shortcuts, relative to `now`.
+pub fn resolve_period(period: &str, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let midnight = |date: NaiveDate| Utc.from_utc_datetime(&date.and_time(chrono::NaiveTime::MIN));
+    let today = now.date_naive();
+    let quarter = NaiveDate::from_ymd_opt(today.year(), (today.month() - 1) / 3 * 3 + 1, 1)?;
+    match period.trim() {
+        "this_month" => parse_period(&now.format("%Y-%m").to_string()),
+        "last_month" => {
+            let (this_month, _) = parse_period(&now.format("%Y-%m").to_string())?;
+            parse_period(&(this_month - chrono::Months::new(1)).format("%Y-%m").to_string())
+        }
+        "this_quarter" => Some((midnight(quarter), midnight(quarter.checked_add_months(chrono::Months::new(3))?))),
+        "last_quarter" => Some((midnight(quarter.checked_sub_months(chrono::Months::new(3))?), midnight(quarter))),
+        "ytd" => Some((midnight(NaiveDate::from_ymd_opt(today.year(), 1, 1)?), midnight(today.succ_opt()?))),
+        period => parse_period(period),
+    }
+}
+
+/// Parse typed "YYYY-MM-DD" start and end dates into the [start, end)
+/// range that includes both days.
+pub fn parse_date_range(start: &str, end: &str) -> Result<(DateTime<Utc>, DateTime<Utc>), &'static str> {
+    let parse = |s: &str| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok();
+    let midnight = |date: NaiveDate| Utc.from_utc_datetime(&date.and_time(chrono::NaiveTime::MIN));
+    match (parse(start), parse(end)) {
+        (Some(start), Some(end)) if start <= end => Ok((midnight(start), midnight(end + chrono::Duration::days(1)))),
+        (Some(_), Some(_)) => Err("The range must start before it ends"),
+        _ => Err("Enter both dates as YYYY-MM-DD"),
+    }
+}
+
+/// "under", "near" (at least 90% used) or "over" the limit.
+pub fn variance_status(budgeted: f64, actual: f64) -> &'static str {
+    if actual > budgeted {
+        "over"
+    } else if actual >= budgeted * NEAR_THRESHOLD {
+        "near"
+    } else {
+        "under"
+    }
+}
+
+/// Spend by the end of `period` if it keeps the pace of `actual` so far:
+/// actual / days elapsed (today included) * days in the month. A finished
+/// month projects its actual spend; one that hasn't started yet, None.
+pub fn projected_spend(actual: f64, period: &str, now: DateTime<Utc>) -> Option<f64> {
+    let (start, end) = parse_period(period)?;
+    if now < start {
+        return None;
+    }
+    if now >= end {
+        return Some(actual);
+    }
+    let days = (end - start).num_days() as f64;
+    let elapsed = (now.date_naive() - start.date_naive()).num_days() as f64 + 1.0;
+    Some(actual / elapsed * days)
+}
+
+/// Every budget of the user with its actual spend in `period` ("YYYY-MM", see
+/// `parse_period`; an invalid period yields no rows).
+/// Actual spend is the expense splits in the category plus fees booked to it;
+/// `projected` is where it ends the month at the current pace.
+pub async fn budget_variance(
+    pool: &SqlitePool,
+    user_id: i64,
+    period: &str,
+) -> Result<Vec<BudgetVariance>, sqlx::Error> {
+    let Some((start, end)) = parse_period(period) else {
+        return Ok(Vec::new());
+    };
+
+    let sql = format!(
+        "SELECT b.id as budget_id, b.category_id, c.name as category_name, b.monthly_limit,
+                COALESCE((
+                    SELECT SUM(ABS(tc.amount) * {share})
+                    FROM transaction_categories tc
+                    INNER JOIN transactions t ON tc.transaction_id = t.id
+                    INNER JOIN accounts a ON t.account_id = a.id
+                    WHERE tc.category_id = b.category_id AND a.user_id = b.user_id
+                    AND t.transaction_type = 'expense' AND {counted}
+                    AND t.transaction_date >= ? AND t.transaction_date < ?
+                ), 0.0) + COALESCE((
+                    SELECT SUM(t.fee)
+                    FROM transactions t
+                    INNER JOIN accounts a ON t.account_id = a.id
+                    WHERE t.fee_category_id = b.category_id AND a.user_id = b.user_id AND {counted}
+                    AND t.transaction_date >= ? AND t.transaction_date < ?
+                ), 0.0) as actual
+         FROM budgets b
+         INNER JOIN categories c ON b.category_id = c.id
+         WHERE b.user_id = ?
+         ORDER BY c.name",
+        counted = exclusions::COUNTED,
+        share = reimbursements::UNREIMBURSED_SHARE
+    );
+    let rows = sqlx::query_as::<_, BudgetActual>(&sql)
+        .bind(start)
+        .bind(end)
+        .bind(start)
+        .bind(end)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    let now = Utc::now();
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let variance_amount = row.monthly_limit - row.actual;
+            let projected = projected_spend(row.actual, period, now);
+            BudgetVariance {
+                budget_id: row.budget_id,
+                category_id: row.category_id,
+                category_name: row.category_name,
+                period: period.trim().to_string(),
+                budgeted: row.monthly_limit,
+                actual: row.actual,
+                variance_amount,
+                variance_percent: variance_amount / row.monthly_limit * 100.0,
+                status: variance_status(row.monthly_limit, row.actual).to_string(),
+                projected,
+                projected_status: projected.map(|p| variance_status(row.monthly_limit, p).to_string()),
+            }
+        })
+        .collect())
+}
+
+/// Counts per status and totals across the rows of a variance report.
+pub fn adherence(rows: &[BudgetVariance]) -> BudgetAdherence {
+    let count = |status: &str| rows.iter().filter(|r| r.status == status).count();
+    BudgetAdherence {
+        under: count("under"),
+        near: count("near"),
+        over: count("over"),
+        total_budgeted: rows.iter().map(|r| r.budgeted).sum(),
+        total_actual: rows.iter().map(|r| r.actual).sum(),
+    }
+}
+
+/// Set the monthly limit for a category; setting it again replaces the limit.
+pub async fn set_budget(
+    pool: &SqlitePool,
+    user_id: i64,
+    category_id: i64,
+    monthly_limit: f64,
+) -> Result<Budget, sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO budgets (user_id, category_id, monthly_limit) VALUES (?, ?, ?)
+         ON CONFLICT(user_id, category_id) DO UPDATE SET monthly_limit = excluded.monthly_limit",
+    )
+    .bind(user_id)
+    .bind(category_id)
+    .bind(monthly_limit)
+    .execute(pool)
+    .await?;
+
+    sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE user_id = ? AND category_id = ?")
+        .bind(user_id)
+        .bind(category_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// CSV rendering of the variance report
+pub fn render_csv(rows: &[BudgetVariance]) -> String {
+    let mut csv = String::from(
+        "period,category_id,category,budgeted,actual,variance_amount,variance_percent,status,projected\n",
+    );
+    for r in rows {
+        csv.push_str(&format!(
+            "{},{},\"{}\",{:.2},{:.2},{:.2},{:.1},{},{}\n",
+            r.period,
+            r.category_id,
+            r.category_name.replace('"', "\"\""),
+            r.budgeted,
+            r.actual,
+            r.variance_amount,
+            r.variance_percent,
+            r.status,
+            r.projected.map(|p| format!("{:.2}", p)).unwrap_or_default()
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_period() {
+        let (start, end) = parse_period("2024-12").unwrap();
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert!(parse_period("2024-13").is_none());
+        assert!(parse_period("June").is_none());
+    }
+
+    #[test]
+    fn test_resolve_period_shortcuts() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 9, 30, 0).unwrap();
+        assert_eq!(resolve_period("this_month", now), parse_period("2025-01"));
+        assert_eq!(resolve_period("last_month", now), parse_period("2024-12"));
+        assert_eq!(resolve_period("2024-06", now), parse_period("2024-06"));
+        assert!(resolve_period("next_month", now).is_none());
+
+        let midnight = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+        assert_eq!(resolve_period("this_quarter", now), Some((midnight(2025, 1, 1), midnight(2025, 4, 1))));
+        assert_eq!(resolve_period("last_quarter", now), Some((midnight(2024, 10, 1), midnight(2025, 1, 1))));
+        assert_eq!(resolve_period("ytd", now), Some((midnight(2025, 1, 1), midnight(2025, 1, 16))));
+    }
+
+    #[test]
+    fn test_parse_date_range() {
+        let midnight = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+        assert_eq!(parse_date_range("2025-01-01", "2025-01-31"), Ok((midnight(2025, 1, 1), midnight(2025, 2, 1))));
+        // One day, end included; surrounding spaces are ignored
+        assert_eq!(parse_date_range(" 2024-02-29 ", "2024-02-29"), Ok((midnight(2024, 2, 29), midnight(2024, 3, 1))));
+        assert_eq!(parse_date_range("2025-03-02", "2025-03-01"), Err("The range must start before it ends"));
+        assert_eq!(parse_date_range("2025-03-01", ""), Err("Enter both dates as YYYY-MM-DD"));
+        assert_eq!(parse_date_range("01/03/2025", "2025-03-31"), Err("Enter both dates as YYYY-MM-DD"));
+    }
+
+    #[test]
+    fn test_adherence_counts_statuses() {
+        let row = |budgeted: f64, actual: f64| BudgetVariance {
+            budget_id: 1,
+            category_id: 1,
+            category_name: "Dining".to_string(),
+            period: "2025-03".to_string(),
+            budgeted,
+            actual,
+            variance_amount: budgeted - actual,
+            variance_percent: 0.0,
+            status: variance_status(budgeted, actual).to_string(),
+            projected: None,
+            projected_status: None,
+        };
+        let summary = adherence(&[row(100.0, 20.0), row(100.0, 95.0), row(50.0, 80.0), row(10.0, 0.0)]);
+        assert_eq!((summary.under, summary.near, summary.over), (2, 1, 1));
+        assert_eq!(summary.total_budgeted, 260.0);
+        assert_eq!(summary.total_actual, 195.0);
+        assert_eq!(summary.kept_percent(), 75.0);
+        assert_eq!(adherence(&[]).kept_percent(), 100.0);
+    }
+
+    #[test]
+    fn test_projected_spend_follows_the_pace() {
+        // 10 of June's 30 days elapsed (the 10th included)
+        let now = Utc.with_ymd_and_hms(2025, 6, 10, 18, 0, 0).unwrap();
+        assert_eq!(projected_spend(240.0, "2025-06", now), Some(720.0));
+        assert_eq!(variance_status(600.0, 720.0), "over");
+        // A first-day purchase counts as one day of pace
+        let first = Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap();
+        assert_eq!(projected_spend(20.0, "2025-06", first), Some(600.0));
+        assert_eq!(projected_spend(240.0, "2025-05", now), Some(240.0));
+        assert_eq!(projected_spend(0.0, "2025-07", now), None);
+        assert_eq!(projected_spend(10.0, "June", now), None);
+    }
+
+    #[test]
+    fn test_variance_status() {
+        assert_eq!(variance_status(100.0, 50.0), "under");
+        assert_eq!(variance_status(100.0, 95.0), "near");
+        assert_eq!(variance_status(100.0, 100.0), "near");
+        assert_eq!(variance_status(100.0, 120.0), "over");
+    }
+}
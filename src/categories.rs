@@ -0,0 +1,166 @@
+// categories.rs
+// Category hierarchy: a category can sit under a top-level parent (Food >
+// Groceries, Food > Restaurants). Spending is recorded against the
+// sub-category and can be rolled up into the parent for reports.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::models::{Category, CategorySpendingSummary};
+
+/// Why `parent` can't take the category `category_id` (None when creating
+/// one) of user `user_id` as a sub-category, if anything. Nesting is one
+/// level deep, so the parent must be top-level and a category that has
+/// sub-categories of its own can't be nested.
+pub fn validate_parent(
+    user_id: i64,
+    category_id: Option<i64>,
+    parent: &Category,
+    has_sub_categories: bool,
+) -> Result<(), String> {
+    if parent.user_id != user_id {
+        return Err("Parent category belongs to another user".to_string());
+    }
+    if Some(parent.id) == category_id {
+        return Err("A category can't be its own parent".to_string());
+    }
+    if parent.parent_category_id.is_some() {
+        return Err(format!("'{}' is already a sub-category", parent.name));
+    }
+    if has_sub_categories {
+        return Err("A category with sub-categories can't be nested".to_string());
+    }
+    Ok(())
+}
+
+pub async fn has_sub_categories(pool: &SqlitePool, category_id: i64) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM categories WHERE parent_category_id = ?)")
+        .bind(category_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// The first of `category_ids` that isn't one of the user's categories,
+/// whether it belongs to someone else or doesn't exist
+pub async fn first_foreign(pool: &SqlitePool, user_id: i64, category_ids: &[i64]) -> Result<Option<i64>, sqlx::Error> {
+    for &category_id in category_ids {
+        let owned: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM categories WHERE id = ? AND user_id = ?)")
+            .bind(category_id)
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+        if !owned {
+            return Ok(Some(category_id));
+        }
+    }
+    Ok(None)
+}
+
+/// Each top-level category followed by its sub-categories, both by name.
+/// Sub-categories whose parent isn't in the list go at the top level.
+pub fn tree_order(categories: Vec<Category>) -> Vec<Category> {
+    let ids: Vec<i64> = categories.iter().map(|c| c.id).collect();
+    let (mut roots, children): (Vec<Category>, Vec<Category>) = categories
+        .into_iter()
+        .partition(|c| c.parent_category_id.filter(|p| ids.contains(p)).is_none());
+    roots.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut by_parent: HashMap<i64, Vec<Category>> = HashMap::new();
+    for child in children {
+        by_parent.entry(child.parent_category_id.unwrap()).or_default().push(child);
+    }
+
+    let mut ordered = Vec::new();
+    for root in roots {
+        let mut subs = by_parent.remove(&root.id).unwrap_or_default();
+        subs.sort_by(|a, b| a.name.cmp(&b.name));
+        ordered.push(root);
+        ordered.extend(subs);
+    }
+    ordered
+}
+
+/// Fold each sub-category's spending row into its parent's, largest first.
+/// A transaction split across two sub-categories counts once for each.
+pub fn roll_up(spending: Vec<CategorySpendingSummary>, categories: &[Category]) -> Vec<CategorySpendingSummary> {
+    let by_id: HashMap<i64, &Category> = categories.iter().map(|c| (c.id, c)).collect();
+    let mut rolled: Vec<CategorySpendingSummary> = Vec::new();
+    for row in spending {
+        let parent = by_id
+            .get(&row.category_id)
+            .and_then(|c| c.parent_category_id)
+            .and_then(|p| by_id.get(&p));
+        let (id, name) = match parent {
+            Some(parent) => (parent.id, parent.name.clone()),
+            None => (row.category_id, row.category_name),
+        };
+        match rolled.iter_mut().find(|r| r.category_id == id) {
+            Some(existing) => {
+                existing.total_amount += row.total_amount;
+                existing.transaction_count += row.transaction_count;
+            }
+            None => rolled.push(CategorySpendingSummary {
+                category_id: id,
+                category_name: name,
+                total_amount: row.total_amount,
+                transaction_count: row.transaction_count,
+            }),
+        }
+    }
+    rolled.sort_by(|a, b| b.total_amount.total_cmp(&a.total_amount));
+    rolled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    async fn nest(pool: &SqlitePool, child: &Category, parent: &Category) -> Category {
+        sqlx::query_as::<_, Category>("UPDATE categories SET parent_category_id = ? WHERE id = ? RETURNING *")
+            .bind(parent.id)
+            .bind(child.id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn row(category: &Category, total_amount: f64) -> CategorySpendingSummary {
+        CategorySpendingSummary {
+            category_id: category.id,
+            category_name: category.name.clone(),
+            total_amount,
+            transaction_count: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sub_categories_nest_and_roll_up() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let food = testing::category(&pool, user.id, "Food").await;
+        let rent = testing::category(&pool, user.id, "Rent").await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let restaurants = testing::category(&pool, user.id, "Restaurants").await;
+
+        assert!(validate_parent(user.id, Some(groceries.id), &food, false).is_ok());
+        let groceries = nest(&pool, &groceries, &food).await;
+        let restaurants = nest(&pool, &restaurants, &food).await;
+
+        // One level deep, no self-parenting
+        assert!(validate_parent(user.id, Some(rent.id), &groceries, false).is_err());
+        assert!(validate_parent(user.id, Some(food.id), &food, false).is_err());
+        assert!(has_sub_categories(&pool, food.id).await.unwrap());
+        assert!(validate_parent(user.id, Some(food.id), &rent, true).is_err());
+
+        let categories = vec![restaurants.clone(), rent.clone(), groceries.clone(), food.clone()];
+        let names: Vec<String> = tree_order(categories.clone()).into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Food", "Groceries", "Restaurants", "Rent"]);
+
+        let spending = vec![row(&rent, 900.0), row(&groceries, 400.0), row(&restaurants, 650.0), row(&food, 20.0)];
+        let rolled = roll_up(spending, &categories);
+        assert_eq!(rolled.len(), 2);
+        assert_eq!((rolled[0].category_name.as_str(), rolled[0].total_amount, rolled[0].transaction_count), ("Food", 1070.0, 3));
+        assert_eq!((rolled[1].category_id, rolled[1].total_amount), (rent.id, 900.0));
+    }
+}
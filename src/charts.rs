@@ -0,0 +1,224 @@
+// charts.rs
+// Server-side SVG charts of category spending, for embedding in emails and reports
+
+use chrono::{DateTime, Utc};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use sqlx::SqlitePool;
+
+use crate::exclusions;
+use crate::models::CategorySpendingSummary;
+use crate::reimbursements;
+
+/// Categories drawn individually; the rest are merged into "Other".
+const MAX_SLICES: usize = 8;
+const PALETTE: [RGBColor; 9] = [
+    RGBColor(0x4e, 0x79, 0xa7),
+    RGBColor(0xf2, 0x8e, 0x2b),
+    RGBColor(0xe1, 0x57, 0x59),
+    RGBColor(0x76, 0xb7, 0xb2),
+    RGBColor(0x59, 0xa1, 0x4f),
+    RGBColor(0xed, 0xc9, 0x48),
+    RGBColor(0xb0, 0x7a, 0xa1),
+    RGBColor(0xff, 0x9d, 0xa7),
+    RGBColor(0x9c, 0x75, 0x5f),
+];
+const MUTED: RGBColor = RGBColor(0x88, 0x88, 0x88);
+
+type Canvas<'a> = DrawingArea<SVGBackend<'a>, Shift>;
+type DrawResult = Result<(), DrawingAreaErrorKind<std::io::Error>>;
+
+/// Expense splits plus fees per category in [start, end), largest first.
+/// Fees without a fee category are grouped under "Fees" (id 0), as in the
+/// spending-by-category analytics. Transactions excluded from reports are left out
+/// and reimbursed expenses count what they still cost.
+pub async fn category_spending(
+    pool: &SqlitePool,
+    user_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<CategorySpendingSummary>, sqlx::Error> {
+    let sql = format!(
+        "SELECT category_id, category_name,
+                SUM(total_amount) as total_amount, SUM(transaction_count) as transaction_count
+         FROM (
+             SELECT c.id as category_id, c.name as category_name,
+                    SUM(ABS(tc.amount) * {share}) as total_amount, COUNT(DISTINCT t.id) as transaction_count
+             FROM transactions t
+             JOIN transaction_categories tc ON t.id = tc.transaction_id
+             JOIN categories c ON tc.category_id = c.id
+             JOIN accounts a ON t.account_id = a.id
+             WHERE a.user_id = ? AND t.transaction_type = 'expense' AND {counted}
+             AND t.transaction_date >= ? AND t.transaction_date < ?
+             GROUP BY c.id, c.name
+             UNION ALL
+             SELECT COALESCE(c.id, 0) as category_id, COALESCE(c.name, 'Fees') as category_name,
+                    SUM(t.fee) as total_amount, COUNT(*) as transaction_count
+             FROM transactions t
+             LEFT JOIN categories c ON t.fee_category_id = c.id
+             JOIN accounts a ON t.account_id = a.id
+             WHERE a.user_id = ? AND t.fee > 0 AND {counted}
+             AND t.transaction_date >= ? AND t.transaction_date < ?
+             GROUP BY COALESCE(c.id, 0), COALESCE(c.name, 'Fees')
+         )
+         GROUP BY category_id, category_name
+         ORDER BY total_amount DESC",
+        counted = exclusions::COUNTED,
+        share = reimbursements::UNREIMBURSED_SHARE
+    );
+    sqlx::query_as::<_, CategorySpendingSummary>(&sql)
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+}
+
+/// Top categories by amount, with the remainder summed into "Other".
+fn top_slices(spending: &[CategorySpendingSummary]) -> Vec<(String, f64)> {
+    let mut slices: Vec<(String, f64)> = spending
+        .iter()
+        .filter(|s| s.total_amount > 0.0)
+        .take(MAX_SLICES)
+        .map(|s| (s.category_name.clone(), s.total_amount))
+        .collect();
+    let other: f64 = spending
+        .iter()
+        .filter(|s| s.total_amount > 0.0)
+        .skip(MAX_SLICES)
+        .map(|s| s.total_amount)
+        .sum();
+    if other > 0.0 {
+        slices.push(("Other".to_string(), other));
+    }
+    slices
+}
+
+fn text_style(size: u32, h_pos: HPos) -> TextStyle<'static> {
+    TextStyle::from(("sans-serif", size).into_font()).pos(Pos::new(h_pos, VPos::Center))
+}
+
+/// White canvas with the title centred at the top, and `draw` for the rest.
+/// Text goes out as SVG text, so labels are escaped by the backend.
+fn render(width: u32, height: u32, title: &str, draw: impl FnOnce(&Canvas) -> DrawResult) -> String {
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, (width, height)).into_drawing_area();
+        let title_style = TextStyle::from(("sans-serif", 16).into_font().style(FontStyle::Bold))
+            .pos(Pos::new(HPos::Center, VPos::Center));
+        root.fill(&WHITE)
+            .and_then(|_| root.draw(&Text::new(title, (width as i32 / 2, 20), title_style)))
+            .and_then(|_| draw(&root))
+            .and_then(|_| root.present())
+            // Only the sizing of the built-in font and writes to the string can fail
+            .expect("render chart into an in-memory SVG");
+    }
+    svg
+}
+
+fn draw_empty(root: &Canvas, width: u32, height: u32) -> DrawResult {
+    let style = text_style(14, HPos::Center).color(&MUTED);
+    root.draw(&Text::new("No spending in this period", (width as i32 / 2, height as i32 / 2), style))
+}
+
+/// Horizontal bar chart, one bar per category.
+pub fn bar_chart_svg(title: &str, spending: &[CategorySpendingSummary]) -> String {
+    let slices = top_slices(spending);
+    let (width, row_height, label_width) = (640u32, 28i32, 160i32);
+    let height = 60 + row_height as u32 * slices.len().max(1) as u32;
+    if slices.is_empty() {
+        return render(width, height, title, |root| draw_empty(root, width, height));
+    }
+
+    let max = slices.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let bar_space = (width as i32 - label_width - 100) as f64;
+    render(width, height, title, |root| {
+        for (i, (name, amount)) in slices.iter().enumerate() {
+            let y = 44 + i as i32 * row_height;
+            let middle = y + (row_height - 8) / 2;
+            let bar_width = (amount / max * bar_space).max(1.0) as i32;
+            root.draw(&Text::new(name.as_str(), (label_width - 8, middle), text_style(12, HPos::Right)))?;
+            root.draw(&Rectangle::new(
+                [(label_width, y), (label_width + bar_width, y + row_height - 8)],
+                PALETTE[i % PALETTE.len()].filled(),
+            ))?;
+            root.draw(&Text::new(
+                format!("{:.2}", amount),
+                (label_width + bar_width + 6, middle),
+                text_style(12, HPos::Left),
+            ))?;
+        }
+        Ok(())
+    })
+}
+
+/// Pie chart with the share on each slice and a legend of the amounts.
+pub fn pie_chart_svg(title: &str, spending: &[CategorySpendingSummary]) -> String {
+    let slices = top_slices(spending);
+    let (width, height) = (640u32, 360u32);
+    if slices.is_empty() {
+        return render(width, height, title, |root| draw_empty(root, width, height));
+    }
+
+    let total: f64 = slices.iter().map(|(_, v)| v).sum();
+    let sizes: Vec<f64> = slices.iter().map(|(_, v)| *v).collect();
+    let colors: Vec<RGBColor> = (0..slices.len()).map(|i| PALETTE[i % PALETTE.len()]).collect();
+    let names: Vec<&str> = slices.iter().map(|(name, _)| name.as_str()).collect();
+    render(width, height, title, |root| {
+        let (center, radius) = ((180, 195), 140.0);
+        let mut pie = Pie::new(&center, &radius, &sizes, &colors, &names);
+        pie.start_angle(-90.0);
+        // Names go in the legend rather than around the pie
+        pie.label_style(text_style(12, HPos::Left).color(&TRANSPARENT));
+        pie.percentages(text_style(12, HPos::Left).color(&WHITE));
+        root.draw(&pie)?;
+
+        for (i, (name, amount)) in slices.iter().enumerate() {
+            let y = 60 + i as i32 * 26;
+            root.draw(&Rectangle::new([(360, y), (374, y + 14)], colors[i].filled()))?;
+            root.draw(&Text::new(
+                format!("{} - {:.2} ({:.1}%)", name, amount, amount / total * 100.0),
+                (382, y + 7),
+                text_style(12, HPos::Left),
+            ))?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spending(name: &str, total_amount: f64) -> CategorySpendingSummary {
+        CategorySpendingSummary {
+            category_id: 1,
+            category_name: name.to_string(),
+            total_amount,
+            transaction_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_charts_escape_labels_and_merge_small_categories() {
+        let mut rows: Vec<_> = (0..10)
+            .map(|i| spending(&format!("Cat {}", i), 100.0 - i as f64))
+            .collect();
+        rows[0].category_name = "Food & Drink".to_string();
+
+        let bar = bar_chart_svg("Spending", &rows);
+        assert!(bar.contains("Food &amp; Drink"));
+        assert_eq!(bar.matches("<rect").count(), 1 + MAX_SLICES + 1); // background + bars + Other
+
+        let pie = pie_chart_svg("Spending", &rows);
+        assert_eq!(pie.matches("<polygon").count(), MAX_SLICES + 1);
+        assert!(pie.contains("Other - "));
+        assert!(pie.contains("Food &amp; Drink - 100.00"));
+
+        assert!(pie_chart_svg("Spending", &[]).contains("No spending in this period"));
+    }
+}
@@ -0,0 +1,67 @@
+// credit.rs
+// Credit limit utilization and the alert threshold
+
+use std::env;
+
+use crate::models::{Account, AccountStats};
+
+/// Alert threshold used when CREDIT_UTILIZATION_THRESHOLD is unset (percent of the limit).
+pub const DEFAULT_THRESHOLD: f64 = 80.0;
+
+/// Utilization percentage that triggers an alert, read from
+/// CREDIT_UTILIZATION_THRESHOLD (e.g. "75"); invalid values fall back to the default.
+pub fn utilization_threshold() -> f64 {
+    env::var("CREDIT_UTILIZATION_THRESHOLD")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|v| *v > 0.0)
+        .unwrap_or(DEFAULT_THRESHOLD)
+}
+
+/// Share of the credit limit in use. Card balances are negative while money
+/// is owed; a positive balance (overpayment) counts as 0%.
+pub fn utilization_percent(balance: f64, credit_limit: Option<f64>) -> Option<f64> {
+    let limit = credit_limit.filter(|l| *l > 0.0)?;
+    Some((-balance).max(0.0) / limit * 100.0)
+}
+
+/// True when a balance change moved utilization from below the threshold to at or above it.
+pub fn crossed_threshold(before: Option<f64>, after: Option<f64>, threshold: f64) -> bool {
+    match (before, after) {
+        (Some(before), Some(after)) => before < threshold && after >= threshold,
+        _ => false,
+    }
+}
+
+pub fn account_stats(account: &Account, projected_balance: f64, threshold: f64) -> AccountStats {
+    let utilization = utilization_percent(account.current_balance, account.credit_limit);
+    AccountStats {
+        account_id: account.id,
+        account_name: account.name.clone(),
+        account_type: account.account_type.clone(),
+        currency: account.currency.clone(),
+        current_balance: account.current_balance,
+        projected_balance,
+        credit_limit: account.credit_limit,
+        available_credit: account.credit_limit.map(|l| l + account.current_balance),
+        utilization_percent: utilization,
+        utilization_threshold: threshold,
+        over_threshold: utilization.is_some_and(|u| u >= threshold),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utilization_and_threshold_crossing() {
+        assert_eq!(utilization_percent(-850.0, Some(1000.0)), Some(85.0));
+        assert_eq!(utilization_percent(25.0, Some(1000.0)), Some(0.0));
+        assert_eq!(utilization_percent(-850.0, None), None);
+
+        assert!(crossed_threshold(Some(70.0), Some(85.0), 80.0));
+        assert!(!crossed_threshold(Some(85.0), Some(90.0), 80.0));
+        assert!(!crossed_threshold(None, None, 80.0));
+    }
+}
@@ -0,0 +1,99 @@
+// currencies.rs
+// Currencies a user can pick from: those of their accounts plus every currency
+// with a stored rate. Scraped rates label currencies like "Argentine Peso
+// (ARS)", which is where the display names come from.
+
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+use crate::models::CurrencyInfo;
+
+/// Code and display name of a currency label: "Argentine Peso (ARS)" gives
+/// ("ARS", Some("Argentine Peso")); anything else is taken as a bare code.
+pub fn parse_label(label: &str) -> (String, Option<String>) {
+    let label = label.trim();
+    if let (Some(start), Some(end)) = (label.rfind('('), label.rfind(')')) {
+        if end > start {
+            let code = &label[start + 1..end];
+            // Looks like a currency code: 2-4 uppercase letters
+            if (2..=4).contains(&code.len()) && code.chars().all(|c| c.is_ascii_uppercase()) {
+                let name = label[..start].trim();
+                return (code.to_string(), Some(name.to_string()).filter(|n| !n.is_empty()));
+            }
+        }
+    }
+    (label.to_string(), None)
+}
+
+/// "Argentine Peso (ARS)" when the name is known, otherwise the code
+pub fn display_label(currency: &CurrencyInfo) -> String {
+    match &currency.name {
+        Some(name) => format!("{} ({})", name, currency.code),
+        None => currency.code.clone(),
+    }
+}
+
+/// Currencies of the user's accounts and of all stored rates, by code
+pub async fn currencies_in_use(pool: &SqlitePool, user_id: i64) -> Result<Vec<CurrencyInfo>, sqlx::Error> {
+    let account_currencies: Vec<String> =
+        sqlx::query_scalar("SELECT DISTINCT currency FROM accounts WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+    let rate_currencies: Vec<String> = sqlx::query_scalar(
+        "SELECT from_currency FROM exchange_rates UNION SELECT to_currency FROM exchange_rates",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut currencies: BTreeMap<String, CurrencyInfo> = BTreeMap::new();
+    let labels = account_currencies
+        .iter()
+        .map(|label| (label, true))
+        .chain(rate_currencies.iter().map(|label| (label, false)));
+    for (label, from_account) in labels {
+        let (code, name) = parse_label(label);
+        let entry = currencies.entry(code.clone()).or_insert(CurrencyInfo {
+            code,
+            name: None,
+            in_accounts: false,
+            has_rates: false,
+        });
+        if from_account {
+            entry.in_accounts = true;
+        } else {
+            entry.has_rates = true;
+        }
+        if entry.name.is_none() {
+            entry.name = name;
+        }
+    }
+    Ok(currencies.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_currencies_merge_accounts_and_rates() {
+        assert_eq!(parse_label("Argentine Peso (ARS)"), ("ARS".to_string(), Some("Argentine Peso".to_string())));
+        assert_eq!(parse_label("usd"), ("usd".to_string(), None));
+
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let other = testing::user().insert(&pool).await;
+        testing::account(user.id).currency("CAD").insert(&pool).await;
+        testing::account(other.id).currency("JPY").insert(&pool).await;
+        testing::rate(&pool, "CAD", "Euro (EUR)", 0.68, Utc::now()).await;
+
+        let currencies = currencies_in_use(&pool, user.id).await.unwrap();
+        let codes: Vec<&str> = currencies.iter().map(|c| c.code.as_str()).collect();
+        assert_eq!(codes, vec!["CAD", "EUR"]);
+        assert!(currencies[0].in_accounts && currencies[0].has_rates);
+        assert_eq!(display_label(&currencies[1]), "Euro (EUR)");
+        assert!(!currencies[1].in_accounts);
+    }
+}
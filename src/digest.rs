@@ -0,0 +1,133 @@
+// digest.rs
+// Weekly digest shown after TUI login: spending against the week before, new
+// large expenses, budgets at risk and bills due in the coming week
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+use std::env;
+
+use crate::budgets;
+use crate::charts;
+use crate::exclusions;
+use crate::forecast;
+use crate::models::{RecurringTransaction, Transaction, UpcomingBill, WeeklyDigest};
+
+/// Expenses of at least this much are listed when DIGEST_LARGE_TRANSACTION is unset.
+pub const DEFAULT_LARGE_TRANSACTION: f64 = 200.0;
+
+/// Large-expense threshold, read from DIGEST_LARGE_TRANSACTION.
+pub fn large_transaction_threshold() -> f64 {
+    env::var("DIGEST_LARGE_TRANSACTION")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|v| *v > 0.0)
+        .unwrap_or(DEFAULT_LARGE_TRANSACTION)
+}
+
+async fn spent_between(
+    pool: &SqlitePool,
+    user_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<f64, sqlx::Error> {
+    Ok(charts::category_spending(pool, user_id, start, end)
+        .await?
+        .iter()
+        .map(|s| s.total_amount)
+        .sum())
+}
+
+/// The digest for the seven days up to `now`
+pub async fn weekly_digest(
+    pool: &SqlitePool,
+    user_id: i64,
+    now: DateTime<Utc>,
+    large_threshold: f64,
+) -> Result<WeeklyDigest, sqlx::Error> {
+    let week_ago = now - Duration::days(7);
+    let spent_this_week = spent_between(pool, user_id, week_ago, now).await?;
+    let spent_last_week = spent_between(pool, user_id, week_ago - Duration::days(7), week_ago).await?;
+
+    let large_sql = format!(
+        "SELECT t.* FROM transactions t
+         JOIN accounts a ON t.account_id = a.id
+         WHERE a.user_id = ? AND t.transaction_type = 'expense' AND {}
+         AND ABS(t.amount) >= ? AND t.transaction_date >= ? AND t.transaction_date < ?
+         ORDER BY ABS(t.amount) DESC",
+        exclusions::COUNTED
+    );
+    let large_transactions = sqlx::query_as::<_, Transaction>(&large_sql)
+        .bind(user_id)
+        .bind(large_threshold)
+        .bind(week_ago)
+        .bind(now)
+        .fetch_all(pool)
+        .await?;
+
+    let period = now.format("%Y-%m").to_string();
+    let budgets_at_risk = budgets::budget_variance(pool, user_id, &period)
+        .await?
+        .into_iter()
+        .filter(|b| b.status != "under")
+        .collect();
+
+    let recurring = sqlx::query_as::<_, RecurringTransaction>(
+        "SELECT r.* FROM recurring_transactions r
+         JOIN accounts a ON r.account_id = a.id
+         WHERE a.user_id = ? AND r.is_active = 1 AND r.transaction_type = 'expense'",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    let mut upcoming_bills: Vec<UpcomingBill> = recurring
+        .iter()
+        .flat_map(|r| {
+            forecast::occurrences_between(r, now, now + Duration::days(7))
+                .into_iter()
+                .map(|due| UpcomingBill {
+                    recurring_transaction_id: r.id,
+                    description: r.description.clone(),
+                    amount: r.amount.abs(),
+                    due,
+                })
+        })
+        .collect();
+    upcoming_bills.sort_by_key(|b| b.due);
+
+    Ok(WeeklyDigest {
+        spent_this_week,
+        spent_last_week,
+        large_transactions,
+        budgets_at_risk,
+        upcoming_bills,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_weekly_digest_compares_weeks_and_lists_bills() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let rent = testing::category(&pool, user.id, "Rent").await;
+        let now = Utc::now();
+
+        testing::txn(account.id).amount(900.0).category(rent.id).date(now - Duration::minutes(1)).insert(&pool).await;
+        testing::txn(account.id).amount(40.0).category(rent.id).date(now - Duration::days(2)).insert(&pool).await;
+        testing::txn(account.id).amount(300.0).category(rent.id).date(now - Duration::days(10)).insert(&pool).await;
+        budgets::set_budget(&pool, user.id, rent.id, 500.0).await.unwrap();
+        testing::recurring(account.id).due(now + Duration::days(3)).insert(&pool).await;
+        testing::recurring(account.id).due(now + Duration::days(20)).insert(&pool).await;
+
+        let digest = weekly_digest(&pool, user.id, now, 200.0).await.unwrap();
+        assert_eq!((digest.spent_this_week, digest.spent_last_week), (940.0, 300.0));
+        assert_eq!(digest.large_transactions.len(), 1);
+        assert_eq!(digest.large_transactions[0].amount, 900.0);
+        assert_eq!(digest.upcoming_bills.len(), 1);
+        assert_eq!(digest.budgets_at_risk[0].status, "over");
+    }
+}
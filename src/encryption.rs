@@ -0,0 +1,281 @@
+// encryption.rs
+// Passphrase-based encryption for exported files: a zip holding the export
+// as a single WinZip AES-256 (AE-2) entry, which 7-Zip, WinZip, macOS
+// Archive Utility and `cargo run decrypt_export` can all open
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{hmac, pbkdf2};
+use std::num::NonZeroU32;
+
+type Aes256Ctr = ctr::Ctr128LE<aes::Aes256>;
+
+/// Fixed by the WinZip AES specification for AES-256
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const VERIFIER_LEN: usize = 2;
+const AUTH_CODE_LEN: usize = 10;
+const PBKDF2_ITERATIONS: u32 = 1000;
+
+/// Compression method marking an AES entry, and its extra field header id
+const METHOD_AES: u16 = 99;
+const AES_EXTRA_ID: u16 = 0x9901;
+const LOCAL_HEADER_LEN: usize = 30;
+const END_OF_CENTRAL_LEN: usize = 22;
+
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+/// Extension appended to encrypted export files ("transactions.csv.zip").
+pub const FILE_EXTENSION: &str = "zip";
+
+/// AES key, HMAC key and password verifier, derived with PBKDF2-HMAC-SHA1
+fn derive_keys(passphrase: &str, salt: &[u8]) -> ([u8; KEY_LEN], hmac::Key, [u8; VERIFIER_LEN]) {
+    let mut derived = [0u8; 2 * KEY_LEN + VERIFIER_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA1,
+        NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut derived,
+    );
+    let mut aes_key = [0u8; KEY_LEN];
+    aes_key.copy_from_slice(&derived[..KEY_LEN]);
+    let mac_key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, &derived[KEY_LEN..2 * KEY_LEN]);
+    let mut verifier = [0u8; VERIFIER_LEN];
+    verifier.copy_from_slice(&derived[2 * KEY_LEN..]);
+    (aes_key, mac_key, verifier)
+}
+
+/// AES-256 in CTR mode with WinZip's little-endian counter starting at 1
+fn apply_keystream(aes_key: &[u8; KEY_LEN], data: &mut [u8]) {
+    let mut iv = [0u8; 16];
+    iv[0] = 1;
+    Aes256Ctr::new(aes_key.into(), &iv.into()).apply_keystream(data);
+}
+
+fn auth_code(mac_key: &hmac::Key, ciphertext: &[u8]) -> [u8; AUTH_CODE_LEN] {
+    let mut code = [0u8; AUTH_CODE_LEN];
+    code.copy_from_slice(&hmac::sign(mac_key, ciphertext).as_ref()[..AUTH_CODE_LEN]);
+    code
+}
+
+/// Encrypt an export with a user-supplied passphrase into a zip holding it
+/// as `name`.
+///
+/// The entry is stored uncompressed and encrypted with WinZip AE-2: a random
+/// salt per file, PBKDF2-HMAC-SHA1 key derivation, AES-256-CTR and a
+/// truncated HMAC-SHA1 over the ciphertext. AE-2 leaves the CRC at zero, so
+/// nothing about the plaintext is written unencrypted.
+pub fn encrypt(name: &str, plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| "Failed to generate salt".to_string())?;
+    let (aes_key, mac_key, verifier) = derive_keys(passphrase, &salt);
+    let mut ciphertext = plaintext.to_vec();
+    apply_keystream(&aes_key, &mut ciphertext);
+
+    let mut data = Vec::with_capacity(SALT_LEN + VERIFIER_LEN + ciphertext.len() + AUTH_CODE_LEN);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&verifier);
+    data.extend_from_slice(&ciphertext);
+    data.extend_from_slice(&auth_code(&mac_key, &ciphertext));
+    Ok(zip_entry(name, &data, plaintext.len()))
+}
+
+/// One-entry zip around the encrypted data
+fn zip_entry(name: &str, data: &[u8], plaintext_len: usize) -> Vec<u8> {
+    // 1980-01-01 00:00, the earliest DOS timestamp
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = (1 << 5) | 1;
+
+    let mut extra = Vec::new();
+    extra.extend_from_slice(&AES_EXTRA_ID.to_le_bytes());
+    extra.extend_from_slice(&7u16.to_le_bytes()); // extra data size
+    extra.extend_from_slice(&2u16.to_le_bytes()); // AE-2
+    extra.extend_from_slice(b"AE");
+    extra.push(3); // AES-256
+    extra.extend_from_slice(&0u16.to_le_bytes()); // stored
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&51u16.to_le_bytes()); // version needed
+    header.extend_from_slice(&1u16.to_le_bytes()); // flags: encrypted
+    header.extend_from_slice(&METHOD_AES.to_le_bytes());
+    header.extend_from_slice(&DOS_TIME.to_le_bytes());
+    header.extend_from_slice(&DOS_DATE.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes()); // CRC, unused by AE-2
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&(plaintext_len as u32).to_le_bytes());
+    header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    header.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&0x04034b50u32.to_le_bytes());
+    out.extend_from_slice(&header);
+    out.extend_from_slice(name.as_bytes());
+    out.extend_from_slice(&extra);
+    out.extend_from_slice(data);
+
+    let central_offset = out.len() as u32;
+    let mut central = Vec::new();
+    central.extend_from_slice(&0x02014b50u32.to_le_bytes());
+    central.extend_from_slice(&51u16.to_le_bytes()); // version made by
+    central.extend_from_slice(&header);
+    central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+    central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+    central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+    central.extend_from_slice(&0u32.to_le_bytes()); // local header offset
+    central.extend_from_slice(name.as_bytes());
+    central.extend_from_slice(&extra);
+
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    out
+}
+
+fn u16_at(data: &[u8], at: usize) -> usize {
+    u16::from_le_bytes([data[at], data[at + 1]]) as usize
+}
+
+fn u32_at(data: &[u8], at: usize) -> usize {
+    u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]) as usize
+}
+
+/// Decrypt the first entry of an AES-encrypted zip such as `encrypt`
+/// produces, returning its name and contents. Sizes are read from the central
+/// directory, so zips written with data descriptors open too; the entry must
+/// be stored rather than compressed.
+pub fn decrypt(zip: &[u8], passphrase: &str) -> Result<(String, Vec<u8>), String> {
+    let not_export = || "Not an encrypted export file".to_string();
+    let slice = |start: usize, len: usize| {
+        start
+            .checked_add(len)
+            .and_then(|end| zip.get(start..end))
+            .ok_or_else(not_export)
+    };
+
+    // End of central directory record, without an archive comment
+    let end = slice(zip.len().checked_sub(END_OF_CENTRAL_LEN).ok_or_else(not_export)?, END_OF_CENTRAL_LEN)?;
+    if u32_at(end, 0) != 0x06054b50 {
+        return Err(not_export());
+    }
+    let central = slice(u32_at(end, 16), 46)?;
+    if u32_at(central, 0) != 0x02014b50 || u16_at(central, 10) as u16 != METHOD_AES {
+        return Err(not_export());
+    }
+    let data_len = u32_at(central, 20);
+    let name_len = u16_at(central, 28);
+    let name = String::from_utf8_lossy(slice(u32_at(end, 16) + 46, name_len)?).into_owned();
+    let local_offset = u32_at(central, 42);
+
+    let local = slice(local_offset, LOCAL_HEADER_LEN)?;
+    if u32_at(local, 0) != 0x04034b50 {
+        return Err(not_export());
+    }
+    let extra = slice(local_offset + LOCAL_HEADER_LEN + u16_at(local, 26), u16_at(local, 28))?;
+    // AES extra field: id, size, vendor version, "AE", strength, method
+    let aes_extra = extra.windows(11).find(|f| u16_at(f, 0) as u16 == AES_EXTRA_ID && &f[6..8] == b"AE");
+    match aes_extra {
+        Some(field) if field[8] != 3 => return Err("Only AES-256 encrypted exports are supported".to_string()),
+        Some(field) if u16_at(field, 9) != 0 => return Err("Compressed entries are not supported".to_string()),
+        Some(_) => {}
+        None => return Err(not_export()),
+    }
+    let start = local_offset + LOCAL_HEADER_LEN + u16_at(local, 26) + u16_at(local, 28);
+    let data = slice(start, data_len)?;
+    if data_len < SALT_LEN + VERIFIER_LEN + AUTH_CODE_LEN {
+        return Err(not_export());
+    }
+
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (verifier, rest) = rest.split_at(VERIFIER_LEN);
+    let (ciphertext, code) = rest.split_at(rest.len() - AUTH_CODE_LEN);
+    let (aes_key, mac_key, expected) = derive_keys(passphrase, salt);
+    if verifier != expected {
+        return Err("Wrong passphrase".to_string());
+    }
+    if code != auth_code(&mac_key, ciphertext) {
+        return Err("Wrong passphrase or corrupted file".to_string());
+    }
+    let mut plaintext = ciphertext.to_vec();
+    apply_keystream(&aes_key, &mut plaintext);
+    Ok((name, plaintext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let data = b"id,amount\n1,42.00\n";
+        let encrypted = encrypt("transactions.csv", data, "correct horse").unwrap();
+
+        assert!(encrypted.starts_with(b"PK\x03\x04"));
+        assert!(!encrypted.windows(data.len()).any(|w| w == data));
+        let (name, plaintext) = decrypt(&encrypted, "correct horse").unwrap();
+        assert_eq!(name, "transactions.csv");
+        assert_eq!(plaintext, data);
+        assert!(decrypt(&encrypted, "wrong horse!").is_err());
+        assert!(encrypt("transactions.csv", data, "short").is_err());
+
+        let mut tampered = encrypted.clone();
+        let first_ciphertext_byte = LOCAL_HEADER_LEN + name.len() + 11 + SALT_LEN + VERIFIER_LEN;
+        tampered[first_ciphertext_byte] ^= 1;
+        assert!(decrypt(&tampered, "correct horse").is_err());
+    }
+
+    #[test]
+    fn test_decrypts_zip_written_by_bsdtar() {
+        // bsdtar 3.8.2: --format zip --options zip:compression=store,zip:encryption=aes256
+        // --passphrase "correct horse"; it writes a data descriptor and
+        // Unix extra fields next to the AES one
+        let zip = include_bytes!("../fixtures/bsdtar_aes256.zip");
+        let (name, plaintext) = decrypt(zip, "correct horse").unwrap();
+        assert_eq!(name, "transactions.csv");
+        assert_eq!(plaintext, b"id,amount\n1,42.00\n");
+        assert_eq!(decrypt(zip, "wrong horse!").unwrap_err(), "Wrong passphrase");
+    }
+
+    #[test]
+    fn test_key_schedule_matches_winzip_aes_spec() {
+        // Key schedule from the WinZip AES spec: PBKDF2-HMAC-SHA1, 1000
+        // rounds, 66 bytes (AES key, HMAC key, verifier)
+        let mut reference = [0u8; 2 * KEY_LEN + VERIFIER_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA1,
+            NonZeroU32::new(1000).unwrap(),
+            b"saltsaltsaltsalt",
+            b"password",
+            &mut reference,
+        );
+        let (aes_key, _, verifier) = derive_keys("password", b"saltsaltsaltsalt");
+        assert_eq!(aes_key[..], reference[..KEY_LEN]);
+        assert_eq!(verifier[..], reference[2 * KEY_LEN..]);
+
+        // CTR counter starts at 1, little-endian: the first block's keystream
+        // is AES(key, 01 00 .. 00)
+        use aes::cipher::{BlockEncrypt, KeyInit};
+        let mut block = [0u8; 16];
+        block[0] = 1;
+        let mut expected = aes::Block::from(block);
+        aes::Aes256::new(&aes_key.into()).encrypt_block(&mut expected);
+        let mut stream = [0u8; 16];
+        apply_keystream(&aes_key, &mut stream);
+        assert_eq!(stream[..], expected[..]);
+    }
+}
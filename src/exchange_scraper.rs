@@ -2,16 +2,36 @@
 // Website: https://www.x-rates.com/table/?from=CAD&amount=1
 
 // Functions:
-// 1. Scrape the FX rates and the date on website.
-// 2. Check if needs to scrape or not automatically
-//   1) if db contains the date's FX rates already, no need to scrape.
-//.  2) if db doesn't contain the date's FX rates, scrape and insert.
+// 1. Scrape the FX rates and the date on website, as a rate_providers::RateProvider
+//    (skipping days the db has already happens there, for every provider).
+// 2. Dry run: compare fetched rates with the rates in db without writing.
+// 3. Record each run's metrics in scrape_runs.
 
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::BoxFuture;
 use reqwest::Client;
 use scraper::{Html, Selector};
 use sqlx::SqlitePool;
-use std::collections::HashMap;
+use std::env;
+
+use crate::currencies;
+use crate::models::{RateDiff, ScrapeRun};
+use crate::rate_providers::RateProvider;
+
+// Used when no account exists yet and FX_SCRAPE_CURRENCIES is unset
+pub const DEFAULT_CURRENCIES: [&str; 4] = ["CAD", "USD", "EUR", "GBP"];
+pub const PROVIDER: &str = "x-rates.com";
+
+// Tally of one scrape run, saved with record_scrape_run
+pub struct ScrapeRunMetrics {
+    pub provider: String, // Providers that delivered rates, comma-separated
+    pub currencies: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub duration: std::time::Duration,
+    pub rates_saved: usize,
+    pub currencies_skipped: usize,
+    pub errors: Vec<String>, // "CODE: message" per failed currency
+}
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -25,6 +45,7 @@ pub struct ExchangeRate {
 pub struct ExchangeRateScraper {
     client: Client,
     base_url: String,
+    verbose: bool,
 }
 
 impl ExchangeRateScraper {
@@ -38,46 +59,23 @@ impl ExchangeRateScraper {
         Self {
             client,
             base_url: "https://www.x-rates.com/table/".to_string(),
+            verbose: true,
         }
     }
 
-    // check if db contains the date's FX rates.
-    pub async fn check_if_up_to_date(
-        &self,
-        pool: &SqlitePool,
-        from_currency: &str,
-        date: NaiveDate,
-    ) -> Result<bool, sqlx::Error> {
-        let date_str = date.format("%Y-%m-%d").to_string();
-
-        let count: i64 = sqlx::query_scalar(
-            r#"
-            SELECT COUNT(*)
-            FROM exchange_rates
-            WHERE from_currency = ?
-            AND DATE(rate_date) = ?
-            AND source = 'scraper'
-            "#,
-        )
-        .bind(from_currency)
-        .bind(date_str)
-        .fetch_one(pool)
-        .await?;
-
-        Ok(count > 0)
+    // Same scraper without progress output, for callers that own the terminal (TUI)
+    pub fn quiet() -> Self {
+        Self {
+            verbose: false,
+            ..Self::new()
+        }
     }
 
-    // Only scrape if db doesn't contain the date's FX rates
-    pub async fn smart_fetch_exchange_rates(
+    // Scrape the rates of the page's date
+    pub async fn fetch_exchange_rates(
         &self,
-        pool: &SqlitePool,
         from_currency: &str,
-    ) -> Result<(Vec<ExchangeRate>, bool), Box<dyn std::error::Error>> {
-        println!(
-            "1. Checking if {}'s FX rates need to be updated...",
-            from_currency
-        );
-
+    ) -> Result<Vec<ExchangeRate>, Box<dyn std::error::Error>> {
         let url = format!("{}?from={}&amount=1", self.base_url, from_currency);
         let response = self.client.get(&url).send().await?;
         if !response.status().is_success() {
@@ -86,26 +84,7 @@ impl ExchangeRateScraper {
 
         let html = response.text().await?;
         let rate_date = self.extract_timestamp(&html)?;
-
-        let is_up_to_date = self
-            .check_if_up_to_date(pool, from_currency, rate_date)
-            .await?;
-
-        if is_up_to_date {
-            println!(
-                "  1.3 DB contains the FX rates of {} already，no need to update.",
-                rate_date
-            );
-            return Ok((Vec::new(), true));
-        }
-
-        println!(
-            "  1.3 DB doesn't contain the FX rates of {} yet, scrape {}.",
-            rate_date, url
-        );
-
-        let rates = self.parse_exchange_rates(&html, from_currency, rate_date)?;
-        Ok((rates, false))
+        self.parse_exchange_rates(&html, from_currency, rate_date)
     }
 
     fn extract_timestamp(&self, html: &str) -> Result<NaiveDate, Box<dyn std::error::Error>> {
@@ -117,13 +96,17 @@ impl ExchangeRateScraper {
 
             let date_str = self.parse_date_from_timestamp(&timestamp_text)?;
 
-            println!("  1.1 Original timestamp online: {}", timestamp_text.trim());
-            println!("  1.2 Online Date after parsing: {}", date_str);
+            if self.verbose {
+                println!("  1.1 Original timestamp online: {}", timestamp_text.trim());
+                println!("  1.2 Online Date after parsing: {}", date_str);
+            }
 
             return Ok(date_str);
         }
 
-        println!("  1.1 If original timestamp online not found, use current timestamp.");
+        if self.verbose {
+            println!("  1.1 If original timestamp online not found, use current timestamp.");
+        }
         Ok(Utc::now().date_naive())
     }
 
@@ -155,10 +138,12 @@ impl ExchangeRateScraper {
             }
         }
 
-        println!(
-            "Cannot parse the timestamp: '{}' online, use current timestamp instead.",
-            timestamp
-        );
+        if self.verbose {
+            println!(
+                "Cannot parse the timestamp: '{}' online, use current timestamp instead.",
+                timestamp
+            );
+        }
         Ok(Utc::now().date_naive())
     }
 
@@ -215,7 +200,9 @@ impl ExchangeRateScraper {
             return Err("2. Cannot find foreign exchange rates data.".into());
         }
 
-        println!("2. Parse {} exchange rates successfully.", rates.len());
+        if self.verbose {
+            println!("2. Parse {} exchange rates successfully.", rates.len());
+        }
         Ok(rates)
     }
 
@@ -234,64 +221,187 @@ impl ExchangeRateScraper {
         }
         None
     }
+}
 
-    pub async fn smart_fetch_multiple(
-        &self,
-        pool: &SqlitePool,
-        currencies: Vec<&str>,
-    ) -> HashMap<String, (Vec<ExchangeRate>, bool)> {
-        let mut results = HashMap::new();
-
-        for currency in currencies {
-            println!("\nScraping {} exchange rates...", currency);
-
-            match self.smart_fetch_exchange_rates(pool, currency).await {
-                Ok((rates, was_up_to_date)) => {
-                    if was_up_to_date {
-                        println!("2. Latest FX Rates of {} are scraped already.", currency)
-                    } else {
-                        println!("3. ✓Success: get {} exchange rates.", rates.len());
-                    }
-                    results.insert(currency.to_string(), (rates, was_up_to_date));
-                }
-                Err(e) => {
-                    eprintln!("3. ✗Error: {}.", e);
-                }
-            }
+impl RateProvider for ExchangeRateScraper {
+    fn name(&self) -> &'static str {
+        PROVIDER
+    }
 
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        }
+    fn kind(&self) -> &'static str {
+        "scraper"
+    }
 
-        results
+    fn fetch_rates<'a>(&'a self, from_currency: &'a str) -> BoxFuture<'a, Result<Vec<ExchangeRate>, String>> {
+        Box::pin(async move { self.fetch_exchange_rates(from_currency).await.map_err(|e| e.to_string()) })
     }
+}
 
-    pub async fn save_to_database(
-        &self,
-        pool: &SqlitePool,
-        rates: &[ExchangeRate],
-    ) -> Result<usize, sqlx::Error> {
-        let now = Utc::now();
-        let mut saved_count = 0;
-
-        for rate in rates {
-            sqlx::query!(
-                r#"
-                INSERT INTO exchange_rates (from_currency, to_currency, rate, rate_date, source)
-                VALUES (?, ?, ?, ?, 'scraper')
-                "#,
-                rate.from_currency,
-                rate.to_currency,
-                rate.rate,
-                now
-            )
-            .execute(pool)
-            .await?;
-
-            saved_count += 1;
+// Account currencies followed by the comma-separated extras (e.g. "USD,EUR"),
+// uppercased and without duplicates; DEFAULT_CURRENCIES when both are empty
+pub fn merge_base_currencies(account_currencies: Vec<String>, extra: &str) -> Vec<String> {
+    let mut currencies: Vec<String> = Vec::new();
+    let candidates = account_currencies
+        .into_iter()
+        .chain(extra.split(',').map(str::to_string))
+        .map(|c| c.trim().to_uppercase())
+        .filter(|c| !c.is_empty());
+    for currency in candidates {
+        if !currencies.contains(&currency) {
+            currencies.push(currency);
         }
+    }
+
+    if currencies.is_empty() {
+        DEFAULT_CURRENCIES.iter().map(|c| c.to_string()).collect()
+    } else {
+        currencies
+    }
+}
+
+// Extra base currencies configured with FX_SCRAPE_CURRENCIES, e.g. "USD,EUR"
+pub fn configured_currencies() -> String {
+    env::var("FX_SCRAPE_CURRENCIES").unwrap_or_default()
+}
+
+// Base currencies scraped by default: those used by accounts (most used first)
+// plus the configured ones
+pub async fn base_currencies(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+    let account_currencies: Vec<String> = sqlx::query_scalar(
+        "SELECT UPPER(currency) FROM accounts GROUP BY UPPER(currency) ORDER BY COUNT(*) DESC, UPPER(currency)",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(merge_base_currencies(account_currencies, &configured_currencies()))
+}
+
+// "success" when every currency was fetched, "failed" when none was, otherwise "partial"
+pub fn run_status(requested: usize, failed: usize) -> &'static str {
+    if failed == 0 {
+        "success"
+    } else if failed >= requested {
+        "failed"
+    } else {
+        "partial"
+    }
+}
+
+pub async fn record_scrape_run(pool: &SqlitePool, metrics: &ScrapeRunMetrics) -> Result<i64, sqlx::Error> {
+    let errors = if metrics.errors.is_empty() {
+        None
+    } else {
+        Some(metrics.errors.join("\n"))
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO scrape_runs (provider, currencies, started_at, duration_ms, rates_saved, currencies_skipped, status, errors)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&metrics.provider)
+    .bind(metrics.currencies.join(","))
+    .bind(metrics.started_at)
+    .bind(metrics.duration.as_millis() as i64)
+    .bind(metrics.rates_saved as i64)
+    .bind(metrics.currencies_skipped as i64)
+    .bind(run_status(metrics.currencies.len(), metrics.errors.len()))
+    .bind(errors)
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+// Latest runs first
+pub async fn recent_scrape_runs(pool: &SqlitePool, limit: i64) -> Result<Vec<ScrapeRun>, sqlx::Error> {
+    sqlx::query_as::<_, ScrapeRun>("SELECT * FROM scrape_runs ORDER BY started_at DESC, id DESC LIMIT ?")
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+// Compare fetched rates with the latest stored rate of each pair (any source).
+// Pairs match by currency code, whether labelled "Euro (EUR)" or "EUR".
+pub async fn diff_against_database(
+    pool: &SqlitePool,
+    rates: &[ExchangeRate],
+) -> Result<Vec<RateDiff>, sqlx::Error> {
+    let mut diffs = Vec::new();
+
+    for rate in rates {
+        let (code, _) = currencies::parse_label(&rate.to_currency);
+        let current: Option<(f64, String)> = sqlx::query_as(
+            "SELECT rate, DATE(rate_date) FROM exchange_rates
+             WHERE from_currency = ? AND (to_currency = ? OR to_currency LIKE ?)
+             ORDER BY rate_date DESC LIMIT 1",
+        )
+        .bind(&rate.from_currency)
+        .bind(&code)
+        .bind(format!("%({})%", code))
+        .fetch_optional(pool)
+        .await?;
+
+        let (current_rate, current_date) = match current {
+            Some((rate, date)) => (Some(rate), Some(date)),
+            None => (None, None),
+        };
+        let (status, change_percent) = classify_change(current_rate, rate.rate);
+
+        diffs.push(RateDiff {
+            from_currency: rate.from_currency.clone(),
+            to_currency: rate.to_currency.clone(),
+            current_rate,
+            current_date,
+            new_rate: rate.rate,
+            new_date: rate.rate_date.format("%Y-%m-%d").to_string(),
+            change_percent,
+            status: status.to_string(),
+        });
+    }
+
+    Ok(diffs)
+}
 
-        Ok(saved_count)
+// "insert" for a new pair, "update" when the rate moved, otherwise "unchanged"
+pub fn classify_change(current: Option<f64>, new: f64) -> (&'static str, Option<f64>) {
+    match current {
+        None => ("insert", None),
+        Some(current) if (current - new).abs() < 1e-9 => ("unchanged", Some(0.0)),
+        Some(current) if current != 0.0 => ("update", Some((new - current) / current * 100.0)),
+        Some(_) => ("update", None),
+    }
+}
+
+// Verbose also lists the pairs that would not change
+pub fn print_rate_diffs(currency: &str, diffs: &[RateDiff], verbose: bool) {
+    println!("
+{} dry run (nothing written):", currency);
+    println!(
+        "  {:<10} {:<32} {:>14} {:>14} {:>9}",
+        "Status", "Currency", "Current", "Scraped", "Change"
+    );
+
+    for diff in diffs.iter().filter(|d| verbose || d.status != "unchanged") {
+        let current = diff
+            .current_rate
+            .map(|r| format!("{:.6}", r))
+            .unwrap_or_else(|| "-".to_string());
+        let change = diff
+            .change_percent
+            .map(|c| format!("{:+.2}%", c))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {:<10} {:<32} {:>14} {:>14.6} {:>9}",
+            diff.status, diff.to_currency, current, diff.new_rate, change
+        );
     }
+
+    let count = |status: &str| diffs.iter().filter(|d| d.status == status).count();
+    println!(
+        "  {} to insert, {} to update, {} unchanged",
+        count("insert"),
+        count("update"),
+        count("unchanged")
+    );
 }
 
 pub fn print_exchange_rates(rates: &[ExchangeRate]) {
@@ -317,3 +427,32 @@ pub fn print_exchange_rates(rates: &[ExchangeRate]) {
 
     println!("╚════════════════════════════════════════╝");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_change() {
+        assert_eq!(classify_change(None, 1.25), ("insert", None));
+        assert_eq!(classify_change(Some(1.25), 1.25), ("unchanged", Some(0.0)));
+
+        let (status, change) = classify_change(Some(1.0), 1.1);
+        assert_eq!(status, "update");
+        assert!((change.unwrap() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge_base_currencies() {
+        let accounts = vec!["JPY".to_string(), "CAD".to_string()];
+        assert_eq!(merge_base_currencies(accounts, "usd, JPY,"), vec!["JPY", "CAD", "USD"]);
+        assert_eq!(merge_base_currencies(Vec::new(), ""), DEFAULT_CURRENCIES.to_vec());
+    }
+
+    #[test]
+    fn test_run_status() {
+        assert_eq!(run_status(4, 0), "success");
+        assert_eq!(run_status(4, 1), "partial");
+        assert_eq!(run_status(4, 4), "failed");
+    }
+}
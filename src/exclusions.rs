@@ -0,0 +1,79 @@
+// exclusions.rs
+// Transactions left out of analytics. A transaction is excluded when it's
+// flagged exclude_from_reports itself (a reimbursement, a one-off anomaly) or
+// is filed under a category that is (e.g. "Reimbursable"). Excluded
+// transactions still move account balances.
+
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+use crate::models::{Category, Transaction};
+
+/// SQL condition that the transactions row aliased `t` counts in reports
+pub const COUNTED: &str = "t.exclude_from_reports = 0 AND NOT EXISTS (
+    SELECT 1 FROM transaction_categories xtc
+    JOIN categories xc ON xc.id = xtc.category_id
+    WHERE xtc.transaction_id = t.id AND xc.exclude_from_reports = 1)";
+
+/// Whether the transaction, filed under `category_ids`, counts in reports;
+/// the in-memory form of COUNTED
+pub fn counts_in_reports(transaction: &Transaction, category_ids: &[i64], excluded_categories: &HashSet<i64>) -> bool {
+    !transaction.exclude_from_reports && !category_ids.iter().any(|id| excluded_categories.contains(id))
+}
+
+pub async fn set_transaction_excluded(pool: &SqlitePool, transaction_id: i64, excluded: bool) -> Result<Transaction, sqlx::Error> {
+    sqlx::query_as::<_, Transaction>(
+        "UPDATE transactions SET exclude_from_reports = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(excluded)
+    .bind(transaction_id)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn set_category_excluded(pool: &SqlitePool, category_id: i64, excluded: bool) -> Result<Category, sqlx::Error> {
+    sqlx::query_as::<_, Category>(
+        "UPDATE categories SET exclude_from_reports = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(excluded)
+    .bind(category_id)
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{charts, reports, testing};
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_excluded_transactions_and_categories_leave_reports() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(1000.0).insert(&pool).await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let work = testing::category(&pool, user.id, "Work travel").await;
+        testing::txn(account.id).amount(80.0).category(groceries.id).insert(&pool).await;
+        let flight = testing::txn(account.id).amount(600.0).category(work.id).insert(&pool).await;
+        let refund = testing::txn(account.id).amount(600.0).income().insert(&pool).await;
+        let now = Utc::now();
+        let start = now - chrono::Duration::days(40);
+
+        let totals = reports::monthly_totals(&pool, user.id, 1, now).await.unwrap();
+        assert_eq!((totals[0].total_income, totals[0].total_expense), (600.0, 680.0));
+
+        let refund = set_transaction_excluded(&pool, refund.id, true).await.unwrap();
+        let work = set_category_excluded(&pool, work.id, true).await.unwrap();
+        let totals = reports::monthly_totals(&pool, user.id, 1, now).await.unwrap();
+        assert_eq!((totals[0].total_income, totals[0].total_expense, totals[0].transaction_count), (0.0, 80.0, 1));
+        let spending = charts::category_spending(&pool, user.id, start, now + chrono::Duration::days(1)).await.unwrap();
+        assert_eq!(spending.len(), 1);
+        assert_eq!(spending[0].category_id, groceries.id);
+
+        let excluded: HashSet<i64> = [work.id].into();
+        assert!(!counts_in_reports(&refund, &[], &excluded));
+        assert!(!counts_in_reports(&flight, &[work.id], &excluded));
+        assert!(counts_in_reports(&flight, &[groceries.id], &excluded));
+    }
+}
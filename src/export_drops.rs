@@ -0,0 +1,262 @@
+// export_drops.rs
+// Scheduled CSV/JSON drops of new transactions into a local directory
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::models::{Account, Transaction};
+
+type DropError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DropSchedule {
+    Daily,
+    Weekly,
+}
+
+impl DropSchedule {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DropSchedule::Daily => "daily",
+            DropSchedule::Weekly => "weekly",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "daily" => Some(DropSchedule::Daily),
+            "weekly" => Some(DropSchedule::Weekly),
+            _ => None,
+        }
+    }
+
+    fn period(&self) -> Duration {
+        match self {
+            DropSchedule::Daily => Duration::days(1),
+            DropSchedule::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DropFormat {
+    Csv,
+    Json,
+}
+
+impl DropFormat {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DropFormat::Csv => "csv",
+            DropFormat::Json => "json",
+        }
+    }
+}
+
+/// Export daemon settings, read from the environment:
+/// - EXPORT_DROP_DIR: target directory (the daemon is disabled when unset)
+/// - EXPORT_DROP_SCHEDULE: daily (default) or weekly
+/// - EXPORT_DROP_FORMAT: csv (default), json or both
+pub struct ExportDropConfig {
+    pub directory: PathBuf,
+    pub schedule: DropSchedule,
+    pub formats: Vec<DropFormat>,
+}
+
+impl ExportDropConfig {
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let directory = match env::var("EXPORT_DROP_DIR") {
+            Ok(dir) if !dir.trim().is_empty() => PathBuf::from(dir.trim()),
+            _ => return Ok(None),
+        };
+
+        let schedule = match env::var("EXPORT_DROP_SCHEDULE") {
+            Ok(s) => DropSchedule::from_str(&s)
+                .ok_or_else(|| format!("EXPORT_DROP_SCHEDULE must be daily or weekly, got '{}'", s))?,
+            Err(_) => DropSchedule::Daily,
+        };
+
+        let formats = match env::var("EXPORT_DROP_FORMAT").as_deref().map(str::to_lowercase) {
+            Ok(f) if f == "json" => vec![DropFormat::Json],
+            Ok(f) if f == "both" => vec![DropFormat::Csv, DropFormat::Json],
+            Ok(f) if f == "csv" => vec![DropFormat::Csv],
+            Ok(f) => return Err(format!("EXPORT_DROP_FORMAT must be csv, json or both, got '{}'", f)),
+            Err(_) => vec![DropFormat::Csv],
+        };
+
+        Ok(Some(ExportDropConfig {
+            directory,
+            schedule,
+            formats,
+        }))
+    }
+}
+
+/// One file written by the export daemon.
+pub struct ExportDropResult {
+    pub file_path: PathBuf,
+    pub row_count: usize,
+}
+
+/// Write a drop for every configured format whose schedule is due (or all of
+/// them when `force` is set). Each drop contains the transactions created
+/// since the previous drop of that format into the same directory.
+pub async fn run_due_drops(
+    pool: &SqlitePool,
+    config: &ExportDropConfig,
+    now: DateTime<Utc>,
+    force: bool,
+) -> Result<Vec<ExportDropResult>, DropError> {
+    std::fs::create_dir_all(&config.directory)?;
+    let directory = config.directory.to_string_lossy().to_string();
+
+    let mut results = Vec::new();
+
+    for format in &config.formats {
+        let last_drop = sqlx::query(
+            "SELECT created_at, last_transaction_id FROM export_drops
+             WHERE directory = ? AND format = ?
+             ORDER BY id DESC LIMIT 1",
+        )
+        .bind(&directory)
+        .bind(format.as_str())
+        .fetch_optional(pool)
+        .await?;
+
+        let (last_run, watermark) = match last_drop {
+            Some(row) => (
+                Some(row.get::<DateTime<Utc>, _>("created_at")),
+                row.get::<i64, _>("last_transaction_id"),
+            ),
+            None => (None, 0),
+        };
+
+        if !force && !is_due(last_run, config.schedule, now) {
+            continue;
+        }
+
+        let transactions = sqlx::query_as::<_, Transaction>(
+            "SELECT * FROM transactions WHERE id > ? ORDER BY id",
+        )
+        .bind(watermark)
+        .fetch_all(pool)
+        .await?;
+
+        let contents = match format {
+            DropFormat::Csv => render_csv(pool, &transactions).await?,
+            DropFormat::Json => serde_json::to_string_pretty(&transactions)?,
+        };
+
+        let file_path = drop_path(&config.directory, *format, now);
+        write_atomically(&file_path, &contents)?;
+
+        let new_watermark = transactions.last().map(|t| t.id).unwrap_or(watermark);
+        sqlx::query(
+            "INSERT INTO export_drops (directory, format, schedule, file_path, row_count, last_transaction_id, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&directory)
+        .bind(format.as_str())
+        .bind(config.schedule.as_str())
+        .bind(file_path.to_string_lossy().to_string())
+        .bind(transactions.len() as i64)
+        .bind(new_watermark)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        results.push(ExportDropResult {
+            file_path,
+            row_count: transactions.len(),
+        });
+    }
+
+    Ok(results)
+}
+
+fn is_due(last_run: Option<DateTime<Utc>>, schedule: DropSchedule, now: DateTime<Utc>) -> bool {
+    match last_run {
+        Some(last_run) => now - last_run >= schedule.period(),
+        None => true,
+    }
+}
+
+/// Dated file name, e.g. transactions_2025-03-10.csv; a second drop on the
+/// same day (forced run) gets the time appended instead of overwriting.
+fn drop_path(directory: &Path, format: DropFormat, now: DateTime<Utc>) -> PathBuf {
+    let dated = directory.join(format!("transactions_{}.{}", now.format("%Y-%m-%d"), format.as_str()));
+    if dated.exists() {
+        directory.join(format!(
+            "transactions_{}.{}",
+            now.format("%Y-%m-%d_%H%M%S"),
+            format.as_str()
+        ))
+    } else {
+        dated
+    }
+}
+
+/// Write to a temporary file first so downstream readers never see a partial drop.
+fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+async fn render_csv(pool: &SqlitePool, transactions: &[Transaction]) -> Result<String, sqlx::Error> {
+    let mut csv = String::from(
+        "id,user_id,account_id,account_name,amount,type,description,date,currency,notes,reference,merchant\n",
+    );
+
+    let accounts: HashMap<i64, Account> = sqlx::query_as::<_, Account>("SELECT * FROM accounts")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|a| (a.id, a))
+        .collect();
+
+    for t in transactions {
+        let (user_id, account_name, currency) = match accounts.get(&t.account_id) {
+            Some(a) => (a.user_id.to_string(), a.name.as_str(), a.currency.as_str()),
+            None => (String::new(), "", ""),
+        };
+
+        csv.push_str(&format!(
+            "{},{},{},\"{}\",{:.2},{},\"{}\",{},{},\"{}\",\"{}\",\"{}\"\n",
+            t.id,
+            user_id,
+            t.account_id,
+            account_name.replace('"', "\"\""),
+            t.amount,
+            t.transaction_type,
+            t.description.as_deref().unwrap_or_default().replace('"', "\"\""),
+            t.transaction_date.format("%Y-%m-%d %H:%M:%S"),
+            currency,
+            t.notes.as_deref().unwrap_or_default().replace('"', "\"\""),
+            t.reference.as_deref().unwrap_or_default().replace('"', "\"\""),
+            t.merchant.as_deref().unwrap_or_default().replace('"', "\"\"")
+        ));
+    }
+
+    Ok(csv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_drop_is_due_after_schedule_period() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+
+        assert!(is_due(None, DropSchedule::Daily, now));
+        assert!(!is_due(Some(now - Duration::hours(23)), DropSchedule::Daily, now));
+        assert!(is_due(Some(now - Duration::hours(24)), DropSchedule::Daily, now));
+        assert!(!is_due(Some(now - Duration::days(6)), DropSchedule::Weekly, now));
+        assert!(is_due(Some(now - Duration::days(7)), DropSchedule::Weekly, now));
+    }
+}
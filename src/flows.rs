@@ -0,0 +1,170 @@
+// flows.rs
+// Cash-flow graph for Sankey charts: income source -> account -> spending category
+
+use std::collections::BTreeMap;
+
+use sqlx::{FromRow, SqlitePool};
+
+use crate::budgets::parse_period;
+use crate::exclusions;
+use crate::models::{CashFlows, FlowEdge, FlowNode};
+
+/// Money moved between one counterpart (income source or spending category)
+/// and one account in the period
+#[derive(Debug, Clone, FromRow)]
+pub struct FlowRow {
+    pub direction: String, // "in" (source -> account) or "out" (account -> category)
+    pub label: String,
+    pub account_id: i64,
+    pub account_name: String,
+    pub amount: f64,
+}
+
+/// Cash flows of a user's accounts in `period` ("YYYY-MM").
+///
+/// Income is attributed to its categories (or "Uncategorized income"),
+/// spending to its split categories (or "Uncategorized"), transfers to
+/// "Transfers" (both legs of one between the user's accounts) and fees to
+/// their fee category (or "Fees").
+pub async fn cash_flows(
+    pool: &SqlitePool,
+    user_id: i64,
+    period: &str,
+) -> Result<CashFlows, sqlx::Error> {
+    let Some((start, end)) = parse_period(period) else {
+        return Ok(build_flows(period.trim(), &[]));
+    };
+
+    let sql = format!(
+        "SELECT direction, label, account_id, account_name, SUM(amount) as amount FROM (
+             SELECT CASE WHEN t.transaction_type = 'income' THEN 'in' ELSE 'out' END as direction,
+                    COALESCE(c.name, CASE
+                        WHEN t.transaction_type = 'transfer' OR t.transfer_group_id IS NOT NULL THEN 'Transfers'
+                        WHEN t.transaction_type = 'income' THEN 'Uncategorized income'
+                        ELSE 'Uncategorized' END) as label,
+                    a.id as account_id, a.name as account_name,
+                    ABS(COALESCE(tc.amount, t.amount)) as amount
+             FROM transactions t
+             INNER JOIN accounts a ON t.account_id = a.id
+             LEFT JOIN transaction_categories tc ON tc.transaction_id = t.id
+             LEFT JOIN categories c ON tc.category_id = c.id
+             WHERE a.user_id = ? AND {counted} AND t.transaction_date >= ? AND t.transaction_date < ?
+             UNION ALL
+             SELECT 'out' as direction, COALESCE(c.name, 'Fees') as label,
+                    a.id as account_id, a.name as account_name, t.fee as amount
+             FROM transactions t
+             INNER JOIN accounts a ON t.account_id = a.id
+             LEFT JOIN categories c ON t.fee_category_id = c.id
+             WHERE a.user_id = ? AND t.fee > 0 AND {counted}
+             AND t.transaction_date >= ? AND t.transaction_date < ?
+         )
+         GROUP BY direction, label, account_id, account_name
+         ORDER BY amount DESC",
+        counted = exclusions::COUNTED
+    );
+    let rows = sqlx::query_as::<_, FlowRow>(&sql)
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(build_flows(period.trim(), &rows))
+}
+
+/// Turn flow rows into de-duplicated nodes and weighted edges.
+/// Node ids are namespaced ("source:", "account:", "category:") so a category
+/// used for both income and spending doesn't create a cycle.
+pub fn build_flows(period: &str, rows: &[FlowRow]) -> CashFlows {
+    let mut nodes: BTreeMap<String, FlowNode> = BTreeMap::new();
+    let mut edges: BTreeMap<(String, String), f64> = BTreeMap::new();
+    let mut total_in = 0.0;
+    let mut total_out = 0.0;
+
+    for row in rows {
+        if row.amount <= 0.0 {
+            continue;
+        }
+
+        let account_id = format!("account:{}", row.account_id);
+        nodes.entry(account_id.clone()).or_insert_with(|| FlowNode {
+            id: account_id.clone(),
+            label: row.account_name.clone(),
+            kind: "account".to_string(),
+        });
+
+        let (kind, prefix) = if row.direction == "in" {
+            ("source", "source")
+        } else {
+            ("category", "category")
+        };
+        let counterpart_id = format!("{}:{}", prefix, row.label);
+        nodes.entry(counterpart_id.clone()).or_insert_with(|| FlowNode {
+            id: counterpart_id.clone(),
+            label: row.label.clone(),
+            kind: kind.to_string(),
+        });
+
+        let key = if row.direction == "in" {
+            total_in += row.amount;
+            (counterpart_id, account_id)
+        } else {
+            total_out += row.amount;
+            (account_id, counterpart_id)
+        };
+        *edges.entry(key).or_insert(0.0) += row.amount;
+    }
+
+    let mut edges: Vec<FlowEdge> = edges
+        .into_iter()
+        .map(|((source, target), value)| FlowEdge { source, target, value })
+        .collect();
+    edges.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+    CashFlows {
+        period: period.to_string(),
+        nodes: nodes.into_values().collect(),
+        edges,
+        total_in,
+        total_out,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(direction: &str, label: &str, account_id: i64, amount: f64) -> FlowRow {
+        FlowRow {
+            direction: direction.to_string(),
+            label: label.to_string(),
+            account_id,
+            account_name: format!("Account {}", account_id),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_build_flows_namespaces_nodes() {
+        let flows = build_flows(
+            "2024-06",
+            &[
+                row("in", "Salary", 1, 3000.0),
+                row("out", "Groceries", 1, 400.0),
+                row("out", "Salary", 2, 50.0),
+                row("out", "Groceries", 2, 0.0),
+            ],
+        );
+
+        // Salary appears as both an income source and a spending category
+        assert_eq!(flows.nodes.len(), 5);
+        assert_eq!(flows.edges.len(), 3);
+        assert_eq!(flows.edges[0].source, "source:Salary");
+        assert_eq!(flows.edges[0].target, "account:1");
+        assert_eq!(flows.total_in, 3000.0);
+        assert_eq!(flows.total_out, 450.0);
+    }
+}
@@ -0,0 +1,529 @@
+use chrono::{DateTime, Datelike, Duration, Months, TimeZone, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::budgets;
+use crate::exclusions;
+use crate::fx;
+use crate::goals;
+use crate::models::{
+    Account, AccountForecast, CashFlowForecast, DiscretionaryBudget, ForecastMonth, RecurringTransaction, SafeToSpend,
+};
+use crate::recurring::{calculate_next_occurrence, charge_currency};
+use crate::transaction_status;
+
+/// Full months of history the cash-flow forecast averages
+pub const HISTORY_MONTHS: u32 = 3;
+
+/// Longest cash-flow forecast, in months
+pub const MAX_FORECAST_MONTHS: u32 = 24;
+
+/// All occurrences of a recurring item falling within [from, to].
+pub fn occurrences_between(
+    recurring: &RecurringTransaction,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<DateTime<Utc>> {
+    let mut occurrences = Vec::new();
+    let mut next = recurring.next_occurrence;
+
+    while next <= to {
+        if recurring.end_date.map(|end| next > end).unwrap_or(false) {
+            break;
+        }
+        if next >= from {
+            occurrences.push(next);
+        }
+        next = calculate_next_occurrence(next, &recurring.frequency);
+    }
+
+    occurrences
+}
+
+/// First instant of the month after `now`.
+pub fn end_of_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap()
+}
+
+/// Discretionary budget for the rest of the current month:
+/// projected income - fixed obligations - savings goals, where a goal with a
+/// target date sets aside its required saving for the days left.
+///
+/// Only active recurring items of the user's accounts are projected; variable
+/// recurring expenses are reported separately and left to the discretionary pool.
+/// Items billed in another currency are converted at the latest rate.
+pub async fn discretionary_budget(
+    pool: &SqlitePool,
+    user_id: i64,
+    now: DateTime<Utc>,
+) -> Result<DiscretionaryBudget, sqlx::Error> {
+    let period_end = end_of_month(now);
+
+    let mut projected_income = 0.0;
+    let mut fixed_obligations = 0.0;
+    let mut variable_obligations = 0.0;
+
+    for (r, total) in due_this_month(pool, user_id, now).await? {
+        if r.transaction_type == "income" {
+            projected_income += total;
+        } else if r.classification == "variable" {
+            variable_obligations += total;
+        } else {
+            fixed_obligations += total;
+        }
+    }
+
+    let days_remaining = (period_end - now).num_days() + 1;
+    let savings_goals: f64 = goals::goals_progress(pool, user_id, now.date_naive())
+        .await?
+        .iter()
+        .filter_map(|g| g.required_monthly)
+        .map(|monthly| monthly * days_remaining as f64 / 30.0)
+        .sum();
+
+    Ok(DiscretionaryBudget {
+        user_id,
+        period_start: now,
+        period_end,
+        days_remaining,
+        projected_income,
+        fixed_obligations,
+        variable_obligations,
+        savings_goals,
+        discretionary: projected_income - fixed_obligations - savings_goals,
+    })
+}
+
+/// Active recurring items of the user's accounts with what they still come to
+/// before the end of the month, in the account's currency at the latest rate
+async fn due_this_month(
+    pool: &SqlitePool,
+    user_id: i64,
+    now: DateTime<Utc>,
+) -> Result<Vec<(RecurringTransaction, f64)>, sqlx::Error> {
+    let period_end = end_of_month(now);
+
+    let recurring = sqlx::query_as::<_, RecurringTransaction>(
+        "SELECT r.* FROM recurring_transactions r
+         JOIN accounts a ON r.account_id = a.id
+         WHERE a.user_id = ? AND r.is_active = 1",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let account_currencies: HashMap<i64, String> =
+        sqlx::query_as::<_, (i64, String)>("SELECT id, currency FROM accounts WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    let mut due = Vec::with_capacity(recurring.len());
+    for r in recurring {
+        let count = occurrences_between(&r, now, period_end - Duration::seconds(1)).len() as f64;
+        let account_currency = account_currencies.get(&r.account_id).map(String::as_str).unwrap_or("");
+        let rate = charge_rate(pool, &r, account_currency).await?;
+        let total = r.amount.abs() * rate * count;
+        due.push((r, total));
+    }
+    Ok(due)
+}
+
+/// Balance the account should have at the end of the month: the actual
+/// balance plus pending transactions and every recurring item due before
+/// then, including overdue ones the processor has not booked yet.
+pub async fn projected_balance(
+    pool: &SqlitePool,
+    account: &Account,
+    now: DateTime<Utc>,
+) -> Result<f64, sqlx::Error> {
+    let mut projected = transaction_status::balance_with_pending(pool, account.id).await?;
+
+    let recurring = sqlx::query_as::<_, RecurringTransaction>(
+        "SELECT * FROM recurring_transactions WHERE account_id = ? AND is_active = 1",
+    )
+    .bind(account.id)
+    .fetch_all(pool)
+    .await?;
+
+    for r in &recurring {
+        let count = occurrences_between(r, r.next_occurrence, end_of_month(now) - Duration::seconds(1)).len() as f64;
+        if count == 0.0 {
+            continue;
+        }
+        let rate = charge_rate(pool, r, &account.currency).await?;
+        projected += transaction_status::balance_effect(&r.transaction_type, r.amount * rate, 0.0) * count;
+    }
+
+    Ok((projected * 100.0).round() / 100.0)
+}
+
+/// Rate from the item's billing currency into the account's, at the latest rate
+async fn charge_rate(pool: &SqlitePool, recurring: &RecurringTransaction, account_currency: &str) -> Result<f64, sqlx::Error> {
+    Ok(match charge_currency(recurring, account_currency) {
+        Some(currency) => fx::conversion_rate(pool, currency, account_currency, None)
+            .await?
+            .unwrap_or(1.0),
+        None => 1.0,
+    })
+}
+
+/// Monthly income and expenses of the account over [start, end), averaged
+/// over `months`. Transfers and the transactions recurring items booked are
+/// left out: the forecast projects those from the items themselves. So are
+/// transactions excluded from reports.
+async fn average_flows(
+    pool: &SqlitePool,
+    account_id: i64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    months: u32,
+) -> Result<(f64, f64), sqlx::Error> {
+    let sql = format!(
+        "SELECT COALESCE(SUM(CASE WHEN t.transaction_type = 'income' THEN t.amount - t.fee ELSE 0.0 END), 0.0),
+                COALESCE(SUM(CASE WHEN t.transaction_type = 'expense' THEN ABS(t.amount) + t.fee ELSE 0.0 END), 0.0)
+         FROM transactions t
+         WHERE t.account_id = ? AND t.status = 'cleared' AND t.transfer_group_id IS NULL
+           AND t.recurring_transaction_id IS NULL AND {counted}
+           AND datetime(t.transaction_date) >= datetime(?) AND datetime(t.transaction_date) < datetime(?)",
+        counted = exclusions::COUNTED
+    );
+    let (income, expenses): (f64, f64) = sqlx::query_as(&sql)
+        .bind(account_id)
+        .bind(start.to_rfc3339())
+        .bind(end.to_rfc3339())
+        .fetch_one(pool)
+        .await?;
+    let months = months.max(1) as f64;
+    Ok((income / months, expenses / months))
+}
+
+fn round2(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Income, expenses and month-end balance of each open account for the
+/// `months` months after the current one.
+///
+/// Each account starts from its projected balance at the end of this month;
+/// every month then adds the active recurring items due in it plus the
+/// account's average income and expenses over the last HISTORY_MONTHS full
+/// months.
+pub async fn cash_flow_forecast(
+    pool: &SqlitePool,
+    user_id: i64,
+    months: u32,
+    now: DateTime<Utc>,
+) -> Result<CashFlowForecast, sqlx::Error> {
+    let first = end_of_month(now);
+    let this_month = first - Months::new(1);
+    let history_start = this_month - Months::new(HISTORY_MONTHS);
+
+    let accounts = sqlx::query_as::<_, Account>(
+        "SELECT * FROM accounts WHERE user_id = ? AND is_archived = 0 ORDER BY is_favorite DESC, sort_order, id",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut forecasts = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        let recurring = sqlx::query_as::<_, RecurringTransaction>(
+            "SELECT * FROM recurring_transactions WHERE account_id = ? AND is_active = 1",
+        )
+        .bind(account.id)
+        .fetch_all(pool)
+        .await?;
+        let mut rates = Vec::with_capacity(recurring.len());
+        for r in &recurring {
+            rates.push(charge_rate(pool, r, &account.currency).await?);
+        }
+
+        let (average_income, average_expenses) =
+            average_flows(pool, account.id, history_start, this_month, HISTORY_MONTHS).await?;
+        let starting_balance = projected_balance(pool, &account, now).await?;
+
+        let mut balance = starting_balance;
+        let mut projected = Vec::with_capacity(months as usize);
+        for ahead in 0..months {
+            let start = first + Months::new(ahead);
+            let end = start + Months::new(1);
+            let (mut income, mut expenses) = (average_income, average_expenses);
+            for (r, rate) in recurring.iter().zip(&rates) {
+                let total = r.amount.abs() * rate * occurrences_between(r, start, end - Duration::seconds(1)).len() as f64;
+                if r.transaction_type == "income" {
+                    income += total;
+                } else {
+                    expenses += total;
+                }
+            }
+            balance += income - expenses;
+            projected.push(ForecastMonth {
+                month: start.format("%Y-%m").to_string(),
+                income: round2(income),
+                expenses: round2(expenses),
+                net: round2(income - expenses),
+                ending_balance: round2(balance),
+            });
+        }
+
+        forecasts.push(AccountForecast {
+            account_id: account.id,
+            account_name: account.name,
+            currency: account.currency,
+            starting_balance,
+            average_income: round2(average_income),
+            average_expenses: round2(average_expenses),
+            months: projected,
+        });
+    }
+
+    Ok(CashFlowForecast {
+        user_id,
+        as_of: now,
+        history_months: HISTORY_MONTHS,
+        accounts: forecasts,
+    })
+}
+
+/// How much can be spent per day until the end of the month.
+///
+/// Starts from the money currently sitting in checking accounts, adds the
+/// income still expected this month and sets aside every upcoming recurring
+/// bill (fixed and variable), savings goals and what is left of this month's
+/// category budgets beyond the bills still due in them, so a budgeted bill
+/// is only set aside once. Never goes below zero per day.
+pub async fn safe_to_spend(
+    pool: &SqlitePool,
+    user_id: i64,
+    now: DateTime<Utc>,
+) -> Result<SafeToSpend, sqlx::Error> {
+    let budget = discretionary_budget(pool, user_id, now).await?;
+
+    let available_balance: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(current_balance), 0.0) FROM accounts
+         WHERE user_id = ? AND account_type = 'checking'",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    let mut bills_by_category: HashMap<i64, f64> = HashMap::new();
+    for (r, total) in due_this_month(pool, user_id, now).await? {
+        if r.transaction_type == "income" {
+            continue;
+        }
+        if let Some(category_id) = r.category_id {
+            *bills_by_category.entry(category_id).or_default() += total;
+        }
+    }
+
+    // Budgets already overspent have nothing left to set aside, and the
+    // recurring bills due in a category are already in upcoming_bills
+    let budgets_remaining: f64 = budgets::budget_variance(pool, user_id, &now.format("%Y-%m").to_string())
+        .await?
+        .iter()
+        .map(|b| {
+            let bills = bills_by_category.get(&b.category_id).copied().unwrap_or(0.0);
+            (b.variance_amount - bills).max(0.0)
+        })
+        .sum();
+
+    let upcoming_bills = budget.fixed_obligations + budget.variable_obligations;
+    let safe = available_balance + budget.projected_income - upcoming_bills - budget.savings_goals - budgets_remaining;
+
+    Ok(SafeToSpend {
+        user_id,
+        as_of: now,
+        days_remaining: budget.days_remaining,
+        available_balance,
+        projected_income: budget.projected_income,
+        upcoming_bills,
+        savings_goals: budget.savings_goals,
+        budgets_remaining,
+        safe_to_spend: safe,
+        safe_to_spend_per_day: per_day(safe, budget.days_remaining),
+    })
+}
+
+/// Spread an amount evenly over the remaining days, clamping overspending to zero.
+fn per_day(amount: f64, days_remaining: i64) -> f64 {
+    if amount <= 0.0 {
+        0.0
+    } else {
+        amount / days_remaining.max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn weekly_item(next: DateTime<Utc>) -> RecurringTransaction {
+        RecurringTransaction {
+            id: 1,
+            account_id: 1,
+            category_id: None,
+            amount: 100.0,
+            transaction_type: "expense".to_string(),
+            description: None,
+            frequency: "weekly".to_string(),
+            start_date: next,
+            end_date: None,
+            next_occurrence: next,
+            is_active: true,
+            created_at: next,
+            updated_at: next,
+            classification: "fixed".to_string(),
+            currency: None,
+            catch_up: "all".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_occurrences_until_month_end() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+        let item = weekly_item(Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap());
+
+        // Mar 12, 19, 26 fall before April 1st
+        let occurrences = occurrences_between(&item, now, end_of_month(now));
+        assert_eq!(occurrences.len(), 3);
+        assert_eq!(end_of_month(now), Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_charge_currency_only_when_different() {
+        let mut item = weekly_item(Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap());
+        assert_eq!(charge_currency(&item, "CAD"), None);
+
+        item.currency = Some("usd".to_string());
+        assert_eq!(charge_currency(&item, "USD"), None);
+        assert_eq!(charge_currency(&item, "CAD"), Some("usd"));
+    }
+
+    #[tokio::test]
+    async fn test_projected_balance_includes_pending_and_recurring() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(1000.0).insert(&pool).await;
+        let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+
+        testing::txn(account.id).amount(30.0).pending().insert(&pool).await;
+        // Mar 12, 19 and 26; an overdue bill from Mar 1; one due next month
+        testing::recurring(account.id)
+            .amount(100.0)
+            .frequency("weekly")
+            .due(Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap())
+            .insert(&pool)
+            .await;
+        testing::recurring(account.id)
+            .due(Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap())
+            .insert(&pool)
+            .await;
+        testing::recurring(account.id)
+            .due(Utc.with_ymd_and_hms(2025, 4, 5, 0, 0, 0).unwrap())
+            .insert(&pool)
+            .await;
+
+        assert_eq!(projected_balance(&pool, &account, now).await.unwrap(), 620.0);
+    }
+
+    #[tokio::test]
+    async fn test_cash_flow_forecast_adds_recurring_items_to_averages() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(1000.0).insert(&pool).await;
+        let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+        let day = |month: u32, day: u32| Utc.with_ymd_and_hms(2025, month, day, 12, 0, 0).unwrap();
+
+        // Dec-Feb: 600 earned and 300 spent, so 200 and 100 a month; March's
+        // own spending is already in the balance
+        testing::txn(account.id).amount(600.0).income().date(day(2, 1)).insert(&pool).await;
+        testing::txn(account.id).amount(150.0).date(day(1, 10)).insert(&pool).await;
+        testing::txn(account.id).amount(150.0).date(day(2, 10)).insert(&pool).await;
+        testing::txn(account.id).amount(80.0).date(day(3, 2)).insert(&pool).await;
+        // A one-off excluded from reports doesn't shift the averages
+        let anomaly = testing::txn(account.id).amount(900.0).date(day(1, 20)).insert(&pool).await;
+        exclusions::set_transaction_excluded(&pool, anomaly.id, true).await.unwrap();
+        // Salary on the 15th; a weekly bill from April 2nd: five in April, four in May
+        testing::recurring(account.id).amount(1000.0).income().due(day(3, 15)).insert(&pool).await;
+        testing::recurring(account.id).amount(50.0).frequency("weekly").due(day(4, 2)).insert(&pool).await;
+
+        let forecast = cash_flow_forecast(&pool, user.id, 2, now).await.unwrap();
+        let projection = &forecast.accounts[0];
+        let start = projected_balance(&pool, &account, now).await.unwrap();
+        assert_eq!(projection.starting_balance, start);
+        assert_eq!((projection.average_income, projection.average_expenses), (200.0, 100.0));
+        let months: Vec<(&str, f64, f64, f64)> = projection
+            .months
+            .iter()
+            .map(|m| (m.month.as_str(), m.income, m.expenses, m.ending_balance - start))
+            .collect();
+        assert_eq!(months, vec![("2025-04", 1200.0, 350.0, 850.0), ("2025-05", 1200.0, 300.0, 1750.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_safe_to_spend_sets_aside_remaining_budgets() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(1000.0).insert(&pool).await;
+        let groceries = testing::category(&pool, user.id, "Groceries").await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        let now = Utc::now();
+        testing::txn(account.id).amount(120.0).category(groceries.id).date(now).insert(&pool).await;
+        testing::txn(account.id).amount(80.0).category(dining.id).date(now).insert(&pool).await;
+
+        let unbudgeted = safe_to_spend(&pool, user.id, now).await.unwrap();
+        assert_eq!(unbudgeted.budgets_remaining, 0.0);
+        assert_eq!(unbudgeted.safe_to_spend, 1000.0);
+
+        // 180 of the grocery budget is still to come; dining is already over
+        budgets::set_budget(&pool, user.id, groceries.id, 300.0).await.unwrap();
+        budgets::set_budget(&pool, user.id, dining.id, 50.0).await.unwrap();
+        let budgeted = safe_to_spend(&pool, user.id, now).await.unwrap();
+        assert_eq!(budgeted.budgets_remaining, 180.0);
+        assert_eq!(budgeted.safe_to_spend, 820.0);
+    }
+
+    #[tokio::test]
+    async fn test_safe_to_spend_sets_aside_a_budgeted_bill_once() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(3000.0).insert(&pool).await;
+        let housing = testing::category(&pool, user.id, "Housing").await;
+        let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+        testing::recurring(account.id)
+            .amount(1000.0)
+            .category(housing.id)
+            .due(Utc.with_ymd_and_hms(2025, 3, 20, 0, 0, 0).unwrap())
+            .insert(&pool)
+            .await;
+
+        // The rent covers the whole Housing budget
+        budgets::set_budget(&pool, user.id, housing.id, 1000.0).await.unwrap();
+        let covered = safe_to_spend(&pool, user.id, now).await.unwrap();
+        assert_eq!((covered.upcoming_bills, covered.budgets_remaining), (1000.0, 0.0));
+        assert_eq!(covered.safe_to_spend, 2000.0);
+
+        // Only the part of the budget beyond the rent is set aside on top
+        budgets::set_budget(&pool, user.id, housing.id, 1200.0).await.unwrap();
+        let beyond = safe_to_spend(&pool, user.id, now).await.unwrap();
+        assert_eq!(beyond.budgets_remaining, 200.0);
+        assert_eq!(beyond.safe_to_spend, 1800.0);
+    }
+
+    #[test]
+    fn test_per_day_clamps_overspending() {
+        assert_eq!(per_day(300.0, 10), 30.0);
+        assert_eq!(per_day(-50.0, 10), 0.0);
+        assert_eq!(per_day(20.0, 0), 20.0);
+    }
+}
@@ -0,0 +1,533 @@
+// fx.rs
+// Exchange-rate lookup for conversions: a pinned manual rate wins outright;
+// otherwise the newest rate date, and when several sources quoted a pair
+// that day, the most trusted source
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::env;
+
+use crate::models::{ConversionResult, ExchangeRate, ExchangeRateSource, StaleRate};
+use crate::settings;
+
+/// Used when FX_SOURCE_PRIORITY is unset: hand-entered and bank rates before feeds.
+pub const DEFAULT_PRIORITY: [ExchangeRateSource; 5] = [
+    ExchangeRateSource::Manual,
+    ExchangeRateSource::Bank,
+    ExchangeRateSource::Api,
+    ExchangeRateSource::Scraper,
+    ExchangeRateSource::Official,
+];
+
+/// Used for tax-related conversions when FX_TAX_SOURCE_PRIORITY is unset:
+/// central-bank reference rates are what tax authorities usually accept.
+pub const TAX_DEFAULT_PRIORITY: [ExchangeRateSource; 5] = [
+    ExchangeRateSource::Official,
+    ExchangeRateSource::Manual,
+    ExchangeRateSource::Bank,
+    ExchangeRateSource::Api,
+    ExchangeRateSource::Scraper,
+];
+
+/// Parse a comma-separated source list, most trusted first, e.g. "api,manual".
+/// Sources left out rank after the listed ones.
+pub fn parse_priority(list: &str) -> Result<Vec<ExchangeRateSource>, String> {
+    let mut priority = Vec::new();
+    for name in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let source = ExchangeRateSource::from_str(&name.to_lowercase()).ok_or_else(|| {
+            format!("Unknown rate source '{}' (expected api, bank, manual, scraper or official)", name)
+        })?;
+        if !priority.contains(&source) {
+            priority.push(source);
+        }
+    }
+    if priority.is_empty() {
+        return Err("Source priority cannot be empty".to_string());
+    }
+    Ok(priority)
+}
+
+/// Source priority configured with FX_SOURCE_PRIORITY; invalid values fall back to the default.
+pub fn source_priority() -> Vec<ExchangeRateSource> {
+    env::var("FX_SOURCE_PRIORITY")
+        .ok()
+        .and_then(|list| parse_priority(&list).ok())
+        .unwrap_or_else(|| DEFAULT_PRIORITY.to_vec())
+}
+
+/// Source priority for tax-related conversions, configured with FX_TAX_SOURCE_PRIORITY.
+pub fn tax_source_priority() -> Vec<ExchangeRateSource> {
+    env::var("FX_TAX_SOURCE_PRIORITY")
+        .ok()
+        .and_then(|list| parse_priority(&list).ok())
+        .unwrap_or_else(|| TAX_DEFAULT_PRIORITY.to_vec())
+}
+
+/// Rates older than this many days are flagged as stale when FX_STALE_AFTER_DAYS is unset.
+pub const DEFAULT_STALE_AFTER_DAYS: i64 = 7;
+
+/// Stale threshold in days, read from FX_STALE_AFTER_DAYS.
+pub fn stale_after_days() -> i64 {
+    env::var("FX_STALE_AFTER_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|v| *v >= 0)
+        .unwrap_or(DEFAULT_STALE_AFTER_DAYS)
+}
+
+/// Whole days since the rate was quoted.
+pub fn age_days(rate_date: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (now - rate_date).num_days().max(0)
+}
+
+pub fn is_stale(rate_date: DateTime<Utc>, now: DateTime<Utc>, max_age_days: i64) -> bool {
+    age_days(rate_date, now) > max_age_days
+}
+
+fn rank(priority: &[ExchangeRateSource], source: &str) -> usize {
+    ExchangeRateSource::from_str(source)
+        .and_then(|s| priority.iter().position(|p| *p == s))
+        .unwrap_or(priority.len())
+}
+
+/// Preferred rate among candidates for one pair: a pinned rate, then the
+/// latest calendar day, source priority and latest timestamp within that day.
+pub fn pick_rate<'a>(rates: &'a [ExchangeRate], priority: &[ExchangeRateSource]) -> Option<&'a ExchangeRate> {
+    rates.iter().min_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.rate_date.date_naive().cmp(&a.rate_date.date_naive()))
+            .then_with(|| rank(priority, &a.source).cmp(&rank(priority, &b.source)))
+            .then_with(|| b.rate_date.cmp(&a.rate_date))
+    })
+}
+
+/// Keep the preferred rate of every currency pair.
+pub fn preferred_per_pair(rates: Vec<ExchangeRate>, priority: &[ExchangeRateSource]) -> Vec<ExchangeRate> {
+    let mut pairs: Vec<(String, String)> = rates
+        .iter()
+        .map(|r| (r.from_currency.clone(), r.to_currency.clone()))
+        .collect();
+    pairs.sort();
+    pairs.dedup();
+
+    pairs
+        .into_iter()
+        .filter_map(|(from, to)| {
+            let candidates: Vec<ExchangeRate> = rates
+                .iter()
+                .filter(|r| r.from_currency == from && r.to_currency == to)
+                .cloned()
+                .collect();
+            pick_rate(&candidates, priority).cloned()
+        })
+        .collect()
+}
+
+/// Rate used to convert from one currency to another, only considering rates
+/// quoted on or before `as_of` when given (pinned rates always apply).
+/// `to_currency` matches either a bare code or a scraped "Name (CODE)" label.
+pub async fn find_rate_as_of(
+    pool: &SqlitePool,
+    from_currency: &str,
+    to_currency: &str,
+    as_of: Option<DateTime<Utc>>,
+    priority: &[ExchangeRateSource],
+) -> Result<Option<ExchangeRate>, sqlx::Error> {
+    let as_of = as_of.map(|d| d.format("%Y-%m-%d").to_string());
+    let candidates = sqlx::query_as::<_, ExchangeRate>(
+        "SELECT * FROM exchange_rates
+         WHERE from_currency = ? AND (to_currency = ? OR to_currency LIKE ?)
+         AND (pinned = 1 OR DATE(rate_date) = (
+             SELECT MAX(DATE(rate_date)) FROM exchange_rates
+             WHERE from_currency = ? AND (to_currency = ? OR to_currency LIKE ?)
+             AND (? IS NULL OR DATE(rate_date) <= ?)
+         ))",
+    )
+    .bind(from_currency)
+    .bind(to_currency)
+    .bind(format!("%({})%", to_currency))
+    .bind(from_currency)
+    .bind(to_currency)
+    .bind(format!("%({})%", to_currency))
+    .bind(&as_of)
+    .bind(&as_of)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(pick_rate(&candidates, priority).cloned())
+}
+
+/// Rate for the pair quoted on the calendar day closest to `date`, before or
+/// after it (the earlier day on a tie), for converting a past transaction at
+/// roughly what the currency was worth then. Pinned rates always apply.
+pub async fn find_rate_near(
+    pool: &SqlitePool,
+    from_currency: &str,
+    to_currency: &str,
+    date: NaiveDate,
+    priority: &[ExchangeRateSource],
+) -> Result<Option<ExchangeRate>, sqlx::Error> {
+    let candidates = sqlx::query_as::<_, ExchangeRate>(
+        "SELECT * FROM exchange_rates
+         WHERE from_currency = ? AND (to_currency = ? OR to_currency LIKE ?)
+         AND (pinned = 1 OR DATE(rate_date) = (
+             SELECT DATE(rate_date) AS day FROM exchange_rates
+             WHERE from_currency = ? AND (to_currency = ? OR to_currency LIKE ?)
+             GROUP BY day
+             ORDER BY ABS(julianday(day) - julianday(?)), day
+             LIMIT 1
+         ))",
+    )
+    .bind(from_currency)
+    .bind(to_currency)
+    .bind(format!("%({})%", to_currency))
+    .bind(from_currency)
+    .bind(to_currency)
+    .bind(format!("%({})%", to_currency))
+    .bind(date.format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(pick_rate(&candidates, priority).cloned())
+}
+
+/// Rates for converting many amounts, each at the rate closest to its own
+/// date, looking every pair and day up once. Used where a list of past
+/// transactions is shown in another currency, e.g. the TUI's "View in
+/// Currency" and its reports.
+pub struct Converter<'a> {
+    pool: &'a SqlitePool,
+    priority: Vec<ExchangeRateSource>,
+    rates: HashMap<(String, String, NaiveDate), Option<f64>>,
+}
+
+impl<'a> Converter<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Converter { pool, priority: source_priority(), rates: HashMap::new() }
+    }
+
+    /// Multiplier from `from_currency` into `to_currency` near `date`, using
+    /// the inverse of the opposite pair when only that one is quoted
+    pub async fn rate(
+        &mut self,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+    ) -> Result<Option<f64>, sqlx::Error> {
+        if from_currency.eq_ignore_ascii_case(to_currency) {
+            return Ok(Some(1.0));
+        }
+        let key = (from_currency.to_string(), to_currency.to_string(), date);
+        if let Some(rate) = self.rates.get(&key) {
+            return Ok(*rate);
+        }
+
+        let rate = match find_rate_near(self.pool, from_currency, to_currency, date, &self.priority).await? {
+            Some(rate) => Some(rate.rate),
+            None => find_rate_near(self.pool, to_currency, from_currency, date, &self.priority)
+                .await?
+                .filter(|r| r.rate != 0.0)
+                .map(|r| 1.0 / r.rate),
+        };
+        self.rates.insert(key, rate);
+        Ok(rate)
+    }
+}
+
+/// Multiplier converting `from_currency` amounts into `to_currency` as of a
+/// date (latest when None), using the inverse of the opposite pair when only
+/// that one is quoted. Same currency converts at 1.
+pub async fn conversion_rate(
+    pool: &SqlitePool,
+    from_currency: &str,
+    to_currency: &str,
+    as_of: Option<DateTime<Utc>>,
+) -> Result<Option<f64>, sqlx::Error> {
+    if from_currency.eq_ignore_ascii_case(to_currency) {
+        return Ok(Some(1.0));
+    }
+    let priority = source_priority();
+
+    if let Some(rate) = find_rate_as_of(pool, from_currency, to_currency, as_of, &priority).await? {
+        return Ok(Some(rate.rate));
+    }
+    Ok(find_rate_as_of(pool, to_currency, from_currency, as_of, &priority)
+        .await?
+        .filter(|r| r.rate != 0.0)
+        .map(|r| 1.0 / r.rate))
+}
+
+/// Convert an amount at the rate quoted for the pair as of a date (latest
+/// when None), reporting which rate was used and how old it is. None when the
+/// pair has no rate by then. Shared by the convert endpoint and CLI command.
+pub async fn convert(
+    pool: &SqlitePool,
+    from_currency: &str,
+    to_currency: &str,
+    amount: f64,
+    as_of: Option<DateTime<Utc>>,
+    priority: &[ExchangeRateSource],
+) -> Result<Option<ConversionResult>, sqlx::Error> {
+    let rate = find_rate_as_of(pool, from_currency, to_currency, as_of, priority).await?;
+    Ok(rate.map(|rate| conversion_result(from_currency, to_currency, amount, rate, as_of)))
+}
+
+/// Like convert, but at the rate quoted closest to `date` (see find_rate_near)
+/// rather than the latest one on or before it, e.g. for a past transaction
+/// entered before that day's rates were fetched
+pub async fn convert_near(
+    pool: &SqlitePool,
+    from_currency: &str,
+    to_currency: &str,
+    amount: f64,
+    date: NaiveDate,
+    priority: &[ExchangeRateSource],
+) -> Result<Option<ConversionResult>, sqlx::Error> {
+    let rate = find_rate_near(pool, from_currency, to_currency, date, priority).await?;
+    let reference = date.and_hms_opt(23, 59, 59).map(|d| d.and_utc());
+    Ok(rate.map(|rate| conversion_result(from_currency, to_currency, amount, rate, reference)))
+}
+
+fn conversion_result(
+    from_currency: &str,
+    to_currency: &str,
+    amount: f64,
+    rate: ExchangeRate,
+    as_of: Option<DateTime<Utc>>,
+) -> ConversionResult {
+    // Age against the requested date, so a historical conversion isn't stale
+    // just because it's in the past
+    let reference = as_of.unwrap_or_else(Utc::now);
+    ConversionResult {
+        from_currency: from_currency.to_string(),
+        to_currency: to_currency.to_string(),
+        amount,
+        rate: rate.rate,
+        converted_amount: amount * rate.rate,
+        source: rate.source,
+        rate_date: rate.rate_date,
+        rate_age_days: age_days(rate.rate_date, reference),
+        stale: is_stale(rate.rate_date, reference, stale_after_days()),
+    }
+}
+
+/// Value of an amount on the account in its owner's base currency (the
+/// base_currency setting) at the rate as of `date`, for storing with a new
+/// transaction. None when no base currency is set, the account already uses
+/// it, or no rate is known yet.
+pub async fn base_amount_snapshot(
+    pool: &SqlitePool,
+    account_id: i64,
+    amount: f64,
+    date: DateTime<Utc>,
+) -> Result<Option<(f64, String)>, sqlx::Error> {
+    let base: Option<(String, String)> = sqlx::query_as(
+        "SELECT a.currency, s.value FROM accounts a
+         JOIN settings s ON s.user_id = a.user_id AND s.key = ?
+         WHERE a.id = ?",
+    )
+    .bind(settings::BASE_CURRENCY)
+    .bind(account_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((currency, base_currency)) = base.filter(|(c, b)| !c.eq_ignore_ascii_case(b)) else {
+        return Ok(None);
+    };
+    Ok(conversion_rate(pool, &currency, &base_currency, Some(date))
+        .await?
+        .map(|rate| ((amount * rate * 100.0).round() / 100.0, base_currency)))
+}
+
+/// The rate each pair currently converts at: quotes from the pair's latest
+/// day plus any pinned rate, reduced with pick_rate.
+pub async fn effective_rates(
+    pool: &SqlitePool,
+    priority: &[ExchangeRateSource],
+) -> Result<Vec<ExchangeRate>, sqlx::Error> {
+    let rates = sqlx::query_as::<_, ExchangeRate>(
+        "SELECT e1.* FROM exchange_rates e1
+         INNER JOIN (
+             SELECT from_currency, to_currency, MAX(DATE(rate_date)) as max_day
+             FROM exchange_rates
+             GROUP BY from_currency, to_currency
+         ) e2 ON e1.from_currency = e2.from_currency
+              AND e1.to_currency = e2.to_currency
+              AND (DATE(e1.rate_date) = e2.max_day OR e1.pinned = 1)
+         ORDER BY e1.from_currency, e1.to_currency",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(preferred_per_pair(rates, priority))
+}
+
+/// Pairs whose effective rate is older than `max_age_days`, oldest first.
+pub async fn stale_rates(
+    pool: &SqlitePool,
+    now: DateTime<Utc>,
+    max_age_days: i64,
+) -> Result<Vec<StaleRate>, sqlx::Error> {
+    let mut stale: Vec<StaleRate> = effective_rates(pool, &source_priority())
+        .await?
+        .into_iter()
+        .filter(|r| is_stale(r.rate_date, now, max_age_days))
+        .map(|r| StaleRate {
+            age_days: age_days(r.rate_date, now),
+            from_currency: r.from_currency,
+            to_currency: r.to_currency,
+            rate: r.rate,
+            source: r.source,
+            pinned: r.pinned,
+            rate_date: r.rate_date,
+        })
+        .collect();
+
+    stale.sort_by_key(|r| std::cmp::Reverse(r.age_days));
+    Ok(stale)
+}
+
+/// Pin or unpin a rate. Pinning first unpins any other rate of the same pair;
+/// the schema only allows manual rates to be pinned.
+pub async fn set_pinned(pool: &SqlitePool, rate_id: i64, pinned: bool) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    if pinned {
+        sqlx::query(
+            "UPDATE exchange_rates SET pinned = 0
+             WHERE pinned = 1 AND id != ?
+             AND (from_currency, to_currency) = (SELECT from_currency, to_currency FROM exchange_rates WHERE id = ?)",
+        )
+        .bind(rate_id)
+        .bind(rate_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE exchange_rates SET pinned = ? WHERE id = ?")
+        .bind(pinned)
+        .bind(rate_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn rate(source: &str, day: u32, hour: u32, value: f64) -> ExchangeRate {
+        let at = Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap();
+        ExchangeRate {
+            id: 0,
+            from_currency: "USD".to_string(),
+            to_currency: "EUR".to_string(),
+            rate: value,
+            rate_date: at,
+            source: source.to_string(),
+            created_at: at,
+            updated_at: at,
+            pinned: false,
+            rate_source_id: None,
+        }
+    }
+
+    #[test]
+    fn test_pick_rate_prefers_trusted_source_on_same_day() {
+        let rates = vec![
+            rate("scraper", 10, 18, 0.91),
+            rate("manual", 10, 9, 0.92),
+            rate("scraper", 9, 12, 0.90),
+        ];
+
+        assert_eq!(pick_rate(&rates, &DEFAULT_PRIORITY).unwrap().rate, 0.92);
+
+        let scraper_first = parse_priority("scraper, manual").unwrap();
+        assert_eq!(pick_rate(&rates, &scraper_first).unwrap().rate, 0.91);
+
+        // A newer day wins over a more trusted source
+        let rates = vec![rate("manual", 9, 9, 0.92), rate("scraper", 10, 9, 0.91)];
+        assert_eq!(pick_rate(&rates, &DEFAULT_PRIORITY).unwrap().rate, 0.91);
+
+        assert!(parse_priority("manual,ecb").is_err());
+    }
+
+    #[test]
+    fn test_pinned_rate_beats_newer_quotes() {
+        let mut pinned = rate("manual", 1, 9, 0.95);
+        pinned.pinned = true;
+        let rates = vec![rate("scraper", 10, 9, 0.91), pinned, rate("api", 10, 12, 0.90)];
+
+        let scraper_first = parse_priority("scraper").unwrap();
+        assert_eq!(pick_rate(&rates, &scraper_first).unwrap().rate, 0.95);
+    }
+
+    #[test]
+    fn test_stale_after_threshold() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+
+        assert_eq!(age_days(now - chrono::Duration::hours(30), now), 1);
+        assert!(!is_stale(now - chrono::Duration::days(7), now, 7));
+        assert!(is_stale(now - chrono::Duration::days(8), now, 7));
+    }
+
+    #[tokio::test]
+    async fn test_base_amount_snapshot_uses_rate_of_transaction_date() {
+        let pool = crate::testing::pool().await;
+        let user = crate::testing::user().insert(&pool).await;
+        let usd = crate::testing::account(user.id).insert(&pool).await;
+        let eur = crate::testing::account(user.id).currency("EUR").insert(&pool).await;
+        let march = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+        crate::testing::rate(&pool, "USD", "EUR", 0.9, march).await;
+        crate::testing::rate(&pool, "USD", "EUR", 0.5, march + chrono::Duration::days(30)).await;
+
+        // Opt-in: nothing is recorded until a base currency is set
+        assert_eq!(base_amount_snapshot(&pool, usd.id, 100.0, march).await.unwrap(), None);
+
+        settings::set(&pool, user.id, settings::BASE_CURRENCY, Some("EUR")).await.unwrap();
+        let snapshot = base_amount_snapshot(&pool, usd.id, 100.0, march).await.unwrap();
+        assert_eq!(snapshot, Some((90.0, "EUR".to_string())));
+        assert_eq!(base_amount_snapshot(&pool, eur.id, 100.0, march).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_convert_uses_rate_as_of_date() {
+        let pool = crate::testing::pool().await;
+        let march = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+        crate::testing::rate(&pool, "USD", "EUR", 0.9, march).await;
+        crate::testing::rate(&pool, "USD", "EUR", 0.5, march + chrono::Duration::days(30)).await;
+
+        let latest = convert(&pool, "USD", "EUR", 100.0, None, &DEFAULT_PRIORITY).await.unwrap().unwrap();
+        assert_eq!(latest.converted_amount, 50.0);
+
+        let as_of = march + chrono::Duration::days(3);
+        let historical = convert(&pool, "USD", "EUR", 100.0, Some(as_of), &DEFAULT_PRIORITY).await.unwrap().unwrap();
+        assert_eq!((historical.converted_amount, historical.rate_date), (90.0, march));
+        assert_eq!(historical.rate_age_days, 3);
+        assert!(convert(&pool, "USD", "JPY", 100.0, None, &DEFAULT_PRIORITY).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_converter_uses_rate_closest_to_date() {
+        let pool = crate::testing::pool().await;
+        let march = Utc.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+        crate::testing::rate(&pool, "USD", "EUR", 0.9, march).await;
+        crate::testing::rate(&pool, "USD", "EUR", 0.5, march + chrono::Duration::days(30)).await;
+        let day = |offset: i64| (march + chrono::Duration::days(offset)).date_naive();
+
+        let mut converter = Converter::new(&pool);
+        // Before any rate, the earliest one is closest; a tie takes the earlier day
+        assert_eq!(converter.rate("USD", "EUR", day(-20)).await.unwrap(), Some(0.9));
+        assert_eq!(converter.rate("USD", "EUR", day(15)).await.unwrap(), Some(0.9));
+        assert_eq!(converter.rate("USD", "EUR", day(25)).await.unwrap(), Some(0.5));
+        assert_eq!(converter.rate("EUR", "USD", day(29)).await.unwrap(), Some(2.0));
+        assert_eq!(converter.rate("USD", "JPY", day(0)).await.unwrap(), None);
+
+        let near = convert_near(&pool, "USD", "EUR", 10.0, day(-2), &DEFAULT_PRIORITY).await.unwrap().unwrap();
+        assert_eq!((near.converted_amount, near.rate_date, near.rate_age_days), (9.0, march, 0));
+    }
+}
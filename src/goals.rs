@@ -0,0 +1,257 @@
+// goals.rs
+// Savings goals: a target amount saved up in one or more linked accounts of
+// the goal's currency, optionally by a target date. Progress is the linked
+// accounts' balance; the net cleared inflow into them over the last 90 days
+// gives the saving rate the completion date is projected from.
+
+use chrono::{Duration, NaiveDate};
+use sqlx::SqlitePool;
+
+use crate::models::{Account, CreateGoal, Goal, GoalProgress, UpdateGoal};
+
+/// Days of history the saving rate is measured over
+const SAVING_RATE_DAYS: i64 = 90;
+
+/// Saving rates and required contributions are quoted per 30 days
+const MONTH_DAYS: f64 = 30.0;
+
+/// Why these accounts can't be linked to a goal of `user_id` in `currency`,
+/// if anything
+pub fn validate_accounts(user_id: i64, currency: &str, accounts: &[Account]) -> Result<(), String> {
+    for account in accounts {
+        if account.user_id != user_id {
+            return Err(format!("Account {} doesn't belong to this user", account.id));
+        }
+        if account.currency != currency {
+            return Err(format!(
+                "Account {} is in {}, the goal is in {}",
+                account.id, account.currency, currency
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// When the target is reached at `monthly_rate` (today when it already is,
+/// None when nothing is being saved), and the saving per 30 days that reaches
+/// it by `target_date` (all of what's left once the date has come).
+pub fn project(
+    saved: f64,
+    target: f64,
+    monthly_rate: f64,
+    today: NaiveDate,
+    target_date: Option<NaiveDate>,
+) -> (Option<NaiveDate>, Option<f64>) {
+    let remaining = (target - saved).max(0.0);
+    let completion = if remaining == 0.0 {
+        Some(today)
+    } else if monthly_rate > 0.0 {
+        let days = (remaining / (monthly_rate / MONTH_DAYS)).ceil() as i64;
+        today.checked_add_signed(Duration::days(days))
+    } else {
+        None
+    };
+    let required = target_date.map(|date| {
+        let days_left = (date - today).num_days();
+        if days_left <= 0 {
+            remaining
+        } else {
+            remaining / (days_left as f64 / MONTH_DAYS).max(1.0)
+        }
+    });
+    (completion, required)
+}
+
+/// Linked accounts that exist, in the order asked for; ids that don't are
+/// returned separately
+pub async fn find_accounts(pool: &SqlitePool, ids: &[i64]) -> Result<(Vec<Account>, Vec<i64>), sqlx::Error> {
+    let mut found = Vec::with_capacity(ids.len());
+    let mut missing = Vec::new();
+    for &id in ids {
+        match sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+        {
+            Some(account) => found.push(account),
+            None => missing.push(id),
+        }
+    }
+    Ok((found, missing))
+}
+
+async fn link_accounts(
+    conn: &mut sqlx::SqliteConnection,
+    goal_id: i64,
+    account_ids: &[i64],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM goal_accounts WHERE goal_id = ?")
+        .bind(goal_id)
+        .execute(&mut *conn)
+        .await?;
+    for account_id in account_ids {
+        sqlx::query("INSERT OR IGNORE INTO goal_accounts (goal_id, account_id) VALUES (?, ?)")
+            .bind(goal_id)
+            .bind(account_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Create a goal in `currency` linked to `data.account_ids`.
+/// Call validate_accounts first.
+pub async fn create_goal(pool: &SqlitePool, data: &CreateGoal, currency: &str) -> Result<Goal, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let goal = sqlx::query_as::<_, Goal>(
+        "INSERT INTO goals (user_id, name, target_amount, currency, target_date) VALUES (?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(data.user_id)
+    .bind(data.name.trim())
+    .bind(data.target_amount)
+    .bind(currency)
+    .bind(data.target_date)
+    .fetch_one(&mut *tx)
+    .await?;
+    link_accounts(&mut tx, goal.id, &data.account_ids).await?;
+    tx.commit().await?;
+    Ok(goal)
+}
+
+/// Apply an update; None when the goal doesn't exist.
+/// Call validate_accounts first when account_ids is given.
+pub async fn update_goal(pool: &SqlitePool, goal_id: i64, data: &UpdateGoal) -> Result<Option<Goal>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let goal = sqlx::query_as::<_, Goal>(
+        "UPDATE goals SET name = COALESCE(?, name), target_amount = COALESCE(?, target_amount),
+                target_date = COALESCE(?, target_date)
+         WHERE id = ? RETURNING *",
+    )
+    .bind(data.name.as_deref().map(str::trim))
+    .bind(data.target_amount)
+    .bind(data.target_date)
+    .bind(goal_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if let (Some(goal), Some(account_ids)) = (&goal, &data.account_ids) {
+        link_accounts(&mut tx, goal.id, account_ids).await?;
+    }
+    tx.commit().await?;
+    Ok(goal)
+}
+
+pub async fn goals_for_user(pool: &SqlitePool, user_id: i64) -> Result<Vec<Goal>, sqlx::Error> {
+    sqlx::query_as::<_, Goal>("SELECT * FROM goals WHERE user_id = ? ORDER BY target_date IS NULL, target_date, name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// The goal's progress as of `today`
+pub async fn goal_progress(pool: &SqlitePool, goal: Goal, today: NaiveDate) -> Result<GoalProgress, sqlx::Error> {
+    let account_ids: Vec<i64> =
+        sqlx::query_scalar("SELECT account_id FROM goal_accounts WHERE goal_id = ? ORDER BY account_id")
+            .bind(goal.id)
+            .fetch_all(pool)
+            .await?;
+    let saved: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(a.current_balance), 0.0) FROM goal_accounts g
+         JOIN accounts a ON g.account_id = a.id
+         WHERE g.goal_id = ?",
+    )
+    .bind(goal.id)
+    .fetch_one(pool)
+    .await?;
+    // Transfers between two of the linked accounts cancel out
+    let since = (today - Duration::days(SAVING_RATE_DAYS)).and_time(chrono::NaiveTime::MIN).and_utc();
+    let inflow: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(CASE WHEN t.transaction_type = 'income' THEN t.amount ELSE -ABS(t.amount) END - t.fee), 0.0)
+         FROM transactions t
+         JOIN goal_accounts g ON t.account_id = g.account_id
+         WHERE g.goal_id = ? AND t.status = 'cleared' AND t.transaction_date >= ?",
+    )
+    .bind(goal.id)
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+
+    let monthly_saving_rate = inflow / SAVING_RATE_DAYS as f64 * MONTH_DAYS;
+    let (projected_completion, required_monthly) =
+        project(saved, goal.target_amount, monthly_saving_rate, today, goal.target_date);
+    let on_track = goal
+        .target_date
+        .map(|date| projected_completion.is_some_and(|done| done <= date));
+    Ok(GoalProgress {
+        account_ids,
+        saved,
+        remaining: (goal.target_amount - saved).max(0.0),
+        percent: (saved / goal.target_amount * 100.0).clamp(0.0, 100.0),
+        monthly_saving_rate,
+        projected_completion,
+        required_monthly,
+        on_track,
+        goal,
+    })
+}
+
+pub async fn goals_progress(pool: &SqlitePool, user_id: i64, today: NaiveDate) -> Result<Vec<GoalProgress>, sqlx::Error> {
+    let mut progress = Vec::new();
+    for goal in goals_for_user(pool, user_id).await? {
+        progress.push(goal_progress(pool, goal, today).await?);
+    }
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_goal_progress_projects_completion_from_saving_rate() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let savings = testing::account(user.id).name("Savings").balance(1500.0).insert(&pool).await;
+        let euros = testing::account(user.id).name("Euro").currency("EUR").insert(&pool).await;
+        testing::account(user.id).name("Checking").balance(900.0).insert(&pool).await;
+        let stranger = testing::user().insert(&pool).await;
+        let foreign = testing::account(stranger.id).insert(&pool).await;
+        assert!(validate_accounts(user.id, "USD", &[savings.clone(), euros]).is_err());
+        assert!(validate_accounts(user.id, "USD", &[foreign]).is_err());
+        validate_accounts(user.id, "USD", std::slice::from_ref(&savings)).unwrap();
+
+        // 900 saved over the last 90 days: 300 per 30 days
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        for days_ago in [10, 40, 70] {
+            let date = Utc.from_utc_datetime(&(today - Duration::days(days_ago)).and_hms_opt(12, 0, 0).unwrap());
+            testing::txn(savings.id).amount(300.0).income().date(date).insert(&pool).await;
+        }
+        let old = Utc.with_ymd_and_hms(2024, 1, 5, 12, 0, 0).unwrap();
+        testing::txn(savings.id).amount(600.0).income().date(old).insert(&pool).await;
+
+        let data = CreateGoal {
+            user_id: user.id,
+            name: " Emergency fund ".to_string(),
+            target_amount: 3000.0,
+            currency: None,
+            target_date: NaiveDate::from_ymd_opt(2025, 9, 29),
+            account_ids: vec![savings.id],
+        };
+        let goal = create_goal(&pool, &data, "USD").await.unwrap();
+        assert_eq!(goal.name, "Emergency fund");
+
+        let progress = goal_progress(&pool, goal, today).await.unwrap();
+        assert_eq!((progress.saved, progress.remaining, progress.percent), (1500.0, 1500.0, 50.0));
+        assert_eq!(progress.monthly_saving_rate, 300.0);
+        // 1500 left at 10 a day
+        assert_eq!(progress.projected_completion, NaiveDate::from_ymd_opt(2025, 10, 29));
+        // 120 days to the target date: 375 per 30 days
+        assert_eq!(progress.required_monthly, Some(375.0));
+        assert_eq!(progress.on_track, Some(false));
+
+        let (done, required) = project(3200.0, 3000.0, 0.0, today, None);
+        assert_eq!((done, required), (Some(today), None));
+        assert_eq!(project(100.0, 3000.0, -50.0, today, None).0, None);
+    }
+}
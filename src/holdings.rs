@@ -0,0 +1,194 @@
+// holdings.rs
+// Investment holdings: positions of a ticker in an investment account, with
+// the total paid for them. A holding is valued at the latest fetched quote of
+// its ticker (see prices.rs), taken to be in the account's currency; until a
+// quote arrives it counts at cost.
+
+use sqlx::SqlitePool;
+
+use crate::models::{Account, AccountHoldings, CreateHolding, Holding, HoldingValue, SecurityPrice, UpdateHolding};
+
+/// Tickers are stored trimmed and in upper case
+pub fn normalize_ticker(ticker: &str) -> String {
+    ticker.trim().to_uppercase()
+}
+
+/// Why holdings can't be kept in this account, if anything
+pub fn validate_account(account: &Account) -> Result<(), String> {
+    if account.account_type != "investment" {
+        return Err(format!("Account {} is not an investment account", account.id));
+    }
+    Ok(())
+}
+
+/// Value each holding at its quote and total them up for the account
+pub fn valuation(account: &Account, holdings: Vec<(Holding, Option<SecurityPrice>)>) -> AccountHoldings {
+    let mut values: Vec<HoldingValue> = holdings
+        .into_iter()
+        .map(|(holding, quote)| {
+            let market_value = match &quote {
+                Some(quote) => quote.price * holding.quantity,
+                None => holding.cost_basis,
+            };
+            let gain = market_value - holding.cost_basis;
+            HoldingValue {
+                price: quote.as_ref().map(|q| q.price),
+                price_date: quote.as_ref().map(|q| q.price_date),
+                market_value,
+                gain,
+                gain_percent: (holding.cost_basis > 0.0).then(|| gain / holding.cost_basis * 100.0),
+                allocation: 0.0,
+                holding,
+            }
+        })
+        .collect();
+
+    let market_value: f64 = values.iter().map(|v| v.market_value).sum();
+    let cost_basis: f64 = values.iter().map(|v| v.holding.cost_basis).sum();
+    if market_value > 0.0 {
+        for value in &mut values {
+            value.allocation = value.market_value / market_value * 100.0;
+        }
+    }
+    let gain = market_value - cost_basis;
+    AccountHoldings {
+        account_id: account.id,
+        currency: account.currency.clone(),
+        holdings: values,
+        market_value,
+        cost_basis,
+        gain,
+        gain_percent: (cost_basis > 0.0).then(|| gain / cost_basis * 100.0),
+    }
+}
+
+/// Add a holding; fails with a unique violation when the account already
+/// holds the ticker. Call validate_account first.
+pub async fn create_holding(pool: &SqlitePool, account_id: i64, data: &CreateHolding) -> Result<Holding, sqlx::Error> {
+    sqlx::query_as::<_, Holding>(
+        "INSERT INTO holdings (account_id, ticker, quantity, cost_basis) VALUES (?, ?, ?, ?) RETURNING *",
+    )
+    .bind(account_id)
+    .bind(normalize_ticker(&data.ticker))
+    .bind(data.quantity)
+    .bind(data.cost_basis)
+    .fetch_one(pool)
+    .await
+}
+
+/// Apply an update; None when the holding doesn't exist
+pub async fn update_holding(pool: &SqlitePool, id: i64, data: &UpdateHolding) -> Result<Option<Holding>, sqlx::Error> {
+    sqlx::query_as::<_, Holding>(
+        "UPDATE holdings SET quantity = COALESCE(?, quantity), cost_basis = COALESCE(?, cost_basis)
+         WHERE id = ? RETURNING *",
+    )
+    .bind(data.quantity)
+    .bind(data.cost_basis)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Latest quote of a ticker
+pub async fn latest_price(pool: &SqlitePool, ticker: &str) -> Result<Option<SecurityPrice>, sqlx::Error> {
+    sqlx::query_as::<_, SecurityPrice>(
+        "SELECT * FROM security_prices WHERE ticker = ? ORDER BY price_date DESC, fetched_at DESC LIMIT 1",
+    )
+    .bind(ticker)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Store a quote, replacing the one of the same trading day
+pub async fn save_price(
+    pool: &SqlitePool,
+    ticker: &str,
+    price: f64,
+    price_date: chrono::NaiveDate,
+    source: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO security_prices (ticker, price, price_date, source) VALUES (?, ?, ?, ?)
+         ON CONFLICT(ticker, price_date) DO UPDATE SET
+             price = excluded.price, source = excluded.source, fetched_at = CURRENT_TIMESTAMP",
+    )
+    .bind(normalize_ticker(ticker))
+    .bind(price)
+    .bind(price_date)
+    .bind(source)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Every ticker held in any account
+pub async fn held_tickers(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT DISTINCT ticker FROM holdings ORDER BY ticker")
+        .fetch_all(pool)
+        .await
+}
+
+/// The account's holdings, largest position first, valued at their latest quotes
+pub async fn account_holdings(pool: &SqlitePool, account: &Account) -> Result<AccountHoldings, sqlx::Error> {
+    let holdings = sqlx::query_as::<_, Holding>("SELECT * FROM holdings WHERE account_id = ? ORDER BY ticker")
+        .bind(account.id)
+        .fetch_all(pool)
+        .await?;
+    let mut priced = Vec::with_capacity(holdings.len());
+    for holding in holdings {
+        let quote = latest_price(pool, &holding.ticker).await?;
+        priced.push((holding, quote));
+    }
+    let mut valued = valuation(account, priced);
+    valued.holdings.sort_by(|a, b| b.market_value.total_cmp(&a.market_value));
+    Ok(valued)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::NaiveDate;
+
+    #[tokio::test]
+    async fn test_holdings_are_valued_at_latest_quote() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).insert(&pool).await;
+        let brokerage = testing::account(user.id).name("Brokerage").account_type("investment").insert(&pool).await;
+        assert!(validate_account(&checking).is_err());
+        validate_account(&brokerage).unwrap();
+
+        let add = |ticker: &str, quantity: f64, cost_basis: f64| CreateHolding {
+            ticker: ticker.to_string(),
+            quantity,
+            cost_basis,
+        };
+        let apple = create_holding(&pool, brokerage.id, &add(" aapl ", 10.0, 1500.0)).await.unwrap();
+        assert_eq!(apple.ticker, "AAPL");
+        create_holding(&pool, brokerage.id, &add("VFV.TO", 4.0, 400.0)).await.unwrap();
+        assert!(create_holding(&pool, brokerage.id, &add("AAPL", 1.0, 0.0)).await.is_err());
+
+        let day = |d: u32| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+        save_price(&pool, "AAPL", 150.0, day(2), "stooq").await.unwrap();
+        save_price(&pool, "aapl", 190.0, day(3), "stooq").await.unwrap();
+        save_price(&pool, "AAPL", 200.0, day(3), "stooq").await.unwrap();
+        assert_eq!(held_tickers(&pool).await.unwrap(), vec!["AAPL", "VFV.TO"]);
+
+        // AAPL at 200: 2000 against 1500 paid; VFV.TO has no quote and counts at cost
+        let valued = account_holdings(&pool, &brokerage).await.unwrap();
+        assert_eq!((valued.market_value, valued.cost_basis, valued.gain), (2400.0, 1900.0, 500.0));
+        let aapl = &valued.holdings[0];
+        assert_eq!((aapl.price, aapl.price_date), (Some(200.0), Some(day(3))));
+        assert_eq!((aapl.gain, aapl.gain_percent.map(f64::round)), (500.0, Some(33.0)));
+        assert_eq!(valued.holdings[1].price, None);
+        assert_eq!(valued.holdings[1].gain, 0.0);
+        let allocation: Vec<f64> = valued.holdings.iter().map(|h| (h.allocation * 100.0).round() / 100.0).collect();
+        assert_eq!(allocation, vec![83.33, 16.67]);
+
+        let update = UpdateHolding { quantity: Some(12.0), cost_basis: None };
+        let apple = update_holding(&pool, apple.id, &update).await.unwrap().unwrap();
+        assert_eq!((apple.quantity, apple.cost_basis), (12.0, 1500.0));
+        assert!(update_holding(&pool, 999, &update).await.unwrap().is_none());
+    }
+}
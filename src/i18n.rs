@@ -0,0 +1,996 @@
+// i18n.rs
+// Translations of the TUI's text. Messages are keyed by their English text,
+// gettext-style, so the English bundle is the text itself and anything missing
+// from another bundle shows in English. The language is the user's `language`
+// setting, else TUI_LANGUAGE ("en" or "fr", default "en").
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    /// Language for a code or name such as "fr" or "French" (case-insensitive)
+    pub fn parse(value: &str) -> Option<Language> {
+        match value.trim().to_lowercase().as_str() {
+            "en" | "english" => Some(Language::English),
+            "fr" | "french" | "français" | "francais" => Some(Language::French),
+            _ => None,
+        }
+    }
+
+    /// Code stored in the `language` setting
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+        }
+    }
+
+    /// Name in the language itself
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    /// The language after this one, for the TUI toggle
+    pub fn next(self) -> Language {
+        let i = Language::ALL.iter().position(|l| *l == self).unwrap_or(0);
+        Language::ALL[(i + 1) % Language::ALL.len()]
+    }
+}
+
+/// Language used before a user logs in, or when they haven't picked one
+pub fn configured_language() -> Language {
+    env::var("TUI_LANGUAGE")
+        .ok()
+        .and_then(|v| Language::parse(&v))
+        .unwrap_or_default()
+}
+
+/// French bundle: English text -> French text
+const FRENCH: &[(&str, &str)] = &[
+    // Header and tabs
+    ("Personal Finance Tracker", "Suivi des finances personnelles"),
+    (" - User: {}", " - Utilisateur : {}"),
+    ("Menu (1-9, 0, -, =)", "Menu (1-9, 0, -, =)"),
+    ("Dashboard", "Tableau de bord"),
+    ("Accounts", "Comptes"),
+    ("Transactions", "Transactions"),
+    ("Categories", "Catégories"),
+    ("Recurring", "Récurrentes"),
+    ("FX Rates", "Taux de change"),
+    ("Reports", "Rapports"),
+    ("Export", "Exporter"),
+    ("Budgets", "Budgets"),
+    ("Settings", "Paramètres"),
+    ("Goals", "Objectifs"),
+    ("Investments", "Placements"),
+    // Mode indicators
+    ("ADD TRANSACTION", "AJOUT DE TRANSACTION"),
+    ("ADD EXCHANGE RATE", "AJOUT DE TAUX DE CHANGE"),
+    ("ADD RECURRING", "AJOUT DE RÉCURRENTE"),
+    ("ADD ACCOUNT", "AJOUT DE COMPTE"),
+    ("EDIT CATEGORY", "MODIFIER LA CATÉGORIE"),
+    ("ADD CATEGORY", "AJOUT DE CATÉGORIE"),
+    ("ADD USER", "AJOUT D'UTILISATEUR"),
+    ("CONVERT CURRENCY", "CONVERSION DE DEVISE"),
+    ("DELETE CONFIRM", "CONFIRMER LA SUPPRESSION"),
+    ("DETAILS", "DÉTAILS"),
+    ("TAGS", "ÉTIQUETTES"),
+    ("EXPORT DATA", "EXPORTER LES DONNÉES"),
+    ("FILTER CURRENCY", "FILTRER PAR DEVISE"),
+    ("DATE RANGE", "PÉRIODE"),
+    ("IMPORT CSV", "IMPORTER UN CSV"),
+    ("ACCOUNT GROUP", "GROUPE DE COMPTES"),
+    ("ACCOUNT CURRENCY", "DEVISE DU COMPTE"),
+    ("VIEW IN CURRENCY", "VOIR EN DEVISE"),
+    ("EDIT NOTES", "MODIFIER LES NOTES"),
+    ("EXPORT PASSPHRASE", "PHRASE SECRÈTE D'EXPORT"),
+    ("QUICK ADD", "AJOUT RAPIDE"),
+    ("SETUP", "CONFIGURATION"),
+    ("SWITCH PROFILE", "CHANGER DE PROFIL"),
+    ("RECATEGORIZE", "RECATÉGORISER"),
+    ("SET BUDGET", "FIXER UN BUDGET"),
+    ("ADD GOAL", "AJOUT D'OBJECTIF"),
+    ("ADD HOLDING", "AJOUT DE POSITION"),
+    ("ADD TRANSFER", "AJOUT DE VIREMENT"),
+    ("WEEKLY DIGEST", "RÉSUMÉ DE LA SEMAINE"),
+    ("STATUS", "ÉTAT"),
+    ("PASSWORD", "MOT DE PASSE"),
+    ("READ RECEIPT", "LIRE UN REÇU"),
+    ("EDIT TRANSACTION", "MODIFIER LA TRANSACTION"),
+    ("SEARCH", "RECHERCHE"),
+    // Panel titles
+    ("Account Balances", "Soldes des comptes"),
+    ("Account Group", "Groupe de comptes"),
+    ("Account Currency", "Devise du compte"),
+    ("Add Account", "Ajouter un compte"),
+    ("Add Exchange Rate Form", "Ajouter un taux de change"),
+    ("Add Holding", "Ajouter une position"),
+    ("Add Recurring", "Ajouter une récurrente"),
+    ("Add Transfer", "Ajouter un virement"),
+    ("Confirm Delete", "Confirmer la suppression"),
+    ("Currency Conversion", "Conversion de devise"),
+    ("Currency Filter", "Filtre de devise"),
+    ("Date Range", "Période"),
+    ("Details", "Détails"),
+    ("Edit Notes", "Modifier les notes"),
+    ("Export Data", "Exporter les données"),
+    ("Export Options", "Options d'export"),
+    ("Export Passphrase", "Phrase secrète d'export"),
+    ("Export Status", "État de l'export"),
+    ("Financial Summary", "Sommaire financier"),
+    ("Getting Started", "Premiers pas"),
+    ("Import", "Importer"),
+    ("Last Scrape", "Dernière collecte"),
+    ("Needs / Wants / Savings", "Besoins / Envies / Épargne"),
+    ("New User Details", "Nouvel utilisateur"),
+    ("Options", "Options"),
+    ("Payee Leaderboard", "Palmarès des bénéficiaires"),
+    ("Quick Add Expense", "Ajout rapide d'une dépense"),
+    ("Quick Stats", "En bref"),
+    ("Read Receipt", "Lire un reçu"),
+    ("Recent Activity", "Activité récente"),
+    (
+        "Recent Transactions (Up/Down to select, Enter for details)",
+        "Transactions récentes (Haut/Bas pour choisir, Entrée pour les détails)",
+    ),
+    ("Select Account / Categories", "Choisir le compte / les catégories"),
+    ("Set Budget", "Fixer un budget"),
+    ("Switch Profile - Enter: Switch | Esc: Cancel", "Changer de profil - Entrée : Changer | Échap : Annuler"),
+    ("System Status", "État du système"),
+    ("Transaction Tags", "Étiquettes de la transaction"),
+    ("Your Week", "Votre semaine"),
+    // Wizard steps
+    ("Step", "Étape"),
+    ("Amount", "Montant"),
+    ("Holding", "Position"),
+    ("Schedule", "Échéancier"),
+    // Forecast columns
+    ("Month", "Mois"),
+    ("Income", "Revenus"),
+    ("Expenses", "Dépenses"),
+    ("Net", "Net"),
+    ("End Balance", "Solde final"),
+    // Field labels
+    ("Account: ", "Compte : "),
+    ("Amount: ", "Montant : "),
+    ("Available: ", "Disponible : "),
+    ("Average: ", "Moyenne : "),
+    ("Balance: ", "Solde : "),
+    ("Bank (optional): ", "Banque (facultatif) : "),
+    ("Bank: ", "Banque : "),
+    ("CSV file: ", "Fichier CSV : "),
+    ("Category ID: ", "N° de catégorie : "),
+    ("Category: ", "Catégorie : "),
+    ("Closed: ", "Fermé : "),
+    ("Cost Basis: ", "Prix de revient : "),
+    ("Credit Limit: ", "Limite de crédit : "),
+    ("Currency: ", "Devise : "),
+    ("Date: ", "Date : "),
+    ("Description: ", "Description : "),
+    ("Fee Category ID: ", "N° de catégorie des frais : "),
+    ("Fee: ", "Frais : "),
+    ("First Date: ", "Première date : "),
+    ("Fixed/Variable: ", "Fixe/Variable : "),
+    ("Frequency: ", "Fréquence : "),
+    ("From Currency: ", "Devise source : "),
+    ("From: ", "De : "),
+    ("Group: ", "Groupe : "),
+    ("Initial Balance: ", "Solde initial : "),
+    ("Missed Occurrences: ", "Échéances manquées : "),
+    ("Month end: ", "Fin du mois : "),
+    ("Monthly limit: ", "Limite mensuelle : "),
+    ("Name: ", "Nom : "),
+    ("New currency: ", "Nouvelle devise : "),
+    ("Net Change: ", "Variation nette : "),
+    ("Note: ", "Note : "),
+    ("Notes: ", "Notes : "),
+    ("Opened: ", "Ouvert le : "),
+    ("Parent ID (optional): ", "N° du parent (facultatif) : "),
+    ("Passphrase: ", "Phrase secrète : "),
+    ("Password: ", "Mot de passe : "),
+    ("Profile: ", "Profil : "),
+    ("Projected (month end): ", "Prévu (fin du mois) : "),
+    ("Quantity: ", "Quantité : "),
+    ("Rate: ", "Taux : "),
+    ("Receipt file: ", "Fichier du reçu : "),
+    ("Reference: ", "Référence : "),
+    ("Report Period: ", "Période du rapport : "),
+    ("Result: ", "Résultat : "),
+    ("Safe to Spend Today: ", "Dépensable aujourd'hui : "),
+    ("Safe to Spend Today: N/A", "Dépensable aujourd'hui : s.o."),
+    ("Tags: ", "Étiquettes : "),
+    ("Ticker: ", "Symbole : "),
+    ("This Month Expenses: ", "Dépenses du mois : "),
+    ("This Month Income: ", "Revenus du mois : "),
+    ("To: ", "À : "),
+    ("To Currency: ", "Devise cible : "),
+    ("Total Accounts: ", "Nombre de comptes : "),
+    ("Total Balance: ", "Solde total : "),
+    ("Type: ", "Type : "),
+    ("Utilization: ", "Utilisation : "),
+    ("★ No favorite account (press s on the Accounts tab)", "★ Aucun compte favori (touche s dans l'onglet Comptes)"),
+    ("No description", "Sans description"),
+    ("Base currency", "Devise de base"),
+    ("Date format", "Format de date"),
+    ("Default account", "Compte par défaut"),
+    ("Week starts on", "Début de semaine"),
+    ("Weekly digest", "Résumé hebdomadaire"),
+    ("Page after login", "Page après connexion"),
+    // Footer help labels, as in "a: Add"
+    ("Add", "Ajouter"),
+    ("Add expense", "Ajouter la dépense"),
+    ("Add it, or remove it if the transaction has it", "L'ajouter, ou la retirer si la transaction l'a déjà"),
+    ("All", "Tout"),
+    ("All Currencies", "Toutes les devises"),
+    ("Account", "Compte"),
+    ("Apply rules", "Appliquer les règles"),
+    ("Apply template", "Appliquer un modèle"),
+    ("Back", "Retour"),
+    ("Cancel", "Annuler"),
+    ("Category", "Catégorie"),
+    ("Change currency", "Changer de devise"),
+    ("Choose", "Choisir"),
+    ("Chart months (3/6/12)", "Mois du graphique (3/6/12)"),
+    ("Clear", "Effacer"),
+    ("Confirm delete", "Confirmer la suppression"),
+    ("Convert", "Convertir"),
+    ("Create User", "Créer l'utilisateur"),
+    ("Currency", "Devise"),
+    ("Dates", "Dates"),
+    ("Delete", "Supprimer"),
+    ("Dismiss", "Fermer"),
+    ("Don't show after login", "Ne plus afficher à la connexion"),
+    ("Edit", "Modifier"),
+    ("Edit limit", "Modifier la limite"),
+    ("Edit notes/reference", "Modifier notes/référence"),
+    ("Enable encryption", "Activer le chiffrement"),
+    ("Exclude from reports", "Exclure des rapports"),
+    ("Export data", "Exporter les données"),
+    ("Field", "Champ"),
+    ("Filter", "Filtrer"),
+    ("Finish", "Terminer"),
+    ("Finish setup", "Terminer la configuration"),
+    ("Go back", "Retour"),
+    ("Group", "Groupe"),
+    ("Group view", "Vue par groupe"),
+    ("Jump 10", "Sauter de 10"),
+    ("Keep", "Garder"),
+    ("Language", "Langue"),
+    ("Last month", "Mois dernier"),
+    ("Login", "Connexion"),
+    ("Move down/up", "Descendre/Monter"),
+    ("Move to category", "Déplacer vers la catégorie"),
+    ("Next", "Suivant"),
+    ("Next field", "Champ suivant"),
+    ("Open here on login", "Ouvrir ici à la connexion"),
+    ("Pending/Cleared", "En attente/Compensée"),
+    ("Pick profile or account", "Choisir le profil ou le compte"),
+    ("Pin", "Épingler"),
+    ("Process", "Traiter"),
+    ("Profile", "Profil"),
+    ("Quick add", "Ajout rapide"),
+    ("Quit", "Quitter"),
+    ("Read receipt", "Lire le reçu"),
+    ("Receipt", "Reçu"),
+    ("Refresh", "Actualiser"),
+    ("Roll up sub-categories", "Regrouper les sous-catégories"),
+    ("Save", "Enregistrer"),
+    ("Save changes", "Enregistrer les modifications"),
+    ("Save, empty to ungroup", "Enregistrer, vide pour dégrouper"),
+    ("Scroll", "Défiler"),
+    ("Search", "Rechercher"),
+    ("Select", "Choisir"),
+    ("Select currency", "Choisir la devise"),
+    ("Select export format", "Choisir le format d'export"),
+    ("Skip", "Passer"),
+    ("Star", "Favori"),
+    ("Status", "État"),
+    ("Submit", "Valider"),
+    ("Switch profile", "Changer de profil"),
+    ("Switch user", "Changer d'utilisateur"),
+    ("Tabs", "Onglets"),
+    ("Tags", "Étiquettes"),
+    ("This month", "Ce mois-ci"),
+    ("Toggle", "Activer/Désactiver"),
+    ("Toggle encryption", "Activer/désactiver le chiffrement"),
+    ("Top/Bottom", "Début/Fin"),
+    ("Transfer", "Virer"),
+    ("Update prices", "Mettre à jour les cours"),
+    ("User", "Utilisateur"),
+    ("View in Currency", "Voir en devise"),
+    ("View in different currency", "Voir dans une autre devise"),
+    ("Void", "Invalider"),
+    ("Yes", "Oui"),
+    ("Add User", "Ajouter un utilisateur"),
+    ("Delete User", "Supprimer l'utilisateur"),
+    ("Next Field", "Champ suivant"),
+    ("Template", "Modèle"),
+    ("Create", "Créer"),
+    ("Set limit", "Fixer la limite"),
+    ("Change", "Modifier"),
+    ("Forecast", "Prévision"),
+    ("Date range", "Période"),
+    ("Edit dates", "Modifier les dates"),
+    ("Press Esc to go back | n: Edit notes/reference | T: Add/remove tags | E: Exclude from reports", "Échap pour revenir | n : modifier les notes/la référence | T : ajouter/retirer des étiquettes | E : exclure des rapports"),
+    ("Inclusive, YYYY-MM-DD | Tab: Edit dates", "Bornes incluses, AAAA-MM-JJ | Tab : modifier les dates"),
+    ("YYYY-MM-DD | Tab: Next field | Enter: Apply | Esc: Cancel", "AAAA-MM-JJ | Tab : champ suivant | Entrée : appliquer | Échap : annuler"),
+    ("Esc: Back | {}", "Échap : retour | {}"),
+    ("v: View in different currency", "v : voir dans une autre devise"),
+    ("v: Change view (current: {})", "v : changer de vue (actuelle : {})"),
+    // Footer help text without a key
+    ("(Tab cycles through fields)", "(Tab passe d'un champ à l'autre)"),
+    ("Type a group name (new or existing)", "Saisir un nom de groupe (nouveau ou existant)"),
+    ("Type a tag", "Saisir une étiquette"),
+    ("Type amount", "Saisir le montant"),
+    ("Type file path", "Saisir le chemin du fichier"),
+    ("Type limit", "Saisir la limite"),
+    ("Type passphrase", "Saisir la phrase secrète"),
+    ("Type password", "Saisir le mot de passe"),
+    ("Type receipt file path", "Saisir le chemin du reçu"),
+    ("Type to filter by description, amount, category or tag", "Saisir pour filtrer par description, montant, catégorie ou étiquette"),
+    ("Type to input", "Saisir au clavier"),
+    (
+        "Type YYYY-MM-DD, Tab: Next field, Enter: Apply",
+        "Saisir AAAA-MM-JJ, Tab : Champ suivant, Entrée : Appliquer",
+    ),
+    // Screen text
+    ("Select a User to Continue", "Choisissez un utilisateur pour continuer"),
+    ("Select a User to Continue (profile: {})", "Choisissez un utilisateur pour continuer (profil : {})"),
+    ("ID: {} ", "N° {} "),
+    ("Available Users ({}) - a: Add New User", "Utilisateurs disponibles ({}) - a : ajouter un utilisateur"),
+    ("Log in as {}", "Connexion en tant que {}"),
+    ("Welcome! Setup 1/4: Create your user", "Bienvenue ! Configuration 1/4 : créez votre utilisateur"),
+    ("Create New User", "Créer un utilisateur"),
+    ("  Username: ", "  Nom d'utilisateur : "),
+    ("  Email:    ", "  E-mail :            "),
+    ("  (Password will be set to '{}' by default)", "  (Le mot de passe sera '{}' par défaut)"),
+    ("⚠ DELETE USER?", "⚠ SUPPRIMER L'UTILISATEUR ?"),
+    ("  Username: {}", "  Nom d'utilisateur : {}"),
+    ("  Email: {}", "  E-mail : {}"),
+    ("  ID: {}", "  N° : {}"),
+    ("  ⚠ WARNING: This will PERMANENTLY delete:", "  ⚠ ATTENTION : ceci supprimera DÉFINITIVEMENT :"),
+    ("    - All accounts for this user", "    - Tous les comptes de cet utilisateur"),
+    ("    - All transactions for this user", "    - Toutes les transactions de cet utilisateur"),
+    ("    - All categories for this user", "    - Toutes les catégories de cet utilisateur"),
+    ("    - All recurring transactions", "    - Toutes les transactions récurrentes"),
+    (" (base currency)", " (devise de base)"),
+    (" (base currency; {} account(s) without a rate left out)", " (devise de base ; {} compte(s) sans taux exclu(s))"),
+    (" (mixed currencies - b: set a base currency)", " (devises mixtes - b : choisir une devise de base)"),
+    (" ({} days left, ${} bills upcoming)", " ({} jours restants, {} $ de factures à venir)"),
+    ("⚠ {} exchange rates older than {} days (see Exchange Rates)", "⚠ {} taux de change de plus de {} jours (voir Taux de change)"),
+    ("All Time", "Depuis le début"),
+    ("Total Income:       ", "Revenus totaux :    "),
+    ("     Transaction Count:  ", "     Transactions :      "),
+    ("Total Expenses:     ", "Dépenses totales :  "),
+    ("     Categories:         ", "     Catégories :        "),
+    ("Net Change:         ", "Variation nette :   "),
+    ("     Recurring:          ", "     Récurrentes :       "),
+    ("No merchant data yet", "Aucune donnée de commerçant pour l'instant"),
+    ("No Bank", "Sans banque"),
+    ("  ⚠ {}% of limit", "  ⚠ {} % de la limite"),
+    ("  {}% of limit", "  {} % de la limite"),
+    ("  closed {}", "  fermé le {}"),
+    ("Ungrouped", "Sans groupe"),
+    (" by group", " par groupe"),
+    (" | J/K: Move", " | J/K : déplacer"),
+    ("Accounts{} ({}){} - balance → projected month end - a: Add | t: Transfer | d: Delete | s: Star | A: Group | C: Currency | o: Group view{} | Enter: Details", "Comptes{} ({}){} - solde → prévu en fin de mois - a : ajouter | t : virement | d : supprimer | s : favori | A : groupe | C : devise | o : vue par groupe{} | Entrée : détails"),
+    ("Account: {} [{}]{}", "Compte : {} [{}]{}"),
+    (" → {} (⚠ rate {}d old)", " → {} (⚠ taux de {} j)"),
+    (" | Default Category: ", " | Catégorie par défaut : "),
+    (" incl. pending and recurring items due", " y compris en attente et récurrentes dues"),
+    ("{} with {} {}", "{} avec {} {}"),
+    ("{} (archived, no new transactions)", "{} (archivé, plus de nouvelles transactions)"),
+    ("{}% ⚠ over {}%", "{} % ⚠ au-dessus de {} %"),
+    ("─── Transactions ({}) ───", "─── Transactions ({}) ───"),
+    ("  ... and {} more", "  ... et {} de plus"),
+    ("Income  ", "Revenu  "),
+    ("Expense ", "Dépense "),
+    ("Unknown ", "Inconnu "),
+    ("transfer", "virement"),
+    ("  ↺ reimburses #{}", "  ↺ rembourse n° {}"),
+    ("  ↺ reimbursed", "  ↺ remboursée"),
+    (" [Filter: {}]", " [Filtre : {}]"),
+    (" [Latest 100]", " [100 dernières]"),
+    (" [Search: {}_]", " [Recherche : {}_]"),
+    (" [Search: \"{}\" - Esc: Clear]", " [Recherche : \"{}\" - Échap : effacer]"),
+    (" [View: {} ⚠ rates up to {}d old]", " [Vue : {} ⚠ taux jusqu'à {} j]"),
+    (" [View: {}]", " [Vue : {}]"),
+    ("Transactions ({}){}{}{}{}{} - /: Search | D: Dates | f: Filter | v: View in $ | ↑↓: Scroll", "Transactions ({}){}{}{}{}{} - / : rechercher | D : dates | f : filtrer | v : voir en $ | ↑↓ : défiler"),
+    ("  (excluded from reports)", "  (exclue des rapports)"),
+    ("Categories ({}){} - a: Add | e: Edit | d: Delete | b: Bucket | E: Exclude from reports | R: Apply rules | ↑↓: Scroll", "Catégories ({}){} - a : ajouter | e : modifier | d : supprimer | b : enveloppe | E : exclure des rapports | R : appliquer les règles | ↑↓ : défiler"),
+    ("Edit Category", "Modifier la catégorie"),
+    ("Add New Category", "Nouvelle catégorie"),
+    ("Examples: Salary, Groceries, Rent, Entertainment, Subscriptions...", "Exemples : Salaire, Épicerie, Loyer, Loisirs, Abonnements..."),
+    ("A parent nests the category under it, e.g. Food > Groceries", "Un parent range la catégorie sous lui, p. ex. Alimentation > Épicerie"),
+    (" (pinned)", " (épinglé)"),
+    (" ⚠ {}d old", " ⚠ {} j"),
+    ("Exchange Rates ({}) - a: Add | c: Convert | p: Pin | d: Delete | ↑↓: Scroll{}", "Taux de change ({}) - a : ajouter | c : convertir | p : épingler | d : supprimer | ↑↓ : défiler{}"),
+    ("  {} rates saved, {} up to date | {} | {}s", "  {} taux enregistrés, {} à jour | {} | {} s"),
+    ("No scrape runs yet - run 'scrape_rates' to fetch rates", "Aucune collecte pour l'instant - lancez 'scrape_rates' pour récupérer les taux"),
+    ("Add New Exchange Rate", "Nouveau taux de change"),
+    ("From Currency (e.g., USD): ", "Devise source (p. ex. USD) : "),
+    ("To Currency (e.g., Euro (EUR)): ", "Devise cible (p. ex. Euro (EUR)) : "),
+    ("Source (manual/api/scraper): ", "Source (manual/api/scraper) : "),
+    ("{} {} = {} {} (rate: {})", "{} {} = {} {} (taux : {})"),
+    ("No rate found from {} to {}", "Aucun taux trouvé de {} vers {}"),
+    (" in {}", " en {}"),
+    ("Income vs. Expenses - last {} months{} (m: 3/6/12)", "Revenus et dépenses - {} derniers mois{} (m : 3/6/12)"),
+    ("Spending by Category - last {} months{}", "Dépenses par catégorie - {} derniers mois{}"),
+    (" (rolled up)", " (regroupées)"),
+    ("No spending in this period", "Aucune dépense sur cette période"),
+    ("{} {} {} {} {}  Status", "{} {} {} {} {}  État"),
+    ("Budget", "Budget"),
+    ("Actual", "Réel"),
+    ("Variance", "Écart"),
+    ("No budgets set (press 9 for Budgets)", "Aucun budget défini (touche 9 pour les Budgets)"),
+    ("Budget vs. Actual ({}) - {}/{} kept ({}%), {} near, {} over", "Budget et réel ({}) - {}/{} respectés ({} %), {} proches, {} dépassés"),
+    ("Spending by Weekday/Hour", "Dépenses par jour et heure"),
+    ("Spending by Weekday/Hour - Peak: {} {}:00 (${})", "Dépenses par jour et heure - pic : {} {}:00 ({} $)"),
+    ("No expenses yet", "Aucune dépense pour l'instant"),
+    ("  Total", "  Total"),
+    ("Cash-Flow Forecast - next {} months: recurring items plus {}-month averages (F: back to charts)", "Prévision de trésorerie - {} prochains mois : récurrentes plus moyennes sur {} mois (F : retour aux graphiques)"),
+    ("done ~{}", "atteint vers {}"),
+    ("not saving yet", "pas encore d'épargne"),
+    (" | by {} needs {}/mo", " | pour le {} il faut {}/mois"),
+    (" | by {}", " | pour le {}"),
+    ("{}/mo, {}{}", "{}/mois, {}{}"),
+    ("Goals - none yet, press 'a' to add one", "Objectifs - aucun pour l'instant, touche 'a' pour en ajouter un"),
+    ("Goals - saved in the linked accounts, pace over the last 90 days", "Objectifs - épargne des comptes liés, rythme des 90 derniers jours"),
+    ("Name:        ", "Nom :        "),
+    ("Target:      ", "Cible :      "),
+    ("Target date: ", "Échéance :   "),
+    ("  YYYY-MM-DD, optional", "  AAAA-MM-JJ, facultatif"),
+    ("Saved in:    ", "Épargné sur : "),
+    ("  No holdings", "  Aucune position"),
+    ("no quote, at cost", "sans cours, au prix de revient"),
+    ("Investments - add an investment account to track holdings", "Placements - ajoutez un compte de placement pour suivre des positions"),
+    ("Investments - latest quotes from {} (p: update), value | gain/loss | allocation", "Placements - derniers cours de {} (p : actualiser), valeur | plus/moins-value | répartition"),
+    ("The account already holds {}", "Le compte détient déjà {}"),
+    ("Error adding holding: {}", "Erreur d'ajout de la position : {}"),
+    ("None (sum as recorded)", "Aucune (somme telle qu'enregistrée)"),
+    ("None (most recently used)", "Aucun (le plus récent)"),
+    ("Weekly digest turned off", "Résumé hebdomadaire désactivé"),
+    ("This page now opens after login ({})", "Cette page s'ouvre désormais après la connexion ({})"),
+    ("Could not save default page: {}", "Impossible d'enregistrer la page par défaut : {}"),
+    ("⚠ Over budget: {}", "⚠ Budget dépassé : {}"),
+    ("⚠ Near the limit: {}", "⚠ Proche de la limite : {}"),
+    ("No budgets set - press 'a' to add one", "Aucun budget défini - touche 'a' pour en ajouter un"),
+    ("All budgets on track", "Tous les budgets sont respectés"),
+    ("{} / {} ({} left)", "{} / {} ({} restant)"),
+    ("  on pace for {}", "  en route vers {}"),
+    ("Budgets ({}) - a: Set limit | Enter: Edit | d: Delete", "Budgets ({}) - a : fixer la limite | Entrée : modifier | d : supprimer"),
+    ("{}% (target {}%)  {}", "{} % (cible {} %)  {}"),
+    ("{} in categories without a bucket (press 'b' on Categories)", "{} dans des catégories sans enveloppe (touche 'b' dans Catégories)"),
+    ("No bucketed spending this month - tag categories with 'b' on the Categories tab", "Aucune dépense par enveloppe ce mois-ci - marquez les catégories avec 'b' dans l'onglet Catégories"),
+    ("Budget for {} set to {}/month", "Budget de {} fixé à {}/mois"),
+    ("Active", "Active"),
+    ("Paused", "En pause"),
+    (" | Next: {} ", " | Prochaine : {} "),
+    ("Recurring ({}) {} - a: Add | p: Process | t: Toggle | d: Delete | ↑↓: Scroll", "Récurrentes ({}) {} - a : ajouter | p : traiter | t : activer/suspendre | d : supprimer | ↑↓ : défiler"),
+    ("post the latest only", "ne passer que la dernière"),
+    ("post all", "tout passer"),
+    ("Amount must be a positive number", "Le montant doit être un nombre positif"),
+    ("First date must be YYYY-MM-DD", "La première date doit être au format AAAA-MM-JJ"),
+    ("Error adding recurring transaction: {}", "Erreur d'ajout de la transaction récurrente : {}"),
+    ("Data Export Options", "Options d'export des données"),
+    ("Press ", "Appuyez sur "),
+    (" to export data for the current user.", " pour exporter les données de l'utilisateur actuel."),
+    ("Supported export formats:", "Formats d'export pris en charge :"),
+    ("  • CSV  ", "  • CSV  "),
+    ("- Comma-separated values for spreadsheets", "- Valeurs séparées par des virgules pour les tableurs"),
+    ("  • JSON ", "  • JSON "),
+    ("- Structured data for other applications", "- Données structurées pour d'autres applications"),
+    ("Data Available for Export:", "Données disponibles à l'export :"),
+    ("  • {} Accounts", "  • {} comptes"),
+    ("  • {} Transactions", "  • {} transactions"),
+    ("  • {} Categories", "  • {} catégories"),
+    ("  • {} Recurring Transactions", "  • {} transactions récurrentes"),
+    ("Export files will be saved to the current directory.", "Les fichiers exportés sont enregistrés dans le dossier courant."),
+    ("No range: the Transactions tab's list, this month's statement", "Sans période : la liste de l'onglet Transactions, le relevé du mois"),
+    ("Select Export Format", "Choisir le format d'export"),
+    (" - Export Transactions as CSV", " - Exporter les transactions en CSV"),
+    (" - Export Transactions as JSON", " - Exporter les transactions en JSON"),
+    (" - Export Accounts as CSV", " - Exporter les comptes en CSV"),
+    (" - Export Full Summary as JSON", " - Exporter le résumé complet en JSON"),
+    (" - Export Summary as Excel Workbook", " - Exporter le résumé en classeur Excel"),
+    (" - Export Statement as PDF", " - Exporter le relevé en PDF"),
+    (" - Export Transactions as QIF (GnuCash, Quicken)", " - Exporter les transactions en QIF (GnuCash, Quicken)"),
+    ("   To: ", "   À : "),
+    (" This month  ", " Ce mois-ci  "),
+    (" Last month  ", " Mois dernier  "),
+    (" Last quarter  ", " Trimestre dernier  "),
+    (" Year to date  ", " Depuis janvier  "),
+    (" All", " Tout"),
+    (" - Encryption: ", " - Chiffrement : "),
+    ("ON (AES-256, .enc)", "ACTIVÉ (AES-256, .enc)"),
+    ("OFF", "DÉSACTIVÉ"),
+    ("Press number to export, Esc to cancel", "Appuyez sur un chiffre pour exporter, Échap pour annuler"),
+    ("Encrypt Exports", "Chiffrer les exports"),
+    ("At least {} characters. Files are saved as AES-encrypted .{} archives; open them with 7-Zip or `cargo run decrypt_export <file>`.", "Au moins {} caractères. Les fichiers sont enregistrés en archives .{} chiffrées en AES ; ouvrez-les avec 7-Zip ou `cargo run decrypt_export <fichier>`."),
+    ("User not found", "Utilisateur introuvable"),
+    ("Current: {}", "Actuelle : {}"),
+    ("Current: All", "Actuelle : toutes"),
+    ("Filter Transactions by Currency", "Filtrer les transactions par devise"),
+    ("Show ALL Currencies", "Afficher TOUTES les devises"),
+    (" ◄ active", " ◄ active"),
+    ("No currencies found in transactions", "Aucune devise trouvée dans les transactions"),
+    ("Found {} currencies in transactions | Press key to filter | Esc: cancel", "{} devises trouvées dans les transactions | Appuyez sur une touche pour filtrer | Échap : annuler"),
+    ("Original (no conversion)", "Originale (sans conversion)"),
+    (" [{}-{}/{}] ↑↓ to scroll", " [{}-{}/{}] ↑↓ pour défiler"),
+    (" [{} currencies]", " [{} devises]"),
+    (" View Account In Currency{} ", " Voir le compte en devise{} "),
+    (" Base Currency For Totals{} ", " Devise de base des totaux{} "),
+    (" View Transactions In Currency{} ", " Voir les transactions en devise{} "),
+    ("Show Transactions From a Date Range", "Afficher les transactions d'une période"),
+    ("This week", "Cette semaine"),
+    ("All (latest 100)", "Tout (100 dernières)"),
+    ("Or a custom range (inclusive):", "Ou une période personnalisée (bornes incluses) :"),
+    (" (enter ID or use shortcuts on right)", " (saisir le n° ou utiliser les raccourcis à droite)"),
+    ("Edit Transaction", "Modifier la transaction"),
+    ("Add New Transaction", "Nouvelle transaction"),
+    ("Type (i=income/e=expense/t=transfer): ", "Type (i=revenu/e=dépense/t=virement) : "),
+    (" (or ID:amount, ... to split)", " (ou n°:montant, ... pour ventiler)"),
+    (" (optional, bank/FX fee)", " (facultatif, frais bancaires/de change)"),
+    (" (optional)", " (facultatif)"),
+    (" (from receipt)", " (du reçu)"),
+    ("Edit Transaction Form", "Modification de la transaction"),
+    ("Add Transaction Form", "Ajout d'une transaction"),
+    ("Quick Select (type number in Account field):", "Sélection rapide (saisir le numéro dans le champ Compte) :"),
+    ("Templates (press key to pre-fill)", "Modèles (appuyez sur la touche pour pré-remplir)"),
+    ("Categories (id = name [type])", "Catégories (n° = nom [type])"),
+    ("  None for this user", "  Aucune pour cet utilisateur"),
+    ("  Splits are ID:amount pairs separated by commas", "  Les ventilations sont des paires n°:montant séparées par des virgules"),
+    ("Unknown category", "Catégorie inconnue"),
+    ("Remaining", "Reste"),
+    ("Error: Invalid input! Check account ID, amount, and category ID.", "Erreur : saisie invalide ! Vérifiez le n° de compte, le montant et le n° de catégorie."),
+    ("Error: Account {} not found", "Erreur : compte {} introuvable"),
+    ("Error: Account {} is closed", "Erreur : le compte {} est fermé"),
+    ("Error: Category {} not found", "Erreur : catégorie {} introuvable"),
+    ("Error: Type must be 'income' (i), 'expense' (e) or 'transfer' (t)", "Erreur : le type doit être 'income' (i), 'expense' (e) ou 'transfer' (t)"),
+    ("Error: Fee must be a non-negative number", "Erreur : les frais doivent être un nombre positif ou nul"),
+    ("Transaction added successfully! ID: {}", "Transaction ajoutée ! N° : {}"),
+    ("Transfers can't be edited - delete both legs and transfer again", "Les virements ne se modifient pas - supprimez les deux écritures et refaites le virement"),
+    ("Error: Give a category - a split transaction's amount can't change", "Erreur : indiquez une catégorie - le montant d'une transaction ventilée ne peut pas changer"),
+    ("Transaction {} updated, balance adjusted!", "Transaction {} modifiée, solde ajusté !"),
+    ("Error: Invalid input!", "Erreur : saisie invalide !"),
+    ("Database error: {}", "Erreur de base de données : {}"),
+    ("Error: No category guess - type a category ID (Tab)", "Erreur : aucune catégorie devinée - saisissez un n° de catégorie (Tab)"),
+    ("account default", "défaut du compte"),
+    ("No account", "Aucun compte"),
+    (" (manual)", " (manuel)"),
+    ("none - type an ID", "aucune - saisissez un n°"),
+    ("None saved (columns detected from the header)", "Aucun enregistré (colonnes détectées depuis l'en-tête)"),
+    ("◄ None (detect columns) ►", "◄ Aucun (détecter les colonnes) ►"),
+    ("Import CSV Statement", "Importer un relevé CSV"),
+    ("Columns: {}  |  Amounts: {}", "Colonnes : {}  |  Montants : {}"),
+    ("Rows already in the account are skipped. Save profiles with POST /import/profiles or `import --save-profile`.", "Les lignes déjà présentes dans le compte sont ignorées. Enregistrez des profils avec POST /import/profiles ou `import --save-profile`."),
+    ("Add Transaction From Receipt", "Ajouter une transaction depuis un reçu"),
+    ("The date, total and merchant found on the receipt pre-fill the add-transaction form; nothing is saved until you submit it.", "La date, le total et le commerçant du reçu pré-remplissent le formulaire d'ajout ; rien n'est enregistré avant la validation."),
+    ("{} is now in {}", "{} est maintenant dans {}"),
+    ("{} is no longer in a group", "{} n'est plus dans un groupe"),
+    ("{} is already in {}", "{} est déjà en {}"),
+    ("Converted {} to {} at {}: balance {} {}, {} transactions keep their {} amounts as originals", "{} converti en {} à {} : solde {} {}, {} transactions gardent leurs montants en {} comme originaux"),
+    ("No transactions yet: the currency is just changed", "Aucune transaction : seule la devise change"),
+    ("{} transactions: balances and amounts are converted, the {} ones kept as originals", "{} transactions : soldes et montants sont convertis, ceux en {} gardés comme originaux"),
+    (" {} per {}, empty for the latest FX rate", " {} par {}, vide pour le dernier taux de change"),
+    ("No groups yet", "Aucun groupe pour l'instant"),
+    ("Existing: {}", "Existants : {}"),
+    ("Edit Notes - Transaction {}", "Modifier les notes - transaction {}"),
+    ("  (check number or bank reference)", "  (numéro de chèque ou référence bancaire)"),
+    ("Tag: #", "Étiquette : #"),
+    ("Pick two accounts", "Choisissez deux comptes"),
+    ("Error: No exchange rate from {} to {}", "Erreur : aucun taux de change de {} vers {}"),
+    ("Error creating transfer: {}", "Erreur de création du virement : {}"),
+    ("Category for {}", "Catégorie de {}"),
+    ("Add New Account", "Nouveau compte"),
+    ("Available Currencies:", "Devises disponibles :"),
+    ("Account Types:", "Types de comptes :"),
+    ("Account {}", "Compte {}"),
+    ("No category with ID {}", "Aucune catégorie avec le n° {}"),
+    ("Category '{}' updated", "Catégorie '{}' modifiée"),
+    ("Category '{}' created! ID: {}", "Catégorie '{}' créée ! N° : {}"),
+    ("Add starter categories?", "Ajouter des catégories de départ ?"),
+    ("Creates a basic set of income and expense categories:", "Crée un jeu de base de catégories de revenus et de dépenses :"),
+    ("You can add or delete categories later on the Categories tab.", "Vous pourrez ajouter ou supprimer des catégories plus tard dans l'onglet Catégories."),
+    ("Download exchange rates?", "Télécharger les taux de change ?"),
+    ("Fetches today's rates for {} from x-rates.com.", "Récupère les taux du jour pour {} sur x-rates.com."),
+    ("Needed to convert between currencies; requires an internet connection.", "Nécessaire pour convertir entre devises ; demande une connexion internet."),
+    ("Setup {}/4: {}", "Configuration {}/4 : {}"),
+    ("Setup complete - no exchange rates fetched: {}", "Configuration terminée - aucun taux de change récupéré : {}"),
+    ("Setup complete - saved {} exchange rates", "Configuration terminée - {} taux de change enregistrés"),
+    ("Setup complete - saved {} exchange rates; could not fetch {} (try 'scrape_rates' later)", "Configuration terminée - {} taux de change enregistrés ; impossible de récupérer {} (réessayez 'scrape_rates' plus tard)"),
+    ("transaction {}", "la transaction {}"),
+    ("account '{}'", "le compte '{}'"),
+    ("category '{}'", "la catégorie '{}'"),
+    ("exchange rate {}", "le taux de change {}"),
+    ("Exchange rate {} deleted successfully! (U to undo)", "Taux de change {} supprimé ! (U pour annuler)"),
+    ("recurring transaction {}", "la transaction récurrente {}"),
+    ("Recurring transaction {} deleted successfully! (U to undo)", "Transaction récurrente {} supprimée ! (U pour annuler)"),
+    ("budget for {}", "le budget de {}"),
+    ("goal {}", "l'objectif {}"),
+    ("holding {}", "la position {}"),
+    ("Spending", "Dépenses"),
+    ("  {} this week vs. {} last week  ", "  {} cette semaine contre {} la semaine dernière  "),
+    ("Large transactions", "Grosses transactions"),
+    ("None this week", "Aucune cette semaine"),
+    ("Budgets at risk", "Budgets menacés"),
+    ("  {}: {} of {} ({})", "  {} : {} sur {} ({})"),
+    ("Upcoming bills (next 7 days)", "Factures à venir (7 prochains jours)"),
+    ("Nothing due", "Rien à payer"),
+    ("just now", "à l'instant"),
+    ("{}m ago", "il y a {} min"),
+    ("{}h ago", "il y a {} h"),
+    ("{}d ago", "il y a {} j"),
+    ("Database", "Base de données"),
+    ("in memory", "en mémoire"),
+    ("Rows", "Lignes"),
+    ("Background runs", "Tâches de fond"),
+    ("  Last FX scrape:       {} ({}), {} rates saved, ", "  Dernière collecte FX :   {} ({}), {} taux enregistrés, "),
+    ("Last FX scrape:       never", "Dernière collecte FX :   jamais"),
+    ("  Last recurring post:  {} ({})", "  Dernier passage récurr. : {} ({})"),
+    ("Last recurring post:  never", "Dernier passage récurr. : jamais"),
+    ("Alerts", "Alertes"),
+    ("  {} webhook deliveries pending, {} failed", "  {} envois de webhook en attente, {} en échec"),
+    ("Server tasks", "Tâches du serveur"),
+    ("No server has run against this database (cargo run serve)", "Aucun serveur n'a tourné sur cette base (cargo run serve)"),
+    ("last run {}", "dernier passage {}"),
+    (" ✓ ACTIVE", " ✓ ACTIVE"),
+    ("Forecast unavailable", "Prévision indisponible"),
+    ("No open accounts to forecast", "Aucun compte ouvert à prévoir"),
+    // Detail and confirmation dialogs
+    ("Delete Transaction?", "Supprimer la transaction ?"),
+    ("Press 'y' to confirm, 'n' to cancel", "Appuyez sur 'y' pour confirmer, 'n' pour annuler"),
+    ("⚠ DELETE ACCOUNT?", "⚠ SUPPRIMER LE COMPTE ?"),
+    ("⚠ WARNING: All transactions for this account", "⚠ ATTENTION : toutes les transactions de ce compte"),
+    ("  will also be PERMANENTLY deleted!", "  seront aussi DÉFINITIVEMENT supprimées !"),
+    ("Delete Category?", "Supprimer la catégorie ?"),
+    ("⚠ Categories linked to transactions cannot be deleted", "⚠ Les catégories liées à des transactions ne peuvent pas être supprimées"),
+    ("Delete Exchange Rate?", "Supprimer le taux de change ?"),
+    ("Delete Recurring Transaction?", "Supprimer la transaction récurrente ?"),
+    ("Delete Budget?", "Supprimer le budget ?"),
+    ("Delete Goal?", "Supprimer l'objectif ?"),
+    ("The linked accounts are kept", "Les comptes liés sont conservés"),
+    ("Delete Holding?", "Supprimer la position ?"),
+    ("Invalid selection", "Sélection invalide"),
+    ("No details available", "Aucun détail disponible"),
+    ("Transaction Details", "Détails de la transaction"),
+    ("Exchange Rate Details", "Détails du taux de change"),
+    ("Recurring Transaction Details", "Détails de la transaction récurrente"),
+    ("Press Esc to go back", "Échap pour revenir"),
+    ("ID: {}", "N° : {}"),
+    ("Account ID: {}", "N° de compte : {}"),
+    ("Category ID: {}", "N° de catégorie : {}"),
+    ("Amount: ${}", "Montant : {} $"),
+    ("Type: {}", "Type : {}"),
+    ("Status: {}", "État : {}"),
+    ("Description: {}", "Description : {}"),
+    ("Name: {}", "Nom : {}"),
+    ("Bank: {}", "Banque : {}"),
+    ("Balance: {} {}", "Solde : {} {}"),
+    ("From: {}", "De : {}"),
+    ("To: {}", "À : {}"),
+    ("Rate: {}", "Taux : {}"),
+    ("Date: {}", "Date : {}"),
+    ("Date: {} {}", "Date : {} {}"),
+    ("Frequency: {}", "Fréquence : {}"),
+    ("Classification: {}", "Classement : {}"),
+    ("Category: {}", "Catégorie : {}"),
+    ("Category: None", "Catégorie : aucune"),
+    ("Monthly limit: {}", "Limite mensuelle : {}"),
+    ("Goal: {}", "Objectif : {}"),
+    ("Target: {} {}", "Cible : {} {}"),
+    ("Holding: {} x {}", "Position : {} x {}"),
+    ("Cost basis: {}", "Prix de revient : {}"),
+    ("Original: {} {}{}", "Original : {} {}{}"),
+    ("Original: Same as account currency", "Original : même devise que le compte"),
+    ("Split: {}", "Ventilation : {}"),
+    ("Fee: ${}{}", "Frais : {} ${}"),
+    ("Fee: None", "Frais : aucun"),
+    ("Reference: {}", "Référence : {}"),
+    ("Merchant: {}{}", "Commerçant : {}{}"),
+    ("Created: {}", "Créé le : {}"),
+    ("Updated: {}", "Modifié le : {}"),
+    ("Tags: {}", "Étiquettes : {}"),
+    ("Tags: None", "Étiquettes : aucune"),
+    ("Attachments: {}", "Pièces jointes : {}"),
+    ("Reimbursement: pays back transaction {}", "Remboursement : rembourse la transaction {}"),
+    ("Reimbursement: ${} paid back, ${} still spent", "Remboursement : {} $ remboursés, {} $ encore dépensés"),
+    ("Reimbursement: None", "Remboursement : aucun"),
+    ("Reports: Included", "Rapports : incluse"),
+    ("Reports: Excluded", "Rapports : exclue"),
+    ("Reports: Excluded (by category)", "Rapports : exclue (par catégorie)"),
+    ("Notes:", "Notes :"),
+    ("No notes", "Aucune note"),
+    ("None", "Aucun"),
+    ("Unknown", "Inconnu"),
+    ("From Currency: {}", "Devise source : {}"),
+    ("To Currency: {}", "Devise cible : {}"),
+    ("Source: {}", "Source : {}"),
+    ("Rate Date: {}", "Date du taux : {}"),
+    ("Missed occurrences: {}", "Échéances manquées : {}"),
+    ("Start Date: {}", "Date de début : {}"),
+    ("End Date: {}", "Date de fin : {}"),
+    ("Next Occurrence: {}", "Prochaine échéance : {}"),
+    // Wizard choices and hints
+    ("Expense", "Dépense"),
+    ("Monthly", "Mensuelle"),
+    ("Weekly", "Hebdomadaire"),
+    ("Daily", "Quotidienne"),
+    ("Yearly", "Annuelle"),
+    ("Fixed", "Fixe"),
+    ("Variable", "Variable"),
+    ("Post all", "Tout passer"),
+    ("Latest only", "La dernière seulement"),
+    ("e.g. AAPL, VFV.TO", "p. ex. AAPL, VFV.TO"),
+    ("total paid", "total payé"),
+    // Status messages
+    ("Logged in as {}", "Connecté en tant que {}"),
+    ("Data refreshed!", "Données actualisées !"),
+    ("Nothing to undo", "Rien à annuler"),
+    ("Switched to profile {}", "Profil {} activé"),
+    ("Language: {}", "Langue : {}"),
+    ("Error syncing with {}: {}", "Erreur de synchronisation avec {} : {}"),
+    ("Managing users isn't available in remote mode", "La gestion des utilisateurs n'est pas disponible en mode distant"),
+    ("{} isn't available in remote mode", "{} n'est pas disponible en mode distant"),
+    ("Undo", "Annuler"),
+    ("Saving the start screen", "L'enregistrement de l'écran d'accueil"),
+    ("Saving the view currency", "L'enregistrement de la devise d'affichage"),
+    ("Adding receipts", "L'ajout de reçus"),
+    ("Importing statements", "L'importation de relevés"),
+    ("Changing accounts", "La modification des comptes"),
+    ("Changing categories", "La modification des catégories"),
+    ("Changing exchange rates", "La modification des taux de change"),
+    ("Changing recurring transactions", "La modification des transactions récurrentes"),
+    ("Changing budgets", "La modification des budgets"),
+    ("Changing goals", "La modification des objectifs"),
+    ("Changing investments", "La modification des placements"),
+    ("Changing settings", "La modification des paramètres"),
+    (
+        "moving a transaction to another account isn't available in remote mode",
+        "le déplacement d'une transaction vers un autre compte n'est pas disponible en mode distant",
+    ),
+    ("Wrong password for {}", "Mot de passe incorrect pour {}"),
+    ("Welcome, {}! Setup 2/4: create your first account", "Bienvenue, {} ! Configuration 2/4 : créez votre premier compte"),
+    ("Error loading status: {}", "Erreur de chargement de l'état : {}"),
+    ("No profiles configured (set PROFILES=name=url,...)", "Aucun profil configuré (définissez PROFILES=nom=url,...)"),
+    ("Cannot open profile {}: {}", "Impossible d'ouvrir le profil {} : {}"),
+    ("Forecast of the next {} months", "Prévision des {} prochains mois"),
+    ("Charts show the last {} months", "Les graphiques montrent les {} derniers mois"),
+    ("Showing the report charts", "Affichage des graphiques du rapport"),
+    ("Sub-category spending rolled up into parents", "Dépenses des sous-catégories regroupées dans les parents"),
+    ("Spending shown per sub-category", "Dépenses affichées par sous-catégorie"),
+    ("Add an account before logging transactions", "Ajoutez un compte avant de saisir des transactions"),
+    ("Add an account before importing statements", "Ajoutez un compte avant d'importer des relevés"),
+    ("Error loading transactions: {}", "Erreur de chargement des transactions : {}"),
+    ("Press o to leave the grouped view before reordering", "Appuyez sur o pour quitter la vue groupée avant de réordonner"),
+    ("Error adding transaction: {}", "Erreur d'ajout de la transaction : {}"),
+    ("Transaction {} marked {}", "Transaction {} marquée {}"),
+    ("Error updating transaction status: {}", "Erreur de mise à jour de l'état de la transaction : {}"),
+    ("Error updating transaction: {}", "Erreur de mise à jour de la transaction : {}"),
+    ("Error: Invalid input! Check currencies and rate.", "Erreur : saisie invalide ! Vérifiez les devises et le taux."),
+    ("Exchange rate added successfully! ID: {}", "Taux de change ajouté ! ID : {}"),
+    ("Error adding exchange rate: {}", "Erreur d'ajout du taux de change : {}"),
+    ("Conversion successful!", "Conversion réussie !"),
+    ("Restored {} ({} rows)", "{} restauré ({} lignes)"),
+    ("Cannot restore {}: {}", "Impossible de restaurer {} : {}"),
+    ("Deleted {} on {}", "{} supprimé le {}"),
+    ("Error deleting transaction: {}", "Erreur de suppression de la transaction : {}"),
+    ("Error saving transaction for undo: {}", "Erreur de sauvegarde de la transaction pour l'annulation : {}"),
+    ("Transfer deleted from both accounts, balances updated! (U to undo)", "Virement supprimé des deux comptes, soldes mis à jour ! (U pour annuler)"),
+    ("Transaction {} deleted, balance updated! (U to undo)", "Transaction {} supprimée, solde mis à jour ! (U pour annuler)"),
+    ("Error saving account for undo: {}", "Erreur de sauvegarde du compte pour l'annulation : {}"),
+    ("Account '{}' and {} transactions deleted! (U to undo)", "Compte '{}' et {} transactions supprimés ! (U pour annuler)"),
+    ("Account '{}' deleted! (U to undo)", "Compte '{}' supprimé ! (U pour annuler)"),
+    ("Error deleting account: {}", "Erreur de suppression du compte : {}"),
+    ("Cannot delete '{}': used by {} transactions.", "Impossible de supprimer '{}' : utilisé par {} transactions."),
+    ("Error saving category for undo: {}", "Erreur de sauvegarde de la catégorie pour l'annulation : {}"),
+    ("Category '{}' deleted! (U to undo)", "Catégorie '{}' supprimée ! (U pour annuler)"),
+    ("Error deleting category: {}", "Erreur de suppression de la catégorie : {}"),
+    ("Error saving exchange rate for undo: {}", "Erreur de sauvegarde du taux de change pour l'annulation : {}"),
+    ("Error deleting exchange rate: {}", "Erreur de suppression du taux de change : {}"),
+    ("Error saving recurring transaction for undo: {}", "Erreur de sauvegarde de la transaction récurrente pour l'annulation : {}"),
+    ("Error deleting recurring transaction: {}", "Erreur de suppression de la transaction récurrente : {}"),
+    ("Error saving budget for undo: {}", "Erreur de sauvegarde du budget pour l'annulation : {}"),
+    ("Budget for {} deleted (U to undo)", "Budget de {} supprimé (U pour annuler)"),
+    ("Error deleting budget: {}", "Erreur de suppression du budget : {}"),
+    ("Error saving goal for undo: {}", "Erreur de sauvegarde de l'objectif pour l'annulation : {}"),
+    ("Goal {} deleted (U to undo)", "Objectif {} supprimé (U pour annuler)"),
+    ("Error deleting goal: {}", "Erreur de suppression de l'objectif : {}"),
+    ("Error saving holding for undo: {}", "Erreur de sauvegarde du placement pour l'annulation : {}"),
+    ("Holding {} deleted (U to undo)", "Placement {} supprimé (U pour annuler)"),
+    ("Error deleting holding: {}", "Erreur de suppression du placement : {}"),
+    ("Add an account first", "Ajoutez d'abord un compte"),
+    ("Recurring transaction added successfully! ID: {}", "Transaction récurrente ajoutée ! ID : {}"),
+    ("Error exporting: {}", "Erreur d'exportation : {}"),
+    ("Export encryption disabled", "Chiffrement des exportations désactivé"),
+    ("Error: Passphrase must be at least {} characters", "Erreur : la phrase secrète doit contenir au moins {} caractères"),
+    ("Export encryption enabled", "Chiffrement des exportations activé"),
+    ("Receipt parsing is turned off (RECEIPT_PARSER=none)", "La lecture des reçus est désactivée (RECEIPT_PARSER=none)"),
+    ("Error: {}", "Erreur : {}"),
+    ("Error reading {}: {}", "Erreur de lecture de {} : {}"),
+    ("Error reading receipt: {}", "Erreur de lecture du reçu : {}"),
+    ("Nothing recognised on the receipt - fill in the form", "Rien de reconnu sur le reçu - remplissez le formulaire"),
+    ("Receipt read - check the fields, then Enter to add", "Reçu lu - vérifiez les champs, puis Entrée pour ajouter"),
+    ("Imported {} transactions ({} duplicates skipped, {} before the account opened)", "{} transactions importées ({} doublons ignorés, {} antérieures à l'ouverture du compte)"),
+    ("Error importing: {}", "Erreur d'importation : {}"),
+    ("Exported {} transactions to {}", "{} transactions exportées vers {}"),
+    ("Error serializing: {}", "Erreur de sérialisation : {}"),
+    ("Exported {} accounts to {}", "{} comptes exportés vers {}"),
+    ("Exported full financial summary to {}", "Résumé financier complet exporté vers {}"),
+    ("Exported summary workbook to {}", "Classeur récapitulatif exporté vers {}"),
+    ("Exported {} statement to {}", "Relevé {} exporté vers {}"),
+    ("Processed {} recurring transactions - {} new transactions created.", "{} transactions récurrentes traitées - {} nouvelles transactions créées."),
+    ("Error processing recurring transactions: {}", "Erreur de traitement des transactions récurrentes : {}"),
+    ("Recurring transaction {} {}", "Transaction récurrente {} {}"),
+    ("Error updating status: {}", "Erreur de mise à jour de l'état : {}"),
+    ("Account {} {}", "Compte {} {}"),
+    ("Error updating account: {}", "Erreur de mise à jour du compte : {}"),
+    ("Only manual rates can be pinned", "Seuls les taux manuels peuvent être épinglés"),
+    ("Rate {} {}", "Taux {} {}"),
+    ("Error updating rate: {}", "Erreur de mise à jour du taux : {}"),
+    ("'{}' bucket: {}", "Enveloppe de '{}' : {}"),
+    ("Error setting bucket: {}", "Erreur de définition de l'enveloppe : {}"),
+    ("'{}' transactions excluded from reports", "Transactions '{}' exclues des rapports"),
+    ("'{}' transactions included in reports again", "Transactions '{}' de nouveau incluses dans les rapports"),
+    ("Error updating category: {}", "Erreur de mise à jour de la catégorie : {}"),
+    ("Applied category rules: {} transactions recategorized", "Règles de catégorie appliquées : {} transactions recatégorisées"),
+    ("Error applying category rules: {}", "Erreur d'application des règles de catégorie : {}"),
+    ("Favorites are always listed first", "Les favoris sont toujours listés en premier"),
+    ("Error reordering accounts: {}", "Erreur de réorganisation des comptes : {}"),
+    ("Moved {}", "{} déplacé"),
+    ("Error creating group: {}", "Erreur de création du groupe : {}"),
+    ("Enter a 3-letter currency code", "Saisissez un code de devise à 3 lettres"),
+    ("No exchange rate from {} to {}; type one in the Rate field", "Aucun taux de change de {} vers {} ; saisissez-en un dans le champ Taux"),
+    ("Error looking up the rate: {}", "Erreur de recherche du taux : {}"),
+    ("Rate must be a positive number", "Le taux doit être un nombre positif"),
+    ("Transaction {} excluded from reports", "Transaction {} exclue des rapports"),
+    ("Transaction {} included in reports again", "Transaction {} de nouveau incluse dans les rapports"),
+    ("Error creating tag: {}", "Erreur de création de l'étiquette : {}"),
+    ("Tag #{} {}", "Étiquette #{} {}"),
+    ("Error updating tags: {}", "Erreur de mise à jour des étiquettes : {}"),
+    ("Transaction {} notes updated!", "Notes de la transaction {} mises à jour !"),
+    ("Error updating notes: {}", "Erreur de mise à jour des notes : {}"),
+    ("Add a category before recategorizing transactions", "Ajoutez une catégorie avant de recatégoriser des transactions"),
+    ("Transaction {} moved to {}", "Transaction {} déplacée vers {}"),
+    ("Error recategorizing transaction: {}", "Erreur de recatégorisation de la transaction : {}"),
+    ("Add a category before setting a budget", "Ajoutez une catégorie avant de définir un budget"),
+    ("Monthly limit must be a positive number", "La limite mensuelle doit être un nombre positif"),
+    ("Error setting budget: {}", "Erreur de définition du budget : {}"),
+    ("Add an account to save the goal in first", "Ajoutez d'abord un compte où épargner pour l'objectif"),
+    ("Target date must be YYYY-MM-DD (or empty)", "La date cible doit être AAAA-MM-JJ (ou vide)"),
+    ("Goal {} added: {} {}", "Objectif {} ajouté : {} {}"),
+    ("Error adding goal: {}", "Erreur d'ajout de l'objectif : {}"),
+    ("Add an investment account first", "Ajoutez d'abord un compte d'investissement"),
+    ("Added {} x {}", "{} x {} ajouté"),
+    ("Updated {} quotes", "{} cours mis à jour"),
+    ("Updated {} quotes; {}", "{} cours mis à jour ; {}"),
+    ("Error updating prices: {}", "Erreur de mise à jour des cours : {}"),
+    ("A transfer needs at least two accounts", "Un virement nécessite au moins deux comptes"),
+    ("Transferred {} {} from {} to {} ({} {})", "{} {} virés de {} vers {} ({} {})"),
+    ("Showing all currencies", "Affichage de toutes les devises"),
+    ("Filtering by {}", "Filtrage par {}"),
+    ("The range must start before it ends", "La période doit commencer avant de se terminer"),
+    ("Enter both dates as YYYY-MM-DD", "Saisissez les deux dates au format AAAA-MM-JJ"),
+    ("Showing transactions from {} to {}", "Transactions du {} au {}"),
+    ("Showing the latest 100 transactions", "Affichage des 100 dernières transactions"),
+    ("Account: showing original currency", "Compte : affichage dans la devise d'origine"),
+    ("Showing original currencies", "Affichage des devises d'origine"),
+    ("Account: viewing in {}", "Compte : affichage en {}"),
+    ("Viewing all amounts in {}", "Tous les montants affichés en {}"),
+    ("Could not save base currency: {}", "Impossible d'enregistrer la devise de base : {}"),
+    ("Totals in {}", "Totaux en {}"),
+    ("Totals summed as recorded", "Totaux additionnés tels qu'enregistrés"),
+    ("Could not save setting: {}", "Impossible d'enregistrer le paramètre : {}"),
+    ("Setting saved", "Paramètre enregistré"),
+    ("Could not save view currency: {}", "Impossible d'enregistrer la devise d'affichage : {}"),
+    ("Template '{}' applied - Enter to submit", "Modèle '{}' appliqué - Entrée pour valider"),
+    ("Error: Account name is required!", "Erreur : le nom du compte est obligatoire !"),
+    ("Error: Currency is required!", "Erreur : la devise est obligatoire !"),
+    ("Error: No user logged in!", "Erreur : aucun utilisateur connecté !"),
+    ("Account '{}' created! ID: {} [{}]", "Compte '{}' créé ! ID : {} [{}]"),
+    ("Error creating account: {}", "Erreur de création du compte : {}"),
+    ("Error: Category name is required!", "Erreur : le nom de la catégorie est obligatoire !"),
+    ("Error saving category: {}", "Erreur d'enregistrement de la catégorie : {}"),
+    ("Added {} categories", "{} catégories ajoutées"),
+    ("Error adding categories: {}", "Erreur d'ajout des catégories : {}"),
+    ("Setup complete", "Configuration terminée"),
+    ("Error: Username is required!", "Erreur : le nom d'utilisateur est obligatoire !"),
+    ("Error: Email is required!", "Erreur : l'e-mail est obligatoire !"),
+    ("User '{}' created! ID: {}", "Utilisateur '{}' créé ! ID : {}"),
+    ("Error: Username or email already exists!", "Erreur : le nom d'utilisateur ou l'e-mail existe déjà !"),
+    ("Error creating user: {}", "Erreur de création de l'utilisateur : {}"),
+    ("User '{}' and all data deleted!", "Utilisateur '{}' et toutes ses données supprimés !"),
+    ("Error deleting user: {}", "Erreur de suppression de l'utilisateur : {}"),
+];
+
+fn bundle(language: Language) -> Option<&'static HashMap<&'static str, &'static str>> {
+    static FRENCH_BUNDLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    match language {
+        Language::English => None,
+        Language::French => Some(FRENCH_BUNDLE.get_or_init(|| FRENCH.iter().copied().collect())),
+    }
+}
+
+/// The text in `language`, or as given when it has no translation
+pub fn tr(language: Language, text: &str) -> &str {
+    bundle(language)
+        .and_then(|b| b.get(text).copied())
+        .unwrap_or(text)
+}
+
+/// Translate a message, then fill its `{}` placeholders in order
+pub fn tr_fmt(language: Language, text: &str, args: &[&dyn Display]) -> String {
+    let mut parts = tr(language, text).split("{}");
+    let mut message = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            message.push_str(&arg.to_string());
+        }
+        message.push_str(part);
+    }
+    message
+}
+
+/// Translate a footer help line such as "a: Add | Esc: Cancel": each segment
+/// is looked up whole, else as a key and its label
+pub fn help_line(language: Language, line: &str) -> String {
+    if language == Language::English {
+        return line.to_string();
+    }
+    line.split(" | ")
+        .map(|segment| match bundle(language).and_then(|b| b.get(segment)) {
+            Some(translated) => translated.to_string(),
+            None => match segment.split_once(": ") {
+                Some((key, label)) => format!("{}: {}", key.replace("Enter", "Entrée").replace("Esc", "Échap"), tr(language, label)),
+                None => tr(language, segment).to_string(),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_french_bundle_translates_and_falls_back() {
+        assert_eq!(Language::parse(" FR "), Some(Language::French));
+        assert_eq!(Language::parse("en"), Some(Language::English));
+        assert_eq!(Language::parse("de"), None);
+        assert_eq!(Language::English.next(), Language::French);
+        assert_eq!(Language::French.next(), Language::English);
+
+        assert_eq!(tr(Language::French, "Accounts"), "Comptes");
+        assert_eq!(tr(Language::English, "Accounts"), "Accounts");
+        assert_eq!(tr(Language::French, "Not in the bundle"), "Not in the bundle");
+        assert_eq!(tr_fmt(Language::French, "Logged in as {}", &[&"alice"]), "Connecté en tant que alice");
+        assert_eq!(
+            help_line(Language::French, "a: Add | Enter: Details | Type a tag | X: Not in the bundle"),
+            "a: Ajouter | Entrée: Détails | Saisir une étiquette | X: Not in the bundle"
+        );
+        assert_eq!(help_line(Language::English, "y: Yes | n: Skip"), "y: Yes | n: Skip");
+
+        // Each message appears once and keeps its placeholders
+        let mut seen = HashSet::new();
+        for (english, french) in FRENCH {
+            assert!(seen.insert(english), "'{}' is translated twice", english);
+            assert_eq!(english.matches("{}").count(), french.matches("{}").count(), "{}", english);
+        }
+    }
+}
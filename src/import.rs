@@ -0,0 +1,666 @@
+// import.rs
+// CSV/OFX statement import: parse rows, suggest categories from rules and
+// past transactions with the same payee, then commit the reviewed rows
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::input;
+use crate::merchants;
+use crate::models::{
+    AmountSign, CreateImportProfile, CsvColumnMapping, CsvImportResult, ImportProfile, ImportResult,
+    ImportRow, RuleSuggestion, Transaction,
+};
+use crate::rules;
+use crate::webhooks;
+
+fn new_row(
+    transaction_date: DateTime<Utc>,
+    description: &str,
+    signed_amount: f64,
+    reference: Option<String>,
+) -> ImportRow {
+    // Statement text goes straight into the TUI's tables; keep it one line and bounded
+    let description: String = input::clean_line(description).trim().chars().take(input::MAX_DESCRIPTION_LEN).collect();
+    ImportRow {
+        transaction_date,
+        merchant: merchants::normalize_merchant(&description),
+        description,
+        amount: signed_amount.abs(),
+        transaction_type: if signed_amount < 0.0 { "expense" } else { "income" }.to_string(),
+        reference: reference.map(|r| input::clean_line(&r)).filter(|r| !r.is_empty()),
+        suggested_category_id: None,
+        suggested_category_name: None,
+        suggestion_source: None,
+        category_id: None,
+        create_rule: false,
+        duplicate: false,
+    }
+}
+
+/// Split one CSV line, honouring double-quoted fields ("" is an escaped quote).
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Statement date formats, most common first. Month-first wins over
+/// day-first when a whole file fits both.
+const DATE_FORMATS: [&str; 10] = [
+    "%Y-%m-%d", "%m/%d/%Y", "%d/%m/%Y", "%Y/%m/%d", "%d.%m.%Y", "%d-%b-%Y", "%Y%m%d",
+    "%m/%d/%y", "%d/%m/%y", "%d-%b-%y",
+];
+
+/// Date in the given format. A time after the date ("2025-03-01 14:05") is
+/// ignored, and years outside 1900-2100 are rejected so "03/02/25" isn't
+/// read as the year 25 by a four-digit-year format.
+fn parse_date_with(value: &str, format: &str) -> Option<DateTime<Utc>> {
+    let value = value.split_whitespace().next()?;
+    let date = NaiveDate::parse_from_str(value, format)
+        .ok()
+        .filter(|d| (1900..=2100).contains(&d.year()))?;
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    DATE_FORMATS.iter().find_map(|format| parse_date_with(value, format))
+}
+
+/// First known format that every value parses with, so "01/02/2025" is read
+/// consistently with the rest of the file.
+fn detect_date_format(values: &[&str]) -> Option<&'static str> {
+    DATE_FORMATS.iter().copied().find(|format| {
+        !values.is_empty() && values.iter().all(|v| parse_date_with(v, format).is_some())
+    })
+}
+
+fn parse_amount(value: &str) -> Option<f64> {
+    let cleaned: String = value.chars().filter(|c| !matches!(c, '$' | ',' | ' ')).collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+    // Accounting style "(12.50)" is a negative amount
+    match cleaned.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        Some(inner) => inner.parse::<f64>().ok().map(|v| -v),
+        None => cleaned.parse::<f64>().ok(),
+    }
+}
+
+/// Column mapping from "field=Header,..." (the CLI's --map), where field is
+/// date, description, amount, debit, credit, reference, date_format or
+/// amount_sign.
+pub fn parse_mapping(spec: &str) -> Result<CsvColumnMapping, String> {
+    let mut mapping = CsvColumnMapping::default();
+    for entry in spec.split(',').filter(|e| !e.trim().is_empty()) {
+        let (field, value) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid mapping '{}', expected field=Header", entry))?;
+        let value = Some(value.trim().to_string());
+        match field.trim() {
+            "date" => mapping.date = value,
+            "description" => mapping.description = value,
+            "amount" => mapping.amount = value,
+            "debit" => mapping.debit = value,
+            "credit" => mapping.credit = value,
+            "reference" => mapping.reference = value,
+            "date_format" => mapping.date_format = value,
+            "amount_sign" => mapping.amount_sign = value,
+            other => return Err(format!("Unknown mapping field '{}'", other)),
+        }
+    }
+    Ok(mapping)
+}
+
+/// Parse a CSV statement with a header row. Recognized columns: date,
+/// description (or payee/name/memo), and either a signed amount or separate
+/// debit/credit columns; reference (or check number) is optional. Mapped
+/// headers take precedence; with amount_sign=positive_expenses a positive
+/// signed amount is money out. Also returns the date format used: the mapped
+/// one, else the one detected for the whole file (None if rows mix formats
+/// and were parsed one by one).
+pub fn parse_csv(
+    content: &str,
+    mapping: &CsvColumnMapping,
+) -> Result<(Vec<ImportRow>, Option<String>), String> {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = split_csv_line(lines.next().ok_or("CSV file is empty")?)
+        .into_iter()
+        .map(|h| h.trim_start_matches('\u{feff}').to_lowercase())
+        .collect();
+    let column = |mapped: &Option<String>, names: &[&str]| -> Result<Option<usize>, String> {
+        match mapped {
+            Some(name) => header
+                .iter()
+                .position(|h| *h == name.trim().to_lowercase())
+                .map(Some)
+                .ok_or_else(|| format!("CSV has no column '{}'", name)),
+            None => Ok(header.iter().position(|h| names.contains(&h.as_str()))),
+        }
+    };
+
+    let date_col = column(&mapping.date, &["date", "transaction date", "posted date", "posting date"])?
+        .ok_or("CSV needs a date column")?;
+    let desc_col = column(&mapping.description, &["description", "payee", "name", "merchant", "memo"])?
+        .ok_or("CSV needs a description column")?;
+    let amount_col = column(&mapping.amount, &["amount"])?;
+    let debit_col = column(&mapping.debit, &["debit", "withdrawal"])?;
+    let credit_col = column(&mapping.credit, &["credit", "deposit"])?;
+    let reference_col = column(&mapping.reference, &["reference", "check number", "ref"])?;
+    if amount_col.is_none() && debit_col.is_none() && credit_col.is_none() {
+        return Err("CSV needs an amount column or debit/credit columns".to_string());
+    }
+    let sign = match mapping.amount_sign.as_deref() {
+        Some(sign) => AmountSign::from_str(sign.trim())
+            .ok_or_else(|| format!("Unknown amount_sign '{}' (expected negative_expenses or positive_expenses)", sign))?,
+        None => AmountSign::NegativeExpenses,
+    };
+
+    let records: Vec<Vec<String>> = lines.map(split_csv_line).collect();
+    let date_format = match &mapping.date_format {
+        Some(format) => Some(format.clone()),
+        None => {
+            let dates: Vec<&str> = records
+                .iter()
+                .map(|fields| fields.get(date_col).map(String::as_str).unwrap_or(""))
+                .collect();
+            detect_date_format(&dates).map(str::to_string)
+        }
+    };
+
+    let mut rows = Vec::new();
+    for (i, fields) in records.iter().enumerate() {
+        let field = |col: Option<usize>| col.and_then(|c| fields.get(c)).map(String::as_str).unwrap_or("");
+        let line_no = i + 2;
+
+        let date = match &date_format {
+            Some(format) => parse_date_with(field(Some(date_col)), format),
+            None => parse_date(field(Some(date_col))),
+        }
+        .ok_or_else(|| format!("Line {}: invalid date '{}'", line_no, field(Some(date_col))))?;
+        let amount = match amount_col {
+            Some(_) if sign == AmountSign::PositiveExpenses => parse_amount(field(amount_col)).map(|v| -v),
+            Some(_) => parse_amount(field(amount_col)),
+            None => match (parse_amount(field(debit_col)), parse_amount(field(credit_col))) {
+                (Some(debit), _) if debit != 0.0 => Some(-debit.abs()),
+                (_, Some(credit)) => Some(credit.abs()),
+                (debit, None) => debit,
+            },
+        }
+        .ok_or_else(|| format!("Line {}: invalid amount", line_no))?;
+
+        rows.push(new_row(date, field(Some(desc_col)), amount, Some(field(reference_col).to_string())));
+    }
+    Ok((rows, date_format))
+}
+
+/// Value of an OFX (SGML) element inside a block, e.g. "<TRNAMT>-12.50".
+fn ofx_value(block: &str, tag: &str) -> Option<String> {
+    let start = block.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let rest = &block[start..];
+    let end = rest.find(['<', '\n', '\r']).unwrap_or(rest.len());
+    Some(rest[..end].trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Parse the STMTTRN entries of an OFX statement.
+pub fn parse_ofx(content: &str) -> Result<Vec<ImportRow>, String> {
+    let mut rows = Vec::new();
+    for block in content.split("<STMTTRN>").skip(1) {
+        let block = block.split("</STMTTRN>").next().unwrap_or(block);
+
+        let posted = ofx_value(block, "DTPOSTED").ok_or("OFX transaction without DTPOSTED")?;
+        let date = parse_date(posted.get(..8).unwrap_or(&posted))
+            .ok_or_else(|| format!("Invalid OFX date '{}'", posted))?;
+        let amount = ofx_value(block, "TRNAMT")
+            .and_then(|a| parse_amount(&a))
+            .ok_or("OFX transaction without a valid TRNAMT")?;
+        let description = ofx_value(block, "NAME")
+            .or_else(|| ofx_value(block, "MEMO"))
+            .unwrap_or_default();
+
+        rows.push(new_row(date, &description, amount, ofx_value(block, "CHECKNUM")));
+    }
+
+    if rows.is_empty() {
+        return Err("No transactions found in OFX file".to_string());
+    }
+    Ok(rows)
+}
+
+/// Most used category among the user's past transactions with this payee.
+async fn historical_category(
+    pool: &SqlitePool,
+    user_id: i64,
+    merchant: &str,
+) -> Result<Option<(i64, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT tc.category_id, c.name
+         FROM transactions t
+         INNER JOIN accounts a ON t.account_id = a.id
+         INNER JOIN transaction_categories tc ON tc.transaction_id = t.id
+         INNER JOIN categories c ON tc.category_id = c.id
+         WHERE a.user_id = ? AND t.merchant = ?
+         GROUP BY tc.category_id
+         ORDER BY COUNT(*) DESC, MAX(t.transaction_date) DESC
+         LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(merchant)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Fill in each row's suggestion: a matching rule first, then the payee's
+/// history. category_id starts out as the suggestion.
+pub async fn suggest_categories(
+    pool: &SqlitePool,
+    user_id: i64,
+    rows: &mut [ImportRow],
+) -> Result<(), sqlx::Error> {
+    let rules = rules::rules_for_user(pool, user_id).await?;
+    let names: HashMap<i64, String> =
+        sqlx::query_as::<_, (i64, String)>("SELECT id, name FROM categories WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    for row in rows.iter_mut() {
+        let suggestion = match rules::matching_rule(&rules, &row.description) {
+            Some(rule) => names
+                .get(&rule.category_id)
+                .map(|name| (rule.category_id, name.clone(), "rule")),
+            None => None,
+        };
+        let suggestion = match (suggestion, &row.merchant) {
+            (Some(s), _) => Some(s),
+            (None, Some(merchant)) => historical_category(pool, user_id, merchant)
+                .await?
+                .map(|(id, name)| (id, name, "history")),
+            (None, None) => None,
+        };
+
+        if let Some((category_id, name, source)) = suggestion {
+            row.suggested_category_id = Some(category_id);
+            row.suggested_category_name = Some(name);
+            row.suggestion_source = Some(source.to_string());
+            row.category_id = Some(category_id);
+        }
+    }
+    Ok(())
+}
+
+/// Pattern for a rule learned from a row: the normalized payee, else the description.
+pub fn rule_pattern(row: &ImportRow) -> String {
+    row.merchant.clone().unwrap_or_else(|| row.description.clone())
+}
+
+/// Same entry as far as duplicate detection goes: same day, type and amount,
+/// and the same reference or (case-insensitively) the same description.
+fn same_entry(a: &ImportRow, b: &ImportRow) -> bool {
+    a.transaction_date.date_naive() == b.transaction_date.date_naive()
+        && a.transaction_type == b.transaction_type
+        && (a.amount - b.amount).abs() < 0.005
+        && ((a.reference.is_some() && a.reference == b.reference)
+            || a.description.to_lowercase() == b.description.to_lowercase())
+}
+
+/// Flag rows already recorded in the account. Identical rows within the file
+/// (two coffees on the same day) are only flagged while the account has at
+/// least as many matching transactions, so re-importing an overlapping
+/// statement skips what is there and keeps what is new.
+pub async fn mark_duplicates(
+    pool: &SqlitePool,
+    account_id: i64,
+    rows: &mut [ImportRow],
+) -> Result<(), sqlx::Error> {
+    for i in 0..rows.len() {
+        let row = &rows[i];
+        let existing: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM transactions
+             WHERE account_id = ? AND date(transaction_date) = date(?)
+             AND transaction_type = ? AND ABS(amount - ?) < 0.005
+             AND (reference = ? OR LOWER(description) = LOWER(?))",
+        )
+        .bind(account_id)
+        .bind(row.transaction_date)
+        .bind(&row.transaction_type)
+        .bind(row.amount)
+        .bind(&row.reference)
+        .bind(&row.description)
+        .fetch_one(pool)
+        .await?;
+        let earlier = rows[..i].iter().filter(|other| same_entry(other, row)).count() as i64;
+        rows[i].duplicate = earlier < existing;
+    }
+    Ok(())
+}
+
+/// Insert the reviewed rows into the account in one DB transaction, recorded
+/// as an import batch. Rows whose category was changed from the suggestion
+/// either become rules (create_rule) or are returned as rule suggestions.
+pub async fn commit_import(
+    pool: &SqlitePool,
+    user_id: i64,
+    account_id: i64,
+    rows: &[ImportRow],
+) -> Result<ImportResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut transaction_ids = Vec::new();
+
+    for row in rows {
+        let result = sqlx::query(
+            "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, reference, merchant) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(account_id)
+        .bind(row.amount)
+        .bind(&row.transaction_type)
+        .bind(&row.description)
+        .bind(row.transaction_date)
+        .bind(&row.reference)
+        .bind(&row.merchant)
+        .execute(&mut *tx)
+        .await?;
+        let transaction_id = result.last_insert_rowid();
+
+        if let Some(category_id) = row.category_id {
+            sqlx::query(
+                "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)",
+            )
+            .bind(transaction_id)
+            .bind(category_id)
+            .bind(row.amount)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let balance_change = if row.transaction_type == "income" { row.amount } else { -row.amount };
+        sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+            .bind(balance_change)
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+            .bind(transaction_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let payload = serde_json::to_value(&transaction).unwrap_or_default();
+        webhooks::enqueue_event(&mut tx, user_id, "transaction.created", &payload).await?;
+
+        transaction_ids.push(transaction_id);
+    }
+
+    if !rows.is_empty() {
+        sqlx::query("INSERT INTO import_batches (user_id, account_id, row_count) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(account_id)
+            .bind(rows.len() as i64)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    let mut rules_created = Vec::new();
+    let mut rule_suggestions: Vec<RuleSuggestion> = Vec::new();
+    for row in rows {
+        let Some(category_id) = row.category_id else { continue };
+        if row.suggested_category_id == Some(category_id) {
+            continue;
+        }
+        let suggestion = RuleSuggestion {
+            pattern: rule_pattern(row),
+            category_id,
+        };
+        if row.create_rule {
+            rules_created.push(rules::create_rule(pool, user_id, &suggestion.pattern, category_id).await?);
+        } else if !rule_suggestions.contains(&suggestion) {
+            rule_suggestions.push(suggestion);
+        }
+    }
+
+    Ok(ImportResult {
+        imported: transaction_ids.len(),
+        transaction_ids,
+        rules_created,
+        rule_suggestions,
+    })
+}
+
+pub async fn account_opened_on(
+    pool: &SqlitePool,
+    account_id: i64,
+) -> Result<Option<NaiveDate>, sqlx::Error> {
+    sqlx::query_scalar("SELECT opened_on FROM accounts WHERE id = ?")
+        .bind(account_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Row dated before the account was opened, so already part of its initial balance
+pub fn before_opening(row: &ImportRow, opened_on: Option<NaiveDate>) -> bool {
+    opened_on.is_some_and(|d| row.transaction_date.date_naive() < d)
+}
+
+/// Import parsed CSV rows as-is: categories come from the suggestions, and
+/// rows already in the account or dated before it was opened are skipped.
+pub async fn import_csv(
+    pool: &SqlitePool,
+    user_id: i64,
+    account_id: i64,
+    rows: Vec<ImportRow>,
+    date_format: Option<String>,
+) -> Result<CsvImportResult, sqlx::Error> {
+    let opened_on = account_opened_on(pool, account_id).await?;
+    let (predating, mut rows): (Vec<ImportRow>, Vec<ImportRow>) =
+        rows.into_iter().partition(|row| before_opening(row, opened_on));
+
+    suggest_categories(pool, user_id, &mut rows).await?;
+    mark_duplicates(pool, account_id, &mut rows).await?;
+    let (duplicates, new_rows): (Vec<ImportRow>, Vec<ImportRow>) =
+        rows.into_iter().partition(|row| row.duplicate);
+
+    let result = commit_import(pool, user_id, account_id, &new_rows).await?;
+    Ok(CsvImportResult {
+        imported: result.imported,
+        duplicates_skipped: duplicates.len(),
+        before_opening_skipped: predating.len(),
+        date_format,
+        transaction_ids: result.transaction_ids,
+    })
+}
+
+/// Save a named mapping for the user, replacing their profile of the same name.
+/// Call CreateImportProfile::validate first.
+pub async fn save_profile(pool: &SqlitePool, data: &CreateImportProfile) -> Result<ImportProfile, sqlx::Error> {
+    let name = data.name.trim();
+    let m = &data.mapping;
+    sqlx::query(
+        "INSERT INTO import_profiles (user_id, name, account_id, date_column, description_column, amount_column, debit_column, credit_column, reference_column, date_format, amount_sign)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(user_id, name) DO UPDATE SET
+             account_id = excluded.account_id, date_column = excluded.date_column,
+             description_column = excluded.description_column, amount_column = excluded.amount_column,
+             debit_column = excluded.debit_column, credit_column = excluded.credit_column,
+             reference_column = excluded.reference_column, date_format = excluded.date_format,
+             amount_sign = excluded.amount_sign",
+    )
+    .bind(data.user_id)
+    .bind(name)
+    .bind(data.account_id)
+    .bind(&m.date)
+    .bind(&m.description)
+    .bind(&m.amount)
+    .bind(&m.debit)
+    .bind(&m.credit)
+    .bind(&m.reference)
+    .bind(&m.date_format)
+    .bind(m.amount_sign.as_deref().unwrap_or(AmountSign::NegativeExpenses.as_str()))
+    .execute(pool)
+    .await?;
+
+    sqlx::query_as::<_, ImportProfile>("SELECT * FROM import_profiles WHERE user_id = ? AND name = ?")
+        .bind(data.user_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn profiles_for_user(pool: &SqlitePool, user_id: i64) -> Result<Vec<ImportProfile>, sqlx::Error> {
+    sqlx::query_as::<_, ImportProfile>("SELECT * FROM import_profiles WHERE user_id = ? ORDER BY name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// The mapping parse_csv reads a profile's statements with
+pub fn profile_mapping(profile: &ImportProfile) -> CsvColumnMapping {
+    CsvColumnMapping {
+        date: profile.date_column.clone(),
+        description: profile.description_column.clone(),
+        amount: profile.amount_column.clone(),
+        debit: profile.debit_column.clone(),
+        credit: profile.credit_column.clone(),
+        reference: profile.reference_column.clone(),
+        date_format: profile.date_format.clone(),
+        amount_sign: Some(profile.amount_sign.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_parse_csv_signed_and_split_amounts() {
+        let (rows, format) = parse_csv(
+            "Date,Description,Amount\n2025-03-01,\"STARBUCKS #1234, TORONTO\",-4.50\n03/02/2025,Payroll,2000\n",
+            &CsvColumnMapping::default(),
+        )
+        .unwrap();
+        assert_eq!(format, None); // Mixed formats, parsed row by row
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].description, "STARBUCKS #1234, TORONTO");
+        assert_eq!(rows[0].transaction_type, "expense");
+        assert_eq!(rows[0].amount, 4.5);
+        assert_eq!(rows[1].transaction_type, "income");
+
+        let (rows, _) = parse_csv(
+            "Posted Date,Payee,Debit,Credit\n2025-03-01,Rent,1500.00,\n",
+            &CsvColumnMapping::default(),
+        )
+        .unwrap();
+        assert_eq!(rows[0].transaction_type, "expense");
+        assert_eq!(rows[0].amount, 1500.0);
+
+        assert!(parse_csv("Date,Description,Amount\nyesterday,Coffee,-4\n", &CsvColumnMapping::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_ofx_transactions() {
+        let ofx = "<OFX><BANKTRANLIST>\n<STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20250301120000\n<TRNAMT>-12.50\n<NAME>SQ *BLUE BOTTLE\n<CHECKNUM>42\n</STMTTRN>\n</BANKTRANLIST></OFX>";
+        let rows = parse_ofx(ofx).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].amount, 12.5);
+        assert_eq!(rows[0].merchant.as_deref(), Some("Blue Bottle"));
+        assert_eq!(rows[0].reference.as_deref(), Some("42"));
+        assert_eq!(rows[0].transaction_date.format("%Y-%m-%d").to_string(), "2025-03-01");
+    }
+
+    #[test]
+    fn test_parse_csv_mapping_and_date_detection() {
+        // 13/03 only fits day-first, so 01/03 is read as 1 March too
+        let (rows, format) = parse_csv(
+            "Date,Details,Value\n01/03/2025,Rent,-1500\n13/03/2025,Refund,20\n",
+            &parse_mapping("description=details,amount=Value").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(rows[0].transaction_date.format("%Y-%m-%d").to_string(), "2025-03-01");
+        assert_eq!(rows[0].description, "Rent");
+
+        let (rows, _) = parse_csv(
+            "Date,Description,Amount\n03/02/25,Coffee,-4\n",
+            &parse_mapping("date_format=%m/%d/%y").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(rows[0].transaction_date.format("%Y-%m-%d").to_string(), "2025-03-02");
+
+        assert!(parse_csv("Date,Description,Amount\n", &parse_mapping("amount=Total").unwrap()).is_err());
+        assert!(parse_mapping("colour=Red").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_saved_profile_reimports_with_its_mapping() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let card = testing::account(user.id).insert(&pool).await;
+
+        let mut data = CreateImportProfile {
+            user_id: user.id,
+            name: "Visa".to_string(),
+            account_id: None,
+            mapping: parse_mapping("date=Posted,description=Merchant,amount=Charge,amount_sign=positive_expenses").unwrap(),
+        };
+        assert!(data.validate().is_ok());
+        save_profile(&pool, &data).await.unwrap();
+        // Saving under the same name replaces the profile
+        data.account_id = Some(card.id);
+        let profile = save_profile(&pool, &data).await.unwrap();
+        assert_eq!(profiles_for_user(&pool, user.id).await.unwrap().len(), 1);
+        assert_eq!(profile.account_id, Some(card.id));
+
+        let csv = "Posted,Merchant,Charge\n2025-06-03,Bookstore,23.10\n2025-06-05,Payment received,-200\n";
+        let (rows, _) = parse_csv(csv, &profile_mapping(&profile)).unwrap();
+        assert_eq!((rows[0].transaction_type.as_str(), rows[0].amount), ("expense", 23.1));
+        assert_eq!(rows[1].transaction_type, "income");
+
+        data.mapping.amount_sign = Some("backwards".to_string());
+        assert!(data.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_csv_skips_duplicates_and_updates_balance() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let may_1 = Utc.with_ymd_and_hms(2025, 5, 1, 15, 30, 0).unwrap();
+        testing::txn(account.id).amount(4.5).description("Coffee").date(may_1).insert(&pool).await;
+
+        let csv = "Date,Description,Amount\n2025-05-01,COFFEE,-4.50\n2025-05-01,Coffee,-4.50\n2025-05-02,Payroll,50\n";
+        let (rows, format) = parse_csv(csv, &CsvColumnMapping::default()).unwrap();
+        let result = import_csv(&pool, user.id, account.id, rows, format).await.unwrap();
+        assert_eq!((result.imported, result.duplicates_skipped), (2, 1));
+        assert_eq!(result.date_format.as_deref(), Some("%Y-%m-%d"));
+
+        let balance: f64 = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(account.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!((balance - 145.5).abs() < 1e-9);
+
+        // Importing the same file again adds nothing
+        let (rows, format) = parse_csv(csv, &CsvColumnMapping::default()).unwrap();
+        let result = import_csv(&pool, user.id, account.id, rows, format).await.unwrap();
+        assert_eq!((result.imported, result.duplicates_skipped), (0, 3));
+    }
+}
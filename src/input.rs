@@ -0,0 +1,126 @@
+// input.rs
+// Limits on what clients can send: request body sizes per endpoint, lengths
+// of names and free text, and control characters, which are stripped as
+// request bodies are read. Stray escape codes, carriage returns or bidi
+// overrides in a description would otherwise garble the TUI's tables.
+
+use serde::{Deserialize, Deserializer};
+
+/// Request bodies of ordinary JSON endpoints
+pub const JSON_BODY_BYTES: usize = 64 * 1024;
+
+/// Statement files sent to the import endpoints
+pub const IMPORT_BODY_BYTES: usize = 5 * 1024 * 1024;
+
+/// Receipt images and attachment uploads
+pub const UPLOAD_BODY_BYTES: usize = crate::receipts::MAX_RECEIPT_BYTES;
+
+/// Names: users, accounts, categories, tags, merchants, references...
+pub const MAX_NAME_LEN: usize = 100;
+
+/// One-line transaction descriptions
+pub const MAX_DESCRIPTION_LEN: usize = 500;
+
+/// Long-form transaction notes
+pub const MAX_NOTES_LEN: usize = 5000;
+
+/// Largest request body accepted at `path`
+pub fn body_limit(path: &str) -> usize {
+    if path == "/transactions/receipt" || path.ends_with("/attachments") {
+        UPLOAD_BODY_BYTES
+    } else if path.starts_with("/import/") {
+        IMPORT_BODY_BYTES
+    } else {
+        JSON_BODY_BYTES
+    }
+}
+
+/// Characters that change how text is laid out instead of being shown:
+/// controls (escape, backspace...) and the bidi embedding/override marks
+fn is_layout_control(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// The value on one line: tabs and line breaks become spaces, other control
+/// characters are dropped
+pub fn clean_line(value: &str) -> String {
+    value
+        .chars()
+        .filter_map(|c| match c {
+            '\t' | '\n' | '\r' => Some(' '),
+            c if is_layout_control(c) => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// The value with line breaks and tabs kept (as \n), other control characters dropped
+pub fn clean_text(value: &str) -> String {
+    value
+        .replace("\r\n", "\n")
+        .chars()
+        .filter_map(|c| match c {
+            '\r' => Some('\n'),
+            '\t' | '\n' => Some(c),
+            c if is_layout_control(c) => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// Error if `value` is longer than `max` characters
+pub fn check_length(field: &str, value: Option<&str>, max: usize) -> Result<(), String> {
+    match value {
+        Some(value) if value.chars().count() > max => {
+            Err(format!("{} must be at most {} characters", field, max))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `#[serde(deserialize_with = "crate::input::line")]` on one-line text fields
+pub fn line<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    String::deserialize(deserializer).map(|value| clean_line(&value))
+}
+
+/// Optional one-line text fields; use with `#[serde(default)]`
+pub fn optional_line<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(|value| value.map(|value| clean_line(&value)))
+}
+
+/// Optional multi-line text fields; use with `#[serde(default)]`
+pub fn optional_text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(|value| value.map(|value| clean_text(&value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateTransaction;
+
+    #[test]
+    fn test_request_text_is_cleaned_and_limited() {
+        assert_eq!(clean_line("Coffee\x1b[31m\tshop\r\n"), "Coffee[31m shop  ");
+        assert_eq!(clean_line("\u{202E}evil\u{7}"), "evil");
+        assert_eq!(clean_text("line one\r\nline two\x00\ttab\rend"), "line one\nline two\ttab\nend");
+
+        let txn: CreateTransaction = serde_json::from_str(
+            r#"{"account_id": 1, "amount": 5.0, "transaction_type": "expense", "categories": [],
+                "description": "Lunch\u001b[2J\nwith team", "notes": "first\r\nsecond\u0008"}"#,
+        )
+        .unwrap();
+        assert_eq!(txn.description.as_deref(), Some("Lunch[2J with team"));
+        assert_eq!(txn.notes.as_deref(), Some("first\nsecond"));
+        assert_eq!(txn.merchant, None);
+        txn.validate().unwrap();
+
+        let long = CreateTransaction { description: Some("x".repeat(MAX_DESCRIPTION_LEN + 1)), ..txn };
+        assert!(long.validate().unwrap_err().contains("at most 500"));
+
+        assert_eq!(body_limit("/transactions/receipt"), UPLOAD_BODY_BYTES);
+        assert_eq!(body_limit("/transactions/7/attachments"), UPLOAD_BODY_BYTES);
+        assert_eq!(body_limit("/import/transactions/csv"), IMPORT_BODY_BYTES);
+        assert_eq!(body_limit("/transactions"), JSON_BODY_BYTES);
+        assert!(check_length("Name", Some("ééé"), 3).is_ok());
+    }
+}
@@ -0,0 +1,318 @@
+// ledger.rs
+// Plaintext accounting export (beancount / ledger-cli)
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::models::{Account, Category, Transaction, TransactionCategory};
+
+const OPENING_BALANCES: &str = "Equity:Opening-Balances";
+const TRANSFERS: &str = "Equity:Transfers";
+const UNCATEGORIZED_INCOME: &str = "Income:Uncategorized";
+const UNCATEGORIZED_EXPENSES: &str = "Expenses:Uncategorized";
+const FEES: &str = "Expenses:Fees";
+
+/// Supported plaintext accounting dialects
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LedgerFormat {
+    Beancount,
+    Ledger,
+}
+
+impl LedgerFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "beancount" | "bean" => Some(LedgerFormat::Beancount),
+            "ledger" | "ledger-cli" => Some(LedgerFormat::Ledger),
+            _ => None,
+        }
+    }
+
+    pub fn file_extension(&self) -> &str {
+        match self {
+            LedgerFormat::Beancount => "beancount",
+            LedgerFormat::Ledger => "ledger",
+        }
+    }
+}
+
+/// Turn an arbitrary name into a valid account component ("chase checking" -> "Chase-Checking").
+fn component(name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    if words.is_empty() {
+        "Unknown".to_string()
+    } else {
+        words.join("-")
+    }
+}
+
+/// Account name for a bank account, e.g. "Assets:Checking:Chase-Checking".
+fn asset_account(account: &Account) -> String {
+    let root = match account.account_type.as_str() {
+        "credit_card" => "Liabilities:CreditCard",
+        "loan" => "Liabilities:Loan",
+        "savings" => "Assets:Savings",
+        "investment" => "Assets:Investment",
+        "cash" => "Assets:Cash",
+        _ => "Assets:Checking",
+    };
+    format!("{}:{}", root, component(&account.name))
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace(['\r', '\n'], " "))
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// Render accounts and transactions as a plaintext ledger.
+///
+/// Every transaction balances: the bank account posting carries the signed
+/// amount minus any fee, category splits become Income:/Expenses: postings,
+/// the unsplit remainder goes to an Uncategorized (or Equity:Transfers)
+/// account and the fee is booked to its fee category or Expenses:Fees.
+pub fn render(
+    format: LedgerFormat,
+    accounts: &[Account],
+    transactions: &[Transaction],
+    splits: &[TransactionCategory],
+    categories: &[Category],
+) -> String {
+    let category_names: HashMap<i64, &str> =
+        categories.iter().map(|c| (c.id, c.name.as_str())).collect();
+
+    // Disambiguate bank accounts with the same name
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+    for account in accounts {
+        *name_counts.entry(asset_account(account)).or_insert(0) += 1;
+    }
+    let account_names: HashMap<i64, String> = accounts
+        .iter()
+        .map(|a| {
+            let name = asset_account(a);
+            if name_counts[&name] > 1 {
+                (a.id, format!("{}-{}", name, a.id))
+            } else {
+                (a.id, name)
+            }
+        })
+        .collect();
+    let accounts_by_id: HashMap<i64, &Account> = accounts.iter().map(|a| (a.id, a)).collect();
+
+    let mut splits_by_txn: HashMap<i64, Vec<&TransactionCategory>> = HashMap::new();
+    for split in splits {
+        splits_by_txn.entry(split.transaction_id).or_default().push(split);
+    }
+
+    let date_fmt = match format {
+        LedgerFormat::Beancount => "%Y-%m-%d",
+        LedgerFormat::Ledger => "%Y/%m/%d",
+    };
+
+    // Accounts are opened (and opening balances booked) on their opening date.
+    // Without one, and for the equity/income/expense accounts, use the first
+    // entry, since seeded or imported transactions can predate the account row
+    let open_date = accounts
+        .iter()
+        .map(|a| a.created_at.date_naive())
+        .chain(accounts.iter().filter_map(|a| a.opened_on))
+        .chain(transactions.iter().map(|t| t.transaction_date.date_naive()))
+        .min()
+        .map(|d| d.format(date_fmt).to_string())
+        .unwrap_or_else(|| "1970-01-01".to_string());
+    let account_open_date = |account: &Account| match account.opened_on {
+        Some(opened_on) => opened_on.format(date_fmt).to_string(),
+        None => open_date.clone(),
+    };
+
+    let mut used_accounts: BTreeSet<String> = BTreeSet::new();
+    let mut body = String::new();
+
+    // Opening balances
+    for account in accounts {
+        if account.initial_balance.abs() < 0.005 {
+            continue;
+        }
+        let name = &account_names[&account.id];
+        match format {
+            LedgerFormat::Beancount => body.push_str(&format!(
+                "{} * \"Opening balance\"\n",
+                account_open_date(account)
+            )),
+            LedgerFormat::Ledger => body.push_str(&format!(
+                "{} * Opening balance\n",
+                account_open_date(account)
+            )),
+        }
+        body.push_str(&format!(
+            "  {}  {:.2} {}\n  {}\n\n",
+            name, account.initial_balance, account.currency, OPENING_BALANCES
+        ));
+        used_accounts.insert(OPENING_BALANCES.to_string());
+    }
+
+    for t in transactions {
+        let account = match accounts_by_id.get(&t.account_id) {
+            Some(account) => account,
+            None => continue,
+        };
+        let currency = &account.currency;
+        let amount = t.amount.abs();
+        let narration = t.description.as_deref().unwrap_or("");
+
+        // Header line
+        match format {
+            LedgerFormat::Beancount => match t.merchant.as_deref() {
+                Some(payee) => body.push_str(&format!(
+                    "{} * {} {}\n",
+                    t.transaction_date.format(date_fmt),
+                    quote(payee),
+                    quote(narration)
+                )),
+                None => body.push_str(&format!(
+                    "{} * {}\n",
+                    t.transaction_date.format(date_fmt),
+                    quote(narration)
+                )),
+            },
+            LedgerFormat::Ledger => {
+                let payee = match (t.merchant.as_deref(), narration) {
+                    (Some(payee), "") => payee.to_string(),
+                    (Some(payee), narration) => format!("{} | {}", payee, narration),
+                    (None, narration) => narration.to_string(),
+                };
+                body.push_str(&format!(
+                    "{} * {}\n",
+                    t.transaction_date.format(date_fmt),
+                    single_line(&payee)
+                ));
+            }
+        }
+
+        // Metadata
+        let mut metadata: Vec<(&str, String)> = vec![("id", t.id.to_string())];
+        if let Some(ref reference) = t.reference {
+            metadata.push(("reference", reference.clone()));
+        }
+        if let Some(ref notes) = t.notes {
+            metadata.push(("notes", notes.clone()));
+        }
+        if let (Some(original), Some(original_currency)) = (t.original_amount, t.original_currency.as_deref()) {
+            metadata.push(("original", format!("{:.2} {}", original, original_currency)));
+        }
+        for (key, value) in &metadata {
+            match format {
+                LedgerFormat::Beancount => body.push_str(&format!("  {}: {}\n", key, quote(value))),
+                LedgerFormat::Ledger => body.push_str(&format!("    ; {}: {}\n", key, single_line(value))),
+            }
+        }
+
+        // Postings
+        let is_income = t.transaction_type == "income";
+        let sign = if is_income { 1.0 } else { -1.0 };
+        let mut postings: Vec<(String, f64)> = Vec::new();
+        postings.push((account_names[&account.id].clone(), sign * amount - t.fee));
+
+        let mut categorized = 0.0;
+        for split in splits_by_txn.get(&t.id).into_iter().flatten() {
+            let category = category_names.get(&split.category_id).copied().unwrap_or("Unknown");
+            let root = if is_income { "Income" } else { "Expenses" };
+            postings.push((format!("{}:{}", root, component(category)), -sign * split.amount.abs()));
+            categorized += split.amount.abs();
+        }
+
+        let remainder = amount - categorized;
+        if remainder > 0.005 {
+            // The credit leg of a transfer between two accounts balances the debit leg
+            let counter = match t.transaction_type.as_str() {
+                _ if t.transfer_group_id.is_some() => TRANSFERS,
+                "income" => UNCATEGORIZED_INCOME,
+                "transfer" => TRANSFERS,
+                _ => UNCATEGORIZED_EXPENSES,
+            };
+            postings.push((counter.to_string(), -sign * remainder));
+        }
+
+        if t.fee > 0.0 {
+            let fee_account = t
+                .fee_category_id
+                .and_then(|id| category_names.get(&id))
+                .map(|name| format!("Expenses:{}", component(name)))
+                .unwrap_or_else(|| FEES.to_string());
+            postings.push((fee_account, t.fee));
+        }
+
+        for (name, value) in &postings {
+            body.push_str(&format!("  {}  {:.2} {}\n", name, value, currency));
+            if !name.starts_with("Assets:") && !name.starts_with("Liabilities:") {
+                used_accounts.insert(name.clone());
+            }
+        }
+        body.push('\n');
+    }
+
+    // Header: options and account declarations
+    let mut out = String::new();
+    match format {
+        LedgerFormat::Beancount => {
+            out.push_str("; Exported from Personal Finance Tracker\n");
+            out.push_str("option \"title\" \"Personal Finance Tracker\"\n");
+            let mut currencies: Vec<&str> = accounts.iter().map(|a| a.currency.as_str()).collect();
+            currencies.sort();
+            currencies.dedup();
+            for currency in currencies {
+                out.push_str(&format!("option \"operating_currency\" \"{}\"\n", currency));
+            }
+            out.push('\n');
+
+            for account in accounts {
+                out.push_str(&format!(
+                    "{} open {} {}\n",
+                    account_open_date(account),
+                    account_names[&account.id],
+                    account.currency
+                ));
+            }
+            for name in &used_accounts {
+                out.push_str(&format!("{} open {}\n", open_date, name));
+            }
+        }
+        LedgerFormat::Ledger => {
+            out.push_str("; Exported from Personal Finance Tracker\n");
+            for account in accounts {
+                out.push_str(&format!("account {}\n", account_names[&account.id]));
+            }
+            for name in &used_accounts {
+                out.push_str(&format!("account {}\n", name));
+            }
+        }
+    }
+    out.push('\n');
+    out.push_str(&body);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_sanitizes_names() {
+        assert_eq!(component("chase checking"), "Chase-Checking");
+        assert_eq!(component("Dining Out / Bars"), "Dining-Out-Bars");
+        assert_eq!(component("401k"), "401k");
+        assert_eq!(component("  "), "Unknown");
+    }
+}
@@ -0,0 +1,311 @@
+// loans.rs
+// Loan accounts: the principal, rate and term of a loan, the amortization
+// schedule of its fixed monthly payment, and the recurring transaction that
+// pays it. Each payment is an expense of the paying account split between a
+// principal and an interest category; the principal part is also credited to
+// the loan account, left out of reports, so what's owed goes down with it.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::SqlitePool;
+
+use crate::fx;
+use crate::models::{Account, Amortization, AmortizationRow, CreateLoan, Loan};
+use crate::recurring;
+
+const PRINCIPAL_CATEGORY: &str = "Loan Principal";
+const INTEREST_CATEGORY: &str = "Loan Interest";
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(chrono::NaiveTime::MIN).and_utc()
+}
+
+/// Why `payment_account` can't pay off the loan in `loan_account`, if anything
+pub fn validate_accounts(loan_account: &Account, payment_account: &Account) -> Result<(), String> {
+    if loan_account.account_type != "loan" {
+        return Err("Only loan accounts can have loan terms".to_string());
+    }
+    if payment_account.id == loan_account.id || payment_account.account_type == "loan" {
+        return Err("The payment account can't be a loan account".to_string());
+    }
+    if payment_account.user_id != loan_account.user_id {
+        return Err(format!("Account {} doesn't belong to this user", payment_account.id));
+    }
+    if payment_account.currency != loan_account.currency {
+        return Err(format!(
+            "Account {} is in {}, the loan is in {}",
+            payment_account.id, payment_account.currency, loan_account.currency
+        ));
+    }
+    Ok(())
+}
+
+/// Fixed monthly payment, in cents, that pays off `principal` over
+/// `term_months` at `annual_rate` percent a year
+pub fn monthly_payment(principal: f64, annual_rate: f64, term_months: i64) -> f64 {
+    let rate = annual_rate / 100.0 / 12.0;
+    let payment = if rate == 0.0 {
+        principal / term_months as f64
+    } else {
+        principal * rate / (1.0 - (1.0 + rate).powi(-(term_months as i32)))
+    };
+    round_cents(payment)
+}
+
+/// Every payment of the loan. Interest is rounded to cents each month and
+/// the last payment settles whatever is left.
+pub fn schedule(loan: &Loan) -> Vec<AmortizationRow> {
+    let rate = loan.annual_rate / 100.0 / 12.0;
+    let payment = monthly_payment(loan.principal, loan.annual_rate, loan.term_months);
+    let mut rows = Vec::with_capacity(loan.term_months as usize);
+    let mut balance = loan.principal;
+    let mut date = midnight(loan.first_payment_date);
+    for number in 1..=loan.term_months {
+        let interest = round_cents(balance * rate);
+        let principal = if number == loan.term_months {
+            balance
+        } else {
+            (payment - interest).min(balance)
+        };
+        balance = round_cents(balance - principal);
+        rows.push(AmortizationRow {
+            number,
+            date: date.date_naive(),
+            payment: round_cents(principal + interest),
+            principal: round_cents(principal),
+            interest,
+            balance,
+        });
+        if balance == 0.0 {
+            break;
+        }
+        date = recurring::calculate_next_occurrence(date, "monthly");
+    }
+    rows
+}
+
+/// The loan with its payment plan
+pub fn amortization(loan: Loan) -> Amortization {
+    let schedule = schedule(&loan);
+    Amortization {
+        monthly_payment: monthly_payment(loan.principal, loan.annual_rate, loan.term_months),
+        total_interest: round_cents(schedule.iter().map(|row| row.interest).sum()),
+        schedule,
+        loan,
+    }
+}
+
+pub async fn loan_for_account(pool: &SqlitePool, account_id: i64) -> Result<Option<Loan>, sqlx::Error> {
+    sqlx::query_as::<_, Loan>("SELECT * FROM loans WHERE account_id = ?")
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The loan paid by this recurring transaction, if any
+pub async fn loan_for_recurring(pool: &SqlitePool, recurring_id: i64) -> Result<Option<Loan>, sqlx::Error> {
+    sqlx::query_as::<_, Loan>("SELECT * FROM loans WHERE recurring_transaction_id = ?")
+        .bind(recurring_id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn category_id(conn: &mut sqlx::SqliteConnection, user_id: i64, name: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO categories (user_id, name) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query_scalar("SELECT id FROM categories WHERE user_id = ? AND name = ?")
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+/// Set the loan terms on `account` and schedule its monthly payment from
+/// `data.payment_account_id`, filed under the user's loan principal and
+/// interest categories (created when missing). An account without
+/// transactions yet starts out owing the principal.
+/// Call validate_accounts first.
+pub async fn create_loan(pool: &SqlitePool, account: &Account, data: &CreateLoan) -> Result<Loan, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let principal_category_id = category_id(&mut tx, account.user_id, PRINCIPAL_CATEGORY).await?;
+    let interest_category_id = category_id(&mut tx, account.user_id, INTEREST_CATEGORY).await?;
+
+    let mut loan = sqlx::query_as::<_, Loan>(
+        "INSERT INTO loans (account_id, principal, annual_rate, term_months, first_payment_date, payment_account_id, principal_category_id, interest_category_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(account.id)
+    .bind(data.principal)
+    .bind(data.annual_rate)
+    .bind(data.term_months)
+    .bind(data.first_payment_date)
+    .bind(data.payment_account_id)
+    .bind(principal_category_id)
+    .bind(interest_category_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let plan = schedule(&loan);
+    let start = midnight(loan.first_payment_date);
+    let end = plan.last().map(|row| midnight(row.date));
+    let recurring_id = sqlx::query(
+        "INSERT INTO recurring_transactions (account_id, category_id, amount, transaction_type, description, frequency, start_date, end_date, next_occurrence, is_active, classification)
+         VALUES (?, ?, ?, 'expense', ?, 'monthly', ?, ?, ?, 1, 'fixed')",
+    )
+    .bind(data.payment_account_id)
+    .bind(principal_category_id)
+    .bind(monthly_payment(loan.principal, loan.annual_rate, loan.term_months))
+    .bind(format!("{} payment", account.name))
+    .bind(start)
+    .bind(end)
+    .bind(start)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+    sqlx::query("UPDATE loans SET recurring_transaction_id = ? WHERE account_id = ?")
+        .bind(recurring_id)
+        .bind(account.id)
+        .execute(&mut *tx)
+        .await?;
+    loan.recurring_transaction_id = Some(recurring_id);
+
+    sqlx::query(
+        "UPDATE accounts SET initial_balance = ?, current_balance = ?
+         WHERE id = ? AND NOT EXISTS (SELECT 1 FROM transactions WHERE account_id = ?)",
+    )
+    .bind(-loan.principal)
+    .bind(-loan.principal)
+    .bind(account.id)
+    .bind(account.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(loan)
+}
+
+/// The scheduled payment due on `date`, None when none is
+pub fn payment_on(loan: &Loan, date: NaiveDate) -> Option<AmortizationRow> {
+    schedule(loan).into_iter().find(|row| row.date == date)
+}
+
+/// Book one posted loan payment: split `payment_id` between the principal
+/// and interest categories and credit the principal to the loan account
+pub async fn book_payment(
+    pool: &SqlitePool,
+    loan: &Loan,
+    row: &AmortizationRow,
+    payment_id: i64,
+    description: Option<&str>,
+    occurrence: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let (base_amount, base_currency) =
+        fx::base_amount_snapshot(pool, loan.account_id, row.principal, occurrence)
+            .await?
+            .unzip();
+    let mut tx = pool.begin().await?;
+    for (category_id, amount) in [(loan.principal_category_id, row.principal), (loan.interest_category_id, row.interest)] {
+        if amount > 0.0 {
+            sqlx::query("INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)")
+                .bind(payment_id)
+                .bind(category_id)
+                .bind(amount)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+    sqlx::query(
+        "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, base_amount, base_currency, linked_transaction_id, exclude_from_reports)
+         VALUES (?, ?, 'income', ?, ?, ?, ?, ?, 1)",
+    )
+    .bind(loan.account_id)
+    .bind(row.principal)
+    .bind(description)
+    .bind(occurrence)
+    .bind(base_amount)
+    .bind(base_currency)
+    .bind(payment_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+        .bind(row.principal)
+        .bind(loan.account_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_loan_schedule_and_payments_split_principal_and_interest() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).balance(5000.0).insert(&pool).await;
+        let car = testing::account(user.id).name("Car loan").account_type("loan").insert(&pool).await;
+        let euros = testing::account(user.id).currency("EUR").insert(&pool).await;
+        assert!(validate_accounts(&checking, &car).is_err());
+        assert!(validate_accounts(&car, &euros).is_err());
+        validate_accounts(&car, &checking).unwrap();
+
+        // 12,000 at 6% over 12 months: 1,032.80 a month
+        let data = CreateLoan {
+            principal: 12000.0,
+            annual_rate: 6.0,
+            term_months: 12,
+            first_payment_date: NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            payment_account_id: checking.id,
+        };
+        let loan = create_loan(&pool, &car, &data).await.unwrap();
+        let plan = amortization(loan.clone());
+        assert_eq!(plan.monthly_payment, 1032.8);
+        assert_eq!(plan.schedule.len(), 12);
+        let first = &plan.schedule[0];
+        assert_eq!((first.interest, first.principal, first.balance), (60.0, 972.8, 11027.2));
+        // Month ends are kept, as recurring payments do
+        assert_eq!(plan.schedule[1].date, NaiveDate::from_ymd_opt(2025, 2, 28).unwrap());
+        let last = plan.schedule.last().unwrap();
+        assert_eq!(last.balance, 0.0);
+        let principal: f64 = plan.schedule.iter().map(|row| row.principal).sum();
+        assert!((principal - 12000.0).abs() < 1e-6);
+        assert!((plan.total_interest - 393.6).abs() < 0.05);
+        assert_eq!(monthly_payment(1200.0, 0.0, 12), 100.0);
+
+        let owed: f64 = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(car.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(owed, -12000.0);
+
+        let due = midnight(data.first_payment_date);
+        recurring::process_due_recurring(&pool, due + Duration::days(1)).await.unwrap();
+        let splits: Vec<(i64, f64)> = sqlx::query_as(
+            "SELECT tc.category_id, tc.amount FROM transaction_categories tc
+             JOIN transactions t ON t.id = tc.transaction_id
+             WHERE t.account_id = ? ORDER BY tc.amount DESC",
+        )
+        .bind(checking.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(splits, vec![(loan.principal_category_id, 972.8), (loan.interest_category_id, 60.0)]);
+        let balances: Vec<f64> = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id IN (?, ?) ORDER BY id")
+            .bind(checking.id)
+            .bind(car.id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balances, vec![5000.0 - 1032.8, -11027.2]);
+    }
+}
@@ -1,10 +1,67 @@
 // main.rs
+mod account_groups;
+mod accounts;
+mod activity;
 mod api;
+mod attachments;
+mod auth;
+mod buckets;
+mod budgets;
+mod categories;
+mod charts;
+mod credit;
+mod currencies;
+mod digest;
+mod encryption;
 mod exchange_scraper;
+mod exclusions;
+mod export_drops;
+mod flows;
+mod forecast;
+mod fx;
+mod goals;
+mod holdings;
+mod i18n;
+mod import;
+mod input;
+mod ledger;
+mod loans;
+mod merchants;
 mod models;
+mod net_worth;
+mod official_rates;
+mod patterns;
+mod pdf;
+mod prices;
+mod profiles;
+mod qif;
+#[cfg(test)]
+mod testing;
 mod seed;
+mod scrape_schedule;
+mod settings;
+mod statements;
+mod status;
+mod rate_compaction;
+mod rate_providers;
+mod rate_sources;
+mod receipts;
+mod reconcile;
 mod recurring;
+mod reimbursements;
+mod remote;
+mod reports;
+mod restore;
+mod rules;
+mod tags;
+mod transaction_status;
+mod transfers;
 mod tui;
+mod undo;
+mod web_assets;
+mod webhooks;
+mod wizard;
+mod xlsx;
 
 use actix_web::{middleware, web, App, HttpServer};
 use dotenvy::dotenv;
@@ -15,22 +72,49 @@ use tokio::time::{self, Duration};
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // --profile NAME picks a database from PROFILES, otherwise DATABASE_URL
+    let profile = profiles::take_profile_arg(&mut args);
+
+    // tui --remote URL works on a server's API and needs no database of its own
+    if args.get(1).map(String::as_str) == Some("tui") {
+        if let Some(base_url) = profiles::take_arg(&mut args, "--remote") {
+            let token = profiles::take_arg(&mut args, "--token")
+                .or_else(|| env::var("REMOTE_TOKEN").ok())
+                .ok_or("tui --remote needs --token TOKEN (from POST /auth/login) or REMOTE_TOKEN")?;
+            println!("Connecting to {}...", base_url);
+            let remote = remote::RemoteClient::connect(&base_url, &token).await?;
+            let mirror = remote.mirror().await?;
+            let mut app = tui::App::remote(mirror, remote);
+            app.run().await?;
+            return Ok(());
+        }
+    }
+    let database_url = match &profile {
+        Some(name) => profiles::database_url(&profiles::configured_profiles(), name)?,
+        None => env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
+    };
 
     // Connect to database
     println!("Connecting to database...");
-    let pool = SqlitePool::connect(&database_url).await?;
-    sqlx::query("PRAGMA foreign_keys = ON")
-        .execute(&pool)
-        .await?;
-    println!("Connected to: {}", database_url);
+    let pool = profiles::connect(&database_url).await?;
+    match &profile {
+        Some(name) => println!("Connected to: {} (profile {})", database_url, name),
+        None => println!("Connected to: {}", database_url),
+    }
+
+    // Bring the schema up to date; a new database gets all tables here
+    let migrations_applied = profiles::migrate(&pool).await?;
+    if migrations_applied > 0 {
+        println!("Applied {} database migrations", migrations_applied);
+    }
 
     if args.len() > 1 {
         match args[1].as_str() {
             "tui" => {
                 // Launch TUI
-                let mut app = tui::App::new(pool.clone());
+                let mut app = tui::App::new(pool.clone(), profile);
                 app.run().await?;
                 return Ok(());
             }
@@ -45,24 +129,247 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     let mut interval = time::interval(Duration::from_secs(60*60)); // hourly
                     loop {
                         interval.tick().await;
-                        if let Err(e) = recurring::process_due_recurring(&pool_for_recurring).await {
+                        let result = recurring::process_due_recurring(&pool_for_recurring, chrono::Utc::now()).await;
+                        if let Err(e) = &result {
                             eprintln!("[recurring scheduler] {}", e);
                         }
+                        let error = result.err().map(|e| e.to_string());
+                        let _ = status::record_heartbeat(&pool_for_recurring, status::RECURRING, 60 * 60, error.as_deref()).await;
+                    }
+                });
+
+                // Background task: scheduled CSV/JSON drops of new transactions (opt-in via EXPORT_DROP_DIR)
+                match export_drops::ExportDropConfig::from_env() {
+                    Ok(Some(config)) => {
+                        println!(
+                            "Export drops: {} {} into {}",
+                            config.schedule.as_str(),
+                            config.formats.iter().map(|f| f.as_str()).collect::<Vec<_>>().join("+"),
+                            config.directory.display()
+                        );
+                        let pool_for_drops = pool.clone();
+                        tokio::spawn(async move {
+                            let mut interval = time::interval(Duration::from_secs(60*60)); // hourly check
+                            loop {
+                                interval.tick().await;
+                                let error = match export_drops::run_due_drops(&pool_for_drops, &config, chrono::Utc::now(), false).await {
+                                    Ok(drops) => {
+                                        for drop in drops {
+                                            println!(
+                                                "[export drops] wrote {} transactions to {}",
+                                                drop.row_count,
+                                                drop.file_path.display()
+                                            );
+                                        }
+                                        None
+                                    }
+                                    Err(e) => {
+                                        eprintln!("[export drops] {}", e);
+                                        Some(e.to_string())
+                                    }
+                                };
+                                let _ = status::record_heartbeat(&pool_for_drops, status::EXPORT_DROPS, 60 * 60, error.as_deref()).await;
+                            }
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("[export drops] disabled: {}", e),
+                }
+
+                // Background task: scrape exchange rates on the SCRAPE_CRON schedule (opt-in)
+                let scraping = scrape_schedule::from_env()
+                    .and_then(|schedule| Ok((schedule, rate_providers::RateProviders::configured(None, false)?)));
+                match scraping {
+                    Ok((Some(schedule), providers)) => {
+                        println!(
+                            "Scheduled FX scraping: {} (UTC) from {}",
+                            env::var("SCRAPE_CRON").unwrap_or_default().trim(),
+                            providers.primary()
+                        );
+                        let pool_for_scraper = pool.clone();
+                        tokio::spawn(async move {
+                            while let Some(next) = schedule.next_after(chrono::Utc::now()) {
+                                let wait = (next - chrono::Utc::now()).to_std().unwrap_or_default();
+                                time::sleep(wait).await;
+                                let error = match scrape_schedule::run_scrape(&pool_for_scraper, &providers).await {
+                                    Ok(run) => {
+                                        println!(
+                                            "[fx scraper] saved {} rates, {} currencies up to date, {} failed",
+                                            run.rates_saved,
+                                            run.currencies_skipped,
+                                            run.errors.len()
+                                        );
+                                        None
+                                    }
+                                    Err(e) => {
+                                        eprintln!("[fx scraper] {}", e);
+                                        Some(e.to_string())
+                                    }
+                                };
+                                // Cron runs are irregular; expect the next one when it's due
+                                let now = chrono::Utc::now();
+                                let interval = schedule.next_after(now).map(|next| (next - now).num_seconds()).unwrap_or(0);
+                                let _ = status::record_heartbeat(&pool_for_scraper, status::FX_SCRAPER, interval, error.as_deref()).await;
+                            }
+                        });
+                    }
+                    Ok((None, _)) => {}
+                    Err(e) => eprintln!("[fx scraper] disabled: {}", e),
+                }
+
+                // Background task: fetch quotes of the held tickers on the PRICE_CRON schedule (opt-in)
+                match prices::schedule_from_env() {
+                    Ok(Some(schedule)) => {
+                        println!(
+                            "Scheduled price updates: {} (UTC) from {}",
+                            env::var("PRICE_CRON").unwrap_or_default().trim(),
+                            prices::STOOQ
+                        );
+                        let pool_for_prices = pool.clone();
+                        tokio::spawn(async move {
+                            let provider = prices::Stooq::new();
+                            while let Some(next) = schedule.next_after(chrono::Utc::now()) {
+                                let wait = (next - chrono::Utc::now()).to_std().unwrap_or_default();
+                                time::sleep(wait).await;
+                                let error = match prices::update_prices(&pool_for_prices, &provider).await {
+                                    Ok(update) => {
+                                        println!(
+                                            "[price updates] saved {} quotes, {} failed",
+                                            update.saved,
+                                            update.errors.len()
+                                        );
+                                        None
+                                    }
+                                    Err(e) => {
+                                        eprintln!("[price updates] {}", e);
+                                        Some(e.to_string())
+                                    }
+                                };
+                                let now = chrono::Utc::now();
+                                let interval = schedule.next_after(now).map(|next| (next - now).num_seconds()).unwrap_or(0);
+                                let _ = status::record_heartbeat(&pool_for_prices, status::PRICE_UPDATES, interval, error.as_deref()).await;
+                            }
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("[price updates] disabled: {}", e),
+                }
+
+                // Background task: archive intra-day FX rates of past days, keeping daily closes
+                let pool_for_compaction = pool.clone();
+                tokio::spawn(async move {
+                    let mut interval = time::interval(Duration::from_secs(24 * 60 * 60)); // daily
+                    loop {
+                        interval.tick().await;
+                        let now = chrono::Utc::now();
+                        let error = match rate_compaction::compact_rates(&pool_for_compaction, now.date_naive(), now).await {
+                            Ok(archived) => {
+                                if archived > 0 {
+                                    println!("[rate compaction] archived {} intra-day rates", archived);
+                                }
+                                None
+                            }
+                            Err(e) => {
+                                eprintln!("[rate compaction] {}", e);
+                                Some(e.to_string())
+                            }
+                        };
+                        let _ = status::record_heartbeat(&pool_for_compaction, status::RATE_COMPACTION, 24 * 60 * 60, error.as_deref()).await;
+                    }
+                });
+
+                // Background task: queue last month's report for "monthly-report" webhooks
+                let pool_for_reports = pool.clone();
+                tokio::spawn(async move {
+                    let mut interval = time::interval(Duration::from_secs(60*60)); // hourly check
+                    loop {
+                        interval.tick().await;
+                        let error = match reports::enqueue_due_reports(&pool_for_reports, chrono::Utc::now()).await {
+                            Ok(queued) => {
+                                if queued > 0 {
+                                    println!("[monthly reports] queued {} report deliveries", queued);
+                                }
+                                None
+                            }
+                            Err(e) => {
+                                eprintln!("[monthly reports] {}", e);
+                                Some(e.to_string())
+                            }
+                        };
+                        let _ = status::record_heartbeat(&pool_for_reports, status::MONTHLY_REPORTS, 60 * 60, error.as_deref()).await;
+                    }
+                });
+
+                // Background task: deliver pending webhook events from the outbox
+                let pool_for_webhooks = pool.clone();
+                tokio::spawn(async move {
+                    let client = reqwest::Client::builder()
+                        .timeout(Duration::from_secs(10))
+                        .build()
+                        .unwrap_or_default();
+                    let mut interval = time::interval(Duration::from_secs(30));
+                    loop {
+                        interval.tick().await;
+                        let error = match webhooks::dispatch_pending(&pool_for_webhooks, &client).await {
+                            Ok(result) => {
+                                if result.attempted > 0 {
+                                    println!(
+                                        "[webhook dispatcher] attempted {}, delivered {}, gave up on {}",
+                                        result.attempted, result.delivered, result.failed
+                                    );
+                                }
+                                None
+                            }
+                            Err(e) => {
+                                eprintln!("[webhook dispatcher] {}", e);
+                                Some(e.to_string())
+                            }
+                        };
+                        let _ = status::record_heartbeat(&pool_for_webhooks, status::WEBHOOKS, 30, error.as_deref()).await;
                     }
                 });
 
+                let web_root = web_assets::web_dir();
+
                 println!("Server running at http://{}", bind_address);
+                match &web_root {
+                    Some(root) => println!("Web frontend: serving {} at /", root.display()),
+                    None => println!("Web frontend: disabled (no ./web directory; set WEB_DIR)"),
+                }
                 println!("API Documentation:");
                 println!();
                 println!("  Core Endpoints:");
+                println!("   Login:        POST        /auth/login");
+                println!("   Session:      GET         /auth/session");
                 println!("   Users:        GET/POST    /users");
                 println!("   User:         GET/PUT/DEL /users/{{id}}");
+                println!("   Settings:     GET/PUT     /users/{{id}}/settings");
                 println!("   Accounts:     GET/POST    /accounts");
                 println!("   Account:      GET/PUT/DEL /accounts/{{id}}");
+                println!("   Reorder:      PUT         /accounts/reorder");
+                println!("   Stats:        GET         /accounts/{{id}}/stats");
+                println!("   Loan terms:   POST        /accounts/{{id}}/loan");
+                println!("   Amortization: GET         /accounts/{{id}}/amortization");
+                println!("   Close:        POST        /accounts/{{id}}/close");
+                println!("   Reconcile:    POST        /accounts/{{id}}/reconcile?fix=true");
+                println!("   Groups:       GET/POST    /account-groups");
+                println!("   Del Group:    DELETE      /account-groups/{{id}}");
+                println!("   Group Member: POST/DEL    /account-groups/{{id}}/accounts[/{{account_id}}]");
                 println!("   Categories:   GET/POST    /categories");
                 println!("   Category:     GET/PUT/DEL /categories/{{id}}");
                 println!("   Transactions: GET/POST    /transactions");
                 println!("   Transaction:  GET/PUT/DEL /transactions/{{id}}");
+                println!("   Recategorize: PUT         /transactions/{{id}}/categories");
+                println!("   Tag:          POST        /transactions/{{id}}/tags");
+                println!("   Untag:        DELETE      /transactions/{{id}}/tags/{{tag_id}}");
+                println!("   Reimbursed:   POST        /transactions/{{id}}/link-reimbursement");
+                println!("   Unlink:       DELETE      /transactions/{{id}}/link-reimbursement/{{reimbursement_id}}");
+                println!("   Attachments:  GET/POST    /transactions/{{id}}/attachments");
+                println!("   Attachment:   GET/DELETE  /transactions/{{id}}/attachments/{{attachment_id}}");
+                println!("   Tags:         GET/POST    /tags");
+                println!("   Delete Tag:   DELETE      /tags/{{id}}");
+                println!("   Transfers:    GET/POST    /transfers");
+                println!("   Receipt:      POST        /transactions/receipt?account_id={{id}}");
                 println!();
                 println!("  Recurring Transactions:");
                 println!("   List:         GET         /recurring-transactions");
@@ -72,6 +379,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("   Delete:       DELETE      /recurring-transactions/{{id}}");
                 println!("   Process Due:  POST        /recurring-transactions/process");
                 println!();
+                println!("  Transaction Templates:");
+                println!("   List:         GET         /templates?user_id={{id}}");
+                println!("   Create:       POST        /templates");
+                println!("   Update:       PUT         /templates/{{id}}");
+                println!("   Delete:       DELETE      /templates/{{id}}");
+                println!();
                 println!("  Exchange Rates:");
                 println!("   List:         GET         /exchange-rates");
                 println!("   Get:          GET         /exchange-rates/{{id}}");
@@ -79,27 +392,115 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("   Update:       PUT         /exchange-rates/{{id}}");
                 println!("   Delete:       DELETE      /exchange-rates/{{id}}");
                 println!("   Latest:       GET         /exchange-rates/latest/{{from_currency}}");
-                println!("   Convert:      GET         /exchange-rates/convert?from={{from}}&to={{to}}&amount={{amount}}");
+                println!("   Convert:      GET         /exchange-rates/convert?from={{from}}&to={{to}}&amount={{amount}}&sources=manual,api");
+                println!("   Currencies:   GET         /currencies?user_id={{id}}");
+                println!("   Stale:        GET         /exchange-rates/stale?days={{n}}");
+                println!("   Storage:      GET         /exchange-rates/storage-stats");
+                println!("   Preview:      GET         /exchange-rates/scrape-preview?currencies=USD,EUR");
+                println!("   Scrape Runs:  GET         /exchange-rates/scrape-runs?limit={{n}}");
                 println!("   Bulk Delete:  DELETE      /exchange-rates/bulk?from_currency={{currency}}&date={{date}}&source={{source}}");
                 println!();
+                println!("  Rate Sources:");
+                println!("   List:         GET         /rate-sources?kind={{kind}}");
+                println!("   Get:          GET         /rate-sources/{{id}}");
+                println!("   Create:       POST        /rate-sources");
+                println!("   Update:       PUT         /rate-sources/{{id}}");
+                println!("   Delete:       DELETE      /rate-sources/{{id}}");
+                println!();
+                println!("  Budgets:");
+                println!("   List:         GET         /budgets?user_id={{id}}");
+                println!("   Set Limit:    POST        /budgets");
+                println!("   Get:          GET         /budgets/{{id}}");
+                println!("   Update:       PUT         /budgets/{{id}}");
+                println!("   Delete:       DELETE      /budgets/{{id}}");
+                println!("   Variance:     GET         /reports/budget-variance?user_id={{id}}&period=YYYY-MM&format=json|csv");
+                println!("   Spend Chart:  GET         /reports/charts/spending.svg?user_id={{id}}&period=YYYY-MM&chart=bar|pie");
+                println!();
+                println!("  Goals:");
+                println!("   List:         GET         /goals");
+                println!("   Create:       POST        /goals");
+                println!("   Get:          GET         /goals/{{id}}");
+                println!("   Update:       PUT         /goals/{{id}}");
+                println!("   Delete:       DELETE      /goals/{{id}}");
+                println!();
+                println!("  Investments:");
+                println!("   Holdings:     GET/POST    /accounts/{{id}}/holdings");
+                println!("   Holding:      PUT/DEL     /holdings/{{id}}");
+                println!();
+                println!("  Import:");
+                println!("   Preview:      POST        /import/preview");
+                println!("   Commit:       POST        /import/commit");
+                println!("   CSV Import:   POST        /import/transactions/csv");
+                println!("   Profiles:     GET/POST    /import/profiles?user_id={{id}}");
+                println!("   Del Profile:  DELETE      /import/profiles/{{id}}");
+                println!("   Rules:        GET/POST    /category-rules?user_id={{id}}");
+                println!("   Delete Rule:  DELETE      /category-rules/{{id}}");
+                println!("   Recategorize: POST        /transactions/recategorize");
+                println!();
                 println!("  Analytics & Insights:");
                 println!("   Category Spending: GET    /analytics/spending-by-category?user_id={{id}}");
                 println!("   Monthly Summary:   GET    /analytics/monthly-summary?user_id={{id}}");
                 println!("   Spending Compare:  GET    /analytics/spending-comparison?user_id={{id}}&current_start=...&current_end=...&previous_start=...&previous_end=...");
                 println!("   Top Categories:    GET    /analytics/top-categories?user_id={{id}}&limit={{n}}");
+                println!("   By Merchant:       GET    /analytics/spending-by-merchant?user_id={{id}}&limit={{n}}");
+                println!("   By Currency:       GET    /analytics/spending-by-currency?user_id={{id}}");
+                println!("   Patterns:          GET    /analytics/patterns?user_id={{id}}&utc_offset_minutes={{m}}");
+                println!("   Cash Flows:        GET    /analytics/flows?user_id={{id}}&period=YYYY-MM");
+                println!("   Balances by Type:  GET    /analytics/balances?user_id={{id}}");
+                println!("   Balances by Group: GET    /analytics/balances/groups?user_id={{id}}[&as_of=YYYY-MM-DD]");
+                println!("   Net Worth:         GET    /analytics/net-worth?user_id={{id}}[&as_of=YYYY-MM-DD]");
+                println!("   Discretionary:     GET    /analytics/discretionary-budget?user_id={{id}}");
+                println!("   Buckets:           GET    /analytics/buckets?user_id={{id}}&period=YYYY-MM");
+                println!("   Safe to Spend:     GET    /analytics/safe-to-spend?user_id={{id}}");
+                println!("   Forecast:          GET    /analytics/forecast?user_id={{id}}&months=6");
+                println!();
+                println!("  Activity:");
+                println!("   Recent Activity:   GET    /activity?user_id={{id}}&limit=50");
                 println!();
                 println!("  Data Export:");
                 println!("   Transactions CSV:  GET    /export/transactions/csv?user_id={{id}}&start_date=...&end_date=...");
                 println!("   Transactions JSON: GET    /export/transactions/json?user_id={{id}}");
+                println!("   Transactions JSONL: GET   /export/transactions/jsonl?user_id={{id}}");
+                println!("   Transactions OFX:  GET    /export/transactions/ofx?user_id={{id}}&account_id=...");
+                println!("   Restore Summary:   POST   /import/summary?user_id={{id}}  (body: financial_summary.json)");
+                println!("   Transactions QIF:  GET    /export/transactions/qif?user_id={{id}}&account_id=...");
+                println!("   Ledger:            GET    /export/ledger?user_id={{id}}&format=beancount|ledger");
                 println!("   Accounts CSV:      GET    /export/accounts/csv?user_id={{id}}");
                 println!("   Full Summary:      GET    /export/summary/json?user_id={{id}}");
+                println!("   Summary Workbook:  GET    /export/summary/xlsx?user_id={{id}}");
+                println!("   Statement PDF:     GET    /export/statement/pdf?user_id={{id}}&month=YYYY-MM");
+                println!("   (add &encrypt=true and an X-Export-Passphrase header to any export for an AES-256 encrypted zip)");
+                println!();
+                println!("  Webhooks:");
+                println!("   List:         GET         /webhooks?user_id={{id}}");
+                println!("   Create:       POST        /webhooks");
+                println!("   Delete:       DELETE      /webhooks/{{id}}");
+                println!("   Deliveries:   GET         /webhooks/{{id}}/deliveries");
                 println!();
 
+                // Analytics and exports read through their own read-only pool
+                let read_pool = profiles::connect_read_pool(&database_url, profiles::read_pool_size()).await?;
+                if let Some(reads) = &read_pool {
+                    println!("  Reports use a separate pool of {} read-only connections", reads.options().get_max_connections());
+                }
+
                 HttpServer::new(move || {
-                    App::new()
+                    let mut app = App::new()
                         .app_data(web::Data::new(pool.clone()))
+                        .wrap(middleware::from_fn(api::require_session))
+                        .wrap(middleware::from_fn(api::limit_body))
                         .wrap(middleware::Logger::default())
-                        .configure(api::configure_routes)
+                        .configure(api::configure_routes);
+                    if let Some(reads) = &read_pool {
+                        app = app.app_data(web::Data::new(api::ReadPool(reads.clone())));
+                    }
+                    // Anything the API doesn't handle falls through to the frontend
+                    match web_root.clone() {
+                        Some(root) => app
+                            .app_data(web::Data::new(root))
+                            .default_service(web::to(web_assets::serve)),
+                        None => app,
+                    }
                 })
                 .bind(&bind_address)?
                 .run()
@@ -111,6 +512,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "scrape_rates" => {
                 scrape_exchange_rates(&pool, &args).await?;
             }
+            "update_prices" => {
+                let update = prices::update_prices(&pool, &prices::Stooq::new()).await?;
+                println!("Saved {} quotes from {}.", update.saved, prices::STOOQ);
+                for error in &update.errors {
+                    println!("  {}", error);
+                }
+            }
+            "compact_rates" => {
+                // compact_rates [--keep-days N]: days kept whole, counting today,
+                // which is never compacted
+                let keep_days = match args.iter().position(|a| a == "--keep-days").map(|i| args.get(i + 1)) {
+                    None => Some(1),
+                    Some(days) => days.and_then(|d| d.parse::<i64>().ok()).filter(|d| *d >= 1),
+                };
+                let Some(keep_days) = keep_days else {
+                    println!("Usage: cargo run compact_rates [--keep-days N] (N at least 1)");
+                    return Ok(());
+                };
+                let now = chrono::Utc::now();
+                let before = now.date_naive() - chrono::Duration::days(keep_days - 1);
+                let archived = rate_compaction::compact_rates(&pool, before, now).await?;
+                let stats = rate_compaction::storage_stats(&pool, now).await?;
+                println!(
+                    "Archived {} intra-day rates before {}; {} rates over {} pairs remain, {} archived in total.",
+                    archived, before, stats.rates, stats.pairs, stats.archived_rates
+                );
+            }
             "db_clear" => {
                 println!("WARNING: This will delete ALL data!");
                 println!("Press Enter to continue, Ctrl+C to cancel...");
@@ -127,6 +555,252 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 seed::seed_database(&pool).await?;
             }
             "db_status" => print_database_status(&pool).await?,
+            "db_reconcile" => {
+                let fix = args.iter().any(|a| a == "--fix");
+                let results = reconcile::reconcile(&pool, None, fix).await?;
+                let drifted: Vec<_> = results.iter().filter(|r| reconcile::has_drifted(r)).collect();
+                if drifted.is_empty() {
+                    println!("All {} account balances match their transactions.", results.len());
+                } else {
+                    println!("{:<6} {:<24} {:>14} {:>14} {:>12}", "ID", "Account", "Recorded", "Computed", "Difference");
+                    for r in &drifted {
+                        println!(
+                            "{:<6} {:<24} {:>14.2} {:>14.2} {:>12.2} {}",
+                            r.account_id, r.account_name, r.recorded_balance, r.computed_balance, r.difference, r.currency
+                        );
+                    }
+                    println!();
+                    if fix {
+                        println!("Fixed {} of {} account balances.", drifted.len(), results.len());
+                    } else {
+                        println!(
+                            "{} of {} account balances differ; run 'cargo run db_reconcile --fix' to correct them.",
+                            drifted.len(),
+                            results.len()
+                        );
+                    }
+                }
+            }
+            // Migrations already ran on connect; this just reports the result
+            "db_migrate" => match migrations_applied {
+                0 => println!("Database schema is up to date."),
+                n => println!("Database schema migrated ({} migrations applied).", n),
+            },
+            "decrypt_export" => {
+                let path = match args.get(2) {
+                    Some(path) => path,
+                    None => {
+                        println!("Usage: cargo run decrypt_export <file.zip>");
+                        return Ok(());
+                    }
+                };
+                let passphrase = export_passphrase()?;
+                let data = std::fs::read(path)?;
+                let (name, plaintext) = encryption::decrypt(&data, &passphrase)?;
+                // Written next to the archive, under the name it was exported as
+                let out_path = std::path::Path::new(path)
+                    .with_file_name(std::path::Path::new(&name).file_name().unwrap_or_default())
+                    .to_string_lossy()
+                    .into_owned();
+                std::fs::write(&out_path, plaintext)?;
+                println!("Decrypted export written to {}", out_path);
+            }
+            "import_summary" => {
+                // import_summary <financial_summary.json[.zip]> [--user ID]
+                let Some(path) = args.get(2).filter(|p| !p.starts_with("--")) else {
+                    println!("Usage: cargo run import_summary <financial_summary.json> [--user <id>]");
+                    return Ok(());
+                };
+                let mut data = std::fs::read(path)?;
+                if path.ends_with(&format!(".{}", encryption::FILE_EXTENSION)) {
+                    data = encryption::decrypt(&data, &export_passphrase()?)?.1;
+                }
+                let file: restore::SummaryFile = serde_json::from_slice(&data)?;
+                let user_id = args
+                    .iter()
+                    .position(|a| a == "--user")
+                    .and_then(|i| args.get(i + 1))
+                    .and_then(|id| id.parse::<i64>().ok())
+                    .or(file.user_id);
+                let Some(user_id) = user_id else {
+                    println!("The file doesn't name its user; pass --user <id>");
+                    return Ok(());
+                };
+                restore::validate(&file)?;
+                let user: Option<i64> = sqlx::query_scalar("SELECT id FROM users WHERE id = ?")
+                    .bind(user_id)
+                    .fetch_optional(&pool)
+                    .await?;
+                if user.is_none() {
+                    println!("User {} not found", user_id);
+                    return Ok(());
+                }
+                let conflicts = restore::conflicting_accounts(&pool, user_id, &file).await?;
+                if !conflicts.is_empty() {
+                    println!("User {} already has accounts named {}; nothing was imported", user_id, conflicts.join(", "));
+                    return Ok(());
+                }
+
+                let result = restore::restore(&pool, user_id, &file).await?;
+                println!(
+                    "Restored {} accounts, {} transactions and {} recurring transactions for user {}",
+                    result.accounts, result.transactions, result.recurring_transactions, user_id
+                );
+                println!(
+                    "Categories: {} created, {} already existed and were reused",
+                    result.categories_created, result.categories_matched
+                );
+                if result.skipped > 0 {
+                    println!("Skipped {} entries whose account isn't in the file", result.skipped);
+                }
+            }
+            "import" => {
+                // import <file.csv> --account ID [--map field=Header,...] [--save-profile NAME]
+                // import <file.csv> --profile ID [--account ID]
+                let path = args.get(2).filter(|p| !p.starts_with("--"));
+                let flag = |name: &str| {
+                    args.iter()
+                        .position(|a| a == name)
+                        .and_then(|i| args.get(i + 1))
+                };
+                let profile = match flag("--profile").and_then(|id| id.parse::<i64>().ok()) {
+                    Some(profile_id) => {
+                        let profile = sqlx::query_as::<_, models::ImportProfile>("SELECT * FROM import_profiles WHERE id = ?")
+                            .bind(profile_id)
+                            .fetch_optional(&pool)
+                            .await?;
+                        if profile.is_none() {
+                            println!("Import profile {} not found", profile_id);
+                            return Ok(());
+                        }
+                        profile
+                    }
+                    None => None,
+                };
+                let account_id = flag("--account")
+                    .and_then(|id| id.parse::<i64>().ok())
+                    .or(profile.as_ref().and_then(|p| p.account_id));
+                let (path, account_id) = match (path, account_id) {
+                    (Some(path), Some(account_id)) => (path, account_id),
+                    _ => {
+                        println!("Usage: cargo run import <file.csv> --account <id> [--map field=Header,...] [--save-profile NAME]");
+                        println!("       cargo run import <file.csv> --profile <id> [--account <id>]");
+                        return Ok(());
+                    }
+                };
+                let mapping = match &profile {
+                    Some(profile) => import::profile_mapping(profile),
+                    None => import::parse_mapping(flag("--map").map(String::as_str).unwrap_or(""))?,
+                };
+                let user_id: i64 = match sqlx::query_scalar("SELECT user_id FROM accounts WHERE id = ?")
+                    .bind(account_id)
+                    .fetch_optional(&pool)
+                    .await?
+                {
+                    Some(user_id) => user_id,
+                    None => {
+                        println!("Account {} not found", account_id);
+                        return Ok(());
+                    }
+                };
+
+                if profile.as_ref().is_some_and(|p| p.user_id != user_id) {
+                    println!("Import profile and account {} belong to different users", account_id);
+                    return Ok(());
+                }
+
+                let content = std::fs::read_to_string(path)?;
+                let (rows, date_format) = import::parse_csv(&content, &mapping)?;
+                if let Some(name) = flag("--save-profile") {
+                    let data = models::CreateImportProfile {
+                        user_id,
+                        name: name.clone(),
+                        account_id: Some(account_id),
+                        mapping: mapping.clone(),
+                    };
+                    data.validate()?;
+                    let saved = import::save_profile(&pool, &data).await?;
+                    println!("Saved import profile '{}' (ID {}); next time use --profile {}", saved.name, saved.id, saved.id);
+                }
+                let result = import::import_csv(&pool, user_id, account_id, rows, date_format).await?;
+                println!(
+                    "Imported {} transactions into account {} ({} duplicates skipped, dates read as {})",
+                    result.imported,
+                    account_id,
+                    result.duplicates_skipped,
+                    result.date_format.as_deref().unwrap_or("mixed formats")
+                );
+                if result.before_opening_skipped > 0 {
+                    println!(
+                        "Skipped {} rows dated before the account was opened",
+                        result.before_opening_skipped
+                    );
+                }
+            }
+            "convert" => {
+                // convert <amount> <FROM> <TO> [--date YYYY-MM-DD]
+                let amount = args.get(2).and_then(|a| a.parse::<f64>().ok());
+                let currencies = args.get(3).zip(args.get(4)).filter(|(_, to)| !to.starts_with("--"));
+                let date = match args.iter().position(|a| a == "--date").map(|i| args.get(i + 1)) {
+                    None => Ok(None),
+                    Some(date) => date
+                        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                        .map(Some)
+                        .ok_or(()),
+                };
+                let (amount, (from, to), as_of) = match (amount, currencies, date) {
+                    (Some(amount), Some(currencies), Ok(date)) => (amount, currencies, date),
+                    _ => {
+                        println!("Usage: cargo run convert <amount> <FROM> <TO> [--date YYYY-MM-DD]");
+                        return Ok(());
+                    }
+                };
+                let (from, to) = (from.to_uppercase(), to.to_uppercase());
+                // End of the day, so rates quoted any time that day count
+                let as_of = as_of.and_then(|d| d.and_hms_opt(23, 59, 59)).map(|d| d.and_utc());
+
+                match fx::convert(&pool, &from, &to, amount, as_of, &fx::source_priority()).await? {
+                    Some(result) => {
+                        println!("{:.2} {} = {:.2} {}", result.amount, from, result.converted_amount, to);
+                        println!(
+                            "Rate {} ({}, quoted {}, {} days old{})",
+                            result.rate,
+                            result.source,
+                            result.rate_date.format("%Y-%m-%d"),
+                            result.rate_age_days,
+                            if result.stale { ", stale" } else { "" }
+                        );
+                    }
+                    None => match as_of {
+                        Some(as_of) => println!(
+                            "No exchange rate from {} to {} on or before {}",
+                            from,
+                            to,
+                            as_of.format("%Y-%m-%d")
+                        ),
+                        None => println!("No exchange rate from {} to {}", from, to),
+                    },
+                }
+            }
+            "export_drop" => {
+                // Write a drop right away, regardless of the schedule
+                match export_drops::ExportDropConfig::from_env()? {
+                    Some(config) => {
+                        let drops =
+                            export_drops::run_due_drops(&pool, &config, chrono::Utc::now(), true)
+                                .await
+                                .map_err(|e| e.to_string())?;
+                        for drop in drops {
+                            println!(
+                                "Wrote {} transactions to {}",
+                                drop.row_count,
+                                drop.file_path.display()
+                            );
+                        }
+                    }
+                    None => println!("EXPORT_DROP_DIR is not set; nothing to do."),
+                }
+            }
             _ => {
                 println!("Unknown command: {}", args[1]);
                 println!();
@@ -140,26 +814,67 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Passphrase of an encrypted export: EXPORT_PASSPHRASE, otherwise prompt for it
+fn export_passphrase() -> std::io::Result<String> {
+    if let Ok(passphrase) = env::var("EXPORT_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+    println!("Passphrase:");
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
 fn print_usage() {
     println!("+-----------------------------------------+");
     println!("| Personal Finance Tracker - CLI Tool     |");
     println!("+-----------------------------------------+");
     println!();
-    println!("Usage: cargo run [command]");
+    println!("Usage: cargo run [command] [--profile NAME]");
     println!();
     println!("Commands:");
     println!("  tui                 Launch Text User Interface");
+    println!("  tui --remote URL --token TOKEN");
+    println!("                      Run the TUI on a server's API (token from POST /auth/login)");
     println!("  serve               Start REST API server");
     println!("  db_status           Show database status");
+    println!("  db_migrate          Apply pending database migrations (also done on every start)");
     println!("  db_seed             Populate with sample data");
+    println!("  db_reconcile [--fix]");
+    println!("                      Compare account balances with their transactions (and correct them)");
     println!(
-        "  scrape_rates        Scrape latest FX rates for default currencies - CAD, USD, GBP, EUR"
+        "  scrape_rates        Scrape latest FX rates for your account currencies (+ FX_SCRAPE_CURRENCIES)"
     );
     println!("  scrape_rates XXX    Scrape latest FX rates for the specific currency code XXX");
+    println!("  scrape_rates --dry-run [--verbose] [XXX]");
+    println!("                      Show which rates would be inserted/updated without saving");
+    println!("  scrape_rates --provider NAME [XXX]");
+    println!("                      Try x-rates or frankfurter first (default FX_PROVIDER, else x-rates);");
+    println!("                      the other is the fallback when it fails");
+    println!("  scrape_rates --official");
+    println!("                      Fetch Bank of Canada reference rates (source 'official')");
+    println!("  convert N FROM TO [--date YYYY-MM-DD]");
+    println!("                      Convert N FROM into TO at the latest rate (or the rate as of the date)");
+    println!("  compact_rates [--keep-days N]");
+    println!("                      Archive intra-day FX rates, keeping each day's close (past N days kept whole)");
+    println!("  update_prices       Fetch the latest quotes of every held ticker (also on PRICE_CRON in serve)");
+    println!("  decrypt_export F    Decrypt an encrypted export file F (.zip)");
+    println!("  export_drop         Write a CSV/JSON drop of new transactions to EXPORT_DROP_DIR now");
+    println!("  import F --account ID [--map field=Header,...] [--save-profile NAME]");
+    println!("                      Import CSV statement F into account ID, skipping duplicates,");
+    println!("                      and optionally save the mapping and account as a profile");
+    println!("  import F --profile ID [--account ID]");
+    println!("                      Import F with a saved profile's mapping and account");
+    println!("  import_summary F [--user ID]");
+    println!("                      Recreate the accounts, categories, transactions and recurring");
+    println!("                      transactions of a financial_summary.json export (F may be .zip)");
     println!("  db_clear            Clear all data");
     println!("  db_reseed           Clear and re-seed");
     println!("  help                Show this message");
     println!();
+    println!("Options:");
+    println!("  --profile NAME      Use the database of profile NAME from PROFILES instead of DATABASE_URL");
+    println!();
 }
 
 async fn print_database_status(pool: &SqlitePool) -> Result<(), sqlx::Error> {
@@ -205,33 +920,84 @@ async fn print_database_status(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+/// Scrape and compare with the db without saving anything (scrape_rates --dry-run)
+async fn preview_exchange_rates(
+    pool: &SqlitePool,
+    providers: &rate_providers::RateProviders,
+    currencies: Vec<&str>,
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for currency in currencies {
+        match providers.fetch(currency).await {
+            Ok(fetched) => {
+                let diffs = exchange_scraper::diff_against_database(pool, &fetched.rates).await?;
+                exchange_scraper::print_rate_diffs(currency, &diffs, verbose);
+            }
+            Err(e) => eprintln!("\n{}: ✗Error: {}.", currency, e),
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
 async fn scrape_exchange_rates(
     pool: &SqlitePool,
     args: &[String],
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use exchange_scraper::{print_exchange_rates, ExchangeRateScraper};
+    use exchange_scraper::print_exchange_rates;
 
     println!("\nStart Foreign Exchange Rates Scraper...");
     println!();
 
-    let scraper = ExchangeRateScraper::new();
+    let mut options: Vec<&str> = args[2..].iter().map(String::as_str).collect();
+    if options.contains(&"--official") {
+        return fetch_official_rates(pool).await;
+    }
+    // --provider NAME; the name isn't a currency
+    let provider = options.iter().position(|a| *a == "--provider").map(|i| {
+        let name = options.get(i + 1).copied().unwrap_or_default();
+        options.drain(i..(i + 2).min(options.len()));
+        name
+    });
+    let providers = rate_providers::RateProviders::configured(provider, true)?;
+    let dry_run = options.contains(&"--dry-run");
+    let verbose = options.contains(&"--verbose");
 
-    let currencies: Vec<&str> = if args.len() > 2 {
-        vec![args[2].as_str()]
-    } else {
-        vec!["CAD", "USD", "EUR", "GBP"]
+    let currencies: Vec<String> = match options.iter().find(|a| !a.starts_with("--")) {
+        Some(currency) => vec![currency.to_uppercase()],
+        None => exchange_scraper::base_currencies(pool).await?,
     };
+    let currencies: Vec<&str> = currencies.iter().map(String::as_str).collect();
 
     println!(
-        "Will scrape the following currencies' FX rates: {:?}",
+        "Will scrape the following currencies' FX rates from {}: {:?}",
+        providers.primary(),
         currencies
     );
     println!();
 
-    let all_results = scraper.smart_fetch_multiple(pool, currencies).await;
+    if dry_run {
+        return preview_exchange_rates(pool, &providers, currencies, verbose).await;
+    }
+
+    let started_at = chrono::Utc::now();
+    let timer = std::time::Instant::now();
+    let requested: Vec<String> = currencies.iter().map(|c| c.to_string()).collect();
+    let (all_results, mut errors) = providers.smart_fetch_multiple(pool, currencies).await;
 
     if all_results.is_empty() {
         println!("Failure: Cannot scrape any currencies' FX Rates.");
+        let metrics = exchange_scraper::ScrapeRunMetrics {
+            provider: providers.primary().to_string(),
+            currencies: requested,
+            started_at,
+            duration: timer.elapsed(),
+            rates_saved: 0,
+            currencies_skipped: 0,
+            errors,
+        };
+        record_scrape_run(pool, &metrics).await;
         return Ok(());
     }
 
@@ -242,20 +1008,27 @@ async fn scrape_exchange_rates(
     let mut total_saved = 0;
     let mut total_skipped = 0;
 
-    for (currency, (rates, was_up_to_date)) in &all_results {
+    for (currency, fetched) in &all_results {
         println!("\nExchange rate of {}:", currency);
-        if *was_up_to_date {
+        if fetched.up_to_date {
             println!("\n {} has the latest rates, skipped", currency);
             total_skipped += 1;
         } else {
-            print_exchange_rates(rates);
-            match scraper.save_to_database(pool, rates).await {
-                Ok(count) => {
+            print_exchange_rates(&fetched.rates);
+            match rate_providers::save_rates(pool, fetched, chrono::Utc::now()).await {
+                Ok(results) => {
+                    for pair in &results {
+                        if let rate_providers::PairOutcome::Skipped(reason) = pair.outcome {
+                            println!("  Skipped {} -> {} ({}): {}", pair.from_currency, pair.to_currency, pair.rate, reason);
+                        }
+                    }
+                    let count = rate_providers::saved_count(&results);
                     println!("\nSave {} exchange rates into db.", count);
                     total_saved += count;
                 }
                 Err(e) => {
                     eprintln!("Error: {}", e);
+                    errors.push(format!("{}: {}", currency, e));
                 }
             }
         }
@@ -271,5 +1044,71 @@ async fn scrape_exchange_rates(
     println!("   • FX rates added: {} in total", total_saved);
     println!("{}\n", "=".repeat(50));
 
+    let metrics = exchange_scraper::ScrapeRunMetrics {
+        provider: rate_providers::providers_used(providers.primary(), all_results.values()),
+        currencies: requested,
+        started_at,
+        duration: timer.elapsed(),
+        rates_saved: total_saved,
+        currencies_skipped: total_skipped,
+        errors,
+    };
+    record_scrape_run(pool, &metrics).await;
+
     Ok(())
 }
+
+/// Fetch the Bank of Canada's latest reference rates (scrape_rates --official)
+async fn fetch_official_rates(pool: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Fetching official rates from {}...", official_rates::PROVIDER);
+
+    let started_at = chrono::Utc::now();
+    let timer = std::time::Instant::now();
+    let mut errors = Vec::new();
+    let mut saved = 0;
+
+    match official_rates::fetch_latest().await {
+        Ok(rates) => {
+            let date = rates.first().map(|r| r.rate_date.to_string()).unwrap_or_default();
+            match official_rates::save_official_rates(pool, &rates).await {
+                Ok(count) => {
+                    saved = count;
+                    println!(
+                        "Saved {} official rates for {} ({} already stored).",
+                        count,
+                        date,
+                        rates.len() - count
+                    );
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    errors.push(format!("CAD: {}", e));
+                }
+            }
+        }
+        Err(e) => {
+            println!("Failure: Cannot fetch official rates: {}", e);
+            errors.push(format!("CAD: {}", e));
+        }
+    }
+
+    let metrics = exchange_scraper::ScrapeRunMetrics {
+        provider: official_rates::PROVIDER.to_string(),
+        currencies: vec!["CAD".to_string()],
+        started_at,
+        duration: timer.elapsed(),
+        rates_saved: saved,
+        currencies_skipped: 0,
+        errors,
+    };
+    record_scrape_run(pool, &metrics).await;
+
+    Ok(())
+}
+
+// A failure to record metrics shouldn't fail the scrape itself
+async fn record_scrape_run(pool: &SqlitePool, metrics: &exchange_scraper::ScrapeRunMetrics) {
+    if let Err(e) = exchange_scraper::record_scrape_run(pool, metrics).await {
+        eprintln!("Warning: could not record scrape run: {}", e);
+    }
+}
@@ -0,0 +1,64 @@
+// merchants.rs
+// Merchant name normalization used to enrich transactions
+
+/// Payment-processor prefixes that banks prepend to the real merchant name.
+const PROCESSOR_PREFIXES: [&str; 6] = ["SQ *", "SQ*", "TST*", "TST *", "PAYPAL *", "PP*"];
+
+/// Normalize a raw merchant/payee string into a stable display name.
+///
+/// Strips processor prefixes ("SQ *", "PAYPAL *"), store numbers ("#1234",
+/// trailing digits), collapses whitespace and title-cases the result, so
+/// "STARBUCKS #1234" and "Starbucks  Store 99" both become "Starbucks".
+/// Returns None when nothing meaningful is left.
+pub fn normalize_merchant(raw: &str) -> Option<String> {
+    let mut name = raw.trim().to_uppercase();
+
+    for prefix in PROCESSOR_PREFIXES {
+        if let Some(rest) = name.strip_prefix(prefix) {
+            name = rest.trim().to_string();
+            break;
+        }
+    }
+
+    // Cut everything from a store-number marker onwards
+    if let Some(pos) = name.find('#') {
+        name.truncate(pos);
+    }
+    if let Some(pos) = name.find(" STORE ") {
+        name.truncate(pos);
+    }
+
+    let words: Vec<String> = name
+        .split_whitespace()
+        .filter(|w| !w.chars().all(|c| c.is_ascii_digit() || c == '-'))
+        .map(title_case)
+        .collect();
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(|c| c.to_lowercase())).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_merchant() {
+        assert_eq!(normalize_merchant("STARBUCKS #1234"), Some("Starbucks".to_string()));
+        assert_eq!(normalize_merchant("SQ *blue bottle coffee"), Some("Blue Bottle Coffee".to_string()));
+        assert_eq!(normalize_merchant("walmart store 5521"), Some("Walmart".to_string()));
+        assert_eq!(normalize_merchant("  AMAZON   MKTP  "), Some("Amazon Mktp".to_string()));
+        assert_eq!(normalize_merchant("#0001"), None);
+    }
+}
@@ -1,7 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::input;
+
 // ============================================================================
 // User Models
 // ============================================================================
@@ -12,8 +14,7 @@ pub struct User {
     pub id: i64,
     pub username: String,
     pub email: String,
-    #[serde(skip_serializing)] // Don't expose password hash in JSON responses
-    #[allow(dead_code)]
+    #[serde(skip_serializing, default)] // Don't expose password hash in JSON responses
     pub password_hash: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -22,6 +23,7 @@ pub struct User {
 /// Data required to create a new user
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUser {
+    #[serde(deserialize_with = "crate::input::line")]
     pub username: String,
     pub email: String,
     pub password: String, // Plain text password (will be hashed before storage)
@@ -30,11 +32,107 @@ pub struct CreateUser {
 /// Data for updating a user
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateUser {
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub username: Option<String>,
     pub email: Option<String>,
     pub password: Option<String>, // Plain text password (will be hashed before storage)
 }
 
+/// Credentials for POST /auth/login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginRequest {
+    pub username: String, // Username or email
+    pub password: String,
+}
+
+/// Session issued on login; send the token as "Authorization: Bearer <token>"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginSession {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    pub user: User,
+}
+
+/// Per-user preferences stored in the settings table
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserSettings {
+    pub default_screen: Option<String>, // TUI tab opened after login, e.g. "transactions"
+    pub view_currency: Option<String>,  // Last "view in currency" choice on the Transactions screen
+    pub base_currency: Option<String>,  // Totals across accounts are in it; new transactions store their value in it
+    pub bucket_targets: Option<String>, // Target needs/wants/savings split, e.g. "50/30/20"
+    pub weekly_digest: Option<bool>,    // Weekly digest popup after TUI login; shown unless false
+    pub language: Option<String>,       // TUI language code, "en" or "fr"
+    pub date_format: Option<String>,    // How the TUI shows dates: "iso", "us" or "eu"
+    pub default_account_id: Option<i64>, // Account new TUI transactions start on
+    pub week_start: Option<String>,     // "monday" (when unset) or "sunday"
+}
+
+/// Data for updating user settings; an empty string clears a setting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserSettings {
+    pub default_screen: Option<String>,
+    pub view_currency: Option<String>,
+    pub base_currency: Option<String>,
+    pub bucket_targets: Option<String>,
+    pub weekly_digest: Option<bool>,
+    pub language: Option<String>,
+    pub date_format: Option<String>,
+    pub default_account_id: Option<i64>, // 0 clears it
+    pub week_start: Option<String>,
+}
+
+impl UpdateUserSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(screen) = self.default_screen.as_deref().filter(|s| !s.is_empty()) {
+            if crate::settings::screen_index(screen).is_none() {
+                return Err(format!(
+                    "Unknown screen '{}' (expected one of: {})",
+                    screen,
+                    crate::settings::SCREENS.join(", ")
+                ));
+            }
+        }
+        if let Some(targets) = self.bucket_targets.as_deref().filter(|t| !t.is_empty()) {
+            crate::buckets::parse_targets(targets)?;
+        }
+        if let Some(language) = self.language.as_deref().filter(|l| !l.is_empty()) {
+            if crate::i18n::Language::parse(language).is_none() {
+                return Err(format!(
+                    "Unknown language '{}' (expected one of: {})",
+                    language,
+                    crate::i18n::Language::ALL.map(|l| l.code()).join(", ")
+                ));
+            }
+        }
+        if let Some(format) = self.date_format.as_deref().filter(|f| !f.is_empty()) {
+            if !crate::settings::DATE_FORMATS.iter().any(|(name, _)| name.eq_ignore_ascii_case(format.trim())) {
+                return Err(format!(
+                    "Unknown date format '{}' (expected one of: {})",
+                    format,
+                    crate::settings::DATE_FORMATS.map(|(name, _)| name).join(", ")
+                ));
+            }
+        }
+        if let Some(day) = self.week_start.as_deref().filter(|d| !d.is_empty()) {
+            if !crate::settings::WEEK_STARTS.iter().any(|s| s.eq_ignore_ascii_case(day.trim())) {
+                return Err(format!(
+                    "Unknown week start '{}' (expected one of: {})",
+                    day,
+                    crate::settings::WEEK_STARTS.join(", ")
+                ));
+            }
+        }
+        for currency in [&self.view_currency, &self.base_currency] {
+            if let Some(currency) = currency.as_deref().filter(|c| !c.is_empty()) {
+                if currency.len() != 3 {
+                    return Err("Currency must be a 3-letter code".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Account Models
 // ============================================================================
@@ -45,33 +143,133 @@ pub struct Account {
     pub id: i64,
     pub user_id: i64,
     pub name: String,
-    pub account_type: String, // "checking", "savings", "credit_card"
+    pub account_type: String, // "checking", "savings", "credit_card", "investment", "cash", "loan"
     pub bank_name: Option<String>,
     pub currency: String, // ISO 4217 currency code (e.g., "USD", "EUR")
     pub initial_balance: f64,
     pub current_balance: f64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub sort_order: i64,   // Position in pickers and lists (ascending)
+    pub is_favorite: bool, // Favorites are always listed first
+    pub default_category_id: Option<i64>, // Applied to new transactions without splits
+    pub credit_limit: Option<f64>,        // Credit card accounts only
+    pub opened_on: Option<NaiveDate>,     // initial_balance is the balance on this day
+    pub is_archived: bool,                // Closed; takes no new transactions
+    pub closed_on: Option<NaiveDate>,
+    pub account_group_id: Option<i64>,    // User-defined group, e.g. "Liquid"
 }
 
 /// Data required to create a new account
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateAccount {
     pub user_id: i64,
+    #[serde(deserialize_with = "crate::input::line")]
     pub name: String,
-    pub account_type: String, // "checking", "savings", "credit_card"
+    pub account_type: String, // "checking", "savings", "credit_card", "investment", "cash", "loan"
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub bank_name: Option<String>,
     pub currency: Option<String>, // Defaults to "USD" if not provided
     pub initial_balance: Option<f64>, // Defaults to 0.0 if not provided
+    pub default_category_id: Option<i64>,
+    pub credit_limit: Option<f64>,
+    pub opened_on: Option<NaiveDate>, // Defaults to today
 }
 
 /// Data for updating an account
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateAccount {
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub name: Option<String>,
     pub account_type: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub bank_name: Option<String>,
     pub currency: Option<String>,
+    pub sort_order: Option<i64>,
+    pub is_favorite: Option<bool>,
+    pub default_category_id: Option<i64>,
+    pub credit_limit: Option<f64>, // 0 clears the limit
+    pub opened_on: Option<NaiveDate>, // Not after the first transaction
+}
+
+/// What a currency change does to an account that already has transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrencyChangeMode {
+    #[default]
+    Block, // Refuse the change; their amounts would be silently re-labelled
+    Convert, // Convert balances and history, keeping the old amounts as originals
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAccountQuery {
+    #[serde(default)]
+    pub currency_mode: CurrencyChangeMode,
+    pub rate: Option<f64>, // New currency per old unit; the latest FX rate if omitted
+}
+
+/// Data for closing an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseAccount {
+    pub destination_account_id: Option<i64>, // Required unless the balance is zero
+}
+
+/// Account group entity - a user-defined set of accounts ("Liquid", "Retirement", "Joint")
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AccountGroup {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create an account group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAccountGroup {
+    pub user_id: i64,
+    #[serde(deserialize_with = "crate::input::line")]
+    pub name: String,
+}
+
+/// Account to move into a group (out of any group it was in)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddAccountGroupMember {
+    pub account_id: i64,
+}
+
+/// A closed account and the transactions of its closing transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountClosure {
+    pub account: Account,
+    pub transferred: f64, // In the closed account's currency; negative when a debt was paid off
+    pub transaction_ids: Vec<i64>,
+}
+
+/// An account's stored balance against the one recomputed from its transactions
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BalanceReconciliation {
+    pub account_id: i64,
+    pub account_name: String,
+    pub currency: String,
+    pub recorded_balance: f64, // current_balance before any fix
+    pub computed_balance: f64, // initial_balance plus all transactions
+    pub difference: f64,       // recorded - computed
+    pub transaction_count: i64,
+    pub fixed: bool, // current_balance was set to computed_balance
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReconcileQuery {
+    #[serde(default)]
+    pub fix: bool,
+}
+
+/// New account order for a user: account IDs from first to last
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderAccounts {
+    pub user_id: i64,
+    pub account_ids: Vec<i64>,
 }
 
 // ============================================================================
@@ -87,19 +285,29 @@ pub struct Category {
     pub name: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub bucket: Option<String>, // "needs", "wants" or "savings"
+    pub parent_category_id: Option<i64>, // Set on sub-categories, e.g. Food > Groceries
+    pub exclude_from_reports: bool,      // Its transactions are left out of analytics
 }
 
 /// Data required to create a new category
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCategory {
     pub user_id: i64,
+    #[serde(deserialize_with = "crate::input::line")]
     pub name: String,
+    pub bucket: Option<String>,
+    pub parent_category_id: Option<i64>,
 }
 
 /// Data for updating a category
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateCategory {
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub name: Option<String>,
+    pub bucket: Option<String>, // An empty string clears the bucket
+    pub parent_category_id: Option<i64>, // 0 makes it a top-level category again
+    pub exclude_from_reports: Option<bool>,
 }
 
 // ============================================================================
@@ -117,6 +325,24 @@ pub struct Transaction {
     pub transaction_date: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub notes: Option<String>,     // Long-form memo
+    pub reference: Option<String>, // Check number / bank reference
+    pub merchant: Option<String>,  // Normalized merchant/payee name
+    pub merchant_city: Option<String>,
+    pub merchant_country: Option<String>,
+    pub original_amount: Option<f64>,     // Amount in the original (foreign) currency
+    pub original_currency: Option<String>,
+    pub exchange_rate: Option<f64>,       // Applied rate: account currency per original unit
+    pub fee: f64,                         // Bank/FX fee, debited on top of the amount
+    pub fee_category_id: Option<i64>,
+    pub recurring_transaction_id: Option<i64>, // Set when generated by the recurring processor
+    pub base_amount: Option<f64>,         // Amount in base_currency at the rate of the transaction date
+    pub base_currency: Option<String>,    // Owner's base currency when the transaction was recorded
+    pub linked_transaction_id: Option<i64>, // Other leg of a transfer between accounts
+    pub status: String,                     // "pending", "cleared" or "void"; only cleared ones are in current_balance
+    pub transfer_group_id: Option<i64>,     // Shared by both legs of a transfer: the debit leg's id
+    pub exclude_from_reports: bool,         // Left out of analytics, e.g. a reimbursement
+    pub reimburses_transaction_id: Option<i64>, // Set on income paying back an expense
 }
 
 /// Data required to create a new transaction
@@ -125,9 +351,45 @@ pub struct CreateTransaction {
     pub account_id: i64,
     pub amount: f64,
     pub transaction_type: String, // "income", "expense", "transfer"
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub description: Option<String>,
     pub transaction_date: Option<DateTime<Utc>>, // Defaults to now if not provided
     pub categories: Vec<CategoryAmount>,         // For split transactions
+    #[serde(default, deserialize_with = "crate::input::optional_text")]
+    pub notes: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub reference: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant: Option<String>, // Normalized before storage
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant_city: Option<String>,
+    pub merchant_country: Option<String>,
+    pub original_amount: Option<f64>,
+    pub original_currency: Option<String>,
+    pub exchange_rate: Option<f64>, // Derived from amount / original_amount if omitted
+    pub fee: Option<f64>,
+    pub fee_category_id: Option<i64>,
+    #[serde(default)]
+    pub status: Option<String>, // Defaults to "cleared"
+}
+
+/// Expense pre-filled from a receipt; nothing is saved until it is confirmed
+/// and sent to POST /transactions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptDraft {
+    pub parser: String, // Receipt parser that read it, e.g. "local"
+    pub account_id: Option<i64>,
+    pub amount: Option<f64>, // The receipt total
+    pub transaction_type: String, // Always "expense"
+    pub description: Option<String>,
+    pub merchant: Option<String>,
+    pub transaction_date: Option<DateTime<Utc>>,
+    pub category_id: Option<i64>, // Suggested by the user's category rules
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReceiptQuery {
+    pub account_id: Option<i64>,
 }
 
 /// Category amount for split transactions
@@ -138,12 +400,29 @@ pub struct CategoryAmount {
 }
 
 /// Data for updating a transaction
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateTransaction {
     pub amount: Option<f64>,
     pub transaction_type: Option<String>,
-    pub description: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub description: Option<String>, // Empty clears it
     pub transaction_date: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "crate::input::optional_text")]
+    pub notes: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub reference: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant: Option<String>, // Normalized before storage; empty clears it
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant_city: Option<String>,
+    pub merchant_country: Option<String>,
+    pub original_amount: Option<f64>,
+    pub original_currency: Option<String>,
+    pub exchange_rate: Option<f64>,
+    pub fee: Option<f64>,
+    pub fee_category_id: Option<i64>, // 0 clears it
+    pub exclude_from_reports: Option<bool>,
+    pub categories: Option<Vec<CategoryAmount>>, // Replaces the splits; they must add up to the amount
 }
 
 /// Transaction with its categories (joined data)
@@ -152,6 +431,27 @@ pub struct TransactionWithCategories {
     #[serde(flatten)]
     pub transaction: Transaction,
     pub categories: Vec<TransactionCategoryDetail>,
+    pub tags: Vec<String>,
+}
+
+/// Transaction with the account balance right after it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionWithRunningBalance {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub running_balance: f64,
+}
+
+/// New split set for a transaction (replaces all existing splits)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTransactionCategories {
+    pub categories: Vec<CategoryAmount>, // Empty to uncategorize
+}
+
+/// New status for a transaction: "pending", "cleared" or "void"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTransactionStatus {
+    pub status: String,
 }
 
 /// Category detail for a transaction
@@ -162,6 +462,98 @@ pub struct TransactionCategoryDetail {
     pub amount: f64,
 }
 
+/// Body of POST /transactions/{id}/link-reimbursement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkReimbursement {
+    pub reimbursement_id: i64, // The later income that pays the expense back
+}
+
+/// An expense with the reimbursements linked to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReimbursedExpense {
+    pub expense: Transaction,
+    pub reimbursements: Vec<Transaction>,
+    pub reimbursed_amount: f64,
+    pub net_amount: f64, // What the expense still costs, never below zero
+}
+
+/// Data required to move money between two accounts of the same user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTransfer {
+    pub from_account_id: i64,
+    pub to_account_id: i64,
+    pub amount: f64, // In the source account's currency
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub description: Option<String>,
+    pub transaction_date: Option<DateTime<Utc>>, // Defaults to now if not provided
+    pub fee: Option<f64>, // Debited from the source account
+}
+
+/// Both legs of a transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transfer {
+    pub from_transaction: Transaction, // "transfer" leg debiting the source
+    pub to_transaction: Transaction,   // "income" leg crediting the destination
+    pub exchange_rate: f64,            // Destination currency per source unit
+}
+
+// ============================================================================
+// Transaction_Templates Models
+// ============================================================================
+
+/// Transaction_Template entity - a saved, pre-filled transaction for frequent entries
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TransactionTemplate {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String, // e.g. "Coffee"
+    pub account_id: i64,
+    pub category_id: Option<i64>,
+    pub amount: f64,
+    pub transaction_type: String, // "income", "expense", "transfer"
+    pub description: Option<String>,
+    pub merchant: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a new transaction template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTransactionTemplate {
+    pub user_id: i64,
+    #[serde(deserialize_with = "crate::input::line")]
+    pub name: String,
+    pub account_id: i64,
+    pub category_id: Option<i64>,
+    pub amount: f64,
+    pub transaction_type: Option<String>, // Defaults to "expense" if not provided
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant: Option<String>,
+}
+
+/// Data for updating a transaction template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTransactionTemplate {
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub name: Option<String>,
+    pub account_id: Option<i64>,
+    pub category_id: Option<i64>,
+    pub amount: Option<f64>,
+    pub transaction_type: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant: Option<String>,
+}
+
+/// Transaction template filter parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateFilter {
+    pub user_id: Option<i64>,
+}
+
 // ============================================================================
 // Transaction_Categories Models
 // ============================================================================
@@ -185,6 +577,49 @@ pub struct CreateTransactionCategory {
     pub amount: f64,
 }
 
+// ============================================================================
+// Tags Models
+// ============================================================================
+
+/// Tag entity - a free-form label on transactions ("vacation2024", "reimbursable")
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String, // Lowercase
+    pub created_at: DateTime<Utc>,
+}
+
+/// Data required to create a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTag {
+    pub user_id: i64,
+    pub name: String,
+}
+
+/// Tag to put on a transaction, created if the user doesn't have it yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddTransactionTag {
+    pub name: String,
+}
+
+// ============================================================================
+// Attachments Models
+// ============================================================================
+
+/// Attachment entity - a receipt image or PDF stored on disk for a transaction
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub filename: String, // As uploaded
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(skip_serializing)] // Relative to ATTACHMENT_DIR; downloads go through the API
+    pub storage_path: String,
+    pub created_at: DateTime<Utc>,
+}
+
 // ============================================================================
 // Recurring_Transactions Models
 // ============================================================================
@@ -205,6 +640,9 @@ pub struct RecurringTransaction {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub classification: String, // "fixed", "variable"
+    pub currency: Option<String>, // Billing currency when it differs from the account's
+    pub catch_up: String, // "all", "latest" - what processing does with missed occurrences
 }
 
 /// Data required to create a new recurring transaction
@@ -214,10 +652,14 @@ pub struct CreateRecurringTransaction {
     pub category_id: Option<i64>,
     pub amount: f64,
     pub transaction_type: String, // "income", "expense"
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub description: Option<String>,
     pub frequency: String, // "daily", "weekly", "monthly", "yearly"
     pub start_date: DateTime<Utc>,
     pub end_date: Option<DateTime<Utc>>,
+    pub classification: Option<String>, // "fixed", "variable" - defaults to "fixed"
+    pub currency: Option<String>,       // Defaults to the account currency
+    pub catch_up: Option<String>,       // "all", "latest" - defaults to "all"
 }
 
 /// Data for updating a recurring transaction
@@ -226,11 +668,15 @@ pub struct UpdateRecurringTransaction {
     pub category_id: Option<i64>,
     pub amount: Option<f64>,
     pub transaction_type: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
     pub description: Option<String>,
     pub frequency: Option<String>,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
     pub is_active: Option<bool>,
+    pub classification: Option<String>,
+    pub currency: Option<String>, // Empty string resets to the account currency
+    pub catch_up: Option<String>,
 }
 
 // ============================================================================
@@ -248,6 +694,8 @@ pub struct ExchangeRate {
     pub source: String, // "api", "bank", "manual", "scraper"
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub pinned: bool, // Manual rate used for its pair until unpinned
+    pub rate_source_id: Option<i64>, // Provider in rate_sources, None if unknown
 }
 
 /// Data required to create a new exchange rate
@@ -258,6 +706,8 @@ pub struct CreateExchangeRate {
     pub rate: f64,
     pub rate_date: Option<DateTime<Utc>>,
     pub source: Option<String>, // "api", "bank", "manual", "scraper"
+    pub pinned: Option<bool>,   // Manual rates only
+    pub rate_source_id: Option<i64>, // Provider; its kind becomes the source
 }
 
 /// Data for updating an exchange rate
@@ -265,6 +715,8 @@ pub struct CreateExchangeRate {
 pub struct UpdateExchangeRate {
     pub rate: Option<f64>,
     pub source: Option<String>,
+    pub pinned: Option<bool>, // Pinning unpins any other rate of the pair
+    pub rate_source_id: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -276,14 +728,108 @@ pub struct ExchangeRateFilter {
     pub from_currency: Option<String>,
     pub to_currency: Option<String>,
     pub source: Option<String>,
+    pub provider: Option<String>, // Rate source name, e.g. "x-rates.com"
     pub date: Option<chrono::NaiveDate>,
 }
 
+/// A named provider of exchange rates, e.g. "x-rates.com" of kind "scraper"
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct RateSource {
+    pub id: i64,
+    pub name: String,
+    pub kind: String, // One of the exchange rate sources: "api", "bank", "manual", "scraper", "official"
+    pub url: Option<String>,
+    pub reliability_notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a rate source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRateSource {
+    pub name: String,
+    pub kind: String,
+    pub url: Option<String>,
+    pub reliability_notes: Option<String>,
+}
+
+/// Data for updating a rate source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRateSource {
+    pub name: Option<String>,
+    pub kind: Option<String>, // Only while no rates refer to the source
+    pub url: Option<String>,
+    pub reliability_notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RateSourceFilter {
+    pub kind: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CurrencyConversion {
     pub from_currency: String,
     pub to_currency: String,
     pub amount: f64,
+    pub sources: Option<String>, // Source priority override, e.g. "api,manual"
+    pub purpose: Option<String>, // "tax" uses FX_TAX_SOURCE_PRIORITY (official rates first)
+    pub date: Option<NaiveDate>, // Convert at the rate quoted closest to this day instead of the latest
+}
+
+/// Currency list query parameters
+#[derive(Debug, Deserialize)]
+pub struct CurrencyQuery {
+    pub user_id: Option<i64>,
+}
+
+/// A currency offered in filter and convert pickers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyInfo {
+    pub code: String,         // e.g. "ARS"
+    pub name: Option<String>, // e.g. "Argentine Peso", when a scraped rate names it
+    pub in_accounts: bool,    // Used by one of the user's accounts
+    pub has_rates: bool,      // Appears in stored exchange rates
+}
+
+/// Scrape preview query parameters
+#[derive(Debug, Deserialize)]
+pub struct ScrapePreviewQuery {
+    pub currencies: Option<String>, // Comma-separated, defaults to the account currencies
+}
+
+/// Scrape run history query parameters
+#[derive(Debug, Deserialize)]
+pub struct ScrapeRunQuery {
+    pub limit: Option<i64>, // Defaults to 20
+}
+
+/// Scrape run entity - metrics of one exchange-rate scraper run
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ScrapeRun {
+    pub id: i64,
+    pub provider: String,
+    pub currencies: String, // Comma-separated base currencies
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub rates_saved: i64,
+    pub currencies_skipped: i64, // Already up to date
+    pub status: String,          // "success", "partial", "failed"
+    pub errors: Option<String>,  // One "CODE: message" line per failed currency
+    pub created_at: DateTime<Utc>,
+}
+
+/// Scraped rate compared with the latest stored rate of its pair
+#[derive(Debug, Clone, Serialize)]
+pub struct RateDiff {
+    pub from_currency: String,
+    pub to_currency: String,
+    pub current_rate: Option<f64>,
+    pub current_date: Option<String>,
+    pub new_rate: f64,
+    pub new_date: String,
+    pub change_percent: Option<f64>,
+    pub status: String, // "insert", "update", "unchanged"
 }
 
 #[derive(Debug, Serialize)]
@@ -293,6 +839,40 @@ pub struct ConversionResult {
     pub amount: f64,
     pub rate: f64,
     pub converted_amount: f64,
+    pub source: String, // Source of the rate that was used
+    pub rate_date: DateTime<Utc>,
+    pub rate_age_days: i64,
+    pub stale: bool, // Older than FX_STALE_AFTER_DAYS
+}
+
+/// Stale rates query parameters
+#[derive(Debug, Deserialize)]
+pub struct StaleRateQuery {
+    pub days: Option<i64>, // Overrides FX_STALE_AFTER_DAYS
+}
+
+/// Currency pair whose effective rate needs a refresh
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleRate {
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate: f64,
+    pub source: String,
+    pub pinned: bool,
+    pub rate_date: DateTime<Utc>,
+    pub age_days: i64,
+}
+
+/// Size of the exchange-rate tables, see rate_compaction
+#[derive(Debug, Clone, Serialize)]
+pub struct RateStorageStats {
+    pub rates: i64,             // Rows in exchange_rates
+    pub pairs: i64,             // Currency pairs with a rate
+    pub compactable_rates: i64, // Intra-day rates the next compaction would archive
+    pub archived_rates: i64,    // Rows in exchange_rates_archive
+    pub oldest_rate_date: Option<DateTime<Utc>>,
+    pub newest_rate_date: Option<DateTime<Utc>>,
+    pub last_archived_at: Option<DateTime<Utc>>, // Latest compaction that archived anything
 }
 
 #[derive(Debug, Deserialize)]
@@ -321,6 +901,50 @@ pub struct AnalyticsFilter {
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
     pub limit: Option<i64>,
+    pub rollup: Option<bool>, // Fold sub-category spending into the parent
+}
+
+/// Spending pattern query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternFilter {
+    pub user_id: Option<i64>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub utc_offset_minutes: Option<i64>, // Shift hours into the user's local time (default 0)
+}
+
+/// Cash-flow query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowQuery {
+    pub user_id: Option<i64>,
+    pub period: Option<String>, // "YYYY-MM", defaults to the current month
+}
+
+/// Bucket split query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketQuery {
+    pub user_id: Option<i64>,
+    pub period: Option<String>,  // "YYYY-MM", defaults to the current month
+    pub targets: Option<String>, // e.g. "60/30/10", overrides the bucket_targets setting
+}
+
+/// Spending of one bucket against its target share
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketAllocation {
+    pub bucket: String,
+    pub amount: f64,
+    pub percent: f64,        // Share of the bucketed spending
+    pub target_percent: f64,
+    pub difference: f64,     // percent - target_percent
+}
+
+/// Needs/wants/savings split of a period's spending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketSplit {
+    pub period: String,
+    pub buckets: Vec<BucketAllocation>,
+    pub total: f64,
+    pub unassigned: f64, // Spending in categories without a bucket, and uncategorized fees
 }
 
 /// Spending comparison query parameters
@@ -350,15 +974,507 @@ pub struct ExportFilter {
     pub end_date: Option<DateTime<Utc>>,
     pub account_id: Option<i64>,
     pub category_id: Option<i64>,
+    pub encrypt: Option<bool>, // Passphrase is sent in the X-Export-Passphrase header
 }
 
-/// Financial export summary
+/// Plaintext ledger export query parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FinancialExportSummary {
-    pub export_date: DateTime<Utc>,
-    pub accounts: Vec<Account>,
+pub struct LedgerExportFilter {
+    pub user_id: Option<i64>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub format: Option<String>, // "beancount" (default) or "ledger"
+    pub encrypt: Option<bool>,
+}
+
+/// Monthly statement query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementQuery {
+    pub user_id: i64,
+    pub month: String, // "YYYY-MM"
+    pub encrypt: Option<bool>,
+}
+
+/// Financial export summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinancialExportSummary {
+    pub export_date: DateTime<Utc>,
+    pub accounts: Vec<Account>,
     pub categories: Vec<Category>,
     pub transactions: Vec<Transaction>,
+    pub transaction_categories: Vec<TransactionCategory>, // Splits, so a restore can re-link categories
+    pub recurring_transactions: Vec<RecurringTransaction>,
+}
+
+/// Query parameters of POST /import/summary
+#[derive(Debug, Deserialize)]
+pub struct SummaryImportQuery {
+    pub user_id: Option<i64>, // Defaults to the user_id in the file (TUI exports have one)
+}
+
+/// What restoring a financial summary recreated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryImportResult {
+    pub user_id: i64,
+    pub accounts: usize,
+    pub categories_created: usize,
+    pub categories_matched: usize, // Already existed by name and were reused
+    pub transactions: usize,
+    pub recurring_transactions: usize,
+    pub skipped: usize, // Transactions and recurring transactions of accounts not in the file
+}
+
+// ============================================================================
+// Budget Models
+// ============================================================================
+
+/// Budget entity - a monthly spending limit for one category
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Budget {
+    pub id: i64,
+    pub user_id: i64,
+    pub category_id: i64,
+    pub monthly_limit: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a budget (replaces an existing budget for the category)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBudget {
+    pub user_id: i64,
+    pub category_id: i64,
+    pub monthly_limit: f64,
+}
+
+/// Data for changing a budget's limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateBudget {
+    pub monthly_limit: f64,
+}
+
+/// Budget filter parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetFilter {
+    pub user_id: Option<i64>,
+}
+
+// ============================================================================
+// Goal Models
+// ============================================================================
+
+/// Goal entity - a target amount to save up in the linked accounts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub target_amount: f64,
+    pub currency: String, // Linked accounts are all in it
+    pub target_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a goal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateGoal {
+    pub user_id: i64,
+    #[serde(deserialize_with = "crate::input::line")]
+    pub name: String,
+    pub target_amount: f64,
+    pub currency: Option<String>, // Defaults to the first linked account's currency, else "USD"
+    pub target_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub account_ids: Vec<i64>,
+}
+
+/// Data for changing a goal; fields left out are unchanged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateGoal {
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub name: Option<String>,
+    pub target_amount: Option<f64>,
+    pub target_date: Option<NaiveDate>,
+    pub account_ids: Option<Vec<i64>>, // Replaces the linked accounts
+}
+
+/// A goal with its progress and where it is heading at the recent saving rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalProgress {
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub account_ids: Vec<i64>,
+    pub saved: f64, // Current balance of the linked accounts
+    pub remaining: f64,
+    pub percent: f64,
+    pub monthly_saving_rate: f64, // Net cleared inflow into the linked accounts per 30 days, over the last 90
+    pub projected_completion: Option<NaiveDate>, // Today once reached; None when nothing is being saved
+    pub required_monthly: Option<f64>, // Saving per 30 days that reaches the target by target_date
+    pub on_track: Option<bool>,        // projected_completion is on or before target_date
+}
+
+// ============================================================================
+// Holding Models
+// ============================================================================
+
+/// Holding entity - a position in one security of an investment account
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Holding {
+    pub id: i64,
+    pub account_id: i64,
+    pub ticker: String,
+    pub quantity: f64,
+    pub cost_basis: f64, // Total paid for the position, in the account's currency
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to add a holding to an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateHolding {
+    #[serde(deserialize_with = "crate::input::line")]
+    pub ticker: String,
+    pub quantity: f64,
+    #[serde(default)]
+    pub cost_basis: f64,
+}
+
+/// Data for changing a holding; fields left out are unchanged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateHolding {
+    pub quantity: Option<f64>,
+    pub cost_basis: Option<f64>,
+}
+
+/// A fetched security quote
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SecurityPrice {
+    pub id: i64,
+    pub ticker: String,
+    pub price: f64,
+    pub price_date: NaiveDate,
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A holding valued at the latest quote of its ticker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingValue {
+    #[serde(flatten)]
+    pub holding: Holding,
+    pub price: Option<f64>, // None until a quote was fetched
+    pub price_date: Option<NaiveDate>,
+    pub market_value: f64, // At cost while there is no quote
+    pub gain: f64,
+    pub gain_percent: Option<f64>, // None when the cost basis is 0
+    pub allocation: f64, // Percent of the account's market value
+}
+
+/// The holdings of an investment account and their totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountHoldings {
+    pub account_id: i64,
+    pub currency: String,
+    pub holdings: Vec<HoldingValue>,
+    pub market_value: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub gain_percent: Option<f64>,
+}
+
+// ============================================================================
+// Loan Models
+// ============================================================================
+
+/// Loan terms of a loan account
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Loan {
+    pub account_id: i64,
+    pub principal: f64,
+    pub annual_rate: f64, // Nominal yearly rate in percent, compounded monthly
+    pub term_months: i64,
+    pub first_payment_date: NaiveDate,
+    pub payment_account_id: i64, // Account the monthly payment is debited from
+    pub recurring_transaction_id: Option<i64>, // The monthly payment; None once it was deleted
+    pub principal_category_id: i64,
+    pub interest_category_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Loan terms to set on a loan account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLoan {
+    pub principal: f64,
+    pub annual_rate: f64,
+    pub term_months: i64,
+    pub first_payment_date: NaiveDate,
+    pub payment_account_id: i64,
+}
+
+/// One payment of an amortization schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmortizationRow {
+    pub number: i64, // 1-based
+    pub date: NaiveDate,
+    pub payment: f64,
+    pub principal: f64,
+    pub interest: f64,
+    pub balance: f64, // Left owing after the payment
+}
+
+/// A loan with its payment plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Amortization {
+    pub loan: Loan,
+    pub monthly_payment: f64,
+    pub total_interest: f64,
+    pub schedule: Vec<AmortizationRow>,
+}
+
+/// Budget variance report query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetVarianceQuery {
+    pub user_id: Option<i64>,
+    pub period: Option<String>, // "YYYY-MM", defaults to the current month
+    pub format: Option<String>, // "json" (default) or "csv"
+    pub encrypt: Option<bool>,
+}
+
+/// Spending chart query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendingChartQuery {
+    pub user_id: Option<i64>,
+    pub period: Option<String>, // "YYYY-MM", defaults to the current month
+    pub chart: Option<String>,  // "bar" (default) or "pie"
+}
+
+/// One row of the budget vs. actual report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetVariance {
+    pub budget_id: i64,
+    pub category_id: i64,
+    pub category_name: String,
+    pub period: String,
+    pub budgeted: f64,
+    pub actual: f64,
+    pub variance_amount: f64,  // budgeted - actual; negative when over budget
+    pub variance_percent: f64, // variance_amount / budgeted * 100
+    pub status: String,        // "under", "near", "over"
+    pub projected: Option<f64>, // Spend by month end at the pace so far; None for months not started
+    pub projected_status: Option<String>, // status of the projected spend
+}
+
+// ============================================================================
+// Import Models
+// ============================================================================
+
+/// Category rule entity - suggests a category for descriptions containing the pattern
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub id: i64,
+    pub user_id: i64,
+    pub pattern: String, // Matched case-insensitively as a substring
+    pub category_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a category rule (replaces an existing rule with the same pattern)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCategoryRule {
+    pub user_id: i64,
+    pub pattern: String,
+    pub category_id: i64,
+}
+
+/// Category rule filter parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRuleFilter {
+    pub user_id: Option<i64>,
+}
+
+/// Bulk recategorization: move every matching transaction of a user to one category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecategorizeRequest {
+    pub user_id: i64,
+    pub pattern: Option<String>, // Case-insensitive substring of the payee or description
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub from_category_id: Option<i64>, // Only move splits booked to this category
+    pub to_category_id: i64,
+}
+
+/// Outcome of a bulk recategorization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecategorizeResult {
+    pub updated: u64, // Transactions whose categories changed
+}
+
+/// Statement file to preview before importing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPreviewRequest {
+    pub user_id: i64,
+    pub account_id: Option<i64>, // Defaults to the profile's account
+    pub format: Option<String>, // "csv" (default) or "ofx"
+    pub content: String,        // Raw file contents
+    #[serde(default)]
+    pub mapping: CsvColumnMapping,
+    pub profile_id: Option<i64>, // Saved mapping to use instead of `mapping`
+}
+
+/// Header names to read each field from when a bank's CSV doesn't use the
+/// usual ones. Unset fields fall back to the built-in header aliases.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CsvColumnMapping {
+    pub date: Option<String>,
+    pub description: Option<String>,
+    pub amount: Option<String>, // Signed amount, negative = expense
+    pub debit: Option<String>,
+    pub credit: Option<String>,
+    pub reference: Option<String>,
+    pub date_format: Option<String>, // chrono format, e.g. "%d/%m/%Y"; detected when unset
+    pub amount_sign: Option<String>, // "negative_expenses" (default) or "positive_expenses"
+}
+
+/// CSV statement to import straight into an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvImportRequest {
+    pub user_id: i64,
+    pub account_id: Option<i64>, // Defaults to the profile's account
+    pub content: String, // Raw file contents
+    #[serde(default)]
+    pub mapping: CsvColumnMapping,
+    pub profile_id: Option<i64>, // Saved mapping to use instead of `mapping`
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvImportResult {
+    pub imported: usize,
+    pub duplicates_skipped: usize,
+    pub before_opening_skipped: usize, // Dated before the account's opened_on
+    pub date_format: Option<String>, // None when rows use mixed date formats
+    pub transaction_ids: Vec<i64>,
+}
+
+/// One statement line. The preview fills in the suggestion and sets
+/// category_id to it; the client may change category_id before committing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRow {
+    pub transaction_date: DateTime<Utc>,
+    #[serde(deserialize_with = "crate::input::line")]
+    pub description: String,
+    pub amount: f64,              // Always positive; the sign is in transaction_type
+    pub transaction_type: String, // "income", "expense"
+    pub merchant: Option<String>, // Normalized payee
+    pub reference: Option<String>,
+    pub suggested_category_id: Option<i64>,
+    pub suggested_category_name: Option<String>,
+    pub suggestion_source: Option<String>, // "rule" or "history"
+    pub category_id: Option<i64>,
+    #[serde(default)]
+    pub create_rule: bool, // Save a rule from a manual correction on commit
+    #[serde(default)]
+    pub duplicate: bool, // Already in the account (same day, amount and payee or reference)
+}
+
+/// Reviewed rows to import into an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCommitRequest {
+    pub user_id: i64,
+    pub account_id: i64,
+    pub rows: Vec<ImportRow>,
+}
+
+/// Rule that would make a manual correction the suggestion next time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSuggestion {
+    pub pattern: String,
+    pub category_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub transaction_ids: Vec<i64>,
+    pub rules_created: Vec<CategoryRule>,
+    pub rule_suggestions: Vec<RuleSuggestion>, // Corrections not saved as rules
+}
+
+/// Import profile entity - a named CSV mapping for one bank's statements
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ImportProfile {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub account_id: Option<i64>, // Default account to import into
+    pub date_column: Option<String>,
+    pub description_column: Option<String>,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub reference_column: Option<String>,
+    pub date_format: Option<String>,
+    pub amount_sign: String, // "negative_expenses" or "positive_expenses"
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to save an import profile (replaces an existing profile with the same name)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateImportProfile {
+    pub user_id: i64,
+    pub name: String,
+    pub account_id: Option<i64>,
+    #[serde(default)]
+    pub mapping: CsvColumnMapping,
+}
+
+/// Import profile filter parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProfileFilter {
+    pub user_id: Option<i64>,
+}
+
+// ============================================================================
+// Webhook Models
+// ============================================================================
+
+/// Webhook entity - an outbound HTTP endpoint subscribed to domain events
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: i64,
+    pub user_id: i64,
+    pub url: String,
+    pub event_types: String, // Comma-separated event types, or "*" for all
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a new webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWebhook {
+    pub user_id: i64,
+    pub url: String,
+    pub event_types: Option<String>, // Defaults to "*" if not provided; list "monthly-report" for monthly summaries
+}
+
+/// Webhook delivery - one event_outbox row for a webhook
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_type: String, // e.g. "transaction.created"
+    pub payload: String,    // JSON body sent to the webhook
+    pub status: String,     // "pending", "delivered", "failed"
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub last_response_status: Option<i64>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 // ============================================================================
@@ -373,6 +1489,9 @@ pub enum AccountType {
     Checking,
     Savings,
     CreditCard,
+    Investment,
+    Cash,
+    Loan,
 }
 
 #[allow(dead_code)]
@@ -382,6 +1501,9 @@ impl AccountType {
             AccountType::Checking => "checking",
             AccountType::Savings => "savings",
             AccountType::CreditCard => "credit_card",
+            AccountType::Investment => "investment",
+            AccountType::Cash => "cash",
+            AccountType::Loan => "loan",
         }
     }
 
@@ -390,9 +1512,17 @@ impl AccountType {
             "checking" => Some(AccountType::Checking),
             "savings" => Some(AccountType::Savings),
             "credit_card" => Some(AccountType::CreditCard),
+            "investment" => Some(AccountType::Investment),
+            "cash" => Some(AccountType::Cash),
+            "loan" => Some(AccountType::Loan),
             _ => None,
         }
     }
+
+    /// Money owed rather than owned; subtracted from net worth
+    pub fn is_liability(&self) -> bool {
+        matches!(self, AccountType::CreditCard | AccountType::Loan)
+    }
 }
 
 /// Transaction types
@@ -458,6 +1588,88 @@ impl Frequency {
     }
 }
 
+/// Recurring transaction classification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+pub enum RecurringClassification {
+    Fixed,
+    Variable,
+}
+
+#[allow(dead_code)]
+impl RecurringClassification {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurringClassification::Fixed => "fixed",
+            RecurringClassification::Variable => "variable",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "fixed" => Some(RecurringClassification::Fixed),
+            "variable" => Some(RecurringClassification::Variable),
+            _ => None,
+        }
+    }
+}
+
+/// What processing does with the occurrences of a recurring item that were
+/// missed while the app wasn't running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+pub enum CatchUpPolicy {
+    All,    // Post every missed occurrence
+    Latest, // Post only the most recent one and skip ahead
+}
+
+#[allow(dead_code)]
+impl CatchUpPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CatchUpPolicy::All => "all",
+            CatchUpPolicy::Latest => "latest",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "all" => Some(CatchUpPolicy::All),
+            "latest" => Some(CatchUpPolicy::Latest),
+            _ => None,
+        }
+    }
+}
+
+/// How a bank's signed amount column marks expenses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+pub enum AmountSign {
+    NegativeExpenses, // -12.50 is money out (most banks)
+    PositiveExpenses, // 12.50 is money out (many credit card statements)
+}
+
+#[allow(dead_code)]
+impl AmountSign {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AmountSign::NegativeExpenses => "negative_expenses",
+            AmountSign::PositiveExpenses => "positive_expenses",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "negative_expenses" => Some(AmountSign::NegativeExpenses),
+            "positive_expenses" => Some(AmountSign::PositiveExpenses),
+            _ => None,
+        }
+    }
+}
+
 /// Exchange rate source types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -467,6 +1679,7 @@ pub enum ExchangeRateSource {
     Bank,
     Manual,
     Scraper,
+    Official, // Central-bank reference rates
 }
 
 #[allow(dead_code)]
@@ -477,6 +1690,7 @@ impl ExchangeRateSource {
             ExchangeRateSource::Bank => "bank",
             ExchangeRateSource::Manual => "manual",
             ExchangeRateSource::Scraper => "scraper",
+            ExchangeRateSource::Official => "official",
         }
     }
 
@@ -486,6 +1700,7 @@ impl ExchangeRateSource {
             "bank" => Some(ExchangeRateSource::Bank),
             "manual" => Some(ExchangeRateSource::Manual),
             "scraper" => Some(ExchangeRateSource::Scraper),
+            "official" => Some(ExchangeRateSource::Official),
             _ => None,
         }
     }
@@ -569,35 +1784,210 @@ pub struct TransactionFilter {
     pub category_id: Option<i64>,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
+    pub period: Option<String>, // "this_month", "last_month" or "YYYY-MM"
     pub min_amount: Option<f64>,
     pub max_amount: Option<f64>,
+    pub status: Option<String>, // "pending", "cleared" or "void"
+    pub transfer_group_id: Option<i64>, // Both legs of one transfer
+    pub tag: Option<String>, // Comma-separated; transactions with all of these tags
+    #[serde(default)]
+    pub include_running_balance: bool, // Needs account_id
     #[serde(default = "default_page")]
     pub page: i64,
     #[serde(default = "default_page_size")]
     pub page_size: i64,
 }
 
-// ============================================================================
-// Statistics Models
-// ============================================================================
-
-/// Account balance summary
+/// Activity feed parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityFilter {
+    pub user_id: Option<i64>,
+    pub limit: Option<i64>, // Defaults to 50
+}
+
+// ============================================================================
+// Statistics Models
+// ============================================================================
+
+/// One entry of the recent-activity feed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ActivityItem {
+    pub kind: String, // "transaction", "recurring", "import", "rate_refresh", "export", "alert"
+    pub occurred_at: DateTime<Utc>,
+    pub title: String,
+    pub detail: Option<String>,
+    pub amount: Option<f64>, // Signed: income positive, expenses negative
+    pub reference_id: i64,   // ID of the underlying row (transaction, import batch, rate, drop, delivery)
+}
+
+/// Discretionary budget for the remainder of the current month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscretionaryBudget {
+    pub user_id: i64,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub days_remaining: i64,
+    pub projected_income: f64,
+    pub fixed_obligations: f64,
+    pub variable_obligations: f64,
+    pub savings_goals: f64,
+    pub discretionary: f64, // projected_income - fixed_obligations - savings_goals
+}
+
+/// Amount that can be spent per day for the rest of the month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafeToSpend {
+    pub user_id: i64,
+    pub as_of: DateTime<Utc>,
+    pub days_remaining: i64,
+    pub available_balance: f64, // current balance of checking accounts
+    pub projected_income: f64,
+    pub upcoming_bills: f64, // fixed + variable recurring expenses until month end
+    pub savings_goals: f64,
+    pub budgets_remaining: f64, // unspent part of this month's category budgets beyond the recurring bills due in them
+    pub safe_to_spend: f64, // available_balance + projected_income - upcoming_bills - savings_goals - budgets_remaining
+    pub safe_to_spend_per_day: f64,
+}
+
+/// Cash-flow forecast query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastQuery {
+    pub user_id: Option<i64>,
+    pub months: Option<u32>, // Months after the current one to project, 6 by default
+}
+
+/// One projected month of an account, in its currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastMonth {
+    pub month: String, // YYYY-MM
+    pub income: f64,   // Recurring income due plus the historical average
+    pub expenses: f64,
+    pub net: f64,
+    pub ending_balance: f64,
+}
+
+/// An account's projection, starting from its projected month-end balance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountForecast {
+    pub account_id: i64,
+    pub account_name: String,
+    pub currency: String,
+    pub starting_balance: f64, // At the end of the current month, see forecast::projected_balance
+    pub average_income: f64,   // Per month, leaving out transfers and recurring items
+    pub average_expenses: f64,
+    pub months: Vec<ForecastMonth>,
+}
+
+/// Monthly cash-flow forecast of every open account of a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashFlowForecast {
+    pub user_id: i64,
+    pub as_of: DateTime<Utc>,
+    pub history_months: u32, // Full months the averages are taken over
+    pub accounts: Vec<AccountForecast>,
+}
+
+/// Account balance summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
+pub struct AccountBalanceSummary {
+    pub account_id: i64,
+    pub account_name: String,
+    pub account_type: String,
+    pub currency: String,
+    pub current_balance: f64,
+}
+
+/// Category spending summary
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CategorySpendingSummary {
+    pub category_id: i64,
+    pub category_name: String,
+    pub total_amount: f64,
+    pub transaction_count: i64,
+}
+
+/// Merchant spending summary (payee leaderboard)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct MerchantSpendingSummary {
+    pub merchant: String,
+    pub merchant_city: Option<String>,
+    pub merchant_country: Option<String>,
+    pub total_amount: f64,
+    pub transaction_count: i64,
+}
+
+/// Expense totals for one weekday/hour cell (weekday 0 = Sunday)
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SpendingPatternCell {
+    pub weekday: i64,
+    pub hour: i64,
+    pub total_amount: f64,
+    pub transaction_count: i64,
+}
+
+/// Expense totals for one weekday or one hour of day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternBucket {
+    pub bucket: i64,
+    pub label: String, // "Fri" or "21:00"
+    pub total_amount: f64,
+    pub transaction_count: i64,
+}
+
+impl PatternBucket {
+    pub fn empty(bucket: i64, label: String) -> Self {
+        Self {
+            bucket,
+            label,
+            total_amount: 0.0,
+            transaction_count: 0,
+        }
+    }
+}
+
+/// Spending by weekday and hour of day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendingPatterns {
+    pub by_weekday: Vec<PatternBucket>,
+    pub by_hour: Vec<PatternBucket>,
+    pub cells: Vec<SpendingPatternCell>, // Only non-empty cells
+    pub peak: Option<SpendingPatternCell>,
+}
+
+/// Node of the cash-flow graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowNode {
+    pub id: String,    // "source:Salary", "account:3", "category:Groceries"
+    pub label: String,
+    pub kind: String,  // "source", "account", "category"
+}
+
+/// Weighted edge of the cash-flow graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlowEdge {
+    pub source: String, // FlowNode id
+    pub target: String, // FlowNode id
+    pub value: f64,
+}
+
+/// Income source -> account -> category flows, ready for Sankey rendering
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[allow(dead_code)]
-pub struct AccountBalanceSummary {
-    pub account_id: i64,
-    pub account_name: String,
-    pub account_type: String,
-    pub currency: String,
-    pub current_balance: f64,
+pub struct CashFlows {
+    pub period: String,
+    pub nodes: Vec<FlowNode>,
+    pub edges: Vec<FlowEdge>,
+    pub total_in: f64,
+    pub total_out: f64,
 }
 
-/// Category spending summary
+/// Spending grouped by the currency it was made in
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
-pub struct CategorySpendingSummary {
-    pub category_id: i64,
-    pub category_name: String,
-    pub total_amount: f64,
+pub struct CurrencySpendingSummary {
+    pub currency: String,         // Original currency, or the account currency for domestic spending
+    pub account_currency: String, // Currency the amounts were posted in
+    pub total_original_amount: f64,
+    pub total_posted_amount: f64,
     pub transaction_count: i64,
 }
 
@@ -611,6 +2001,82 @@ pub struct MonthlySummary {
     pub transaction_count: i64,
 }
 
+/// A recurring expense due within the digest's coming week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingBill {
+    pub recurring_transaction_id: i64,
+    pub description: Option<String>,
+    pub amount: f64,
+    pub due: DateTime<Utc>,
+}
+
+/// Last seven days at a glance, shown in the TUI after login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    pub spent_this_week: f64,         // Expense splits plus fees over the last 7 days
+    pub spent_last_week: f64,         // Same for the 7 days before
+    pub large_transactions: Vec<Transaction>, // Expenses this week at or above the large threshold, largest first
+    pub budgets_at_risk: Vec<BudgetVariance>, // This month's budgets near or over their limit
+    pub upcoming_bills: Vec<UpcomingBill>,    // Recurring expenses due in the next 7 days, soonest first
+}
+
+/// Latest run of one of the server's background tasks
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TaskHeartbeat {
+    pub task: String, // "recurring", "export_drops", "fx_scraper", "monthly_reports", "webhooks"
+    pub interval_seconds: i64,
+    pub last_run_at: DateTime<Utc>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>, // None when the latest run succeeded
+}
+
+/// Rows in one database table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+/// State of the database and the background tasks, shown on the TUI Status screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemStatus {
+    pub database_path: Option<String>, // None for an in-memory database
+    pub database_size: Option<u64>,    // Bytes, including the write-ahead log
+    pub tables: Vec<TableCount>,
+    pub last_scrape: Option<ScrapeRun>,
+    pub last_recurring_run: Option<DateTime<Utc>>, // When a recurring transaction last posted
+    pub pending_deliveries: i64,                   // Webhook events waiting to be delivered
+    pub failed_deliveries: i64,                    // Alerts: deliveries that gave up
+    pub tasks: Vec<TaskHeartbeat>,                 // Empty unless a server ran against this database
+}
+
+/// Previous month's summary POSTed to "monthly-report" webhooks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyReport {
+    pub user_id: i64,
+    pub period: String, // "YYYY-MM"
+    pub currency: Option<String>, // The user's base currency the totals are in; None when summed as recorded
+    pub totals: MonthlySummary,
+    pub budget_variance: Vec<BudgetVariance>,
+    pub top_categories: Vec<CategorySpendingSummary>, // Largest first
+}
+
+/// Credit usage of one account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountStats {
+    pub account_id: i64,
+    pub account_name: String,
+    pub account_type: String,
+    pub currency: String,
+    pub current_balance: f64,
+    pub projected_balance: f64, // At month end, with pending transactions and recurring items due
+    pub credit_limit: Option<f64>,
+    pub available_credit: Option<f64>,
+    pub utilization_percent: Option<f64>, // Share of the limit in use; None without a limit
+    pub utilization_threshold: f64,
+    pub over_threshold: bool,
+}
+
 /// Currency balance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
@@ -620,6 +2086,49 @@ pub struct CurrencyBalance {
     pub account_count: i64,
 }
 
+/// Balances of one account type in one currency
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AccountTypeBalance {
+    pub account_type: String,
+    pub currency: String,
+    pub classification: String, // "asset" or "liability"
+    pub total_balance: f64,
+    pub account_count: i64,
+}
+
+/// Assets minus liabilities in one currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetWorthSummary {
+    pub currency: String,
+    pub assets: f64,
+    pub liabilities: f64, // Amount owed, as a positive number
+    pub net_worth: f64,
+}
+
+/// Assets minus liabilities of the accounts in one group, per currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountGroupBalance {
+    pub group_id: Option<i64>, // None for the accounts in no group
+    pub group_name: String,
+    pub account_count: i64,
+    pub totals: Vec<NetWorthSummary>,
+}
+
+/// Net worth query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetWorthQuery {
+    pub user_id: Option<i64>,
+    pub as_of: Option<NaiveDate>, // End of this day; defaults to current balances
+}
+
+/// Net worth per currency with the account-type and account-group breakdowns behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetWorth {
+    pub totals: Vec<NetWorthSummary>,
+    pub by_account_type: Vec<AccountTypeBalance>,
+    pub by_group: Vec<AccountGroupBalance>,
+}
+
 // ============================================================================
 // Validation Helpers
 // ============================================================================
@@ -630,6 +2139,7 @@ impl CreateUser {
         if self.username.is_empty() {
             return Err("Username cannot be empty".to_string());
         }
+        input::check_length("Username", Some(&self.username), input::MAX_NAME_LEN)?;
         if self.email.is_empty() {
             return Err("Email cannot be empty".to_string());
         }
@@ -643,19 +2153,400 @@ impl CreateUser {
     }
 }
 
+impl UpdateUser {
+    /// Validate user update data
+    pub fn validate(&self) -> Result<(), String> {
+        input::check_length("Username", self.username.as_deref(), input::MAX_NAME_LEN)
+    }
+}
+
+impl CreateAccountGroup {
+    /// Validate account group creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Group name cannot be empty".to_string());
+        }
+        input::check_length("Group name", Some(&self.name), input::MAX_NAME_LEN)
+    }
+}
+
 impl CreateAccount {
     /// Validate account creation data
     pub fn validate(&self) -> Result<(), String> {
         if self.name.is_empty() {
             return Err("Account name cannot be empty".to_string());
         }
-        if !["checking", "savings", "credit_card"].contains(&self.account_type.as_str()) {
+        if AccountType::from_str(&self.account_type).is_none() {
             return Err("Invalid account type".to_string());
         }
+        input::check_length("Account name", Some(&self.name), input::MAX_NAME_LEN)?;
+        input::check_length("Bank name", self.bank_name.as_deref(), input::MAX_NAME_LEN)?;
+        if let Some(limit) = self.credit_limit {
+            if self.account_type != "credit_card" {
+                return Err("Only credit card accounts can have a credit limit".to_string());
+            }
+            if limit <= 0.0 {
+                return Err("Credit limit must be positive".to_string());
+            }
+        }
+        if self.opened_on.is_some_and(|d| d > Utc::now().date_naive()) {
+            return Err("Opening date cannot be in the future".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl UpdateAccount {
+    /// Validate account update data
+    pub fn validate(&self) -> Result<(), String> {
+        input::check_length("Account name", self.name.as_deref(), input::MAX_NAME_LEN)?;
+        input::check_length("Bank name", self.bank_name.as_deref(), input::MAX_NAME_LEN)
+    }
+}
+
+impl CreateCategory {
+    /// Validate category creation data
+    pub fn validate(&self) -> Result<(), String> {
+        input::check_length("Category name", Some(&self.name), input::MAX_NAME_LEN)
+    }
+}
+
+impl UpdateCategory {
+    /// Validate category update data
+    pub fn validate(&self) -> Result<(), String> {
+        input::check_length("Category name", self.name.as_deref(), input::MAX_NAME_LEN)
+    }
+}
+
+impl CreateTransactionTemplate {
+    /// Validate transaction template creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Template name cannot be empty".to_string());
+        }
+        if self.amount <= 0.0 {
+            return Err("Template amount must be positive".to_string());
+        }
+        if let Some(ref transaction_type) = self.transaction_type {
+            if !["income", "expense", "transfer"].contains(&transaction_type.as_str()) {
+                return Err("Invalid transaction type".to_string());
+            }
+        }
+        input::check_length("Template name", Some(&self.name), input::MAX_NAME_LEN)?;
+        input::check_length("Description", self.description.as_deref(), input::MAX_DESCRIPTION_LEN)?;
+        input::check_length("Merchant", self.merchant.as_deref(), input::MAX_NAME_LEN)
+    }
+}
+
+impl UpdateTransactionTemplate {
+    /// Validate transaction template update data
+    pub fn validate(&self) -> Result<(), String> {
+        input::check_length("Template name", self.name.as_deref(), input::MAX_NAME_LEN)?;
+        input::check_length("Description", self.description.as_deref(), input::MAX_DESCRIPTION_LEN)?;
+        input::check_length("Merchant", self.merchant.as_deref(), input::MAX_NAME_LEN)
+    }
+}
+
+impl CreateRateSource {
+    /// Validate rate source creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Rate source name cannot be empty".to_string());
+        }
+        if ExchangeRateSource::from_str(&self.kind).is_none() {
+            return Err(format!(
+                "Invalid kind '{}' (expected api, bank, manual, scraper or official)",
+                self.kind
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl CreateBudget {
+    /// Validate budget creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.monthly_limit <= 0.0 {
+            return Err("Monthly limit must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl CreateGoal {
+    /// Validate goal creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Goal name cannot be empty".to_string());
+        }
+        input::check_length("Goal name", Some(&self.name), input::MAX_NAME_LEN)?;
+        if self.target_amount <= 0.0 {
+            return Err("Target amount must be positive".to_string());
+        }
+        if let Some(currency) = &self.currency {
+            if currency.len() != 3 {
+                return Err("Currency must be a 3-letter ISO code".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl UpdateGoal {
+    /// Validate goal update data
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.name {
+            if name.trim().is_empty() {
+                return Err("Goal name cannot be empty".to_string());
+            }
+            input::check_length("Goal name", Some(name), input::MAX_NAME_LEN)?;
+        }
+        if self.target_amount.is_some_and(|amount| amount <= 0.0) {
+            return Err("Target amount must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl CreateLoan {
+    /// Validate loan terms
+    pub fn validate(&self) -> Result<(), String> {
+        if self.principal <= 0.0 {
+            return Err("Principal must be positive".to_string());
+        }
+        if !(0.0..=100.0).contains(&self.annual_rate) {
+            return Err("Interest rate must be between 0 and 100 percent".to_string());
+        }
+        if !(1..=600).contains(&self.term_months) {
+            return Err("Term must be between 1 and 600 months".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl CreateHolding {
+    /// Validate holding data
+    pub fn validate(&self) -> Result<(), String> {
+        let ticker = self.ticker.trim();
+        if ticker.is_empty() {
+            return Err("Ticker cannot be empty".to_string());
+        }
+        if !ticker.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '^')) {
+            return Err("Ticker may only contain letters, digits, '.', '-' and '^'".to_string());
+        }
+        if self.quantity <= 0.0 {
+            return Err("Quantity must be positive".to_string());
+        }
+        if self.cost_basis < 0.0 {
+            return Err("Cost basis cannot be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl UpdateHolding {
+    /// Validate holding update data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.quantity.is_some_and(|q| q <= 0.0) {
+            return Err("Quantity must be positive".to_string());
+        }
+        if self.cost_basis.is_some_and(|c| c < 0.0) {
+            return Err("Cost basis cannot be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl UpdateBudget {
+    /// Validate budget update data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.monthly_limit <= 0.0 {
+            return Err("Monthly limit must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl CreateCategoryRule {
+    /// Validate category rule creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.pattern.trim().is_empty() {
+            return Err("Pattern cannot be empty".to_string());
+        }
+        input::check_length("Pattern", Some(&self.pattern), input::MAX_NAME_LEN)
+    }
+}
+
+impl CreateImportProfile {
+    /// Validate import profile data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Profile name cannot be empty".to_string());
+        }
+        input::check_length("Profile name", Some(&self.name), input::MAX_NAME_LEN)?;
+        if let Some(sign) = self.mapping.amount_sign.as_deref() {
+            AmountSign::from_str(sign).ok_or_else(|| {
+                format!("Unknown amount_sign '{}' (expected negative_expenses or positive_expenses)", sign)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl RecategorizeRequest {
+    /// Validate bulk recategorization data
+    pub fn validate(&self) -> Result<(), String> {
+        let has_pattern = self.pattern.as_deref().is_some_and(|p| !p.trim().is_empty());
+        if !has_pattern && self.from_category_id.is_none() {
+            return Err("Provide a pattern or a from_category_id".to_string());
+        }
+        if self.from_category_id == Some(self.to_category_id) {
+            return Err("from_category_id and to_category_id must differ".to_string());
+        }
+        if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
+            if start > end {
+                return Err("start_date must be before end_date".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CreateWebhook {
+    /// Validate webhook creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.url.starts_with("http://") && !self.url.starts_with("https://") {
+            return Err("Webhook URL must start with http:// or https://".to_string());
+        }
         Ok(())
     }
 }
 
+impl CreateRecurringTransaction {
+    /// Validate recurring transaction creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref classification) = self.classification {
+            if RecurringClassification::from_str(classification).is_none() {
+                return Err("Classification must be 'fixed' or 'variable'".to_string());
+            }
+        }
+        if let Some(ref catch_up) = self.catch_up {
+            if CatchUpPolicy::from_str(catch_up).is_none() {
+                return Err("Catch-up policy must be 'all' or 'latest'".to_string());
+            }
+        }
+        if let Some(ref currency) = self.currency {
+            if !currency.is_empty() && currency.len() != 3 {
+                return Err("Currency must be a 3-letter code".to_string());
+            }
+        }
+        input::check_length("Description", self.description.as_deref(), input::MAX_DESCRIPTION_LEN)
+    }
+}
+
+impl UpdateRecurringTransaction {
+    /// Validate recurring transaction update data
+    pub fn validate(&self) -> Result<(), String> {
+        input::check_length("Description", self.description.as_deref(), input::MAX_DESCRIPTION_LEN)
+    }
+}
+
+impl UpdateTransactionCategories {
+    /// Validate the new splits against the transaction amount
+    pub fn validate(&self, transaction_amount: f64) -> Result<(), String> {
+        if self.categories.iter().any(|c| c.amount <= 0.0) {
+            return Err("Category amounts must be positive".to_string());
+        }
+        let mut category_ids: Vec<i64> = self.categories.iter().map(|c| c.category_id).collect();
+        category_ids.sort_unstable();
+        category_ids.dedup();
+        if category_ids.len() != self.categories.len() {
+            return Err("Each category can only appear once".to_string());
+        }
+
+        if !self.categories.is_empty() {
+            let categories_sum: f64 = self.categories.iter().map(|c| c.amount).sum();
+            if (transaction_amount.abs() - categories_sum).abs() > 0.01 {
+                return Err(format!(
+                    "Category amounts ({}) must sum to transaction amount ({})",
+                    categories_sum,
+                    transaction_amount.abs()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CreateTransfer {
+    /// Validate transfer creation data
+    pub fn validate(&self) -> Result<(), String> {
+        if self.amount <= 0.0 {
+            return Err("Amount must be positive".to_string());
+        }
+        if self.from_account_id == self.to_account_id {
+            return Err("Source and destination must be different accounts".to_string());
+        }
+        if self.fee.unwrap_or(0.0) < 0.0 {
+            return Err("Fee must not be negative".to_string());
+        }
+        input::check_length("Description", self.description.as_deref(), input::MAX_DESCRIPTION_LEN)
+    }
+}
+
+/// Length limits on the free-text fields of a transaction
+fn check_transaction_text(
+    description: Option<&str>,
+    notes: Option<&str>,
+    reference: Option<&str>,
+    merchant: Option<&str>,
+    merchant_city: Option<&str>,
+) -> Result<(), String> {
+    input::check_length("Description", description, input::MAX_DESCRIPTION_LEN)?;
+    input::check_length("Notes", notes, input::MAX_NOTES_LEN)?;
+    input::check_length("Reference", reference, input::MAX_NAME_LEN)?;
+    input::check_length("Merchant", merchant, input::MAX_NAME_LEN)?;
+    input::check_length("Merchant city", merchant_city, input::MAX_NAME_LEN)
+}
+
+impl UpdateTransaction {
+    /// True when the request would change nothing
+    pub fn is_empty(&self) -> bool {
+        self.amount.is_none()
+            && self.transaction_type.is_none()
+            && self.description.is_none()
+            && self.transaction_date.is_none()
+            && self.notes.is_none()
+            && self.reference.is_none()
+            && self.merchant.is_none()
+            && self.merchant_city.is_none()
+            && self.merchant_country.is_none()
+            && self.original_amount.is_none()
+            && self.original_currency.is_none()
+            && self.exchange_rate.is_none()
+            && self.fee.is_none()
+            && self.fee_category_id.is_none()
+            && self.exclude_from_reports.is_none()
+            && self.categories.is_none()
+    }
+
+    /// Validate transaction update data
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref transaction_type) = self.transaction_type {
+            if !["income", "expense", "transfer"].contains(&transaction_type.as_str()) {
+                return Err("Invalid transaction type".to_string());
+            }
+        }
+        check_transaction_text(
+            self.description.as_deref(),
+            self.notes.as_deref(),
+            self.reference.as_deref(),
+            self.merchant.as_deref(),
+            self.merchant_city.as_deref(),
+        )
+    }
+}
+
 impl CreateTransaction {
     /// Validate transaction creation data
     pub fn validate(&self) -> Result<(), String> {
@@ -675,7 +2566,52 @@ impl CreateTransaction {
             }
         }
 
-        Ok(())
+        // Original amount and currency only make sense together
+        match (self.original_amount, &self.original_currency) {
+            (Some(original), Some(currency)) => {
+                if original == 0.0 {
+                    return Err("Original amount must not be zero".to_string());
+                }
+                if currency.len() != 3 {
+                    return Err("Original currency must be a 3-letter code".to_string());
+                }
+            }
+            (None, None) => {}
+            _ => {
+                return Err(
+                    "original_amount and original_currency must be provided together".to_string(),
+                )
+            }
+        }
+        if let Some(rate) = self.exchange_rate {
+            if rate <= 0.0 {
+                return Err("Exchange rate must be positive".to_string());
+            }
+        }
+
+        if self.fee.unwrap_or(0.0) < 0.0 {
+            return Err("Fee must not be negative".to_string());
+        }
+        if let Some(status) = &self.status {
+            crate::transaction_status::validate_status(status)?;
+        }
+
+        check_transaction_text(
+            self.description.as_deref(),
+            self.notes.as_deref(),
+            self.reference.as_deref(),
+            self.merchant.as_deref(),
+            self.merchant_city.as_deref(),
+        )
+    }
+
+    /// Rate applied to convert the original amount into the posted amount
+    pub fn applied_rate(&self) -> Option<f64> {
+        match (self.exchange_rate, self.original_amount) {
+            (Some(rate), _) => Some(rate),
+            (None, Some(original)) if original != 0.0 => Some(self.amount.abs() / original.abs()),
+            _ => None,
+        }
     }
 }
 
@@ -723,4 +2659,32 @@ mod tests {
         );
         assert_eq!(TransactionType::from_str("invalid"), None);
     }
+
+    #[test]
+    fn test_original_currency_validation() {
+        let mut txn = CreateTransaction {
+            account_id: 1,
+            amount: 74.0,
+            transaction_type: "expense".to_string(),
+            description: None,
+            transaction_date: None,
+            categories: Vec::new(),
+            notes: None,
+            reference: None,
+            merchant: None,
+            merchant_city: None,
+            merchant_country: None,
+            original_amount: Some(50.0),
+            original_currency: Some("EUR".to_string()),
+            exchange_rate: None,
+            fee: None,
+            fee_category_id: None,
+            status: None,
+        };
+        assert!(txn.validate().is_ok());
+        assert_eq!(txn.applied_rate(), Some(1.48));
+
+        txn.original_currency = None;
+        assert!(txn.validate().is_err());
+    }
 }
@@ -0,0 +1,243 @@
+// net_worth.rs
+// Balances grouped by account type or by user-defined account group, split
+// into assets and liabilities
+
+use chrono::NaiveDate;
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+
+use crate::models::{
+    AccountGroup, AccountGroupBalance, AccountType, AccountTypeBalance, NetWorth, NetWorthSummary,
+};
+
+/// "asset" or "liability" for an account_type column value.
+pub fn classification(account_type: &str) -> &'static str {
+    match AccountType::from_str(account_type) {
+        Some(t) if t.is_liability() => "liability",
+        _ => "asset",
+    }
+}
+
+/// Balances of a user's accounts per account group, account type and
+/// currency: current balances, or as of the end of `as_of`. A past balance
+/// is the initial balance plus the transactions from the opening date up to
+/// that day (fees debited as usual), and accounts opened later are left out.
+async fn group_type_balances(
+    pool: &SqlitePool,
+    user_id: i64,
+    as_of: Option<NaiveDate>,
+) -> Result<Vec<(Option<i64>, AccountTypeBalance)>, sqlx::Error> {
+    let rows = match as_of {
+        None => {
+            sqlx::query(
+                "SELECT account_group_id, account_type, currency, SUM(current_balance) as total_balance, COUNT(*) as account_count
+                 FROM accounts
+                 WHERE user_id = ?
+                 GROUP BY account_group_id, account_type, currency
+                 ORDER BY account_type, currency",
+            )
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?
+        }
+        Some(as_of) => {
+            sqlx::query(
+                "SELECT a.account_group_id, a.account_type, a.currency, COUNT(*) as account_count,
+                        SUM(a.initial_balance + COALESCE((
+                            SELECT SUM(CASE WHEN t.transaction_type = 'income' THEN t.amount ELSE -ABS(t.amount) END - t.fee)
+                            FROM transactions t
+                            WHERE t.account_id = a.id AND t.status = 'cleared'
+                            AND (a.opened_on IS NULL OR date(t.transaction_date) >= a.opened_on)
+                            AND date(t.transaction_date) <= ?
+                        ), 0)) as total_balance
+                 FROM accounts a
+                 WHERE a.user_id = ? AND (a.opened_on IS NULL OR a.opened_on <= ?)
+                 GROUP BY a.account_group_id, a.account_type, a.currency
+                 ORDER BY a.account_type, a.currency",
+            )
+            .bind(as_of)
+            .bind(user_id)
+            .bind(as_of)
+            .fetch_all(pool)
+            .await?
+        }
+    };
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let account_type: String = row.get("account_type");
+            let balance = AccountTypeBalance {
+                classification: classification(&account_type).to_string(),
+                account_type,
+                currency: row.get("currency"),
+                total_balance: row.get("total_balance"),
+                account_count: row.get("account_count"),
+            };
+            (row.get("account_group_id"), balance)
+        })
+        .collect())
+}
+
+/// Balances of a user's accounts, grouped by account type and currency,
+/// now or as of the end of `as_of` (see group_type_balances)
+pub async fn account_type_balances(
+    pool: &SqlitePool,
+    user_id: i64,
+    as_of: Option<NaiveDate>,
+) -> Result<Vec<AccountTypeBalance>, sqlx::Error> {
+    let mut merged: BTreeMap<(String, String), AccountTypeBalance> = BTreeMap::new();
+    for (_, balance) in group_type_balances(pool, user_id, as_of).await? {
+        let key = (balance.account_type.clone(), balance.currency.clone());
+        match merged.get_mut(&key) {
+            Some(total) => {
+                total.total_balance += balance.total_balance;
+                total.account_count += balance.account_count;
+            }
+            None => {
+                merged.insert(key, balance);
+            }
+        }
+    }
+    Ok(merged.into_values().collect())
+}
+
+/// Net worth of each of the user's account groups, by name, then of the
+/// accounts in no group. Groups without accounts (at `as_of`) are listed
+/// with no totals.
+pub async fn group_balances(
+    pool: &SqlitePool,
+    user_id: i64,
+    as_of: Option<NaiveDate>,
+) -> Result<Vec<AccountGroupBalance>, sqlx::Error> {
+    let groups = sqlx::query_as::<_, AccountGroup>("SELECT * FROM account_groups WHERE user_id = ? ORDER BY name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    let balances = group_type_balances(pool, user_id, as_of).await?;
+
+    let group_balance = |group_id: Option<i64>, group_name: String| {
+        let members: Vec<AccountTypeBalance> = balances
+            .iter()
+            .filter(|(id, _)| *id == group_id)
+            .map(|(_, balance)| balance.clone())
+            .collect();
+        AccountGroupBalance {
+            group_id,
+            group_name,
+            account_count: members.iter().map(|b| b.account_count).sum(),
+            totals: summarize(&members),
+        }
+    };
+
+    let mut result: Vec<AccountGroupBalance> =
+        groups.into_iter().map(|g| group_balance(Some(g.id), g.name)).collect();
+    let ungrouped = group_balance(None, "Ungrouped".to_string());
+    if ungrouped.account_count > 0 {
+        result.push(ungrouped);
+    }
+    Ok(result)
+}
+
+pub async fn net_worth(
+    pool: &SqlitePool,
+    user_id: i64,
+    as_of: Option<NaiveDate>,
+) -> Result<NetWorth, sqlx::Error> {
+    let by_account_type = account_type_balances(pool, user_id, as_of).await?;
+    Ok(NetWorth {
+        totals: summarize(&by_account_type),
+        by_account_type,
+        by_group: group_balances(pool, user_id, as_of).await?,
+    })
+}
+
+/// Assets minus liabilities per currency. Card balances are stored negative
+/// while money is owed, so the amount owed is the negated balance; this keeps
+/// debt on the liabilities side instead of netting it against the assets.
+pub fn summarize(balances: &[AccountTypeBalance]) -> Vec<NetWorthSummary> {
+    let mut totals: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    for b in balances {
+        let entry = totals.entry(b.currency.as_str()).or_insert((0.0, 0.0));
+        if b.classification == "liability" {
+            entry.1 -= b.total_balance;
+        } else {
+            entry.0 += b.total_balance;
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(currency, (assets, liabilities))| NetWorthSummary {
+            currency: currency.to_string(),
+            assets,
+            liabilities,
+            net_worth: assets - liabilities,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::{TimeZone, Utc};
+
+    fn balance(account_type: &str, currency: &str, total_balance: f64) -> AccountTypeBalance {
+        AccountTypeBalance {
+            account_type: account_type.to_string(),
+            currency: currency.to_string(),
+            classification: classification(account_type).to_string(),
+            total_balance,
+            account_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_summarize_subtracts_card_debt() {
+        let totals = summarize(&[
+            balance("checking", "CAD", 5000.0),
+            balance("investment", "CAD", 2000.0),
+            balance("credit_card", "CAD", -850.0),
+            balance("cash", "EUR", 100.0),
+        ]);
+
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].currency, "CAD");
+        assert_eq!(totals[0].assets, 7000.0);
+        assert_eq!(totals[0].liabilities, 850.0);
+        assert_eq!(totals[0].net_worth, 6150.0);
+        assert_eq!(totals[1].net_worth, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_balances_as_of_start_at_opening_date() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let checking = testing::account(user.id)
+            .balance(100.0)
+            .opened_on(date(1, 10))
+            .insert(&pool)
+            .await;
+        testing::account(user.id)
+            .name("Savings")
+            .balance(500.0)
+            .opened_on(date(3, 1))
+            .insert(&pool)
+            .await;
+        let at = |m, d| Utc.with_ymd_and_hms(2025, m, d, 12, 0, 0).unwrap();
+        testing::txn(checking.id).amount(30.0).date(at(1, 15)).insert(&pool).await;
+        testing::txn(checking.id).amount(50.0).income().date(at(2, 1)).insert(&pool).await;
+
+        let before = net_worth(&pool, user.id, Some(date(1, 9))).await.unwrap();
+        assert!(before.totals.is_empty());
+
+        let january = account_type_balances(&pool, user.id, Some(date(1, 31))).await.unwrap();
+        assert_eq!(january.len(), 1);
+        assert_eq!((january[0].total_balance, january[0].account_count), (70.0, 1));
+
+        let march = net_worth(&pool, user.id, Some(date(3, 1))).await.unwrap();
+        assert_eq!(march.totals[0].net_worth, 620.0);
+    }
+}
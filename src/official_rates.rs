@@ -0,0 +1,136 @@
+// official_rates.rs
+// Central-bank reference rates from the Bank of Canada Valet API
+// Website: https://www.bankofcanada.ca/valet/observations/group/FX_RATES_DAILY/json
+//
+// The daily group quotes each currency against CAD (series FXUSDCAD = CAD per
+// 1 USD). Both directions are stored with source 'official' and dated the
+// observation day, so they can be preferred for tax-related conversions.
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use reqwest::Client;
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::exchange_scraper::ExchangeRate;
+
+pub const PROVIDER: &str = "bankofcanada.ca";
+const VALET_URL: &str = "https://www.bankofcanada.ca/valet/observations/group/FX_RATES_DAILY/json";
+
+/// Rates of the latest observation day in a Valet FX_RATES_DAILY response,
+/// as XXX->CAD and CAD->XXX pairs.
+pub fn parse_valet(body: &str) -> Result<Vec<ExchangeRate>, String> {
+    let json: Value = serde_json::from_str(body).map_err(|e| format!("Invalid Valet response: {}", e))?;
+    let observation = json["observations"]
+        .as_array()
+        .and_then(|obs| obs.iter().max_by_key(|o| o["d"].as_str().unwrap_or("").to_string()))
+        .ok_or("Valet response has no observations")?;
+    let rate_date = observation["d"]
+        .as_str()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .ok_or("Valet observation has no valid date")?;
+
+    let mut rates = Vec::new();
+    for (series, value) in observation.as_object().into_iter().flatten() {
+        let Some(currency) = series.strip_prefix("FX").and_then(|s| s.strip_suffix("CAD")) else {
+            continue;
+        };
+        let Some(rate) = value["v"].as_str().and_then(|v| v.parse::<f64>().ok()).filter(|r| *r > 0.0) else {
+            continue;
+        };
+        rates.push(ExchangeRate {
+            from_currency: currency.to_string(),
+            to_currency: "CAD".to_string(),
+            rate,
+            rate_date,
+        });
+        rates.push(ExchangeRate {
+            from_currency: "CAD".to_string(),
+            to_currency: currency.to_string(),
+            rate: 1.0 / rate,
+            rate_date,
+        });
+    }
+
+    if rates.is_empty() {
+        return Err("Valet response has no FX series".to_string());
+    }
+    Ok(rates)
+}
+
+/// Latest official rates from the Bank of Canada
+pub async fn fetch_latest() -> Result<Vec<ExchangeRate>, Box<dyn std::error::Error>> {
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+    let body = client
+        .get(VALET_URL)
+        .query(&[("recent", "1")])
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(parse_valet(&body)?)
+}
+
+/// Store rates as 'official', dated midnight UTC of the observation day.
+/// Rates already stored for that day are skipped; returns the number saved.
+pub async fn save_official_rates(pool: &SqlitePool, rates: &[ExchangeRate]) -> Result<usize, sqlx::Error> {
+    let mut saved_count = 0;
+
+    for rate in rates {
+        let rate_date = Utc.from_utc_datetime(&rate.rate_date.and_hms_opt(0, 0, 0).unwrap());
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO exchange_rates (from_currency, to_currency, rate, rate_date, source, rate_source_id)
+             VALUES (?, ?, ?, ?, 'official', (SELECT id FROM rate_sources WHERE name = ?))",
+        )
+        .bind(&rate.from_currency)
+        .bind(&rate.to_currency)
+        .bind(rate.rate)
+        .bind(rate_date)
+        .bind(PROVIDER)
+        .execute(pool)
+        .await?;
+
+        saved_count += result.rows_affected() as usize;
+    }
+
+    Ok(saved_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fx;
+    use crate::testing;
+
+    const SAMPLE: &str = r#"{
+        "seriesDetail": {"FXUSDCAD": {"label": "USD/CAD"}, "FXEURCAD": {"label": "EUR/CAD"}},
+        "observations": [
+            {"d": "2025-06-09", "FXUSDCAD": {"v": "1.3700"}, "FXEURCAD": {"v": "1.5600"}},
+            {"d": "2025-06-10", "FXUSDCAD": {"v": "1.3650"}, "FXEURCAD": {"v": "1.5625"}, "OTHER": {"v": "1"}}
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn test_valet_rates_are_saved_as_official_and_preferred_for_tax() {
+        let rates = parse_valet(SAMPLE).unwrap();
+        assert_eq!(rates.len(), 4);
+        assert!(rates.iter().all(|r| r.rate_date == NaiveDate::from_ymd_opt(2025, 6, 10).unwrap()));
+        let usd = rates.iter().find(|r| r.from_currency == "USD").unwrap();
+        assert_eq!((usd.to_currency.as_str(), usd.rate), ("CAD", 1.365));
+        assert!(parse_valet(r#"{"observations": []}"#).is_err());
+
+        let pool = testing::pool().await;
+        assert_eq!(save_official_rates(&pool, &rates).await.unwrap(), 4);
+        assert_eq!(save_official_rates(&pool, &rates).await.unwrap(), 0);
+
+        // Same day as the official quote: the tax priority picks it over a manual rate
+        let noon = Utc.with_ymd_and_hms(2025, 6, 10, 12, 0, 0).unwrap();
+        testing::rate(&pool, "USD", "CAD", 1.40, noon).await;
+        let tax = fx::find_rate_as_of(&pool, "USD", "CAD", None, &fx::TAX_DEFAULT_PRIORITY).await.unwrap().unwrap();
+        assert_eq!((tax.source.as_str(), tax.rate), ("official", 1.365));
+        let default = fx::find_rate_as_of(&pool, "USD", "CAD", None, &fx::DEFAULT_PRIORITY).await.unwrap().unwrap();
+        assert_eq!(default.source, "manual");
+    }
+}
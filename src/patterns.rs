@@ -0,0 +1,114 @@
+// patterns.rs
+// When money gets spent: expense totals by weekday and hour of day
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::exclusions;
+use crate::models::{PatternBucket, SpendingPatternCell, SpendingPatterns};
+
+pub const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Expense totals per (weekday, hour) cell, shifted by `utc_offset_minutes`
+/// so "Friday night" means the user's Friday night rather than UTC's.
+pub async fn spending_patterns(
+    pool: &SqlitePool,
+    user_id: Option<i64>,
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+    utc_offset_minutes: i64,
+) -> Result<SpendingPatterns, sqlx::Error> {
+    let shift = format!("{:+} minutes", utc_offset_minutes);
+
+    let sql = format!(
+        "SELECT CAST(strftime('%w', t.transaction_date, ?) AS INTEGER) as weekday,
+                CAST(strftime('%H', t.transaction_date, ?) AS INTEGER) as hour,
+                SUM(ABS(t.amount)) as total_amount,
+                COUNT(*) as transaction_count
+         FROM transactions t
+         INNER JOIN accounts a ON t.account_id = a.id
+         WHERE t.transaction_type = 'expense' AND {}
+         AND (? IS NULL OR a.user_id = ?)
+         AND (? IS NULL OR t.transaction_date >= ?)
+         AND (? IS NULL OR t.transaction_date <= ?)
+         GROUP BY weekday, hour
+         ORDER BY weekday, hour",
+        exclusions::COUNTED
+    );
+    let cells = sqlx::query_as::<_, SpendingPatternCell>(&sql)
+        .bind(&shift)
+        .bind(&shift)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(start_date)
+        .bind(start_date)
+        .bind(end_date)
+        .bind(end_date)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(summarize(cells))
+}
+
+/// Roll cells up into full weekday (0 = Sunday) and hour-of-day series,
+/// including empty buckets so clients can draw a complete grid.
+pub fn summarize(cells: Vec<SpendingPatternCell>) -> SpendingPatterns {
+    let mut by_weekday: Vec<PatternBucket> = WEEKDAYS
+        .iter()
+        .enumerate()
+        .map(|(i, label)| PatternBucket::empty(i as i64, label.to_string()))
+        .collect();
+    let mut by_hour: Vec<PatternBucket> = (0..24)
+        .map(|h| PatternBucket::empty(h, format!("{:02}:00", h)))
+        .collect();
+
+    for cell in &cells {
+        if let Some(bucket) = by_weekday.get_mut(cell.weekday as usize) {
+            bucket.total_amount += cell.total_amount;
+            bucket.transaction_count += cell.transaction_count;
+        }
+        if let Some(bucket) = by_hour.get_mut(cell.hour as usize) {
+            bucket.total_amount += cell.total_amount;
+            bucket.transaction_count += cell.transaction_count;
+        }
+    }
+
+    let peak = cells
+        .iter()
+        .max_by(|a, b| a.total_amount.total_cmp(&b.total_amount))
+        .cloned();
+
+    SpendingPatterns {
+        by_weekday,
+        by_hour,
+        cells,
+        peak,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(weekday: i64, hour: i64, total_amount: f64) -> SpendingPatternCell {
+        SpendingPatternCell {
+            weekday,
+            hour,
+            total_amount,
+            transaction_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_summarize_fills_weekdays_and_hours() {
+        let patterns = summarize(vec![cell(5, 21, 80.0), cell(5, 22, 40.0), cell(1, 9, 10.0)]);
+
+        assert_eq!(patterns.by_weekday.len(), 7);
+        assert_eq!(patterns.by_hour.len(), 24);
+        assert_eq!(patterns.by_weekday[5].label, "Fri");
+        assert_eq!(patterns.by_weekday[5].total_amount, 120.0);
+        assert_eq!(patterns.by_weekday[5].transaction_count, 2);
+        assert_eq!(patterns.by_hour[9].total_amount, 10.0);
+        assert_eq!(patterns.peak.map(|p| (p.weekday, p.hour)), Some((5, 21)));
+    }
+}
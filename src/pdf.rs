@@ -0,0 +1,188 @@
+// pdf.rs
+// Just enough PDF to print reports: Letter-size pages of text in the
+// standard Helvetica and Courier fonts, plus ruled lines and shaded bands.
+// The standard fonts need no embedding, so a file is a handful of objects
+// and one uncompressed content stream per page.
+
+pub const PAGE_WIDTH: f64 = 612.0;
+pub const PAGE_HEIGHT: f64 = 792.0;
+
+/// Characters of Courier take 600/1000 of the font size
+const MONO_ADVANCE: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+}
+
+/// Text as a PDF string literal in WinAnsiEncoding; characters it lacks print as '?'
+fn literal(text: &str) -> String {
+    let mut out = String::from("(");
+    for c in text.chars() {
+        let byte = match c {
+            '€' => 0x80,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            c if (c as u32) < 0x100 => c as u32 as u8,
+            _ => b'?',
+        };
+        match byte {
+            b'(' | b')' | b'\\' => {
+                out.push('\\');
+                out.push(byte as char);
+            }
+            0x20..=0x7e => out.push(byte as char),
+            _ => out.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// A document being drawn page by page; coordinates are points from the
+/// bottom-left corner
+#[derive(Debug, Default)]
+pub struct Document {
+    pages: Vec<String>,
+}
+
+impl Document {
+    pub fn new() -> Document {
+        Document::default()
+    }
+
+    /// Start a new page; everything drawn goes on the latest one
+    pub fn new_page(&mut self) {
+        self.pages.push(String::new());
+    }
+
+    fn page(&mut self) -> &mut String {
+        if self.pages.is_empty() {
+            self.new_page();
+        }
+        self.pages.last_mut().expect("a page was just added")
+    }
+
+    pub fn text(&mut self, x: f64, y: f64, font: Font, size: f64, text: &str) {
+        let op = format!("BT /{} {} Tf {:.2} {:.2} Td {} Tj ET\n", font.resource(), size, x, y, literal(text));
+        self.page().push_str(&op);
+    }
+
+    /// Courier text ending at `right`, for columns of figures
+    pub fn text_right(&mut self, right: f64, y: f64, size: f64, text: &str) {
+        let width = text.chars().count() as f64 * size * MONO_ADVANCE;
+        self.text(right - width, y, Font::Mono, size, text);
+    }
+
+    /// Horizontal rule from `x1` to `x2`
+    pub fn rule(&mut self, x1: f64, x2: f64, y: f64) {
+        let op = format!("0.5 w {:.2} {:.2} m {:.2} {:.2} l S\n", x1, y, x2, y);
+        self.page().push_str(&op);
+    }
+
+    /// Light gray band behind a heading or table header
+    pub fn band(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let op = format!("0.92 g {:.2} {:.2} {:.2} {:.2} re f 0 g\n", x, y, width, height);
+        self.page().push_str(&op);
+    }
+
+    /// The finished file
+    pub fn finish(mut self) -> Vec<u8> {
+        if self.pages.is_empty() {
+            self.new_page();
+        }
+        // 1 catalog, 2 page tree, 3-5 fonts, then a page and its contents per page
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|i| 6 + 2 * i).collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+                page_ids.len()
+            ),
+        ];
+        for font in ["Helvetica", "Helvetica-Bold", "Courier"] {
+            objects.push(format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                font
+            ));
+        }
+        for (page_id, contents) in page_ids.iter().zip(&self.pages) {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id + 1
+            ));
+            objects.push(format!("<< /Length {} >>\nstream\n{}endstream", contents.len(), contents));
+        }
+
+        let mut out: Vec<u8> = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            table.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        table.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_objects_are_where_the_xref_says() {
+        let mut doc = Document::new();
+        doc.text(50.0, 700.0, Font::Bold, 18.0, "Statement (May)");
+        doc.new_page();
+        doc.text_right(562.0, 700.0, 10.0, "1234.50");
+        doc.rule(50.0, 562.0, 690.0);
+        let file = doc.finish();
+        let text = String::from_utf8_lossy(&file);
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("(Statement \\(May\\)) Tj"));
+        // 7 characters of 10pt Courier end at the right edge
+        assert!(text.contains("/F3 10 Tf 520.00 700.00 Td (1234.50) Tj"));
+        assert_eq!(literal("Café €5"), "(Caf\\351 \\2005)");
+
+        let startxref: usize = text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        let entries: Vec<usize> = std::str::from_utf8(&file[startxref..])
+            .unwrap()
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert_eq!(entries.len(), 9);
+        for (i, offset) in entries.iter().enumerate() {
+            assert!(file[*offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+}
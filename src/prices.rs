@@ -0,0 +1,183 @@
+// prices.rs
+// Security quotes for the holdings: `update_prices`, and in `serve` the
+// PRICE_CRON schedule (a cron expression in UTC, like SCRAPE_CRON), fetch
+// the latest close of every held ticker from stooq.com's CSV quotes in one
+// request and store them in security_prices.
+
+use chrono::NaiveDate;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use sqlx::SqlitePool;
+use std::env;
+
+use crate::holdings;
+use crate::scrape_schedule::CronSchedule;
+
+pub const STOOQ: &str = "stooq.com";
+const STOOQ_URL: &str = "https://stooq.com/q/l/";
+
+/// The latest close of a ticker
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub ticker: String,
+    pub price: f64,
+    pub price_date: NaiveDate,
+}
+
+/// A source of security quotes
+pub trait PriceProvider: Send + Sync {
+    /// Source name quotes are stored with
+    fn name(&self) -> &'static str;
+
+    /// Quotes of whichever of `tickers` the provider knows
+    fn fetch_quotes<'a>(&'a self, tickers: &'a [String]) -> BoxFuture<'a, Result<Vec<Quote>, String>>;
+}
+
+/// Daily closes through stooq.com; tickers without an exchange suffix are
+/// taken to be US listings ("AAPL" is "aapl.us" there)
+pub struct Stooq {
+    client: Client,
+}
+
+impl Stooq {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+}
+
+/// Symbol stooq.com quotes a ticker under
+pub fn stooq_symbol(ticker: &str) -> String {
+    let ticker = ticker.trim().to_lowercase();
+    if ticker.contains('.') || ticker.starts_with('^') {
+        ticker
+    } else {
+        format!("{}.us", ticker)
+    }
+}
+
+/// Quotes in a stooq.com CSV response (Symbol,Date,Time,Open,High,Low,Close,Volume),
+/// under the tickers they were asked for; symbols it doesn't know ("N/D") are left out
+pub fn parse_stooq(body: &str, tickers: &[String]) -> Result<Vec<Quote>, String> {
+    let mut lines = body.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = lines
+        .next()
+        .ok_or("Empty stooq.com response")?
+        .split(',')
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(symbol), Some(date), Some(close)) = (column("symbol"), column("date"), column("close")) else {
+        return Err("stooq.com response has no Symbol, Date and Close columns".to_string());
+    };
+
+    let mut quotes = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let Some(ticker) = fields
+            .get(symbol)
+            .and_then(|s| tickers.iter().find(|t| stooq_symbol(t) == s.to_lowercase()))
+        else {
+            continue;
+        };
+        let price = fields.get(close).and_then(|c| c.parse::<f64>().ok()).filter(|p| *p > 0.0);
+        let price_date = fields.get(date).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        if let (Some(price), Some(price_date)) = (price, price_date) {
+            quotes.push(Quote { ticker: holdings::normalize_ticker(ticker), price, price_date });
+        }
+    }
+    Ok(quotes)
+}
+
+impl PriceProvider for Stooq {
+    fn name(&self) -> &'static str {
+        STOOQ
+    }
+
+    fn fetch_quotes<'a>(&'a self, tickers: &'a [String]) -> BoxFuture<'a, Result<Vec<Quote>, String>> {
+        Box::pin(async move {
+            let symbols: Vec<String> = tickers.iter().map(|t| stooq_symbol(t)).collect();
+            let response = self
+                .client
+                .get(STOOQ_URL)
+                .query(&[("s", symbols.join(" ").as_str()), ("f", "sd2t2ohlcv"), ("h", ""), ("e", "csv")])
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("HTTP Error: {}", response.status()));
+            }
+            let body = response.text().await.map_err(|e| e.to_string())?;
+            parse_stooq(&body, tickers)
+        })
+    }
+}
+
+/// What one price update did
+#[derive(Debug, Default)]
+pub struct PriceUpdate {
+    pub saved: usize,
+    pub errors: Vec<String>, // Tickers without a quote, or why the fetch failed
+}
+
+/// Fetch and store a quote for every held ticker
+pub async fn update_prices(pool: &SqlitePool, provider: &dyn PriceProvider) -> Result<PriceUpdate, sqlx::Error> {
+    let tickers = holdings::held_tickers(pool).await?;
+    let mut update = PriceUpdate::default();
+    if tickers.is_empty() {
+        return Ok(update);
+    }
+    let quotes = match provider.fetch_quotes(&tickers).await {
+        Ok(quotes) => quotes,
+        Err(e) => {
+            update.errors.push(format!("{}: {}", provider.name(), e));
+            return Ok(update);
+        }
+    };
+    for ticker in &tickers {
+        match quotes.iter().find(|q| &q.ticker == ticker) {
+            Some(quote) => {
+                holdings::save_price(pool, ticker, quote.price, quote.price_date, provider.name()).await?;
+                update.saved += 1;
+            }
+            None => update.errors.push(format!("{}: no quote", ticker)),
+        }
+    }
+    Ok(update)
+}
+
+/// The PRICE_CRON schedule; None (scheduling disabled) when unset
+pub fn schedule_from_env() -> Result<Option<CronSchedule>, String> {
+    match env::var("PRICE_CRON") {
+        Ok(expr) if !expr.trim().is_empty() => CronSchedule::parse(&expr).map(Some),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stooq_maps_symbols_back_to_tickers() {
+        let body = "Symbol,Date,Time,Open,High,Low,Close,Volume\r\n\
+                    AAPL.US,2025-06-03,22:00:11,201.35,203.77,200.96,203.27,46381567\r\n\
+                    VFV.TO,2025-06-03,21:00:03,140.1,141.02,139.87,140.95,250233\r\n\
+                    NOPE.US,N/D,N/D,N/D,N/D,N/D,N/D,N/D\r\n";
+        let tickers = vec!["AAPL".to_string(), "VFV.TO".to_string(), "NOPE".to_string()];
+        let quotes = parse_stooq(body, &tickers).unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 6, 3).unwrap();
+        assert_eq!(
+            quotes,
+            vec![
+                Quote { ticker: "AAPL".to_string(), price: 203.27, price_date: date },
+                Quote { ticker: "VFV.TO".to_string(), price: 140.95, price_date: date },
+            ]
+        );
+        assert_eq!((stooq_symbol("brk-b"), stooq_symbol("^SPX")), ("brk-b.us".to_string(), "^spx".to_string()));
+        assert!(parse_stooq("<html>", &tickers).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+// profiles.rs
+// Named profiles: separate SQLite databases (e.g. personal, business) selected
+// with --profile instead of editing DATABASE_URL
+
+use sqlx::migrate::{Migrate, MigrateError, Migration, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{Executor, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use std::env;
+use std::str::FromStr;
+use std::time::Instant;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub database_url: String,
+}
+
+/// Parse "name=url,name=url" (PROFILES). Entries without a name or URL are skipped.
+pub fn parse_profiles(spec: &str) -> Vec<Profile> {
+    spec.split(',')
+        .filter_map(|entry| {
+            let (name, url) = entry.split_once('=')?;
+            let (name, url) = (name.trim(), url.trim());
+            (!name.is_empty() && !url.is_empty()).then(|| Profile {
+                name: name.to_string(),
+                database_url: url.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Profiles from the PROFILES environment variable (or .env)
+pub fn configured_profiles() -> Vec<Profile> {
+    env::var("PROFILES")
+        .map(|spec| parse_profiles(&spec))
+        .unwrap_or_default()
+}
+
+/// Remove "--profile NAME" / "--profile=NAME" from the arguments and return NAME.
+pub fn take_profile_arg(args: &mut Vec<String>) -> Option<String> {
+    take_arg(args, "--profile")
+}
+
+/// Remove "FLAG VALUE" / "FLAG=VALUE" from the arguments and return VALUE.
+pub fn take_arg(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    let prefix = format!("{}=", flag);
+    let index = args.iter().position(|a| a == flag || a.starts_with(&prefix))?;
+    let arg = args.remove(index);
+    match arg.strip_prefix(&prefix) {
+        Some(value) => Some(value.to_string()),
+        None if index < args.len() => Some(args.remove(index)),
+        None => None,
+    }
+}
+
+/// Database URL of the named profile
+pub fn database_url(profiles: &[Profile], name: &str) -> Result<String, String> {
+    profiles
+        .iter()
+        .find(|p| p.name == name)
+        .map(|p| p.database_url.clone())
+        .ok_or_else(|| {
+            let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+            if names.is_empty() {
+                format!("Unknown profile '{}': no profiles configured (set PROFILES)", name)
+            } else {
+                format!("Unknown profile '{}', expected one of: {}", name, names.join(", "))
+            }
+        })
+}
+
+/// Read-only connections analytics and exports get when READ_POOL_SIZE is unset
+pub const DEFAULT_READ_POOL_SIZE: u32 = 4;
+
+/// Open the database, creating the file when it doesn't exist yet. It is
+/// switched to WAL journaling so readers never block a write.
+pub async fn connect(database_url: &str) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePool::connect_with(options).await?;
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&pool)
+        .await?;
+    Ok(pool)
+}
+
+/// Size of the read pool (READ_POOL_SIZE); 0 turns it off
+pub fn read_pool_size() -> u32 {
+    env::var("READ_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_READ_POOL_SIZE)
+}
+
+/// A second pool on the same database for long report scans, so they don't
+/// hold up the main pool's connections. Its connections set query_only, so
+/// nothing run on them can write. None for in-memory databases, where every
+/// connection would see a database of its own, and when `size` is 0.
+pub async fn connect_read_pool(database_url: &str, size: u32) -> Result<Option<SqlitePool>, sqlx::Error> {
+    if size == 0 || database_url.contains(":memory:") || database_url.contains("mode=memory") {
+        return Ok(None);
+    }
+    let options = SqliteConnectOptions::from_str(database_url)?.pragma("query_only", "ON");
+    let pool = SqlitePoolOptions::new().max_connections(size).connect_with(options).await?;
+    Ok(Some(pool))
+}
+
+/// Apply pending migrations from migrations/ (embedded at build time) and
+/// return how many were applied. The history is sqlx's _sqlx_migrations, so
+/// databases set up with `sqlx migrate run` only get the newer ones.
+///
+/// sqlx runs every SQLite migration inside a transaction, where foreign keys
+/// cannot be switched off, so a rebuild of a table other tables reference
+/// would cascade its drop into them. Migrations starting with
+/// `-- no-transaction` are therefore run as written, and manage their own
+/// transaction.
+pub async fn migrate(pool: &SqlitePool) -> Result<usize, MigrateError> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    if let Some(version) = conn.dirty_version().await? {
+        return Err(MigrateError::Dirty(version));
+    }
+    let applied: HashMap<i64, Vec<u8>> = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|m| (m.version, m.checksum.into_owned()))
+        .collect();
+
+    let mut count = 0;
+    for migration in MIGRATOR.iter().filter(|m| m.migration_type.is_up_migration()) {
+        match applied.get(&migration.version) {
+            Some(checksum) if checksum.as_slice() != &*migration.checksum => {
+                return Err(MigrateError::VersionMismatch(migration.version))
+            }
+            Some(_) => continue,
+            None if migration.no_tx => apply_without_transaction(&mut conn, migration).await?,
+            None => {
+                conn.apply(migration).await?;
+            }
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+async fn apply_without_transaction(conn: &mut SqliteConnection, migration: &Migration) -> Result<(), MigrateError> {
+    let start = Instant::now();
+    conn.execute(&*migration.sql)
+        .await
+        .map_err(|e| MigrateError::ExecuteMigration(e, migration.version))?;
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+         VALUES (?, ?, TRUE, ?, ?)",
+    )
+    .bind(migration.version)
+    .bind(&*migration.description)
+    .bind(&*migration.checksum)
+    .bind(start.elapsed().as_nanos() as i64)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_from_config_and_args() {
+        let profiles = parse_profiles("personal=sqlite:personal.db, business = sqlite:biz.db,broken");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(database_url(&profiles, "business").unwrap(), "sqlite:biz.db");
+        assert!(database_url(&profiles, "work").is_err());
+
+        let mut args: Vec<String> = ["app", "tui", "--profile", "business"].map(String::from).to_vec();
+        assert_eq!(take_profile_arg(&mut args).as_deref(), Some("business"));
+        assert_eq!(args, ["app", "tui"]);
+
+        let mut args: Vec<String> = ["app", "--profile=personal", "serve"].map(String::from).to_vec();
+        assert_eq!(take_profile_arg(&mut args).as_deref(), Some("personal"));
+        assert_eq!(args, ["app", "serve"]);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_creates_schema_once() {
+        // One connection: every in-memory connection is a database of its own
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        assert!(migrate(&pool).await.unwrap() > 0);
+        assert_eq!(migrate(&pool).await.unwrap(), 0);
+        let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users").fetch_one(&pool).await.unwrap();
+        assert_eq!(users, 0);
+        // The accounts rebuilds must leave references to the new table behind
+        let references: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pragma_foreign_key_list('transactions') WHERE \"table\" = 'accounts'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(references, 1);
+    }
+
+    #[tokio::test]
+    async fn test_read_pool_sees_writes_but_cannot_write() {
+        let path = env::temp_dir().join(format!("pft-read-pool-{}.db", std::process::id()));
+        let url = format!("sqlite:{}", path.display());
+        let pool = connect(&url).await.unwrap();
+        migrate(&pool).await.unwrap();
+        let reads = connect_read_pool(&url, 2).await.unwrap().unwrap();
+        assert!(connect_read_pool("sqlite::memory:", 2).await.unwrap().is_none());
+        assert!(connect_read_pool(&url, 0).await.unwrap().is_none());
+
+        sqlx::query("INSERT INTO users (username, email, password_hash) VALUES ('reader', 'r@example.com', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users").fetch_one(&reads).await.unwrap();
+        assert_eq!(users, 1);
+        assert!(sqlx::query("DELETE FROM users").execute(&reads).await.is_err());
+
+        reads.close().await;
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
@@ -0,0 +1,251 @@
+// qif.rs
+// Quicken Interchange Format export, which GnuCash, Quicken and most desktop
+// finance tools can import: the account list, the category list, then each
+// account's transactions under its own header. QIF has no notion of currency,
+// so every amount is in its account's currency.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Account, Category, Transaction, TransactionCategory};
+
+/// Quicken's US date format, which GnuCash also reads
+const DATE_FORMAT: &str = "%m/%d/%Y";
+
+/// Split category for fees without a category of their own
+const FEES: &str = "Fees";
+
+/// QIF account type for an account type
+fn account_type(account: &Account) -> &'static str {
+    match account.account_type.as_str() {
+        "credit_card" => "CCard",
+        "loan" => "Oth L",
+        "cash" => "Cash",
+        "investment" => "Oth A",
+        _ => "Bank",
+    }
+}
+
+/// A field value on one line; QIF records are line-based
+fn field(value: &str) -> String {
+    value.replace(['\r', '\n'], " ").trim().to_string()
+}
+
+/// "Parent:Child" names, the way QIF nests categories
+fn category_paths(categories: &[Category]) -> HashMap<i64, String> {
+    let names: HashMap<i64, &Category> = categories.iter().map(|c| (c.id, c)).collect();
+    categories
+        .iter()
+        .map(|c| {
+            // ':' separates levels, so it can't appear inside a name
+            let name = field(&c.name).replace(':', "-");
+            let path = match c.parent_category_id.and_then(|id| names.get(&id)) {
+                Some(parent) => format!("{}:{}", field(&parent.name).replace(':', "-"), name),
+                None => name,
+            };
+            (c.id, path)
+        })
+        .collect()
+}
+
+/// Render accounts, categories and transactions as a QIF file.
+///
+/// Each account's initial balance is its first entry, an "Opening Balance"
+/// transferred from itself as Quicken writes it. A transaction with several
+/// categories, or a fee, is written as a split; transfer legs point at the
+/// other account in [brackets]. Void transactions are left out.
+pub fn render(
+    accounts: &[Account],
+    transactions: &[Transaction],
+    splits: &[TransactionCategory],
+    categories: &[Category],
+) -> String {
+    let paths = category_paths(categories);
+    let account_names: HashMap<i64, String> = accounts.iter().map(|a| (a.id, field(&a.name))).collect();
+
+    let mut splits_by_txn: HashMap<i64, Vec<&TransactionCategory>> = HashMap::new();
+    for split in splits {
+        splits_by_txn.entry(split.transaction_id).or_default().push(split);
+    }
+    // Both legs of a transfer share the group id; each points at the other's account
+    let mut legs_by_group: HashMap<i64, Vec<&Transaction>> = HashMap::new();
+    for t in transactions {
+        if let Some(group_id) = t.transfer_group_id {
+            legs_by_group.entry(group_id).or_default().push(t);
+        }
+    }
+
+    // QIF categories are either income or expense; go by how they're used
+    let income_categories: HashSet<i64> = transactions
+        .iter()
+        .filter(|t| t.transaction_type == "income")
+        .flat_map(|t| splits_by_txn.get(&t.id).into_iter().flatten())
+        .map(|split| split.category_id)
+        .collect();
+
+    let mut out = String::from("!Option:AutoSwitch\n!Account\n");
+    for account in accounts {
+        out.push_str(&format!("N{}\nT{}\n", account_names[&account.id], account_type(account)));
+        let description = match account.bank_name.as_deref().map(field).filter(|b| !b.is_empty()) {
+            Some(bank) => format!("{} ({})", bank, account.currency),
+            None => account.currency.clone(),
+        };
+        out.push_str(&format!("D{}\n", description));
+        if let Some(limit) = account.credit_limit {
+            out.push_str(&format!("L{:.2}\n", limit));
+        }
+        out.push_str("^\n");
+    }
+    out.push_str("!Clear:AutoSwitch\n");
+
+    if !categories.is_empty() {
+        out.push_str("!Type:Cat\n");
+        for category in categories {
+            let kind = if income_categories.contains(&category.id) { "I" } else { "E" };
+            out.push_str(&format!("N{}\n{}\n^\n", paths[&category.id], kind));
+        }
+    }
+
+    for account in accounts {
+        let name = &account_names[&account.id];
+        out.push_str(&format!("!Account\nN{}\nT{}\n^\n!Type:{}\n", name, account_type(account), account_type(account)));
+
+        let opened = account.opened_on.unwrap_or_else(|| account.created_at.date_naive());
+        out.push_str(&format!(
+            "D{}\nT{:.2}\nCX\nPOpening Balance\nL[{}]\n^\n",
+            opened.format(DATE_FORMAT),
+            account.initial_balance,
+            name
+        ));
+
+        for t in transactions.iter().filter(|t| t.account_id == account.id && t.status != "void") {
+            let is_income = t.transaction_type == "income";
+            let sign = if is_income { 1.0 } else { -1.0 };
+            let amount = t.amount.abs();
+            out.push_str(&format!(
+                "D{}\nT{:.2}\n",
+                t.transaction_date.format(DATE_FORMAT),
+                sign * amount - t.fee
+            ));
+            if t.status == "cleared" {
+                out.push_str("C*\n");
+            }
+            if let Some(reference) = t.reference.as_deref().map(field).filter(|r| !r.is_empty()) {
+                out.push_str(&format!("N{}\n", reference));
+            }
+
+            let description = t.description.as_deref().map(field).filter(|d| !d.is_empty());
+            let payee = t.merchant.as_deref().map(field).filter(|m| !m.is_empty());
+            let memo: Vec<String> = [payee.as_ref().and(description.clone()), t.notes.as_deref().map(field)]
+                .into_iter()
+                .flatten()
+                .filter(|m| !m.is_empty())
+                .collect();
+            if let Some(payee) = payee.or(description) {
+                out.push_str(&format!("P{}\n", payee));
+            }
+            if !memo.is_empty() {
+                out.push_str(&format!("M{}\n", memo.join(" - ")));
+            }
+
+            // Category lines: the named splits, the rest to the transfer's other
+            // account (or left blank), and the fee
+            let mut lines: Vec<(String, f64)> = splits_by_txn
+                .get(&t.id)
+                .into_iter()
+                .flatten()
+                .map(|split| {
+                    let path = paths.get(&split.category_id).cloned().unwrap_or_default();
+                    (path, sign * split.amount.abs())
+                })
+                .collect();
+            let categorized: f64 = lines.iter().map(|(_, value)| value.abs()).sum();
+            let counter = t
+                .transfer_group_id
+                .and_then(|group_id| legs_by_group.get(&group_id))
+                .and_then(|legs| legs.iter().find(|leg| leg.id != t.id))
+                .and_then(|leg| account_names.get(&leg.account_id))
+                .map(|name| format!("[{}]", name));
+            if amount - categorized > 0.005 {
+                lines.push((counter.unwrap_or_default(), sign * (amount - categorized)));
+            }
+            if t.fee > 0.0 {
+                let fee_category = t
+                    .fee_category_id
+                    .and_then(|id| paths.get(&id).cloned())
+                    .unwrap_or_else(|| FEES.to_string());
+                lines.push((fee_category, -t.fee));
+            }
+
+            match lines.as_slice() {
+                [] => {}
+                [(category, _)] => {
+                    if !category.is_empty() {
+                        out.push_str(&format!("L{}\n", category));
+                    }
+                }
+                _ => {
+                    for (category, value) in &lines {
+                        out.push_str(&format!("S{}\n${:.2}\n", category, value));
+                    }
+                }
+            }
+            out.push_str("^\n");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateTransfer;
+    use crate::testing;
+    use crate::transfers;
+
+    #[tokio::test]
+    async fn test_render_splits_fees_and_transfers() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).name("Checking").balance(500.0).insert(&pool).await;
+        let savings = testing::account(user.id).name("Savings").insert(&pool).await;
+        let food = testing::category(&pool, user.id, "Food").await;
+        let lunch = testing::txn(checking.id).amount(30.0).category(food.id).description("Lunch").insert(&pool).await;
+        testing::txn(checking.id).amount(1000.0).income().description("Salary").insert(&pool).await;
+        sqlx::query("UPDATE transactions SET fee = 1.5, merchant = 'Cafe' WHERE id = ?")
+            .bind(lunch.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let transfer = CreateTransfer {
+            from_account_id: checking.id,
+            to_account_id: savings.id,
+            amount: 200.0,
+            description: Some("Top up".to_string()),
+            transaction_date: None,
+            fee: None,
+        };
+        transfers::create_transfer(&pool, &checking, &savings, &transfer, 1.0).await.unwrap();
+
+        let accounts = sqlx::query_as::<_, Account>("SELECT * FROM accounts ORDER BY id").fetch_all(&pool).await.unwrap();
+        let transactions = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let splits = sqlx::query_as::<_, TransactionCategory>("SELECT * FROM transaction_categories")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let categories = sqlx::query_as::<_, Category>("SELECT * FROM categories").fetch_all(&pool).await.unwrap();
+        let qif = render(&accounts, &transactions, &splits, &categories);
+
+        assert!(qif.starts_with("!Option:AutoSwitch\n!Account\nNChecking\nTBank\nDUSD\n^\n"));
+        assert!(qif.contains("!Type:Cat\nNFood\nE\n^\n"));
+        assert!(qif.contains("T500.00\nCX\nPOpening Balance\nL[Checking]\n^\n"));
+        // The fee comes off the account on top of the amount, as its own split
+        assert!(qif.contains("T-31.50\nC*\nPCafe\nMLunch\nSFood\n$-30.00\nSFees\n$-1.50\n^\n"));
+        assert!(qif.contains("T1000.00\nC*\nPSalary\n^\n"));
+        assert!(qif.contains("T-200.00\nC*\nPTop up\nL[Savings]\n^\n"));
+        assert!(qif.contains("T200.00\nC*\nPTop up\nL[Checking]\n^\n"));
+        assert_eq!(qif.matches("!Type:Bank\n").count(), 2);
+    }
+}
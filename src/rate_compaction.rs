@@ -0,0 +1,122 @@
+// rate_compaction.rs
+// Keeps exchange_rates small: for each pair, source and past day only the
+// latest rate (the day's close) stays, and the intra-day rates before it move
+// to exchange_rates_archive. A close per source keeps source priority working
+// (fx::pick_rate ranks sources within a day); pinned rates and the current
+// day are left alone.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::SqlitePool;
+
+use crate::models::RateStorageStats;
+
+/// Unpinned rates of days before ?1 with a later rate for the same pair,
+/// source and day
+const SUPERSEDED: &str = "SELECT e.id FROM exchange_rates e
+     WHERE e.pinned = 0 AND DATE(e.rate_date) < ?
+     AND EXISTS (
+         SELECT 1 FROM exchange_rates l
+         WHERE l.from_currency = e.from_currency AND l.to_currency = e.to_currency
+         AND l.source = e.source AND l.pinned = 0
+         AND DATE(l.rate_date) = DATE(e.rate_date)
+         AND julianday(l.rate_date) > julianday(e.rate_date)
+     )";
+
+/// Archive the superseded intra-day rates of every day before `before`, in
+/// one transaction. Returns the number of rates archived.
+pub async fn compact_rates(pool: &SqlitePool, before: NaiveDate, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let before = before.format("%Y-%m-%d").to_string();
+    let mut tx = pool.begin().await?;
+
+    let ids: Vec<i64> = sqlx::query_scalar(SUPERSEDED).bind(&before).fetch_all(&mut *tx).await?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    let ids = serde_json::to_string(&ids).unwrap_or_default();
+
+    sqlx::query(
+        "INSERT INTO exchange_rates_archive
+             (id, from_currency, to_currency, rate, rate_date, source, rate_source_id, created_at, archived_at)
+         SELECT id, from_currency, to_currency, rate, rate_date, source, rate_source_id, created_at, ?
+         FROM exchange_rates WHERE id IN (SELECT value FROM json_each(?))",
+    )
+    .bind(now)
+    .bind(&ids)
+    .execute(&mut *tx)
+    .await?;
+    let archived = sqlx::query("DELETE FROM exchange_rates WHERE id IN (SELECT value FROM json_each(?))")
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    tx.commit().await?;
+    Ok(archived)
+}
+
+pub async fn storage_stats(pool: &SqlitePool, now: DateTime<Utc>) -> Result<RateStorageStats, sqlx::Error> {
+    let (rates, pairs, oldest_rate_date, newest_rate_date): (i64, i64, Option<DateTime<Utc>>, Option<DateTime<Utc>>) =
+        sqlx::query_as(
+            "SELECT COUNT(*), COUNT(DISTINCT from_currency || '/' || to_currency), MIN(rate_date), MAX(rate_date)
+             FROM exchange_rates",
+        )
+        .fetch_one(pool)
+        .await?;
+    let compactable_rates: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM ({})", SUPERSEDED))
+        .bind(now.format("%Y-%m-%d").to_string())
+        .fetch_one(pool)
+        .await?;
+    let (archived_rates, last_archived_at): (i64, Option<DateTime<Utc>>) =
+        sqlx::query_as("SELECT COUNT(*), MAX(archived_at) FROM exchange_rates_archive")
+            .fetch_one(pool)
+            .await?;
+
+    Ok(RateStorageStats {
+        rates,
+        pairs,
+        compactable_rates,
+        archived_rates,
+        oldest_rate_date,
+        newest_rate_date,
+        last_archived_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::{Duration, TimeZone};
+
+    #[tokio::test]
+    async fn test_compaction_keeps_each_sources_daily_close() {
+        let pool = testing::pool().await;
+        let morning = Utc.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+        for (hours, rate) in [(0, 0.90), (3, 0.91), (6, 0.92)] {
+            testing::rate(&pool, "USD", "EUR", rate, morning + Duration::hours(hours)).await;
+        }
+        sqlx::query("UPDATE exchange_rates SET source = 'official' WHERE rate = 0.90")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Today's rates aren't a close yet
+        let now = morning + Duration::days(1);
+        testing::rate(&pool, "USD", "EUR", 0.5, now).await;
+        testing::rate(&pool, "USD", "EUR", 0.6, now + Duration::hours(1)).await;
+
+        let stats = storage_stats(&pool, now).await.unwrap();
+        assert_eq!((stats.rates, stats.pairs, stats.compactable_rates), (5, 1, 1));
+
+        assert_eq!(compact_rates(&pool, now.date_naive(), now).await.unwrap(), 1);
+        let left: Vec<f64> = sqlx::query_scalar("SELECT rate FROM exchange_rates ORDER BY rate_date")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(left, [0.90, 0.92, 0.5, 0.6]);
+        assert_eq!(compact_rates(&pool, now.date_naive(), now).await.unwrap(), 0);
+
+        let stats = storage_stats(&pool, now).await.unwrap();
+        assert_eq!((stats.rates, stats.archived_rates, stats.compactable_rates), (4, 1, 0));
+        assert_eq!(stats.last_archived_at, Some(now));
+    }
+}
@@ -0,0 +1,468 @@
+// rate_providers.rs
+// Where fetched exchange rates come from: x-rates.com's HTML tables or the
+// frankfurter.app API (European Central Bank reference rates). FX_PROVIDER,
+// or `scrape_rates --provider NAME`, picks the provider tried first; when it
+// fails for a currency the others are tried in turn.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::env;
+
+use crate::exchange_scraper::{self, ExchangeRate, ExchangeRateScraper};
+
+pub const FRANKFURTER: &str = "frankfurter.app";
+const FRANKFURTER_URL: &str = "https://api.frankfurter.app/latest";
+
+/// A source of the latest rates from a base currency
+pub trait RateProvider: Send + Sync {
+    /// Provider name in rate_sources and scrape_runs, e.g. "x-rates.com"
+    fn name(&self) -> &'static str;
+
+    /// Rate source kind the rates are stored as, for source priorities
+    fn kind(&self) -> &'static str;
+
+    /// The latest rates from `from_currency` to every currency the provider quotes
+    fn fetch_rates<'a>(&'a self, from_currency: &'a str) -> BoxFuture<'a, Result<Vec<ExchangeRate>, String>>;
+}
+
+/// ECB reference rates through the frankfurter.app API; currencies are bare codes
+pub struct Frankfurter {
+    client: Client,
+}
+
+impl Frankfurter {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+}
+
+/// Rates in a frankfurter.app /latest response
+pub fn parse_frankfurter(body: &str) -> Result<Vec<ExchangeRate>, String> {
+    let json: Value = serde_json::from_str(body).map_err(|e| format!("Invalid frankfurter.app response: {}", e))?;
+    let from_currency = json["base"].as_str().ok_or("frankfurter.app response has no base currency")?;
+    let rate_date = json["date"]
+        .as_str()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .ok_or("frankfurter.app response has no valid date")?;
+
+    let rates: Vec<ExchangeRate> = json["rates"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(code, rate)| {
+            Some(ExchangeRate {
+                from_currency: from_currency.to_string(),
+                to_currency: code.clone(),
+                rate: rate.as_f64().filter(|r| *r > 0.0)?,
+                rate_date,
+            })
+        })
+        .collect();
+    if rates.is_empty() {
+        return Err("frankfurter.app response has no rates".to_string());
+    }
+    Ok(rates)
+}
+
+impl RateProvider for Frankfurter {
+    fn name(&self) -> &'static str {
+        FRANKFURTER
+    }
+
+    fn kind(&self) -> &'static str {
+        "api"
+    }
+
+    fn fetch_rates<'a>(&'a self, from_currency: &'a str) -> BoxFuture<'a, Result<Vec<ExchangeRate>, String>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .get(FRANKFURTER_URL)
+                .query(&[("from", from_currency)])
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("HTTP Error: {}", response.status()));
+            }
+            let body = response.text().await.map_err(|e| e.to_string())?;
+            parse_frankfurter(&body)
+        })
+    }
+}
+
+/// Providers accepted by FX_PROVIDER and --provider, in fallback order
+const PROVIDERS: [&str; 2] = [exchange_scraper::PROVIDER, FRANKFURTER];
+
+/// Provider for a name such as "frankfurter" or "x-rates.com" (case-insensitive)
+fn provider_name(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    PROVIDERS
+        .into_iter()
+        .find(|provider| *provider == name || provider.split('.').next() == Some(name.as_str()))
+}
+
+/// Rates fetched for one base currency and the provider they came from
+pub struct FetchedRates {
+    pub provider: &'static str,
+    pub kind: &'static str,
+    pub rates: Vec<ExchangeRate>,
+    pub up_to_date: bool, // Rates of this day are stored already; `rates` is empty
+}
+
+/// Every provider, the preferred one first
+pub struct RateProviders {
+    providers: Vec<Box<dyn RateProvider>>,
+    verbose: bool,
+}
+
+impl RateProviders {
+    /// `primary` (FX_PROVIDER when None, else x-rates.com) first, then the
+    /// others as fallbacks. Verbose prints progress for the command line.
+    pub fn configured(primary: Option<&str>, verbose: bool) -> Result<Self, String> {
+        let primary = match primary.map(str::to_string).or_else(|| env::var("FX_PROVIDER").ok()) {
+            Some(name) if !name.trim().is_empty() => provider_name(&name).ok_or_else(|| {
+                format!("Unknown rate provider '{}' (expected {})", name.trim(), PROVIDERS.join(" or "))
+            })?,
+            _ => PROVIDERS[0],
+        };
+
+        let mut order = vec![primary];
+        order.extend(PROVIDERS.into_iter().filter(|p| *p != primary));
+        let providers = order
+            .into_iter()
+            .map(|name| -> Box<dyn RateProvider> {
+                match name {
+                    FRANKFURTER => Box::new(Frankfurter::new()),
+                    _ if verbose => Box::new(ExchangeRateScraper::new()),
+                    _ => Box::new(ExchangeRateScraper::quiet()),
+                }
+            })
+            .collect();
+        Ok(Self { providers, verbose })
+    }
+
+    #[cfg(test)]
+    fn from_providers(providers: Vec<Box<dyn RateProvider>>) -> Self {
+        Self { providers, verbose: false }
+    }
+
+    /// The provider tried first
+    pub fn primary(&self) -> &'static str {
+        self.providers[0].name()
+    }
+
+    /// Latest rates from the first provider that has them; the error lists
+    /// why each one failed
+    pub async fn fetch(&self, from_currency: &str) -> Result<FetchedRates, String> {
+        let mut errors = Vec::new();
+        for provider in &self.providers {
+            match provider.fetch_rates(from_currency).await {
+                Ok(rates) => {
+                    if self.verbose && !errors.is_empty() {
+                        println!("  Fell back to {} ({})", provider.name(), errors.join("; "));
+                    }
+                    return Ok(FetchedRates {
+                        provider: provider.name(),
+                        kind: provider.kind(),
+                        rates,
+                        up_to_date: false,
+                    });
+                }
+                Err(e) => errors.push(format!("{}: {}", provider.name(), e)),
+            }
+        }
+        Err(errors.join("; "))
+    }
+
+    /// Fetch, but drop the rates when the database has rates from these
+    /// providers for the day they are quoted for
+    pub async fn smart_fetch(&self, pool: &SqlitePool, from_currency: &str) -> Result<FetchedRates, String> {
+        if self.verbose {
+            println!("1. Checking if {}'s FX rates need to be updated...", from_currency);
+        }
+        let mut fetched = self.fetch(from_currency).await?;
+        let Some(rate_date) = fetched.rates.iter().map(|r| r.rate_date).max() else {
+            return Ok(fetched);
+        };
+
+        if self.is_up_to_date(pool, from_currency, rate_date).await.map_err(|e| e.to_string())? {
+            if self.verbose {
+                println!("  DB contains the FX rates of {} already, no need to update.", rate_date);
+            }
+            fetched.rates.clear();
+            fetched.up_to_date = true;
+        } else if self.verbose {
+            println!("  DB doesn't contain the FX rates of {} yet, fetched from {}.", rate_date, fetched.provider);
+        }
+        Ok(fetched)
+    }
+
+    /// Whether rates from `from_currency` stored by any of these providers
+    /// are dated `date` or later
+    pub async fn is_up_to_date(
+        &self,
+        pool: &SqlitePool,
+        from_currency: &str,
+        date: NaiveDate,
+    ) -> Result<bool, sqlx::Error> {
+        let names: Vec<&str> = self.providers.iter().map(|p| p.name()).collect();
+        let placeholders = vec!["?"; names.len()].join(", ");
+        let sql = format!(
+            "SELECT COUNT(*) FROM exchange_rates
+             WHERE from_currency = ? AND DATE(rate_date) >= ?
+             AND rate_source_id IN (SELECT id FROM rate_sources WHERE name IN ({}))",
+            placeholders
+        );
+        let mut query = sqlx::query_scalar::<_, i64>(&sql)
+            .bind(from_currency)
+            .bind(date.format("%Y-%m-%d").to_string());
+        for name in names {
+            query = query.bind(name);
+        }
+        Ok(query.fetch_one(pool).await? > 0)
+    }
+
+    /// Smart-fetch several base currencies, printing progress when verbose.
+    /// Errors are returned as "CODE: message" lines next to the results.
+    pub async fn smart_fetch_multiple(
+        &self,
+        pool: &SqlitePool,
+        currencies: Vec<&str>,
+    ) -> (HashMap<String, FetchedRates>, Vec<String>) {
+        let mut results = HashMap::new();
+        let mut errors = Vec::new();
+
+        for currency in currencies {
+            if self.verbose {
+                println!("\nFetching {} exchange rates...", currency);
+            }
+            match self.smart_fetch(pool, currency).await {
+                Ok(fetched) => {
+                    if self.verbose {
+                        if fetched.up_to_date {
+                            println!("2. Latest FX Rates of {} are stored already.", currency)
+                        } else {
+                            println!("3. ✓Success: get {} exchange rates from {}.", fetched.rates.len(), fetched.provider);
+                        }
+                    }
+                    results.insert(currency.to_string(), fetched);
+                }
+                Err(e) => {
+                    if self.verbose {
+                        eprintln!("3. ✗Error: {}.", e);
+                    }
+                    errors.push(format!("{}: {}", currency, e));
+                }
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+
+        (results, errors)
+    }
+}
+
+/// Rows per INSERT statement; 5 bound values each stays under SQLite's
+/// oldest limit of 999 variables
+const SAVE_BATCH_ROWS: usize = 150;
+
+/// What happened to one fetched pair in save_rates
+#[derive(Debug, Clone, PartialEq)]
+pub enum PairOutcome {
+    Saved,
+    Skipped(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct PairResult {
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate: f64,
+    pub outcome: PairOutcome,
+}
+
+/// Number of pairs save_rates stored
+pub fn saved_count(results: &[PairResult]) -> usize {
+    results.iter().filter(|r| r.outcome == PairOutcome::Saved).count()
+}
+
+/// Store fetched rates under their provider, dated `now` like every fetched
+/// rate. The currency's rates go in one transaction, a few multi-row INSERTs
+/// rather than a statement per pair, so a run either stores all of them or
+/// none. Rates that aren't positive numbers, and a pair listed twice, are
+/// skipped; the result says so for each pair.
+pub async fn save_rates(
+    pool: &SqlitePool,
+    fetched: &FetchedRates,
+    now: DateTime<Utc>,
+) -> Result<Vec<PairResult>, sqlx::Error> {
+    let mut results: Vec<PairResult> = Vec::with_capacity(fetched.rates.len());
+    let mut pairs = HashSet::new();
+    for rate in &fetched.rates {
+        let outcome = if !rate.rate.is_finite() || rate.rate <= 0.0 {
+            PairOutcome::Skipped("rate is not a positive number")
+        } else if !pairs.insert((rate.from_currency.as_str(), rate.to_currency.as_str())) {
+            PairOutcome::Skipped("pair listed twice")
+        } else {
+            PairOutcome::Saved
+        };
+        results.push(PairResult {
+            from_currency: rate.from_currency.clone(),
+            to_currency: rate.to_currency.clone(),
+            rate: rate.rate,
+            outcome,
+        });
+    }
+
+    let saved: Vec<&PairResult> = results.iter().filter(|r| r.outcome == PairOutcome::Saved).collect();
+    let mut tx = pool.begin().await?;
+    let source_id: Option<i64> = sqlx::query_scalar("SELECT id FROM rate_sources WHERE name = ?")
+        .bind(fetched.provider)
+        .fetch_optional(&mut *tx)
+        .await?;
+    for batch in saved.chunks(SAVE_BATCH_ROWS) {
+        let rows = vec!["(?, ?, ?, ?, ?, ?)"; batch.len()].join(", ");
+        let sql = format!(
+            "INSERT INTO exchange_rates (from_currency, to_currency, rate, rate_date, source, rate_source_id) VALUES {}",
+            rows
+        );
+        let mut query = sqlx::query(&sql);
+        for pair in batch {
+            query = query
+                .bind(&pair.from_currency)
+                .bind(&pair.to_currency)
+                .bind(pair.rate)
+                .bind(now)
+                .bind(fetched.kind)
+                .bind(source_id);
+        }
+        query.execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+
+    Ok(results)
+}
+
+/// Providers that delivered rates in a run, for scrape_runs; the primary
+/// when none did
+pub fn providers_used<'a>(primary: &str, fetched: impl IntoIterator<Item = &'a FetchedRates>) -> String {
+    let mut used: Vec<&str> = Vec::new();
+    for provider in fetched.into_iter().filter(|f| !f.up_to_date).map(|f| f.provider) {
+        if !used.contains(&provider) {
+            used.push(provider);
+        }
+    }
+    if used.is_empty() {
+        primary.to_string()
+    } else {
+        used.join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    /// Answers from a fixed list, or fails like an unreachable site
+    struct Fixed {
+        name: &'static str,
+        rates: Option<Vec<ExchangeRate>>,
+    }
+
+    impl RateProvider for Fixed {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn kind(&self) -> &'static str {
+            "scraper"
+        }
+
+        fn fetch_rates<'a>(&'a self, _from_currency: &'a str) -> BoxFuture<'a, Result<Vec<ExchangeRate>, String>> {
+            Box::pin(async move { self.rates.clone().ok_or_else(|| "HTTP Error: 503".to_string()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_and_skips_stored_days() {
+        let body = r#"{"amount": 1.0, "base": "CAD", "date": "2025-06-10", "rates": {"EUR": 0.64, "USD": 0.73}}"#;
+        let rates = parse_frankfurter(body).unwrap();
+        assert_eq!(rates.len(), 2);
+        assert!(rates.iter().all(|r| r.from_currency == "CAD" && r.rate_date.to_string() == "2025-06-10"));
+        assert!(parse_frankfurter(r#"{"base": "CAD", "date": "2025-06-10", "rates": {}}"#).is_err());
+        assert_eq!(provider_name("Frankfurter"), Some(FRANKFURTER));
+        assert!(RateProviders::configured(Some("oanda"), false).is_err());
+
+        let providers = RateProviders::from_providers(vec![
+            Box::new(Fixed { name: exchange_scraper::PROVIDER, rates: None }),
+            Box::new(Fixed { name: FRANKFURTER, rates: Some(rates) }),
+        ]);
+        let pool = testing::pool().await;
+        let fetched = providers.smart_fetch(&pool, "CAD").await.unwrap();
+        assert_eq!((fetched.provider, fetched.rates.len()), (FRANKFURTER, 2));
+        assert_eq!(providers_used(providers.primary(), [&fetched]), FRANKFURTER);
+        assert_eq!(saved_count(&save_rates(&pool, &fetched, Utc::now()).await.unwrap()), 2);
+
+        // Stored under the provider that answered, so the next run skips the day
+        let stored: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM exchange_rates e JOIN rate_sources s ON s.id = e.rate_source_id WHERE s.name = ?",
+        )
+        .bind(FRANKFURTER)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored, 2);
+        assert!(providers.smart_fetch(&pool, "CAD").await.unwrap().up_to_date);
+
+        let failing = RateProviders::from_providers(vec![Box::new(Fixed { name: FRANKFURTER, rates: None })]);
+        assert_eq!(failing.fetch("CAD").await.err().unwrap(), "frankfurter.app: HTTP Error: 503");
+    }
+
+    #[tokio::test]
+    async fn test_save_rates_in_batches_with_per_pair_results() {
+        let pool = testing::pool().await;
+        let rate_date = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+        let rate = |to: String, rate: f64| ExchangeRate { from_currency: "USD".to_string(), to_currency: to, rate, rate_date };
+        // More pairs than one statement holds
+        let mut rates: Vec<ExchangeRate> = (0..SAVE_BATCH_ROWS + 10).map(|i| rate(format!("C{:03}", i), 1.0 + i as f64)).collect();
+        rates.push(rate("C000".to_string(), 9.0));
+        rates.push(rate("EUR".to_string(), 0.0));
+        let fetched = FetchedRates { provider: FRANKFURTER, kind: "api", rates, up_to_date: false };
+
+        let results = save_rates(&pool, &fetched, Utc::now()).await.unwrap();
+        assert_eq!(results.len(), SAVE_BATCH_ROWS + 12);
+        assert_eq!(saved_count(&results), SAVE_BATCH_ROWS + 10);
+        let skipped: Vec<(&str, &PairOutcome)> = results
+            .iter()
+            .filter(|r| r.outcome != PairOutcome::Saved)
+            .map(|r| (r.to_currency.as_str(), &r.outcome))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("C000", &PairOutcome::Skipped("pair listed twice")),
+                ("EUR", &PairOutcome::Skipped("rate is not a positive number"))
+            ]
+        );
+
+        let (count, last): (i64, f64) = sqlx::query_as(
+            "SELECT COUNT(*), MAX(rate) FROM exchange_rates e JOIN rate_sources s ON s.id = e.rate_source_id
+             WHERE s.name = ? AND e.source = 'api'",
+        )
+        .bind(FRANKFURTER)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((count, last), ((SAVE_BATCH_ROWS + 10) as i64, (SAVE_BATCH_ROWS + 10) as f64));
+    }
+}
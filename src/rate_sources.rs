@@ -0,0 +1,79 @@
+// rate_sources.rs
+// Providers behind stored exchange rates. A rate's `source` is the kind used
+// for source priorities; `rate_source_id` names the provider, so rates from
+// "x-rates.com" and another scraper can be told apart and filtered.
+
+use sqlx::SqlitePool;
+
+use crate::models::RateSource;
+
+pub async fn find(pool: &SqlitePool, id: i64) -> Result<Option<RateSource>, sqlx::Error> {
+    sqlx::query_as::<_, RateSource>("SELECT * FROM rate_sources WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Number of exchange rates that name this provider
+pub async fn rates_using(pool: &SqlitePool, id: i64) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM exchange_rates WHERE rate_source_id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Kind and provider for a new rate. An explicit provider decides the kind
+/// (a different `source` is an error); otherwise the oldest provider of the
+/// kind is used, and rates of a kind without providers get none.
+pub async fn resolve(
+    pool: &SqlitePool,
+    source: Option<&str>,
+    rate_source_id: Option<i64>,
+) -> Result<Result<(String, Option<i64>), String>, sqlx::Error> {
+    match rate_source_id {
+        Some(id) => Ok(match find(pool, id).await? {
+            None => Err(format!("Rate source {} not found", id)),
+            Some(provider) if source.is_some_and(|s| s != provider.kind) => Err(format!(
+                "Rate source '{}' is of kind '{}', not '{}'",
+                provider.name,
+                provider.kind,
+                source.unwrap_or_default()
+            )),
+            Some(provider) => Ok((provider.kind, Some(provider.id))),
+        }),
+        None => {
+            let kind = source.unwrap_or("manual");
+            let id: Option<i64> =
+                sqlx::query_scalar("SELECT id FROM rate_sources WHERE kind = ? ORDER BY id LIMIT 1")
+                    .bind(kind)
+                    .fetch_optional(pool)
+                    .await?;
+            Ok(Ok((kind.to_string(), id)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_resolve_picks_kind_and_provider() {
+        let pool = testing::pool().await;
+        let scraper_id: i64 = sqlx::query_scalar("SELECT id FROM rate_sources WHERE name = 'x-rates.com'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let resolved = resolve(&pool, Some("scraper"), None).await.unwrap();
+        assert_eq!(resolved, Ok(("scraper".to_string(), Some(scraper_id))));
+        let resolved = resolve(&pool, None, Some(scraper_id)).await.unwrap();
+        assert_eq!(resolved, Ok(("scraper".to_string(), Some(scraper_id))));
+        assert_eq!(resolve(&pool, Some("bank"), None).await.unwrap(), Ok(("bank".to_string(), None)));
+
+        assert!(resolve(&pool, Some("manual"), Some(scraper_id)).await.unwrap().is_err());
+        assert!(resolve(&pool, None, Some(9999)).await.unwrap().is_err());
+        assert_eq!(rates_using(&pool, scraper_id).await.unwrap(), 0);
+    }
+}
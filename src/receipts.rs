@@ -0,0 +1,168 @@
+// receipts.rs
+// Receipt-assisted entry: a ReceiptParser reads the date, total and merchant
+// off an uploaded receipt, which pre-fills a draft transaction the user
+// confirms. Only a local parser ships; OCR backends plug in through the trait
+// and are picked with RECEIPT_PARSER.
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use sqlx::SqlitePool;
+use std::env;
+
+use crate::merchants;
+use crate::models::ReceiptDraft;
+use crate::rules;
+
+/// Largest receipt upload accepted by the API
+pub const MAX_RECEIPT_BYTES: usize = 10 * 1024 * 1024;
+
+/// What a parser could read off a receipt; anything it missed stays None
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReceiptFields {
+    pub date: Option<NaiveDate>,
+    pub total: Option<f64>,
+    pub merchant: Option<String>,
+}
+
+pub trait ReceiptParser: Send + Sync {
+    /// Reported with each draft, e.g. "local"
+    fn name(&self) -> &'static str;
+
+    fn parse(&self, receipt: &[u8]) -> Result<ReceiptFields, String>;
+}
+
+/// Stub parser that needs no OCR engine: it reads the text an upload already
+/// carries (a plain-text receipt, or text embedded in an image file) and
+/// picks the fields out of it. A bare photo gives empty fields.
+pub struct LocalReceiptParser;
+
+impl ReceiptParser for LocalReceiptParser {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn parse(&self, receipt: &[u8]) -> Result<ReceiptFields, String> {
+        Ok(parse_text_lines(&text_lines(receipt)))
+    }
+}
+
+/// The configured parser: "local" (the default) or "none" to turn receipt
+/// parsing off
+pub fn parser_from_env() -> Result<Option<Box<dyn ReceiptParser>>, String> {
+    match env::var("RECEIPT_PARSER").unwrap_or_default().trim() {
+        "" | "local" => Ok(Some(Box::new(LocalReceiptParser))),
+        "none" | "off" => Ok(None),
+        other => Err(format!("Unknown RECEIPT_PARSER '{}' (expected local or none)", other)),
+    }
+}
+
+/// Image-format chunk names that show up as text in JPEG and PNG files
+const FORMAT_MARKERS: [&str; 6] = ["JFIF", "Exif", "IHDR", "IDAT", "IEND", "sRGB"];
+
+/// Runs of printable text of at least four characters, one per line
+fn text_lines(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .split(|b| !(b.is_ascii_graphic() || *b == b' ' || *b == b'\t'))
+        .map(|run| String::from_utf8_lossy(run).trim().to_string())
+        .filter(|line| line.len() >= 4 && !FORMAT_MARKERS.iter().any(|m| line.starts_with(m)))
+        .collect()
+}
+
+fn parse_date(token: &str) -> Option<NaiveDate> {
+    let token = token.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    ["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%m/%d/%y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(token, format).ok())
+}
+
+fn parse_amount(token: &str) -> Option<f64> {
+    let token = token.trim_start_matches(['$', '€', '£']).replace(',', "");
+    token.contains('.').then(|| token.parse::<f64>().ok()).flatten()
+}
+
+fn parse_text_lines(lines: &[String]) -> ReceiptFields {
+    let date = lines
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .find_map(parse_date);
+
+    // The last total line wins ("Subtotal" comes first, tips may follow)
+    let total = lines
+        .iter()
+        .rev()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            (lower.contains("total") && !lower.contains("subtotal")) || lower.contains("amount due")
+        })
+        .find_map(|line| line.split_whitespace().rev().find_map(parse_amount));
+
+    // The store name is printed first, before addresses and items
+    let merchant = lines
+        .iter()
+        .filter(|line| line.chars().filter(|c| c.is_alphabetic()).count() >= 3)
+        .find(|line| line.split_whitespace().all(|t| parse_date(t).is_none() && parse_amount(t).is_none()))
+        .and_then(|line| merchants::normalize_merchant(line));
+
+    ReceiptFields { date, total, merchant }
+}
+
+/// Unsaved expense for the user to confirm, categorized by their rules
+pub async fn draft(
+    pool: &SqlitePool,
+    user_id: i64,
+    account_id: Option<i64>,
+    fields: ReceiptFields,
+    parser: &str,
+) -> Result<ReceiptDraft, sqlx::Error> {
+    let category_id = match fields.merchant.as_deref() {
+        Some(merchant) => {
+            let rules = rules::rules_for_user(pool, user_id).await?;
+            rules::matching_rule(&rules, merchant).map(|r| r.category_id)
+        }
+        None => None,
+    };
+
+    Ok(ReceiptDraft {
+        parser: parser.to_string(),
+        account_id,
+        amount: fields.total,
+        transaction_type: "expense".to_string(),
+        description: fields.merchant.clone(),
+        merchant: fields.merchant,
+        // Noon UTC keeps the receipt's day in every timezone the TUI shows
+        transaction_date: fields
+            .date
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .map(|d| Utc.from_utc_datetime(&d)),
+        category_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_local_parser_drafts_expense_from_receipt_text() {
+        let receipt = b"\xff\xd8JFIF\x00 BLUE BOTTLE COFFEE #221\n123 Main St\n06/14/2025 08:12\nLatte 5.25\nSubtotal 9.75\nTax 1.27\nTOTAL $11.02\n";
+        let fields = LocalReceiptParser.parse(receipt).unwrap();
+        assert_eq!(
+            fields,
+            ReceiptFields {
+                date: NaiveDate::from_ymd_opt(2025, 6, 14),
+                total: Some(11.02),
+                merchant: Some("Blue Bottle Coffee".to_string()),
+            }
+        );
+        assert_eq!(LocalReceiptParser.parse(b"\x89PNG\x00\x01").unwrap(), ReceiptFields::default());
+
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        rules::create_rule(&pool, user.id, "coffee", dining.id).await.unwrap();
+
+        let draft = draft(&pool, user.id, None, fields, "local").await.unwrap();
+        assert_eq!((draft.amount, draft.category_id), (Some(11.02), Some(dining.id)));
+        assert_eq!(draft.transaction_date.unwrap().date_naive(), NaiveDate::from_ymd_opt(2025, 6, 14).unwrap());
+    }
+}
@@ -0,0 +1,148 @@
+// reconcile.rs
+// Balance reconciliation: current_balance is kept up to date as transactions
+// are booked, so rows added behind the app's back (raw SQL, imports gone
+// wrong) make it drift. Recomputing it from initial_balance plus every
+// transaction shows the drift and can correct it.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::models::BalanceReconciliation;
+
+/// Differences below this are rounding noise, not drift
+const TOLERANCE: f64 = 0.005;
+
+/// Recomputed balance of account `a` from its transactions `t` joined with
+/// POSTED_JOIN. Same sum as net_worth's balances as of a date: initial_balance
+/// is the balance on opened_on, so earlier transactions don't count, and only
+/// cleared transactions are posted.
+const COMPUTED_BALANCE: &str = "ROUND(a.initial_balance + COALESCE(SUM(
+                    CASE WHEN t.transaction_type = 'income' THEN t.amount ELSE -ABS(t.amount) END - t.fee
+                ), 0), 2)";
+const POSTED_JOIN: &str = "LEFT JOIN transactions t ON t.account_id = a.id AND t.status = 'cleared'
+             AND (a.opened_on IS NULL OR date(t.transaction_date) >= a.opened_on)";
+
+/// Recorded and recomputed balances of one account, or of all accounts when
+/// `account_id` is None. With `fix`, drifted balances are overwritten with
+/// the recomputed ones, summed again in the UPDATE and in the same DB
+/// transaction as the check, so a transaction booked meanwhile isn't lost.
+pub async fn reconcile(
+    pool: &SqlitePool,
+    account_id: Option<i64>,
+    fix: bool,
+) -> Result<Vec<BalanceReconciliation>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut results = sqlx::query_as::<_, BalanceReconciliation>(&format!(
+        "SELECT a.id as account_id, a.name as account_name, a.currency,
+                a.current_balance as recorded_balance,
+                {} as computed_balance,
+                0.0 as difference,
+                COUNT(t.id) as transaction_count,
+                0 as fixed
+         FROM accounts a
+         {}
+         WHERE ? IS NULL OR a.id = ?
+         GROUP BY a.id
+         ORDER BY a.id",
+        COMPUTED_BALANCE, POSTED_JOIN
+    ))
+    .bind(account_id)
+    .bind(account_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let fix_query = format!(
+        "UPDATE accounts SET current_balance = (
+             SELECT {} FROM accounts a {} WHERE a.id = accounts.id
+         ), updated_at = CURRENT_TIMESTAMP
+         WHERE id = ?",
+        COMPUTED_BALANCE, POSTED_JOIN
+    );
+    for result in &mut results {
+        result.difference = ((result.recorded_balance - result.computed_balance) * 100.0).round() / 100.0;
+        if fix && result.difference.abs() >= TOLERANCE {
+            sqlx::query(&fix_query).bind(result.account_id).execute(&mut *tx).await?;
+            result.fixed = true;
+        }
+    }
+    tx.commit().await?;
+    Ok(results)
+}
+
+/// Balance of the account after each of its transactions, by transaction ID,
+/// from the same sum as `reconcile`. Transactions before opened_on and ones
+/// not cleared leave the running balance where it was.
+pub async fn running_balances(pool: &SqlitePool, account_id: i64) -> Result<HashMap<i64, f64>, sqlx::Error> {
+    let rows: Vec<(i64, f64)> = sqlx::query_as(
+        "SELECT t.id,
+                ROUND(a.initial_balance + SUM(
+                    CASE WHEN t.status = 'cleared' AND (a.opened_on IS NULL OR date(t.transaction_date) >= a.opened_on)
+                         THEN (CASE WHEN t.transaction_type = 'income' THEN t.amount ELSE -ABS(t.amount) END - t.fee)
+                         ELSE 0 END
+                ) OVER (ORDER BY t.transaction_date, t.id), 2) as running_balance
+         FROM transactions t
+         JOIN accounts a ON a.id = t.account_id
+         WHERE t.account_id = ?",
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Whether the recorded balance is off by more than rounding
+pub fn has_drifted(result: &BalanceReconciliation) -> bool {
+    result.difference.abs() >= TOLERANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_reconcile_reports_and_fixes_drift() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let other = testing::account(user.id).balance(50.0).insert(&pool).await;
+
+        // Booked behind the app's back: the balance stays at 100
+        testing::txn(account.id).amount(30.0).insert(&pool).await;
+        sqlx::query("INSERT INTO transactions (account_id, amount, transaction_type, transaction_date, fee) VALUES (?, 20, 'income', datetime('now'), 0.5)")
+            .bind(account.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = reconcile(&pool, None, false).await.unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!((report[0].computed_balance, report[0].difference), (89.5, 10.5));
+        assert!(has_drifted(&report[0]) && !has_drifted(&report[1]));
+
+        let fixed = reconcile(&pool, Some(account.id), true).await.unwrap();
+        assert!(fixed[0].fixed);
+        let again = reconcile(&pool, Some(account.id), false).await.unwrap();
+        assert_eq!((again[0].recorded_balance, again[0].transaction_count), (89.5, 2));
+        assert!(reconcile(&pool, Some(other.id), true).await.unwrap().iter().all(|r| !r.fixed));
+    }
+
+    #[tokio::test]
+    async fn test_running_balances_follow_transaction_order() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let day = |d| Utc.with_ymd_and_hms(2025, 6, d, 12, 0, 0).unwrap();
+
+        // Inserted out of order; the pending one doesn't move the balance
+        let rent = testing::txn(account.id).amount(60.0).date(day(3)).insert(&pool).await;
+        let salary = testing::txn(account.id).amount(500.0).income().date(day(1)).insert(&pool).await;
+        let pending = testing::txn(account.id).amount(20.0).date(day(2)).pending().insert(&pool).await;
+
+        let balances = running_balances(&pool, account.id).await.unwrap();
+        assert_eq!(balances[&salary.id], 600.0);
+        assert_eq!(balances[&pending.id], 600.0);
+        assert_eq!(balances[&rent.id], 540.0);
+    }
+}
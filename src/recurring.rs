@@ -1,7 +1,10 @@
-use chrono::{Datelike, Duration, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
 use sqlx::SqlitePool;
 
-use crate::models::RecurringTransaction;
+use crate::forecast;
+use crate::fx;
+use crate::loans;
+use crate::models::{CatchUpPolicy, RecurringTransaction};
 
 /// Result from processing recurring transactions.
 pub struct RecurringProcessResult {
@@ -10,15 +13,18 @@ pub struct RecurringProcessResult {
 }
 
 /// Process all due recurring transactions:
-/// - create concrete transactions
-/// - link categories
+/// - create concrete transactions for the occurrences due by `now` (all
+///   missed ones, or only the latest, per the item's catch_up policy),
+///   converting foreign-currency items at the rate of the occurrence date
+///   (items without a rate yet stay due)
+/// - link categories (loan payments split into principal and interest, with
+///   the principal credited to the loan account)
 /// - update account balances
 /// - advance next_occurrence or deactivate when past end_date
 pub async fn process_due_recurring(
     pool: &SqlitePool,
+    now: DateTime<Utc>,
 ) -> Result<RecurringProcessResult, sqlx::Error> {
-    let now = Utc::now();
-
     let transactions = sqlx::query_as::<_, RecurringTransaction>(
         "SELECT * FROM recurring_transactions 
          WHERE is_active = 1 AND next_occurrence <= ? 
@@ -32,77 +38,54 @@ pub async fn process_due_recurring(
     let mut created_count = 0;
 
     for recurring in &transactions {
-        let result = sqlx::query(
-            "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date) 
-             VALUES (?, ?, ?, ?, ?)",
-        )
-        .bind(recurring.account_id)
-        .bind(recurring.amount)
-        .bind(&recurring.transaction_type)
-        .bind(&recurring.description)
-        .bind(recurring.next_occurrence)
-        .execute(pool)
-        .await;
+        let account_currency: String = sqlx::query_scalar("SELECT currency FROM accounts WHERE id = ?")
+            .bind(recurring.account_id)
+            .fetch_optional(pool)
+            .await?
+            .unwrap_or_default();
 
-        if let Ok(res) = result {
-            let transaction_id = res.last_insert_rowid();
-
-            // Link category if exists
-            if let Some(category_id) = recurring.category_id {
-                let _ = sqlx::query(
-                    "INSERT INTO transaction_categories (transaction_id, category_id, amount) 
-                     VALUES (?, ?, ?)",
-                )
-                .bind(transaction_id)
-                .bind(category_id)
-                .bind(recurring.amount.abs())
-                .execute(pool)
-                .await;
+        let mut occurrences = forecast::occurrences_between(recurring, recurring.next_occurrence, now);
+        if CatchUpPolicy::from_str(&recurring.catch_up) == Some(CatchUpPolicy::Latest) {
+            occurrences.drain(..occurrences.len().saturating_sub(1));
+        }
+
+        let mut last_posted = None;
+        for occurrence in occurrences {
+            if !post_occurrence(pool, recurring, &account_currency, occurrence).await? {
+                break;
             }
+            last_posted = Some(occurrence);
+            created_count += 1;
+        }
+        let Some(last_posted) = last_posted else {
+            continue;
+        };
 
-            // Update account balance
-            let balance_change = if recurring.transaction_type == "income" {
-                recurring.amount
-            } else {
-                -recurring.amount.abs()
-            };
+        // Calculate next occurrence
+        let next = calculate_next_occurrence(last_posted, &recurring.frequency);
+
+        // Check if should deactivate (past end_date)
+        let should_deactivate = recurring
+            .end_date
+            .map(|end| next > end)
+            .unwrap_or(false);
 
+        if should_deactivate {
             let _ = sqlx::query(
-                "UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?",
+                "UPDATE recurring_transactions SET is_active = 0, next_occurrence = ?, updated_at = datetime('now') WHERE id = ?",
             )
-            .bind(balance_change)
-            .bind(recurring.account_id)
+            .bind(next)
+            .bind(recurring.id)
+            .execute(pool)
+            .await;
+        } else {
+            let _ = sqlx::query(
+                "UPDATE recurring_transactions SET next_occurrence = ?, updated_at = datetime('now') WHERE id = ?",
+            )
+            .bind(next)
+            .bind(recurring.id)
             .execute(pool)
             .await;
-
-            // Calculate next occurrence
-            let next = calculate_next_occurrence(recurring.next_occurrence, &recurring.frequency);
-
-            // Check if should deactivate (past end_date)
-            let should_deactivate = recurring
-                .end_date
-                .map(|end| next > end)
-                .unwrap_or(false);
-
-            if should_deactivate {
-                let _ = sqlx::query(
-                    "UPDATE recurring_transactions SET is_active = 0, next_occurrence = ?, updated_at = datetime('now') WHERE id = ?",
-                )
-                .bind(next)
-                .bind(recurring.id)
-                .execute(pool)
-                .await;
-            } else {
-                let _ = sqlx::query(
-                    "UPDATE recurring_transactions SET next_occurrence = ?, updated_at = datetime('now') WHERE id = ?",
-                )
-                .bind(next)
-                .bind(recurring.id)
-                .execute(pool)
-                .await;
-            }
-
-            created_count += 1;
         }
     }
 
@@ -112,7 +95,109 @@ pub async fn process_due_recurring(
     })
 }
 
-fn calculate_next_occurrence(current: chrono::DateTime<Utc>, frequency: &str) -> chrono::DateTime<Utc> {
+/// Create the transaction of one occurrence, with its category and balance
+/// change. False when it couldn't be posted (no rate yet for a foreign
+/// currency item), leaving it and later occurrences due.
+async fn post_occurrence(
+    pool: &SqlitePool,
+    recurring: &RecurringTransaction,
+    account_currency: &str,
+    occurrence: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    // A loan payment follows its schedule, the last one settling what's left
+    let loan_payment = match loans::loan_for_recurring(pool, recurring.id).await? {
+        Some(loan) => loans::payment_on(&loan, occurrence.date_naive()).map(|row| (loan, row)),
+        None => None,
+    };
+
+    // (posted amount, original amount, original currency, rate)
+    let (amount, original_amount, original_currency, exchange_rate) =
+        match (&loan_payment, charge_currency(recurring, account_currency)) {
+            (Some((_, row)), _) => (row.payment, None, None, None),
+            (None, Some(currency)) => {
+                let rate = fx::conversion_rate(pool, currency, account_currency, Some(occurrence)).await?;
+                match rate {
+                    Some(rate) => (
+                        recurring.amount * rate,
+                        Some(recurring.amount),
+                        Some(currency.to_uppercase()),
+                        Some(rate),
+                    ),
+                    None => return Ok(false),
+                }
+            }
+            (None, None) => (recurring.amount, None, None, None),
+        };
+    let (base_amount, base_currency) =
+        fx::base_amount_snapshot(pool, recurring.account_id, amount, occurrence)
+            .await?
+            .unzip();
+
+    let result = sqlx::query(
+        "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, recurring_transaction_id, original_amount, original_currency, exchange_rate, base_amount, base_currency) 
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(recurring.account_id)
+    .bind(amount)
+    .bind(&recurring.transaction_type)
+    .bind(&recurring.description)
+    .bind(occurrence)
+    .bind(recurring.id)
+    .bind(original_amount)
+    .bind(original_currency)
+    .bind(exchange_rate)
+    .bind(base_amount)
+    .bind(base_currency)
+    .execute(pool)
+    .await;
+
+    let Ok(res) = result else {
+        return Ok(false);
+    };
+    let transaction_id = res.last_insert_rowid();
+
+    // Link category if exists
+    if let Some((loan, row)) = &loan_payment {
+        loans::book_payment(pool, loan, row, transaction_id, recurring.description.as_deref(), occurrence).await?;
+    } else if let Some(category_id) = recurring.category_id {
+        let _ = sqlx::query(
+            "INSERT INTO transaction_categories (transaction_id, category_id, amount) 
+             VALUES (?, ?, ?)",
+        )
+        .bind(transaction_id)
+        .bind(category_id)
+        .bind(amount.abs())
+        .execute(pool)
+        .await;
+    }
+
+    // Update account balance
+    let balance_change = if recurring.transaction_type == "income" {
+        amount
+    } else {
+        -amount.abs()
+    };
+
+    let _ = sqlx::query(
+        "UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?",
+    )
+    .bind(balance_change)
+    .bind(recurring.account_id)
+    .execute(pool)
+    .await;
+
+    Ok(true)
+}
+
+/// Billing currency of a recurring item when it differs from the account currency.
+pub fn charge_currency<'a>(recurring: &'a RecurringTransaction, account_currency: &str) -> Option<&'a str> {
+    recurring
+        .currency
+        .as_deref()
+        .filter(|c| !c.is_empty() && !c.eq_ignore_ascii_case(account_currency))
+}
+
+pub fn calculate_next_occurrence(current: chrono::DateTime<Utc>, frequency: &str) -> chrono::DateTime<Utc> {
     match frequency {
         "daily" => current + Duration::days(1),
         "weekly" => current + Duration::weeks(1),
@@ -164,3 +249,144 @@ fn days_in_month(year: i32, month: u32) -> u32 {
 fn is_leap_year(year: i32) -> bool {
     (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Transaction;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_process_due_recurring_posts_and_advances() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(500.0).insert(&pool).await;
+        let due = Utc.with_ymd_and_hms(2025, 1, 31, 0, 0, 0).unwrap();
+        let rent = testing::recurring(account.id).amount(200.0).due(due).insert(&pool).await;
+        testing::recurring(account.id)
+            .due(Utc::now() + Duration::days(10))
+            .insert(&pool)
+            .await;
+
+        let result = process_due_recurring(&pool, due + Duration::days(1)).await.unwrap();
+        assert_eq!((result.due, result.created), (1, 1));
+
+        let posted: Vec<Transaction> = sqlx::query_as("SELECT * FROM transactions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0].recurring_transaction_id, Some(rent.id));
+        assert_eq!(posted[0].transaction_date, due);
+
+        let next: chrono::DateTime<Utc> =
+            sqlx::query_scalar("SELECT next_occurrence FROM recurring_transactions WHERE id = ?")
+                .bind(rent.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2025, 2, 28, 0, 0, 0).unwrap());
+
+        let balance: f64 = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(account.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balance, 300.0);
+    }
+
+    #[tokio::test]
+    async fn test_process_due_recurring_converts_foreign_currency() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).currency("CAD").insert(&pool).await;
+        let due = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let subscription = testing::recurring(account.id)
+            .amount(10.0)
+            .currency("USD")
+            .due(due)
+            .insert(&pool)
+            .await;
+
+        // No USD rate yet: the item stays due
+        let result = process_due_recurring(&pool, due + Duration::days(1)).await.unwrap();
+        assert_eq!((result.due, result.created), (1, 0));
+
+        testing::rate(&pool, "USD", "CAD", 1.4, due - Duration::days(1)).await;
+        let result = process_due_recurring(&pool, due + Duration::days(1)).await.unwrap();
+        assert_eq!(result.created, 1);
+
+        let posted: Transaction = sqlx::query_as("SELECT * FROM transactions WHERE recurring_transaction_id = ?")
+            .bind(subscription.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!((posted.amount - 14.0).abs() < 1e-9);
+        assert_eq!(posted.original_amount, Some(10.0));
+        assert_eq!(posted.original_currency.as_deref(), Some("USD"));
+    }
+
+    #[tokio::test]
+    async fn test_process_due_recurring_deactivates_after_end_date() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let due = Utc::now() - Duration::days(1);
+        let gym = testing::recurring(account.id)
+            .frequency("weekly")
+            .due(due)
+            .end_date(due + Duration::days(3))
+            .insert(&pool)
+            .await;
+
+        assert_eq!(process_due_recurring(&pool, Utc::now()).await.unwrap().created, 1);
+
+        let is_active: bool =
+            sqlx::query_scalar("SELECT is_active FROM recurring_transactions WHERE id = ?")
+                .bind(gym.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(!is_active);
+        assert_eq!(process_due_recurring(&pool, Utc::now()).await.unwrap().due, 0);
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_policy_after_missed_weeks() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let due = Utc.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap();
+        let now = due + Duration::days(22); // Four weekly occurrences missed
+        let all = testing::recurring(account.id).frequency("weekly").due(due).insert(&pool).await;
+        let latest = testing::recurring(account.id)
+            .frequency("weekly")
+            .catch_up("latest")
+            .due(due)
+            .insert(&pool)
+            .await;
+
+        assert_eq!(process_due_recurring(&pool, now).await.unwrap().created, 5);
+
+        for (item, posted) in [(&all, vec![3, 10, 17, 24]), (&latest, vec![24])] {
+            let dates: Vec<chrono::DateTime<Utc>> = sqlx::query_scalar(
+                "SELECT transaction_date FROM transactions WHERE recurring_transaction_id = ? ORDER BY transaction_date",
+            )
+            .bind(item.id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+            let days: Vec<u32> = dates.iter().map(|d| d.day()).collect();
+            assert_eq!(days, posted);
+
+            // Both pick up again from the next future occurrence
+            let next: chrono::DateTime<Utc> =
+                sqlx::query_scalar("SELECT next_occurrence FROM recurring_transactions WHERE id = ?")
+                    .bind(item.id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(next, Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// reimbursements.rs
+// Expenses paid back later, e.g. work travel reimbursed by an employer. The
+// incoming reimbursement points at the expense through
+// reimburses_transaction_id, and category analytics only count what the
+// expense still costs after its reimbursements.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::models::{ReimbursedExpense, Transaction};
+
+/// SQL share (0 to 1) of the expense row aliased `t` not yet paid back by its
+/// reimbursements; category spending scales each split by it
+pub const UNREIMBURSED_SHARE: &str = "MAX(0.0, 1.0 - COALESCE((
+    SELECT SUM(ABS(r.amount)) FROM transactions r WHERE r.reimburses_transaction_id = t.id
+) / NULLIF(ABS(t.amount), 0.0), 0.0))";
+
+/// Why `reimbursement` can't be linked as paying back `expense`, if anything.
+/// Both must belong to the same user; that's checked by the caller.
+pub fn validate_link(expense: &Transaction, reimbursement: &Transaction) -> Result<(), String> {
+    if expense.id == reimbursement.id {
+        return Err("A transaction can't reimburse itself".to_string());
+    }
+    if expense.transaction_type != "expense" {
+        return Err("Only expenses can be reimbursed".to_string());
+    }
+    if reimbursement.transaction_type != "income" {
+        return Err("The reimbursement must be an income transaction".to_string());
+    }
+    if expense.transfer_group_id.is_some() || reimbursement.transfer_group_id.is_some() {
+        return Err("Transfers between your accounts can't be linked as reimbursements".to_string());
+    }
+    if reimbursement.transaction_date < expense.transaction_date {
+        return Err("The reimbursement must come after the expense".to_string());
+    }
+    match reimbursement.reimburses_transaction_id {
+        Some(other) if other != expense.id => Err(format!(
+            "Transaction {} already reimburses transaction {}",
+            reimbursement.id, other
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// What an expense of `amount` still costs once `reimbursed` has been paid back
+pub fn net_amount(amount: f64, reimbursed: f64) -> f64 {
+    (amount.abs() - reimbursed).max(0.0)
+}
+
+/// Link the reimbursement to the expense. Call validate_link first.
+pub async fn link(pool: &SqlitePool, expense_id: i64, reimbursement_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE transactions SET reimburses_transaction_id = ?, updated_at = datetime('now') WHERE id = ?")
+        .bind(expense_id)
+        .bind(reimbursement_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Unlink the reimbursement from the expense; false if it wasn't linked to it
+pub async fn unlink(pool: &SqlitePool, expense_id: i64, reimbursement_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE transactions SET reimburses_transaction_id = NULL, updated_at = datetime('now')
+         WHERE id = ? AND reimburses_transaction_id = ?",
+    )
+    .bind(reimbursement_id)
+    .bind(expense_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// The expense with its reimbursements, oldest first, and what it still costs
+pub async fn reimbursed_expense(pool: &SqlitePool, expense: Transaction) -> Result<ReimbursedExpense, sqlx::Error> {
+    let reimbursements = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE reimburses_transaction_id = ? ORDER BY transaction_date, id",
+    )
+    .bind(expense.id)
+    .fetch_all(pool)
+    .await?;
+    let reimbursed_amount: f64 = reimbursements.iter().map(|r| r.amount.abs()).sum();
+    Ok(ReimbursedExpense {
+        net_amount: net_amount(expense.amount, reimbursed_amount),
+        expense,
+        reimbursements,
+        reimbursed_amount,
+    })
+}
+
+/// Amount paid back so far on each of the user's reimbursed expenses
+pub async fn reimbursed_amounts(pool: &SqlitePool, user_id: i64) -> Result<HashMap<i64, f64>, sqlx::Error> {
+    let rows: Vec<(i64, f64)> = sqlx::query_as(
+        "SELECT r.reimburses_transaction_id, SUM(ABS(r.amount)) FROM transactions r
+         JOIN accounts a ON a.id = r.account_id
+         WHERE a.user_id = ? AND r.reimburses_transaction_id IS NOT NULL
+         GROUP BY r.reimburses_transaction_id",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{charts, testing};
+    use chrono::{Duration, Utc};
+
+    #[tokio::test]
+    async fn test_reimbursements_net_category_spending() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let travel = testing::category(&pool, user.id, "Work travel").await;
+        let now = Utc::now();
+        let flight = testing::txn(account.id).amount(600.0).category(travel.id).date(now - Duration::days(3)).insert(&pool).await;
+        let refund = testing::txn(account.id).amount(450.0).income().date(now - Duration::days(1)).insert(&pool).await;
+        let early = testing::txn(account.id).amount(50.0).income().date(now - Duration::days(5)).insert(&pool).await;
+
+        assert!(validate_link(&flight, &early).is_err());
+        assert!(validate_link(&refund, &flight).is_err());
+        assert!(validate_link(&flight, &flight).is_err());
+        validate_link(&flight, &refund).unwrap();
+        link(&pool, flight.id, refund.id).await.unwrap();
+
+        let spending = charts::category_spending(&pool, user.id, now - Duration::days(7), now).await.unwrap();
+        assert_eq!((spending[0].category_id, spending[0].total_amount), (travel.id, 150.0));
+
+        let linked = reimbursed_expense(&pool, flight.clone()).await.unwrap();
+        assert_eq!((linked.reimbursed_amount, linked.net_amount), (450.0, 150.0));
+        assert_eq!(linked.reimbursements[0].reimburses_transaction_id, Some(flight.id));
+        assert_eq!(net_amount(-100.0, 250.0), 0.0);
+        assert_eq!(reimbursed_amounts(&pool, user.id).await.unwrap()[&flight.id], 450.0);
+
+        assert!(unlink(&pool, flight.id, refund.id).await.unwrap());
+        assert!(!unlink(&pool, flight.id, refund.id).await.unwrap());
+        let spending = charts::category_spending(&pool, user.id, now - Duration::days(7), now).await.unwrap();
+        assert_eq!(spending[0].total_amount, 600.0);
+    }
+}
@@ -0,0 +1,333 @@
+// remote.rs
+// Remote mode: the TUI on a server's REST API instead of a database file
+// (`tui --remote http://host:8080 --token TOKEN`), so a household can share one
+// server from several terminals. The signed-in user's accounts, categories,
+// transactions, recurring transactions and the exchange rates are copied into
+// an in-memory mirror, which the screens read like a local database. The
+// mirror is read-only: the writes the TUI makes remotely (transactions,
+// transfers, new accounts and categories) go through the API and the mirror
+// is synced again after them; any other write fails instead of being lost on
+// the next sync.
+
+use chrono::DateTime;
+use reqwest::{Client, Method};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::models::{
+    ApiResponse, CreateAccount, CreateCategory, CreateTransaction, CreateTransfer,
+    PaginatedResponse, UpdateTransaction, UpdateTransactionStatus, User,
+};
+use crate::profiles;
+
+/// How often the TUI pulls the server's data again while idle
+pub const SYNC_SECONDS: u64 = 30;
+
+/// Exchange rates fetched per request while syncing
+const RATE_PAGE_SIZE: i64 = 500;
+
+/// Rows from the server, table by table
+pub type Snapshot = Vec<(&'static str, Vec<Value>)>;
+
+#[derive(Debug, Deserialize)]
+struct Created {
+    id: i64,
+}
+
+/// The data of an ApiResponse, or its message when the call failed
+async fn data<T: DeserializeOwned>(path: &str, response: reqwest::Response) -> Result<T, String> {
+    let status = response.status();
+    let body: ApiResponse<T> = response
+        .json()
+        .await
+        .map_err(|e| format!("{} answered {}: {}", path, status, e))?;
+    match body.data {
+        Some(data) if body.success => Ok(data),
+        _ => Err(body.message.unwrap_or_else(|| format!("{} answered {}", path, status))),
+    }
+}
+
+/// Session on a server, from `--remote URL --token TOKEN`
+pub struct RemoteClient {
+    base_url: String,
+    token: String,
+    client: Client,
+    pub user: User,
+}
+
+impl RemoteClient {
+    /// Check the token with GET /auth/session; the session's user is the one
+    /// the TUI signs in as
+    pub async fn connect(base_url: &str, token: &str) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let response = client
+            .get(format!("{}/auth/session", base_url))
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| format!("Cannot reach {}: {}", base_url, e))?;
+        let user = data("/auth/session", response).await?;
+        Ok(Self { base_url, token: token.to_string(), client, user })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn response(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<reqwest::Response, String> {
+        let mut request = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path))
+            .bearer_auth(&self.token);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        request
+            .send()
+            .await
+            .map_err(|e| format!("Cannot reach {}: {}", self.base_url, e))
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<T, String> {
+        data(path, self.response(method, path, body).await?).await
+    }
+
+    /// Like call, for endpoints whose data isn't needed (or is null)
+    async fn send(&self, method: Method, path: &str, body: Option<&impl Serialize>) -> Result<(), String> {
+        let response = self.response(method, path, body).await?;
+        let status = response.status();
+        let body: ApiResponse<IgnoredAny> = response
+            .json()
+            .await
+            .map_err(|e| format!("{} answered {}: {}", path, status, e))?;
+        match body.success {
+            true => Ok(()),
+            false => Err(body.message.unwrap_or_else(|| format!("{} answered {}", path, status))),
+        }
+    }
+
+    /// The user's rows and the exchange rates, as the API returns them
+    pub async fn snapshot(&self) -> Result<Snapshot, String> {
+        let summary_path = format!("/export/summary/json?user_id={}", self.user.id);
+        let summary: Value = self
+            .response(Method::GET, &summary_path, None::<&()>)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("{}: {}", summary_path, e))?;
+        let rows = |key: &str| summary[key].as_array().cloned().unwrap_or_default();
+
+        let rate_sources: Vec<Value> = self.call(Method::GET, "/rate-sources", None::<&()>).await?;
+        let mut exchange_rates = Vec::new();
+        for page in 1.. {
+            let path = format!("/exchange-rates?page={}&page_size={}", page, RATE_PAGE_SIZE);
+            let rates: PaginatedResponse<Value> = self.call(Method::GET, &path, None::<&()>).await?;
+            exchange_rates.extend(rates.items);
+            if page >= rates.total_pages {
+                break;
+            }
+        }
+
+        // The API never shows the password hash; the mirror only needs the column filled
+        let mut user = serde_json::to_value(&self.user).map_err(|e| e.to_string())?;
+        user["password_hash"] = Value::from("");
+
+        Ok(vec![
+            ("users", vec![user]),
+            ("accounts", rows("accounts")),
+            ("categories", rows("categories")),
+            ("transactions", rows("transactions")),
+            ("transaction_categories", rows("transaction_categories")),
+            ("recurring_transactions", rows("recurring_transactions")),
+            ("rate_sources", rate_sources),
+            ("exchange_rates", exchange_rates),
+        ])
+    }
+
+    /// A new mirror of the server, filled
+    pub async fn mirror(&self) -> Result<SqlitePool, String> {
+        let pool = open_mirror().await.map_err(|e| e.to_string())?;
+        self.sync(&pool).await?;
+        Ok(pool)
+    }
+
+    /// Replace the mirror's rows with the server's current ones
+    pub async fn sync(&self, pool: &SqlitePool) -> Result<(), String> {
+        let snapshot = self.snapshot().await?;
+        load_snapshot(pool, &snapshot).await.map_err(|e| format!("Cannot update the mirror: {}", e))
+    }
+
+    pub async fn create_transaction(&self, data: &CreateTransaction) -> Result<i64, String> {
+        let created: Created = self.call(Method::POST, "/transactions", Some(data)).await?;
+        Ok(created.id)
+    }
+
+    /// Update a transaction, with its splits when `data.categories` is set
+    pub async fn update_transaction(&self, id: i64, data: &UpdateTransaction) -> Result<(), String> {
+        self.send(Method::PUT, &format!("/transactions/{}", id), Some(data)).await
+    }
+
+    pub async fn set_transaction_status(&self, id: i64, status: &str) -> Result<(), String> {
+        let data = UpdateTransactionStatus { status: status.to_string() };
+        self.send(Method::PUT, &format!("/transactions/{}/status", id), Some(&data)).await
+    }
+
+    pub async fn delete_transaction(&self, id: i64) -> Result<(), String> {
+        self.send(Method::DELETE, &format!("/transactions/{}", id), None::<&()>).await
+    }
+
+    pub async fn create_transfer(&self, data: &CreateTransfer) -> Result<(), String> {
+        self.send(Method::POST, "/transfers", Some(data)).await
+    }
+
+    pub async fn create_account(&self, data: &CreateAccount) -> Result<i64, String> {
+        let created: Created = self.call(Method::POST, "/accounts", Some(data)).await?;
+        Ok(created.id)
+    }
+
+    pub async fn create_category(&self, data: &CreateCategory) -> Result<i64, String> {
+        let created: Created = self.call(Method::POST, "/categories", Some(data)).await?;
+        Ok(created.id)
+    }
+}
+
+/// An empty in-memory database with the current schema. One connection that
+/// is never closed, since the data lives and dies with it; foreign keys are
+/// off so tables can be filled in any order.
+pub async fn open_mirror() -> Result<SqlitePool, sqlx::Error> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await?;
+    profiles::migrate(&pool).await?;
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&pool).await?;
+    sqlx::query("PRAGMA query_only = ON").execute(&pool).await?;
+    Ok(pool)
+}
+
+/// Replace every snapshot table's rows, keeping the server's ids
+pub async fn load_snapshot(pool: &SqlitePool, snapshot: &Snapshot) -> Result<(), sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("PRAGMA query_only = OFF").execute(&mut *conn).await?;
+    let result = async {
+        sqlx::query("BEGIN").execute(&mut *conn).await?;
+        for (table, rows) in snapshot {
+            replace_rows(&mut conn, table, rows).await?;
+        }
+        sqlx::query("COMMIT").execute(&mut *conn).await?;
+        Ok(())
+    }
+    .await;
+    if result.is_err() {
+        let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+    }
+    sqlx::query("PRAGMA query_only = ON").execute(&mut *conn).await?;
+    result
+}
+
+/// Insert JSON rows into a table; fields that aren't columns are skipped
+async fn replace_rows(conn: &mut SqliteConnection, table: &str, rows: &[Value]) -> Result<(), sqlx::Error> {
+    let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?;
+    sqlx::query(&format!("DELETE FROM {}", table)).execute(&mut *conn).await?;
+
+    for row in rows {
+        let Some(fields) = row.as_object() else { continue };
+        let fields: Vec<(&String, &Value)> = fields.iter().filter(|(name, _)| columns.contains(name)).collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            fields.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", "),
+            vec!["?"; fields.len()].join(", ")
+        );
+        let mut query = sqlx::query(&sql);
+        for (_, value) in fields {
+            query = match value {
+                Value::Null => query.bind(None::<String>),
+                Value::Bool(b) => query.bind(*b),
+                Value::Number(n) => match n.as_i64() {
+                    Some(i) => query.bind(i),
+                    None => query.bind(n.as_f64()),
+                },
+                // Timestamps are stored the way local writes store them, so
+                // comparisons with bound dates work the same
+                Value::String(s) => match DateTime::parse_from_rfc3339(s) {
+                    Ok(at) => query.bind(at.to_utc()),
+                    Err(_) => query.bind(s.clone()),
+                },
+                other => query.bind(other.to_string()),
+            };
+        }
+        query.execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Account, Transaction};
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_mirror_keeps_server_ids_and_is_read_only() {
+        let server = testing::pool().await;
+        let user = testing::user().insert(&server).await;
+        testing::account(user.id).name("Filler").insert(&server).await;
+        let account = testing::account(user.id).name("Joint").currency("EUR").insert(&server).await;
+        let food = testing::category(&server, user.id, "Food").await;
+        let txn = testing::txn(account.id).amount(12.5).category(food.id).insert(&server).await;
+
+        let mut user_row = serde_json::to_value(&user).unwrap();
+        user_row["password_hash"] = Value::from("");
+        let split = serde_json::json!({ "id": 9, "transaction_id": txn.id, "category_id": food.id, "amount": 12.5 });
+        let snapshot: Snapshot = vec![
+            ("users", vec![user_row]),
+            ("accounts", vec![serde_json::to_value(&account).unwrap()]),
+            ("categories", vec![serde_json::to_value(&food).unwrap()]),
+            ("transactions", vec![serde_json::to_value(&txn).unwrap()]),
+            ("transaction_categories", vec![split]),
+        ];
+
+        let mirror = open_mirror().await.unwrap();
+        load_snapshot(&mirror, &snapshot).await.unwrap();
+        // Syncing again replaces rather than duplicates
+        load_snapshot(&mirror, &snapshot).await.unwrap();
+
+        let accounts = sqlx::query_as::<_, Account>("SELECT * FROM accounts").fetch_all(&mirror).await.unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!((accounts[0].id, accounts[0].currency.as_str()), (account.id, "EUR"));
+        let mirrored = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions").fetch_one(&mirror).await.unwrap();
+        assert_eq!((mirrored.id, mirrored.transaction_date), (txn.id, txn.transaction_date));
+        let split: i64 = sqlx::query_scalar("SELECT category_id FROM transaction_categories WHERE transaction_id = ?")
+            .bind(txn.id)
+            .fetch_one(&mirror)
+            .await
+            .unwrap();
+        assert_eq!(split, food.id);
+
+        let write = sqlx::query("DELETE FROM transactions").execute(&mirror).await;
+        assert!(write.unwrap_err().to_string().contains("readonly"));
+    }
+}
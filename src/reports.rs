@@ -0,0 +1,288 @@
+// reports.rs
+// Monthly summary reports, delivered to webhooks subscribed to "monthly-report".
+// Totals are in the user's base currency when one is set, each transaction at
+// the rate closest to its date
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use sqlx::SqlitePool;
+
+use crate::budgets;
+use crate::charts;
+use crate::exclusions;
+use crate::fx;
+use crate::models::{MonthlyReport, MonthlySummary};
+use crate::settings;
+use crate::webhooks;
+
+/// Event type a webhook lists in event_types to receive monthly reports.
+/// Reports are opt-in: "*" subscriptions don't get them.
+pub const MONTHLY_REPORT_EVENT: &str = "monthly-report";
+
+/// Categories listed in a report's top_categories
+const TOP_CATEGORIES: usize = 5;
+
+/// The "YYYY-MM" month before `now`
+pub fn previous_period(now: DateTime<Utc>) -> String {
+    let first = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap_or_default();
+    (first - Months::new(1)).format("%Y-%m").to_string()
+}
+
+/// Totals, budget variance and top spending categories for one month
+pub async fn monthly_report(
+    pool: &SqlitePool,
+    user_id: i64,
+    period: &str,
+) -> Result<Option<MonthlyReport>, sqlx::Error> {
+    let Some((start, end)) = budgets::parse_period(period) else {
+        return Ok(None);
+    };
+    let currency = settings::base_currency(pool, user_id).await?;
+    let totals = month_totals(pool, user_id, period, start, end, currency.as_deref()).await?;
+
+    let mut top_categories = charts::category_spending(pool, user_id, start, end).await?;
+    top_categories.truncate(TOP_CATEGORIES);
+
+    Ok(Some(MonthlyReport {
+        user_id,
+        period: period.to_string(),
+        currency,
+        totals,
+        budget_variance: budgets::budget_variance(pool, user_id, period).await?,
+        top_categories,
+    }))
+}
+
+/// Totals of the last `months` months up to and including the one of `now`,
+/// oldest first; months without transactions are all zero
+pub async fn monthly_totals(
+    pool: &SqlitePool,
+    user_id: i64,
+    months: u32,
+    now: DateTime<Utc>,
+) -> Result<Vec<MonthlySummary>, sqlx::Error> {
+    let current = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap_or_default();
+    let currency = settings::base_currency(pool, user_id).await?;
+    let mut totals = Vec::new();
+    for back in (0..months).rev() {
+        let period = (current - Months::new(back)).format("%Y-%m").to_string();
+        if let Some((start, end)) = budgets::parse_period(&period) {
+            totals.push(month_totals(pool, user_id, &period, start, end, currency.as_deref()).await?);
+        }
+    }
+    Ok(totals)
+}
+
+async fn month_totals(
+    pool: &SqlitePool,
+    user_id: i64,
+    period: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    base_currency: Option<&str>,
+) -> Result<MonthlySummary, sqlx::Error> {
+    if let Some(base_currency) = base_currency {
+        return converted_month_totals(pool, user_id, period, start, end, base_currency).await;
+    }
+    // Same figures as GET /analytics/monthly-summary for the month; transfers
+    // between the user's own accounts are neither income nor spending, and
+    // transactions excluded from reports are left out
+    let sql = format!(
+        "SELECT ? as month,
+                COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0.0 END), 0.0) as total_income,
+                COALESCE(SUM(CASE WHEN transaction_type = 'expense' THEN ABS(amount) ELSE 0.0 END), 0.0) as total_expense,
+                COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE -ABS(amount) END), 0.0) as net_change,
+                COUNT(*) as transaction_count
+         FROM transactions t
+         WHERE account_id IN (SELECT id FROM accounts WHERE user_id = ?)
+         AND transfer_group_id IS NULL AND {}
+         AND transaction_date >= ? AND transaction_date < ?",
+        exclusions::COUNTED
+    );
+    sqlx::query_as::<_, MonthlySummary>(&sql)
+        .bind(period)
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_one(pool)
+        .await
+}
+
+/// Type, amount, date, account currency, base_amount and base_currency of a transaction
+type ConversionRow = (String, f64, DateTime<Utc>, String, Option<f64>, Option<String>);
+
+/// month_totals with every amount in `base_currency`: the value recorded with
+/// the transaction when it's in that currency, else the amount at the rate
+/// closest to its date. Amounts with no rate at all count as recorded.
+async fn converted_month_totals(
+    pool: &SqlitePool,
+    user_id: i64,
+    period: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    base_currency: &str,
+) -> Result<MonthlySummary, sqlx::Error> {
+    let sql = format!(
+        "SELECT t.transaction_type, t.amount, t.transaction_date, a.currency, t.base_amount, t.base_currency
+         FROM transactions t
+         JOIN accounts a ON a.id = t.account_id
+         WHERE a.user_id = ? AND t.transfer_group_id IS NULL AND {}
+         AND t.transaction_date >= ? AND t.transaction_date < ?",
+        exclusions::COUNTED
+    );
+    let rows: Vec<ConversionRow> = sqlx::query_as(&sql)
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+    let mut converter = fx::Converter::new(pool);
+    let mut totals = MonthlySummary {
+        month: period.to_string(),
+        total_income: 0.0,
+        total_expense: 0.0,
+        net_change: 0.0,
+        transaction_count: rows.len() as i64,
+    };
+    for (transaction_type, amount, date, currency, base_amount, recorded_in) in rows {
+        let amount = match base_amount.filter(|_| recorded_in.as_deref() == Some(base_currency)) {
+            Some(base_amount) => base_amount,
+            None => amount * converter.rate(&currency, base_currency, date.date_naive()).await?.unwrap_or(1.0),
+        };
+        if transaction_type == "income" {
+            totals.total_income += amount;
+            totals.net_change += amount;
+        } else {
+            totals.total_expense += amount.abs();
+            totals.net_change -= amount.abs();
+        }
+    }
+    Ok(totals)
+}
+
+/// Queue last month's report for every active monthly-report webhook that
+/// existed during that month and hasn't received it yet. Meant to run
+/// periodically: the first run in a new month sends the reports, later runs
+/// find them already queued. Returns the number of outbox rows written.
+pub async fn enqueue_due_reports(pool: &SqlitePool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let period = previous_period(now);
+    let Some((_, period_end)) = budgets::parse_period(&period) else {
+        return Ok(0);
+    };
+
+    let webhooks: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT w.id, w.user_id FROM webhooks w
+         WHERE w.is_active = 1 AND datetime(w.created_at) < datetime(?)
+         AND (',' || REPLACE(w.event_types, ' ', '') || ',') LIKE ('%,' || ? || ',%')
+         AND NOT EXISTS (
+             SELECT 1 FROM event_outbox o
+             WHERE o.webhook_id = w.id AND o.event_type = ?
+             AND json_extract(o.payload, '$.data.period') = ?
+         )
+         ORDER BY w.user_id",
+    )
+    .bind(period_end)
+    .bind(MONTHLY_REPORT_EVENT)
+    .bind(MONTHLY_REPORT_EVENT)
+    .bind(&period)
+    .fetch_all(pool)
+    .await?;
+
+    let mut queued = 0;
+    let mut report: Option<MonthlyReport> = None;
+    for (webhook_id, user_id) in webhooks {
+        if report.as_ref().map(|r| r.user_id) != Some(user_id) {
+            report = monthly_report(pool, user_id, &period).await?;
+        }
+        let Some(report) = &report else { continue };
+        let payload = serde_json::to_value(report).unwrap_or_default();
+        let mut conn = pool.acquire().await?;
+        queued += webhooks::enqueue_for_webhook(&mut conn, webhook_id, MONTHLY_REPORT_EVENT, &payload).await?;
+    }
+    Ok(queued)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_previous_period_wraps_year() {
+        assert_eq!(previous_period(Utc.with_ymd_and_hms(2025, 1, 1, 0, 30, 0).unwrap()), "2024-12");
+        assert_eq!(previous_period(Utc.with_ymd_and_hms(2025, 7, 15, 12, 0, 0).unwrap()), "2025-06");
+    }
+
+    #[tokio::test]
+    async fn test_monthly_totals_cover_empty_months() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let may = Utc.with_ymd_and_hms(2025, 5, 20, 12, 0, 0).unwrap();
+        testing::txn(account.id).amount(42.0).date(may).insert(&pool).await;
+        testing::txn(account.id).amount(1000.0).income().date(may).insert(&pool).await;
+
+        let now = Utc.with_ymd_and_hms(2025, 7, 3, 9, 0, 0).unwrap();
+        let totals = monthly_totals(&pool, user.id, 3, now).await.unwrap();
+        let months: Vec<&str> = totals.iter().map(|t| t.month.as_str()).collect();
+        assert_eq!(months, ["2025-05", "2025-06", "2025-07"]);
+        assert_eq!((totals[0].total_income, totals[0].total_expense), (1000.0, 42.0));
+        assert_eq!(totals[1].transaction_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_monthly_totals_convert_into_base_currency() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let cad = testing::account(user.id).currency("CAD").insert(&pool).await;
+        let eur = testing::account(user.id).currency("EUR").insert(&pool).await;
+        let may = Utc.with_ymd_and_hms(2025, 5, 20, 12, 0, 0).unwrap();
+        testing::rate(&pool, "EUR", "CAD", 1.5, may).await;
+        testing::rate(&pool, "EUR", "CAD", 2.0, may + chrono::Duration::days(60)).await;
+        testing::txn(cad.id).amount(1000.0).income().date(may).insert(&pool).await;
+        testing::txn(eur.id).amount(100.0).date(may).insert(&pool).await;
+        settings::set(&pool, user.id, settings::BASE_CURRENCY, Some("CAD")).await.unwrap();
+
+        let now = Utc.with_ymd_and_hms(2025, 5, 31, 9, 0, 0).unwrap();
+        let totals = monthly_totals(&pool, user.id, 1, now).await.unwrap();
+        // The May rate, not the later one
+        assert_eq!((totals[0].total_income, totals[0].total_expense, totals[0].net_change), (1000.0, 150.0, 850.0));
+        let report = monthly_report(&pool, user.id, "2025-05").await.unwrap().unwrap();
+        assert_eq!(report.currency.as_deref(), Some("CAD"));
+    }
+
+    #[tokio::test]
+    async fn test_monthly_report_is_queued_once_for_subscribers() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let dining = testing::category(&pool, user.id, "Dining").await;
+        let june = Utc.with_ymd_and_hms(2025, 6, 10, 12, 0, 0).unwrap();
+        testing::txn(account.id).amount(42.0).category(dining.id).date(june).insert(&pool).await;
+        testing::txn(account.id).amount(1000.0).income().date(june).insert(&pool).await;
+
+        for event_types in ["monthly-report", "*"] {
+            sqlx::query("INSERT INTO webhooks (user_id, url, event_types, created_at) VALUES (?, 'http://localhost/hook', ?, '2025-05-01 00:00:00')")
+                .bind(user.id)
+                .bind(event_types)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let july = Utc.with_ymd_and_hms(2025, 7, 1, 1, 0, 0).unwrap();
+        assert_eq!(enqueue_due_reports(&pool, july).await.unwrap(), 1);
+        assert_eq!(enqueue_due_reports(&pool, july).await.unwrap(), 0);
+
+        let payload: String = sqlx::query_scalar("SELECT payload FROM event_outbox WHERE event_type = ?")
+            .bind(MONTHLY_REPORT_EVENT)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(body["data"]["period"], "2025-06");
+        assert_eq!(body["data"]["totals"]["total_income"], 1000.0);
+        assert_eq!(body["data"]["top_categories"][0]["category_name"], "Dining");
+    }
+}
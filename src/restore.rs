@@ -0,0 +1,486 @@
+// restore.rs
+// Bringing a financial_summary.json export (TUI option 4 or GET
+// /export/summary/json) back in: its accounts, categories, transactions, splits
+// and recurring transactions are recreated for a user. Every row gets a new id,
+// and references between rows are remapped through the ids the file used;
+// references to rows that aren't in the file are dropped rather than left
+// pointing at whatever has that id here.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::input;
+use crate::models::{AccountType, SummaryImportResult, TransactionCategory};
+use crate::transaction_status;
+
+// The file's rows. Fields added to the schema after the export format are
+// optional here, so older exports still load.
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummaryAccount {
+    pub id: i64,
+    #[serde(deserialize_with = "crate::input::line")]
+    pub name: String,
+    pub account_type: String,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub bank_name: Option<String>,
+    pub currency: String,
+    pub initial_balance: f64,
+    pub current_balance: f64,
+    #[serde(default)]
+    pub sort_order: i64,
+    #[serde(default)]
+    pub is_favorite: bool,
+    pub default_category_id: Option<i64>,
+    pub credit_limit: Option<f64>,
+    pub opened_on: Option<NaiveDate>,
+    #[serde(default)]
+    pub is_archived: bool,
+    pub closed_on: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummaryCategory {
+    pub id: i64,
+    #[serde(deserialize_with = "crate::input::line")]
+    pub name: String,
+    pub bucket: Option<String>,
+    pub parent_category_id: Option<i64>,
+    #[serde(default)]
+    pub exclude_from_reports: bool,
+}
+
+fn cleared() -> String {
+    "cleared".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummaryTransaction {
+    pub id: i64,
+    pub account_id: i64,
+    pub amount: f64,
+    pub transaction_type: String,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub description: Option<String>,
+    pub transaction_date: DateTime<Utc>,
+    #[serde(default, deserialize_with = "crate::input::optional_text")]
+    pub notes: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub reference: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant_city: Option<String>,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub merchant_country: Option<String>,
+    pub original_amount: Option<f64>,
+    pub original_currency: Option<String>,
+    pub exchange_rate: Option<f64>,
+    #[serde(default)]
+    pub fee: f64,
+    pub fee_category_id: Option<i64>,
+    pub recurring_transaction_id: Option<i64>,
+    pub base_amount: Option<f64>,
+    pub base_currency: Option<String>,
+    pub linked_transaction_id: Option<i64>,
+    #[serde(default = "cleared")]
+    pub status: String,
+    pub transfer_group_id: Option<i64>,
+    #[serde(default)]
+    pub exclude_from_reports: bool,
+    pub reimburses_transaction_id: Option<i64>,
+}
+
+fn fixed() -> String {
+    "fixed".to_string()
+}
+
+fn all() -> String {
+    "all".to_string()
+}
+
+fn active() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummaryRecurring {
+    pub id: i64,
+    pub account_id: i64,
+    pub category_id: Option<i64>,
+    pub amount: f64,
+    pub transaction_type: String,
+    #[serde(default, deserialize_with = "crate::input::optional_line")]
+    pub description: Option<String>,
+    pub frequency: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub next_occurrence: DateTime<Utc>,
+    #[serde(default = "active")]
+    pub is_active: bool,
+    #[serde(default = "fixed")]
+    pub classification: String,
+    pub currency: Option<String>,
+    #[serde(default = "all")]
+    pub catch_up: String,
+}
+
+/// A financial_summary.json file. The TUI's export names its user; the API's
+/// doesn't, so the user is given when importing it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummaryFile {
+    pub user_id: Option<i64>,
+    pub accounts: Vec<SummaryAccount>,
+    #[serde(default)]
+    pub categories: Vec<SummaryCategory>,
+    #[serde(default)]
+    pub transactions: Vec<SummaryTransaction>,
+    #[serde(default)]
+    pub transaction_categories: Vec<TransactionCategory>, // Splits; older exports lack them
+    #[serde(default)]
+    pub recurring_transactions: Vec<SummaryRecurring>,
+}
+
+/// Why the file can't be restored as it is, if anything: values the schema
+/// would reject, or accounts named the same
+pub fn validate(file: &SummaryFile) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+    for account in &file.accounts {
+        if account.name.trim().is_empty() {
+            return Err(format!("Account {} has no name", account.id));
+        }
+        input::check_length("Account name", Some(&account.name), input::MAX_NAME_LEN)?;
+        if AccountType::from_str(&account.account_type).is_none() {
+            return Err(format!("Account '{}' has an invalid type '{}'", account.name, account.account_type));
+        }
+        if !names.insert(account.name.to_lowercase()) {
+            return Err(format!("The file has more than one account named '{}'", account.name));
+        }
+    }
+    for category in &file.categories {
+        if category.name.trim().is_empty() {
+            return Err(format!("Category {} has no name", category.id));
+        }
+        input::check_length("Category name", Some(&category.name), input::MAX_NAME_LEN)?;
+    }
+    for t in &file.transactions {
+        if !["income", "expense", "transfer"].contains(&t.transaction_type.as_str()) {
+            return Err(format!("Transaction {} has an invalid type '{}'", t.id, t.transaction_type));
+        }
+        transaction_status::validate_status(&t.status).map_err(|e| format!("Transaction {}: {}", t.id, e))?;
+        if t.fee < 0.0 || !t.amount.is_finite() {
+            return Err(format!("Transaction {} has an invalid amount or fee", t.id));
+        }
+    }
+    for r in &file.recurring_transactions {
+        if !["income", "expense"].contains(&r.transaction_type.as_str())
+            || !["daily", "weekly", "monthly", "yearly"].contains(&r.frequency.as_str())
+            || !["fixed", "variable"].contains(&r.classification.as_str())
+            || !["all", "latest"].contains(&r.catch_up.as_str())
+        {
+            return Err(format!("Recurring transaction {} has an invalid type, frequency, classification or catch-up", r.id));
+        }
+    }
+    Ok(())
+}
+
+/// Names of the file's accounts the user already has, which would be
+/// duplicated by restoring it
+pub async fn conflicting_accounts(
+    pool: &SqlitePool,
+    user_id: i64,
+    file: &SummaryFile,
+) -> Result<Vec<String>, sqlx::Error> {
+    let existing: Vec<String> = sqlx::query_scalar("SELECT lower(name) FROM accounts WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(file
+        .accounts
+        .iter()
+        .filter(|a| existing.contains(&a.name.to_lowercase()))
+        .map(|a| a.name.clone())
+        .collect())
+}
+
+/// Recreate the file's rows for `user_id` in one DB transaction. Categories
+/// the user already has (by name) are reused; transactions, splits and
+/// recurring transactions of accounts missing from the file are skipped.
+/// Call validate and conflicting_accounts first.
+pub async fn restore(
+    pool: &SqlitePool,
+    user_id: i64,
+    file: &SummaryFile,
+) -> Result<SummaryImportResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut result = SummaryImportResult {
+        user_id,
+        accounts: 0,
+        categories_created: 0,
+        categories_matched: 0,
+        transactions: 0,
+        recurring_transactions: 0,
+        skipped: 0,
+    };
+
+    // Parents first, so sub-categories can point at them
+    let existing: HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>("SELECT name, id FROM categories WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+    let mut categories: Vec<&SummaryCategory> = file.categories.iter().collect();
+    categories.sort_by_key(|c| c.parent_category_id.is_some());
+    let mut category_ids: HashMap<i64, i64> = HashMap::new();
+    for category in categories {
+        if let Some(id) = existing.get(&category.name) {
+            category_ids.insert(category.id, *id);
+            result.categories_matched += 1;
+            continue;
+        }
+        let bucket = category.bucket.as_deref().filter(|b| ["needs", "wants", "savings"].contains(b));
+        let id = sqlx::query(
+            "INSERT INTO categories (user_id, name, bucket, parent_category_id, exclude_from_reports) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(&category.name)
+        .bind(bucket)
+        .bind(category.parent_category_id.and_then(|id| category_ids.get(&id)))
+        .bind(category.exclude_from_reports)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        category_ids.insert(category.id, id);
+        result.categories_created += 1;
+    }
+
+    let mut account_ids: HashMap<i64, i64> = HashMap::new();
+    for a in &file.accounts {
+        let id = sqlx::query(
+            "INSERT INTO accounts (user_id, name, account_type, bank_name, currency, initial_balance, current_balance,
+                 sort_order, is_favorite, default_category_id, credit_limit, opened_on, is_archived, closed_on)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(&a.name)
+        .bind(&a.account_type)
+        .bind(&a.bank_name)
+        .bind(a.currency.to_uppercase())
+        .bind(a.initial_balance)
+        .bind(a.current_balance)
+        .bind(a.sort_order)
+        .bind(a.is_favorite)
+        .bind(a.default_category_id.and_then(|id| category_ids.get(&id)))
+        .bind(a.credit_limit.filter(|limit| *limit > 0.0))
+        .bind(a.opened_on)
+        .bind(a.is_archived)
+        .bind(a.closed_on)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        account_ids.insert(a.id, id);
+        result.accounts += 1;
+    }
+
+    // Before transactions, which may have been generated by them
+    let mut recurring_ids: HashMap<i64, i64> = HashMap::new();
+    for r in &file.recurring_transactions {
+        let Some(account_id) = account_ids.get(&r.account_id) else {
+            result.skipped += 1;
+            continue;
+        };
+        let id = sqlx::query(
+            "INSERT INTO recurring_transactions (account_id, category_id, amount, transaction_type, description, frequency,
+                 start_date, end_date, next_occurrence, is_active, classification, currency, catch_up)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(account_id)
+        .bind(r.category_id.and_then(|id| category_ids.get(&id)))
+        .bind(r.amount)
+        .bind(&r.transaction_type)
+        .bind(&r.description)
+        .bind(&r.frequency)
+        .bind(r.start_date)
+        .bind(r.end_date.filter(|end| *end > r.start_date))
+        .bind(r.next_occurrence)
+        .bind(r.is_active)
+        .bind(&r.classification)
+        .bind(&r.currency)
+        .bind(&r.catch_up)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        recurring_ids.insert(r.id, id);
+        result.recurring_transactions += 1;
+    }
+
+    let mut transaction_ids: HashMap<i64, i64> = HashMap::new();
+    for t in &file.transactions {
+        let Some(account_id) = account_ids.get(&t.account_id) else {
+            result.skipped += 1;
+            continue;
+        };
+        let id = sqlx::query(
+            "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, notes, reference,
+                 merchant, merchant_city, merchant_country, original_amount, original_currency, exchange_rate, fee,
+                 fee_category_id, recurring_transaction_id, base_amount, base_currency, status, exclude_from_reports)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(account_id)
+        .bind(t.amount)
+        .bind(&t.transaction_type)
+        .bind(&t.description)
+        .bind(t.transaction_date)
+        .bind(&t.notes)
+        .bind(&t.reference)
+        .bind(&t.merchant)
+        .bind(&t.merchant_city)
+        .bind(&t.merchant_country)
+        .bind(t.original_amount)
+        .bind(&t.original_currency)
+        .bind(t.exchange_rate)
+        .bind(t.fee)
+        .bind(t.fee_category_id.and_then(|id| category_ids.get(&id)))
+        .bind(t.recurring_transaction_id.and_then(|id| recurring_ids.get(&id)))
+        .bind(t.base_amount)
+        .bind(&t.base_currency)
+        .bind(&t.status)
+        .bind(t.exclude_from_reports)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        transaction_ids.insert(t.id, id);
+        result.transactions += 1;
+    }
+
+    // Links between transactions, now that both ends have their new ids
+    for t in &file.transactions {
+        let Some(id) = transaction_ids.get(&t.id) else { continue };
+        let remap = |old: Option<i64>| old.and_then(|old| transaction_ids.get(&old).copied());
+        let links = (
+            remap(t.linked_transaction_id),
+            remap(t.transfer_group_id),
+            remap(t.reimburses_transaction_id),
+        );
+        if links == (None, None, None) {
+            continue;
+        }
+        sqlx::query(
+            "UPDATE transactions SET linked_transaction_id = ?, transfer_group_id = ?, reimburses_transaction_id = ? WHERE id = ?",
+        )
+        .bind(links.0)
+        .bind(links.1)
+        .bind(links.2)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for split in &file.transaction_categories {
+        let (Some(transaction_id), Some(category_id)) =
+            (transaction_ids.get(&split.transaction_id), category_ids.get(&split.category_id))
+        else {
+            continue;
+        };
+        sqlx::query("INSERT OR IGNORE INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)")
+            .bind(transaction_id)
+            .bind(category_id)
+            .bind(split.amount)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Transaction;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_restore_remaps_ids_and_reuses_categories() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        // Takes up the ids the file uses, so nothing may keep them
+        let decoy = testing::account(user.id).name("Decoy").insert(&pool).await;
+        testing::category(&pool, user.id, "Food").await;
+        assert_eq!(decoy.id, 1);
+
+        let file: SummaryFile = serde_json::from_str(
+            r#"{
+                "user_id": 99,
+                "accounts": [
+                    {"id": 1, "name": "Checking", "account_type": "checking", "currency": "usd",
+                     "initial_balance": 100.0, "current_balance": 60.0, "default_category_id": 8},
+                    {"id": 2, "name": "Savings", "account_type": "savings", "currency": "USD",
+                     "initial_balance": 0.0, "current_balance": 25.0}
+                ],
+                "categories": [
+                    {"id": 9, "name": "Groceries", "parent_category_id": 8},
+                    {"id": 8, "name": "Food"}
+                ],
+                "transactions": [
+                    {"id": 5, "account_id": 1, "amount": 15.0, "transaction_type": "expense",
+                     "description": "Market", "transaction_date": "2025-03-01T10:00:00Z", "recurring_transaction_id": 3},
+                    {"id": 6, "account_id": 1, "amount": 25.0, "transaction_type": "transfer",
+                     "transaction_date": "2025-03-02T10:00:00Z", "transfer_group_id": 6},
+                    {"id": 7, "account_id": 2, "amount": 25.0, "transaction_type": "income",
+                     "transaction_date": "2025-03-02T10:00:00Z", "transfer_group_id": 6, "reimburses_transaction_id": 40},
+                    {"id": 8, "account_id": 77, "amount": 1.0, "transaction_type": "expense",
+                     "transaction_date": "2025-03-02T10:00:00Z"}
+                ],
+                "transaction_categories": [{"id": 1, "transaction_id": 5, "category_id": 9, "amount": 15.0}],
+                "recurring_transactions": [
+                    {"id": 3, "account_id": 1, "category_id": 9, "amount": 15.0, "transaction_type": "expense",
+                     "frequency": "weekly", "start_date": "2025-01-01T00:00:00Z", "next_occurrence": "2025-03-08T00:00:00Z"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        validate(&file).unwrap();
+        assert!(conflicting_accounts(&pool, user.id, &file).await.unwrap().is_empty());
+
+        let result = restore(&pool, user.id, &file).await.unwrap();
+        assert_eq!((result.accounts, result.transactions, result.recurring_transactions), (2, 3, 1));
+        assert_eq!((result.categories_created, result.categories_matched, result.skipped), (1, 1, 1));
+        assert_eq!(conflicting_accounts(&pool, user.id, &file).await.unwrap(), vec!["Checking", "Savings"]);
+
+        let (checking, currency, default_category): (i64, String, Option<i64>) =
+            sqlx::query_as("SELECT id, currency, default_category_id FROM accounts WHERE name = 'Checking'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let food: i64 = sqlx::query_scalar("SELECT id FROM categories WHERE name = 'Food'").fetch_one(&pool).await.unwrap();
+        let (groceries, parent): (i64, Option<i64>) =
+            sqlx::query_as("SELECT id, parent_category_id FROM categories WHERE name = 'Groceries'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!((currency.as_str(), default_category, parent), ("USD", Some(food), Some(food)));
+
+        let transactions = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let market = &transactions[0];
+        assert_eq!(market.account_id, checking);
+        let recurring: i64 = sqlx::query_scalar("SELECT id FROM recurring_transactions").fetch_one(&pool).await.unwrap();
+        assert_eq!(market.recurring_transaction_id, Some(recurring));
+        let split: i64 = sqlx::query_scalar("SELECT category_id FROM transaction_categories WHERE transaction_id = ?")
+            .bind(market.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(split, groceries);
+        // Both transfer legs are grouped under the new debit leg; the unknown reimbursement is dropped
+        assert_eq!(transactions[1].transfer_group_id, Some(transactions[1].id));
+        assert_eq!(transactions[2].transfer_group_id, Some(transactions[1].id));
+        assert_eq!(transactions[2].reimburses_transaction_id, None);
+    }
+}
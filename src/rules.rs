@@ -0,0 +1,285 @@
+// rules.rs
+// Category rules: description patterns that suggest a category on import
+
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::collections::HashSet;
+
+use crate::models::{CategoryRule, RecategorizeRequest};
+
+/// Rule whose pattern occurs in the text (case-insensitive). When several
+/// match, the longest pattern wins as the most specific one.
+pub fn matching_rule<'a>(rules: &'a [CategoryRule], text: &str) -> Option<&'a CategoryRule> {
+    let text = text.to_lowercase();
+    rules
+        .iter()
+        .filter(|r| text.contains(&r.pattern.trim().to_lowercase()))
+        .max_by_key(|r| r.pattern.trim().len())
+}
+
+pub async fn rules_for_user(pool: &SqlitePool, user_id: i64) -> Result<Vec<CategoryRule>, sqlx::Error> {
+    sqlx::query_as::<_, CategoryRule>("SELECT * FROM category_rules WHERE user_id = ? ORDER BY id")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Create a rule, or point an existing rule with the same pattern at the new category.
+pub async fn create_rule(
+    pool: &SqlitePool,
+    user_id: i64,
+    pattern: &str,
+    category_id: i64,
+) -> Result<CategoryRule, sqlx::Error> {
+    let pattern = pattern.trim();
+    sqlx::query(
+        "INSERT INTO category_rules (user_id, pattern, category_id) VALUES (?, ?, ?)
+         ON CONFLICT(user_id, pattern) DO UPDATE SET category_id = excluded.category_id",
+    )
+    .bind(user_id)
+    .bind(pattern)
+    .bind(category_id)
+    .execute(pool)
+    .await?;
+
+    sqlx::query_as::<_, CategoryRule>("SELECT * FROM category_rules WHERE user_id = ? AND pattern = ?")
+        .bind(user_id)
+        .bind(pattern)
+        .fetch_one(pool)
+        .await
+}
+
+/// LIKE pattern matching `text` anywhere, with wildcards in it escaped
+fn contains_pattern(text: &str) -> String {
+    let escaped = text
+        .trim()
+        .to_lowercase()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Move the matching transactions in one DB transaction; returns how many changed.
+/// With from_category_id only that split moves (merged into an existing split of
+/// the target category), otherwise the whole amount is booked to the target.
+pub async fn recategorize(pool: &SqlitePool, request: &RecategorizeRequest) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let changed = recategorize_in(&mut tx, request).await?;
+    tx.commit().await?;
+    Ok(changed.len() as u64)
+}
+
+/// Apply every rule of the user to their existing transactions, less specific
+/// patterns first so the longest matching pattern wins as on import.
+pub async fn apply_rules(pool: &SqlitePool, user_id: i64) -> Result<u64, sqlx::Error> {
+    let mut rules = rules_for_user(pool, user_id).await?;
+    rules.sort_by_key(|r| r.pattern.trim().len());
+
+    let mut tx = pool.begin().await?;
+    let mut changed = HashSet::new();
+    for rule in &rules {
+        let request = RecategorizeRequest {
+            user_id,
+            pattern: Some(rule.pattern.clone()),
+            start_date: None,
+            end_date: None,
+            from_category_id: None,
+            to_category_id: rule.category_id,
+        };
+        changed.extend(recategorize_in(&mut tx, &request).await?);
+    }
+    tx.commit().await?;
+    Ok(changed.len() as u64)
+}
+
+async fn recategorize_in(
+    tx: &mut Transaction<'_, Sqlite>,
+    request: &RecategorizeRequest,
+) -> Result<Vec<i64>, sqlx::Error> {
+    let pattern = request
+        .pattern
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+        .map(contains_pattern);
+
+    let mut sql = String::from(
+        "SELECT t.id, t.amount FROM transactions t
+         JOIN accounts a ON t.account_id = a.id
+         WHERE a.user_id = ? AND t.transaction_type != 'transfer'",
+    );
+    if pattern.is_some() {
+        sql.push_str(
+            " AND (LOWER(COALESCE(t.merchant, '')) LIKE ? ESCAPE '\\'
+                  OR LOWER(COALESCE(t.description, '')) LIKE ? ESCAPE '\\')",
+        );
+    }
+    if request.start_date.is_some() {
+        sql.push_str(" AND t.transaction_date >= ?");
+    }
+    if request.end_date.is_some() {
+        sql.push_str(" AND t.transaction_date <= ?");
+    }
+    if request.from_category_id.is_some() {
+        sql.push_str(
+            " AND EXISTS (SELECT 1 FROM transaction_categories tc WHERE tc.transaction_id = t.id AND tc.category_id = ?)",
+        );
+    }
+
+    let mut query = sqlx::query_as::<_, (i64, f64)>(&sql).bind(request.user_id);
+    if let Some(ref pattern) = pattern {
+        query = query.bind(pattern).bind(pattern);
+    }
+    if let Some(start_date) = request.start_date {
+        query = query.bind(start_date);
+    }
+    if let Some(end_date) = request.end_date {
+        query = query.bind(end_date);
+    }
+    if let Some(from_category_id) = request.from_category_id {
+        query = query.bind(from_category_id);
+    }
+    let candidates = query.fetch_all(&mut **tx).await?;
+
+    let to = request.to_category_id;
+    let mut changed = Vec::new();
+    for (transaction_id, amount) in candidates {
+        let splits: Vec<(i64, f64)> =
+            sqlx::query_as("SELECT category_id, amount FROM transaction_categories WHERE transaction_id = ?")
+                .bind(transaction_id)
+                .fetch_all(&mut **tx)
+                .await?;
+
+        match request.from_category_id {
+            Some(from) => {
+                let moved: f64 = splits.iter().filter(|(c, _)| *c == from).map(|(_, a)| a).sum();
+                if splits.iter().any(|(c, _)| *c == to) {
+                    sqlx::query(
+                        "UPDATE transaction_categories SET amount = amount + ? WHERE transaction_id = ? AND category_id = ?",
+                    )
+                    .bind(moved)
+                    .bind(transaction_id)
+                    .bind(to)
+                    .execute(&mut **tx)
+                    .await?;
+                    sqlx::query("DELETE FROM transaction_categories WHERE transaction_id = ? AND category_id = ?")
+                        .bind(transaction_id)
+                        .bind(from)
+                        .execute(&mut **tx)
+                        .await?;
+                } else {
+                    sqlx::query(
+                        "UPDATE transaction_categories SET category_id = ? WHERE transaction_id = ? AND category_id = ?",
+                    )
+                    .bind(to)
+                    .bind(transaction_id)
+                    .bind(from)
+                    .execute(&mut **tx)
+                    .await?;
+                }
+            }
+            None => {
+                if splits.len() == 1 && splits[0].0 == to {
+                    continue; // Already there
+                }
+                sqlx::query("DELETE FROM transaction_categories WHERE transaction_id = ?")
+                    .bind(transaction_id)
+                    .execute(&mut **tx)
+                    .await?;
+                sqlx::query("INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)")
+                    .bind(transaction_id)
+                    .bind(to)
+                    .bind(amount.abs())
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+        changed.push(transaction_id);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::Utc;
+
+    fn rule(pattern: &str, category_id: i64) -> CategoryRule {
+        CategoryRule {
+            id: category_id,
+            user_id: 1,
+            pattern: pattern.to_string(),
+            category_id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_matching_rule_prefers_longest_pattern() {
+        let rules = vec![rule("amazon", 1), rule("Amazon Prime", 2)];
+
+        assert_eq!(matching_rule(&rules, "AMAZON MKTP US").unwrap().category_id, 1);
+        assert_eq!(matching_rule(&rules, "amazon prime*2k4").unwrap().category_id, 2);
+        assert!(matching_rule(&rules, "Starbucks").is_none());
+    }
+
+    async fn splits(pool: &SqlitePool, transaction_id: i64) -> Vec<(i64, f64)> {
+        sqlx::query_as("SELECT category_id, amount FROM transaction_categories WHERE transaction_id = ? ORDER BY category_id")
+            .bind(transaction_id)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_recategorize_by_pattern_and_old_category() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let shopping = testing::category(&pool, user.id, "Shopping").await;
+        let coffee = testing::category(&pool, user.id, "Coffee").await;
+        let starbucks = testing::txn(account.id).description("STARBUCKS #123").category(shopping.id).insert(&pool).await;
+        let percent = testing::txn(account.id).description("100% juice").insert(&pool).await;
+        let other = testing::txn(account.id).description("Amazon").category(shopping.id).insert(&pool).await;
+
+        let request = RecategorizeRequest {
+            user_id: user.id,
+            pattern: Some("starbucks".to_string()),
+            start_date: None,
+            end_date: None,
+            from_category_id: None,
+            to_category_id: coffee.id,
+        };
+        assert_eq!(recategorize(&pool, &request).await.unwrap(), 1);
+        assert_eq!(splits(&pool, starbucks.id).await, vec![(coffee.id, 10.0)]);
+        assert_eq!(recategorize(&pool, &request).await.unwrap(), 0); // Already there
+
+        // % is matched literally
+        let request = RecategorizeRequest { pattern: Some("0% j".to_string()), ..request };
+        assert_eq!(recategorize(&pool, &request).await.unwrap(), 1);
+        assert_eq!(splits(&pool, percent.id).await, vec![(coffee.id, 10.0)]);
+
+        // Everything left in Shopping
+        let request = RecategorizeRequest { pattern: None, from_category_id: Some(shopping.id), ..request };
+        assert_eq!(recategorize(&pool, &request).await.unwrap(), 1);
+        assert_eq!(splits(&pool, other.id).await, vec![(coffee.id, 10.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_apply_rules_prefers_longest_pattern() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let shopping = testing::category(&pool, user.id, "Shopping").await;
+        let streaming = testing::category(&pool, user.id, "Streaming").await;
+        create_rule(&pool, user.id, "Amazon Prime", streaming.id).await.unwrap();
+        create_rule(&pool, user.id, "amazon", shopping.id).await.unwrap();
+        let prime = testing::txn(account.id).description("AMAZON PRIME VIDEO").insert(&pool).await;
+        let order = testing::txn(account.id).description("Amazon.com order").insert(&pool).await;
+
+        assert_eq!(apply_rules(&pool, user.id).await.unwrap(), 2);
+        assert_eq!(splits(&pool, prime.id).await, vec![(streaming.id, 10.0)]);
+        assert_eq!(splits(&pool, order.id).await, vec![(shopping.id, 10.0)]);
+    }
+}
@@ -0,0 +1,199 @@
+// scrape_schedule.rs
+// Scheduled exchange-rate scraping for `serve`: SCRAPE_CRON holds a cron
+// expression (UTC) and every due run fetches the base currencies from the
+// FX_PROVIDER providers, skipping those whose rates for the day are stored
+// already
+
+use chrono::{DateTime, Datelike, Duration, DurationRound, TimeZone, Timelike, Utc};
+use sqlx::SqlitePool;
+use std::env;
+
+use crate::exchange_scraper::{self, ScrapeRunMetrics};
+use crate::rate_providers::{self, RateProviders};
+
+/// Allowed values per bit, one mask per cron field
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,     // Bit 0 is Sunday
+    any_day: bool,     // Day of month was '*'
+    any_weekday: bool, // Day of week was '*'
+}
+
+/// Bits `min..=max` that a cron field ("*", "5", "1-5", "*/15", "0,30") allows
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let invalid = || format!("Invalid cron field '{}' (allowed {}-{})", field, min, max);
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (a.parse().map_err(|_| invalid())?, b.parse().map_err(|_| invalid())?),
+                None => {
+                    let value = range.parse().map_err(|_| invalid())?;
+                    // "5/10" means from 5 to the end in steps of 10
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn allows(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+impl CronSchedule {
+    /// Five fields: minute hour day-of-month month day-of-week,
+    /// or one of @hourly, @daily, @weekly
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Cron expression '{}' needs five fields: minute hour day month weekday",
+                expr
+            ));
+        };
+
+        // 7 is Sunday as well
+        let weekdays = parse_field(weekday, 0, 7)?;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// Like cron, a restricted day of month and day of week match either one
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let day = allows(self.days, time.day());
+        let weekday = allows(self.weekdays, time.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// First matching minute strictly after `after`, within the next five years
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = after.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let limit = after + Duration::days(5 * 366);
+
+        while time < limit {
+            if !allows(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 { (time.year() + 1, 1) } else { (time.year(), time.month() + 1) };
+                time = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(time) {
+                time = time.duration_trunc(Duration::days(1)).ok()? + Duration::days(1);
+            } else if !allows(self.hours, time.hour()) {
+                time = time.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+            } else if !allows(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+/// The SCRAPE_CRON schedule; None (scheduling disabled) when unset
+pub fn from_env() -> Result<Option<CronSchedule>, String> {
+    match env::var("SCRAPE_CRON") {
+        Ok(expr) if !expr.trim().is_empty() => CronSchedule::parse(&expr).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Fetch the base currencies once, like `scrape_rates` without arguments,
+/// and record the run
+pub async fn run_scrape(pool: &SqlitePool, providers: &RateProviders) -> Result<ScrapeRunMetrics, sqlx::Error> {
+    let currencies = exchange_scraper::base_currencies(pool).await?;
+    let started_at = Utc::now();
+    let timer = std::time::Instant::now();
+    let mut rates_saved = 0;
+    let mut currencies_skipped = 0;
+    let mut errors = Vec::new();
+    let mut fetched_all = Vec::new();
+
+    for currency in &currencies {
+        match providers.smart_fetch(pool, currency).await {
+            Ok(fetched) if fetched.up_to_date => currencies_skipped += 1,
+            Ok(fetched) => {
+                match rate_providers::save_rates(pool, &fetched, Utc::now()).await {
+                    Ok(results) => rates_saved += rate_providers::saved_count(&results),
+                    Err(e) => errors.push(format!("{}: {}", currency, e)),
+                }
+                fetched_all.push(fetched);
+            }
+            Err(e) => errors.push(format!("{}: {}", currency, e)),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    }
+
+    let metrics = ScrapeRunMetrics {
+        provider: rate_providers::providers_used(providers.primary(), &fetched_all),
+        currencies,
+        started_at,
+        duration: timer.elapsed(),
+        rates_saved,
+        currencies_skipped,
+        errors,
+    };
+    exchange_scraper::record_scrape_run(pool, &metrics).await?;
+    Ok(metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        // June 2025: the 1st is a Sunday
+        Utc.with_ymd_and_hms(2025, 6, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_run() {
+        let daily = CronSchedule::parse("30 6 * * *").unwrap();
+        assert_eq!(daily.next_after(at(3, 5, 0)), Some(at(3, 6, 30)));
+        assert_eq!(daily.next_after(at(3, 6, 30)), Some(at(4, 6, 30)));
+
+        let weekdays = CronSchedule::parse("0 */6 * * 1-5").unwrap();
+        assert_eq!(weekdays.next_after(at(6, 19, 0)), Some(at(9, 0, 0))); // Friday evening -> Monday
+        assert_eq!(CronSchedule::parse("@daily").unwrap().next_after(at(3, 5, 0)), Some(at(4, 0, 0)));
+
+        // Day of month or Sunday (7)
+        let either = CronSchedule::parse("0 0 15 * 7").unwrap();
+        assert_eq!(either.next_after(at(2, 0, 0)), Some(at(8, 0, 0)));
+        assert_eq!(either.next_after(at(13, 0, 0)), Some(at(15, 0, 0)));
+
+        assert!(CronSchedule::parse("0 6 * *").is_err());
+        assert!(CronSchedule::parse("61 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+}
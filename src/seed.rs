@@ -1,6 +1,8 @@
 use chrono::{DateTime, Duration, Utc};
 use sqlx::SqlitePool;
 
+use crate::auth;
+
 /// Main seeding function - populates all tables with sample data
 pub async fn seed_database(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Check if database is already seeded
@@ -43,13 +45,15 @@ async fn is_database_seeded(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
 async fn seed_users(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     println!("👤 Seeding users...");
 
-    let users = vec![
-        ("alice_wang", "alice@example.com", "$argon2id$v=19$m=19456,t=2,p=1$VE3VyJmJqKmZmZmZmZmZmQ$Jmw/A8cPvgLKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGK"),
-        ("bob_chen", "bob@example.com", "$argon2id$v=19$m=19456,t=2,p=1$VE3VyJmJqKmZmZmZmZmZmQ$Jmw/A8cPvgLKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGK"),
-        ("carol_liu", "carol@example.com", "$argon2id$v=19$m=19456,t=2,p=1$VE3VyJmJqKmZmZmZmZmZmQ$Jmw/A8cPvgLKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGKKPGK"),
+    let users = [
+        ("alice_wang", "alice@example.com"),
+        ("bob_chen", "bob@example.com"),
+        ("carol_liu", "carol@example.com"),
     ];
 
-    for (i, (username, email, password_hash)) in users.iter().enumerate() {
+    for (i, (username, email)) in users.iter().enumerate() {
+        let password_hash =
+            auth::hash_password(auth::DEFAULT_PASSWORD).map_err(sqlx::Error::Protocol)?;
         let result = sqlx::query!(
             r#"
             INSERT INTO users (username, email, password_hash)
@@ -73,6 +77,39 @@ async fn seed_users(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+/// Starter categories offered to a new user during TUI onboarding
+pub const DEFAULT_CATEGORIES: [&str; 14] = [
+    "Salary",
+    "Freelance",
+    "Investment Returns",
+    "Groceries",
+    "Dining Out",
+    "Transportation",
+    "Rent",
+    "Utilities",
+    "Phone",
+    "Entertainment",
+    "Shopping",
+    "Healthcare",
+    "Travel",
+    "Subscriptions",
+];
+
+/// Create DEFAULT_CATEGORIES for one user, skipping names they already have.
+/// Returns how many were added.
+pub async fn create_default_categories(pool: &SqlitePool, user_id: i64) -> Result<usize, sqlx::Error> {
+    let mut created = 0;
+    for name in DEFAULT_CATEGORIES {
+        let result = sqlx::query("INSERT OR IGNORE INTO categories (user_id, name) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(name)
+            .execute(pool)
+            .await?;
+        created += result.rows_affected() as usize;
+    }
+    Ok(created)
+}
+
 /// Seed categories table
 async fn seed_categories(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     println!("🏷️  Seeding categories...");
@@ -112,7 +149,7 @@ async fn seed_categories(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     ];
 
     // Categories for user 2 (bob_chen)
-    let user2_categories = vec![
+    let user2_categories = [
         "Salary",
         "Food",
         "Transportation",
@@ -124,7 +161,7 @@ async fn seed_categories(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     ];
 
     // Categories for user 3 (carol_liu)
-    let user3_categories = vec![
+    let user3_categories = [
         "Income",
         "Groceries",
         "Restaurants",
@@ -322,7 +359,7 @@ async fn seed_transactions(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     ];
 
     // User 2 (bob_chen) transactions
-    let user2_transactions = vec![
+    let user2_transactions = [
         (5, 4000.0, "income", "Salary", 0),
         (5, -1200.0, "expense", "Rent", 0),
         (5, -100.0, "expense", "Groceries", -3),
@@ -332,7 +369,7 @@ async fn seed_transactions(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     ];
 
     // User 3 (carol_liu) transactions
-    let user3_transactions = vec![
+    let user3_transactions = [
         (8, 3500.0, "income", "Paycheck", 0),
         (8, -1000.0, "expense", "Rent", 0),
         (8, -80.0, "expense", "Groceries", -2),
@@ -679,6 +716,24 @@ pub async fn clear_database(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     sqlx::query!("DELETE FROM exchange_rates")
         .execute(pool)
         .await?;
+    sqlx::query!("DELETE FROM exchange_rates_archive")
+        .execute(pool)
+        .await?;
+    sqlx::query!("DELETE FROM event_outbox")
+        .execute(pool)
+        .await?;
+    sqlx::query!("DELETE FROM webhooks").execute(pool).await?;
+    sqlx::query!("DELETE FROM export_drops").execute(pool).await?;
+    sqlx::query!("DELETE FROM import_batches").execute(pool).await?;
+    sqlx::query!("DELETE FROM transaction_templates")
+        .execute(pool)
+        .await?;
+    sqlx::query!("DELETE FROM budgets").execute(pool).await?;
+    sqlx::query!("DELETE FROM goal_accounts").execute(pool).await?;
+    sqlx::query!("DELETE FROM goals").execute(pool).await?;
+    sqlx::query!("DELETE FROM loans").execute(pool).await?;
+    sqlx::query!("DELETE FROM holdings").execute(pool).await?;
+    sqlx::query!("DELETE FROM security_prices").execute(pool).await?;
 
     sqlx::query!("DELETE FROM accounts").execute(pool).await?;
     sqlx::query!("DELETE FROM categories").execute(pool).await?;
@@ -696,7 +751,14 @@ pub async fn clear_database(pool: &SqlitePool) -> Result<(), sqlx::Error> {
             'transactions',
             'transaction_categories',
             'recurring_transactions',
-            'exchange_rates'
+            'exchange_rates',
+            'webhooks',
+            'event_outbox',
+            'export_drops',
+            'import_batches',
+            'transaction_templates',
+            'budgets',
+            'goals'
         )
         "#
     )
@@ -710,8 +772,6 @@ pub async fn clear_database(pool: &SqlitePool) -> Result<(), sqlx::Error> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[tokio::test]
     async fn test_seed_database() {
         // This test requires a test database
@@ -0,0 +1,217 @@
+// settings.rs
+// Per-user key/value preferences, such as the TUI tab opened after login
+
+use chrono::{Datelike, Duration, NaiveDate};
+use sqlx::{Row, SqlitePool};
+
+use crate::i18n::Language;
+use crate::models::{UpdateUserSettings, UserSettings};
+
+pub const DEFAULT_SCREEN: &str = "default_screen";
+pub const VIEW_CURRENCY: &str = "view_currency";
+pub const BASE_CURRENCY: &str = "base_currency";
+pub const BUCKET_TARGETS: &str = "bucket_targets";
+pub const WEEKLY_DIGEST: &str = "weekly_digest";
+pub const LANGUAGE: &str = "language";
+pub const DATE_FORMAT: &str = "date_format";
+pub const DEFAULT_ACCOUNT: &str = "default_account";
+pub const WEEK_START: &str = "week_start";
+
+/// Names accepted for date_format and the chrono pattern dates show in;
+/// the first is the default.
+pub const DATE_FORMATS: [(&str, &str); 3] = [("iso", "%Y-%m-%d"), ("us", "%m/%d/%Y"), ("eu", "%d/%m/%Y")];
+
+/// Values accepted for week_start; weeks start on Monday unless set.
+pub const WEEK_STARTS: [&str; 2] = ["monday", "sunday"];
+
+/// Pattern of a date_format name, the ISO one when unknown or None.
+pub fn date_pattern(format: Option<&str>) -> &'static str {
+    DATE_FORMATS
+        .iter()
+        .find(|(name, _)| Some(*name) == format)
+        .map_or(DATE_FORMATS[0].1, |(_, pattern)| pattern)
+}
+
+/// First day of the week `date` falls in, for weeks starting on `week_start`
+/// (a week_start value; Monday when None).
+pub fn week_start_date(date: NaiveDate, week_start: Option<&str>) -> NaiveDate {
+    let days = match week_start {
+        Some("sunday") => date.weekday().num_days_from_sunday(),
+        _ => date.weekday().num_days_from_monday(),
+    };
+    date - Duration::days(days as i64)
+}
+
+/// Screen names accepted for default_screen, in TUI tab order.
+pub const SCREENS: [&str; 12] = [
+    "dashboard",
+    "accounts",
+    "transactions",
+    "categories",
+    "recurring",
+    "exchange_rates",
+    "reports",
+    "export",
+    "budgets",
+    "settings",
+    "goals",
+    "investments",
+];
+
+/// TUI tab index for a screen name (case-insensitive).
+pub fn screen_index(name: &str) -> Option<usize> {
+    SCREENS.iter().position(|s| s.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Store a setting; None removes it.
+pub async fn set(
+    pool: &SqlitePool,
+    user_id: i64,
+    key: &str,
+    value: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    match value {
+        Some(value) => {
+            sqlx::query(
+                "INSERT INTO settings (user_id, key, value) VALUES (?, ?, ?)
+                 ON CONFLICT(user_id, key) DO UPDATE SET value = excluded.value",
+            )
+            .bind(user_id)
+            .bind(key)
+            .bind(value)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM settings WHERE user_id = ? AND key = ?")
+                .bind(user_id)
+                .bind(key)
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// The user's base_currency: what new transactions record their value in and
+/// totals across accounts are converted into. None when not set.
+pub async fn base_currency(pool: &SqlitePool, user_id: i64) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT value FROM settings WHERE user_id = ? AND key = ?")
+        .bind(user_id)
+        .bind(BASE_CURRENCY)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn user_settings(pool: &SqlitePool, user_id: i64) -> Result<UserSettings, sqlx::Error> {
+    let rows = sqlx::query("SELECT key, value FROM settings WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    let mut settings = UserSettings::default();
+    for row in rows {
+        let key: String = row.get("key");
+        let value: String = row.get("value");
+        match key.as_str() {
+            DEFAULT_SCREEN => settings.default_screen = Some(value),
+            VIEW_CURRENCY => settings.view_currency = Some(value),
+            BASE_CURRENCY => settings.base_currency = Some(value),
+            BUCKET_TARGETS => settings.bucket_targets = Some(value),
+            WEEKLY_DIGEST => settings.weekly_digest = value.parse().ok(),
+            LANGUAGE => settings.language = Some(value),
+            DATE_FORMAT => settings.date_format = Some(value),
+            DEFAULT_ACCOUNT => settings.default_account_id = value.parse().ok(),
+            WEEK_START => settings.week_start = Some(value),
+            _ => {}
+        }
+    }
+    Ok(settings)
+}
+
+/// Apply an update: fields left out are unchanged, empty strings clear the setting.
+pub async fn update_user_settings(
+    pool: &SqlitePool,
+    user_id: i64,
+    update: &UpdateUserSettings,
+) -> Result<UserSettings, sqlx::Error> {
+    if let Some(screen) = &update.default_screen {
+        let value = screen_index(screen).map(|i| SCREENS[i]);
+        set(pool, user_id, DEFAULT_SCREEN, value).await?;
+    }
+    if let Some(currency) = &update.view_currency {
+        let currency = currency.trim().to_uppercase();
+        let value = Some(currency.as_str()).filter(|c| !c.is_empty());
+        set(pool, user_id, VIEW_CURRENCY, value).await?;
+    }
+    if let Some(currency) = &update.base_currency {
+        let currency = currency.trim().to_uppercase();
+        let value = Some(currency.as_str()).filter(|c| !c.is_empty());
+        set(pool, user_id, BASE_CURRENCY, value).await?;
+    }
+    if let Some(targets) = &update.bucket_targets {
+        let value = Some(targets.trim()).filter(|t| !t.is_empty());
+        set(pool, user_id, BUCKET_TARGETS, value).await?;
+    }
+    if let Some(show) = update.weekly_digest {
+        set(pool, user_id, WEEKLY_DIGEST, Some(&show.to_string())).await?;
+    }
+    if let Some(language) = &update.language {
+        let value = Language::parse(language).map(Language::code);
+        set(pool, user_id, LANGUAGE, value).await?;
+    }
+    if let Some(format) = &update.date_format {
+        let value = Some(format.trim().to_lowercase()).filter(|f| !f.is_empty());
+        set(pool, user_id, DATE_FORMAT, value.as_deref()).await?;
+    }
+    if let Some(account_id) = update.default_account_id {
+        let value = Some(account_id.to_string()).filter(|_| account_id != 0);
+        set(pool, user_id, DEFAULT_ACCOUNT, value.as_deref()).await?;
+    }
+    if let Some(day) = &update.week_start {
+        let value = Some(day.trim().to_lowercase()).filter(|d| !d.is_empty());
+        set(pool, user_id, WEEK_START, value.as_deref()).await?;
+    }
+    user_settings(pool, user_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_index_matches_tab_order() {
+        assert_eq!(screen_index("dashboard"), Some(0));
+        assert_eq!(screen_index("Transactions"), Some(2));
+        assert_eq!(screen_index(" export "), Some(7));
+        assert_eq!(screen_index("budgets"), Some(8));
+        assert_eq!(screen_index("goals"), Some(10));
+        assert_eq!(screen_index("investments"), Some(11));
+        assert_eq!(screen_index("loans"), None);
+    }
+
+    #[tokio::test]
+    async fn test_update_validates_and_clears_settings() {
+        let pool = crate::testing::pool().await;
+        let user = crate::testing::user().insert(&pool).await;
+        let update = |json: serde_json::Value| serde_json::from_value::<UpdateUserSettings>(json).unwrap();
+
+        assert!(update(serde_json::json!({ "date_format": "roman" })).validate().is_err());
+        assert!(update(serde_json::json!({ "week_start": "friday" })).validate().is_err());
+        let saved = update(serde_json::json!({ "date_format": "EU", "week_start": "sunday", "default_account_id": 7 }));
+        saved.validate().unwrap();
+        let settings = update_user_settings(&pool, user.id, &saved).await.unwrap();
+        assert_eq!(date_pattern(settings.date_format.as_deref()), "%d/%m/%Y");
+        assert_eq!((settings.week_start.as_deref(), settings.default_account_id), (Some("sunday"), Some(7)));
+
+        let cleared = update(serde_json::json!({ "date_format": "", "default_account_id": 0 }));
+        let settings = update_user_settings(&pool, user.id, &cleared).await.unwrap();
+        assert_eq!((settings.date_format, settings.default_account_id), (None, None));
+        assert_eq!(date_pattern(None), "%Y-%m-%d");
+
+        // 2025-07-16 is a Wednesday
+        let wednesday = NaiveDate::from_ymd_opt(2025, 7, 16).unwrap();
+        assert_eq!(week_start_date(wednesday, None), NaiveDate::from_ymd_opt(2025, 7, 14).unwrap());
+        assert_eq!(week_start_date(wednesday, Some("sunday")), NaiveDate::from_ymd_opt(2025, 7, 13).unwrap());
+    }
+}
@@ -0,0 +1,472 @@
+// statements.rs
+// Monthly PDF statements (or over any range of days): a summary page with
+// every account's balances and their total in the user's base currency, then
+// a page per account with its opening and closing balance, the period's
+// transactions with the running balance, and spending by category. Balances
+// count cleared transactions only, the same way current_balance does.
+
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::fx;
+use crate::models::{Account, Transaction};
+use crate::pdf::{Document, Font, PAGE_HEIGHT, PAGE_WIDTH};
+use crate::settings;
+use crate::transaction_status;
+use crate::xlsx;
+
+const MARGIN: f64 = 50.0;
+const RIGHT: f64 = PAGE_WIDTH - MARGIN;
+const LINE: f64 = 14.0;
+
+/// One row of an account's transaction list
+#[derive(Debug, Clone)]
+pub struct StatementLine {
+    pub transaction: Transaction,
+    pub categories: String,
+    pub balance: f64, // Account balance after it
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountStatement {
+    pub account: Account,
+    pub opening_balance: f64,
+    pub money_in: f64,
+    pub money_out: f64, // Positive
+    pub closing_balance: f64,
+    pub lines: Vec<StatementLine>,
+    pub categories: Vec<(String, f64)>, // Spending per category, largest first
+    pub rate: Option<f64>,              // Account currency -> statement currency at the period's end
+}
+
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub username: String,
+    pub start: NaiveDate, // First day of the period
+    pub end: NaiveDate,   // Day after its last one
+    pub currency: String, // Totals are converted into it
+    pub accounts: Vec<AccountStatement>,
+}
+
+/// First day of a "YYYY-MM" month
+pub fn parse_month(month: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d").ok()
+}
+
+fn next_month(month: NaiveDate) -> NaiveDate {
+    (month + Duration::days(32)).with_day(1).unwrap_or(month)
+}
+
+impl Statement {
+    /// A whole calendar month
+    pub fn is_monthly(&self) -> bool {
+        self.start.day() == 1 && self.end == next_month(self.start)
+    }
+
+    /// "May 2025", or "2025-01-01 to 2025-03-31"
+    pub fn period_label(&self) -> String {
+        if self.is_monthly() {
+            self.start.format("%B %Y").to_string()
+        } else {
+            format!("{} to {}", self.start, self.end - Duration::days(1))
+        }
+    }
+}
+
+/// Balance of the account at the start of `day`: initial_balance plus the
+/// cleared transactions from opened_on up to the day before
+async fn balance_before(pool: &SqlitePool, account: &Account, day: NaiveDate) -> Result<f64, sqlx::Error> {
+    let posted: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE -ABS(amount) END - fee), 0.0)
+         FROM transactions
+         WHERE account_id = ? AND status = 'cleared' AND date(transaction_date) < ?
+           AND (? IS NULL OR date(transaction_date) >= ?)",
+    )
+    .bind(account.id)
+    .bind(day.to_string())
+    .bind(account.opened_on.map(|d| d.to_string()))
+    .bind(account.opened_on.map(|d| d.to_string()))
+    .fetch_one(pool)
+    .await?;
+    Ok(account.initial_balance + posted)
+}
+
+/// Cleared spending in the account per category over [start, end)
+async fn category_spending(
+    pool: &SqlitePool,
+    account_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<(String, f64)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT COALESCE(c.name, 'Uncategorized') as name, SUM(COALESCE(tc.amount, ABS(t.amount))) as total
+         FROM transactions t
+         LEFT JOIN transaction_categories tc ON tc.transaction_id = t.id
+         LEFT JOIN categories c ON c.id = tc.category_id
+         WHERE t.account_id = ? AND t.transaction_type = 'expense' AND t.status = 'cleared'
+           AND date(t.transaction_date) >= ? AND date(t.transaction_date) < ?
+         GROUP BY name
+         ORDER BY total DESC, name",
+    )
+    .bind(account_id)
+    .bind(start.to_string())
+    .bind(end.to_string())
+    .fetch_all(pool)
+    .await
+}
+
+/// The user's statement for the month starting on `month`. Totals are in the
+/// base_currency setting, else the first account's currency.
+pub async fn statement(pool: &SqlitePool, user_id: i64, month: NaiveDate) -> Result<Option<Statement>, sqlx::Error> {
+    period_statement(pool, user_id, month, next_month(month)).await
+}
+
+/// The user's statement for the days from `start` up to, not including, `end`
+pub async fn period_statement(
+    pool: &SqlitePool,
+    user_id: i64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Option<Statement>, sqlx::Error> {
+    let Some(username) = sqlx::query_scalar::<_, String>("SELECT username FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let accounts = sqlx::query_as::<_, Account>(
+        "SELECT * FROM accounts WHERE user_id = ? ORDER BY is_favorite DESC, sort_order, id",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    let currency = settings::user_settings(pool, user_id)
+        .await?
+        .base_currency
+        .or_else(|| accounts.first().map(|a| a.currency.clone()))
+        .unwrap_or_else(|| "USD".to_string());
+
+    let rate_date = Utc.from_utc_datetime(&(end - Duration::days(1)).and_hms_opt(23, 59, 59).unwrap_or_default());
+    let names: HashMap<i64, String> = xlsx::transaction_categories(pool, Some(user_id)).await?;
+
+    let mut statements = Vec::new();
+    for account in accounts {
+        let opening_balance = balance_before(pool, &account, start).await?;
+        let transactions = sqlx::query_as::<_, Transaction>(
+            "SELECT * FROM transactions
+             WHERE account_id = ? AND date(transaction_date) >= ? AND date(transaction_date) < ?
+             ORDER BY transaction_date, id",
+        )
+        .bind(account.id)
+        .bind(start.to_string())
+        .bind(end.to_string())
+        .fetch_all(pool)
+        .await?;
+
+        let (mut balance, mut money_in, mut money_out) = (opening_balance, 0.0, 0.0);
+        let mut lines = Vec::new();
+        for transaction in transactions {
+            let counted = account.opened_on.is_none_or(|d| transaction.transaction_date.date_naive() >= d);
+            let effect = if counted { transaction_status::posted_effect(&transaction) } else { 0.0 };
+            balance += effect;
+            if effect > 0.0 {
+                money_in += effect;
+            } else {
+                money_out -= effect;
+            }
+            lines.push(StatementLine {
+                categories: names.get(&transaction.id).cloned().unwrap_or_default(),
+                transaction,
+                balance,
+            });
+        }
+
+        statements.push(AccountStatement {
+            categories: category_spending(pool, account.id, start, end).await?,
+            rate: fx::conversion_rate(pool, &account.currency, &currency, Some(rate_date)).await?,
+            account,
+            opening_balance,
+            money_in,
+            money_out,
+            closing_balance: balance,
+            lines,
+        });
+    }
+
+    Ok(Some(Statement {
+        username,
+        start,
+        end,
+        currency,
+        accounts: statements,
+    }))
+}
+
+fn money(amount: f64) -> String {
+    let cents = (amount.abs() * 100.0).round() as i64;
+    let digits = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if amount < 0.0 && cents > 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, grouped, cents % 100)
+}
+
+fn clipped(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(max.saturating_sub(3)).collect::<String>())
+    }
+}
+
+/// Draws top to bottom, starting a new page when the next row won't fit
+struct Writer {
+    doc: Document,
+    y: f64,
+    footer: String,
+}
+
+impl Writer {
+    fn page(&mut self) {
+        self.doc.new_page();
+        self.doc.text(MARGIN, MARGIN / 2.0, Font::Regular, 8.0, &self.footer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Make room for `height` points, on a new page if need be
+    fn room(&mut self, height: f64) {
+        if self.y - height < MARGIN {
+            self.page();
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.room(LINE * 3.0);
+        self.y -= LINE;
+        self.doc.band(MARGIN - 4.0, self.y - 4.0, RIGHT - MARGIN + 8.0, LINE + 2.0);
+        self.doc.text(MARGIN, self.y, Font::Bold, 11.0, text);
+        self.y -= LINE + 4.0;
+    }
+
+    /// Label with figures right-aligned at the given column edges
+    fn row(&mut self, font: Font, cells: &[(f64, &str)], figures: &[(f64, String)]) {
+        self.room(LINE);
+        for (x, text) in cells {
+            self.doc.text(*x, self.y, font, 9.0, text);
+        }
+        for (right, figure) in figures {
+            self.doc.text_right(*right, self.y, 9.0, figure);
+        }
+        self.y -= LINE;
+    }
+
+    fn balance(&mut self, label: &str, amount: f64, currency: &str) {
+        self.row(Font::Regular, &[(MARGIN, label)], &[(RIGHT, format!("{} {}", money(amount), currency))]);
+    }
+}
+
+/// The statement as a PDF file
+pub fn render(statement: &Statement) -> Vec<u8> {
+    let period = statement.period_label();
+    let this_period = if statement.is_monthly() { "this month" } else { "in this period" };
+    let mut w = Writer {
+        doc: Document::new(),
+        y: 0.0,
+        footer: format!(
+            "Statement for {}, {} - generated {}",
+            statement.username,
+            period,
+            Utc::now().format("%Y-%m-%d")
+        ),
+    };
+
+    w.page();
+    let title = if statement.is_monthly() { "Monthly Statement" } else { "Statement" };
+    w.doc.text(MARGIN, w.y, Font::Bold, 18.0, title);
+    w.y -= LINE * 1.6;
+    w.doc.text(MARGIN, w.y, Font::Regular, 11.0, &format!("{} - {}", statement.username, period));
+    w.y -= LINE;
+
+    let cur = &statement.currency;
+    w.heading("Summary");
+    w.row(
+        Font::Bold,
+        &[(MARGIN, "Account")],
+        &[
+            (290.0, "Opening".to_string()),
+            (362.0, "In".to_string()),
+            (434.0, "Out".to_string()),
+            (506.0, "Closing".to_string()),
+            (RIGHT, format!("in {}", cur)),
+        ],
+    );
+    let mut totals = [0.0; 4];
+    let mut unconverted = Vec::new();
+    for a in &statement.accounts {
+        let converted = match a.rate {
+            Some(rate) => {
+                for (total, amount) in totals.iter_mut().zip([a.opening_balance, a.money_in, a.money_out, a.closing_balance]) {
+                    *total += amount * rate;
+                }
+                money(a.closing_balance * rate)
+            }
+            None => {
+                unconverted.push(a.account.currency.clone());
+                "no rate".to_string()
+            }
+        };
+        let name = clipped(&format!("{} ({})", a.account.name, a.account.currency), 34);
+        w.row(
+            Font::Regular,
+            &[(MARGIN, &name)],
+            &[
+                (290.0, money(a.opening_balance)),
+                (362.0, money(a.money_in)),
+                (434.0, money(a.money_out)),
+                (506.0, money(a.closing_balance)),
+                (RIGHT, converted),
+            ],
+        );
+    }
+    if statement.accounts.is_empty() {
+        w.row(Font::Regular, &[(MARGIN, "No accounts")], &[]);
+    }
+    w.doc.rule(MARGIN, RIGHT, w.y + LINE - 3.0);
+    let total_label = format!("Total in {}", cur);
+    w.row(
+        Font::Bold,
+        &[(MARGIN, &total_label)],
+        &[
+            (290.0, money(totals[0])),
+            (362.0, money(totals[1])),
+            (434.0, money(totals[2])),
+            (RIGHT, money(totals[3])),
+        ],
+    );
+    if !unconverted.is_empty() {
+        unconverted.dedup();
+        let note = format!("Not in the totals: no {} rate for {}", cur, unconverted.join(", "));
+        w.row(Font::Regular, &[(MARGIN, &note)], &[]);
+    }
+
+    for a in &statement.accounts {
+        let account = &a.account;
+        w.page();
+        w.doc.text(MARGIN, w.y, Font::Bold, 16.0, &clipped(&account.name, 50));
+        w.y -= LINE * 1.4;
+        let details: Vec<&str> = [Some(account.account_type.as_str()), account.bank_name.as_deref(), Some(account.currency.as_str())]
+            .into_iter()
+            .flatten()
+            .collect();
+        w.doc.text(MARGIN, w.y, Font::Regular, 10.0, &format!("{} - {}", details.join(" | "), period));
+        w.y -= LINE;
+
+        w.heading("Balances");
+        w.balance("Opening balance", a.opening_balance, &account.currency);
+        w.balance("Money in", a.money_in, &account.currency);
+        w.balance("Money out", a.money_out, &account.currency);
+        w.balance("Closing balance", a.closing_balance, &account.currency);
+        if !account.currency.eq_ignore_ascii_case(cur) {
+            match a.rate {
+                Some(rate) => {
+                    let label = format!("Closing balance in {} (at {:.4})", cur, rate);
+                    w.balance(&label, a.closing_balance * rate, cur);
+                }
+                None => w.row(Font::Regular, &[(MARGIN, &format!("No {} rate for {}", cur, account.currency))], &[]),
+            }
+        }
+
+        w.heading("Transactions");
+        w.row(
+            Font::Bold,
+            &[(MARGIN, "Date"), (110.0, "Description"), (310.0, "Category")],
+            &[(490.0, "Amount".to_string()), (RIGHT, "Balance".to_string())],
+        );
+        for line in &a.lines {
+            let t = &line.transaction;
+            let mut description = t.description.clone().or_else(|| t.merchant.clone()).unwrap_or_default();
+            if t.status != "cleared" {
+                description = format!("{} ({})", description, t.status);
+            }
+            let effect = transaction_status::balance_effect(&t.transaction_type, t.amount, t.fee);
+            w.row(
+                Font::Regular,
+                &[
+                    (MARGIN, &t.transaction_date.format("%Y-%m-%d").to_string()),
+                    (110.0, &clipped(&description, 38)),
+                    (310.0, &clipped(&line.categories, 18)),
+                ],
+                &[(490.0, money(effect)), (RIGHT, money(line.balance))],
+            );
+        }
+        if a.lines.is_empty() {
+            w.row(Font::Regular, &[(MARGIN, &format!("No transactions {}", this_period))], &[]);
+        }
+
+        w.heading("Spending by Category");
+        let spent: f64 = a.categories.iter().map(|(_, amount)| amount).sum();
+        for (name, amount) in &a.categories {
+            let share = if spent > 0.0 { amount / spent * 100.0 } else { 0.0 };
+            w.row(
+                Font::Regular,
+                &[(MARGIN, &clipped(name, 50))],
+                &[(490.0, money(*amount)), (RIGHT, format!("{:.1}%", share))],
+            );
+        }
+        if a.categories.is_empty() {
+            w.row(Font::Regular, &[(MARGIN, &format!("No spending {}", this_period))], &[]);
+        }
+    }
+
+    w.doc.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_statement_balances_and_categories() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(1000.0).insert(&pool).await;
+        let food = testing::category(&pool, user.id, "Food").await;
+        let day = |d: u32, m: u32| Utc.with_ymd_and_hms(2025, m, d, 12, 0, 0).unwrap();
+        testing::txn(account.id).amount(200.0).income().date(day(20, 4)).insert(&pool).await;
+        testing::txn(account.id).amount(40.0).category(food.id).date(day(3, 5)).insert(&pool).await;
+        testing::txn(account.id).amount(500.0).income().date(day(15, 5)).insert(&pool).await;
+        testing::txn(account.id).amount(75.0).date(day(1, 6)).insert(&pool).await;
+
+        let month = parse_month("2025-05").unwrap();
+        assert_eq!(parse_month("2025-13"), None);
+        let statement = statement(&pool, user.id, month).await.unwrap().unwrap();
+        let a = &statement.accounts[0];
+        assert_eq!((a.opening_balance, a.money_in, a.money_out, a.closing_balance), (1200.0, 500.0, 40.0, 1660.0));
+        assert_eq!(a.lines.iter().map(|l| l.balance).collect::<Vec<_>>(), [1160.0, 1660.0]);
+        assert_eq!(a.lines[0].categories, "Food");
+        assert_eq!(a.categories, [("Food".to_string(), 40.0)]);
+        assert_eq!((a.rate, statement.currency.as_str()), (Some(1.0), account.currency.as_str()));
+        assert_eq!(money(-1234567.891), "-1,234,567.89");
+
+        let pdf = render(&statement);
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(String::from_utf8_lossy(&pdf).contains("/Count 2"));
+        assert!(super::statement(&pool, 9999, month).await.unwrap().is_none());
+        assert_eq!(statement.period_label(), "May 2025");
+
+        // April 20 to May 14: the May 15 income falls outside
+        let (start, end) = (NaiveDate::from_ymd_opt(2025, 4, 20).unwrap(), NaiveDate::from_ymd_opt(2025, 5, 15).unwrap());
+        let ranged = period_statement(&pool, user.id, start, end).await.unwrap().unwrap();
+        let a = &ranged.accounts[0];
+        assert_eq!((a.opening_balance, a.money_in, a.money_out, a.closing_balance), (1000.0, 200.0, 40.0, 1160.0));
+        assert_eq!(ranged.period_label(), "2025-04-20 to 2025-05-14");
+    }
+}
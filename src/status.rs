@@ -0,0 +1,159 @@
+// status.rs
+// System status: the database file and its tables, the latest FX scrape and
+// recurring run, alerts waiting, and the health of the background tasks of a
+// server running against the same database, which record a heartbeat after
+// every run
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+
+use crate::exchange_scraper;
+use crate::models::{SystemStatus, TableCount, TaskHeartbeat};
+
+pub const RECURRING: &str = "recurring";
+pub const EXPORT_DROPS: &str = "export_drops";
+pub const FX_SCRAPER: &str = "fx_scraper";
+pub const MONTHLY_REPORTS: &str = "monthly_reports";
+pub const PRICE_UPDATES: &str = "price_updates";
+pub const RATE_COMPACTION: &str = "rate_compaction";
+pub const WEBHOOKS: &str = "webhooks";
+
+/// Slack on top of twice the interval before a task counts as stalled
+const GRACE_SECONDS: i64 = 60;
+
+/// Record one run of a background task; `error` is None when it succeeded
+pub async fn record_heartbeat(
+    pool: &SqlitePool,
+    task: &str,
+    interval_seconds: i64,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    sqlx::query(
+        "INSERT INTO task_heartbeats (task, interval_seconds, last_run_at, last_success_at, last_error)
+         VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(task) DO UPDATE SET
+             interval_seconds = excluded.interval_seconds,
+             last_run_at = excluded.last_run_at,
+             last_success_at = COALESCE(excluded.last_success_at, task_heartbeats.last_success_at),
+             last_error = excluded.last_error",
+    )
+    .bind(task)
+    .bind(interval_seconds)
+    .bind(now)
+    .bind(error.is_none().then_some(now))
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// "ok", "failing" (the latest run errored) or "stalled" (no run for twice
+/// the interval, e.g. the server stopped)
+pub fn task_health(heartbeat: &TaskHeartbeat, now: DateTime<Utc>) -> &'static str {
+    let overdue = Duration::seconds(heartbeat.interval_seconds * 2 + GRACE_SECONDS);
+    if now - heartbeat.last_run_at > overdue {
+        "stalled"
+    } else if heartbeat.last_error.is_some() {
+        "failing"
+    } else {
+        "ok"
+    }
+}
+
+/// Row counts of every table but SQLite's and sqlx's own
+async fn table_counts(pool: &SqlitePool) -> Result<Vec<TableCount>, sqlx::Error> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '_sqlx_%'
+         ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut counts = Vec::with_capacity(tables.len());
+    for table in tables {
+        // Names come from sqlite_master; quote them like identifiers
+        let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\"")))
+            .fetch_one(pool)
+            .await?;
+        counts.push(TableCount { table, rows });
+    }
+    Ok(counts)
+}
+
+/// The main database file, None when it lives in memory
+async fn database_path(pool: &SqlitePool) -> Result<Option<String>, sqlx::Error> {
+    let file: Option<String> =
+        sqlx::query_scalar("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .fetch_optional(pool)
+            .await?;
+    Ok(file.filter(|f| !f.is_empty()))
+}
+
+/// Size of the database file plus its write-ahead log
+fn database_size(path: &str) -> Option<u64> {
+    let main = std::fs::metadata(path).ok()?.len();
+    let wal = std::fs::metadata(format!("{}-wal", path)).map(|m| m.len()).unwrap_or(0);
+    Some(main + wal)
+}
+
+pub async fn system_status(pool: &SqlitePool) -> Result<SystemStatus, sqlx::Error> {
+    let database_path = database_path(pool).await?;
+    let database_size = database_path.as_deref().and_then(database_size);
+    let last_recurring_run: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT MAX(created_at) FROM transactions WHERE recurring_transaction_id IS NOT NULL",
+    )
+    .fetch_one(pool)
+    .await?;
+    let (pending_deliveries, failed_deliveries): (i64, i64) = sqlx::query_as(
+        "SELECT COALESCE(SUM(status = 'pending'), 0), COALESCE(SUM(status = 'failed'), 0) FROM event_outbox",
+    )
+    .fetch_one(pool)
+    .await?;
+    let tasks = sqlx::query_as::<_, TaskHeartbeat>("SELECT * FROM task_heartbeats ORDER BY task")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(SystemStatus {
+        database_path,
+        database_size,
+        tables: table_counts(pool).await?,
+        last_scrape: exchange_scraper::recent_scrape_runs(pool, 1).await?.into_iter().next(),
+        last_recurring_run,
+        pending_deliveries,
+        failed_deliveries,
+        tasks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_status_counts_rows_and_tracks_heartbeats() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        testing::txn(account.id).insert(&pool).await;
+        testing::txn(account.id).insert(&pool).await;
+        record_heartbeat(&pool, RECURRING, 3600, None).await.unwrap();
+        record_heartbeat(&pool, WEBHOOKS, 30, None).await.unwrap();
+        record_heartbeat(&pool, WEBHOOKS, 30, Some("database is locked")).await.unwrap();
+
+        let status = system_status(&pool).await.unwrap();
+        let rows = |table: &str| status.tables.iter().find(|t| t.table == table).map(|t| t.rows);
+        assert_eq!((rows("transactions"), rows("accounts")), (Some(2), Some(1)));
+        assert!(rows("_sqlx_migrations").is_none());
+        assert_eq!(status.last_recurring_run, None);
+
+        let now = Utc::now();
+        let health: Vec<(&str, &str)> = status.tasks.iter().map(|t| (t.task.as_str(), task_health(t, now))).collect();
+        assert_eq!(health, vec![(RECURRING, "ok"), (WEBHOOKS, "failing")]);
+        // A failed run keeps the last success
+        assert!(status.tasks[1].last_success_at.is_some());
+        assert_eq!(task_health(&status.tasks[0], now + Duration::hours(3)), "stalled");
+    }
+}
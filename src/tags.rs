@@ -0,0 +1,136 @@
+// tags.rs
+// Free-form tags on transactions ("vacation2024", "reimbursable"). Unlike
+// categories they don't split the amount: a transaction has any number of
+// tags, each just a lowercase label of the user's.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::models::Tag;
+
+/// The stored form of a tag name: trimmed, lowercase, without a leading '#'.
+/// Letters, digits, '-' and '_' only, so "#Vacation2024" is "vacation2024".
+pub fn normalize_tag(name: &str) -> Result<String, String> {
+    let name = name.trim().trim_start_matches('#').to_lowercase();
+    if name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    crate::input::check_length("Tag name", Some(&name), crate::input::MAX_NAME_LEN)?;
+    if let Some(c) = name.chars().find(|c| !c.is_alphanumeric() && *c != '-' && *c != '_') {
+        return Err(format!("Tag names can't contain '{}' (letters, digits, - and _ only)", c));
+    }
+    Ok(name)
+}
+
+/// Create the user's tag with this (normalized) name, or return it if it exists
+pub async fn create_tag(pool: &SqlitePool, user_id: i64, name: &str) -> Result<Tag, sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO tags (user_id, name) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(name)
+        .execute(pool)
+        .await?;
+
+    sqlx::query_as::<_, Tag>("SELECT * FROM tags WHERE user_id = ? AND name = ?")
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn tags_for_user(pool: &SqlitePool, user_id: i64) -> Result<Vec<Tag>, sqlx::Error> {
+    sqlx::query_as::<_, Tag>("SELECT * FROM tags WHERE user_id = ? ORDER BY name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Names of the transaction's tags, alphabetically
+pub async fn tags_for_transaction(pool: &SqlitePool, transaction_id: i64) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT g.name FROM transaction_tags tt
+         JOIN tags g ON g.id = tt.tag_id
+         WHERE tt.transaction_id = ?
+         ORDER BY g.name",
+    )
+    .bind(transaction_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Tag names of each of the user's tagged transactions, by transaction ID
+pub async fn tags_by_transaction(pool: &SqlitePool, user_id: i64) -> Result<HashMap<i64, Vec<String>>, sqlx::Error> {
+    let rows: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT tt.transaction_id, g.name FROM transaction_tags tt
+         JOIN tags g ON g.id = tt.tag_id
+         WHERE g.user_id = ?
+         ORDER BY g.name",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for (transaction_id, name) in rows {
+        tags.entry(transaction_id).or_default().push(name);
+    }
+    Ok(tags)
+}
+
+/// Tag the transaction; tagging it twice is a no-op
+pub async fn add_tag(pool: &SqlitePool, transaction_id: i64, tag_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO transaction_tags (transaction_id, tag_id) VALUES (?, ?)")
+        .bind(transaction_id)
+        .bind(tag_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Take the tag off the transaction; false if it didn't have it
+pub async fn remove_tag(pool: &SqlitePool, transaction_id: i64, tag_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM transaction_tags WHERE transaction_id = ? AND tag_id = ?")
+        .bind(transaction_id)
+        .bind(tag_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_tags_are_normalized_and_shared_by_name() {
+        assert_eq!(normalize_tag(" #Vacation2024 ").unwrap(), "vacation2024");
+        assert!(normalize_tag("#").is_err());
+        assert!(normalize_tag("road trip").is_err());
+        assert!(normalize_tag("x'; DROP TABLE tags; --").is_err());
+
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).insert(&pool).await;
+        let hotel = testing::txn(account.id).amount(300.0).insert(&pool).await;
+        let taxi = testing::txn(account.id).amount(40.0).insert(&pool).await;
+
+        let vacation = create_tag(&pool, user.id, "vacation2024").await.unwrap();
+        let reimbursable = create_tag(&pool, user.id, "reimbursable").await.unwrap();
+        assert_eq!(create_tag(&pool, user.id, "vacation2024").await.unwrap().id, vacation.id);
+
+        for transaction in [&hotel, &taxi] {
+            add_tag(&pool, transaction.id, vacation.id).await.unwrap();
+        }
+        add_tag(&pool, taxi.id, reimbursable.id).await.unwrap();
+        add_tag(&pool, taxi.id, reimbursable.id).await.unwrap();
+
+        assert_eq!(tags_for_transaction(&pool, taxi.id).await.unwrap(), ["reimbursable", "vacation2024"]);
+        assert!(remove_tag(&pool, hotel.id, vacation.id).await.unwrap());
+        assert!(!remove_tag(&pool, hotel.id, vacation.id).await.unwrap());
+
+        let by_transaction = tags_by_transaction(&pool, user.id).await.unwrap();
+        assert_eq!(by_transaction.len(), 1);
+        assert_eq!(by_transaction[&taxi.id].len(), 2);
+        assert_eq!(tags_for_user(&pool, user.id).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,365 @@
+// testing.rs
+// Test harness: a migrated in-memory SQLite pool and builder-style fixtures
+//
+//     let pool = testing::pool().await;
+//     let user = testing::user().insert(&pool).await;
+//     let account = testing::account(user.id).currency("EUR").balance(100.0).insert(&pool).await;
+//     testing::txn(account.id).amount(12.5).insert(&pool).await;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::models::{Account, Category, RecurringTransaction, Transaction, User};
+use crate::profiles;
+
+/// Fresh in-memory database with all migrations applied and foreign keys on.
+/// The pool holds a single connection that is never recycled, since an
+/// in-memory database only lives as long as its connection.
+pub async fn pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .expect("open in-memory database");
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&pool)
+        .await
+        .expect("enable foreign keys");
+    profiles::migrate(&pool).await.expect("run migrations");
+    pool
+}
+
+/// Suffix that keeps default usernames and emails unique within a test run
+fn next_id() -> usize {
+    static COUNTER: AtomicUsize = AtomicUsize::new(1);
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+pub fn user() -> UserFixture {
+    let n = next_id();
+    UserFixture {
+        username: format!("user{}", n),
+        email: format!("user{}@example.com", n),
+    }
+}
+
+pub struct UserFixture {
+    username: String,
+    email: String,
+}
+
+impl UserFixture {
+    pub fn username(mut self, username: &str) -> Self {
+        self.username = username.to_string();
+        self
+    }
+
+    pub async fn insert(self, pool: &SqlitePool) -> User {
+        let id =
+            sqlx::query("INSERT INTO users (username, email, password_hash) VALUES (?, ?, 'x')")
+                .bind(&self.username)
+                .bind(&self.email)
+                .execute(pool)
+                .await
+                .expect("insert user")
+                .last_insert_rowid();
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .expect("load user")
+    }
+}
+
+pub fn account(user_id: i64) -> AccountFixture {
+    AccountFixture {
+        user_id,
+        name: "Checking".to_string(),
+        account_type: "checking".to_string(),
+        currency: "USD".to_string(),
+        balance: 0.0,
+        opened_on: None,
+    }
+}
+
+pub struct AccountFixture {
+    user_id: i64,
+    name: String,
+    account_type: String,
+    currency: String,
+    balance: f64,
+    opened_on: Option<NaiveDate>,
+}
+
+impl AccountFixture {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn account_type(mut self, account_type: &str) -> Self {
+        self.account_type = account_type.to_string();
+        self
+    }
+
+    pub fn currency(mut self, currency: &str) -> Self {
+        self.currency = currency.to_string();
+        self
+    }
+
+    /// Initial and current balance
+    pub fn balance(mut self, balance: f64) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    pub fn opened_on(mut self, opened_on: NaiveDate) -> Self {
+        self.opened_on = Some(opened_on);
+        self
+    }
+
+    pub async fn insert(self, pool: &SqlitePool) -> Account {
+        let id = sqlx::query(
+            "INSERT INTO accounts (user_id, name, account_type, currency, initial_balance, current_balance, opened_on)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.user_id)
+        .bind(&self.name)
+        .bind(&self.account_type)
+        .bind(&self.currency)
+        .bind(self.balance)
+        .bind(self.balance)
+        .bind(self.opened_on)
+        .execute(pool)
+        .await
+        .expect("insert account")
+        .last_insert_rowid();
+        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .expect("load account")
+    }
+}
+
+pub async fn category(pool: &SqlitePool, user_id: i64, name: &str) -> Category {
+    let id = sqlx::query("INSERT INTO categories (user_id, name) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(name)
+        .execute(pool)
+        .await
+        .expect("insert category")
+        .last_insert_rowid();
+    sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .expect("load category")
+}
+
+pub fn txn(account_id: i64) -> TxnFixture {
+    TxnFixture {
+        account_id,
+        amount: 10.0,
+        transaction_type: "expense".to_string(),
+        description: None,
+        transaction_date: Utc::now(),
+        category_id: None,
+        status: "cleared",
+    }
+}
+
+pub struct TxnFixture {
+    account_id: i64,
+    amount: f64,
+    transaction_type: String,
+    description: Option<String>,
+    transaction_date: DateTime<Utc>,
+    category_id: Option<i64>,
+    status: &'static str,
+}
+
+impl TxnFixture {
+    pub fn amount(mut self, amount: f64) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    pub fn income(mut self) -> Self {
+        self.transaction_type = "income".to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn date(mut self, transaction_date: DateTime<Utc>) -> Self {
+        self.transaction_date = transaction_date;
+        self
+    }
+
+    pub fn pending(mut self) -> Self {
+        self.status = "pending";
+        self
+    }
+
+    /// Book the whole amount to one category
+    pub fn category(mut self, category_id: i64) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    /// Insert the row (and its split) as-is; the account balance is left untouched.
+    pub async fn insert(self, pool: &SqlitePool) -> Transaction {
+        let id = sqlx::query(
+            "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, status)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.account_id)
+        .bind(self.amount)
+        .bind(&self.transaction_type)
+        .bind(&self.description)
+        .bind(self.transaction_date)
+        .bind(self.status)
+        .execute(pool)
+        .await
+        .expect("insert transaction")
+        .last_insert_rowid();
+
+        if let Some(category_id) = self.category_id {
+            sqlx::query("INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)")
+                .bind(id)
+                .bind(category_id)
+                .bind(self.amount)
+                .execute(pool)
+                .await
+                .expect("insert transaction split");
+        }
+
+        sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .expect("load transaction")
+    }
+}
+
+pub fn recurring(account_id: i64) -> RecurringFixture {
+    let now = Utc::now();
+    RecurringFixture {
+        account_id,
+        category_id: None,
+        amount: 50.0,
+        transaction_type: "expense".to_string(),
+        frequency: "monthly".to_string(),
+        next_occurrence: now,
+        end_date: None,
+        currency: None,
+        catch_up: "all".to_string(),
+    }
+}
+
+pub struct RecurringFixture {
+    account_id: i64,
+    category_id: Option<i64>,
+    amount: f64,
+    transaction_type: String,
+    frequency: String,
+    next_occurrence: DateTime<Utc>,
+    end_date: Option<DateTime<Utc>>,
+    currency: Option<String>,
+    catch_up: String,
+}
+
+impl RecurringFixture {
+    pub fn amount(mut self, amount: f64) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    pub fn income(mut self) -> Self {
+        self.transaction_type = "income".to_string();
+        self
+    }
+
+    pub fn category(mut self, category_id: i64) -> Self {
+        self.category_id = Some(category_id);
+        self
+    }
+
+    pub fn frequency(mut self, frequency: &str) -> Self {
+        self.frequency = frequency.to_string();
+        self
+    }
+
+    /// Start date and next occurrence
+    pub fn due(mut self, next_occurrence: DateTime<Utc>) -> Self {
+        self.next_occurrence = next_occurrence;
+        self
+    }
+
+    pub fn end_date(mut self, end_date: DateTime<Utc>) -> Self {
+        self.end_date = Some(end_date);
+        self
+    }
+
+    /// Billing currency, when it differs from the account currency
+    pub fn currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
+    /// "all" (the default) or "latest"
+    pub fn catch_up(mut self, catch_up: &str) -> Self {
+        self.catch_up = catch_up.to_string();
+        self
+    }
+
+    pub async fn insert(self, pool: &SqlitePool) -> RecurringTransaction {
+        let id = sqlx::query(
+            "INSERT INTO recurring_transactions (account_id, category_id, amount, transaction_type, description, frequency, start_date, end_date, next_occurrence, currency, catch_up)
+             VALUES (?, ?, ?, ?, 'Recurring', ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.account_id)
+        .bind(self.category_id)
+        .bind(self.amount)
+        .bind(&self.transaction_type)
+        .bind(&self.frequency)
+        .bind(self.next_occurrence)
+        .bind(self.end_date)
+        .bind(self.next_occurrence)
+        .bind(&self.currency)
+        .bind(&self.catch_up)
+        .execute(pool)
+        .await
+        .expect("insert recurring transaction")
+        .last_insert_rowid();
+        sqlx::query_as::<_, RecurringTransaction>(
+            "SELECT * FROM recurring_transactions WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .expect("load recurring transaction")
+    }
+}
+
+/// Store a rate as if entered by hand (source "manual").
+pub async fn rate(pool: &SqlitePool, from: &str, to: &str, rate: f64, date: DateTime<Utc>) {
+    sqlx::query(
+        "INSERT INTO exchange_rates (from_currency, to_currency, rate, rate_date, source) VALUES (?, ?, ?, ?, 'manual')",
+    )
+    .bind(from)
+    .bind(to)
+    .bind(rate)
+    .bind(date)
+    .execute(pool)
+    .await
+    .expect("insert exchange rate");
+}
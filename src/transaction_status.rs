@@ -0,0 +1,116 @@
+// transaction_status.rs
+// Pending, cleared and void transactions. Only cleared transactions are
+// posted to current_balance; pending ones count towards the projected
+// balance until the bank clears them, and void ones are kept for the record.
+
+use sqlx::SqlitePool;
+
+use crate::models::Transaction;
+
+pub const STATUSES: [&str; 3] = ["pending", "cleared", "void"];
+
+pub fn validate_status(status: &str) -> Result<(), String> {
+    if STATUSES.contains(&status) {
+        Ok(())
+    } else {
+        Err(format!("Unknown status '{}' (expected pending, cleared or void)", status))
+    }
+}
+
+/// Change in balance when a transaction is posted: income adds its amount,
+/// everything else is debited, and the fee always is
+pub fn balance_effect(transaction_type: &str, amount: f64, fee: f64) -> f64 {
+    if transaction_type == "income" {
+        amount - fee
+    } else {
+        -amount.abs() - fee
+    }
+}
+
+/// What the transaction contributes to current_balance in its present status
+pub fn posted_effect(transaction: &Transaction) -> f64 {
+    if transaction.status == "cleared" {
+        balance_effect(&transaction.transaction_type, transaction.amount, transaction.fee)
+    } else {
+        0.0
+    }
+}
+
+/// Move a transaction to another status, posting its effect to the account
+/// when it clears and taking it back out when a cleared one is reopened or
+/// voided
+pub async fn set_status(
+    pool: &SqlitePool,
+    transaction: &Transaction,
+    status: &str,
+) -> Result<Transaction, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let updated = sqlx::query_as::<_, Transaction>(
+        "UPDATE transactions SET status = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? RETURNING *",
+    )
+    .bind(status)
+    .bind(transaction.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let change = posted_effect(&updated) - posted_effect(transaction);
+    if change != 0.0 {
+        sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+            .bind(change)
+            .bind(transaction.account_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// current_balance plus the pending transactions that have not posted yet
+pub async fn balance_with_pending(pool: &SqlitePool, account_id: i64) -> Result<f64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT a.current_balance + COALESCE((
+             SELECT SUM(CASE WHEN t.transaction_type = 'income' THEN t.amount ELSE -ABS(t.amount) END - t.fee)
+             FROM transactions t
+             WHERE t.account_id = a.id AND t.status = 'pending'
+         ), 0)
+         FROM accounts a WHERE a.id = ?",
+    )
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    async fn current_balance(pool: &SqlitePool, account_id: i64) -> f64 {
+        sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(account_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_clearing_and_voiding_moves_balance() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).balance(100.0).insert(&pool).await;
+        let pending = testing::txn(account.id).amount(30.0).pending().insert(&pool).await;
+
+        assert_eq!(current_balance(&pool, account.id).await, 100.0);
+        assert_eq!(balance_with_pending(&pool, account.id).await.unwrap(), 70.0);
+
+        let cleared = set_status(&pool, &pending, "cleared").await.unwrap();
+        assert_eq!(current_balance(&pool, account.id).await, 70.0);
+        assert_eq!(balance_with_pending(&pool, account.id).await.unwrap(), 70.0);
+
+        // Voiding a cleared transaction gives the money back
+        set_status(&pool, &cleared, "void").await.unwrap();
+        assert_eq!(current_balance(&pool, account.id).await, 100.0);
+        assert_eq!(balance_with_pending(&pool, account.id).await.unwrap(), 100.0);
+        assert!(validate_status("posted").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+// transfers.rs
+// Moving money between two accounts of a user: a "transfer" leg debits the
+// source and an "income" leg credits the destination, booked together and
+// linked to each other through linked_transaction_id. Both legs share a
+// transfer_group_id (the debit leg's id), which keeps internal transfers out
+// of income and expense analytics.
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+use crate::fx;
+use crate::models::{Account, CreateTransfer, Transaction, Transfer};
+use crate::webhooks;
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Why money can't move from `from` to `to` on `date`, if anything
+pub fn validate_transfer(from: &Account, to: &Account, date: DateTime<Utc>) -> Result<(), String> {
+    if from.id == to.id {
+        return Err("Source and destination must be different accounts".to_string());
+    }
+    if from.user_id != to.user_id {
+        return Err("Destination account belongs to another user".to_string());
+    }
+    for account in [from, to] {
+        if account.is_archived {
+            return Err(format!("Account '{}' is closed", account.name));
+        }
+        if let Some(opened_on) = account.opened_on.filter(|d| date.date_naive() < *d) {
+            return Err(format!(
+                "Transfer date is before '{}' was opened ({})",
+                account.name, opened_on
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Book both legs of a transfer and update both balances in one DB
+/// transaction. `rate` converts the amount into the destination currency;
+/// the fee is only debited from the source. Call validate_transfer first.
+pub async fn create_transfer(
+    pool: &SqlitePool,
+    from: &Account,
+    to: &Account,
+    data: &CreateTransfer,
+    rate: f64,
+) -> Result<Transfer, sqlx::Error> {
+    let date = data.transaction_date.unwrap_or_else(Utc::now);
+    let amount = round_cents(data.amount);
+    let converted = round_cents(amount * rate);
+    let fee = data.fee.unwrap_or(0.0);
+    let cross_currency = !from.currency.eq_ignore_ascii_case(&to.currency);
+
+    let from_base = fx::base_amount_snapshot(pool, from.id, amount, date).await?.unzip();
+    let to_base = fx::base_amount_snapshot(pool, to.id, converted, date).await?.unzip();
+
+    let mut tx = pool.begin().await?;
+
+    let from_id = sqlx::query(
+        "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, fee, base_amount, base_currency) VALUES (?, ?, 'transfer', ?, ?, ?, ?, ?)",
+    )
+    .bind(from.id)
+    .bind(amount)
+    .bind(data.description.clone().unwrap_or_else(|| format!("Transfer to {}", to.name)))
+    .bind(date)
+    .bind(fee)
+    .bind(from_base.0)
+    .bind(&from_base.1)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    // The credit keeps what was sent in the source currency, like a foreign purchase
+    let to_id = sqlx::query(
+        "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, original_amount, original_currency, exchange_rate, base_amount, base_currency, linked_transaction_id, transfer_group_id) VALUES (?, ?, 'income', ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(to.id)
+    .bind(converted)
+    .bind(data.description.clone().unwrap_or_else(|| format!("Transfer from {}", from.name)))
+    .bind(date)
+    .bind(cross_currency.then_some(amount))
+    .bind(cross_currency.then(|| from.currency.to_uppercase()))
+    .bind(cross_currency.then_some(rate))
+    .bind(to_base.0)
+    .bind(&to_base.1)
+    .bind(from_id)
+    .bind(from_id)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    sqlx::query("UPDATE transactions SET linked_transaction_id = ?, transfer_group_id = id WHERE id = ?")
+        .bind(to_id)
+        .bind(from_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (account_id, balance_change) in [(from.id, -amount - fee), (to.id, converted)] {
+        sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+            .bind(balance_change)
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let mut legs = Vec::new();
+    for transaction_id in [from_id, to_id] {
+        let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+            .bind(transaction_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let payload = serde_json::to_value(&transaction).unwrap_or_default();
+        webhooks::enqueue_event(&mut tx, from.user_id, "transaction.created", &payload).await?;
+        legs.push(transaction);
+    }
+
+    tx.commit().await?;
+
+    let to_transaction = legs.pop().unwrap();
+    let from_transaction = legs.pop().unwrap();
+    Ok(Transfer {
+        from_transaction,
+        to_transaction,
+        exchange_rate: rate,
+    })
+}
+
+/// Pair up transfer legs by transfer group, newest transfer first. Groups
+/// missing a leg (one was deleted) are left out.
+pub fn pair_legs(legs: Vec<Transaction>) -> Vec<Transfer> {
+    let mut groups: BTreeMap<i64, (Option<Transaction>, Option<Transaction>)> = BTreeMap::new();
+    for leg in legs {
+        let Some(group_id) = leg.transfer_group_id else { continue };
+        let entry = groups.entry(group_id).or_default();
+        if leg.transaction_type == "income" {
+            entry.1 = Some(leg);
+        } else {
+            entry.0 = Some(leg);
+        }
+    }
+
+    let mut transfers: Vec<Transfer> = groups
+        .into_values()
+        .filter_map(|pair| match pair {
+            (Some(from_transaction), Some(to_transaction)) => {
+                // Closing transfers don't record their rate
+                let exchange_rate = to_transaction
+                    .exchange_rate
+                    .unwrap_or(if from_transaction.amount > 0.0 {
+                        to_transaction.amount / from_transaction.amount
+                    } else {
+                        1.0
+                    });
+                Some(Transfer {
+                    from_transaction,
+                    to_transaction,
+                    exchange_rate,
+                })
+            }
+            _ => None,
+        })
+        .collect();
+    transfers.sort_by(|a, b| {
+        b.from_transaction
+            .transaction_date
+            .cmp(&a.from_transaction.transaction_date)
+            .then(b.from_transaction.id.cmp(&a.from_transaction.id))
+    });
+    transfers
+}
+
+/// All transfers between the user's accounts, newest first
+pub async fn transfers_for_user(pool: &SqlitePool, user_id: i64) -> Result<Vec<Transfer>, sqlx::Error> {
+    let legs = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions
+         WHERE transfer_group_id IS NOT NULL
+         AND account_id IN (SELECT id FROM accounts WHERE user_id = ?)",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(pair_legs(legs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn test_transfer_books_linked_legs_across_currencies() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let other = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).balance(500.0).insert(&pool).await;
+        let euros = testing::account(user.id).currency("EUR").balance(10.0).insert(&pool).await;
+        let foreign = testing::account(other.id).insert(&pool).await;
+
+        let now = Utc::now();
+        assert!(validate_transfer(&checking, &checking, now).is_err());
+        assert!(validate_transfer(&checking, &foreign, now).is_err());
+        validate_transfer(&checking, &euros, now).unwrap();
+
+        let data = CreateTransfer {
+            from_account_id: checking.id,
+            to_account_id: euros.id,
+            amount: 200.0,
+            description: None,
+            transaction_date: None,
+            fee: Some(2.5),
+        };
+        let transfer = create_transfer(&pool, &checking, &euros, &data, 0.68).await.unwrap();
+        let (from, to) = (&transfer.from_transaction, &transfer.to_transaction);
+        assert_eq!((from.transaction_type.as_str(), from.amount), ("transfer", 200.0));
+        assert_eq!((to.transaction_type.as_str(), to.amount), ("income", 136.0));
+        assert_eq!((from.linked_transaction_id, to.linked_transaction_id), (Some(to.id), Some(from.id)));
+        assert_eq!((from.transfer_group_id, to.transfer_group_id), (Some(from.id), Some(from.id)));
+        assert_eq!((to.original_amount, to.original_currency.as_deref()), (Some(200.0), Some("USD")));
+
+        let balances: Vec<f64> = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id IN (?, ?) ORDER BY id")
+            .bind(checking.id)
+            .bind(euros.id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balances, vec![297.5, 146.0]);
+    }
+
+    #[tokio::test]
+    async fn test_transfers_pair_up_and_stay_out_of_monthly_totals() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).balance(1000.0).insert(&pool).await;
+        let savings = testing::account(user.id).balance(0.0).insert(&pool).await;
+        testing::txn(checking.id).amount(2000.0).income().insert(&pool).await;
+        testing::txn(checking.id).amount(150.0).insert(&pool).await;
+
+        let data = CreateTransfer {
+            from_account_id: checking.id,
+            to_account_id: savings.id,
+            amount: 400.0,
+            description: None,
+            transaction_date: None,
+            fee: None,
+        };
+        let booked = create_transfer(&pool, &checking, &savings, &data, 1.0).await.unwrap();
+
+        let transfers = transfers_for_user(&pool, user.id).await.unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!((transfers[0].from_transaction.id, transfers[0].to_transaction.id), (booked.from_transaction.id, booked.to_transaction.id));
+        assert_eq!((transfers[0].from_transaction.account_id, transfers[0].to_transaction.account_id), (checking.id, savings.id));
+
+        // A leg on its own isn't a transfer anymore
+        assert!(pair_legs(vec![booked.to_transaction.clone()]).is_empty());
+
+        // The 400 moved to savings is neither income nor spending
+        let totals = crate::reports::monthly_totals(&pool, user.id, 1, Utc::now()).await.unwrap();
+        assert_eq!((totals[0].total_income, totals[0].total_expense, totals[0].transaction_count), (2000.0, 150.0, 2));
+    }
+}
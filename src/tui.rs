@@ -11,17 +11,65 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Frame, Terminal,
 };
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::time::{Duration as StdDuration, Instant};
 
-use chrono::Utc;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use crate::account_groups;
+use crate::accounts;
+use crate::activity;
+use crate::attachments;
+use crate::auth;
+use crate::buckets;
+use crate::budgets;
+use crate::categories;
+use crate::charts;
+use crate::credit;
+use crate::currencies;
+use crate::digest;
+use crate::encryption;
+use crate::exclusions;
+use crate::exchange_scraper;
+use crate::forecast;
+use crate::fx;
+use crate::goals;
+use crate::holdings;
+use crate::i18n::{self, Language};
+use crate::import;
+use crate::input;
+use crate::net_worth;
 use crate::models::*;
+use crate::patterns;
+use crate::prices;
+use crate::profiles::{self, Profile};
+use crate::qif;
+use crate::rate_providers;
+use crate::rate_sources;
+use crate::receipts;
+use crate::reconcile;
 use crate::recurring;
+use crate::reimbursements;
+use crate::remote::{self, RemoteClient};
+use crate::reports;
+use crate::rules;
+use crate::seed;
+use crate::settings;
+use crate::statements;
+use crate::status;
+use crate::transaction_status::{self, balance_effect};
+use crate::tags;
+use crate::transfers;
+use crate::undo;
+use crate::wizard::{Field, Step, Wizard, WizardEvent};
+use crate::xlsx;
 use sqlx::SqlitePool;
 
+type DateRange = (DateTime<Utc>, DateTime<Utc>); // [start, end)
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Screen {
     UserSelect,
@@ -33,8 +81,23 @@ enum Screen {
     ExchangeRates,
     Reports,
     Export,
+    Budgets,
+    Settings,
+    Goals,
+    Investments,
 }
 
+/// Rows of the Settings tab, in order; see change_setting
+const SETTING_ROWS: [&str; 7] = [
+    "Base currency",
+    "Date format",
+    "Default account",
+    "Language",
+    "Week starts on",
+    "Weekly digest",
+    "Page after login",
+];
+
 #[derive(Debug, Clone, PartialEq)]
 enum Mode {
     Normal,
@@ -50,29 +113,175 @@ enum Mode {
     ExportData,
     SelectCurrencyFilter,
     SelectViewCurrency,
+    EditTransactionNotes,
+    ExportPassphrase,
+    QuickAdd,
+    Onboarding,
+    SelectProfile,
+    Recategorize,
+    SetBudget,
+    AddGoal,
+    AddHolding,
+    AddTransfer,
+    WeeklyDigest,
+    EnterPassword,
+    ReceiptPath,
+    EditTransaction,
+    SearchTransactions,
+    SelectDateRange,
+    ImportCsv,
+    SetAccountGroup,
+    ChangeAccountCurrency,
+    SystemStatus,
+    EditTags,
+}
+
+/// First-run setup steps, shown when there are no users or the user has no accounts
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OnboardingStep {
+    CreateUser,
+    CreateAccount,
+    SeedCategories,
+    ScrapeRates,
+}
+
+impl OnboardingStep {
+    fn number(self) -> usize {
+        match self {
+            OnboardingStep::CreateUser => 1,
+            OnboardingStep::CreateAccount => 2,
+            OnboardingStep::SeedCategories => 3,
+            OnboardingStep::ScrapeRates => 4,
+        }
+    }
+}
+
+/// The add/edit transaction form once validated
+struct TransactionForm {
+    account_id: i64,
+    amount: f64,
+    transaction_type: &'static str,
+    description: Option<String>,
+    categories: Vec<CategoryAmount>, // Empty: no category given and no account default
+    fee: f64,
+    fee_category_id: Option<i64>,
+}
+
+/// Categories typed into the transaction form: a single category ID books
+/// the whole amount, "ID:amount, ID:amount" splits it. None when malformed.
+fn parse_category_splits(input: &str, amount: f64) -> Option<Vec<CategoryAmount>> {
+    let input = input.trim();
+    if !input.contains(':') {
+        let category_id = input.parse::<i64>().ok()?;
+        return Some(vec![CategoryAmount { category_id, amount: amount.abs() }]);
+    }
+    input
+        .split(',')
+        .filter(|split| !split.trim().is_empty())
+        .map(|split| {
+            let (category_id, amount) = split.split_once(':')?;
+            Some(CategoryAmount {
+                category_id: category_id.trim().parse().ok()?,
+                amount: amount.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Short bar label for an amount: "850", "1.2k", "12k"
+fn compact_amount(amount: f64) -> String {
+    match amount.abs() {
+        a if a >= 10_000.0 => format!("{:.0}k", amount / 1000.0),
+        a if a >= 1000.0 => format!("{:.1}k", amount / 1000.0),
+        _ => format!("{:.0}", amount),
+    }
+}
+
+/// `text` split into spans with the (ASCII case-insensitive) matches of
+/// `query` highlighted
+fn highlight_matches(text: &str, query: &str, style: Style) -> Vec<Span<'static>> {
+    let query = query.to_ascii_lowercase();
+    if query.is_empty() {
+        return vec![Span::styled(text.to_string(), style)];
+    }
+    let lower = text.to_ascii_lowercase();
+    let mut spans = Vec::new();
+    let mut rest = 0;
+    for (start, _) in lower.match_indices(&query) {
+        spans.push(Span::styled(text[rest..start].to_string(), style));
+        spans.push(Span::styled(
+            text[start..start + query.len()].to_string(),
+            style.fg(Color::Black).bg(Color::Yellow),
+        ));
+        rest = start + query.len();
+    }
+    spans.push(Span::styled(text[rest..].to_string(), style));
+    spans
 }
 
 pub struct App {
-    pool: SqlitePool,
+    pool: SqlitePool, // The in-memory mirror in remote mode
+    remote: Option<RemoteClient>, // --remote: writes go to the server's API
+    last_remote_sync: Instant,
+    profile: Option<String>, // Active --profile, None when running on DATABASE_URL
+    profiles: Vec<Profile>,
+    profile_index: usize, // Highlighted entry in the profile switcher
     current_screen: Screen,
     selected_tab: usize,
     should_quit: bool,
     mode: Mode,
+    onboarding: Option<OnboardingStep>,
 
     // User selection
     current_user_id: Option<i64>,
 
     // Cached data
     accounts: Vec<Account>,
+    accounts_grouped: bool, // Accounts tab lists accounts under their groups
+    account_groups: Vec<AccountGroup>,
+    group_balances: Vec<AccountGroupBalance>,
     transactions: Vec<Transaction>,
+    transfers: HashMap<i64, (i64, i64)>, // Transfer group -> source and destination account id
     categories: Vec<Category>,
+    excluded_categories: HashSet<i64>, // Categories whose transactions are left out of the summaries
     users: Vec<User>,
     exchange_rates: Vec<ExchangeRate>,
+    last_scrape_run: Option<ScrapeRun>,
     recurring_transactions: Vec<RecurringTransaction>,
+    templates: Vec<TransactionTemplate>,
+    import_profiles: Vec<ImportProfile>, // Saved CSV mappings for the import wizard
+    category_hints: HashMap<String, i64>, // Lowercased description/merchant -> last category used
+    projected_balances: HashMap<i64, f64>, // Account id -> month-end balance with pending and recurring items
+    running_balances: HashMap<i64, f64>,   // Transaction id -> account balance right after it
     category_spending: Vec<CategorySpendingSummary>,
+    report_months: u32, // Window of the Reports charts: 3, 6 or 12 months
+    monthly_totals: Vec<MonthlySummary>,
+    report_category_spending: Vec<CategorySpendingSummary>,
+    report_rollup: bool, // Fold sub-categories into their parents in the category chart
+    report_forecast: bool, // Reports shows the cash-flow forecast instead of the charts
+    cash_flow_forecast: Option<CashFlowForecast>, // For the report_months after this one
+    merchant_spending: Vec<MerchantSpendingSummary>,
+    spending_patterns: Option<SpendingPatterns>,
+    budget_variance: Vec<BudgetVariance>, // Current month
+    goals: Vec<GoalProgress>,
+    investments: Vec<AccountHoldings>, // One per investment account
+    bucket_split: Option<BucketSplit>,    // Current month, needs/wants/savings vs. target
+    weekly_digest: Option<WeeklyDigest>,  // Shown once after login
+    system_status: Option<SystemStatus>,  // Loaded when the Status screen opens
+    safe_to_spend: Option<SafeToSpend>,
+    activity: Vec<ActivityItem>,
 
     // Selection state
     selected_index: usize,
+    category_picker_index: usize, // Highlighted category when recategorizing a transaction
+    budget_category_index: usize, // Category being budgeted in the set-budget form
+    form_budget_limit: String,
+    wizard: Option<Wizard>, // The recurring or transfer form being filled in
+    goal_field: usize, // 0: name, 1: target, 2: target date, 3: account
+    goal_account_index: usize,
+    form_goal_name: String,
+    form_goal_target: String,
+    form_goal_date: String,
     #[allow(dead_code)]
     list_state: ListState,
 
@@ -82,7 +291,26 @@ pub struct App {
     form_type: String,
     form_description: String,
     form_category_id: String,
+    form_fee: String,
+    form_fee_category_id: String,
+    form_merchant: Option<String>, // Set when the form is pre-filled from a template
+    form_date: Option<DateTime<Utc>>, // Set when the form is pre-filled from a receipt
+    form_receipt_path: String,
+    form_import_profile: Option<usize>, // Index into import_profiles; None detects the columns
+    form_import_account: usize,         // Index into accounts
+    form_import_path: String,
+    form_account_group: String, // Group name typed for the selected account; empty ungroups it
+    form_new_currency: String,  // Currency typed for the selected account
+    form_currency_rate: String, // New currency per old unit; empty uses the latest FX rate
+    form_account_transactions: i64, // Transactions the currency change would convert
+    form_tag: String,           // Tag typed in the details view: added, or removed if already there
+    editing_transaction: Option<Transaction>, // Row being changed through the transaction form
     form_field_index: usize,
+    last_used_account_id: Option<i64>, // Account of the last transaction added from the TUI
+
+    // Form data for editing transaction notes/reference
+    form_notes: String,
+    form_reference: String,
 
     // Form data for adding exchange rate
     form_from_currency: String,
@@ -96,9 +324,6 @@ pub struct App {
     form_convert_amount: String,
     form_converted_result: String,
 
-    // Form data for recurring transaction
-    form_recurring_frequency: String,
-
     // Form data for adding account
     form_account_name: String,
     form_account_bank: String,
@@ -109,24 +334,49 @@ pub struct App {
     // Form data for adding category
     form_category_name: String,
     form_category_type: String,  // income or expense
+    form_category_parent: String, // Parent category ID; empty for a top-level category
+    editing_category: Option<Category>, // Category being changed through the category form
 
     // Form data for adding user
     form_user_username: String,
     form_user_email: String,
+    require_password: bool, // Ask for the password before logging in (TUI_REQUIRE_PASSWORD)
+    login_password: String,
 
     // Export options
     #[allow(dead_code)]
     export_format: String,
     export_message: String,
+    export_encrypt: bool, // Encrypt export files with export_passphrase
+    export_passphrase: String,
+    export_range_start: String, // Inclusive YYYY-MM-DD dates; both empty exports the Transactions tab's list
+    export_range_end: String,
+    export_range_field: usize, // 0: format keys, 1: From, 2: To
 
     // Currency filter for transactions view
     currency_filter: Option<String>,
+    transaction_search: String,                   // Live filter of the Transactions tab, typed after '/'
+    transaction_range: Option<(DateTime<Utc>, DateTime<Utc>)>, // [start, end) loaded instead of the latest 100
+    form_range_start: String,
+    form_range_end: String,
+    transaction_categories: HashMap<i64, String>, // Transaction id -> its category names, for searching
+    transaction_tags: HashMap<i64, Vec<String>>,  // Transaction id -> its tag names
+    attachment_counts: HashMap<i64, i64>,         // Transaction id -> number of attached receipts
+    reimbursed_amounts: HashMap<i64, f64>,        // Expense id -> amount reimbursed so far
+    view_rates: HashMap<i64, f64>,                // Transaction id -> rate into view_in_currency, see refresh_view_rates
+    undo_stack: Vec<undo::Deletion>,              // Deletions of this session, most recent last
+    language: Language,                           // Language of the interface text, see i18n
+    transaction_splits: HashMap<i64, Vec<TransactionCategoryDetail>>, // Transaction id -> its category splits
     filter_currencies: Vec<String>,  // Only currencies from user's transactions (for filter)
     available_currencies: Vec<String>,  // All currencies from FX rates (for view in currency)
     
     // View in currency conversion
     view_in_currency: Option<String>,  // For Transactions screen - convert all amounts
     account_view_currency: Option<String>,  // For Account Details only - separate from transactions
+    base_currency: Option<String>,  // User's base_currency setting - Dashboard and Reports totals are in it
+    base_rates: HashMap<i64, f64>,  // Transaction id -> rate into base_currency, see refresh_view_rates
+    account_base_rates: HashMap<i64, f64>,  // Account id -> latest rate into base_currency
+    user_settings: UserSettings,  // The logged-in user's settings, shown on the Settings tab
     currency_scroll_offset: usize,  // Scroll offset for currency selection dialogs
 
     // Status message
@@ -137,29 +387,88 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: SqlitePool, profile: Option<String>) -> Self {
         Self {
             pool,
+            remote: None,
+            last_remote_sync: Instant::now(),
+            profile,
+            profiles: profiles::configured_profiles(),
+            profile_index: 0,
             current_screen: Screen::UserSelect,
             selected_tab: 0,
             should_quit: false,
             mode: Mode::Normal,
+            onboarding: None,
             current_user_id: None,
             accounts: Vec::new(),
+            accounts_grouped: false,
+            account_groups: Vec::new(),
+            group_balances: Vec::new(),
             transactions: Vec::new(),
+            transfers: HashMap::new(),
             categories: Vec::new(),
+            excluded_categories: HashSet::new(),
             users: Vec::new(),
             exchange_rates: Vec::new(),
+            last_scrape_run: None,
             recurring_transactions: Vec::new(),
+            templates: Vec::new(),
+            import_profiles: Vec::new(),
+            category_hints: HashMap::new(),
+            projected_balances: HashMap::new(),
+            running_balances: HashMap::new(),
             category_spending: Vec::new(),
+            report_months: 6,
+            monthly_totals: Vec::new(),
+            report_category_spending: Vec::new(),
+            report_rollup: false,
+            report_forecast: false,
+            cash_flow_forecast: None,
+            merchant_spending: Vec::new(),
+            spending_patterns: None,
+            budget_variance: Vec::new(),
+            goals: Vec::new(),
+            investments: Vec::new(),
+            bucket_split: None,
+            weekly_digest: None,
+            system_status: None,
+            safe_to_spend: None,
+            activity: Vec::new(),
             selected_index: 0,
+            category_picker_index: 0,
+            budget_category_index: 0,
+            form_budget_limit: String::new(),
+            wizard: None,
+            goal_field: 0,
+            goal_account_index: 0,
+            form_goal_name: String::new(),
+            form_goal_target: String::new(),
+            form_goal_date: String::new(),
             list_state: ListState::default(),
             form_account_id: String::new(),
             form_amount: String::new(),
             form_type: String::from("expense"),
             form_description: String::new(),
             form_category_id: String::new(),
+            form_fee: String::new(),
+            form_fee_category_id: String::new(),
+            form_merchant: None,
+            form_date: None,
+            form_receipt_path: String::new(),
+            form_import_profile: None,
+            form_import_account: 0,
+            form_import_path: String::new(),
+            form_account_group: String::new(),
+            form_new_currency: String::new(),
+            form_currency_rate: String::new(),
+            form_account_transactions: 0,
+            form_tag: String::new(),
+            editing_transaction: None,
             form_field_index: 0,
+            last_used_account_id: None,
+            form_notes: String::new(),
+            form_reference: String::new(),
             form_from_currency: String::new(),
             form_to_currency: String::new(),
             form_rate: String::new(),
@@ -168,7 +477,6 @@ impl App {
             form_convert_to: String::new(),
             form_convert_amount: String::new(),
             form_converted_result: String::new(),
-            form_recurring_frequency: String::from("monthly"),
             form_account_name: String::new(),
             form_account_bank: String::new(),
             form_account_type: String::from("checking"),
@@ -176,24 +484,64 @@ impl App {
             form_account_balance: String::from("0"),
             form_category_name: String::new(),
             form_category_type: String::from("expense"),
+            form_category_parent: String::new(),
+            editing_category: None,
             form_user_username: String::new(),
             form_user_email: String::new(),
+            require_password: auth::tui_requires_password(),
+            login_password: String::new(),
             export_format: String::from("csv"),
             export_message: String::new(),
+            export_encrypt: false,
+            export_passphrase: String::new(),
+            export_range_start: String::new(),
+            export_range_end: String::new(),
+            export_range_field: 0,
             currency_filter: None,
+            transaction_search: String::new(),
+            transaction_range: None,
+            form_range_start: String::new(),
+            form_range_end: String::new(),
+            transaction_categories: HashMap::new(),
+            transaction_splits: HashMap::new(),
+            transaction_tags: HashMap::new(),
+            attachment_counts: HashMap::new(),
+            reimbursed_amounts: HashMap::new(),
+            view_rates: HashMap::new(),
+            undo_stack: Vec::new(),
+            language: i18n::configured_language(),
             filter_currencies: Vec::new(),
             available_currencies: Vec::new(),
             view_in_currency: None,
             account_view_currency: None,
+            base_currency: None,
+            base_rates: HashMap::new(),
+            account_base_rates: HashMap::new(),
+            user_settings: UserSettings::default(),
             currency_scroll_offset: 0,
             status_message: String::new(),
             last_auto_refresh: Instant::now(),
         }
     }
 
+    /// The TUI on a server's API: `mirror` holds the server's data
+    /// (remote::RemoteClient::mirror) and the session's user is signed in.
+    /// Profiles are local databases, so there are none to switch to.
+    pub fn remote(mirror: SqlitePool, remote: RemoteClient) -> Self {
+        let mut app = Self::new(mirror, None);
+        app.profiles.clear();
+        app.require_password = false;
+        app.remote = Some(remote);
+        app
+    }
+
     pub async fn run(&mut self) -> io::Result<()> {
         // Load users first
         self.load_users().await;
+        self.start_onboarding_if_empty();
+        if let Some(index) = self.remote_user_index() {
+            self.log_in(index).await;
+        }
 
         // Setup terminal
         enable_raw_mode()?;
@@ -208,6 +556,7 @@ impl App {
             self.handle_events().await?;
             self.maybe_auto_refresh().await;
         }
+        self.clear_undo_stack();
 
         // Restore terminal
         disable_raw_mode()?;
@@ -221,6 +570,14 @@ impl App {
         Ok(())
     }
 
+    fn start_onboarding_if_empty(&mut self) {
+        if self.users.is_empty() {
+            self.onboarding = Some(OnboardingStep::CreateUser);
+            self.clear_user_form();
+            self.mode = Mode::AddUser;
+        }
+    }
+
     async fn load_users(&mut self) {
         if let Ok(users) = sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY created_at DESC")
             .fetch_all(&self.pool)
@@ -230,6 +587,34 @@ impl App {
         }
     }
 
+    /// Monthly totals and category spending for the Reports charts, and the
+    /// forecast of as many months ahead while it is shown
+    async fn load_report_charts(&mut self, user_id: i64) {
+        let now = Utc::now();
+        if let Ok(totals) = reports::monthly_totals(&self.pool, user_id, self.report_months, now).await {
+            let start = totals.first().and_then(|t| budgets::parse_period(&t.month)).map(|(start, _)| start);
+            if let Ok(spending) = charts::category_spending(&self.pool, user_id, start.unwrap_or(now), now).await {
+                self.report_category_spending = if self.report_rollup {
+                    categories::roll_up(spending, &self.categories)
+                } else {
+                    spending
+                };
+            }
+            self.monthly_totals = totals;
+        }
+        self.load_forecast(user_id).await;
+    }
+
+    /// The forecast replays every account's recurring items, so it is only
+    /// computed while Reports shows it
+    async fn load_forecast(&mut self, user_id: i64) {
+        self.cash_flow_forecast = if self.report_forecast {
+            forecast::cash_flow_forecast(&self.pool, user_id, self.report_months, Utc::now()).await.ok()
+        } else {
+            None
+        };
+    }
+
     async fn load_data(&mut self) {
         if self.current_user_id.is_none() {
             return;
@@ -239,7 +624,7 @@ impl App {
 
         // Load accounts for current user
         if let Ok(accounts) = sqlx::query_as::<_, Account>(
-            "SELECT * FROM accounts WHERE user_id = ? ORDER BY created_at DESC",
+            "SELECT * FROM accounts WHERE user_id = ? ORDER BY is_favorite DESC, sort_order ASC, created_at DESC",
         )
         .bind(user_id)
         .fetch_all(&self.pool)
@@ -248,6 +633,33 @@ impl App {
             self.accounts = accounts;
         }
 
+        if let Ok(groups) = account_groups::groups_for_user(&self.pool, user_id).await {
+            self.account_groups = groups;
+        }
+        if let Ok(balances) = net_worth::group_balances(&self.pool, user_id, None).await {
+            self.group_balances = balances;
+        }
+        if self.accounts_grouped {
+            // Same order as the group headers: by group name, ungrouped last
+            let groups = &self.account_groups;
+            self.accounts.sort_by_key(|a| {
+                a.account_group_id
+                    .and_then(|id| groups.iter().position(|g| g.id == id))
+                    .unwrap_or(groups.len())
+            });
+        }
+
+        self.projected_balances.clear();
+        self.running_balances.clear();
+        for account in &self.accounts {
+            if let Ok(projected) = forecast::projected_balance(&self.pool, account, Utc::now()).await {
+                self.projected_balances.insert(account.id, projected);
+            }
+            if let Ok(balances) = reconcile::running_balances(&self.pool, account.id).await {
+                self.running_balances.extend(balances);
+            }
+        }
+
         // Get account IDs for this user
         let account_ids: Vec<i64> = self.accounts.iter().map(|a| a.id).collect();
 
@@ -258,15 +670,27 @@ impl App {
                 .map(|_| "?")
                 .collect::<Vec<_>>()
                 .join(",");
-            let query = format!(
-                "SELECT * FROM transactions WHERE account_id IN ({}) ORDER BY transaction_date DESC LIMIT 100",
-                placeholders
-            );
+            // The whole chosen period, otherwise the latest 100
+            let query = match self.transaction_range {
+                Some(_) => format!(
+                    "SELECT * FROM transactions WHERE account_id IN ({})
+                     AND datetime(transaction_date) >= datetime(?) AND datetime(transaction_date) < datetime(?)
+                     ORDER BY transaction_date DESC",
+                    placeholders
+                ),
+                None => format!(
+                    "SELECT * FROM transactions WHERE account_id IN ({}) ORDER BY transaction_date DESC LIMIT 100",
+                    placeholders
+                ),
+            };
 
             let mut q = sqlx::query_as::<_, Transaction>(&query);
             for id in &account_ids {
                 q = q.bind(*id);
             }
+            if let Some((start, end)) = self.transaction_range {
+                q = q.bind(start.to_rfc3339()).bind(end.to_rfc3339());
+            }
 
             if let Ok(transactions) = q.fetch_all(&self.pool).await {
                 self.transactions = transactions;
@@ -275,35 +699,80 @@ impl App {
             self.transactions.clear();
         }
 
+        if let Ok(transfers) = transfers::transfers_for_user(&self.pool, user_id).await {
+            self.transfers = transfers
+                .into_iter()
+                .filter_map(|t| {
+                    let group_id = t.from_transaction.transfer_group_id?;
+                    Some((group_id, (t.from_transaction.account_id, t.to_transaction.account_id)))
+                })
+                .collect();
+        }
+
         // Load categories for current user
-        if let Ok(categories) = sqlx::query_as::<_, Category>(
+        if let Ok(loaded) = sqlx::query_as::<_, Category>(
             "SELECT * FROM categories WHERE user_id = ? ORDER BY name",
         )
         .bind(user_id)
         .fetch_all(&self.pool)
         .await
         {
-            self.categories = categories;
-        }
-
-        // Load exchange rates - get the most recent rate for each currency pair
-        // Use subquery to get only the latest rate per pair to avoid duplicates
-        if let Ok(rates) = sqlx::query_as::<_, ExchangeRate>(
-            "SELECT e1.* FROM exchange_rates e1
-             INNER JOIN (
-                 SELECT from_currency, to_currency, MAX(rate_date) as max_date
-                 FROM exchange_rates
-                 GROUP BY from_currency, to_currency
-             ) e2 ON e1.from_currency = e2.from_currency 
-                  AND e1.to_currency = e2.to_currency 
-                  AND e1.rate_date = e2.max_date
-             ORDER BY e1.from_currency, e1.to_currency",
+            // Sub-categories are listed under their parent
+            self.categories = categories::tree_order(loaded);
+            self.excluded_categories =
+                self.categories.iter().filter(|c| c.exclude_from_reports).map(|c| c.id).collect();
+        }
+
+        // Category splits per transaction, for the details view and so the
+        // Transactions search can match category names
+        if let Ok(rows) = sqlx::query_as::<_, (i64, i64, String, f64)>(
+            "SELECT tc.transaction_id, c.id, c.name, tc.amount
+             FROM transaction_categories tc
+             JOIN categories c ON c.id = tc.category_id
+             WHERE c.user_id = ?
+             ORDER BY tc.transaction_id, tc.amount DESC",
         )
+        .bind(user_id)
         .fetch_all(&self.pool)
         .await
         {
+            self.transaction_splits.clear();
+            for (transaction_id, category_id, category_name, amount) in rows {
+                self.transaction_splits.entry(transaction_id).or_default().push(TransactionCategoryDetail {
+                    category_id,
+                    category_name,
+                    amount,
+                });
+            }
+            self.transaction_categories = self
+                .transaction_splits
+                .iter()
+                .map(|(id, splits)| {
+                    let names: Vec<&str> = splits.iter().map(|s| s.category_name.as_str()).collect();
+                    (*id, names.join(", "))
+                })
+                .collect();
+        }
+
+        if let Ok(tags) = tags::tags_by_transaction(&self.pool, user_id).await {
+            self.transaction_tags = tags;
+        }
+        if let Ok(counts) = attachments::attachment_counts(&self.pool, user_id).await {
+            self.attachment_counts = counts;
+        }
+        if let Ok(amounts) = reimbursements::reimbursed_amounts(&self.pool, user_id).await {
+            self.reimbursed_amounts = amounts;
+        }
+
+        // Load exchange rates - the rate each pair currently converts at
+        // (pinned rate, then the latest day's most trusted source)
+        if let Ok(rates) = fx::effective_rates(&self.pool, &fx::source_priority()).await {
             self.exchange_rates = rates;
         }
+        self.refresh_view_rates().await;
+        if let Ok(runs) = exchange_scraper::recent_scrape_runs(&self.pool, 1).await {
+            self.last_scrape_run = runs.into_iter().next();
+        }
 
         // Load recurring transactions for user's accounts
         if !account_ids.is_empty() {
@@ -329,6 +798,68 @@ impl App {
             self.recurring_transactions.clear();
         }
 
+        // Load transaction templates for the add-transaction form
+        if let Ok(templates) = sqlx::query_as::<_, TransactionTemplate>(
+            "SELECT * FROM transaction_templates WHERE user_id = ? ORDER BY name",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        {
+            self.templates = templates;
+        }
+
+        // Load transaction templates for the add-transaction form
+        if let Ok(templates) = sqlx::query_as::<_, TransactionTemplate>(
+            "SELECT * FROM transaction_templates WHERE user_id = ? ORDER BY name",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        {
+            self.templates = templates;
+        }
+
+        if let Ok(profiles) = import::profiles_for_user(&self.pool, user_id).await {
+            self.import_profiles = profiles;
+        }
+
+        // Category hints for quick-add: the most recent category per description and merchant
+        if let Ok(hints) = sqlx::query_as::<_, (String, i64)>(
+            "SELECT LOWER(key), category_id FROM (
+                 SELECT t.description as key, tc.category_id, t.transaction_date
+                 FROM transactions t
+                 INNER JOIN transaction_categories tc ON tc.transaction_id = t.id
+                 INNER JOIN accounts a ON t.account_id = a.id
+                 WHERE a.user_id = ? AND t.description IS NOT NULL
+                 UNION ALL
+                 SELECT t.merchant as key, tc.category_id, t.transaction_date
+                 FROM transactions t
+                 INNER JOIN transaction_categories tc ON tc.transaction_id = t.id
+                 INNER JOIN accounts a ON t.account_id = a.id
+                 WHERE a.user_id = ? AND t.merchant IS NOT NULL
+             )
+             ORDER BY transaction_date DESC",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        {
+            self.category_hints.clear();
+            for (key, category_id) in hints {
+                self.category_hints.entry(key).or_insert(category_id);
+            }
+        }
+
+        // Safe-to-spend headline for the dashboard
+        self.safe_to_spend = forecast::safe_to_spend(&self.pool, user_id, Utc::now()).await.ok();
+
+        // Recent activity panel
+        if let Ok(items) = activity::recent_activity(&self.pool, user_id, 20).await {
+            self.activity = items;
+        }
+
         // Load category spending summary
         self.load_category_spending().await;
     }
@@ -341,19 +872,38 @@ impl App {
         let user_id = self.current_user_id.unwrap();
 
         // Query that only shows categories with actual spending (INNER JOIN instead of LEFT JOIN)
+        // Fees are added as their own category ("Fees" when no fee category is set);
+        // reimbursed expenses only count what they still cost
         let query = format!(
-            "SELECT c.id as category_id, c.name as category_name,
-                    SUM(ABS(tc.amount)) as total_amount,
-                    COUNT(DISTINCT t.id) as transaction_count
-             FROM transaction_categories tc
-             INNER JOIN categories c ON tc.category_id = c.id
-             INNER JOIN transactions t ON tc.transaction_id = t.id
-             INNER JOIN accounts a ON t.account_id = a.id
-             WHERE a.user_id = {} AND t.transaction_type = 'expense'
-             GROUP BY c.id, c.name
+            "SELECT category_id, category_name,
+                    SUM(total_amount) as total_amount,
+                    SUM(transaction_count) as transaction_count
+             FROM (
+                 SELECT c.id as category_id, c.name as category_name,
+                        SUM(ABS(tc.amount) * {2}) as total_amount,
+                        COUNT(DISTINCT t.id) as transaction_count
+                 FROM transaction_categories tc
+                 INNER JOIN categories c ON tc.category_id = c.id
+                 INNER JOIN transactions t ON tc.transaction_id = t.id
+                 INNER JOIN accounts a ON t.account_id = a.id
+                 WHERE a.user_id = {0} AND t.transaction_type = 'expense' AND {1}
+                 GROUP BY c.id, c.name
+                 UNION ALL
+                 SELECT COALESCE(c.id, 0) as category_id, COALESCE(c.name, 'Fees') as category_name,
+                        SUM(t.fee) as total_amount,
+                        COUNT(*) as transaction_count
+                 FROM transactions t
+                 INNER JOIN accounts a ON t.account_id = a.id
+                 LEFT JOIN categories c ON t.fee_category_id = c.id
+                 WHERE a.user_id = {0} AND t.fee > 0 AND {1}
+                 GROUP BY COALESCE(c.id, 0), COALESCE(c.name, 'Fees')
+             )
+             GROUP BY category_id, category_name
              HAVING total_amount > 0
              ORDER BY total_amount DESC",
-            user_id
+            user_id,
+            exclusions::COUNTED,
+            reimbursements::UNREIMBURSED_SHARE
         );
 
         if let Ok(spending) = sqlx::query_as::<_, CategorySpendingSummary>(&query)
@@ -363,51 +913,84 @@ impl App {
             self.category_spending = spending;
         }
 
+        self.load_report_charts(user_id).await;
+
+        // Payee leaderboard: expense totals per normalized merchant
+        if let Ok(merchants) = sqlx::query_as::<_, MerchantSpendingSummary>(
+            "SELECT t.merchant as merchant,
+                    MAX(t.merchant_city) as merchant_city,
+                    MAX(t.merchant_country) as merchant_country,
+                    SUM(ABS(t.amount)) as total_amount,
+                    COUNT(*) as transaction_count
+             FROM transactions t
+             INNER JOIN accounts a ON t.account_id = a.id
+             WHERE a.user_id = ? AND t.transaction_type = 'expense' AND t.merchant IS NOT NULL
+             GROUP BY t.merchant
+             ORDER BY total_amount DESC
+             LIMIT 10",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        {
+            self.merchant_spending = merchants;
+        }
+
+        // Weekday/hour spending grid, in local time
+        let utc_offset_minutes = chrono::Local::now().offset().local_minus_utc() as i64 / 60;
+        self.spending_patterns = patterns::spending_patterns(
+            &self.pool,
+            Some(user_id),
+            None,
+            None,
+            utc_offset_minutes,
+        )
+        .await
+        .ok();
+
+        // Budget vs. actual for the current month
+        let period = chrono::Local::now().format("%Y-%m").to_string();
+        if let Ok(rows) = budgets::budget_variance(&self.pool, user_id, &period).await {
+            self.budget_variance = rows;
+        }
+        if let Ok(progress) = goals::goals_progress(&self.pool, user_id, Utc::now().date_naive()).await {
+            self.goals = progress;
+        }
+        let mut investments = Vec::new();
+        for account in self.accounts.iter().filter(|a| a.account_type == "investment") {
+            if let Ok(valued) = holdings::account_holdings(&self.pool, account).await {
+                investments.push(valued);
+            }
+        }
+        self.investments = investments;
+        if let Some((start, end)) = budgets::parse_period(&period) {
+            let targets = buckets::target_allocation(&self.pool, user_id)
+                .await
+                .unwrap_or(buckets::DEFAULT_TARGETS);
+            self.bucket_split = buckets::bucket_split(&self.pool, user_id, &period, start, end, targets)
+                .await
+                .ok();
+        }
+
         // Build filter_currencies: only currencies from accounts that have transactions
         let mut filter_currency_codes: std::collections::HashSet<String> = std::collections::HashSet::new();
         for t in &self.transactions {
             if let Some(account) = self.accounts.iter().find(|a| a.id == t.account_id) {
                 filter_currency_codes.insert(account.currency.clone());
             }
+            if let Some(ref original) = t.original_currency {
+                filter_currency_codes.insert(original.clone());
+            }
         }
         self.filter_currencies = filter_currency_codes.into_iter().collect();
         self.filter_currencies.sort();
         
-        // Collect all available currencies from accounts AND exchange rates (for View in Currency)
-        // Use HashSet to deduplicate, but store as (code, display_name) pairs
-        let mut currency_codes: std::collections::HashSet<String> = std::collections::HashSet::new();
-        let mut code_to_display: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-        
-        // Add currencies from user's accounts
-        for a in &self.accounts {
-            let code = Self::extract_currency_code(&a.currency);
-            currency_codes.insert(code.clone());
-            // Account currencies are usually just codes, so display as-is
-            code_to_display.entry(code).or_insert(a.currency.clone());
-        }
-        
-        // Add currencies from exchange rates (both from and to)
-        for r in &self.exchange_rates {
-            let from_code = Self::extract_currency_code(&r.from_currency);
-            let to_code = Self::extract_currency_code(&r.to_currency);
-            
-            currency_codes.insert(from_code.clone());
-            currency_codes.insert(to_code.clone());
-            
-            // Prefer full names with codes like "Argentine Peso (ARS)" over just "ARS"
-            if r.from_currency.contains('(') {
-                code_to_display.insert(from_code, r.from_currency.clone());
-            }
-            if r.to_currency.contains('(') {
-                code_to_display.insert(to_code, r.to_currency.clone());
-            }
+        // Currencies from accounts AND exchange rates (for View in Currency), named
+        // like "Argentine Peso (ARS)" when a scraped rate gives the name
+        if let Ok(currencies) = currencies::currencies_in_use(&self.pool, user_id).await {
+            self.available_currencies = currencies.iter().map(currencies::display_label).collect();
+            self.available_currencies.sort();
         }
-        
-        // Build available currencies list with nice display names (for View in Currency)
-        self.available_currencies = currency_codes.into_iter()
-            .map(|code| code_to_display.get(&code).cloned().unwrap_or(code))
-            .collect();
-        self.available_currencies.sort();
     }
 
     fn ui(&self, frame: &mut Frame) {
@@ -418,6 +1001,11 @@ impl App {
                 self.render_delete_user_confirm(frame);
             } else {
                 self.render_user_select(frame);
+                if self.mode == Mode::SelectProfile {
+                    self.render_profile_dialog(frame, frame.area());
+                } else if self.mode == Mode::EnterPassword {
+                    self.render_password_prompt(frame, frame.area());
+                }
             }
             return;
         }
@@ -440,35 +1028,99 @@ impl App {
 
         // Content based on mode
         match self.mode {
-            Mode::Normal => match self.current_screen {
-                Screen::Dashboard => self.render_dashboard(frame, chunks[2]),
-                Screen::Accounts => self.render_accounts(frame, chunks[2]),
-                Screen::Transactions => self.render_transactions(frame, chunks[2]),
-                Screen::Categories => self.render_categories(frame, chunks[2]),
-                Screen::RecurringTransactions => self.render_recurring_transactions(frame, chunks[2]),
-                Screen::ExchangeRates => self.render_exchange_rates(frame, chunks[2]),
-                Screen::Reports => self.render_reports(frame, chunks[2]),
-                Screen::Export => self.render_export(frame, chunks[2]),
-                Screen::UserSelect => {}
-            },
-            Mode::AddTransaction => self.render_add_transaction_form(frame, chunks[2]),
+            Mode::Normal => self.render_screen(frame, chunks[2]),
+            Mode::AddTransaction | Mode::EditTransaction => self.render_add_transaction_form(frame, chunks[2]),
             Mode::AddExchangeRate => self.render_add_exchange_rate_form(frame, chunks[2]),
-            Mode::AddRecurringTransaction => self.render_add_recurring_form(frame, chunks[2]),
+            Mode::AddRecurringTransaction => {
+                self.render_screen(frame, chunks[2]);
+                self.render_wizard(frame, chunks[2]);
+            }
             Mode::AddAccount => self.render_add_account_form(frame, chunks[2]),
             Mode::AddCategory => self.render_add_category_form(frame, chunks[2]),
             Mode::AddUser => {} // Handled separately in ui()
             Mode::ConvertCurrency => self.render_currency_conversion(frame, chunks[2]),
             Mode::DeleteConfirm => self.render_delete_confirm(frame, chunks[2]),
             Mode::ViewDetails => self.render_details(frame, chunks[2]),
+            Mode::EditTags => {
+                self.render_details(frame, chunks[2]);
+                self.render_tag_form(frame, chunks[2]);
+            }
             Mode::ExportData => self.render_export_dialog(frame, chunks[2]),
             Mode::SelectCurrencyFilter => self.render_currency_filter_dialog(frame, chunks[2]),
+            Mode::SelectDateRange => self.render_date_range_dialog(frame, chunks[2]),
             Mode::SelectViewCurrency => self.render_view_currency_dialog(frame, chunks[2]),
+            Mode::EditTransactionNotes => self.render_edit_notes_form(frame, chunks[2]),
+            Mode::ExportPassphrase => self.render_export_passphrase_form(frame, chunks[2]),
+            Mode::ReceiptPath => self.render_receipt_path_form(frame, chunks[2]),
+            Mode::ImportCsv => self.render_import_form(frame, chunks[2]),
+            Mode::SetAccountGroup => {
+                self.render_screen(frame, chunks[2]);
+                self.render_account_group_form(frame, chunks[2]);
+            }
+            Mode::ChangeAccountCurrency => {
+                self.render_screen(frame, chunks[2]);
+                self.render_account_currency_form(frame, chunks[2]);
+            }
+            Mode::SearchTransactions => self.render_screen(frame, chunks[2]),
+            Mode::Onboarding => self.render_onboarding(frame, chunks[2]),
+            Mode::SelectProfile => {
+                self.render_screen(frame, chunks[2]);
+                self.render_profile_dialog(frame, chunks[2]);
+            }
+            Mode::Recategorize => {
+                self.render_screen(frame, chunks[2]);
+                self.render_category_picker(frame, chunks[2]);
+            }
+            Mode::SetBudget => {
+                self.render_screen(frame, chunks[2]);
+                self.render_budget_form(frame, chunks[2]);
+            }
+            Mode::AddGoal => {
+                self.render_screen(frame, chunks[2]);
+                self.render_goal_form(frame, chunks[2]);
+            }
+            Mode::AddHolding | Mode::AddTransfer => {
+                self.render_screen(frame, chunks[2]);
+                self.render_wizard(frame, chunks[2]);
+            }
+            Mode::WeeklyDigest => {
+                self.render_screen(frame, chunks[2]);
+                self.render_weekly_digest(frame, chunks[2]);
+            }
+            Mode::SystemStatus => {
+                self.render_screen(frame, chunks[2]);
+                self.render_system_status(frame, chunks[2]);
+            }
+            Mode::EnterPassword => self.render_screen(frame, chunks[2]), // Only used on user select
+            Mode::QuickAdd => {
+                // Popup over whatever screen quick-add was opened from
+                self.render_screen(frame, chunks[2]);
+                self.render_quick_add_popup(frame, chunks[2]);
+            }
         }
 
         // Footer
         self.render_footer(frame, chunks[3]);
     }
 
+    fn render_screen(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        match self.current_screen {
+            Screen::Dashboard => self.render_dashboard(frame, area),
+            Screen::Accounts => self.render_accounts(frame, area),
+            Screen::Transactions => self.render_transactions(frame, area),
+            Screen::Categories => self.render_categories(frame, area),
+            Screen::RecurringTransactions => self.render_recurring_transactions(frame, area),
+            Screen::ExchangeRates => self.render_exchange_rates(frame, area),
+            Screen::Reports => self.render_reports(frame, area),
+            Screen::Export => self.render_export(frame, area),
+            Screen::Budgets => self.render_budgets(frame, area),
+            Screen::Settings => self.render_settings(frame, area),
+            Screen::Goals => self.render_goals(frame, area),
+            Screen::Investments => self.render_investments(frame, area),
+            Screen::UserSelect => {}
+        }
+    }
+
     fn render_user_select(&self, frame: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -482,14 +1134,17 @@ impl App {
         // Title
         let title = Paragraph::new(vec![
             Line::from(vec![Span::styled(
-                "Personal Finance Tracker",
+                self.tr("Personal Finance Tracker"),
                 Style::default()
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             )]),
             Line::from(""),
             Line::from(vec![Span::styled(
-                "Select a User to Continue",
+                match &self.profile {
+                    Some(name) => self.tr_fmt("Select a User to Continue (profile: {})", &[&name]),
+                    None => self.tr("Select a User to Continue").to_string(),
+                },
                 Style::default().fg(Color::Yellow),
             )]),
         ])
@@ -514,7 +1169,7 @@ impl App {
                 };
 
                 ListItem::new(Line::from(vec![
-                    Span::styled(format!("ID: {} ", u.id), Style::default().fg(Color::Gray)),
+                    Span::styled(self.tr_fmt("ID: {} ", &[&u.id]), Style::default().fg(Color::Gray)),
                     Span::styled(
                         format!("{:<20}", u.username),
                         Style::default().fg(Color::White),
@@ -528,13 +1183,13 @@ impl App {
         let list = List::new(user_items).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Available Users ({}) - a: Add New User", self.users.len())),
+                .title(self.tr_fmt("Available Users ({}) - a: Add New User", &[&self.users.len()])),
         );
         frame.render_widget(list, chunks[1]);
 
         // Instructions
         let instructions =
-            Paragraph::new("↑↓: Select | Enter: Login | a: Add User | d: Delete User | q: Quit")
+            Paragraph::new(self.help("↑↓: Select | Enter: Login | a: Add User | d: Delete User | P: Profile | q: Quit"))
                 .style(Style::default().fg(Color::Gray))
                 .alignment(Alignment::Center)
                 .block(Block::default().borders(Borders::ALL));
@@ -544,23 +1199,55 @@ impl App {
     fn render_header(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         let mode_indicator = match self.mode {
             Mode::Normal => "",
-            Mode::AddTransaction => " [ADD TRANSACTION]",
-            Mode::AddExchangeRate => " [ADD EXCHANGE RATE]",
-            Mode::AddRecurringTransaction => " [ADD RECURRING]",
-            Mode::AddAccount => " [ADD ACCOUNT]",
-            Mode::AddCategory => " [ADD CATEGORY]",
-            Mode::AddUser => " [ADD USER]",
-            Mode::ConvertCurrency => " [CONVERT CURRENCY]",
-            Mode::DeleteConfirm => " [DELETE CONFIRM]",
-            Mode::ViewDetails => " [DETAILS]",
-            Mode::ExportData => " [EXPORT DATA]",
-            Mode::SelectCurrencyFilter => " [FILTER CURRENCY]",
-            Mode::SelectViewCurrency => " [VIEW IN CURRENCY]",
+            Mode::AddTransaction => "ADD TRANSACTION",
+            Mode::AddExchangeRate => "ADD EXCHANGE RATE",
+            Mode::AddRecurringTransaction => "ADD RECURRING",
+            Mode::AddAccount => "ADD ACCOUNT",
+            Mode::AddCategory if self.editing_category.is_some() => "EDIT CATEGORY",
+            Mode::AddCategory => "ADD CATEGORY",
+            Mode::AddUser => "ADD USER",
+            Mode::ConvertCurrency => "CONVERT CURRENCY",
+            Mode::DeleteConfirm => "DELETE CONFIRM",
+            Mode::ViewDetails => "DETAILS",
+            Mode::EditTags => "TAGS",
+            Mode::ExportData => "EXPORT DATA",
+            Mode::SelectCurrencyFilter => "FILTER CURRENCY",
+            Mode::SelectDateRange => "DATE RANGE",
+            Mode::ImportCsv => "IMPORT CSV",
+            Mode::SetAccountGroup => "ACCOUNT GROUP",
+            Mode::ChangeAccountCurrency => "ACCOUNT CURRENCY",
+            Mode::SelectViewCurrency => "VIEW IN CURRENCY",
+            Mode::EditTransactionNotes => "EDIT NOTES",
+            Mode::ExportPassphrase => "EXPORT PASSPHRASE",
+            Mode::QuickAdd => "QUICK ADD",
+            Mode::Onboarding => "SETUP",
+            Mode::SelectProfile => "SWITCH PROFILE",
+            Mode::Recategorize => "RECATEGORIZE",
+            Mode::SetBudget => "SET BUDGET",
+            Mode::AddGoal => "ADD GOAL",
+            Mode::AddHolding => "ADD HOLDING",
+            Mode::AddTransfer => "ADD TRANSFER",
+            Mode::WeeklyDigest => "WEEKLY DIGEST",
+            Mode::SystemStatus => "STATUS",
+            Mode::EnterPassword => "PASSWORD",
+            Mode::ReceiptPath => "READ RECEIPT",
+            Mode::EditTransaction => "EDIT TRANSACTION",
+            Mode::SearchTransactions => "SEARCH",
+        };
+        let mode_indicator = match mode_indicator {
+            "" => String::new(),
+            mode => format!(" [{}]", self.tr(mode)),
         };
 
+        let profile = self
+            .profile
+            .as_ref()
+            .map(|name| format!(" [{}]", name))
+            .unwrap_or_default();
+
         let current_user = if let Some(user_id) = self.current_user_id {
             if let Some(user) = self.users.iter().find(|u| u.id == user_id) {
-                format!(" - User: {}", user.username)
+                self.tr_fmt(" - User: {}", &[&user.username])
             } else {
                 String::new()
             }
@@ -569,8 +1256,8 @@ impl App {
         };
 
         let title = Paragraph::new(format!(
-            "Personal Finance Tracker{}{}",
-            current_user, mode_indicator
+            "{}{}{}{}",
+            self.tr("Personal Finance Tracker"), profile, current_user, mode_indicator
         ))
         .style(
             Style::default()
@@ -592,9 +1279,13 @@ impl App {
             "FX Rates",
             "Reports",
             "Export",
+            "Budgets",
+            "Settings",
+            "Goals",
+            "Investments",
         ];
-        let tabs = Tabs::new(titles)
-            .block(Block::default().borders(Borders::ALL).title("Menu (1-8)"))
+        let tabs = Tabs::new(titles.into_iter().map(|t| self.tr(t)).collect::<Vec<_>>())
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Menu (1-9, 0, -, =)")))
             .select(self.selected_tab)
             .style(Style::default().fg(Color::White))
             .highlight_style(
@@ -608,60 +1299,119 @@ impl App {
     fn render_dashboard(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(10), Constraint::Min(0)])
+            .constraints([Constraint::Length(12), Constraint::Min(0)])
             .split(area);
 
         let total_accounts = self.accounts.len();
-        let total_balance: f64 = self.accounts.iter().map(|a| a.current_balance).sum();
+        let (total_balance, total_currency, unconverted) = self.total_balance();
+        let total_label = match &total_currency {
+            Some(currency) => format!("{:.2} {}", total_balance, currency),
+            None => format!("{:.2}", total_balance),
+        };
+        let total_note = if self.base_currency.is_some() {
+            if unconverted > 0 {
+                self.tr_fmt(" (base currency; {} account(s) without a rate left out)", &[&unconverted])
+            } else {
+                self.tr(" (base currency)").to_string()
+            }
+        } else if total_currency.is_none() {
+            self.tr(" (mixed currencies - b: set a base currency)").to_string()
+        } else {
+            String::new()
+        };
+        // Income and expenses in the base currency, when set
+        let symbol = match self.base_currency.as_deref() {
+            Some(base) => format!("{} ", base),
+            None => "$".to_string(),
+        };
 
         let this_month_income: f64 = self
             .transactions
             .iter()
-            .filter(|t| t.transaction_type == "income")
-            .map(|t| t.amount)
+            .filter(|t| t.transaction_type == "income" && t.transfer_group_id.is_none() && self.counts_in_reports(t))
+            .map(|t| self.base_amount(t))
             .sum();
 
         let this_month_expenses: f64 = self
             .transactions
             .iter()
-            .filter(|t| t.transaction_type == "expense")
-            .map(|t| t.amount.abs())
+            .filter(|t| t.transaction_type == "expense" && self.counts_in_reports(t))
+            .map(|t| self.base_amount(t).abs())
             .sum();
 
         let net_change = this_month_income - this_month_expenses;
 
-        let stats_text = vec![
-            Line::from(vec![
-                Span::styled("Total Accounts: ", Style::default().fg(Color::Gray)),
+        let safe_to_spend_line = match &self.safe_to_spend {
+            Some(s) => Line::from(vec![
+                Span::styled(self.tr("Safe to Spend Today: "), Style::default().fg(Color::Gray)),
                 Span::styled(
-                    format!("{}", total_accounts),
+                    format!("${:.2}", s.safe_to_spend_per_day),
+                    if s.safe_to_spend_per_day > 0.0 {
+                        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                    },
+                ),
+                Span::styled(
+                    self.tr_fmt(" ({} days left, ${} bills upcoming)", &[&s.days_remaining, &format!("{:.2}", s.upcoming_bills)]),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+            None => Line::from(Span::styled(
+                self.tr("Safe to Spend Today: N/A"),
+                Style::default().fg(Color::DarkGray),
+            )),
+        };
+
+        // Accounts are loaded favorites-first, so the first favorite is the daily driver
+        let favorite_line = match self.accounts.iter().find(|a| a.is_favorite) {
+            Some(a) => Line::from(vec![
+                Span::styled("★ ", Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{}: ", a.name), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{:.2} {}", a.current_balance, a.currency),
                     Style::default().fg(Color::Green),
                 ),
             ]),
+            None => Line::from(Span::styled(
+                self.tr("★ No favorite account (press s on the Accounts tab)"),
+                Style::default().fg(Color::DarkGray),
+            )),
+        };
+
+        let mut stats_text = vec![
+            safe_to_spend_line,
+            Line::from(""),
+            favorite_line,
             Line::from(vec![
-                Span::styled("Total Balance: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Total Accounts: "), Style::default().fg(Color::Gray)),
                 Span::styled(
-                    format!("${:.2}", total_balance),
+                    format!("{}", total_accounts),
                     Style::default().fg(Color::Green),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled(self.tr("Total Balance: "), Style::default().fg(Color::Gray)),
+                Span::styled(total_label, Style::default().fg(Color::Green)),
+                Span::styled(total_note, Style::default().fg(Color::DarkGray)),
+            ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("This Month Income: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("This Month Income: "), Style::default().fg(Color::Gray)),
                 Span::styled(
-                    format!("${:.2}", this_month_income),
+                    format!("{}{:.2}", symbol, this_month_income),
                     Style::default().fg(Color::Cyan),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("This Month Expenses: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("This Month Expenses: "), Style::default().fg(Color::Gray)),
                 Span::styled(
-                    format!("${:.2}", this_month_expenses),
+                    format!("{}{:.2}", symbol, this_month_expenses),
                     Style::default().fg(Color::Red),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Net Change: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Net Change: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     format!(
                         "{}{:.2}",
@@ -677,8 +1427,22 @@ impl App {
             ]),
         ];
 
+        // Conversions use the loaded rates, so flag when any of them needs a refresh
+        let stale_days = fx::stale_after_days();
+        let stale_rates = self
+            .exchange_rates
+            .iter()
+            .filter(|r| fx::is_stale(r.rate_date, Utc::now(), stale_days))
+            .count();
+        if stale_rates > 0 {
+            stats_text.push(Line::from(Span::styled(
+                self.tr_fmt("⚠ {} exchange rates older than {} days (see Exchange Rates)", &[&stale_rates, &stale_days]),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+
         let stats = Paragraph::new(stats_text)
-            .block(Block::default().borders(Borders::ALL).title("Quick Stats"))
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Quick Stats")))
             .alignment(Alignment::Left);
         frame.render_widget(stats, chunks[0]);
 
@@ -701,21 +1465,70 @@ impl App {
                 } else {
                     "-"
                 };
-                let desc = t.description.as_deref().unwrap_or("No description");
+                let desc = t.description.as_deref().unwrap_or(self.tr("No description"));
                 ListItem::new(format!("{} ${:.2} - {}", icon, t.amount.abs(), desc)).style(style)
             })
             .collect();
 
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+
         let list = List::new(transactions).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Recent Transactions (Up/Down to select, Enter for details)"),
+                .title(self.tr("Recent Transactions (Up/Down to select, Enter for details)")),
         );
-        frame.render_widget(list, chunks[1]);
+        frame.render_widget(list, bottom[0]);
+
+        let activity: Vec<ListItem> = self
+            .activity
+            .iter()
+            .map(|item| {
+                let (icon, color) = match item.kind.as_str() {
+                    "recurring" => ("↻", Color::Cyan),
+                    "import" => ("⇩", Color::Green),
+                    "rate_refresh" => ("$", Color::Blue),
+                    "export" => ("⇪", Color::Magenta),
+                    "alert" => ("!", Color::Red),
+                    _ => ("•", Color::White),
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{} ", item.occurred_at.format("%m-%d %H:%M")),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                    Span::raw(item.title.clone()),
+                ];
+                if let Some(amount) = item.amount {
+                    spans.push(Span::styled(
+                        format!(" {:+.2}", amount),
+                        if amount >= 0.0 {
+                            Style::default().fg(Color::Green)
+                        } else {
+                            Style::default().fg(Color::Red)
+                        },
+                    ));
+                }
+                if let Some(ref detail) = item.detail {
+                    spans.push(Span::styled(
+                        format!(" ({})", detail),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let activity_list = List::new(activity)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Recent Activity")));
+        frame.render_widget(activity_list, bottom[1]);
     }
 
     fn render_accounts(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let accounts: Vec<ListItem> = self
+        let rows: Vec<ListItem> = self
             .accounts
             .iter()
             .enumerate()
@@ -736,32 +1549,112 @@ impl App {
                     Color::Red
                 };
 
-                let bank = a.bank_name.as_deref().unwrap_or("No Bank");
+                let bank = a.bank_name.as_deref().unwrap_or(self.tr("No Bank"));
+
+                // Credit cards with a limit show how much of it is in use
+                let utilization = match credit::utilization_percent(a.current_balance, a.credit_limit) {
+                    Some(u) if u >= credit::utilization_threshold() => Span::styled(
+                        self.tr_fmt("  ⚠ {}% of limit", &[&format!("{:.0}", u)]),
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    ),
+                    Some(u) => Span::styled(
+                        self.tr_fmt("  {}% of limit", &[&format!("{:.0}", u)]),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    None => Span::raw(""),
+                };
 
                 ListItem::new(Line::from(vec![
-                    Span::styled(format!("{:<30}", a.name), Style::default().fg(Color::White)),
+                    Span::styled(
+                        if a.is_favorite { "★ " } else { "  " },
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("{:<30}", a.name),
+                        Style::default().fg(if a.is_archived { Color::DarkGray } else { Color::White }),
+                    ),
                     Span::styled(format!("{:<15}", bank), Style::default().fg(Color::Gray)),
                     Span::styled(
                         format!("{:>12.2} {}", a.current_balance, a.currency),
                         Style::default().fg(balance_color),
                     ),
+                    // Month-end balance once pending and recurring items post
+                    match self.projected_balances.get(&a.id) {
+                        Some(projected) => Span::styled(
+                            format!("  → {:>12.2}", projected),
+                            Style::default().fg(if (projected - a.current_balance).abs() >= 0.005 {
+                                Color::Yellow
+                            } else {
+                                Color::DarkGray
+                            }),
+                        ),
+                        None => Span::raw(""),
+                    },
+                    utilization,
+                    match a.closed_on {
+                        Some(closed_on) => Span::styled(
+                            self.tr_fmt("  closed {}", &[&closed_on]),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        None => Span::raw(""),
+                    },
                 ]))
                 .style(style)
             })
             .collect();
 
+        // Grouped view: a header with the group's net worth above each group's
+        // accounts, which load_data has already put in group order
+        let (accounts, selected) = if self.accounts_grouped {
+            let mut items = Vec::new();
+            let mut selected = 0;
+            let mut current_group = None;
+            for (i, (account, row)) in self.accounts.iter().zip(rows).enumerate() {
+                if current_group != Some(account.account_group_id) {
+                    current_group = Some(account.account_group_id);
+                    let header = match self.group_balances.iter().find(|g| g.group_id == account.account_group_id) {
+                        Some(group) => {
+                            let totals: Vec<String> = group
+                                .totals
+                                .iter()
+                                .map(|t| format!("{:.2} {}", t.net_worth, t.currency))
+                                .collect();
+                            format!("{} ({})  {}", group.group_name, group.account_count, totals.join(" · "))
+                        }
+                        None => self.tr("Ungrouped").to_string(),
+                    };
+                    items.push(ListItem::new(Line::from(Span::styled(
+                        header,
+                        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                    ))));
+                }
+                if i == self.selected_index {
+                    selected = items.len();
+                }
+                items.push(row);
+            }
+            (items, selected)
+        } else {
+            (rows, self.selected_index)
+        };
+
         let total = self.accounts.len();
         let pos_indicator = if total > 0 {
             format!(" [{}/{}]", self.selected_index + 1, total)
         } else {
             String::new()
         };
+        let (view, move_hint) = if self.accounts_grouped {
+            (self.tr(" by group"), "")
+        } else {
+            ("", self.tr(" | J/K: Move"))
+        };
 
         let list = List::new(accounts)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("Accounts ({}){} - a: Add | d: Delete | ↑↓: Scroll | Enter: Details", total, pos_indicator)),
+                    .title(self.tr_fmt("Accounts{} ({}){} - balance → projected month end - a: Add | t: Transfer | d: Delete | s: Star | A: Group | C: Currency | o: Group view{} | Enter: Details", &[&view, &total, &pos_indicator, &move_hint])),
             )
             .highlight_style(
                 Style::default()
@@ -771,27 +1664,13 @@ impl App {
             .highlight_symbol("► ");
 
         let mut state = ListState::default();
-        state.select(Some(self.selected_index));
+        state.select(Some(selected));
         frame.render_stateful_widget(list, area, &mut state);
     }
 
     fn render_transactions(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        // Filter transactions by currency if filter is set
-        let filtered_transactions: Vec<_> = if let Some(ref currency) = self.currency_filter {
-            self.transactions
-                .iter()
-                .filter(|t| {
-                    // Find the account for this transaction and check its currency
-                    self.accounts
-                        .iter()
-                        .find(|a| a.id == t.account_id)
-                        .map(|a| &a.currency == currency)
-                        .unwrap_or(false)
-                })
-                .collect()
-        } else {
-            self.transactions.iter().collect()
-        };
+        let filtered_transactions = self.visible_transactions();
+        let search = self.transaction_search.trim();
 
         let transactions: Vec<ListItem> = filtered_transactions
             .iter()
@@ -807,14 +1686,18 @@ impl App {
                     Style::default().bg(Color::Rgb(30, 30, 30))
                 };
 
-                let date_str = t.transaction_date.format("%Y-%m-%d").to_string();
+                let date_str = t.transaction_date.format(self.date_pattern()).to_string();
+                let transfer_label = self.transfer_label(t);
                 let type_str = match t.transaction_type.as_str() {
-                    "income" => "Income  ",
-                    "expense" => "Expense ",
-                    "transfer" => "Transfer",
-                    _ => "Unknown ",
+                    _ if transfer_label.is_some() => self.tr("Transfer"),
+                    "income" => self.tr("Income  "),
+                    "expense" => self.tr("Expense "),
+                    "transfer" => self.tr("Transfer"),
+                    _ => self.tr("Unknown "),
                 };
-                let desc = t.description.as_deref().unwrap_or("No description");
+                let desc = transfer_label
+                    .as_deref()
+                    .unwrap_or_else(|| t.description.as_deref().unwrap_or(self.tr("No description")));
 
                 // Get currency from account
                 let original_currency = self.accounts
@@ -824,57 +1707,109 @@ impl App {
                     .unwrap_or("???");
 
                 // Determine display amount and currency
-                let (display_amount, display_currency) = if let Some(ref target_currency) = self.view_in_currency {
-                    let rate = self.get_exchange_rate(original_currency, target_currency);
-                    (t.amount.abs() * rate, target_currency.as_str())
-                } else {
-                    (t.amount.abs(), original_currency)
+                let (display_amount, display_currency) = match self.view_amount(t) {
+                    Some((amount, currency)) => (amount.abs(), currency),
+                    None => (t.amount.abs(), original_currency),
                 };
 
-                ListItem::new(Line::from(vec![
+                let mut spans = vec![
                     Span::styled(format!("{} ", date_str), Style::default().fg(Color::Gray)),
+                    match t.status.as_str() {
+                        "pending" => Span::styled("[P] ", Style::default().fg(Color::Yellow)),
+                        "void" => Span::styled("[V] ", Style::default().fg(Color::DarkGray)),
+                        _ => Span::raw("    "),
+                    },
                     Span::styled(format!("{} ", type_str), 
-                        if t.transaction_type == "income" {
+                        if transfer_label.is_some() {
+                            Style::default().fg(Color::Cyan)
+                        } else if t.transaction_type == "income" {
                             Style::default().fg(Color::Green)
                         } else {
                             Style::default().fg(Color::Red)
                         }
                     ),
-                    Span::styled(format!("{:>10.2} ", display_amount), Style::default().fg(Color::White)),
+                    Span::styled(
+                        format!("{:>10.2} ", display_amount),
+                        if self.amount_matches_search(t) {
+                            Style::default().fg(Color::Black).bg(Color::Yellow)
+                        } else {
+                            Style::default().fg(Color::White)
+                        },
+                    ),
                     Span::styled(format!("{:<4}", display_currency), Style::default().fg(Color::Cyan)),
                     if self.view_in_currency.is_some() && original_currency != display_currency {
                         Span::styled(format!("({})", original_currency), Style::default().fg(Color::DarkGray))
                     } else {
                         Span::raw("")
                     },
-                    Span::styled(format!(" | {}", desc), Style::default().fg(Color::White)),
-                ]))
-                .style(style)
+                    Span::styled(" | ", Style::default().fg(Color::White)),
+                ];
+                spans.extend(highlight_matches(desc, search, Style::default().fg(Color::White)));
+
+                // Show the categories that matched, since the row doesn't otherwise list them
+                if let Some(names) = self.matching_categories(t) {
+                    spans.push(Span::styled("  #", Style::default().fg(Color::DarkGray)));
+                    spans.extend(highlight_matches(names, search, Style::default().fg(Color::Magenta)));
+                }
+                if let Some(expense_id) = t.reimburses_transaction_id {
+                    spans.push(Span::styled(self.tr_fmt("  ↺ reimburses #{}", &[&expense_id]), Style::default().fg(Color::Cyan)));
+                } else if self.reimbursed_amounts.contains_key(&t.id) {
+                    spans.push(Span::styled(self.tr("  ↺ reimbursed"), Style::default().fg(Color::Cyan)));
+                }
+
+                ListItem::new(Line::from(spans)).style(style)
             })
             .collect();
 
         // Build title with filter and view info
         let filter_str = match &self.currency_filter {
-            Some(c) => format!(" [Filter: {}]", c),
+            Some(c) => self.tr_fmt(" [Filter: {}]", &[&c]),
             None => String::new(),
         };
-        
-        let view_str = match &self.view_in_currency {
-            Some(c) => format!(" [View: {}]", c),
-            None => String::new(),
+
+        let range_str = match self.transaction_range {
+            Some((start, end)) => format!(
+                " [{} – {}]",
+                start.format(self.date_pattern()),
+                (end - chrono::Duration::days(1)).format(self.date_pattern())
+            ),
+            None => self.tr(" [Latest 100]").to_string(),
         };
 
-        let total = filtered_transactions.len();
-        let pos_indicator = if total > 0 {
+        let search_str = if self.mode == Mode::SearchTransactions {
+            self.tr_fmt(" [Search: {}_]", &[&self.transaction_search])
+        } else if search.is_empty() {
+            String::new()
+        } else {
+            self.tr_fmt(" [Search: \"{}\" - Esc: Clear]", &[&search])
+        };
+        
+        let view_str = match &self.view_in_currency {
+            Some(c) => {
+                let stale_age = self
+                    .accounts
+                    .iter()
+                    .filter_map(|a| self.stale_rate_age(&a.currency, c))
+                    .max();
+                match stale_age {
+                    Some(days) => self.tr_fmt(" [View: {} ⚠ rates up to {}d old]", &[&c, &days]),
+                    None => self.tr_fmt(" [View: {}]", &[&c]),
+                }
+            }
+            None => String::new(),
+        };
+
+        let total = filtered_transactions.len();
+        let pos_indicator = if total > 0 {
             format!(" [{}/{}]", self.selected_index + 1, total)
         } else {
             String::new()
         };
 
         let list = List::new(transactions)
-            .block(Block::default().borders(Borders::ALL).title(format!(
-                "Transactions ({}){}{}{} - f: Filter | v: View in $ | ↑↓: Scroll",
-                total, filter_str, view_str, pos_indicator
+            .block(Block::default().borders(Borders::ALL).title(self.tr_fmt(
+                "Transactions ({}){}{}{}{}{} - /: Search | D: Dates | f: Filter | v: View in $ | ↑↓: Scroll",
+                &[&total, &range_str, &filter_str, &search_str, &view_str, &pos_indicator],
             )))
             .highlight_style(
                 Style::default()
@@ -907,14 +1842,24 @@ impl App {
                 };
                 ListItem::new(Line::from(vec![
                     Span::styled(format!("{:>3}: ", c.id), Style::default().fg(Color::Cyan)),
+                    Span::styled(if c.parent_category_id.is_some() { "  └ " } else { "" }, Style::default().fg(Color::DarkGray)),
                     Span::styled(&c.name, Style::default().fg(Color::White)),
+                    match &c.bucket {
+                        Some(bucket) => Span::styled(format!("  [{}]", bucket), Style::default().fg(Color::DarkGray)),
+                        None => Span::raw(""),
+                    },
+                    if c.exclude_from_reports {
+                        Span::styled(self.tr("  (excluded from reports)"), Style::default().fg(Color::DarkGray))
+                    } else {
+                        Span::raw("")
+                    },
                 ])).style(style)
             })
             .collect();
 
         let list = List::new(cat_items)
             .block(Block::default().borders(Borders::ALL)
-                .title(format!("Categories ({}){} - a: Add | d: Delete | ↑↓: Scroll", total, pos_indicator)))
+                .title(self.tr_fmt("Categories ({}){} - a: Add | e: Edit | d: Delete | b: Bucket | E: Exclude from reports | R: Apply rules | ↑↓: Scroll", &[&total, &pos_indicator])))
             .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
             .highlight_symbol("► ");
 
@@ -924,6 +1869,14 @@ impl App {
     }
 
     fn render_exchange_rates(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+        self.render_last_scrape_run(frame, chunks[0]);
+        let area = chunks[1];
+
+        let stale_days = fx::stale_after_days();
         let rate_items: Vec<ListItem> = self
             .exchange_rates
             .iter()
@@ -939,7 +1892,8 @@ impl App {
                     Style::default().bg(Color::Rgb(30, 30, 30))
                 };
 
-                let date_str = r.rate_date.format("%Y-%m-%d").to_string();
+                let date_str = r.rate_date.format(self.date_pattern()).to_string();
+                let stale = fx::is_stale(r.rate_date, Utc::now(), stale_days);
 
                 // Truncate long currency names for display
                 let to_curr_display = if r.to_currency.len() > 25 {
@@ -962,11 +1916,26 @@ impl App {
                         format!("{:>12.6}", r.rate),
                         Style::default().fg(Color::Green),
                     ),
-                    Span::styled(format!("  {}", date_str), Style::default().fg(Color::Gray)),
+                    Span::styled(
+                        format!("  {}", date_str),
+                        if stale { Style::default().fg(Color::Red) } else { Style::default().fg(Color::Gray) },
+                    ),
                     Span::styled(
                         format!(" [{}]", r.source),
                         Style::default().fg(Color::Yellow),
                     ),
+                    Span::styled(
+                        if r.pinned { self.tr(" (pinned)") } else { "" },
+                        Style::default().fg(Color::Magenta),
+                    ),
+                    Span::styled(
+                        if stale {
+                            self.tr_fmt(" ⚠ {}d old", &[&fx::age_days(r.rate_date, Utc::now())])
+                        } else {
+                            String::new()
+                        },
+                        Style::default().fg(Color::Red),
+                    ),
                 ]))
                 .style(style)
             })
@@ -981,9 +1950,9 @@ impl App {
         };
 
         let list = List::new(rate_items)
-            .block(Block::default().borders(Borders::ALL).title(format!(
-                "Exchange Rates ({}) - a: Add | c: Convert | d: Delete | ↑↓: Scroll{}",
-                total, pos_indicator
+            .block(Block::default().borders(Borders::ALL).title(self.tr_fmt(
+                "Exchange Rates ({}) - a: Add | c: Convert | p: Pin | d: Delete | ↑↓: Scroll{}",
+                &[&total, &pos_indicator],
             )))
             .highlight_style(
                 Style::default()
@@ -998,65 +1967,119 @@ impl App {
         frame.render_stateful_widget(list, area, &mut state);
     }
 
+    fn render_last_scrape_run(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let line = match &self.last_scrape_run {
+            Some(run) => {
+                let color = match run.status.as_str() {
+                    "success" => Color::Green,
+                    "partial" => Color::Yellow,
+                    _ => Color::Red,
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        run.started_at.format("%Y-%m-%d %H:%M").to_string(),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(format!("  {}", run.status.to_uppercase()), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        self.tr_fmt(
+                            "  {} rates saved, {} up to date | {} | {}s",
+                            &[
+                                &run.rates_saved,
+                                &run.currencies_skipped,
+                                &run.currencies,
+                                &format!("{:.1}", run.duration_ms as f64 / 1000.0),
+                            ],
+                        ),
+                        Style::default().fg(Color::White),
+                    ),
+                ];
+                if let Some(error) = run.errors.as_deref().and_then(|e| e.lines().next()) {
+                    spans.push(Span::styled(format!("  ✗ {}", error), Style::default().fg(Color::Red)));
+                }
+                Line::from(spans)
+            }
+            None => Line::from(Span::styled(
+                self.tr("No scrape runs yet - run 'scrape_rates' to fetch rates"),
+                Style::default().fg(Color::DarkGray),
+            )),
+        };
+
+        let status = Paragraph::new(line)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Last Scrape")));
+        frame.render_widget(status, area);
+    }
+
     fn render_reports(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        if self.report_forecast {
+            return self.render_forecast(frame, area);
+        }
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(10), // Summary
-                Constraint::Length(12), // Top categories
+                Constraint::Length(14), // Charts and payees
+                Constraint::Length(10), // Weekday/hour patterns
                 Constraint::Min(0),     // Account balances
             ])
             .split(area);
 
+        // In the View in Currency currency when one is picked, else the base
+        // currency, each transaction at the rate of its own date
+        let amount = |t: &Transaction| self.view_amount(t).map_or_else(|| self.base_amount(t), |(amount, _)| amount);
         let total_income: f64 = self
             .transactions
             .iter()
-            .filter(|t| t.transaction_type == "income")
-            .map(|t| t.amount)
+            .filter(|t| t.transaction_type == "income" && t.transfer_group_id.is_none() && self.counts_in_reports(t))
+            .map(amount)
             .sum();
 
         let total_expenses: f64 = self
             .transactions
             .iter()
-            .filter(|t| t.transaction_type == "expense")
-            .map(|t| t.amount.abs())
+            .filter(|t| t.transaction_type == "expense" && self.counts_in_reports(t))
+            .map(|t| amount(t).abs())
             .sum();
+        let symbol = match self.view_in_currency.as_deref().or(self.base_currency.as_deref()) {
+            Some(currency) => format!("{} ", currency),
+            None => "$".to_string(),
+        };
 
         let net_change = total_income - total_expenses;
         let transaction_count = self.transactions.len();
 
         let summary_text = vec![
             Line::from(vec![
-                Span::styled("Report Period: ", Style::default().fg(Color::Gray)),
-                Span::styled("All Time", Style::default().fg(Color::Yellow)),
+                Span::styled(self.tr("Report Period: "), Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("All Time"), Style::default().fg(Color::Yellow)),
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Total Income:       ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Total Income:       "), Style::default().fg(Color::Gray)),
                 Span::styled(
-                    format!("${:.2}", total_income),
+                    format!("{}{:.2}", symbol, total_income),
                     Style::default().fg(Color::Green),
                 ),
-                Span::styled("     Transaction Count:  ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("     Transaction Count:  "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     format!("{}", transaction_count),
                     Style::default().fg(Color::Cyan),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Total Expenses:     ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Total Expenses:     "), Style::default().fg(Color::Gray)),
                 Span::styled(
-                    format!("${:.2}", total_expenses),
+                    format!("{}{:.2}", symbol, total_expenses),
                     Style::default().fg(Color::Red),
                 ),
-                Span::styled("     Categories:         ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("     Categories:         "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     format!("{}", self.categories.len()),
                     Style::default().fg(Color::Cyan),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Net Change:         ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Net Change:         "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     format!(
                         "{}{:.2}",
@@ -1069,7 +2092,7 @@ impl App {
                         Style::default().fg(Color::Red)
                     },
                 ),
-                Span::styled("     Recurring:          ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("     Recurring:          "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     format!("{}", self.recurring_transactions.len()),
                     Style::default().fg(Color::Cyan),
@@ -1081,56 +2104,63 @@ impl App {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Financial Summary"),
+                    .title(self.tr("Financial Summary")),
             )
             .alignment(Alignment::Left);
         frame.render_widget(summary, chunks[0]);
 
-        // Top spending categories (Insights)
-        let top_categories: Vec<ListItem> = self
-            .category_spending
-            .iter()
-            .take(5)
-            .enumerate()
-            .map(|(i, cs)| {
-                let percentage = if total_expenses > 0.0 {
-                    (cs.total_amount / total_expenses) * 100.0
-                } else {
-                    0.0
-                };
+        let insight_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Percentage(35),
+                Constraint::Percentage(25),
+            ])
+            .split(chunks[1]);
 
-                // Create a simple bar
-                let bar_width = (percentage / 100.0 * 20.0) as usize;
-                let bar = "█".repeat(bar_width);
+        self.render_monthly_chart(frame, insight_chunks[0]);
+        self.render_category_chart(frame, insight_chunks[1]);
 
-                ListItem::new(Line::from(vec![
-                    Span::styled(
-                        format!("{}. ", i + 1),
-                        Style::default().fg(Color::Gray),
-                    ),
-                    Span::styled(
-                        format!("{:<20}", cs.category_name),
-                        Style::default().fg(Color::White),
-                    ),
-                    Span::styled(
-                        format!("${:>10.2}", cs.total_amount),
-                        Style::default().fg(Color::Red),
-                    ),
-                    Span::styled(
-                        format!(" ({:>5.1}%) ", percentage),
-                        Style::default().fg(Color::Yellow),
-                    ),
-                    Span::styled(bar, Style::default().fg(Color::Magenta)),
-                ]))
-            })
-            .collect();
+        // Payee leaderboard
+        let payee_items: Vec<ListItem> = if self.merchant_spending.is_empty() {
+            vec![ListItem::new(Line::from(Span::styled(
+                self.tr("No merchant data yet"),
+                Style::default().fg(Color::DarkGray),
+            )))]
+        } else {
+            self.merchant_spending
+                .iter()
+                .take(10)
+                .enumerate()
+                .map(|(i, ms)| {
+                    let location = ms.merchant_city.as_deref().unwrap_or("");
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:>2}. ", i + 1), Style::default().fg(Color::Gray)),
+                        Span::styled(
+                            format!("{:<16}", ms.merchant.chars().take(16).collect::<String>()),
+                            Style::default().fg(Color::White),
+                        ),
+                        Span::styled(
+                            format!("${:>9.2}", ms.total_amount),
+                            Style::default().fg(Color::Red),
+                        ),
+                        Span::styled(
+                            format!(" x{} {}", ms.transaction_count, location),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ]))
+                })
+                .collect()
+        };
 
-        let category_list = List::new(top_categories).block(
+        let payee_list = List::new(payee_items).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Top Spending Categories (Insights)"),
+                .title(self.tr("Payee Leaderboard")),
         );
-        frame.render_widget(category_list, chunks[1]);
+        frame.render_widget(payee_list, insight_chunks[2]);
+
+        self.render_spending_patterns(frame, chunks[2]);
 
         // Account balances
         let account_items: Vec<ListItem> = self
@@ -1143,331 +2173,938 @@ impl App {
                 } else {
                     Color::Red
                 };
+                // What a foreign-currency balance is worth in the base currency
+                let converted = match (&self.base_currency, self.account_base_rates.get(&a.id)) {
+                    (Some(base), Some(rate)) if !a.currency.eq_ignore_ascii_case(base) => {
+                        format!("  ≈ {:.2} {}", a.current_balance * rate, base)
+                    }
+                    _ => String::new(),
+                };
 
                 ListItem::new(Line::from(vec![
                     Span::styled(format!("{:<30}", a.name), Style::default().fg(Color::White)),
                     Span::styled(format!("{:>15}", balance_str), Style::default().fg(color)),
+                    Span::styled(converted, Style::default().fg(Color::DarkGray)),
                 ]))
             })
             .collect();
 
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(chunks[3]);
+
         let list = List::new(account_items).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Account Balances"),
+                .title(self.tr("Account Balances")),
         );
-        frame.render_widget(list, chunks[2]);
+        frame.render_widget(list, bottom[0]);
+
+        self.render_budget_variance(frame, bottom[1]);
     }
 
-    fn render_recurring_transactions(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let recurring_items: Vec<ListItem> = self
-            .recurring_transactions
+    /// Income next to expenses for each month of the chart window
+    fn render_monthly_chart(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let groups: Vec<BarGroup> = self
+            .monthly_totals
+            .iter()
+            .map(|m| {
+                let label = NaiveDate::parse_from_str(&format!("{}-01", m.month), "%Y-%m-%d")
+                    .map(|d| d.format("%b").to_string())
+                    .unwrap_or_else(|_| m.month.clone());
+                BarGroup::default().label(Line::from(label)).bars(&[
+                    Bar::default()
+                        .value(m.total_income.round() as u64)
+                        .text_value(compact_amount(m.total_income))
+                        .style(Style::default().fg(Color::Green)),
+                    Bar::default()
+                        .value(m.total_expense.round() as u64)
+                        .text_value(compact_amount(m.total_expense))
+                        .style(Style::default().fg(Color::Red)),
+                ])
+            })
+            .collect();
+
+        // Two bars and a gap per month, as wide as the area allows
+        let months = groups.len().max(1) as u16;
+        let bar_width = (area.width.saturating_sub(2) / months).saturating_sub(2) / 2;
+
+        let mut chart = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.tr_fmt(
+                        "Income vs. Expenses - last {} months{} (m: 3/6/12)",
+                        &[
+                            &self.report_months,
+                            &self.base_currency.as_deref().map(|c| self.tr_fmt(" in {}", &[&c])).unwrap_or_default(),
+                        ],
+                    )),
+            )
+            .bar_width(bar_width.clamp(1, 6))
+            .bar_gap(0)
+            .group_gap(1)
+            .value_style(Style::default().fg(Color::Black))
+            .label_style(Style::default().fg(Color::Gray));
+        for group in groups {
+            chart = chart.data(group);
+        }
+        frame.render_widget(chart, area);
+    }
+
+    /// Spending per category over the chart window, largest first
+    fn render_category_chart(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.tr_fmt(
+                "Spending by Category - last {} months{}",
+                &[&self.report_months, &if self.report_rollup { self.tr(" (rolled up)") } else { "" }],
+            ));
+        if self.report_category_spending.is_empty() {
+            let empty = Paragraph::new(Span::styled(self.tr("No spending in this period"), Style::default().fg(Color::DarkGray)))
+                .block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+
+        // Names get at most half the width, leaving the rest for the bars
+        let label_width = (area.width.saturating_sub(2) / 2).min(14) as usize;
+        let bars: Vec<Bar> = self
+            .report_category_spending
+            .iter()
+            .take(area.height.saturating_sub(2) as usize)
+            .map(|cs| {
+                Bar::default()
+                    .label(Line::from(cs.category_name.chars().take(label_width).collect::<String>()))
+                    .value(cs.total_amount.round() as u64)
+                    .text_value(format!("${:.0}", cs.total_amount))
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .block(block)
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(Style::default().fg(Color::Magenta))
+            .value_style(Style::default().fg(Color::White).bg(Color::Magenta))
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, area);
+    }
+
+    fn render_budget_variance(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let mut items: Vec<ListItem> = vec![ListItem::new(Line::from(Span::styled(
+            self.tr_fmt(
+                "{} {} {} {} {}  Status",
+                &[
+                    &format!("{:<16}", self.tr("Category")),
+                    &format!("{:>10}", self.tr("Budget")),
+                    &format!("{:>10}", self.tr("Actual")),
+                    &format!("{:>10}", self.tr("Variance")),
+                    &format!("{:>7}", "%"),
+                ],
+            ),
+            Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+        )))];
+
+        if self.budget_variance.is_empty() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                self.tr("No budgets set (press 9 for Budgets)"),
+                Style::default().fg(Color::DarkGray),
+            ))));
+        }
+
+        for r in &self.budget_variance {
+            let color = match r.status.as_str() {
+                "over" => Color::Red,
+                "near" => Color::Yellow,
+                _ => Color::Green,
+            };
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<16} ", r.category_name.chars().take(16).collect::<String>()),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!("{:>10.2} {:>10.2} ", r.budgeted, r.actual),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("{:>+10.2} {:>+6.1}%  {}", r.variance_amount, r.variance_percent, r.status),
+                    Style::default().fg(color),
+                ),
+            ])));
+        }
+
+        let summary = budgets::adherence(&self.budget_variance);
+        let title = self.tr_fmt(
+            "Budget vs. Actual ({}) - {}/{} kept ({}%), {} near, {} over",
+            &[
+                &self.budget_period(),
+                &(summary.under + summary.near),
+                &self.budget_variance.len(),
+                &format!("{:.0}", summary.kept_percent()),
+                &summary.near,
+                &summary.over,
+            ],
+        );
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(list, area);
+    }
+
+    fn budget_period(&self) -> String {
+        self.budget_variance
+            .first()
+            .map(|r| r.period.clone())
+            .unwrap_or_else(|| chrono::Local::now().format("%Y-%m").to_string())
+    }
+
+    /// Limits for the current month with a progress bar each, flagged when near or over
+    fn render_goals(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        const BAR_WIDTH: usize = 20;
+        let items: Vec<ListItem> = self
+            .goals
             .iter()
             .enumerate()
-            .map(|(i, r)| {
+            .map(|(i, g)| {
+                let filled = ((g.percent / 100.0 * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+                let color = match g.on_track {
+                    _ if g.remaining <= 0.0 => Color::Green,
+                    Some(true) => Color::Green,
+                    Some(false) => Color::Red,
+                    None => Color::Cyan,
+                };
+                let completion = match g.projected_completion {
+                    _ if g.remaining <= 0.0 => "reached".to_string(),
+                    Some(date) => self.tr_fmt("done ~{}", &[&date.format(self.date_pattern())]),
+                    None => self.tr("not saving yet").to_string(),
+                };
+                let target_date = match (g.goal.target_date, g.required_monthly) {
+                    (Some(date), Some(required)) if g.remaining > 0.0 => {
+                        self.tr_fmt(" | by {} needs {}/mo", &[&date.format(self.date_pattern()), &format!("{:.2}", required)])
+                    }
+                    (Some(date), _) => self.tr_fmt(" | by {}", &[&date.format(self.date_pattern())]),
+                    _ => String::new(),
+                };
                 let style = if i == self.selected_index {
-                    Style::default()
-                        .bg(Color::DarkGray)
-                        .add_modifier(Modifier::BOLD)
-                } else if i % 2 == 0 {
-                    Style::default()
+                    Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().bg(Color::Rgb(30, 30, 30))
+                    Style::default()
                 };
-
-                let status = if r.is_active { "Active" } else { "Paused" };
-                let status_color = if r.is_active { Color::Green } else { Color::Red };
-                let next_date = r.next_occurrence.format("%Y-%m-%d").to_string();
-                let desc = r.description.as_deref().unwrap_or("No description");
-
                 ListItem::new(Line::from(vec![
                     Span::styled(
-                        format!("[{}] ", status),
-                        Style::default().fg(status_color),
+                        format!("{:<16} ", g.goal.name.chars().take(16).collect::<String>()),
+                        Style::default().fg(Color::White),
                     ),
                     Span::styled(
-                        format!("{:<10}", r.frequency),
-                        Style::default().fg(Color::Cyan),
+                        format!("[{}{}] {:>4.0}%  ", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled), g.percent),
+                        Style::default().fg(color),
                     ),
                     Span::styled(
-                        format!(" ${:>10.2}", r.amount.abs()),
-                        if r.transaction_type == "income" {
-                            Style::default().fg(Color::Green)
-                        } else {
-                            Style::default().fg(Color::Red)
-                        },
+                        format!("{:.2} / {:.2} {}  ", g.saved, g.goal.target_amount, g.goal.currency),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(
+                        self.tr_fmt("{}/mo, {}{}", &[&format!("{:+.2}", g.monthly_saving_rate), &completion, &target_date]),
+                        Style::default().fg(color),
                     ),
-                    Span::styled(format!(" | Next: {} ", next_date), Style::default().fg(Color::Gray)),
-                    Span::styled(desc, Style::default().fg(Color::White)),
                 ]))
                 .style(style)
             })
             .collect();
 
-        let total = self.recurring_transactions.len();
-        let pos_indicator = if total > 0 {
-            format!(" [{}/{}]", self.selected_index + 1, total)
+        let title = if self.goals.is_empty() {
+            self.tr("Goals - none yet, press 'a' to add one").to_string()
         } else {
-            String::new()
+            self.tr("Goals - saved in the linked accounts, pace over the last 90 days").to_string()
         };
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(list, area);
+    }
 
-        let list = List::new(recurring_items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!(
-                        "Recurring ({}) {} - a: Add | p: Process | t: Toggle | d: Delete | ↑↓: Scroll",
-                        total, pos_indicator
-                    )),
-            )
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol("► ");
-
-        let mut state = ListState::default();
-        state.select(Some(self.selected_index));
-        frame.render_stateful_widget(list, area, &mut state);
+    /// The holding selected on the Investments tab; rows run through the
+    /// investment accounts in order
+    fn selected_holding(&self) -> Option<&HoldingValue> {
+        if self.current_screen != Screen::Investments {
+            return None;
+        }
+        self.investments.iter().flat_map(|i| &i.holdings).nth(self.selected_index)
     }
 
-    fn render_export(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(8),  // Instructions
-                Constraint::Min(0),     // Export options
-            ])
-            .split(area);
+    /// Reports' forecast view: each account's projected months under its
+    /// starting balance and historical averages
+    fn render_forecast(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let signed = |amount: f64| if amount < 0.0 { Color::Red } else { Color::Green };
+        let mut lines = Vec::new();
+        match &self.cash_flow_forecast {
+            None => lines.push(Line::from(Span::styled(
+                self.tr("Forecast unavailable"),
+                Style::default().fg(Color::Red),
+            ))),
+            Some(forecast) if forecast.accounts.is_empty() => lines.push(Line::from(Span::styled(
+                self.tr("No open accounts to forecast"),
+                Style::default().fg(Color::DarkGray),
+            ))),
+            Some(forecast) => {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  {:<10}{:>14}{:>14}{:>14}{:>16}",
+                        self.tr("Month"),
+                        self.tr("Income"),
+                        self.tr("Expenses"),
+                        self.tr("Net"),
+                        self.tr("End Balance")
+                    ),
+                    Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+                )));
+                for account in &forecast.accounts {
+                    lines.push(Line::from(""));
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("{} ({})  ", account.account_name, account.currency),
+                            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(
+                            format!(
+                                "{}{:.2} | {}+{:.2} / -{:.2}",
+                                self.tr("Month end: "),
+                                account.starting_balance,
+                                self.tr("Average: "),
+                                account.average_income,
+                                account.average_expenses
+                            ),
+                            Style::default().fg(Color::Gray),
+                        ),
+                    ]));
+                    for month in &account.months {
+                        lines.push(Line::from(vec![
+                            Span::styled(format!("  {:<10}", month.month), Style::default().fg(Color::White)),
+                            Span::styled(format!("{:>14.2}", month.income), Style::default().fg(Color::Green)),
+                            Span::styled(format!("{:>14.2}", month.expenses), Style::default().fg(Color::Red)),
+                            Span::styled(format!("{:>+14.2}", month.net), Style::default().fg(signed(month.net))),
+                            Span::styled(
+                                format!("{:>16.2}", month.ending_balance),
+                                Style::default().fg(signed(month.ending_balance)).add_modifier(Modifier::BOLD),
+                            ),
+                        ]));
+                    }
+                }
+            }
+        }
 
-        let instructions = vec![
-            Line::from(vec![Span::styled(
-                "Data Export Options",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )]),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Press ", Style::default().fg(Color::Gray)),
-                Span::styled("e", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::styled(" to export data for the current user.", Style::default().fg(Color::Gray)),
-            ]),
-            Line::from(""),
-            Line::from("Supported export formats:"),
-            Line::from(vec![
-                Span::styled("  • CSV  ", Style::default().fg(Color::Green)),
-                Span::styled("- Comma-separated values for spreadsheets", Style::default().fg(Color::Gray)),
-            ]),
-            Line::from(vec![
-                Span::styled("  • JSON ", Style::default().fg(Color::Green)),
-                Span::styled("- Structured data for other applications", Style::default().fg(Color::Gray)),
-            ]),
-        ];
+        let title = self.tr_fmt(
+            "Cash-Flow Forecast - next {} months: recurring items plus {}-month averages (F: back to charts)",
+            &[&self.report_months, &forecast::HISTORY_MONTHS],
+        );
+        let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(widget, area);
+    }
 
-        let instr_widget = Paragraph::new(instructions)
-            .block(Block::default().borders(Borders::ALL).title("Export Data"))
-            .alignment(Alignment::Left);
-        frame.render_widget(instr_widget, chunks[0]);
+    /// Holdings per investment account with market value, gain/loss and allocation
+    fn render_investments(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        const BAR_WIDTH: usize = 10;
+        let gain_color = |gain: f64| if gain < 0.0 { Color::Red } else { Color::Green };
+        let percent = |p: Option<f64>| p.map(|p| format!("{:+.1}%", p)).unwrap_or_else(|| "-".to_string());
+        let mut items: Vec<ListItem> = Vec::new();
+        let mut selected = None;
+        let mut row = 0;
+        for valued in &self.investments {
+            let name = self
+                .accounts
+                .iter()
+                .find(|a| a.id == valued.account_id)
+                .map(|a| a.name.clone())
+                .unwrap_or_default();
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(format!("{}  ", name), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!("{:.2} {}  ", valued.market_value, valued.currency),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!("{:+.2} ({})", valued.gain, percent(valued.gain_percent)),
+                    Style::default().fg(gain_color(valued.gain)),
+                ),
+            ])));
+            if valued.holdings.is_empty() {
+                items.push(ListItem::new(Span::styled(self.tr("  No holdings"), Style::default().fg(Color::DarkGray))));
+            }
+            for h in &valued.holdings {
+                let filled = ((h.allocation / 100.0 * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+                let price = match (h.price, h.price_date) {
+                    (Some(price), Some(date)) => format!("@ {:>10.2} ({})", price, date.format(self.date_pattern())),
+                    _ => format!("{:<25}", self.tr("no quote, at cost")),
+                };
+                let style = if row == self.selected_index {
+                    selected = Some(items.len());
+                    Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                items.push(
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("  {:<10} ", h.holding.ticker), Style::default().fg(Color::White)),
+                        Span::styled(format!("{:>10} ", h.holding.quantity), Style::default().fg(Color::Gray)),
+                        Span::styled(format!("{}  ", price), Style::default().fg(Color::Gray)),
+                        Span::styled(format!("{:>12.2}  ", h.market_value), Style::default().fg(Color::White)),
+                        Span::styled(
+                            format!("{:>+11.2} {:>8}  ", h.gain, percent(h.gain_percent)),
+                            Style::default().fg(gain_color(h.gain)),
+                        ),
+                        Span::styled(
+                            format!("{}{} {:>5.1}%", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled), h.allocation),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                    ]))
+                    .style(style),
+                );
+                row += 1;
+            }
+        }
 
-        // Show export status or message
-        let export_content = if !self.export_message.is_empty() {
-            vec![
-                Line::from(""),
-                Line::from(vec![Span::styled(
-                    &self.export_message,
-                    Style::default().fg(Color::Green),
-                )]),
-            ]
+        let title = if self.investments.is_empty() {
+            self.tr("Investments - add an investment account to track holdings").to_string()
         } else {
-            let mut lines = vec![
-                Line::from(vec![Span::styled(
-                    "Data Available for Export:",
-                    Style::default().fg(Color::Yellow),
-                )]),
-                Line::from(""),
-            ];
-
-            lines.push(Line::from(format!("  • {} Accounts", self.accounts.len())));
-            lines.push(Line::from(format!("  • {} Transactions", self.transactions.len())));
-            lines.push(Line::from(format!("  • {} Categories", self.categories.len())));
-            lines.push(Line::from(format!("  • {} Recurring Transactions", self.recurring_transactions.len())));
-            lines.push(Line::from(""));
-            lines.push(Line::from(vec![
-                Span::styled("Note: ", Style::default().fg(Color::Gray)),
-                Span::styled("Export files will be saved to the current directory.", Style::default().fg(Color::Gray)),
-            ]));
-            lines
+            self.tr_fmt("Investments - latest quotes from {} (p: update), value | gain/loss | allocation", &[&prices::STOOQ])
         };
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+        // Stateful so the list scrolls to keep the selected holding visible
+        let mut state = ListState::default().with_selected(selected);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
 
-        let export_widget = Paragraph::new(export_content)
-            .block(Block::default().borders(Borders::ALL).title("Export Status"))
-            .alignment(Alignment::Left);
-        frame.render_widget(export_widget, chunks[1]);
+    fn render_settings(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let current = &self.user_settings;
+        let default_account = current
+            .default_account_id
+            .and_then(|id| self.accounts.iter().find(|a| a.id == id))
+            .map(|a| format!("{} ({})", a.name, a.currency));
+        let today = chrono::Local::now().date_naive();
+        let values = [
+            self.base_currency.clone().unwrap_or_else(|| self.tr("None (sum as recorded)").to_string()),
+            format!(
+                "{} - e.g. {}",
+                current.date_format.as_deref().unwrap_or("iso"),
+                today.format(self.date_pattern())
+            ),
+            default_account.unwrap_or_else(|| self.tr("None (most recently used)").to_string()),
+            self.language.name().to_string(),
+            current.week_start.clone().unwrap_or_else(|| "monday".to_string()),
+            if current.weekly_digest.unwrap_or(true) { "on" } else { "off" }.to_string(),
+            current.default_screen.clone().unwrap_or_else(|| "dashboard".to_string()),
+        ];
+
+        let items: Vec<ListItem> = SETTING_ROWS
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(i, (label, value))| {
+                let style = if i == self.selected_index {
+                    Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<20} ", self.tr(label)), Style::default().fg(Color::White)),
+                    Span::styled(value, Style::default().fg(Color::Cyan)),
+                ]))
+                .style(style)
+            })
+            .collect();
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(self.tr("Settings (Enter: change)")),
+        );
+        frame.render_widget(list, area);
     }
 
-    fn render_add_recurring_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+    fn render_budgets(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Length(6), Constraint::Min(0)])
             .split(area);
+        self.render_bucket_split(frame, chunks[1]);
 
-        let (selected_account_name, selected_currency) = if let Ok(account_id) = self.form_account_id.parse::<i64>() {
-            self.accounts.iter().find(|a| a.id == account_id)
-                .map(|a| (a.name.clone(), a.currency.clone())).unwrap_or_default()
-        } else { (String::new(), String::new()) };
-
-        let currency_display = if !selected_currency.is_empty() {
-            format!(" → {} [{}]", selected_account_name, selected_currency)
-        } else { String::new() };
+        let over: Vec<String> = self
+            .budget_variance
+            .iter()
+            .filter(|r| r.status == "over")
+            .map(|r| format!("{} (+{:.2})", r.category_name, -r.variance_amount))
+            .collect();
+        let near: Vec<&str> = self
+            .budget_variance
+            .iter()
+            .filter(|r| r.status == "near")
+            .map(|r| r.category_name.as_str())
+            .collect();
+        let warning = if !over.is_empty() {
+            Span::styled(
+                self.tr_fmt("⚠ Over budget: {}", &[&over.join(", ")]),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )
+        } else if !near.is_empty() {
+            Span::styled(
+                self.tr_fmt("⚠ Near the limit: {}", &[&near.join(", ")]),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )
+        } else if self.budget_variance.is_empty() {
+            Span::styled(
+                self.tr("No budgets set - press 'a' to add one"),
+                Style::default().fg(Color::DarkGray),
+            )
+        } else {
+            Span::styled(self.tr("All budgets on track"), Style::default().fg(Color::Green))
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(warning)).block(Block::default().borders(Borders::ALL)),
+            chunks[0],
+        );
 
-        let form_text = vec![
-            Line::from(vec![Span::styled("Add Recurring Transaction",
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))]),
-            Line::from(""),
-            Line::from(vec![
-                Span::styled("Account: ", Style::default().fg(Color::Gray)),
-                Span::styled(&self.form_account_id,
-                    if self.form_field_index == 0 {
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
-                    } else { Style::default().fg(Color::White) }),
-                Span::styled(&currency_display, Style::default().fg(Color::Cyan)),
-            ]),
-            Line::from(vec![
-                Span::styled("Amount: ", Style::default().fg(Color::Gray)),
-                Span::styled(&self.form_amount,
-                    if self.form_field_index == 1 {
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
-                    } else { Style::default().fg(Color::White) }),
-                Span::styled(if !selected_currency.is_empty() { format!(" {}", selected_currency) } else { String::new() },
-                    Style::default().fg(Color::Green)),
-            ]),
-            Line::from(vec![
-                Span::styled("Type (i=income/e=expense): ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    &self.form_type,
-                    if self.form_field_index == 2 {
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::UNDERLINED)
-                    } else {
-                        Style::default().fg(Color::White)
-                    },
-                ),
-            ]),
-            Line::from(vec![
-                Span::styled("Description: ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    &self.form_description,
-                    if self.form_field_index == 3 {
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::UNDERLINED)
-                    } else {
-                        Style::default().fg(Color::White)
-                    },
-                ),
-            ]),
-            Line::from(vec![
-                Span::styled("Category ID: ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    &self.form_category_id,
-                    if self.form_field_index == 4 {
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::UNDERLINED)
-                    } else {
-                        Style::default().fg(Color::White)
+        const BAR_WIDTH: usize = 20;
+        let items: Vec<ListItem> = self
+            .budget_variance
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let color = match r.status.as_str() {
+                    "over" => Color::Red,
+                    "near" => Color::Yellow,
+                    _ => Color::Green,
+                };
+                let used = if r.budgeted > 0.0 { r.actual / r.budgeted } else { 0.0 };
+                let filled = ((used * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+                let style = if i == self.selected_index {
+                    Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<16} ", r.category_name.chars().take(16).collect::<String>()),
+                        Style::default().fg(Color::White),
+                    ),
+                    Span::styled(
+                        format!("[{}{}] {:>4.0}%  ", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled), used * 100.0),
+                        Style::default().fg(color),
+                    ),
+                    Span::styled(
+                        self.tr_fmt("{} / {} ({} left)", &[&format!("{:.2}", r.actual), &format!("{:.2}", r.budgeted), &format!("{:+.2}", r.variance_amount)]),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    match (r.projected, r.projected_status.as_deref()) {
+                        (Some(projected), Some(pace)) => Span::styled(
+                            self.tr_fmt("  on pace for {}", &[&format!("{:.2}", projected)]),
+                            Style::default().fg(match pace {
+                                "over" => Color::Red,
+                                "near" => Color::Yellow,
+                                _ => Color::Green,
+                            }),
+                        ),
+                        _ => Span::raw(""),
                     },
-                ),
-            ]),
-            Line::from(vec![
-                Span::styled("Frequency: ", Style::default().fg(Color::Gray)),
-                Span::styled(&self.form_recurring_frequency,
-                    if self.form_field_index == 5 {
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
-                    } else { Style::default().fg(Color::White) }),
-                Span::styled(" (d=day/w=week/m=month/y=year)", Style::default().fg(Color::DarkGray)),
-            ]),
-            Line::from(""),
-            Line::from(vec![Span::styled("Tab: Next | Enter: Submit | Esc: Cancel", Style::default().fg(Color::Cyan))]),
-        ];
+                ]))
+                .style(style)
+            })
+            .collect();
 
-        let form = Paragraph::new(form_text)
-            .block(Block::default().borders(Borders::ALL).title("Add Recurring"))
-            .alignment(Alignment::Left);
-        frame.render_widget(form, chunks[0]);
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(self.tr_fmt(
+                "Budgets ({}) - a: Set limit | Enter: Edit | d: Delete",
+                &[&self.budget_period()],
+            )))
+            .highlight_symbol("► ");
 
-        // Account list grouped by currency
-        let mut lines: Vec<Line> = vec![Line::from("Select by Currency:"), Line::from("")];
-        let mut currencies: Vec<String> = self.accounts.iter().map(|a| a.currency.clone()).collect();
-        currencies.sort(); currencies.dedup();
-        for curr in &currencies {
-            lines.push(Line::from(Span::styled(format!("── {} ──", curr), Style::default().fg(Color::Green))));
-            for a in self.accounts.iter().filter(|a| &a.currency == curr) {
-                let sel = self.form_account_id.parse::<i64>().ok() == Some(a.id);
-                lines.push(Line::from(vec![
-                    Span::styled(format!("  {}: {}", a.id, a.name),
-                        if sel { Style::default().fg(Color::Yellow) } else { Style::default().fg(Color::White) }),
-                    if sel { Span::styled(" ◄", Style::default().fg(Color::Yellow)) } else { Span::raw("") },
-                ]));
+        let mut state = ListState::default();
+        state.select(Some(self.selected_index));
+        frame.render_stateful_widget(list, chunks[2], &mut state);
+    }
+
+    /// Actual needs/wants/savings shares of this month's spending against the targets
+    fn render_bucket_split(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        const BAR_WIDTH: usize = 20;
+        let mut lines: Vec<Line> = Vec::new();
+
+        match &self.bucket_split {
+            Some(split) if split.total > 0.0 => {
+                for b in &split.buckets {
+                    let filled = ((b.percent / 100.0 * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+                    let target = ((b.target_percent / 100.0 * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+                    // The target position is marked with │ inside the bar
+                    let bar: String = (0..BAR_WIDTH)
+                        .map(|i| match (i < filled, i + 1 == target) {
+                            (_, true) => '│',
+                            (true, false) => '█',
+                            (false, false) => '░',
+                        })
+                        .collect();
+                    // Overspending needs/wants is bad, undersaving is bad
+                    let off_target = if b.bucket == "savings" { b.difference < -5.0 } else { b.difference > 5.0 };
+                    let color = if off_target { Color::Yellow } else { Color::Green };
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("{:<8} ", b.bucket), Style::default().fg(Color::White)),
+                        Span::styled(format!("[{}] ", bar), Style::default().fg(color)),
+                        Span::styled(
+                            self.tr_fmt("{}% (target {}%)  {}", &[&format!("{:>4.0}", b.percent), &format!("{:.0}", b.target_percent), &format!("{:.2}", b.amount)]),
+                            Style::default().fg(Color::Gray),
+                        ),
+                    ]));
+                }
+                if split.unassigned > 0.0 {
+                    lines.push(Line::from(Span::styled(
+                        self.tr_fmt("{} in categories without a bucket (press 'b' on Categories)", &[&format!("{:.2}", split.unassigned)]),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
             }
+            _ => lines.push(Line::from(Span::styled(
+                self.tr("No bucketed spending this month - tag categories with 'b' on the Categories tab"),
+                Style::default().fg(Color::DarkGray),
+            ))),
         }
-        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Accounts")), chunks[1]);
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(self.tr("Needs / Wants / Savings"))),
+            area,
+        );
     }
 
-    fn render_export_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let dialog_text = vec![
+    /// Weekday x hour grid (3-hour blocks), shaded by share of the busiest block
+    fn render_spending_patterns(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let mut lines: Vec<Line> = Vec::new();
+
+        match &self.spending_patterns {
+            Some(p) if !p.cells.is_empty() => {
+                // Fold hourly cells into 8 blocks of 3 hours
+                let mut grid = [[0.0f64; 8]; 7];
+                for cell in &p.cells {
+                    grid[cell.weekday as usize % 7][cell.hour as usize / 3 % 8] += cell.total_amount;
+                }
+                let max = grid.iter().flatten().cloned().fold(0.0, f64::max);
+
+                let mut header = vec![Span::styled("     ", Style::default())];
+                for block in 0..8 {
+                    header.push(Span::styled(
+                        format!("{:02}h   ", block * 3),
+                        Style::default().fg(Color::Gray),
+                    ));
+                }
+                header.push(Span::styled(self.tr("  Total"), Style::default().fg(Color::Gray)));
+                lines.push(Line::from(header));
+
+                // Monday or Sunday first, per the week_start setting
+                let order = match self.user_settings.week_start.as_deref() {
+                    Some("sunday") => [0, 1, 2, 3, 4, 5, 6],
+                    _ => [1, 2, 3, 4, 5, 6, 0],
+                };
+                for weekday in order {
+                    let mut spans = vec![Span::styled(
+                        format!("{:<5}", patterns::WEEKDAYS[weekday]),
+                        Style::default().fg(Color::Cyan),
+                    )];
+                    for amount in grid[weekday] {
+                        let share = if max > 0.0 { amount / max } else { 0.0 };
+                        let (shade, color) = match share {
+                            s if s <= 0.0 => ("·", Color::DarkGray),
+                            s if s < 0.25 => ("░", Color::Green),
+                            s if s < 0.5 => ("▒", Color::Yellow),
+                            s if s < 0.75 => ("▓", Color::LightRed),
+                            _ => ("█", Color::Red),
+                        };
+                        spans.push(Span::styled(format!("{:<6}", shade.repeat(3)), Style::default().fg(color)));
+                    }
+                    spans.push(Span::styled(
+                        format!("  ${:.2}", p.by_weekday[weekday].total_amount),
+                        Style::default().fg(Color::White),
+                    ));
+                    lines.push(Line::from(spans));
+                }
+            }
+            _ => lines.push(Line::from(Span::styled(
+                self.tr("No expenses yet"),
+                Style::default().fg(Color::DarkGray),
+            ))),
+        }
+
+        let title = match self.spending_patterns.as_ref().and_then(|p| p.peak.as_ref()) {
+            Some(peak) => self.tr_fmt(
+                "Spending by Weekday/Hour - Peak: {} {}:00 (${})",
+                &[
+                    &patterns::WEEKDAYS[peak.weekday as usize % 7],
+                    &format!("{:02}", peak.hour),
+                    &format!("{:.2}", peak.total_amount),
+                ],
+            ),
+            None => self.tr("Spending by Weekday/Hour").to_string(),
+        };
+
+        let grid = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .alignment(Alignment::Left);
+        frame.render_widget(grid, area);
+    }
+
+    fn render_recurring_transactions(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let recurring_items: Vec<ListItem> = self
+            .recurring_transactions
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let style = if i == self.selected_index {
+                    Style::default()
+                        .bg(Color::DarkGray)
+                        .add_modifier(Modifier::BOLD)
+                } else if i % 2 == 0 {
+                    Style::default()
+                } else {
+                    Style::default().bg(Color::Rgb(30, 30, 30))
+                };
+
+                let status = if r.is_active { self.tr("Active") } else { self.tr("Paused") };
+                let status_color = if r.is_active { Color::Green } else { Color::Red };
+                let next_date = r.next_occurrence.format(self.date_pattern()).to_string();
+                let desc = r.description.as_deref().unwrap_or(self.tr("No description"));
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("[{}] ", status),
+                        Style::default().fg(status_color),
+                    ),
+                    Span::styled(
+                        format!("{:<10}", r.frequency),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(
+                        format!("{:<9}", r.classification),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                    Span::styled(
+                        match &r.currency {
+                            Some(currency) => format!(" {:>7.2} {}", r.amount.abs(), currency),
+                            None => format!(" ${:>10.2}", r.amount.abs()),
+                        },
+                        if r.transaction_type == "income" {
+                            Style::default().fg(Color::Green)
+                        } else {
+                            Style::default().fg(Color::Red)
+                        },
+                    ),
+                    Span::styled(self.tr_fmt(" | Next: {} ", &[&next_date]), Style::default().fg(Color::Gray)),
+                    Span::styled(desc, Style::default().fg(Color::White)),
+                ]))
+                .style(style)
+            })
+            .collect();
+
+        let total = self.recurring_transactions.len();
+        let pos_indicator = if total > 0 {
+            format!(" [{}/{}]", self.selected_index + 1, total)
+        } else {
+            String::new()
+        };
+
+        let list = List::new(recurring_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.tr_fmt(
+                        "Recurring ({}) {} - a: Add | p: Process | t: Toggle | d: Delete | ↑↓: Scroll",
+                        &[&total, &pos_indicator],
+                    )),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+
+        let mut state = ListState::default();
+        state.select(Some(self.selected_index));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn render_export(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(8),  // Instructions
+                Constraint::Min(0),     // Export options
+            ])
+            .split(area);
+
+        let instructions = vec![
             Line::from(vec![Span::styled(
-                "Select Export Format",
+                self.tr("Data Export Options"),
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             )]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("1", Style::default().fg(Color::Cyan)),
-                Span::styled(" - Export Transactions as CSV", Style::default().fg(Color::White)),
-            ]),
-            Line::from(vec![
-                Span::styled("2", Style::default().fg(Color::Cyan)),
-                Span::styled(" - Export Transactions as JSON", Style::default().fg(Color::White)),
+                Span::styled(self.tr("Press "), Style::default().fg(Color::Gray)),
+                Span::styled("e", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled(self.tr(" to export data for the current user."), Style::default().fg(Color::Gray)),
             ]),
+            Line::from(""),
+            Line::from(self.tr("Supported export formats:")),
             Line::from(vec![
-                Span::styled("3", Style::default().fg(Color::Cyan)),
-                Span::styled(" - Export Accounts as CSV", Style::default().fg(Color::White)),
+                Span::styled(self.tr("  • CSV  "), Style::default().fg(Color::Green)),
+                Span::styled(self.tr("- Comma-separated values for spreadsheets"), Style::default().fg(Color::Gray)),
             ]),
             Line::from(vec![
-                Span::styled("4", Style::default().fg(Color::Cyan)),
-                Span::styled(" - Export Full Summary as JSON", Style::default().fg(Color::White)),
+                Span::styled(self.tr("  • JSON "), Style::default().fg(Color::Green)),
+                Span::styled(self.tr("- Structured data for other applications"), Style::default().fg(Color::Gray)),
             ]),
-            Line::from(""),
-            Line::from(vec![Span::styled(
-                "Press number to export, Esc to cancel",
-                Style::default().fg(Color::Gray),
-            )]),
         ];
 
-        let dialog = Paragraph::new(dialog_text)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Export Options"),
-            )
-            .alignment(Alignment::Center);
-        frame.render_widget(dialog, area);
-    }
+        let instr_widget = Paragraph::new(instructions)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Export Data")))
+            .alignment(Alignment::Left);
+        frame.render_widget(instr_widget, chunks[0]);
 
-    fn render_currency_filter_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let current_filter = match &self.currency_filter {
-            Some(c) => format!("Current: {}", c),
-            None => "Current: All".to_string(),
-        };
+        // Show export status or message
+        let export_content = if !self.export_message.is_empty() {
+            vec![
+                Line::from(""),
+                Line::from(vec![Span::styled(
+                    &self.export_message,
+                    Style::default().fg(Color::Green),
+                )]),
+            ]
+        } else {
+            let mut lines = vec![
+                Line::from(vec![Span::styled(
+                    self.tr("Data Available for Export:"),
+                    Style::default().fg(Color::Yellow),
+                )]),
+                Line::from(""),
+            ];
+
+            lines.push(Line::from(self.tr_fmt("  • {} Accounts", &[&self.accounts.len()])));
+            lines.push(Line::from(self.tr_fmt("  • {} Transactions", &[&self.transactions.len()])));
+            lines.push(Line::from(self.tr_fmt("  • {} Categories", &[&self.categories.len()])));
+            lines.push(Line::from(self.tr_fmt("  • {} Recurring Transactions", &[&self.recurring_transactions.len()])));
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled(self.tr("Note: "), Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Export files will be saved to the current directory."), Style::default().fg(Color::Gray)),
+            ]));
+            lines
+        };
+
+        let export_widget = Paragraph::new(export_content)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Export Status")))
+            .alignment(Alignment::Left);
+        frame.render_widget(export_widget, chunks[1]);
+    }
+
+    fn render_export_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let range_style = |field: usize| {
+            if self.export_range_field == field {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        let range_note = if self.export_range_start.is_empty() && self.export_range_end.is_empty() {
+            self.tr("No range: the Transactions tab's list, this month's statement")
+        } else {
+            self.tr("Inclusive, YYYY-MM-DD | Tab: Edit dates")
+        };
+        let dialog_text = vec![
+            Line::from(vec![Span::styled(
+                self.tr("Select Export Format"),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("1", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Export Transactions as CSV"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(vec![
+                Span::styled("2", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Export Transactions as JSON"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(vec![
+                Span::styled("3", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Export Accounts as CSV"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(vec![
+                Span::styled("4", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Export Full Summary as JSON"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(vec![
+                Span::styled("5", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Export Summary as Excel Workbook"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(vec![
+                Span::styled("6", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Export Statement as PDF"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(vec![
+                Span::styled("7", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Export Transactions as QIF (GnuCash, Quicken)"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("From: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{:<10}", self.export_range_start), range_style(1)),
+                Span::styled(self.tr("   To: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{:<10}", self.export_range_end), range_style(2)),
+            ]),
+            Line::from(vec![Span::styled(range_note, Style::default().fg(Color::Gray))]),
+            Line::from(vec![
+                Span::styled("t", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" This month  "), Style::default().fg(Color::White)),
+                Span::styled("l", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" Last month  "), Style::default().fg(Color::White)),
+                Span::styled("q", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" Last quarter  "), Style::default().fg(Color::White)),
+                Span::styled("y", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" Year to date  "), Style::default().fg(Color::White)),
+                Span::styled("a", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" All"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("x", Style::default().fg(Color::Cyan)),
+                Span::styled(self.tr(" - Encryption: "), Style::default().fg(Color::White)),
+                if self.export_encrypt {
+                    Span::styled(self.tr("ON (AES-256, .enc)"), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+                } else {
+                    Span::styled(self.tr("OFF"), Style::default().fg(Color::DarkGray))
+                },
+            ]),
+            Line::from(""),
+            Line::from(vec![Span::styled(
+                self.tr("Press number to export, Esc to cancel"),
+                Style::default().fg(Color::Gray),
+            )]),
+        ];
+
+        let dialog = Paragraph::new(dialog_text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.tr("Export Options")),
+            )
+            .alignment(Alignment::Center);
+        frame.render_widget(dialog, area);
+    }
+
+    fn render_currency_filter_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let current_filter = match &self.currency_filter {
+            Some(c) => self.tr_fmt("Current: {}", &[&c]),
+            None => self.tr("Current: All").to_string(),
+        };
 
         let mut dialog_lines = vec![
             Line::from(vec![Span::styled(
-                "Filter Transactions by Currency",
+                self.tr("Filter Transactions by Currency"),
                 Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
             )]),
             Line::from(vec![Span::styled(current_filter, Style::default().fg(Color::Gray))]),
@@ -1475,9 +3112,9 @@ impl App {
             Line::from(vec![
                 Span::styled("0", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
                 Span::styled(" - ", Style::default().fg(Color::Gray)),
-                Span::styled("Show ALL Currencies", Style::default().fg(Color::White)),
+                Span::styled(self.tr("Show ALL Currencies"), Style::default().fg(Color::White)),
                 if self.currency_filter.is_none() {
-                    Span::styled(" ◄ active", Style::default().fg(Color::Green))
+                    Span::styled(self.tr(" ◄ active"), Style::default().fg(Color::Green))
                 } else { Span::raw("") },
             ]),
             Line::from(""),
@@ -1486,13 +3123,13 @@ impl App {
         // Add each currency from transactions dynamically (filter_currencies, not all available)
         if self.filter_currencies.is_empty() {
             dialog_lines.push(Line::from(vec![Span::styled(
-                "No currencies found in transactions", Style::default().fg(Color::Red)
+                self.tr("No currencies found in transactions"), Style::default().fg(Color::Red)
             )]));
         } else {
             for (i, currency) in self.filter_currencies.iter().enumerate() {
                 let is_selected = self.currency_filter.as_ref() == Some(currency);
                 let key = if i < 9 { format!("{}", i + 1) } else { format!("{}", (b'a' + (i - 9) as u8) as char) };
-                let active_marker = if is_selected { " ◄ active" } else { "" };
+                let active_marker = if is_selected { self.tr(" ◄ active") } else { "" };
                 dialog_lines.push(Line::from(vec![
                     Span::styled(key, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
                     Span::styled(" - ", Style::default().fg(Color::Gray)),
@@ -1505,12 +3142,109 @@ impl App {
 
         dialog_lines.push(Line::from(""));
         dialog_lines.push(Line::from(vec![Span::styled(
-            format!("Found {} currencies in transactions | Press key to filter | Esc: cancel", self.filter_currencies.len()),
+            self.tr_fmt("Found {} currencies in transactions | Press key to filter | Esc: cancel", &[&self.filter_currencies.len()]),
             Style::default().fg(Color::Gray),
         )]));
 
         let dialog = Paragraph::new(dialog_lines)
-            .block(Block::default().borders(Borders::ALL).title("Currency Filter"))
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Currency Filter")))
+            .alignment(Alignment::Center);
+        frame.render_widget(dialog, area);
+    }
+
+    /// The splits typed into the transaction form's category field, with
+    /// what is left of the amount to assign
+    fn split_preview_lines(&self) -> Vec<Line<'static>> {
+        if !self.form_category_id.contains(':') {
+            return Vec::new();
+        }
+        let amount = self.form_amount.parse::<f64>().unwrap_or(0.0).abs();
+        let Some(splits) = parse_category_splits(&self.form_category_id, amount) else {
+            return vec![Line::from(Span::styled(
+                self.tr("  Splits are ID:amount pairs separated by commas"),
+                Style::default().fg(Color::Red),
+            ))];
+        };
+
+        let mut lines: Vec<Line<'static>> = splits
+            .iter()
+            .map(|split| {
+                let name = self
+                    .categories
+                    .iter()
+                    .find(|c| c.id == split.category_id)
+                    .map(|c| c.name.clone());
+                Line::from(vec![
+                    Span::styled(
+                        format!("  {:<20}", name.as_deref().unwrap_or(self.tr("Unknown category"))),
+                        if name.is_some() {
+                            Style::default().fg(Color::White)
+                        } else {
+                            Style::default().fg(Color::Red)
+                        },
+                    ),
+                    Span::styled(format!("{:>10.2}", split.amount), Style::default().fg(Color::White)),
+                ])
+            })
+            .collect();
+        let remaining = amount - splits.iter().map(|s| s.amount).sum::<f64>();
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<20}", self.tr("Remaining")), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{:>10.2}", remaining),
+                if remaining.abs() <= 0.01 {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default().fg(Color::Yellow)
+                },
+            ),
+        ]));
+        lines
+    }
+
+    fn render_date_range_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let field_style = |index: usize| {
+            if self.form_field_index == index {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        let shortcut = |key: &'static str, label: &'static str| {
+            Line::from(vec![
+                Span::styled(key, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(" - ", Style::default().fg(Color::Gray)),
+                Span::styled(label, Style::default().fg(Color::White)),
+            ])
+        };
+
+        let dialog_lines = vec![
+            Line::from(vec![Span::styled(
+                self.tr("Show Transactions From a Date Range"),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(""),
+            shortcut("w", self.tr("This week")),
+            shortcut("t", self.tr("This month")),
+            shortcut("l", self.tr("Last month")),
+            shortcut("a", self.tr("All (latest 100)")),
+            Line::from(""),
+            Line::from(vec![Span::styled(self.tr("Or a custom range (inclusive):"), Style::default().fg(Color::Gray))]),
+            Line::from(vec![
+                Span::styled(self.tr("From: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{:<10}", self.form_range_start), field_style(0)),
+                Span::styled(self.tr("   To: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{:<10}", self.form_range_end), field_style(1)),
+            ]),
+            Line::from(""),
+            Line::from(vec![Span::styled(
+                self.tr("YYYY-MM-DD | Tab: Next field | Enter: Apply | Esc: Cancel"),
+                Style::default().fg(Color::Gray),
+            )]),
+        ];
+
+        let dialog = Paragraph::new(dialog_lines)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Date Range")))
             .alignment(Alignment::Center);
         frame.render_widget(dialog, area);
     }
@@ -1536,19 +3270,19 @@ impl App {
         let currency_display = if !selected_currency.is_empty() {
             format!(" → {} [{}]", selected_account_name, selected_currency)
         } else {
-            " (enter ID or use shortcuts on right)".to_string()
+            self.tr(" (enter ID or use shortcuts on right)").to_string()
         };
 
-        let form_text = vec![
+        let mut form_text = vec![
             Line::from(vec![Span::styled(
-                "Add New Transaction",
+                if self.mode == Mode::EditTransaction { self.tr("Edit Transaction") } else { self.tr("Add New Transaction") },
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
             )]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Account: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Account: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_account_id,
                     if self.form_field_index == 0 {
@@ -1562,7 +3296,7 @@ impl App {
                 Span::styled(&currency_display, Style::default().fg(Color::Cyan)),
             ]),
             Line::from(vec![
-                Span::styled("Amount: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Amount: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_amount,
                     if self.form_field_index == 1 {
@@ -1579,7 +3313,7 @@ impl App {
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Type (i=income/e=expense): ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Type (i=income/e=expense/t=transfer): "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_type,
                     if self.form_field_index == 2 {
@@ -1592,7 +3326,7 @@ impl App {
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Description: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Description: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_description,
                     if self.form_field_index == 3 {
@@ -1605,7 +3339,7 @@ impl App {
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Category ID: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Category ID: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_category_id,
                     if self.form_field_index == 4 {
@@ -1616,19 +3350,61 @@ impl App {
                         Style::default().fg(Color::White)
                     },
                 ),
+                Span::styled(self.tr(" (or ID:amount, ... to split)"), Style::default().fg(Color::DarkGray)),
+            ]),
+        ];
+        form_text.extend(self.split_preview_lines());
+        form_text.extend([
+            Line::from(vec![
+                Span::styled(self.tr("Fee: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    &self.form_fee,
+                    if self.form_field_index == 5 {
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::UNDERLINED)
+                    } else {
+                        Style::default().fg(Color::White)
+                    },
+                ),
+                Span::styled(self.tr(" (optional, bank/FX fee)"), Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Fee Category ID: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    &self.form_fee_category_id,
+                    if self.form_field_index == 6 {
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::UNDERLINED)
+                    } else {
+                        Style::default().fg(Color::White)
+                    },
+                ),
+                Span::styled(self.tr(" (optional)"), Style::default().fg(Color::DarkGray)),
             ]),
             Line::from(""),
             Line::from(vec![Span::styled(
-                "Tab: Next Field | Enter: Submit | Esc: Cancel",
+                self.help("Tab: Next Field | F1-F9: Template | Enter: Submit | Esc: Cancel"),
                 Style::default().fg(Color::Cyan),
             )]),
-        ];
+        ]);
+        if let Some(date) = self.form_date {
+            form_text.insert(
+                form_text.len() - 2,
+                Line::from(vec![
+                    Span::styled(self.tr("Date: "), Style::default().fg(Color::Gray)),
+                    Span::styled(date.format(self.date_pattern()).to_string(), Style::default().fg(Color::White)),
+                    Span::styled(self.tr(" (from receipt)"), Style::default().fg(Color::DarkGray)),
+                ]),
+            );
+        }
 
         let form = Paragraph::new(form_text)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Add Transaction Form"),
+                    .title(if self.mode == Mode::EditTransaction { self.tr("Edit Transaction Form") } else { self.tr("Add Transaction Form") }),
             )
             .alignment(Alignment::Left);
         frame.render_widget(form, chunks[0]);
@@ -1636,14 +3412,15 @@ impl App {
         // Account list with currency - grouped by currency
         let mut account_lines: Vec<Line> = vec![
             Line::from(vec![Span::styled(
-                "Quick Select (type number in Account field):",
+                self.tr("Quick Select (type number in Account field):"),
                 Style::default().fg(Color::Gray),
             )]),
             Line::from(""),
         ];
 
-        // Group accounts by currency
-        let mut currencies: Vec<String> = self.accounts.iter().map(|a| a.currency.clone()).collect();
+        // Group open accounts by currency
+        let open_accounts = || self.accounts.iter().filter(|a| !a.is_archived);
+        let mut currencies: Vec<String> = open_accounts().map(|a| a.currency.clone()).collect();
         currencies.sort();
         currencies.dedup();
 
@@ -1653,7 +3430,7 @@ impl App {
                 Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
             )]));
             
-            for a in self.accounts.iter().filter(|a| &a.currency == currency) {
+            for a in open_accounts().filter(|a| &a.currency == currency) {
                 let is_selected = self.form_account_id.parse::<i64>().ok() == Some(a.id);
                 account_lines.push(Line::from(vec![
                     Span::styled(
@@ -1665,7 +3442,7 @@ impl App {
                         },
                     ),
                     Span::styled(
-                        format!("{}", a.name),
+                        a.name.clone(),
                         if is_selected {
                             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
                         } else {
@@ -1682,18 +3459,31 @@ impl App {
             account_lines.push(Line::from(""));
         }
 
-        // Build combined panel with accounts (top) and categories (below)
+        // Build combined panel with templates and accounts (top) and categories (below)
         let mut right_lines: Vec<Line> = Vec::new();
+        if !self.templates.is_empty() {
+            right_lines.push(Line::from(Span::styled(
+                self.tr("Templates (press key to pre-fill)"),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+            for (i, t) in self.templates.iter().take(9).enumerate() {
+                right_lines.push(Line::from(vec![
+                    Span::styled(format!("  F{}: ", i + 1), Style::default().fg(Color::Cyan)),
+                    Span::styled(self.template_label(t), Style::default().fg(Color::White)),
+                ]));
+            }
+            right_lines.push(Line::from(""));
+        }
         right_lines.extend(account_lines);
         right_lines.push(Line::from(""));
         right_lines.push(Line::from(Span::styled(
-            "Categories (id = name [type])",
+            self.tr("Categories (id = name [type])"),
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         )));
 
         if self.categories.is_empty() {
             right_lines.push(Line::from(Span::styled(
-                "  None for this user",
+                self.tr("  None for this user"),
                 Style::default().fg(Color::DarkGray),
             )));
         } else {
@@ -1715,7 +3505,7 @@ impl App {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Select Account / Categories"),
+                    .title(self.tr("Select Account / Categories")),
             )
             .alignment(Alignment::Left);
 
@@ -1725,7 +3515,7 @@ impl App {
     fn render_add_exchange_rate_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         let form_text = vec![
             Line::from(vec![Span::styled(
-                "Add New Exchange Rate",
+                self.tr("Add New Exchange Rate"),
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
@@ -1733,7 +3523,7 @@ impl App {
             Line::from(""),
             Line::from(vec![
                 Span::styled(
-                    "From Currency (e.g., USD): ",
+                    self.tr("From Currency (e.g., USD): "),
                     Style::default().fg(Color::Gray),
                 ),
                 Span::styled(
@@ -1749,7 +3539,7 @@ impl App {
             ]),
             Line::from(vec![
                 Span::styled(
-                    "To Currency (e.g., Euro (EUR)): ",
+                    self.tr("To Currency (e.g., Euro (EUR)): "),
                     Style::default().fg(Color::Gray),
                 ),
                 Span::styled(
@@ -1764,7 +3554,7 @@ impl App {
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Rate: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Rate: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_rate,
                     if self.form_field_index == 2 {
@@ -1778,7 +3568,7 @@ impl App {
             ]),
             Line::from(vec![
                 Span::styled(
-                    "Source (manual/api/scraper): ",
+                    self.tr("Source (manual/api/scraper): "),
                     Style::default().fg(Color::Gray),
                 ),
                 Span::styled(
@@ -1794,7 +3584,7 @@ impl App {
             ]),
             Line::from(""),
             Line::from(vec![Span::styled(
-                "Tab: Next field | Enter: Submit | Esc: Cancel",
+                self.help("Tab: Next field | Enter: Submit | Esc: Cancel"),
                 Style::default().fg(Color::Cyan),
             )]),
         ];
@@ -1803,7 +3593,7 @@ impl App {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Add Exchange Rate Form"),
+                    .title(self.tr("Add Exchange Rate Form")),
             )
             .alignment(Alignment::Left);
         frame.render_widget(form, area);
@@ -1812,14 +3602,14 @@ impl App {
     fn render_currency_conversion(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         let form_text = vec![
             Line::from(vec![Span::styled(
-                "Currency Conversion",
+                self.tr("Currency Conversion"),
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
             )]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("From Currency: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("From Currency: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_convert_from,
                     if self.form_field_index == 0 {
@@ -1832,7 +3622,7 @@ impl App {
                 ),
             ]),
             Line::from(vec![
-                Span::styled("To Currency: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("To Currency: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_convert_to,
                     if self.form_field_index == 1 {
@@ -1845,7 +3635,7 @@ impl App {
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Amount: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Amount: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_convert_amount,
                     if self.form_field_index == 2 {
@@ -1859,7 +3649,7 @@ impl App {
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Result: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Result: "), Style::default().fg(Color::Gray)),
                 Span::styled(
                     &self.form_converted_result,
                     Style::default()
@@ -1869,7 +3659,7 @@ impl App {
             ]),
             Line::from(""),
             Line::from(vec![Span::styled(
-                "Tab: Next field | Enter: Convert | Esc: Cancel",
+                self.help("Tab: Next field | Enter: Convert | Esc: Cancel"),
                 Style::default().fg(Color::Cyan),
             )]),
         ];
@@ -1878,32 +3668,32 @@ impl App {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Currency Conversion"),
+                    .title(self.tr("Currency Conversion")),
             )
             .alignment(Alignment::Left);
         frame.render_widget(form, area);
     }
 
     fn render_delete_confirm(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let confirm_text = if self.current_screen == Screen::Transactions
-            && self.selected_index < self.transactions.len()
+        let confirm_text = if let Some(t) = self
+            .selected_transaction()
+            .filter(|_| self.current_screen == Screen::Transactions)
         {
-            let t = &self.transactions[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "Delete Transaction?",
+                    self.tr("Delete Transaction?"),
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("Amount: ${:.2}", t.amount)),
-                Line::from(format!("Type: {}", t.transaction_type)),
-                Line::from(format!(
+                Line::from(self.tr_fmt("Amount: ${}", &[&format!("{:.2}", t.amount)])),
+                Line::from(self.tr_fmt("Type: {}", &[&t.transaction_type])),
+                Line::from(self.tr_fmt(
                     "Description: {}",
-                    t.description.as_deref().unwrap_or("N/A")
+                    &[&t.description.as_deref().unwrap_or("N/A")],
                 )),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press 'y' to confirm, 'n' to cancel",
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
                     Style::default().fg(Color::Yellow),
                 )]),
             ]
@@ -1913,25 +3703,25 @@ impl App {
             let a = &self.accounts[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "⚠ DELETE ACCOUNT?",
+                    self.tr("⚠ DELETE ACCOUNT?"),
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("Name: {}", a.name)),
-                Line::from(format!("Bank: {}", a.bank_name.as_deref().unwrap_or("N/A"))),
-                Line::from(format!("Balance: {:.2} {}", a.current_balance, a.currency)),
+                Line::from(self.tr_fmt("Name: {}", &[&a.name])),
+                Line::from(self.tr_fmt("Bank: {}", &[&a.bank_name.as_deref().unwrap_or("N/A")])),
+                Line::from(self.tr_fmt("Balance: {} {}", &[&format!("{:.2}", a.current_balance), &a.currency])),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "⚠ WARNING: All transactions for this account",
+                    self.tr("⚠ WARNING: All transactions for this account"),
                     Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(vec![Span::styled(
-                    "  will also be PERMANENTLY deleted!",
+                    self.tr("  will also be PERMANENTLY deleted!"),
                     Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press 'y' to confirm, 'n' to cancel",
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
                     Style::default().fg(Color::Cyan),
                 )]),
             ]
@@ -1941,20 +3731,20 @@ impl App {
             let c = &self.categories[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "Delete Category?",
+                    self.tr("Delete Category?"),
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("ID: {}", c.id)),
-                Line::from(format!("Name: {}", c.name)),
+                Line::from(self.tr_fmt("ID: {}", &[&c.id])),
+                Line::from(self.tr_fmt("Name: {}", &[&c.name])),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "⚠ Categories linked to transactions cannot be deleted",
+                    self.tr("⚠ Categories linked to transactions cannot be deleted"),
                     Style::default().fg(Color::Yellow),
                 )]),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press 'y' to confirm, 'n' to cancel",
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
                     Style::default().fg(Color::Yellow),
                 )]),
             ]
@@ -1964,17 +3754,17 @@ impl App {
             let r = &self.exchange_rates[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "Delete Exchange Rate?",
+                    self.tr("Delete Exchange Rate?"),
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("From: {}", r.from_currency)),
-                Line::from(format!("To: {}", r.to_currency)),
-                Line::from(format!("Rate: {:.6}", r.rate)),
-                Line::from(format!("Date: {}", r.rate_date.format("%Y-%m-%d"))),
+                Line::from(self.tr_fmt("From: {}", &[&r.from_currency])),
+                Line::from(self.tr_fmt("To: {}", &[&r.to_currency])),
+                Line::from(self.tr_fmt("Rate: {}", &[&format!("{:.6}", r.rate)])),
+                Line::from(self.tr_fmt("Date: {}", &[&r.rate_date.format(self.date_pattern())])),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press 'y' to confirm, 'n' to cancel",
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
                     Style::default().fg(Color::Yellow),
                 )]),
             ]
@@ -1984,69 +3774,207 @@ impl App {
             let r = &self.recurring_transactions[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "Delete Recurring Transaction?",
+                    self.tr("Delete Recurring Transaction?"),
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("Amount: ${:.2}", r.amount)),
-                Line::from(format!("Type: {}", r.transaction_type)),
-                Line::from(format!("Frequency: {}", r.frequency)),
-                Line::from(format!(
+                Line::from(self.tr_fmt("Amount: ${}", &[&format!("{:.2}", r.amount)])),
+                Line::from(self.tr_fmt("Type: {}", &[&r.transaction_type])),
+                Line::from(self.tr_fmt("Frequency: {}", &[&r.frequency])),
+                Line::from(self.tr_fmt("Classification: {}", &[&r.classification])),
+                Line::from(self.tr_fmt(
                     "Description: {}",
-                    r.description.as_deref().unwrap_or("N/A")
+                    &[&r.description.as_deref().unwrap_or("N/A")],
                 )),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press 'y' to confirm, 'n' to cancel",
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
+                    Style::default().fg(Color::Yellow),
+                )]),
+            ]
+        } else if self.current_screen == Screen::Budgets
+            && self.selected_index < self.budget_variance.len()
+        {
+            let b = &self.budget_variance[self.selected_index];
+            vec![
+                Line::from(vec![Span::styled(
+                    self.tr("Delete Budget?"),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )]),
+                Line::from(""),
+                Line::from(self.tr_fmt("Category: {}", &[&b.category_name])),
+                Line::from(self.tr_fmt("Monthly limit: {}", &[&format!("{:.2}", b.budgeted)])),
+                Line::from(""),
+                Line::from(vec![Span::styled(
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
+                    Style::default().fg(Color::Yellow),
+                )]),
+            ]
+        } else if self.current_screen == Screen::Goals && self.selected_index < self.goals.len() {
+            let g = &self.goals[self.selected_index];
+            vec![
+                Line::from(vec![Span::styled(
+                    self.tr("Delete Goal?"),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )]),
+                Line::from(""),
+                Line::from(self.tr_fmt("Goal: {}", &[&g.goal.name])),
+                Line::from(self.tr_fmt("Target: {} {}", &[&format!("{:.2}", g.goal.target_amount), &g.goal.currency])),
+                Line::from(self.tr("The linked accounts are kept")),
+                Line::from(""),
+                Line::from(vec![Span::styled(
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
+                    Style::default().fg(Color::Yellow),
+                )]),
+            ]
+        } else if let Some(h) = self.selected_holding() {
+            vec![
+                Line::from(vec![Span::styled(
+                    self.tr("Delete Holding?"),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )]),
+                Line::from(""),
+                Line::from(self.tr_fmt("Holding: {} x {}", &[&h.holding.ticker, &h.holding.quantity])),
+                Line::from(self.tr_fmt("Cost basis: {}", &[&format!("{:.2}", h.holding.cost_basis)])),
+                Line::from(""),
+                Line::from(vec![Span::styled(
+                    self.tr("Press 'y' to confirm, 'n' to cancel"),
                     Style::default().fg(Color::Yellow),
                 )]),
             ]
         } else {
-            vec![Line::from("Invalid selection")]
+            vec![Line::from(self.tr("Invalid selection"))]
         };
 
         let confirm = Paragraph::new(confirm_text)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Confirm Delete"),
+                    .title(self.tr("Confirm Delete")),
             )
             .alignment(Alignment::Center);
         frame.render_widget(confirm, area);
     }
 
     fn render_details(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let details_text = if self.current_screen == Screen::Transactions
-            && self.selected_index < self.transactions.len()
+        let details_text = if let Some(t) = self
+            .selected_transaction()
+            .filter(|_| self.current_screen == Screen::Transactions)
         {
-            let t = &self.transactions[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "Transaction Details",
+                    self.tr("Transaction Details"),
                     Style::default()
                         .fg(Color::Cyan)
                         .add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("ID: {}", t.id)),
-                Line::from(format!("Account ID: {}", t.account_id)),
-                Line::from(format!("Amount: ${:.2}", t.amount)),
-                Line::from(format!("Type: {}", t.transaction_type)),
-                Line::from(format!(
+                Line::from(self.tr_fmt("ID: {}", &[&t.id])),
+                Line::from(self.tr_fmt("Account ID: {}", &[&t.account_id])),
+                Line::from(self.tr_fmt("Amount: ${}", &[&format!("{:.2}", t.amount)])),
+                Line::from(match (t.original_amount, t.original_currency.as_deref()) {
+                    (Some(original), Some(currency)) => self.tr_fmt(
+                        "Original: {} {}{}",
+                        &[
+                            &format!("{:.2}", original),
+                            &currency,
+                            &t.exchange_rate.map(|r| format!(" @ {:.4}", r)).unwrap_or_default(),
+                        ],
+                    ),
+                    _ => self.tr("Original: Same as account currency").to_string(),
+                }),
+                Line::from(self.tr_fmt("Type: {}", &[&t.transaction_type])),
+                Line::from(self.tr_fmt("Status: {}", &[&t.status])),
+                Line::from(match self.transaction_splits.get(&t.id).map(Vec::as_slice) {
+                    None | Some([]) => self.tr("Category: None").to_string(),
+                    Some([split]) => self.tr_fmt("Category: {}", &[&split.category_name]),
+                    Some(splits) => self.tr_fmt(
+                        "Split: {}",
+                        &[
+                            &splits
+                                .iter()
+                                .map(|s| format!("{} ${:.2}", s.category_name, s.amount))
+                                .collect::<Vec<_>>()
+                                .join(" | "),
+                        ],
+                    ),
+                }),
+                Line::from(if t.fee > 0.0 {
+                    self.tr_fmt(
+                        "Fee: ${}{}",
+                        &[
+                            &format!("{:.2}", t.fee),
+                            &t.fee_category_id
+                                .and_then(|id| self.categories.iter().find(|c| c.id == id))
+                                .map(|c| format!(" ({})", c.name))
+                                .unwrap_or_default(),
+                        ],
+                    )
+                } else {
+                    self.tr("Fee: None").to_string()
+                }),
+                Line::from(self.tr_fmt(
                     "Description: {}",
-                    t.description.as_deref().unwrap_or("No description")
+                    &[&t.description.as_deref().unwrap_or(self.tr("No description"))],
+                )),
+                Line::from(self.tr_fmt(
+                    "Reference: {}",
+                    &[&t.reference.as_deref().unwrap_or(self.tr("None"))],
+                )),
+                Line::from(self.tr_fmt(
+                    "Merchant: {}{}",
+                    &[
+                        &t.merchant.as_deref().unwrap_or(self.tr("None")),
+                        &match (t.merchant_city.as_deref(), t.merchant_country.as_deref()) {
+                            (Some(city), Some(country)) => format!(" ({}, {})", city, country),
+                            (Some(place), None) | (None, Some(place)) => format!(" ({})", place),
+                            (None, None) => String::new(),
+                        },
+                    ],
                 )),
-                Line::from(format!(
-                    "Date: {}",
-                    t.transaction_date.format("%Y-%m-%d %H:%M:%S")
+                Line::from(self.tr_fmt(
+                    "Date: {} {}",
+                    &[&t.transaction_date.format(self.date_pattern()), &t.transaction_date.format("%H:%M:%S")],
                 )),
-                Line::from(format!(
+                Line::from(self.tr_fmt(
                     "Created: {}",
-                    t.created_at.format("%Y-%m-%d %H:%M:%S")
+                    &[&t.created_at.format("%Y-%m-%d %H:%M:%S")],
+                )),
+                Line::from(match self.transaction_tags.get(&t.id) {
+                    Some(names) => self.tr_fmt(
+                        "Tags: {}",
+                        &[&names.iter().map(|n| format!("#{}", n)).collect::<Vec<_>>().join(" ")],
+                    ),
+                    None => self.tr("Tags: None").to_string(),
+                }),
+                Line::from(self.tr_fmt(
+                    "Attachments: {}",
+                    &[&self.attachment_counts.get(&t.id).copied().unwrap_or(0)],
                 )),
+                Line::from(match (t.reimburses_transaction_id, self.reimbursed_amounts.get(&t.id)) {
+                    (Some(expense_id), _) => self.tr_fmt("Reimbursement: pays back transaction {}", &[&expense_id]),
+                    (None, Some(reimbursed)) => self.tr_fmt(
+                        "Reimbursement: ${} paid back, ${} still spent",
+                        &[
+                            &format!("{:.2}", reimbursed),
+                            &format!("{:.2}", reimbursements::net_amount(t.amount, *reimbursed)),
+                        ],
+                    ),
+                    (None, None) => self.tr("Reimbursement: None").to_string(),
+                }),
+                Line::from(if self.counts_in_reports(t) {
+                    self.tr("Reports: Included").to_string()
+                } else if t.exclude_from_reports {
+                    self.tr("Reports: Excluded").to_string()
+                } else {
+                    self.tr("Reports: Excluded (by category)").to_string()
+                }),
+                Line::from(""),
+                Line::from(vec![Span::styled(self.tr("Notes:"), Style::default().fg(Color::Gray))]),
+                Line::from(t.notes.as_deref().unwrap_or(self.tr("No notes")).to_string()),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press Esc to go back",
+                    self.tr("Press Esc to go back | n: Edit notes/reference | T: Add/remove tags | E: Exclude from reports"),
                     Style::default().fg(Color::Gray),
                 )]),
             ]
@@ -2061,31 +3989,41 @@ impl App {
                 .collect();
             
             // Determine display currency (use account-specific view currency)
-            let display_currency = self.account_view_currency.as_ref()
-                .map(|s| s.as_str())
+            let display_currency = self.account_view_currency.as_deref()
                 .unwrap_or(&a.currency);
             let rate = self.get_exchange_rate(&a.currency, display_currency);
+            let projected = self.projected_balances.get(&a.id).copied().unwrap_or(a.current_balance);
             
             let view_indicator = if self.account_view_currency.is_some() {
-                format!(" → {}", display_currency)
+                match self.stale_rate_age(&a.currency, display_currency) {
+                    Some(days) => self.tr_fmt(" → {} (⚠ rate {}d old)", &[&display_currency, &days]),
+                    None => format!(" → {}", display_currency),
+                }
             } else {
                 String::new()
             };
             
             let mut lines = vec![
                 Line::from(vec![Span::styled(
-                    format!("Account: {} [{}]{}", a.name, a.currency, view_indicator),
+                    self.tr_fmt("Account: {} [{}]{}", &[&a.name, &a.currency, &view_indicator]),
                     Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
                 Line::from(vec![
-                    Span::styled("Type: ", Style::default().fg(Color::Gray)),
+                    Span::styled(self.tr("Type: "), Style::default().fg(Color::Gray)),
                     Span::raw(format!("{} | ", a.account_type)),
-                    Span::styled("Bank: ", Style::default().fg(Color::Gray)),
+                    Span::styled(self.tr("Bank: "), Style::default().fg(Color::Gray)),
                     Span::raw(a.bank_name.as_deref().unwrap_or("N/A")),
+                    Span::styled(self.tr(" | Default Category: "), Style::default().fg(Color::Gray)),
+                    Span::raw(
+                        a.default_category_id
+                            .and_then(|id| self.categories.iter().find(|c| c.id == id))
+                            .map(|c| c.name.as_str())
+                            .unwrap_or(self.tr("None")),
+                    ),
                 ]),
                 Line::from(vec![
-                    Span::styled("Balance: ", Style::default().fg(Color::Gray)),
+                    Span::styled(self.tr("Balance: "), Style::default().fg(Color::Gray)),
                     Span::styled(format!("{:.2} {}", a.current_balance * rate, display_currency),
                         if a.current_balance >= 0.0 { Style::default().fg(Color::Green) }
                         else { Style::default().fg(Color::Red) }),
@@ -2096,18 +4034,74 @@ impl App {
                         Span::raw("")
                     },
                 ]),
+                Line::from(vec![
+                    Span::styled(self.tr("Projected (month end): "), Style::default().fg(Color::Gray)),
+                    Span::styled(
+                        format!("{:.2} {}", projected * rate, display_currency),
+                        Style::default().fg(if projected >= 0.0 { Color::Green } else { Color::Red }),
+                    ),
+                    Span::styled(
+                        self.tr(" incl. pending and recurring items due"),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::styled(self.tr("Opened: "), Style::default().fg(Color::Gray)),
+                    Span::raw(match a.opened_on {
+                        Some(opened_on) => self.tr_fmt(
+                            "{} with {} {}",
+                            &[&opened_on, &format!("{:.2}", a.initial_balance), &a.currency],
+                        ),
+                        None => self.tr("Unknown").to_string(),
+                    }),
+                ]),
+            ];
+            if let Some(closed_on) = a.closed_on {
+                lines.push(Line::from(vec![
+                    Span::styled(self.tr("Closed: "), Style::default().fg(Color::Gray)),
+                    Span::styled(
+                        self.tr_fmt("{} (archived, no new transactions)", &[&closed_on]),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+
+            if let Some(stats) = a.credit_limit.map(|_| credit::account_stats(a, projected, credit::utilization_threshold())) {
+                let utilization = stats.utilization_percent.unwrap_or(0.0);
+                lines.push(Line::from(vec![
+                    Span::styled(self.tr("Credit Limit: "), Style::default().fg(Color::Gray)),
+                    Span::raw(format!("{:.2} {} | ", stats.credit_limit.unwrap_or(0.0), a.currency)),
+                    Span::styled(self.tr("Available: "), Style::default().fg(Color::Gray)),
+                    Span::raw(format!("{:.2} | ", stats.available_credit.unwrap_or(0.0))),
+                    Span::styled(self.tr("Utilization: "), Style::default().fg(Color::Gray)),
+                    Span::styled(
+                        if stats.over_threshold {
+                            self.tr_fmt("{}% ⚠ over {}%", &[&format!("{:.1}", utilization), &format!("{:.0}", stats.utilization_threshold)])
+                        } else {
+                            format!("{:.1}%", utilization)
+                        },
+                        if stats.over_threshold {
+                            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(Color::Green)
+                        },
+                    ),
+                ]));
+            }
+
+            lines.extend([
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    format!("─── Transactions ({}) ───", account_txns.len()),
+                    self.tr_fmt("─── Transactions ({}) ───", &[&account_txns.len()]),
                     Style::default().fg(Color::Yellow),
                 )]),
-            ];
+            ]);
             
             // Show transactions (limit to 15)
             for t in account_txns.iter().take(15) {
                 let sign = if t.transaction_type == "income" { "+" } else { "-" };
                 let color = if t.transaction_type == "income" { Color::Green } else { Color::Red };
-                let desc = t.description.as_deref().unwrap_or("No description");
+                let desc = t.description.as_deref().unwrap_or(self.tr("No description"));
                 let display_amount = t.amount * rate;
                 lines.push(Line::from(vec![
                     Span::styled(format!("{}{:.2} ", sign, display_amount), Style::default().fg(color)),
@@ -2118,24 +4112,31 @@ impl App {
                     } else {
                         Span::raw("")
                     },
-                    Span::raw(if desc.len() > 25 { format!("{}...", &desc[..22]) } else { desc.to_string() }),
+                    Span::raw(if desc.len() > 25 { format!("{}...", &desc[..22]) } else { format!("{:<25}", desc) }),
+                    match self.running_balances.get(&t.id) {
+                        Some(balance) => Span::styled(
+                            format!("  = {:.2}", balance * rate),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        None => Span::raw(""),
+                    },
                 ]));
             }
             if account_txns.len() > 15 {
                 lines.push(Line::from(vec![Span::styled(
-                    format!("  ... and {} more", account_txns.len() - 15),
+                    self.tr_fmt("  ... and {} more", &[&(account_txns.len() - 15)]),
                     Style::default().fg(Color::DarkGray),
                 )]));
             }
             
             lines.push(Line::from(""));
             let view_hint = if self.account_view_currency.is_some() {
-                format!("v: Change view (current: {})", display_currency)
+                self.tr_fmt("v: Change view (current: {})", &[&display_currency])
             } else {
-                "v: View in different currency".to_string()
+                self.tr("v: View in different currency").to_string()
             };
             lines.push(Line::from(vec![Span::styled(
-                format!("Esc: Back | {}", view_hint),
+                self.tr_fmt("Esc: Back | {}", &[&view_hint]),
                 Style::default().fg(Color::Gray),
             )]));
             lines
@@ -2145,32 +4146,32 @@ impl App {
             let r = &self.exchange_rates[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "Exchange Rate Details",
+                    self.tr("Exchange Rate Details"),
                     Style::default()
                         .fg(Color::Cyan)
                         .add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("ID: {}", r.id)),
-                Line::from(format!("From Currency: {}", r.from_currency)),
-                Line::from(format!("To Currency: {}", r.to_currency)),
-                Line::from(format!("Rate: {:.6}", r.rate)),
-                Line::from(format!("Source: {}", r.source)),
-                Line::from(format!(
+                Line::from(self.tr_fmt("ID: {}", &[&r.id])),
+                Line::from(self.tr_fmt("From Currency: {}", &[&r.from_currency])),
+                Line::from(self.tr_fmt("To Currency: {}", &[&r.to_currency])),
+                Line::from(self.tr_fmt("Rate: {}", &[&format!("{:.6}", r.rate)])),
+                Line::from(self.tr_fmt("Source: {}", &[&r.source])),
+                Line::from(self.tr_fmt(
                     "Rate Date: {}",
-                    r.rate_date.format("%Y-%m-%d %H:%M:%S")
+                    &[&r.rate_date.format("%Y-%m-%d %H:%M:%S")],
                 )),
-                Line::from(format!(
+                Line::from(self.tr_fmt(
                     "Created: {}",
-                    r.created_at.format("%Y-%m-%d %H:%M:%S")
+                    &[&r.created_at.format("%Y-%m-%d %H:%M:%S")],
                 )),
-                Line::from(format!(
+                Line::from(self.tr_fmt(
                     "Updated: {}",
-                    r.updated_at.format("%Y-%m-%d %H:%M:%S")
+                    &[&r.updated_at.format("%Y-%m-%d %H:%M:%S")],
                 )),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press Esc to go back",
+                    self.tr("Press Esc to go back"),
                     Style::default().fg(Color::Gray),
                 )]),
             ]
@@ -2180,1183 +4181,4563 @@ impl App {
             let r = &self.recurring_transactions[self.selected_index];
             vec![
                 Line::from(vec![Span::styled(
-                    "Recurring Transaction Details",
+                    self.tr("Recurring Transaction Details"),
                     Style::default()
                         .fg(Color::Cyan)
                         .add_modifier(Modifier::BOLD),
                 )]),
                 Line::from(""),
-                Line::from(format!("ID: {}", r.id)),
-                Line::from(format!("Account ID: {}", r.account_id)),
-                Line::from(format!("Category ID: {}", r.category_id.map(|c| c.to_string()).unwrap_or("None".to_string()))),
-                Line::from(format!("Amount: ${:.2}", r.amount)),
-                Line::from(format!("Type: {}", r.transaction_type)),
-                Line::from(format!("Frequency: {}", r.frequency)),
-                Line::from(format!(
+                Line::from(self.tr_fmt("ID: {}", &[&r.id])),
+                Line::from(self.tr_fmt("Account ID: {}", &[&r.account_id])),
+                Line::from(self.tr_fmt("Category ID: {}", &[&r.category_id.map(|c| c.to_string()).unwrap_or(self.tr("None").to_string())])),
+                Line::from(self.tr_fmt("Amount: ${}", &[&format!("{:.2}", r.amount)])),
+                Line::from(self.tr_fmt("Type: {}", &[&r.transaction_type])),
+                Line::from(self.tr_fmt("Frequency: {}", &[&r.frequency])),
+                Line::from(self.tr_fmt("Classification: {}", &[&r.classification])),
+                Line::from(self.tr_fmt(
+                    "Missed occurrences: {}",
+                    &[&if r.catch_up == "latest" { self.tr("post the latest only") } else { self.tr("post all") }],
+                )),
+                Line::from(self.tr_fmt(
                     "Description: {}",
-                    r.description.as_deref().unwrap_or("No description")
+                    &[&r.description.as_deref().unwrap_or(self.tr("No description"))],
                 )),
-                Line::from(format!("Status: {}", if r.is_active { "Active" } else { "Paused" })),
-                Line::from(format!(
+                Line::from(self.tr_fmt("Status: {}", &[&if r.is_active { self.tr("Active") } else { self.tr("Paused") }])),
+                Line::from(self.tr_fmt(
                     "Start Date: {}",
-                    r.start_date.format("%Y-%m-%d")
+                    &[&r.start_date.format(self.date_pattern())],
                 )),
-                Line::from(format!(
+                Line::from(self.tr_fmt(
                     "End Date: {}",
-                    r.end_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or("None".to_string())
+                    &[
+                        &r.end_date.map(|d| d.format(self.date_pattern()).to_string()).unwrap_or(self.tr("None").to_string()),
+                    ],
                 )),
-                Line::from(format!(
+                Line::from(self.tr_fmt(
                     "Next Occurrence: {}",
-                    r.next_occurrence.format("%Y-%m-%d")
+                    &[&r.next_occurrence.format(self.date_pattern())],
                 )),
                 Line::from(""),
                 Line::from(vec![Span::styled(
-                    "Press Esc to go back",
+                    self.tr("Press Esc to go back"),
                     Style::default().fg(Color::Gray),
                 )]),
             ]
         } else {
-            vec![Line::from("No details available")]
+            vec![Line::from(self.tr("No details available"))]
         };
 
-        let details = Paragraph::new(details_text)
-            .block(Block::default().borders(Borders::ALL).title("Details"))
-            .alignment(Alignment::Left);
-        frame.render_widget(details, area);
-    }
+        let details = Paragraph::new(details_text)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Details")))
+            .alignment(Alignment::Left);
+        frame.render_widget(details, area);
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let help_text = if !self.status_message.is_empty() {
+            Paragraph::new(self.status_message.as_str())
+                .style(Style::default().fg(Color::Green))
+                .alignment(Alignment::Center)
+        } else {
+            match self.mode {
+                Mode::Normal => {
+                    if self.current_screen == Screen::UserSelect {
+                        Paragraph::new(self.help("↑↓: Select | Enter: Login | a: Add | d: Delete | L: Language | q: Quit"))
+                    } else if self.current_screen == Screen::Transactions {
+                        Paragraph::new(self.help("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | e: Edit | x: Pending/Cleared | X: Void | R: Receipt | I: Import | c: Category | /: Search | D: Dates | f: Filter | v: View in Currency | d: Delete | Enter: Details | q: Quit"))
+                    } else if self.current_screen == Screen::ExchangeRates {
+                        Paragraph::new(self.help("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | c: Convert | p: Pin | d: Delete | Enter: Details | r: Refresh | q: Quit"))
+                    } else if self.current_screen == Screen::RecurringTransactions {
+                        Paragraph::new(self.help("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | p: Process | t: Toggle | d: Delete | r: Refresh | q: Quit"))
+                    } else if self.current_screen == Screen::Export {
+                        Paragraph::new(self.help("←/→ or 1-9: Tabs | e: Export data | r: Refresh | H: Open here on login | u: Switch user | P: Profile | i: Status | q: Quit"))
+                    } else if self.current_screen == Screen::Dashboard {
+                        Paragraph::new(self.help("←/→ or 1-9: Tabs | ↑/↓: Scroll | r: Refresh | b: Base currency | H: Open here on login | u: Switch user | P: Profile | L: Language | i: Status | q: Quit"))
+                    } else if self.current_screen == Screen::Accounts {
+                        Paragraph::new(self.help("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | d: Delete | s: Star | J/K: Move down/up | A: Group | C: Currency | o: Group view | Enter: Details | q: Quit"))
+                    } else if self.current_screen == Screen::Categories {
+                        Paragraph::new(self.help("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | e: Edit | d: Delete | R: Apply rules | r: Refresh | q: Quit"))
+                    } else if self.current_screen == Screen::Budgets {
+                        Paragraph::new(self.help("↑↓: Select | a: Set limit | Enter: Edit limit | d: Delete | r: Refresh | q: Quit"))
+                    } else if self.current_screen == Screen::Goals {
+                        Paragraph::new(self.help("↑↓: Select | a: Add | d: Delete | r: Refresh | q: Quit"))
+                    } else if self.current_screen == Screen::Investments {
+                        Paragraph::new(self.help("↑↓: Select | a: Add | d: Delete | p: Update prices | r: Refresh | q: Quit"))
+                    } else if self.current_screen == Screen::Settings {
+                        Paragraph::new(self.help("←/→, 1-9, 0, -, =: Tabs | ↑↓: Select | Enter/Space: Change | u: Switch user | q: Quit"))
+                    } else if self.current_screen == Screen::Reports {
+                        Paragraph::new(self.help("←/→ or 1-9: Tabs | m: Chart months (3/6/12) | S: Roll up sub-categories | F: Forecast | r: Refresh | H: Open here on login | u: Switch user | P: Profile | q: Quit"))
+                    } else {
+                        Paragraph::new(self.help("←/→ or 1-9: Tabs | ↑/↓: Select | n: Quick add | r: Refresh | u: User | q: Quit"))
+                    }
+                }
+                Mode::AddTransaction => Paragraph::new(
+                    self.help("Tab: Next field | F1-F9: Apply template | Enter: Submit | Esc: Cancel")
+                ),
+                Mode::EditTransaction => Paragraph::new(
+                    self.help("Tab: Next field | Enter: Save changes | Esc: Cancel")
+                ),
+                Mode::AddExchangeRate => Paragraph::new(
+                    self.help("Tab: Next field | Enter: Submit | Esc: Cancel | (Tab cycles through fields)")
+                ),
+                Mode::AddRecurringTransaction | Mode::AddHolding | Mode::AddTransfer => Paragraph::new(
+                    self.help(self.wizard.as_ref().map(Wizard::help).unwrap_or_default())
+                ),
+                Mode::AddAccount => Paragraph::new(
+                    self.help("Tab: Next field | Enter: Submit | Esc: Cancel | (Tab cycles through fields)")
+                ),
+                Mode::AddCategory => Paragraph::new(
+                    self.help("Tab: Next field | Type to input | Enter: Submit | Esc: Cancel")
+                ),
+                Mode::AddUser => Paragraph::new(
+                    self.help("Tab: Next field | Type to input | Enter: Create User | Esc: Cancel")
+                ),
+                Mode::ConvertCurrency => Paragraph::new(
+                    self.help("Tab: Next field | Enter: Convert | Esc: Cancel | (Tab cycles through fields)")
+                ),
+                Mode::DeleteConfirm => Paragraph::new(
+                    self.help("y: Confirm delete | n: Cancel")
+                ),
+                Mode::ViewDetails => {
+                    if self.current_screen == Screen::Transactions {
+                        Paragraph::new(self.help("Esc: Go back | n: Edit notes/reference | T: Tags | E: Exclude from reports"))
+                    } else {
+                        Paragraph::new(self.help("Esc: Go back | v: View in different currency"))
+                    }
+                }
+                Mode::ExportData => Paragraph::new(
+                    self.help("1-7: Select export format | t/l/q/y/a: Date range | Tab: Edit dates | x: Toggle encryption | Esc: Cancel")
+                ),
+                Mode::ExportPassphrase => Paragraph::new(
+                    self.help("Type passphrase | Enter: Enable encryption | Esc: Cancel")
+                ),
+                Mode::ReceiptPath => Paragraph::new(
+                    self.help("Type receipt file path | Enter: Read receipt | Esc: Cancel")
+                ),
+                Mode::SearchTransactions => Paragraph::new(
+                    self.help("Type to filter by description, amount, category or tag | Enter: Keep | Esc: Clear")
+                ),
+                Mode::SelectCurrencyFilter => Paragraph::new(
+                    self.help("0: All Currencies | 1-9: Select currency | Esc: Cancel")
+                ),
+                Mode::SelectDateRange => Paragraph::new(
+                    self.help("w: This week | t: This month | l: Last month | a: All | Type YYYY-MM-DD, Tab: Next field, Enter: Apply | Esc: Cancel")
+                ),
+                Mode::ImportCsv => Paragraph::new(
+                    self.help("Tab: Next field | ←/→: Pick profile or account | Type file path | Enter: Import | Esc: Cancel")
+                ),
+                Mode::SetAccountGroup => Paragraph::new(
+                    self.help("Type a group name (new or existing) | Enter: Save, empty to ungroup | Esc: Cancel")
+                ),
+                Mode::ChangeAccountCurrency => Paragraph::new(
+                    self.help("Tab: Next field | Enter: Change currency | Esc: Cancel")
+                ),
+                Mode::SelectViewCurrency => Paragraph::new(
+                    self.help("↑↓: Scroll | []: Jump 10 | Enter: Select | Esc: Cancel")
+                ),
+                Mode::EditTransactionNotes => Paragraph::new(
+                    self.help("Tab: Next field | Enter: Save | Esc: Cancel")
+                ),
+                Mode::EditTags => Paragraph::new(
+                    self.help("Type a tag | Enter: Add it, or remove it if the transaction has it | Esc: Back")
+                ),
+                Mode::QuickAdd => Paragraph::new(
+                    self.help("Tab: Next field | Enter: Add expense | Esc: Cancel")
+                ),
+                Mode::Onboarding => Paragraph::new(
+                    self.help("y: Yes | n: Skip | Esc: Finish setup")
+                ),
+                Mode::SelectProfile => Paragraph::new(
+                    self.help("↑↓: Select | Enter: Switch profile | Esc: Cancel")
+                ),
+                Mode::EnterPassword => Paragraph::new(
+                    self.help("Type password | Enter: Login | Esc: Cancel")
+                ),
+                Mode::Recategorize => Paragraph::new(
+                    self.help("↑↓: Select | Enter: Move to category | Esc: Cancel")
+                ),
+                Mode::SetBudget => Paragraph::new(
+                    self.help("←/→: Category | Type limit | Enter: Save | Esc: Cancel")
+                ),
+                Mode::AddGoal => Paragraph::new(
+                    self.help("Tab/↑↓: Field | ←/→: Account | Enter: Save | Esc: Cancel")
+                ),
+                Mode::WeeklyDigest => Paragraph::new(
+                    self.help("Enter/Esc: Dismiss | n: Don't show after login")
+                ),
+                Mode::SystemStatus => Paragraph::new(
+                    self.help("r: Refresh | Enter/Esc: Dismiss")
+                ),
+            }
+            .style(Style::default().fg(Color::Gray))
+            .alignment(Alignment::Center)
+        };
+
+        let footer = help_text.block(Block::default().borders(Borders::ALL));
+        frame.render_widget(footer, area);
+    }
+
+    async fn handle_events(&mut self) -> io::Result<()> {
+        if event::poll(StdDuration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    if self.current_screen == Screen::UserSelect {
+                        self.handle_user_select_mode(key.code).await;
+                    } else {
+                        match self.mode {
+                            Mode::Normal => self.handle_normal_mode(key.code).await,
+                            Mode::AddTransaction | Mode::EditTransaction => {
+                                self.handle_add_transaction_mode(key.code).await
+                            }
+                            Mode::AddExchangeRate => {
+                                self.handle_add_exchange_rate_mode(key.code).await
+                            }
+                            Mode::AddRecurringTransaction => {
+                                self.handle_add_recurring_mode(key.code).await
+                            }
+                            Mode::AddAccount => {
+                                self.handle_add_account_mode(key.code).await
+                            }
+                            Mode::AddCategory => {
+                                self.handle_add_category_mode(key.code).await
+                            }
+                            Mode::AddUser => {
+                                self.handle_add_user_mode(key.code).await
+                            }
+                            Mode::ConvertCurrency => {
+                                self.handle_convert_currency_mode(key.code).await
+                            }
+                            Mode::DeleteConfirm => self.handle_delete_mode(key.code).await,
+                            Mode::ViewDetails => self.handle_details_mode(key.code).await,
+                            Mode::EditTags => self.handle_edit_tags_mode(key.code).await,
+                            Mode::ExportData => self.handle_export_mode(key.code).await,
+                            Mode::SelectCurrencyFilter => self.handle_currency_filter_mode(key.code),
+                            Mode::SelectDateRange => self.handle_date_range_mode(key.code).await,
+                            Mode::ImportCsv => self.handle_import_mode(key.code).await,
+                            Mode::SetAccountGroup => self.handle_account_group_mode(key.code).await,
+                            Mode::ChangeAccountCurrency => {
+                                self.handle_account_currency_mode(key.code).await
+                            }
+                            Mode::SelectViewCurrency => self.handle_view_currency_mode(key.code).await,
+                            Mode::EditTransactionNotes => {
+                                self.handle_edit_notes_mode(key.code).await
+                            }
+                            Mode::ExportPassphrase => self.handle_export_passphrase_mode(key.code),
+                            Mode::QuickAdd => self.handle_quick_add_mode(key.code).await,
+                            Mode::Onboarding => self.handle_onboarding_mode(key.code).await,
+                            Mode::SelectProfile => self.handle_profile_mode(key.code).await,
+                            Mode::Recategorize => self.handle_recategorize_mode(key.code).await,
+                            Mode::SetBudget => self.handle_set_budget_mode(key.code).await,
+                            Mode::AddGoal => self.handle_add_goal_mode(key.code).await,
+                            Mode::AddHolding => self.handle_add_holding_mode(key.code).await,
+                            Mode::AddTransfer => self.handle_add_transfer_mode(key.code).await,
+                            Mode::WeeklyDigest => self.handle_weekly_digest_mode(key.code).await,
+                            Mode::SystemStatus => self.handle_system_status_mode(key.code).await,
+                            Mode::EnterPassword => self.handle_password_mode(key.code).await,
+                            Mode::ReceiptPath => self.handle_receipt_path_mode(key.code).await,
+                            Mode::SearchTransactions => self.handle_search_mode(key.code),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Periodically refresh data so background changes (like auto-processed recurring txns) show up.
+    async fn maybe_auto_refresh(&mut self) {
+        // Only refresh when a user is selected and we are not in a modal/form mode.
+        if self.current_user_id.is_none() || self.mode != Mode::Normal {
+            return;
+        }
+
+        let now = Instant::now();
+        if self.remote.is_some() && now.duration_since(self.last_remote_sync) >= StdDuration::from_secs(remote::SYNC_SECONDS) {
+            self.sync_remote().await;
+        }
+        if now.duration_since(self.last_auto_refresh) >= StdDuration::from_secs(3) {
+            self.load_data().await;
+            self.last_auto_refresh = now;
+        }
+    }
+
+    /// The session's user in the mirror's user list
+    fn remote_user_index(&self) -> Option<usize> {
+        let remote = self.remote.as_ref()?;
+        self.users.iter().position(|u| u.id == remote.user.id)
+    }
+
+    /// What `code` would change on the current screen that the server's API
+    /// has no call for in RemoteClient. Remote mode refuses those keys before
+    /// any form opens, since the read-only mirror would only fail on save.
+    fn remote_unsupported(&self, code: KeyCode) -> Option<&'static str> {
+        let key = match code {
+            KeyCode::Char(key) => key,
+            KeyCode::Enter => '\n',
+            _ => return None,
+        };
+        match (self.current_screen, key) {
+            (_, 'U') => Some("Undo"),
+            (_, 'H') => Some("Saving the start screen"),
+            (Screen::Dashboard, 'b') | (Screen::Transactions, 'v') => Some("Saving the view currency"),
+            (Screen::Transactions, 'R') => Some("Adding receipts"),
+            (Screen::Transactions, 'I') => Some("Importing statements"),
+            (Screen::Accounts, 's' | 'A' | 'C' | 'K' | 'J' | 'd') => Some("Changing accounts"),
+            (Screen::Categories, 'e' | 'R' | 'b' | 'E' | 'd') => Some("Changing categories"),
+            (Screen::ExchangeRates, 'a' | 'p' | 'd') => Some("Changing exchange rates"),
+            (Screen::RecurringTransactions, 'a' | 'p' | 't' | 'd') => Some("Changing recurring transactions"),
+            (Screen::Budgets, 'a' | 'd' | '\n') => Some("Changing budgets"),
+            (Screen::Goals, 'a' | 'd') => Some("Changing goals"),
+            (Screen::Investments, 'a' | 'p' | 'd') => Some("Changing investments"),
+            (Screen::Settings, ' ' | '\n') => Some("Changing settings"),
+            _ => None,
+        }
+    }
+
+    /// Pull the server's data into the mirror again; nothing to do locally.
+    /// Callers reload the screens afterwards.
+    async fn sync_remote(&mut self) {
+        let Some(remote) = &self.remote else { return };
+        self.last_remote_sync = Instant::now();
+        if let Err(e) = remote.sync(&self.pool).await {
+            self.status_message = self.tr_fmt("Error syncing with {}: {}", &[&remote.base_url(), &e]);
+        }
+    }
+
+    async fn handle_user_select_mode(&mut self, code: KeyCode) {
+        if self.mode == Mode::AddUser {
+            self.handle_add_user_mode(code).await;
+            return;
+        }
+        
+        if self.mode == Mode::DeleteConfirm {
+            self.handle_delete_user_mode(code).await;
+            return;
+        }
+
+        if self.mode == Mode::SelectProfile {
+            self.handle_profile_mode(code).await;
+            return;
+        }
+
+        if self.mode == Mode::EnterPassword {
+            self.handle_password_mode(code).await;
+            return;
+        }
+        
+        match code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('P') => self.open_profile_switcher(),
+            KeyCode::Char('L') => self.toggle_language().await,
+            KeyCode::Char('a' | 'd') if self.remote.is_some() => {
+                self.status_message = self.tr("Managing users isn't available in remote mode").to_string();
+            }
+            KeyCode::Char('a') => {
+                self.mode = Mode::AddUser;
+                self.clear_user_form();
+            }
+            KeyCode::Char('d') if !self.users.is_empty() => {
+                self.mode = Mode::DeleteConfirm;
+            }
+            KeyCode::Up => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                self.selected_index =
+                    (self.selected_index + 1).min(self.users.len().saturating_sub(1));
+            }
+            KeyCode::Enter if self.selected_index < self.users.len() && self.require_password => {
+                self.login_password.clear();
+                self.mode = Mode::EnterPassword;
+            }
+            KeyCode::Enter if self.selected_index < self.users.len() => {
+                self.log_in(self.selected_index).await;
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_password_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.login_password.clear();
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Char(c) => self.login_password.push(c),
+            KeyCode::Backspace => {
+                self.login_password.pop();
+            }
+            KeyCode::Enter => {
+                let Some(user) = self.users.get(self.selected_index) else {
+                    self.mode = Mode::Normal;
+                    return;
+                };
+                let verified = auth::verify_password(&self.login_password, &user.password_hash);
+                self.login_password.clear();
+                if verified {
+                    self.mode = Mode::Normal;
+                    self.log_in(self.selected_index).await;
+                } else {
+                    self.status_message = self.tr_fmt("Wrong password for {}", &[&user.username]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_password_prompt(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(5) / 2,
+            width: area.width / 2,
+            height: 5.min(area.height),
+        };
+
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+        let username = self
+            .users
+            .get(self.selected_index)
+            .map(|u| u.username.as_str())
+            .unwrap_or("");
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(self.tr("Password: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    "*".repeat(self.login_password.chars().count()),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                if self.status_message.starts_with(self.tr("Wrong password for {}").trim_end_matches("{}")) {
+                    self.status_message.clone()
+                } else {
+                    self.help("Enter: Login | Esc: Cancel")
+                },
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+
+        let prompt = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(self.tr_fmt("Log in as {}", &[&username])),
+        );
+        frame.render_widget(prompt, popup_area);
+    }
+
+    async fn log_in(&mut self, user_index: usize) {
+        self.current_user_id = Some(self.users[user_index].id);
+        self.selected_tab = 0;
+        self.selected_index = 0;
+        self.view_in_currency = None;
+        self.apply_user_settings().await;
+        self.update_screen();
+        self.load_data().await;
+        self.status_message = self.tr_fmt("Logged in as {}", &[&self.users[user_index].username]);
+
+        if self.accounts.is_empty() {
+            self.onboarding = Some(OnboardingStep::CreateAccount);
+            self.clear_account_form();
+            self.mode = Mode::AddAccount;
+            self.status_message = self.tr_fmt("Welcome, {}! Setup 2/4: create your first account", &[&self.users[user_index].username]);
+        } else {
+            self.open_weekly_digest().await;
+        }
+    }
+
+    /// Weekly digest popup, unless the user turned it off (weekly_digest setting)
+    async fn open_weekly_digest(&mut self) {
+        let Some(user_id) = self.current_user_id else { return };
+        let enabled = settings::user_settings(&self.pool, user_id)
+            .await
+            .map(|s| s.weekly_digest.unwrap_or(true))
+            .unwrap_or(false);
+        if !enabled {
+            return;
+        }
+        let threshold = digest::large_transaction_threshold();
+        if let Ok(weekly) = digest::weekly_digest(&self.pool, user_id, Utc::now(), threshold).await {
+            self.weekly_digest = Some(weekly);
+            self.mode = Mode::WeeklyDigest;
+        }
+    }
+
+    async fn handle_weekly_digest_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::Normal,
+            KeyCode::Char('n') => {
+                self.mode = Mode::Normal;
+                let Some(user_id) = self.current_user_id else { return };
+                self.status_message =
+                    match settings::set(&self.pool, user_id, settings::WEEKLY_DIGEST, Some("false")).await {
+                        Ok(()) => self.tr("Weekly digest turned off").to_string(),
+                        Err(e) => self.tr_fmt("Could not save setting: {}", &[&e]),
+                    };
+            }
+            _ => {}
+        }
+    }
+
+    fn render_weekly_digest(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(weekly) = &self.weekly_digest else { return };
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ))
+        };
+        let muted = |text: &str| Line::from(Span::styled(format!("  {}", text), Style::default().fg(Color::DarkGray)));
+
+        let change = weekly.spent_this_week - weekly.spent_last_week;
+        let (arrow, color) = if change > 0.0 { ("▲", Color::Red) } else { ("▼", Color::Green) };
+        let mut lines = vec![
+            heading(self.tr("Spending")),
+            Line::from(vec![
+                Span::styled(
+                    self.tr_fmt("  {} this week vs. {} last week  ", &[&format!("{:.2}", weekly.spent_this_week), &format!("{:.2}", weekly.spent_last_week)]),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(format!("{} {:.2}", arrow, change.abs()), Style::default().fg(color)),
+            ]),
+            Line::from(""),
+            heading(self.tr("Large transactions")),
+        ];
+        if weekly.large_transactions.is_empty() {
+            lines.push(muted(self.tr("None this week")));
+        }
+        for t in weekly.large_transactions.iter().take(5) {
+            lines.push(Line::from(format!(
+                "  {}  {:>10.2}  {}",
+                t.transaction_date.format("%b %d"),
+                t.amount.abs(),
+                t.description.as_deref().unwrap_or("-")
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading(self.tr("Budgets at risk")));
+        if weekly.budgets_at_risk.is_empty() {
+            lines.push(muted(self.tr("All budgets on track")));
+        }
+        for b in &weekly.budgets_at_risk {
+            let color = if b.status == "over" { Color::Red } else { Color::Yellow };
+            lines.push(Line::from(Span::styled(
+                self.tr_fmt("  {}: {} of {} ({})", &[&b.category_name, &format!("{:.2}", b.actual), &format!("{:.2}", b.budgeted), &b.status]),
+                Style::default().fg(color),
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading(self.tr("Upcoming bills (next 7 days)")));
+        if weekly.upcoming_bills.is_empty() {
+            lines.push(muted(self.tr("Nothing due")));
+        }
+        for bill in weekly.upcoming_bills.iter().take(5) {
+            lines.push(Line::from(format!(
+                "  {}  {:>10.2}  {}",
+                bill.due.format("%b %d"),
+                bill.amount,
+                bill.description.as_deref().unwrap_or("-")
+            )));
+        }
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 6,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: area.width * 2 / 3,
+            height,
+        };
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(self.tr("Your Week"))),
+            popup_area,
+        );
+    }
+
+    /// Status screen: the database, the latest background runs and alerts
+    async fn open_system_status(&mut self) {
+        match status::system_status(&self.pool).await {
+            Ok(system_status) => {
+                self.system_status = Some(system_status);
+                self.mode = Mode::SystemStatus;
+            }
+            Err(e) => self.status_message = self.tr_fmt("Error loading status: {}", &[&e]),
+        }
+    }
+
+    async fn handle_system_status_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => self.mode = Mode::Normal,
+            KeyCode::Char('r') => self.open_system_status().await,
+            _ => {}
+        }
+    }
+
+    fn render_system_status(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(system) = &self.system_status else { return };
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ))
+        };
+        let muted = |text: &str| Line::from(Span::styled(format!("  {}", text), Style::default().fg(Color::DarkGray)));
+        let now = Utc::now();
+        let ago = |at: DateTime<Utc>| {
+            let minutes = (now - at).num_minutes();
+            match minutes {
+                m if m < 1 => self.tr("just now").to_string(),
+                m if m < 60 => self.tr_fmt("{}m ago", &[&m]),
+                m if m < 48 * 60 => self.tr_fmt("{}h ago", &[&(m / 60)]),
+                m => self.tr_fmt("{}d ago", &[&(m / (24 * 60))]),
+            }
+        };
+
+        let size = match system.database_size {
+            Some(bytes) if bytes >= 1024 * 1024 => format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+            Some(bytes) => format!("{:.1} KB", bytes as f64 / 1024.0),
+            None => "-".to_string(),
+        };
+        let mut lines = vec![
+            heading(self.tr("Database")),
+            Line::from(format!("  {}  ({})", system.database_path.as_deref().unwrap_or(self.tr("in memory")), size)),
+            Line::from(""),
+            heading(self.tr("Rows")),
+        ];
+        // Three tables to a line
+        for row in system.tables.chunks(3) {
+            let cells: Vec<String> = row.iter().map(|t| format!("{:<24}{:>7}", t.table, t.rows)).collect();
+            lines.push(Line::from(format!("  {}", cells.join("   "))));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading(self.tr("Background runs")));
+        match &system.last_scrape {
+            Some(run) => {
+                let color = match run.status.as_str() {
+                    "success" => Color::Green,
+                    "partial" => Color::Yellow,
+                    _ => Color::Red,
+                };
+                lines.push(Line::from(vec![
+                    Span::raw(self.tr_fmt(
+                        "  Last FX scrape:       {} ({}), {} rates saved, ",
+                        &[&run.started_at.format("%Y-%m-%d %H:%M"), &ago(run.started_at), &run.rates_saved],
+                    )),
+                    Span::styled(run.status.clone(), Style::default().fg(color)),
+                ]));
+            }
+            None => lines.push(muted(self.tr("Last FX scrape:       never"))),
+        }
+        match system.last_recurring_run {
+            Some(at) => lines.push(Line::from(self.tr_fmt(
+                "  Last recurring post:  {} ({})",
+                &[&at.format("%Y-%m-%d %H:%M"), &ago(at)],
+            ))),
+            None => lines.push(muted(self.tr("Last recurring post:  never"))),
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading(self.tr("Alerts")));
+        let alert_color = if system.failed_deliveries > 0 { Color::Red } else { Color::White };
+        lines.push(Line::from(Span::styled(
+            self.tr_fmt(
+                "  {} webhook deliveries pending, {} failed",
+                &[&system.pending_deliveries, &system.failed_deliveries],
+            ),
+            Style::default().fg(alert_color),
+        )));
+
+        lines.push(Line::from(""));
+        lines.push(heading(self.tr("Server tasks")));
+        if system.tasks.is_empty() {
+            lines.push(muted(self.tr("No server has run against this database (cargo run serve)")));
+        }
+        for task in &system.tasks {
+            let health = status::task_health(task, now);
+            let color = match health {
+                "ok" => Color::Green,
+                "failing" => Color::Red,
+                _ => Color::Yellow,
+            };
+            let mut spans = vec![
+                Span::raw(format!("  {:<18}", task.task)),
+                Span::styled(format!("{:<9}", health), Style::default().fg(color)),
+                Span::raw(self.tr_fmt("last run {}", &[&ago(task.last_run_at)])),
+            ];
+            if let Some(error) = &task.last_error {
+                spans.push(Span::styled(format!("  {}", error), Style::default().fg(Color::Red)));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 12,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: area.width * 5 / 6,
+            height,
+        };
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(self.tr("System Status"))),
+            popup_area,
+        );
+    }
+
+    fn open_profile_switcher(&mut self) {
+        if self.profiles.is_empty() {
+            self.status_message = self.tr("No profiles configured (set PROFILES=name=url,...)").to_string();
+            return;
+        }
+        self.profile_index = self
+            .profile
+            .as_ref()
+            .and_then(|name| self.profiles.iter().position(|p| &p.name == name))
+            .unwrap_or(0);
+        self.mode = Mode::SelectProfile;
+    }
+
+    async fn handle_profile_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Up => self.profile_index = self.profile_index.saturating_sub(1),
+            KeyCode::Down => {
+                self.profile_index =
+                    (self.profile_index + 1).min(self.profiles.len().saturating_sub(1));
+            }
+            KeyCode::Enter => self.switch_profile(self.profile_index).await,
+            _ => {}
+        }
+    }
+
+    /// Reconnect to the profile's database and go back to user selection
+    async fn switch_profile(&mut self, index: usize) {
+        let profile = self.profiles[index].clone();
+        self.mode = Mode::Normal;
+        if self.profile.as_deref() == Some(profile.name.as_str()) {
+            return;
+        }
+
+        let connected = match profiles::connect(&profile.database_url).await {
+            Ok(pool) => profiles::migrate(&pool).await.map(|_| pool).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match connected {
+            Ok(pool) => {
+                self.clear_undo_stack();
+                self.pool = pool;
+                self.profile = Some(profile.name.clone());
+                self.current_user_id = None;
+                self.current_screen = Screen::UserSelect;
+                self.selected_index = 0;
+                self.users.clear();
+                self.load_users().await;
+                self.start_onboarding_if_empty();
+                self.status_message = self.tr_fmt("Switched to profile {}", &[&profile.name]);
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Cannot open profile {}: {}", &[&profile.name, &e]);
+            }
+        }
+    }
+
+    fn render_profile_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let height = (self.profiles.len() as u16 + 2).min(area.height);
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: area.width / 2,
+            height,
+        };
+
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+        let items: Vec<ListItem> = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let style = if i == self.profile_index {
+                    Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let active = if self.profile.as_deref() == Some(p.name.as_str()) {
+                    Span::styled(self.tr(" ✓ ACTIVE"), Style::default().fg(Color::Green))
+                } else {
+                    Span::raw("")
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<12}", p.name), Style::default().fg(Color::White)),
+                    Span::styled(format!(" {}", p.database_url), Style::default().fg(Color::Gray)),
+                    active,
+                ]))
+                .style(style)
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(self.tr("Switch Profile - Enter: Switch | Esc: Cancel")),
+        );
+        frame.render_widget(list, popup_area);
+    }
+
+    async fn handle_normal_mode(&mut self, code: KeyCode) {
+        self.status_message.clear();
+        self.export_message.clear();
+        if let Some(action) = self.remote.as_ref().and(self.remote_unsupported(code)) {
+            self.status_message = self.tr_fmt("{} isn't available in remote mode", &[&self.tr(action)]);
+            return;
+        }
+
+        match code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('u') => {
+                // Switch user
+                self.clear_undo_stack();
+                self.language = i18n::configured_language();
+                self.current_screen = Screen::UserSelect;
+                self.selected_index = 0;
+                self.current_user_id = None;
+                self.accounts.clear();
+                self.transactions.clear();
+                self.transaction_search.clear();
+                self.transaction_range = None;
+                self.categories.clear();
+                self.exchange_rates.clear();
+                self.recurring_transactions.clear();
+                self.category_spending.clear();
+                self.merchant_spending.clear();
+                self.spending_patterns = None;
+                self.budget_variance.clear();
+                self.bucket_split = None;
+                self.safe_to_spend = None;
+                self.activity.clear();
+            }
+            KeyCode::Char('r') => {
+                self.load_data().await;
+                self.status_message = self.tr("Data refreshed!").to_string();
+            }
+            KeyCode::Char('P') => self.open_profile_switcher(),
+            KeyCode::Char('U') => self.undo_last_deletion().await,
+            KeyCode::Char('L') => self.toggle_language().await,
+            KeyCode::Char('i') => self.open_system_status().await,
+            KeyCode::Char('H') => {
+                self.save_default_screen().await;
+            }
+            KeyCode::Char('m') if self.current_screen == Screen::Reports => {
+                self.report_months = match self.report_months {
+                    3 => 6,
+                    6 => 12,
+                    _ => 3,
+                };
+                if let Some(user_id) = self.current_user_id {
+                    self.load_report_charts(user_id).await;
+                }
+                self.status_message = if self.report_forecast {
+                    self.tr_fmt("Forecast of the next {} months", &[&self.report_months])
+                } else {
+                    self.tr_fmt("Charts show the last {} months", &[&self.report_months])
+                };
+            }
+            KeyCode::Char('F') if self.current_screen == Screen::Reports => {
+                self.report_forecast = !self.report_forecast;
+                if let Some(user_id) = self.current_user_id {
+                    self.load_forecast(user_id).await;
+                }
+                self.status_message = if self.report_forecast {
+                    self.tr_fmt("Forecast of the next {} months", &[&self.report_months])
+                } else {
+                    self.tr("Showing the report charts").to_string()
+                };
+            }
+            KeyCode::Char('S') if self.current_screen == Screen::Reports => {
+                self.report_rollup = !self.report_rollup;
+                if let Some(user_id) = self.current_user_id {
+                    self.load_report_charts(user_id).await;
+                }
+                self.status_message = if self.report_rollup {
+                    self.tr("Sub-category spending rolled up into parents").to_string()
+                } else {
+                    self.tr("Spending shown per sub-category").to_string()
+                };
+            }
+            KeyCode::Char('a') => {
+                if self.current_screen == Screen::Transactions {
+                    self.mode = Mode::AddTransaction;
+                    self.clear_transaction_form();
+                    if let Some(id) = self.user_settings.default_account_id {
+                        self.form_account_id = id.to_string();
+                    }
+                } else if self.current_screen == Screen::Accounts {
+                    self.mode = Mode::AddAccount;
+                    self.clear_account_form();
+                } else if self.current_screen == Screen::Categories {
+                    self.mode = Mode::AddCategory;
+                    self.clear_category_form();
+                } else if self.current_screen == Screen::ExchangeRates {
+                    self.mode = Mode::AddExchangeRate;
+                    self.clear_exchange_rate_form();
+                } else if self.current_screen == Screen::RecurringTransactions {
+                    self.open_recurring_wizard();
+                } else if self.current_screen == Screen::Budgets {
+                    self.open_budget_form(None);
+                } else if self.current_screen == Screen::Goals {
+                    self.open_goal_form();
+                } else if self.current_screen == Screen::Investments {
+                    self.open_holding_wizard();
+                }
+            }
+            KeyCode::Char('n') => {
+                // Quick-add an expense from any screen
+                if self.accounts.is_empty() {
+                    self.status_message = self.tr("Add an account before logging transactions").to_string();
+                } else {
+                    self.clear_transaction_form();
+                    self.mode = Mode::QuickAdd;
+                }
+            }
+            KeyCode::Char('c')
+                if self.current_screen == Screen::Transactions
+                    && self.selected_transaction().is_some() =>
+            {
+                self.open_category_picker().await;
+            }
+            KeyCode::Char('c') if self.current_screen == Screen::ExchangeRates => {
+                self.mode = Mode::ConvertCurrency;
+                self.clear_conversion_form();
+            }
+            KeyCode::Char('p')
+                if self.current_screen == Screen::ExchangeRates
+                    && self.selected_index < self.exchange_rates.len() =>
+            {
+                self.toggle_rate_pin().await;
+            }
+            KeyCode::Char('e')
+                if self.current_screen == Screen::Transactions
+                    && self.selected_transaction().is_some() =>
+            {
+                self.open_transaction_edit().await;
+            }
+            KeyCode::Char('e')
+                if self.current_screen == Screen::Categories
+                    && self.selected_index < self.categories.len() =>
+            {
+                let category = self.categories[self.selected_index].clone();
+                self.clear_category_form();
+                self.form_category_name = category.name.clone();
+                self.form_category_parent = category.parent_category_id.map(|p| p.to_string()).unwrap_or_default();
+                self.editing_category = Some(category);
+                self.mode = Mode::AddCategory;
+            }
+            KeyCode::Char('x')
+                if self.current_screen == Screen::Transactions
+                    && self.selected_transaction().is_some() =>
+            {
+                self.cycle_transaction_status(false).await;
+            }
+            KeyCode::Char('X')
+                if self.current_screen == Screen::Transactions
+                    && self.selected_transaction().is_some() =>
+            {
+                self.cycle_transaction_status(true).await;
+            }
+            KeyCode::Char('R') if self.current_screen == Screen::Transactions => {
+                self.clear_transaction_form();
+                self.form_receipt_path.clear();
+                self.mode = Mode::ReceiptPath;
+            }
+            KeyCode::Char('R') if self.current_screen == Screen::Categories => {
+                self.apply_category_rules().await;
+            }
+            KeyCode::Char('b')
+                if self.current_screen == Screen::Categories
+                    && self.selected_index < self.categories.len() =>
+            {
+                self.cycle_category_bucket().await;
+            }
+            KeyCode::Char('E')
+                if self.current_screen == Screen::Categories
+                    && self.selected_index < self.categories.len() =>
+            {
+                self.toggle_category_excluded().await;
+            }
+            KeyCode::Char('d') => {
+                let has_selection = match self.current_screen {
+                    Screen::Transactions => !self.transactions.is_empty(),
+                    Screen::Accounts => !self.accounts.is_empty(),
+                    Screen::Categories => !self.categories.is_empty(),
+                    Screen::ExchangeRates => !self.exchange_rates.is_empty(),
+                    Screen::RecurringTransactions => !self.recurring_transactions.is_empty(),
+                    Screen::Budgets => !self.budget_variance.is_empty(),
+                    Screen::Goals => !self.goals.is_empty(),
+                    Screen::Investments => self.selected_holding().is_some(),
+                    _ => false,
+                };
+                if has_selection {
+                    self.mode = Mode::DeleteConfirm;
+                }
+            }
+            KeyCode::Char('e') if self.current_screen == Screen::Export => {
+                self.mode = Mode::ExportData;
+            }
+            KeyCode::Char('f') if self.current_screen == Screen::Transactions => {
+                // Filter by currency on Transactions screen
+                self.mode = Mode::SelectCurrencyFilter;
+            }
+            KeyCode::Char('b') if self.current_screen == Screen::Dashboard => {
+                // Base currency for the totals, in the currency dialog
+                self.mode = Mode::SelectViewCurrency;
+            }
+            KeyCode::Char('v') if self.current_screen == Screen::Transactions => {
+                // View in currency on Transactions screen
+                self.mode = Mode::SelectViewCurrency;
+            }
+            KeyCode::Char('D') if self.current_screen == Screen::Transactions => {
+                // Pre-fill the custom fields with the range being shown
+                let (start, end) = match self.transaction_range {
+                    Some((start, end)) => (
+                        start.format("%Y-%m-%d").to_string(),
+                        (end - chrono::Duration::days(1)).format("%Y-%m-%d").to_string(),
+                    ),
+                    None => (String::new(), String::new()),
+                };
+                self.form_range_start = start;
+                self.form_range_end = end;
+                self.form_field_index = 0;
+                self.mode = Mode::SelectDateRange;
+            }
+            KeyCode::Char('I') if self.current_screen == Screen::Transactions => {
+                if self.accounts.is_empty() {
+                    self.status_message = self.tr("Add an account before importing statements").to_string();
+                } else {
+                    self.form_import_path.clear();
+                    self.form_field_index = 0;
+                    // Start on the first saved profile, so a monthly re-import is I, path, Enter
+                    self.form_import_profile = None;
+                    self.form_import_account = 0;
+                    if !self.import_profiles.is_empty() {
+                        self.select_import_profile(Some(0));
+                    }
+                    self.mode = Mode::ImportCsv;
+                }
+            }
+            KeyCode::Char('/') if self.current_screen == Screen::Transactions => {
+                self.mode = Mode::SearchTransactions;
+            }
+            KeyCode::Esc
+                if self.current_screen == Screen::Transactions && !self.transaction_search.is_empty() =>
+            {
+                self.transaction_search.clear();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('p') if self.current_screen == Screen::RecurringTransactions => {
+                // Process recurring transactions
+                self.process_recurring_transactions().await;
+            }
+            KeyCode::Char('p') if self.current_screen == Screen::Investments => {
+                self.update_prices().await;
+            }
+            KeyCode::Char('t')
+                if self.current_screen == Screen::RecurringTransactions
+                    && self.selected_index < self.recurring_transactions.len() =>
+            {
+                // Toggle active status for recurring transactions
+                self.toggle_recurring_active().await;
+            }
+            KeyCode::Char('t') if self.current_screen == Screen::Accounts => {
+                self.open_transfer_form();
+            }
+            KeyCode::Char('s')
+                if self.current_screen == Screen::Accounts
+                    && self.selected_index < self.accounts.len() =>
+            {
+                // Star/unstar the selected account
+                self.toggle_account_favorite().await;
+            }
+            KeyCode::Char('o') if self.current_screen == Screen::Accounts => {
+                self.accounts_grouped = !self.accounts_grouped;
+                self.selected_index = 0;
+                self.load_data().await;
+            }
+            KeyCode::Char('A')
+                if self.current_screen == Screen::Accounts
+                    && self.selected_index < self.accounts.len() =>
+            {
+                let account = &self.accounts[self.selected_index];
+                self.form_account_group = account
+                    .account_group_id
+                    .and_then(|id| self.account_groups.iter().find(|g| g.id == id))
+                    .map(|g| g.name.clone())
+                    .unwrap_or_default();
+                self.mode = Mode::SetAccountGroup;
+            }
+            KeyCode::Char('C')
+                if self.current_screen == Screen::Accounts
+                    && self.selected_index < self.accounts.len() =>
+            {
+                let account_id = self.accounts[self.selected_index].id;
+                match accounts::transaction_count(&self.pool, account_id).await {
+                    Ok(count) => {
+                        self.form_account_transactions = count;
+                        self.form_new_currency.clear();
+                        self.form_currency_rate.clear();
+                        self.form_field_index = 0;
+                        self.mode = Mode::ChangeAccountCurrency;
+                    }
+                    Err(e) => self.status_message = self.tr_fmt("Error loading transactions: {}", &[&e]),
+                }
+            }
+            KeyCode::Char('K') | KeyCode::Char('J')
+                if self.current_screen == Screen::Accounts && self.accounts_grouped =>
+            {
+                self.status_message = self.tr("Press o to leave the grouped view before reordering").to_string();
+            }
+            KeyCode::Char('K')
+                if self.current_screen == Screen::Accounts && self.selected_index > 0 =>
+            {
+                self.move_account(self.selected_index - 1).await;
+            }
+            KeyCode::Char('J')
+                if self.current_screen == Screen::Accounts
+                    && self.selected_index + 1 < self.accounts.len() =>
+            {
+                self.move_account(self.selected_index + 1).await;
+            }
+            KeyCode::Enter
+                if self.current_screen == Screen::Budgets
+                    && self.selected_index < self.budget_variance.len() =>
+            {
+                self.open_budget_form(Some(self.selected_index));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') if self.current_screen == Screen::Settings => {
+                self.change_setting().await;
+            }
+            KeyCode::Enter => {
+                self.mode = Mode::ViewDetails;
+            }
+            KeyCode::Up => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                let max = self.get_current_list_len().saturating_sub(1);
+                self.selected_index = (self.selected_index + 1).min(max);
+            }
+            KeyCode::PageUp | KeyCode::Char('[') => {
+                // Jump up 10 items (PageUp or '[' for Mac compatibility)
+                self.selected_index = self.selected_index.saturating_sub(10);
+            }
+            KeyCode::PageDown | KeyCode::Char(']') => {
+                // Jump down 10 items (PageDown or ']' for Mac compatibility)
+                let max = self.get_current_list_len().saturating_sub(1);
+                self.selected_index = (self.selected_index + 10).min(max);
+            }
+            KeyCode::Home | KeyCode::Char('g') => {
+                // Jump to first item (Home or 'g' for Mac compatibility)
+                self.selected_index = 0;
+            }
+            KeyCode::End | KeyCode::Char('G') => {
+                // Jump to last item (End or 'G' for Mac compatibility)
+                let max = self.get_current_list_len().saturating_sub(1);
+                self.selected_index = max;
+            }
+            KeyCode::Left => {
+                self.selected_tab = self.selected_tab.saturating_sub(1);
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Right => {
+                self.selected_tab = (self.selected_tab + 1).min(11);
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('1') => {
+                self.selected_tab = 0;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('2') => {
+                self.selected_tab = 1;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('3') => {
+                self.selected_tab = 2;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('4') => {
+                self.selected_tab = 3;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('5') => {
+                self.selected_tab = 4;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('6') => {
+                self.selected_tab = 5;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('7') => {
+                self.selected_tab = 6;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('8') => {
+                self.selected_tab = 7;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('9') => {
+                self.selected_tab = 8;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('0') => {
+                self.selected_tab = 9;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('-') => {
+                self.selected_tab = 10;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            KeyCode::Char('=') => {
+                self.selected_tab = 11;
+                self.update_screen();
+                self.selected_index = 0;
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_add_transaction_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Tab => {
+                self.form_field_index = (self.form_field_index + 1) % 7;
+            }
+            KeyCode::BackTab => {
+                self.form_field_index = if self.form_field_index == 0 {
+                    6
+                } else {
+                    self.form_field_index - 1
+                };
+            }
+            KeyCode::Char(c) => match self.form_field_index {
+                0 => self.form_account_id.push(c),
+                1 => self.form_amount.push(c),
+                2 => self.form_type.push(c),
+                3 => self.form_description.push(c),
+                4 => self.form_category_id.push(c),
+                5 => self.form_fee.push(c),
+                6 => self.form_fee_category_id.push(c),
+                _ => {}
+            },
+            KeyCode::Backspace => match self.form_field_index {
+                0 => {
+                    self.form_account_id.pop();
+                }
+                1 => {
+                    self.form_amount.pop();
+                }
+                2 => {
+                    self.form_type.pop();
+                }
+                3 => {
+                    self.form_description.pop();
+                }
+                4 => {
+                    self.form_category_id.pop();
+                }
+                5 => {
+                    self.form_fee.pop();
+                }
+                6 => {
+                    self.form_fee_category_id.pop();
+                }
+                _ => {}
+            },
+            KeyCode::F(n)
+                if self.mode == Mode::AddTransaction
+                    && n >= 1
+                    && (n as usize) <= self.templates.len().min(9) =>
+            {
+                self.apply_template(n as usize - 1);
+            }
+            KeyCode::Enter if self.mode == Mode::EditTransaction => {
+                self.submit_transaction_edit().await;
+            }
+            KeyCode::Enter => {
+                self.submit_transaction().await;
+            }
+            _ => {}
+        }
+    }
+    async fn handle_quick_add_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.form_field_index = (self.form_field_index + 1) % 3;
+            }
+            KeyCode::Char(c) => match self.form_field_index {
+                0 => self.form_amount.push(c),
+                1 => self.form_description.push(c),
+                2 => self.form_category_id.push(c),
+                _ => {}
+            },
+            KeyCode::Backspace => match self.form_field_index {
+                0 => {
+                    self.form_amount.pop();
+                }
+                1 => {
+                    self.form_description.pop();
+                }
+                2 => {
+                    self.form_category_id.pop();
+                }
+                _ => {}
+            },
+            KeyCode::Enter => {
+                self.submit_quick_add().await;
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_add_exchange_rate_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Tab => {
+                self.form_field_index = (self.form_field_index + 1) % 4;
+            }
+            KeyCode::BackTab => {
+                self.form_field_index = if self.form_field_index == 0 {
+                    3
+                } else {
+                    self.form_field_index - 1
+                };
+            }
+            KeyCode::Char(c) => match self.form_field_index {
+                0 => self.form_from_currency.push(c),
+                1 => self.form_to_currency.push(c),
+                2 => self.form_rate.push(c),
+                3 => self.form_source.push(c),
+                _ => {}
+            },
+            KeyCode::Backspace => match self.form_field_index {
+                0 => {
+                    self.form_from_currency.pop();
+                }
+                1 => {
+                    self.form_to_currency.pop();
+                }
+                2 => {
+                    self.form_rate.pop();
+                }
+                3 => {
+                    self.form_source.pop();
+                }
+                _ => {}
+            },
+            KeyCode::Enter => {
+                self.submit_exchange_rate().await;
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_convert_currency_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Tab => {
+                self.form_field_index = (self.form_field_index + 1) % 3;
+            }
+            KeyCode::BackTab => {
+                self.form_field_index = if self.form_field_index == 0 {
+                    2
+                } else {
+                    self.form_field_index - 1
+                };
+            }
+            KeyCode::Char(c) => match self.form_field_index {
+                0 => self.form_convert_from.push(c),
+                1 => self.form_convert_to.push(c),
+                2 => self.form_convert_amount.push(c),
+                _ => {}
+            },
+            KeyCode::Backspace => match self.form_field_index {
+                0 => {
+                    self.form_convert_from.pop();
+                }
+                1 => {
+                    self.form_convert_to.pop();
+                }
+                2 => {
+                    self.form_convert_amount.pop();
+                }
+                _ => {}
+            },
+            KeyCode::Enter => {
+                self.perform_currency_conversion().await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Validated contents of the add/edit transaction form. An empty
+    /// category falls back to the account's default category, if any.
+    fn parse_transaction_form(&self) -> Result<TransactionForm, String> {
+        let account_id = self.form_account_id.parse::<i64>();
+        let amount = self.form_amount.parse::<f64>();
+
+        let default_category_id = account_id
+            .as_ref()
+            .ok()
+            .and_then(|id| self.accounts.iter().find(|a| a.id == *id))
+            .and_then(|a| a.default_category_id);
+        let (Ok(account_id), Ok(amount)) = (account_id, amount) else {
+            return Err(self.tr("Error: Invalid input! Check account ID, amount, and category ID.").to_string());
+        };
+        let categories = if self.form_category_id.trim().is_empty() {
+            default_category_id
+                .map(|category_id| vec![CategoryAmount { category_id, amount: amount.abs() }])
+                .unwrap_or_default()
+        } else {
+            let categories = parse_category_splits(&self.form_category_id, amount)
+                .ok_or(self.tr("Error: Invalid input! Check account ID, amount, and category ID."))?;
+            UpdateTransactionCategories { categories: categories.clone() }
+                .validate(amount)
+                .map_err(|e| self.tr_fmt("Error: {}", &[&e]))?;
+            categories
+        };
+
+        // Only the user's own accounts and categories, as the API checks
+        match self.accounts.iter().find(|a| a.id == account_id) {
+            None => return Err(self.tr_fmt("Error: Account {} not found", &[&account_id])),
+            Some(account) if account.is_archived => return Err(self.tr_fmt("Error: Account {} is closed", &[&account_id])),
+            Some(_) => {}
+        }
+        let fee_category_id = self.form_fee_category_id.parse::<i64>().ok();
+        let mut category_ids = categories.iter().map(|c| c.category_id).chain(fee_category_id);
+        if let Some(category_id) = category_ids.find(|id| !self.categories.iter().any(|c| c.id == *id)) {
+            return Err(self.tr_fmt("Error: Category {} not found", &[&category_id]));
+        }
+
+        // Normalize transaction type: accept i/e/t shortcuts
+        let transaction_type = match self.form_type.to_lowercase().as_str() {
+            "i" | "income" => "income",
+            "e" | "expense" | "" => "expense",  // default to expense
+            "t" | "transfer" => "transfer",
+            _ => return Err(self.tr("Error: Type must be 'income' (i), 'expense' (e) or 'transfer' (t)").to_string()),
+        };
+
+        // Optional fee, debited on top of the amount
+        let fee = if self.form_fee.is_empty() {
+            0.0
+        } else {
+            match self.form_fee.parse::<f64>() {
+                Ok(fee) if fee >= 0.0 => fee,
+                _ => return Err(self.tr("Error: Fee must be a non-negative number").to_string()),
+            }
+        };
+
+        Ok(TransactionForm {
+            account_id,
+            amount,
+            transaction_type,
+            description: Some(self.form_description.clone()).filter(|d| !d.is_empty()),
+            categories,
+            fee,
+            fee_category_id,
+        })
+    }
+
+    async fn submit_transaction(&mut self) {
+        let form = self.parse_transaction_form().and_then(|form| match form.categories.is_empty() {
+            false => Ok(form),
+            true => Err(self.tr("Error: Invalid input! Check account ID, amount, and category ID.").to_string()),
+        });
+        let form = match form {
+            Ok(form) => form,
+            Err(e) => {
+                self.status_message = e;
+                self.mode = Mode::Normal;
+                return;
+            }
+        };
+
+        let date = self.form_date.unwrap_or_else(|| chrono::Local::now().with_timezone(&Utc));
+        let result = match &self.remote {
+            Some(remote) => {
+                let data = CreateTransaction {
+                    account_id: form.account_id,
+                    amount: form.amount,
+                    transaction_type: form.transaction_type.to_string(),
+                    description: form.description.clone(),
+                    transaction_date: Some(date),
+                    categories: form.categories.clone(),
+                    notes: None,
+                    reference: None,
+                    merchant: self.form_merchant.clone(),
+                    merchant_city: None,
+                    merchant_country: None,
+                    original_amount: None,
+                    original_currency: None,
+                    exchange_rate: None,
+                    fee: Some(form.fee),
+                    fee_category_id: form.fee_category_id,
+                    status: None,
+                };
+                remote.create_transaction(&data).await
+            }
+            None => self.insert_transaction(&form, date).await.map_err(|e| e.to_string()),
+        };
+
+        match result {
+            Ok(transaction_id) => {
+                self.status_message =
+                    self.tr_fmt("Transaction added successfully! ID: {}", &[&transaction_id]);
+                self.last_used_account_id = Some(form.account_id);
+                self.sync_remote().await;
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error adding transaction: {}", &[&e]);
+            }
+        }
+
+        self.mode = Mode::Normal;
+    }
+
+    /// The row, its splits and the balance change, written together or not at all
+    async fn insert_transaction(&self, form: &TransactionForm, date: DateTime<Utc>) -> Result<i64, sqlx::Error> {
+        let TransactionForm {
+            account_id,
+            amount,
+            transaction_type: txn_type,
+            ref description,
+            ref categories,
+            fee,
+            fee_category_id,
+        } = *form;
+        let (base_amount, base_currency) = fx::base_amount_snapshot(&self.pool, account_id, amount, date)
+            .await
+            .unwrap_or(None)
+            .unzip();
+
+        let mut tx = self.pool.begin().await?;
+        let transaction_id = sqlx::query(
+            "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date, fee, fee_category_id, merchant, base_amount, base_currency) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(account_id)
+        .bind(amount)
+        .bind(txn_type)
+        .bind(description)
+        .bind(date)
+        .bind(fee)
+        .bind(fee_category_id)
+        .bind(&self.form_merchant)
+        .bind(base_amount)
+        .bind(base_currency)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        for split in categories {
+            sqlx::query(
+                "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)"
+            )
+            .bind(transaction_id)
+            .bind(split.category_id)
+            .bind(split.amount)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let balance_change = balance_effect(txn_type, amount, fee);
+
+        sqlx::query(
+            "UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?",
+        )
+        .bind(balance_change)
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(transaction_id)
+    }
+
+    /// Toggle the selected transaction between pending and cleared, or void it
+    /// (voiding a void transaction reopens it as pending)
+    async fn cycle_transaction_status(&mut self, void: bool) {
+        let Some(transaction) = self.selected_transaction().cloned() else {
+            return;
+        };
+        let status = match (void, transaction.status.as_str()) {
+            (true, "void") | (false, "cleared") => "pending",
+            (true, _) => "void",
+            (false, _) => "cleared",
+        };
+
+        let result = match &self.remote {
+            Some(remote) => remote.set_transaction_status(transaction.id, status).await,
+            None => transaction_status::set_status(&self.pool, &transaction, status)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(()) => {
+                self.status_message = self.tr_fmt("Transaction {} marked {}", &[&transaction.id, &status]);
+                self.sync_remote().await;
+                self.load_data().await;
+            }
+            Err(e) => self.status_message = self.tr_fmt("Error updating transaction status: {}", &[&e]),
+        }
+    }
+
+    /// Open the transaction form on the selected transaction, with its
+    /// category or its splits filled in.
+    async fn open_transaction_edit(&mut self) {
+        let Some(transaction) = self.selected_transaction().cloned() else {
+            return;
+        };
+        if transaction.transfer_group_id.is_some() || transaction.linked_transaction_id.is_some() {
+            self.status_message =
+                self.tr("Transfers can't be edited - delete both legs and transfer again").to_string();
+            return;
+        }
+
+        let splits: Vec<(i64, f64)> =
+            sqlx::query_as("SELECT category_id, amount FROM transaction_categories WHERE transaction_id = ? ORDER BY amount DESC")
+                .bind(transaction.id)
+                .fetch_all(&self.pool)
+                .await
+                .unwrap_or_default();
+
+        self.clear_transaction_form();
+        self.form_account_id = transaction.account_id.to_string();
+        self.form_amount = format!("{:.2}", transaction.amount);
+        self.form_type = transaction.transaction_type.clone();
+        self.form_description = transaction.description.clone().unwrap_or_default();
+        self.form_category_id = match splits[..] {
+            [(category_id, _)] => category_id.to_string(),
+            _ => splits
+                .iter()
+                .map(|(category_id, amount)| format!("{}:{:.2}", category_id, amount))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        if transaction.fee > 0.0 {
+            self.form_fee = format!("{:.2}", transaction.fee);
+        }
+        self.form_fee_category_id = transaction
+            .fee_category_id
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+        self.form_merchant = transaction.merchant.clone();
+        self.form_field_index = 1;
+        self.editing_transaction = Some(transaction);
+        self.mode = Mode::EditTransaction;
+    }
+
+    /// Save the form over the transaction being edited. The old booking is
+    /// taken off its account's balance and the new one applied, so the
+    /// balance moves by the difference, or between accounts.
+    async fn submit_transaction_edit(&mut self) {
+        let Some(original) = self.editing_transaction.take() else {
+            self.mode = Mode::Normal;
+            return;
+        };
+        let form = match self.parse_transaction_form() {
+            // Without a category the splits stay, and they must still add up
+            Ok(form) if form.categories.is_empty() && form.amount != original.amount => Err(
+                self.tr("Error: Give a category - a split transaction's amount can't change").to_string(),
+            ),
+            form => form,
+        };
+        let form = match form {
+            Ok(form) => form,
+            Err(e) => {
+                self.status_message = e;
+                self.mode = Mode::Normal;
+                return;
+            }
+        };
+
+        let result = match &self.remote {
+            Some(_) if form.account_id != original.account_id => {
+                Err(self.tr("moving a transaction to another account isn't available in remote mode").to_string())
+            }
+            Some(remote) => {
+                let data = UpdateTransaction {
+                    amount: Some(form.amount),
+                    transaction_type: Some(form.transaction_type.to_string()),
+                    description: Some(form.description.clone().unwrap_or_default()),
+                    transaction_date: None,
+                    notes: None,
+                    reference: None,
+                    merchant: Some(self.form_merchant.clone().unwrap_or_default()),
+                    merchant_city: None,
+                    merchant_country: None,
+                    original_amount: None,
+                    original_currency: None,
+                    exchange_rate: None,
+                    fee: Some(form.fee),
+                    fee_category_id: Some(form.fee_category_id.unwrap_or(0)),
+                    exclude_from_reports: None,
+                    categories: (!form.categories.is_empty()).then(|| form.categories.clone()),
+                };
+                remote.update_transaction(original.id, &data).await
+            }
+            None => self.save_transaction_edit(&original, &form).await.map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(()) => {
+                self.status_message =
+                    self.tr_fmt("Transaction {} updated, balance adjusted!", &[&original.id]);
+                self.sync_remote().await;
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error updating transaction: {}", &[&e]);
+            }
+        }
+        self.mode = Mode::Normal;
+    }
+
+    async fn save_transaction_edit(
+        &self,
+        original: &Transaction,
+        form: &TransactionForm,
+    ) -> Result<(), sqlx::Error> {
+        let (base_amount, base_currency) = fx::base_amount_snapshot(
+            &self.pool,
+            form.account_id,
+            form.amount,
+            original.transaction_date,
+        )
+        .await?
+        .unzip();
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE transactions SET account_id = ?, amount = ?, transaction_type = ?, description = ?, fee = ?, fee_category_id = ?, merchant = ?, base_amount = ?, base_currency = ?, updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(form.account_id)
+        .bind(form.amount)
+        .bind(form.transaction_type)
+        .bind(&form.description)
+        .bind(form.fee)
+        .bind(form.fee_category_id)
+        .bind(&self.form_merchant)
+        .bind(base_amount)
+        .bind(base_currency)
+        .bind(original.id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Pending and void transactions were never posted, so there is nothing to move
+        if original.status == "cleared" {
+            let reverted = -balance_effect(&original.transaction_type, original.amount, original.fee);
+            let applied = balance_effect(form.transaction_type, form.amount, form.fee);
+            for (account_id, balance_change) in [(original.account_id, reverted), (form.account_id, applied)] {
+                sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+                    .bind(balance_change)
+                    .bind(account_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        if !form.categories.is_empty() {
+            sqlx::query("DELETE FROM transaction_categories WHERE transaction_id = ?")
+                .bind(original.id)
+                .execute(&mut *tx)
+                .await?;
+            for split in &form.categories {
+                sqlx::query(
+                    "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)",
+                )
+                .bind(original.id)
+                .bind(split.category_id)
+                .bind(split.amount)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await
+    }
+
+    /// Quick-add goes through the regular add-transaction path with the
+    /// guessed account and category filled in.
+    async fn submit_quick_add(&mut self) {
+        let Some(account_id) = self.quick_add_account().map(|a| a.id) else {
+            self.mode = Mode::Normal;
+            return;
+        };
+
+        if self.form_category_id.is_empty() {
+            match self.guess_category(&self.form_description) {
+                Some((category_id, _)) => self.form_category_id = category_id.to_string(),
+                None => {
+                    self.status_message =
+                        self.tr("Error: No category guess - type a category ID (Tab)").to_string();
+                    return;
+                }
+            }
+        }
+
+        self.form_account_id = account_id.to_string();
+        self.form_type = String::from("expense");
+        self.submit_transaction().await;
+    }
+
+    /// Account used by quick-add: the last one used, else the most recently
+    /// used one in the loaded transactions, else the first (favorite) account.
+    fn quick_add_account(&self) -> Option<&Account> {
+        let recent = self
+            .transactions
+            .iter()
+            .max_by_key(|t| (t.created_at, t.id))
+            .map(|t| t.account_id);
+
+        self.last_used_account_id
+            .or(self.user_settings.default_account_id)
+            .or(recent)
+            .and_then(|id| self.accounts.iter().find(|a| a.id == id))
+            .or_else(|| self.accounts.first())
+    }
+
+    /// Best category guess for a description, with where it came from:
+    /// a matching template, the category last used for the same description
+    /// or merchant, or the account's default category.
+    fn guess_category(&self, description: &str) -> Option<(i64, &'static str)> {
+        let needle = description.trim().to_lowercase();
+
+        if !needle.is_empty() {
+            let template = self.templates.iter().find(|t| {
+                t.category_id.is_some()
+                    && (t.name.to_lowercase() == needle
+                        || t.description.as_deref().map(str::to_lowercase).as_deref() == Some(needle.as_str())
+                        || t.merchant.as_deref().map(str::to_lowercase).as_deref() == Some(needle.as_str()))
+            });
+            if let Some(category_id) = template.and_then(|t| t.category_id) {
+                return Some((category_id, "template"));
+            }
+
+            if let Some(category_id) = self.category_hints.get(&needle) {
+                return Some((*category_id, "history"));
+            }
+        }
+
+        self.quick_add_account()
+            .and_then(|a| a.default_category_id)
+            .map(|id| (id, self.tr("account default")))
+    }
+
+    fn render_quick_add_popup(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(11) / 2,
+            width: area.width / 2,
+            height: 11.min(area.height),
+        };
+
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+        let field_style = |index: usize| {
+            if self.form_field_index == index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+
+        let account = self.quick_add_account();
+        let account_text = account
+            .map(|a| format!("{} [{}]", a.name, a.currency))
+            .unwrap_or_else(|| self.tr("No account").to_string());
+
+        let category_text = if !self.form_category_id.is_empty() {
+            String::from(self.tr(" (manual)"))
+        } else {
+            match self.guess_category(&self.form_description) {
+                Some((id, source)) => {
+                    let name = self
+                        .categories
+                        .iter()
+                        .find(|c| c.id == id)
+                        .map(|c| c.name.as_str())
+                        .unwrap_or(self.tr("Unknown"));
+                    format!("{} ({})", name, source)
+                }
+                None => String::from(self.tr("none - type an ID")),
+            }
+        };
+
+        let text = vec![
+            Line::from(vec![
+                Span::styled(self.tr("Account: "), Style::default().fg(Color::Gray)),
+                Span::styled(account_text, Style::default().fg(Color::Cyan)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Amount: "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_amount, field_style(0)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Description: "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_description, field_style(1)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Category: "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_category_id, field_style(2)),
+                Span::styled(category_text, Style::default().fg(Color::Green)),
+            ]),
+            Line::from(""),
+            Line::from(vec![Span::styled(
+                self.help("Tab: Next | Enter: Add expense | Esc: Cancel"),
+                Style::default().fg(Color::DarkGray),
+            )]),
+        ];
+
+        let popup = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(self.tr("Quick Add Expense"))
+                .style(Style::default().fg(Color::White)),
+        );
+        frame.render_widget(popup, popup_area);
+    }
+
+    async fn submit_exchange_rate(&mut self) {
+        let rate = self.form_rate.parse::<f64>();
+
+        if self.form_from_currency.is_empty() || self.form_to_currency.is_empty() || rate.is_err() {
+            self.status_message = self.tr("Error: Invalid input! Check currencies and rate.").to_string();
+            self.mode = Mode::Normal;
+            return;
+        }
+
+        let rate = rate.unwrap();
+        let from_currency = &self.form_from_currency;
+        let to_currency = &self.form_to_currency;
+        let source = &self.form_source;
+        let rate_source_id = match rate_sources::resolve(&self.pool, Some(source), None).await {
+            Ok(Ok((_, rate_source_id))) => rate_source_id,
+            _ => None,
+        };
+
+        let result = sqlx::query(
+            "INSERT INTO exchange_rates (from_currency, to_currency, rate, rate_date, source, rate_source_id) VALUES (?, ?, ?, datetime('now'), ?, ?)"
+        )
+        .bind(from_currency)
+        .bind(to_currency)
+        .bind(rate)
+        .bind(source)
+        .bind(rate_source_id)
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(res) => {
+                let rate_id = res.last_insert_rowid();
+                self.status_message = self.tr_fmt("Exchange rate added successfully! ID: {}", &[&rate_id]);
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error adding exchange rate: {}", &[&e]);
+            }
+        }
+
+        self.mode = Mode::Normal;
+    }
+
+    async fn perform_currency_conversion(&mut self) {
+        let amount = self.form_convert_amount.parse::<f64>();
+
+        if self.form_convert_from.is_empty() || self.form_convert_to.is_empty() || amount.is_err() {
+            self.form_converted_result = self.tr("Error: Invalid input!").to_string();
+            return;
+        }
+
+        let amount = amount.unwrap();
+        let from = &self.form_convert_from;
+        let to = &self.form_convert_to;
+
+        // Query the latest exchange rate
+        let rate: Result<Option<f64>, _> = sqlx::query_scalar(
+            "SELECT rate FROM exchange_rates 
+             WHERE from_currency = ? AND to_currency LIKE ?
+             ORDER BY rate_date DESC 
+             LIMIT 1",
+        )
+        .bind(from)
+        .bind(format!("%({})%", to))
+        .fetch_optional(&self.pool)
+        .await;
+
+        match rate {
+            Ok(Some(rate)) => {
+                let converted = amount * rate;
+                self.form_converted_result = self.tr_fmt(
+                    "{} {} = {} {} (rate: {})",
+                    &[&format!("{:.2}", amount), &from, &format!("{:.2}", converted), &to, &format!("{:.6}", rate)],
+                );
+                self.status_message = self.tr("Conversion successful!").to_string();
+            }
+            Ok(None) => {
+                self.form_converted_result = self.tr_fmt("No rate found from {} to {}", &[&from, &to]);
+            }
+            Err(e) => {
+                self.form_converted_result = self.tr_fmt("Database error: {}", &[&e]);
+            }
+        }
+    }
+
+    /// Remember a deletion so 'U' can restore it
+    fn push_undo(&mut self, deletion: undo::Deletion) {
+        self.undo_stack.push(deletion);
+        if self.undo_stack.len() > undo::LIMIT {
+            undo::discard(self.undo_stack.remove(0));
+        }
+    }
+
+    fn clear_undo_stack(&mut self) {
+        for deletion in self.undo_stack.drain(..) {
+            undo::discard(deletion);
+        }
+    }
+
+    /// Restore the most recent deletion of this session
+    async fn undo_last_deletion(&mut self) {
+        let Some(deletion) = self.undo_stack.pop() else {
+            self.status_message = self.tr("Nothing to undo").to_string();
+            return;
+        };
+        match undo::restore(&self.pool, &deletion).await {
+            Ok(()) => {
+                self.status_message = self.tr_fmt("Restored {} ({} rows)", &[&deletion.label, &deletion.row_count()]);
+                self.load_data().await;
+            }
+            Err(e) => {
+                // Its ids were taken since, so it can't come back later either
+                self.status_message = self.tr_fmt("Cannot restore {}: {}", &[&deletion.label, &e]);
+                undo::discard(deletion);
+            }
+        }
+    }
+
+    async fn handle_delete_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                if let Some(transaction) = self
+                    .selected_transaction()
+                    .filter(|_| self.current_screen == Screen::Transactions)
+                    .cloned()
+                {
+                    let transaction_id = transaction.id;
+                    // A transfer is listed as one entry, so both of its legs go
+                    let legs: Vec<Transaction> = match transaction.transfer_group_id {
+                        Some(group_id) => self
+                            .transactions
+                            .iter()
+                            .filter(|t| t.transfer_group_id == Some(group_id))
+                            .cloned()
+                            .collect(),
+                        None => vec![transaction],
+                    };
+                    let label = if legs.len() > 1 {
+                        self.tr("transfer").to_string()
+                    } else {
+                        self.tr_fmt("transaction {}", &[&transaction_id])
+                    };
+                    // The server keeps no undo history
+                    if let Some(remote) = &self.remote {
+                        let result = async {
+                            for leg in &legs {
+                                remote.delete_transaction(leg.id).await?;
+                            }
+                            Ok::<_, String>(())
+                        }
+                        .await;
+                        self.status_message = match result {
+                            Ok(()) => self.tr_fmt("Deleted {} on {}", &[&label, &remote.base_url()]),
+                            Err(e) => self.tr_fmt("Error deleting transaction: {}", &[&e]),
+                        };
+                        self.sync_remote().await;
+                        self.load_data().await;
+                        self.selected_index = 0;
+                        self.mode = Mode::Normal;
+                        return;
+                    }
+
+                    let leg_ids: Vec<i64> = legs.iter().map(|t| t.id).collect();
+                    let mut deletion = match undo::capture(&self.pool, &label, "transactions", &leg_ids).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving transaction for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+
+                    // The files stay until the deletion can't be undone
+                    let attachment_dir = attachments::storage_dir();
+                    for leg in &legs {
+                        let files = attachments::stored_files(&self.pool, &attachment_dir, leg.id)
+                            .await
+                            .unwrap_or_default();
+                        deletion.files.extend(files);
+                    }
+
+                    // Every leg goes with its balance change, or none does
+                    let result = async {
+                        let mut tx = self.pool.begin().await?;
+                        for leg in &legs {
+                            // First update account balance (reverse the transaction effect, if it was posted)
+                            sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+                                .bind(-transaction_status::posted_effect(leg))
+                                .bind(leg.account_id)
+                                .execute(&mut *tx)
+                                .await?;
+                            // Then delete the transaction
+                            sqlx::query("DELETE FROM transactions WHERE id = ?")
+                                .bind(leg.id)
+                                .execute(&mut *tx)
+                                .await?;
+                        }
+                        tx.commit().await
+                    }
+                    .await;
+
+                    if let Err(e) = result {
+                        self.status_message = self.tr_fmt("Error deleting transaction: {}", &[&e]);
+                        self.mode = Mode::Normal;
+                        return;
+                    }
+                    for leg in &legs {
+                        deletion.restore_balance(leg.account_id, transaction_status::posted_effect(leg));
+                    }
+                    self.push_undo(deletion);
+
+                    self.status_message = if legs.len() > 1 {
+                        self.tr("Transfer deleted from both accounts, balances updated! (U to undo)").to_string()
+                    } else {
+                        self.tr_fmt("Transaction {} deleted, balance updated! (U to undo)", &[&transaction_id])
+                    };
+                    self.load_data().await;
+                    self.selected_index = 0;
+                } else if self.current_screen == Screen::Accounts
+                    && self.selected_index < self.accounts.len()
+                {
+                    let account = &self.accounts[self.selected_index];
+                    let account_id = account.id;
+                    let account_name = account.name.clone();
+
+                    let label = self.tr_fmt("account '{}'", &[&account_name]);
+                    let mut deletion = match undo::capture(&self.pool, &label, "accounts", &[account_id]).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving account for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+                    let attachment_dir = attachments::storage_dir();
+                    let transaction_ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM transactions WHERE account_id = ?")
+                        .bind(account_id)
+                        .fetch_all(&self.pool)
+                        .await
+                        .unwrap_or_default();
+                    for transaction_id in transaction_ids {
+                        let files = attachments::stored_files(&self.pool, &attachment_dir, transaction_id)
+                            .await
+                            .unwrap_or_default();
+                        deletion.files.extend(files);
+                    }
+
+                    let result = async {
+                        let mut tx = self.pool.begin().await?;
+                        // First delete all transaction_categories for this account's transactions
+                        sqlx::query(
+                            "DELETE FROM transaction_categories WHERE transaction_id IN (SELECT id FROM transactions WHERE account_id = ?)"
+                        )
+                            .bind(account_id)
+                            .execute(&mut *tx)
+                            .await?;
+
+                        // Then delete all transactions for this account
+                        let txn_deleted = sqlx::query("DELETE FROM transactions WHERE account_id = ?")
+                            .bind(account_id)
+                            .execute(&mut *tx)
+                            .await?
+                            .rows_affected();
+
+                        // Finally delete the account
+                        sqlx::query("DELETE FROM accounts WHERE id = ?")
+                            .bind(account_id)
+                            .execute(&mut *tx)
+                            .await?;
+                        tx.commit().await?;
+                        Ok::<_, sqlx::Error>(txn_deleted)
+                    }
+                    .await;
+
+                    match result {
+                        Ok(txn_deleted) => {
+                            self.push_undo(deletion);
+                            if txn_deleted > 0 {
+                                self.status_message = self.tr_fmt("Account '{}' and {} transactions deleted! (U to undo)", &[&account_name, &txn_deleted]);
+                            } else {
+                                self.status_message = self.tr_fmt("Account '{}' deleted! (U to undo)", &[&account_name]);
+                            }
+                            self.load_data().await;
+                            self.selected_index = 0;
+                        }
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error deleting account: {}", &[&e]);
+                        }
+                    }
+                    self.mode = Mode::Normal;
+                } else if self.current_screen == Screen::Categories
+                    && self.selected_index < self.categories.len()
+                {
+                    let category = &self.categories[self.selected_index];
+                    let category_id = category.id;
+                    let category_name = category.name.clone();
+
+                    // Check if category is linked to transactions
+                    let link_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM transaction_categories WHERE category_id = ?")
+                        .bind(category_id)
+                        .fetch_one(&self.pool)
+                        .await
+                        .unwrap_or((0,));
+
+                    if link_count.0 > 0 {
+                        self.status_message = self.tr_fmt("Cannot delete '{}': used by {} transactions.", &[&category_name, &link_count.0]);
+                        self.mode = Mode::Normal;
+                        return;
+                    }
+
+                    let label = self.tr_fmt("category '{}'", &[&category_name]);
+                    let deletion = match undo::capture(&self.pool, &label, "categories", &[category_id]).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving category for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+                    let result = sqlx::query("DELETE FROM categories WHERE id = ?")
+                        .bind(category_id)
+                        .execute(&self.pool)
+                        .await;
+
+                    match result {
+                        Ok(_) => {
+                            self.push_undo(deletion);
+                            self.status_message = self.tr_fmt("Category '{}' deleted! (U to undo)", &[&category_name]);
+                            self.load_data().await;
+                            self.selected_index = 0;
+                        }
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error deleting category: {}", &[&e]);
+                        }
+                    }
+                    self.mode = Mode::Normal;
+                } else if self.current_screen == Screen::ExchangeRates
+                    && self.selected_index < self.exchange_rates.len()
+                {
+                    let rate_id = self.exchange_rates[self.selected_index].id;
+
+                    let deletion = match undo::capture(&self.pool, &self.tr_fmt("exchange rate {}", &[&rate_id]), "exchange_rates", &[rate_id]).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving exchange rate for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+                    let result = sqlx::query("DELETE FROM exchange_rates WHERE id = ?")
+                        .bind(rate_id)
+                        .execute(&self.pool)
+                        .await;
+
+                    match result {
+                        Ok(_) => {
+                            self.push_undo(deletion);
+                            self.status_message =
+                                self.tr_fmt("Exchange rate {} deleted successfully! (U to undo)", &[&rate_id]);
+                            self.load_data().await;
+                            self.selected_index = 0;
+                        }
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error deleting exchange rate: {}", &[&e]);
+                        }
+                    }
+                } else if self.current_screen == Screen::RecurringTransactions
+                    && self.selected_index < self.recurring_transactions.len()
+                {
+                    let recurring_id = self.recurring_transactions[self.selected_index].id;
+
+                    let deletion = match undo::capture(&self.pool, &self.tr_fmt("recurring transaction {}", &[&recurring_id]), "recurring_transactions", &[recurring_id]).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving recurring transaction for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+                    let result = sqlx::query("DELETE FROM recurring_transactions WHERE id = ?")
+                        .bind(recurring_id)
+                        .execute(&self.pool)
+                        .await;
+
+                    match result {
+                        Ok(_) => {
+                            self.push_undo(deletion);
+                            self.status_message =
+                                self.tr_fmt("Recurring transaction {} deleted successfully! (U to undo)", &[&recurring_id]);
+                            self.load_data().await;
+                            self.selected_index = 0;
+                        }
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error deleting recurring transaction: {}", &[&e]);
+                        }
+                    }
+                } else if self.current_screen == Screen::Budgets
+                    && self.selected_index < self.budget_variance.len()
+                {
+                    let row = &self.budget_variance[self.selected_index];
+                    let (budget_id, category_name) = (row.budget_id, row.category_name.clone());
+
+                    let deletion = match undo::capture(&self.pool, &self.tr_fmt("budget for {}", &[&category_name]), "budgets", &[budget_id]).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving budget for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+                    let result = sqlx::query("DELETE FROM budgets WHERE id = ?")
+                        .bind(budget_id)
+                        .execute(&self.pool)
+                        .await;
+
+                    match result {
+                        Ok(_) => {
+                            self.push_undo(deletion);
+                            self.status_message = self.tr_fmt("Budget for {} deleted (U to undo)", &[&category_name]);
+                            self.load_data().await;
+                            self.selected_index = 0;
+                        }
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error deleting budget: {}", &[&e]);
+                        }
+                    }
+                } else if self.current_screen == Screen::Goals && self.selected_index < self.goals.len() {
+                    let goal = &self.goals[self.selected_index].goal;
+                    let (goal_id, name) = (goal.id, goal.name.clone());
+
+                    let deletion = match undo::capture(&self.pool, &self.tr_fmt("goal {}", &[&name]), "goals", &[goal_id]).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving goal for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+                    let result = sqlx::query("DELETE FROM goals WHERE id = ?")
+                        .bind(goal_id)
+                        .execute(&self.pool)
+                        .await;
+
+                    match result {
+                        Ok(_) => {
+                            self.push_undo(deletion);
+                            self.status_message = self.tr_fmt("Goal {} deleted (U to undo)", &[&name]);
+                            self.load_data().await;
+                            self.selected_index = 0;
+                        }
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error deleting goal: {}", &[&e]);
+                        }
+                    }
+                } else if let Some(h) = self.selected_holding() {
+                    let (holding_id, ticker) = (h.holding.id, h.holding.ticker.clone());
 
-    fn render_footer(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        let help_text = if !self.status_message.is_empty() {
-            Paragraph::new(self.status_message.as_str())
-                .style(Style::default().fg(Color::Green))
-                .alignment(Alignment::Center)
-        } else {
-            match self.mode {
-                Mode::Normal => {
-                    if self.current_screen == Screen::UserSelect {
-                        Paragraph::new("↑↓: Select | Enter: Login | a: Add | d: Delete | q: Quit")
-                    } else if self.current_screen == Screen::Transactions {
-                        Paragraph::new("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | f: Filter | v: View in Currency | d: Delete | Enter: Details | q: Quit")
-                    } else if self.current_screen == Screen::ExchangeRates {
-                        Paragraph::new("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | c: Convert | d: Delete | Enter: Details | r: Refresh | q: Quit")
-                    } else if self.current_screen == Screen::RecurringTransactions {
-                        Paragraph::new("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | p: Process | t: Toggle | d: Delete | r: Refresh | q: Quit")
-                    } else if self.current_screen == Screen::Export {
-                        Paragraph::new("←/→ or 1-8: Tabs | e: Export data | r: Refresh | u: Switch user | q: Quit")
-                    } else if self.current_screen == Screen::Dashboard {
-                        Paragraph::new("←/→ or 1-8: Tabs | ↑/↓: Scroll | r: Refresh | u: Switch user | q: Quit")
-                    } else if self.current_screen == Screen::Accounts {
-                        Paragraph::new("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | d: Delete | Enter: Details | r: Refresh | q: Quit")
-                    } else if self.current_screen == Screen::Categories {
-                        Paragraph::new("↑↓/[]: Scroll | g/G: Top/Bottom | a: Add | d: Delete | r: Refresh | q: Quit")
-                    } else if self.current_screen == Screen::Reports {
-                        Paragraph::new("←/→ or 1-8: Tabs | r: Refresh | u: Switch user | q: Quit")
-                    } else {
-                        Paragraph::new("←/→ or 1-8: Tabs | ↑/↓: Select | r: Refresh | u: User | q: Quit")
+                    let deletion = match undo::capture(&self.pool, &self.tr_fmt("holding {}", &[&ticker]), "holdings", &[holding_id]).await {
+                        Ok(deletion) => deletion,
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error saving holding for undo: {}", &[&e]);
+                            self.mode = Mode::Normal;
+                            return;
+                        }
+                    };
+                    let result = sqlx::query("DELETE FROM holdings WHERE id = ?")
+                        .bind(holding_id)
+                        .execute(&self.pool)
+                        .await;
+
+                    match result {
+                        Ok(_) => {
+                            self.push_undo(deletion);
+                            self.status_message = self.tr_fmt("Holding {} deleted (U to undo)", &[&ticker]);
+                            self.load_data().await;
+                            self.selected_index = 0;
+                        }
+                        Err(e) => {
+                            self.status_message = self.tr_fmt("Error deleting holding: {}", &[&e]);
+                        }
                     }
                 }
-                Mode::AddTransaction => Paragraph::new(
-                    "Tab: Next field | Enter: Submit | Esc: Cancel | (Tab cycles through fields)"
-                ),
-                Mode::AddExchangeRate => Paragraph::new(
-                    "Tab: Next field | Enter: Submit | Esc: Cancel | (Tab cycles through fields)"
-                ),
-                Mode::AddRecurringTransaction => Paragraph::new(
-                    "Tab: Next field | Enter: Submit | Esc: Cancel | (Tab cycles through fields)"
-                ),
-                Mode::AddAccount => Paragraph::new(
-                    "Tab: Next field | Enter: Submit | Esc: Cancel | (Tab cycles through fields)"
-                ),
-                Mode::AddCategory => Paragraph::new(
-                    "Tab: Next field | Type to input | Enter: Submit | Esc: Cancel"
-                ),
-                Mode::AddUser => Paragraph::new(
-                    "Tab: Next field | Type to input | Enter: Create User | Esc: Cancel"
-                ),
-                Mode::ConvertCurrency => Paragraph::new(
-                    "Tab: Next field | Enter: Convert | Esc: Cancel | (Tab cycles through fields)"
-                ),
-                Mode::DeleteConfirm => Paragraph::new(
-                    "y: Confirm delete | n: Cancel"
-                ),
-                Mode::ViewDetails => Paragraph::new(
-                    "Esc: Go back | v: View in different currency"
-                ),
-                Mode::ExportData => Paragraph::new(
-                    "1-4: Select export format | Esc: Cancel"
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                self.mode = Mode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    /// Add-recurring wizard: what is charged, what for, and when
+    fn open_recurring_wizard(&mut self) {
+        // Closed accounts take no new transactions
+        let accounts: Vec<(String, String)> = self
+            .accounts
+            .iter()
+            .filter(|a| !a.is_archived)
+            .map(|a| (a.id.to_string(), format!("{} [{}]", a.name, a.currency)))
+            .collect();
+        if accounts.is_empty() {
+            self.status_message = self.tr("Add an account first").to_string();
+            return;
+        }
+        let mut categories = vec![(String::new(), self.tr("None").to_string())];
+        categories.extend(self.categories.iter().map(|c| (c.id.to_string(), c.name.clone())));
+        let options = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(value, label)| (value.to_string(), label.to_string())).collect()
+        };
+        let default_account = self.user_settings.default_account_id.map(|id| id.to_string()).unwrap_or_default();
+        let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+
+        self.wizard = Some(Wizard::new(
+            "Add Recurring",
+            vec![
+                Step::new(
+                    "Amount",
+                    vec![
+                        Field::choice("account", "Account: ", accounts).with_choice(&default_account),
+                        Field::choice("type", "Type: ", options(&[("expense", "Expense"), ("income", "Income")])),
+                        Field::text("amount", "Amount: "),
+                    ],
                 ),
-                Mode::SelectCurrencyFilter => Paragraph::new(
-                    "0: All Currencies | 1-9: Select currency | Esc: Cancel"
+                Step::new(
+                    "Details",
+                    vec![
+                        Field::text("description", "Description: "),
+                        Field::choice("category", "Category: ", categories),
+                    ],
                 ),
-                Mode::SelectViewCurrency => Paragraph::new(
-                    "↑↓: Scroll | []: Jump 10 | Enter: Select | Esc: Cancel"
+                Step::new(
+                    "Schedule",
+                    vec![
+                        Field::choice(
+                            "frequency",
+                            "Frequency: ",
+                            options(&[("monthly", "Monthly"), ("weekly", "Weekly"), ("daily", "Daily"), ("yearly", "Yearly")]),
+                        ),
+                        Field::text("start", "First Date: ").with_text(today).with_hint("YYYY-MM-DD"),
+                        Field::choice("classification", "Fixed/Variable: ", options(&[("fixed", "Fixed"), ("variable", "Variable")])),
+                        Field::choice("catch_up", "Missed Occurrences: ", options(&[("all", "Post all"), ("latest", "Latest only")])),
+                    ],
                 ),
-            }
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Center)
+            ],
+        ));
+        self.mode = Mode::AddRecurringTransaction;
+    }
+
+    /// Pass a key to the open wizard, which only moves past a step once
+    /// `check` accepts it; true when the last step was accepted and the form
+    /// can be submitted
+    fn wizard_key(&mut self, code: KeyCode, check: impl Fn(&Self, &Wizard) -> Result<(), String>) -> bool {
+        let Some(mut wizard) = self.wizard.take() else {
+            self.mode = Mode::Normal;
+            return false;
         };
+        let mut finished = false;
+        match wizard.handle_key(code) {
+            WizardEvent::Pending => {}
+            WizardEvent::Cancel => {
+                self.mode = Mode::Normal;
+                return false;
+            }
+            event => match check(self, &wizard) {
+                Ok(()) if event == WizardEvent::Finish => finished = true,
+                Ok(()) => wizard.advance(),
+                Err(e) => wizard.fail(e),
+            },
+        }
+        self.wizard = Some(wizard);
+        finished
+    }
 
-        let footer = help_text.block(Block::default().borders(Borders::ALL));
-        frame.render_widget(footer, area);
+    /// Show why the wizard's form couldn't be saved, keeping it open
+    fn wizard_failed(&mut self, error: impl Into<String>) {
+        if let Some(wizard) = self.wizard.as_mut() {
+            wizard.fail(error);
+        }
     }
 
-    async fn handle_events(&mut self) -> io::Result<()> {
-        if event::poll(StdDuration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if self.current_screen == Screen::UserSelect {
-                        self.handle_user_select_mode(key.code).await;
-                    } else {
-                        match self.mode {
-                            Mode::Normal => self.handle_normal_mode(key.code).await,
-                            Mode::AddTransaction => {
-                                self.handle_add_transaction_mode(key.code).await
-                            }
-                            Mode::AddExchangeRate => {
-                                self.handle_add_exchange_rate_mode(key.code).await
-                            }
-                            Mode::AddRecurringTransaction => {
-                                self.handle_add_recurring_mode(key.code).await
-                            }
-                            Mode::AddAccount => {
-                                self.handle_add_account_mode(key.code).await
-                            }
-                            Mode::AddCategory => {
-                                self.handle_add_category_mode(key.code).await
-                            }
-                            Mode::AddUser => {
-                                self.handle_add_user_mode(key.code).await
-                            }
-                            Mode::ConvertCurrency => {
-                                self.handle_convert_currency_mode(key.code).await
-                            }
-                            Mode::DeleteConfirm => self.handle_delete_mode(key.code).await,
-                            Mode::ViewDetails => self.handle_details_mode(key.code),
-                            Mode::ExportData => self.handle_export_mode(key.code).await,
-                            Mode::SelectCurrencyFilter => self.handle_currency_filter_mode(key.code),
-                            Mode::SelectViewCurrency => self.handle_view_currency_mode(key.code),
-                        }
-                    }
-                }
-            }
+    fn close_wizard(&mut self) {
+        self.wizard = None;
+        self.mode = Mode::Normal;
+    }
+
+    fn render_wizard(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        if let Some(wizard) = &self.wizard {
+            wizard.render(frame, area, self.language);
         }
-        Ok(())
     }
 
-    /// Periodically refresh data so background changes (like auto-processed recurring txns) show up.
-    async fn maybe_auto_refresh(&mut self) {
-        // Only refresh when a user is selected and we are not in a modal/form mode.
-        if self.current_user_id.is_none() || self.mode != Mode::Normal {
-            return;
+    /// Why the recurring wizard can't move past its current step, if anything
+    fn check_recurring_step(&self, wizard: &Wizard) -> Result<(), String> {
+        match wizard.step {
+            0 => match wizard.value("amount").trim().parse::<f64>() {
+                Ok(amount) if amount > 0.0 => Ok(()),
+                _ => Err(self.tr("Amount must be a positive number").to_string()),
+            },
+            1 => input::check_length("Description", Some(wizard.value("description")), input::MAX_DESCRIPTION_LEN),
+            _ => NaiveDate::parse_from_str(wizard.value("start").trim(), "%Y-%m-%d")
+                .map(|_| ())
+                .map_err(|_| self.tr("First date must be YYYY-MM-DD").to_string()),
         }
+    }
 
-        let now = Instant::now();
-        if now.duration_since(self.last_auto_refresh) >= StdDuration::from_secs(3) {
-            self.load_data().await;
-            self.last_auto_refresh = now;
+    async fn handle_add_recurring_mode(&mut self, code: KeyCode) {
+        if self.wizard_key(code, Self::check_recurring_step) {
+            self.submit_recurring_transaction().await;
         }
     }
 
-    async fn handle_user_select_mode(&mut self, code: KeyCode) {
-        if self.mode == Mode::AddUser {
-            self.handle_add_user_mode(code).await;
+    async fn submit_recurring_transaction(&mut self) {
+        let Some(wizard) = &self.wizard else {
             return;
-        }
-        
-        if self.mode == Mode::DeleteConfirm {
-            self.handle_delete_user_mode(code).await;
+        };
+        let start = NaiveDate::parse_from_str(wizard.value("start").trim(), "%Y-%m-%d")
+            .unwrap_or_else(|_| Utc::now().date_naive())
+            .and_time(chrono::NaiveTime::MIN)
+            .and_utc();
+        let description = wizard.value("description").trim();
+        let data = CreateRecurringTransaction {
+            account_id: wizard.value("account").parse().unwrap_or_default(),
+            category_id: wizard.value("category").parse().ok(),
+            amount: wizard.value("amount").trim().parse().unwrap_or_default(),
+            transaction_type: wizard.value("type").to_string(),
+            description: (!description.is_empty()).then(|| description.to_string()),
+            frequency: wizard.value("frequency").to_string(),
+            start_date: start,
+            end_date: None,
+            classification: Some(wizard.value("classification").to_string()),
+            currency: None,
+            catch_up: Some(wizard.value("catch_up").to_string()),
+        };
+        if let Err(e) = data.validate() {
+            self.wizard_failed(self.tr_fmt("Error: {}", &[&e]));
             return;
         }
-        
-        match code {
-            KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Char('a') => {
-                self.mode = Mode::AddUser;
-                self.clear_user_form();
-            }
-            KeyCode::Char('d') => {
-                if !self.users.is_empty() {
-                    self.mode = Mode::DeleteConfirm;
-                }
-            }
-            KeyCode::Up => {
-                self.selected_index = self.selected_index.saturating_sub(1);
-            }
-            KeyCode::Down => {
-                self.selected_index =
-                    (self.selected_index + 1).min(self.users.len().saturating_sub(1));
-            }
-            KeyCode::Enter => {
-                if self.selected_index < self.users.len() {
-                    self.current_user_id = Some(self.users[self.selected_index].id);
-                    self.current_screen = Screen::Dashboard;
-                    self.selected_tab = 0;
-                    self.selected_index = 0;
-                    self.load_data().await;
-                    self.status_message =
-                        format!("Logged in as {}", self.users[self.selected_index].username);
-                }
+
+        let result = sqlx::query(
+            "INSERT INTO recurring_transactions 
+             (account_id, category_id, amount, transaction_type, description, frequency, start_date, next_occurrence, is_active, classification, catch_up) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)"
+        )
+        .bind(data.account_id)
+        .bind(data.category_id)
+        .bind(data.amount)
+        .bind(&data.transaction_type)
+        .bind(&data.description)
+        .bind(&data.frequency)
+        .bind(data.start_date)
+        .bind(data.start_date)
+        .bind(&data.classification)
+        .bind(&data.catch_up)
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(res) => {
+                let recurring_id = res.last_insert_rowid();
+                self.status_message = self.tr_fmt("Recurring transaction added successfully! ID: {}", &[&recurring_id]);
+                self.close_wizard();
+                self.load_data().await;
             }
-            _ => {}
+            Err(e) => self.wizard_failed(self.tr_fmt("Error adding recurring transaction: {}", &[&e])),
         }
     }
 
-    async fn handle_normal_mode(&mut self, code: KeyCode) {
-        self.status_message.clear();
-        self.export_message.clear();
+    /// Fill both date fields with a budgets::resolve_period shortcut
+    fn set_export_range(&mut self, period: &str) {
+        if let Some((start, end)) = budgets::resolve_period(period, Utc::now()) {
+            self.export_range_start = start.date_naive().to_string();
+            self.export_range_end = (end - chrono::Duration::days(1)).date_naive().to_string();
+        }
+    }
+
+    /// The [start, end) window typed into the export dialog; None when both
+    /// dates are empty
+    fn export_range(&self) -> Result<Option<DateRange>, String> {
+        let (start, end) = (self.export_range_start.trim(), self.export_range_end.trim());
+        if start.is_empty() && end.is_empty() {
+            return Ok(None);
+        }
+        budgets::parse_date_range(start, end)
+            .map(Some)
+            .map_err(|e| self.tr(e).to_string())
+    }
+
+    /// Transactions to export: all of the user's in the export range,
+    /// otherwise the Transactions tab's list
+    async fn export_transactions(&self) -> Result<Vec<Transaction>, String> {
+        let Some((start, end)) = self.export_range()? else {
+            return Ok(self.transactions.clone());
+        };
+        sqlx::query_as::<_, Transaction>(
+            "SELECT t.* FROM transactions t
+             JOIN accounts a ON a.id = t.account_id
+             WHERE a.user_id = ? AND datetime(t.transaction_date) >= datetime(?) AND datetime(t.transaction_date) < datetime(?)
+             ORDER BY t.transaction_date DESC",
+        )
+        .bind(self.current_user_id)
+        .bind(start.to_rfc3339())
+        .bind(end.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// The user's splits, only of `transactions` when exporting a range
+    async fn export_splits(&self, transactions: &[Transaction]) -> Result<Vec<TransactionCategory>, String> {
+        let splits = sqlx::query_as::<_, TransactionCategory>(
+            "SELECT tc.* FROM transaction_categories tc
+             JOIN transactions t ON t.id = tc.transaction_id
+             JOIN accounts a ON a.id = t.account_id
+             WHERE a.user_id = ?",
+        )
+        .bind(self.current_user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        if self.export_range()?.is_none() {
+            return Ok(splits);
+        }
+        let ids: HashSet<i64> = transactions.iter().map(|t| t.id).collect();
+        Ok(splits.into_iter().filter(|s| ids.contains(&s.transaction_id)).collect())
+    }
+
+    fn export_failed(&mut self, e: impl std::fmt::Display) {
+        self.export_message = self.tr_fmt("Error exporting: {}", &[&e]);
+        self.status_message = self.export_message.clone();
+    }
 
+    async fn handle_export_mode(&mut self, code: KeyCode) {
+        if self.export_range_field > 0 {
+            let field = if self.export_range_field == 1 {
+                &mut self.export_range_start
+            } else {
+                &mut self.export_range_end
+            };
+            match code {
+                KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => {
+                    field.push(c);
+                    return;
+                }
+                KeyCode::Backspace => {
+                    field.pop();
+                    return;
+                }
+                _ => {}
+            }
+        }
+        if let KeyCode::Char('1'..='7') = code {
+            // Refuse a half-typed range before writing anything
+            if let Err(e) = self.export_range() {
+                self.status_message = e;
+                return;
+            }
+        }
         match code {
-            KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Char('u') => {
-                // Switch user
-                self.current_screen = Screen::UserSelect;
-                self.selected_index = 0;
-                self.current_user_id = None;
-                self.accounts.clear();
-                self.transactions.clear();
-                self.categories.clear();
-                self.exchange_rates.clear();
-                self.recurring_transactions.clear();
-                self.category_spending.clear();
+            KeyCode::Esc if self.export_range_field > 0 => self.export_range_field = 0,
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Tab => self.export_range_field = (self.export_range_field + 1) % 3,
+            KeyCode::Char('t') => self.set_export_range("this_month"),
+            KeyCode::Char('l') => self.set_export_range("last_month"),
+            KeyCode::Char('q') => self.set_export_range("last_quarter"),
+            KeyCode::Char('y') => self.set_export_range("ytd"),
+            KeyCode::Char('a') => {
+                self.export_range_start.clear();
+                self.export_range_end.clear();
+            }
+            KeyCode::Char('1') => {
+                self.export_transactions_csv().await;
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Char('2') => {
+                self.export_transactions_json().await;
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Char('3') => {
+                self.export_accounts_csv().await;
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Char('4') => {
+                self.export_full_summary().await;
+                self.mode = Mode::Normal;
             }
-            KeyCode::Char('r') => {
-                self.load_data().await;
-                self.status_message = "Data refreshed!".to_string();
+            KeyCode::Char('5') => {
+                self.export_summary_xlsx().await;
+                self.mode = Mode::Normal;
             }
-            KeyCode::Char('a') => {
-                if self.current_screen == Screen::Transactions {
-                    self.mode = Mode::AddTransaction;
-                    self.clear_transaction_form();
-                } else if self.current_screen == Screen::Accounts {
-                    self.mode = Mode::AddAccount;
-                    self.clear_account_form();
-                } else if self.current_screen == Screen::Categories {
-                    self.mode = Mode::AddCategory;
-                    self.clear_category_form();
-                } else if self.current_screen == Screen::ExchangeRates {
-                    self.mode = Mode::AddExchangeRate;
-                    self.clear_exchange_rate_form();
-                } else if self.current_screen == Screen::RecurringTransactions {
-                    self.mode = Mode::AddRecurringTransaction;
-                    self.clear_recurring_form();
-                }
+            KeyCode::Char('6') => {
+                self.export_statement_pdf().await;
+                self.mode = Mode::Normal;
             }
-            KeyCode::Char('c') => {
-                if self.current_screen == Screen::ExchangeRates {
-                    self.mode = Mode::ConvertCurrency;
-                    self.clear_conversion_form();
-                }
+            KeyCode::Char('7') => {
+                self.export_transactions_qif().await;
+                self.mode = Mode::Normal;
             }
-            KeyCode::Char('d') => {
-                if self.current_screen == Screen::Transactions && !self.transactions.is_empty() {
-                    self.mode = Mode::DeleteConfirm;
-                } else if self.current_screen == Screen::Accounts && !self.accounts.is_empty() {
-                    self.mode = Mode::DeleteConfirm;
-                } else if self.current_screen == Screen::Categories && !self.categories.is_empty() {
-                    self.mode = Mode::DeleteConfirm;
-                } else if self.current_screen == Screen::ExchangeRates && !self.exchange_rates.is_empty() {
-                    self.mode = Mode::DeleteConfirm;
-                } else if self.current_screen == Screen::RecurringTransactions && !self.recurring_transactions.is_empty() {
-                    self.mode = Mode::DeleteConfirm;
+            KeyCode::Char('x') => {
+                if self.export_encrypt {
+                    self.export_encrypt = false;
+                    self.export_passphrase.clear();
+                    self.status_message = self.tr("Export encryption disabled").to_string();
+                } else {
+                    self.export_passphrase.clear();
+                    self.mode = Mode::ExportPassphrase;
                 }
             }
-            KeyCode::Char('e') => {
-                if self.current_screen == Screen::Export {
+            _ => {}
+        }
+    }
+
+    fn handle_export_passphrase_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.export_passphrase.clear();
+                self.mode = Mode::ExportData;
+            }
+            KeyCode::Char(c) => self.export_passphrase.push(c),
+            KeyCode::Backspace => {
+                self.export_passphrase.pop();
+            }
+            KeyCode::Enter => {
+                if self.export_passphrase.chars().count() < encryption::MIN_PASSPHRASE_LEN {
+                    self.status_message = self.tr_fmt("Error: Passphrase must be at least {} characters", &[&encryption::MIN_PASSPHRASE_LEN]);
+                } else {
+                    self.export_encrypt = true;
+                    self.status_message = self.tr("Export encryption enabled").to_string();
                     self.mode = Mode::ExportData;
                 }
             }
-            KeyCode::Char('f') => {
-                // Filter by currency on Transactions screen
-                if self.current_screen == Screen::Transactions {
-                    self.mode = Mode::SelectCurrencyFilter;
-                }
+            _ => {}
+        }
+    }
+
+    /// Typing narrows the Transactions list as it goes; Enter keeps the
+    /// search, Esc drops it
+    fn handle_search_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.transaction_search.clear();
+                self.selected_index = 0;
+                self.mode = Mode::Normal;
             }
-            KeyCode::Char('v') => {
-                // View in currency on Transactions screen
-                if self.current_screen == Screen::Transactions {
-                    self.mode = Mode::SelectViewCurrency;
-                }
+            KeyCode::Enter => self.mode = Mode::Normal,
+            KeyCode::Char(c) => {
+                self.transaction_search.push(c);
+                self.selected_index = 0;
             }
-            KeyCode::Char('p') => {
-                // Process recurring transactions
-                if self.current_screen == Screen::RecurringTransactions {
-                    self.process_recurring_transactions().await;
-                }
+            KeyCode::Backspace => {
+                self.transaction_search.pop();
+                self.selected_index = 0;
             }
-            KeyCode::Char('t') => {
-                // Toggle active status for recurring transactions
-                if self.current_screen == Screen::RecurringTransactions 
-                    && self.selected_index < self.recurring_transactions.len() 
-                {
-                    self.toggle_recurring_active().await;
-                }
+            _ => {}
+        }
+    }
+
+    async fn handle_receipt_path_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Char(c) => self.form_receipt_path.push(c),
+            KeyCode::Backspace => {
+                self.form_receipt_path.pop();
             }
-            KeyCode::Enter => {
-                self.mode = Mode::ViewDetails;
+            KeyCode::Enter => self.read_receipt().await,
+            _ => {}
+        }
+    }
+
+    /// Parse the receipt file and open the add-transaction form pre-filled
+    /// with what was found, for the user to check and submit
+    async fn read_receipt(&mut self) {
+        let Some(user_id) = self.current_user_id else {
+            return;
+        };
+        let parser = match receipts::parser_from_env() {
+            Ok(Some(parser)) => parser,
+            Ok(None) => {
+                self.status_message = self.tr("Receipt parsing is turned off (RECEIPT_PARSER=none)").to_string();
+                self.mode = Mode::Normal;
+                return;
             }
-            KeyCode::Up => {
-                self.selected_index = self.selected_index.saturating_sub(1);
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error: {}", &[&e]);
+                self.mode = Mode::Normal;
+                return;
             }
-            KeyCode::Down => {
-                let max = self.get_current_list_len().saturating_sub(1);
-                self.selected_index = (self.selected_index + 1).min(max);
+        };
+        let bytes = match std::fs::read(self.form_receipt_path.trim()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error reading {}: {}", &[&self.form_receipt_path.trim(), &e]);
+                return;
             }
-            KeyCode::PageUp | KeyCode::Char('[') => {
-                // Jump up 10 items (PageUp or '[' for Mac compatibility)
-                self.selected_index = self.selected_index.saturating_sub(10);
+        };
+        let fields = match parser.parse(&bytes) {
+            Ok(fields) => fields,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error reading receipt: {}", &[&e]);
+                return;
             }
-            KeyCode::PageDown | KeyCode::Char(']') => {
-                // Jump down 10 items (PageDown or ']' for Mac compatibility)
-                let max = self.get_current_list_len().saturating_sub(1);
-                self.selected_index = (self.selected_index + 10).min(max);
+        };
+
+        let account_id = self.quick_add_account().map(|a| a.id);
+        let draft = match receipts::draft(&self.pool, user_id, account_id, fields, parser.name()).await {
+            Ok(draft) => draft,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error reading receipt: {}", &[&e]);
+                return;
             }
-            KeyCode::Home | KeyCode::Char('g') => {
-                // Jump to first item (Home or 'g' for Mac compatibility)
-                self.selected_index = 0;
+        };
+
+        self.clear_transaction_form();
+        self.form_account_id = draft.account_id.map(|id| id.to_string()).unwrap_or_default();
+        self.form_amount = draft.amount.map(|a| format!("{:.2}", a)).unwrap_or_default();
+        self.form_description = draft.description.clone().unwrap_or_default();
+        let category_id = draft
+            .category_id
+            .or_else(|| self.guess_category(&self.form_description).map(|(id, _)| id));
+        self.form_category_id = category_id.map(|id| id.to_string()).unwrap_or_default();
+        self.form_merchant = draft.merchant;
+        self.form_date = draft.transaction_date;
+        self.form_field_index = if draft.amount.is_some() { 3 } else { 1 };
+        self.status_message = if draft.amount.is_none() && self.form_description.is_empty() {
+            self.tr("Nothing recognised on the receipt - fill in the form").to_string()
+        } else {
+            self.tr("Receipt read - check the fields, then Enter to add").to_string()
+        };
+        self.mode = Mode::AddTransaction;
+    }
+
+    /// Pick a profile (None for auto-detected columns) and move to its default account
+    fn select_import_profile(&mut self, index: Option<usize>) {
+        self.form_import_profile = index;
+        let account_id = index.and_then(|i| self.import_profiles.get(i)).and_then(|p| p.account_id);
+        if let Some(position) = account_id.and_then(|id| self.accounts.iter().position(|a| a.id == id)) {
+            self.form_import_account = position;
+        }
+    }
+
+    async fn handle_import_mode(&mut self, code: KeyCode) {
+        match (code, self.form_field_index) {
+            (KeyCode::Esc, _) => self.mode = Mode::Normal,
+            (KeyCode::Tab, _) => self.form_field_index = (self.form_field_index + 1) % 3,
+            (KeyCode::Enter, _) => self.import_statement().await,
+            // Profiles cycle through "none" after the last one
+            (KeyCode::Right, 0) => {
+                let next = match self.form_import_profile {
+                    None if !self.import_profiles.is_empty() => Some(0),
+                    Some(i) if i + 1 < self.import_profiles.len() => Some(i + 1),
+                    _ => None,
+                };
+                self.select_import_profile(next);
             }
-            KeyCode::End | KeyCode::Char('G') => {
-                // Jump to last item (End or 'G' for Mac compatibility)
-                let max = self.get_current_list_len().saturating_sub(1);
-                self.selected_index = max;
+            (KeyCode::Left, 0) => {
+                let previous = match self.form_import_profile {
+                    None => self.import_profiles.len().checked_sub(1),
+                    Some(i) => i.checked_sub(1),
+                };
+                self.select_import_profile(previous);
             }
-            KeyCode::Left => {
-                self.selected_tab = self.selected_tab.saturating_sub(1);
-                self.update_screen();
-                self.selected_index = 0;
+            (KeyCode::Right, 1) => {
+                self.form_import_account = (self.form_import_account + 1) % self.accounts.len();
             }
-            KeyCode::Right => {
-                self.selected_tab = (self.selected_tab + 1).min(7);
-                self.update_screen();
-                self.selected_index = 0;
+            (KeyCode::Left, 1) => {
+                self.form_import_account = (self.form_import_account + self.accounts.len() - 1) % self.accounts.len();
             }
-            KeyCode::Char('1') => {
-                self.selected_tab = 0;
-                self.update_screen();
-                self.selected_index = 0;
+            (KeyCode::Char(c), 2) => self.form_import_path.push(c),
+            (KeyCode::Backspace, 2) => {
+                self.form_import_path.pop();
             }
-            KeyCode::Char('2') => {
-                self.selected_tab = 1;
-                self.update_screen();
-                self.selected_index = 0;
+            _ => {}
+        }
+    }
+
+    /// Import the CSV file into the chosen account with the chosen profile's
+    /// mapping, skipping rows already there, as `import` on the command line does
+    async fn import_statement(&mut self) {
+        let (Some(user_id), Some(account)) = (self.current_user_id, self.accounts.get(self.form_import_account)) else {
+            return;
+        };
+        let account_id = account.id;
+        let path = self.form_import_path.trim().to_string();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error reading {}: {}", &[&path, &e]);
+                return;
             }
-            KeyCode::Char('3') => {
-                self.selected_tab = 2;
-                self.update_screen();
-                self.selected_index = 0;
+        };
+        let mapping = self
+            .form_import_profile
+            .and_then(|i| self.import_profiles.get(i))
+            .map(import::profile_mapping)
+            .unwrap_or_default();
+        let (rows, date_format) = match import::parse_csv(&content, &mapping) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error: {}", &[&e]);
+                return;
             }
-            KeyCode::Char('4') => {
-                self.selected_tab = 3;
-                self.update_screen();
-                self.selected_index = 0;
+        };
+
+        match import::import_csv(&self.pool, user_id, account_id, rows, date_format).await {
+            Ok(result) => {
+                self.status_message = self.tr_fmt("Imported {} transactions ({} duplicates skipped, {} before the account opened)", &[&result.imported, &result.duplicates_skipped, &result.before_opening_skipped]);
+                self.mode = Mode::Normal;
+                self.load_data().await;
             }
-            KeyCode::Char('5') => {
-                self.selected_tab = 4;
-                self.update_screen();
-                self.selected_index = 0;
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error importing: {}", &[&e]);
+                self.mode = Mode::Normal;
             }
-            KeyCode::Char('6') => {
-                self.selected_tab = 5;
-                self.update_screen();
-                self.selected_index = 0;
+        }
+    }
+
+    fn render_import_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let field_style = |index: usize| {
+            if self.form_field_index == index {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::White)
             }
-            KeyCode::Char('7') => {
-                self.selected_tab = 6;
-                self.update_screen();
-                self.selected_index = 0;
+        };
+        let profile = self.form_import_profile.and_then(|i| self.import_profiles.get(i));
+        let profile_label = match profile {
+            Some(p) => format!("◄ {} ►", p.name),
+            None if self.import_profiles.is_empty() => self.tr("None saved (columns detected from the header)").to_string(),
+            None => self.tr("◄ None (detect columns) ►").to_string(),
+        };
+        let account_label = self
+            .accounts
+            .get(self.form_import_account)
+            .map(|a| format!("◄ {} ({}) ►", a.name, a.currency))
+            .unwrap_or_default();
+
+        let mut form_text = vec![
+            Line::from(Span::styled(
+                self.tr("Import CSV Statement"),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Profile: "), Style::default().fg(Color::Gray)),
+                Span::styled(profile_label, field_style(0)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Account: "), Style::default().fg(Color::Gray)),
+                Span::styled(account_label, field_style(1)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("CSV file: "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_import_path, field_style(2)),
+            ]),
+            Line::from(""),
+        ];
+        if let Some(p) = profile {
+            let mapped: Vec<String> = [
+                ("date", &p.date_column),
+                ("description", &p.description_column),
+                ("amount", &p.amount_column),
+                ("debit", &p.debit_column),
+                ("credit", &p.credit_column),
+                ("reference", &p.reference_column),
+                ("date format", &p.date_format),
+            ]
+            .iter()
+            .filter_map(|(field, value)| value.as_ref().map(|v| format!("{}={}", field, v)))
+            .collect();
+            form_text.push(Line::from(Span::styled(
+                self.tr_fmt("Columns: {}  |  Amounts: {}", &[&if mapped.is_empty() { "detected".to_string() } else { mapped.join(", ") }, &p.amount_sign.replace('_', " ")]),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        form_text.push(Line::from(Span::styled(
+            self.tr("Rows already in the account are skipped. Save profiles with POST /import/profiles or `import --save-profile`."),
+            Style::default().fg(Color::Gray),
+        )));
+
+        let form = Paragraph::new(form_text)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Import")))
+            .alignment(Alignment::Left);
+        frame.render_widget(form, area);
+    }
+
+    fn render_receipt_path_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let form_text = vec![
+            Line::from(vec![Span::styled(
+                self.tr("Add Transaction From Receipt"),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Receipt file: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    &self.form_receipt_path,
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::UNDERLINED),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![Span::styled(
+                self.tr("The date, total and merchant found on the receipt pre-fill the add-transaction form; nothing is saved until you submit it."),
+                Style::default().fg(Color::Gray),
+            )]),
+        ];
+
+        let form = Paragraph::new(form_text)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Read Receipt")))
+            .alignment(Alignment::Left);
+        frame.render_widget(form, area);
+    }
+
+    fn render_export_passphrase_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let form_text = vec![
+            Line::from(vec![Span::styled(
+                self.tr("Encrypt Exports"),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Passphrase: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    "*".repeat(self.export_passphrase.chars().count()),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::UNDERLINED),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![Span::styled(
+                self.tr_fmt(
+                    "At least {} characters. Files are saved as AES-encrypted .{} archives; open them with 7-Zip or `cargo run decrypt_export <file>`.",
+                    &[&encryption::MIN_PASSPHRASE_LEN, &encryption::FILE_EXTENSION],
+                ),
+                Style::default().fg(Color::Gray),
+            )]),
+        ];
+
+        let form = Paragraph::new(form_text)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Export Passphrase")))
+            .alignment(Alignment::Left);
+        frame.render_widget(form, area);
+    }
+
+    /// Write an export file, encrypting it first when export encryption is on.
+    /// Returns the path actually written.
+    fn write_export_file(&self, path: &str, contents: impl AsRef<[u8]>) -> Result<String, String> {
+        let contents = contents.as_ref();
+        if self.export_encrypt {
+            let name = std::path::Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string());
+            let encrypted = encryption::encrypt(&name, contents, &self.export_passphrase)?;
+            let path = format!("{}.{}", path, encryption::FILE_EXTENSION);
+            std::fs::write(&path, encrypted).map_err(|e| e.to_string())?;
+            Ok(path)
+        } else {
+            std::fs::write(path, contents).map_err(|e| e.to_string())?;
+            Ok(path.to_string())
+        }
+    }
+
+    async fn export_transactions_csv(&mut self) {
+        let transactions = match self.export_transactions().await {
+            Ok(transactions) => transactions,
+            Err(e) => return self.export_failed(e),
+        };
+        let mut csv = String::from("id,account_id,amount,type,description,date,notes,reference\n");
+
+        for t in &transactions {
+            csv.push_str(&format!(
+                "{},{},{:.2},{},\"{}\",{},\"{}\",\"{}\"\n",
+                t.id,
+                t.account_id,
+                t.amount,
+                t.transaction_type,
+                t.description.as_deref().unwrap_or("").replace("\"", "\"\""),
+                t.transaction_date.format("%Y-%m-%d %H:%M:%S"),
+                t.notes.as_deref().unwrap_or("").replace("\"", "\"\""),
+                t.reference.as_deref().unwrap_or("").replace("\"", "\"\"")
+            ));
+        }
+
+        match self.write_export_file("transactions_export.csv", &csv) {
+            Ok(path) => {
+                self.export_message = self.tr_fmt("Exported {} transactions to {}", &[&transactions.len(), &path]);
+                self.status_message = self.export_message.clone();
             }
-            KeyCode::Char('8') => {
-                self.selected_tab = 7;
-                self.update_screen();
-                self.selected_index = 0;
+            Err(e) => {
+                self.export_message = self.tr_fmt("Error exporting: {}", &[&e]);
+                self.status_message = self.export_message.clone();
             }
-            _ => {}
         }
     }
 
-    async fn handle_add_transaction_mode(&mut self, code: KeyCode) {
-        match code {
-            KeyCode::Esc => {
-                self.mode = Mode::Normal;
+    async fn export_transactions_json(&mut self) {
+        let transactions = match self.export_transactions().await {
+            Ok(transactions) => transactions,
+            Err(e) => return self.export_failed(e),
+        };
+        match serde_json::to_string_pretty(&transactions) {
+            Ok(json) => {
+                match self.write_export_file("transactions_export.json", &json) {
+                    Ok(path) => {
+                        self.export_message = self.tr_fmt("Exported {} transactions to {}", &[&transactions.len(), &path]);
+                        self.status_message = self.export_message.clone();
+                    }
+                    Err(e) => {
+                        self.export_message = self.tr_fmt("Error exporting: {}", &[&e]);
+                        self.status_message = self.export_message.clone();
+                    }
+                }
             }
-            KeyCode::Tab => {
-                self.form_field_index = (self.form_field_index + 1) % 5;
+            Err(e) => {
+                self.export_message = self.tr_fmt("Error serializing: {}", &[&e]);
+                self.status_message = self.export_message.clone();
             }
-            KeyCode::BackTab => {
-                self.form_field_index = if self.form_field_index == 0 {
-                    4
-                } else {
-                    self.form_field_index - 1
-                };
+        }
+    }
+
+    async fn export_accounts_csv(&mut self) {
+        let mut csv = String::from("id,user_id,name,type,bank_name,currency,initial_balance,current_balance\n");
+
+        for a in &self.accounts {
+            csv.push_str(&format!(
+                "{},{},\"{}\",{},\"{}\",{},{:.2},{:.2}\n",
+                a.id,
+                a.user_id,
+                a.name.replace("\"", "\"\""),
+                a.account_type,
+                a.bank_name.as_deref().unwrap_or("").replace("\"", "\"\""),
+                a.currency,
+                a.initial_balance,
+                a.current_balance
+            ));
+        }
+
+        match self.write_export_file("accounts_export.csv", &csv) {
+            Ok(path) => {
+                self.export_message = self.tr_fmt("Exported {} accounts to {}", &[&self.accounts.len(), &path]);
+                self.status_message = self.export_message.clone();
             }
-            KeyCode::Char(c) => match self.form_field_index {
-                0 => self.form_account_id.push(c),
-                1 => self.form_amount.push(c),
-                2 => self.form_type.push(c),
-                3 => self.form_description.push(c),
-                4 => self.form_category_id.push(c),
-                _ => {}
-            },
-            KeyCode::Backspace => match self.form_field_index {
-                0 => {
-                    self.form_account_id.pop();
-                }
-                1 => {
-                    self.form_amount.pop();
-                }
-                2 => {
-                    self.form_type.pop();
-                }
-                3 => {
-                    self.form_description.pop();
-                }
-                4 => {
-                    self.form_category_id.pop();
-                }
-                _ => {}
-            },
-            KeyCode::Enter => {
-                self.submit_transaction().await;
+            Err(e) => {
+                self.export_message = self.tr_fmt("Error exporting: {}", &[&e]);
+                self.status_message = self.export_message.clone();
             }
-            _ => {}
         }
     }
-    async fn handle_add_exchange_rate_mode(&mut self, code: KeyCode) {
-        match code {
-            KeyCode::Esc => {
-                self.mode = Mode::Normal;
+
+    async fn export_full_summary(&mut self) {
+        use serde_json::json;
+
+        let transactions = match self.export_transactions().await {
+            Ok(transactions) => transactions,
+            Err(e) => return self.export_failed(e),
+        };
+        // The splits too, so `import_summary` can restore the file in full
+        let splits = self.export_splits(&transactions).await.unwrap_or_default();
+        let range = self.export_range().unwrap_or(None);
+
+        let summary = json!({
+            "export_date": chrono::Utc::now().to_rfc3339(),
+            "user_id": self.current_user_id,
+            "start_date": range.map(|(start, _)| start.date_naive()),
+            "end_date": range.map(|(_, end)| (end - chrono::Duration::days(1)).date_naive()),
+            "accounts": self.accounts,
+            "transactions": transactions,
+            "categories": self.categories,
+            "transaction_categories": splits,
+            "recurring_transactions": self.recurring_transactions,
+        });
+
+        match serde_json::to_string_pretty(&summary) {
+            Ok(json) => {
+                match self.write_export_file("financial_summary.json", &json) {
+                    Ok(path) => {
+                        self.export_message = self.tr_fmt("Exported full financial summary to {}", &[&path]);
+                        self.status_message = self.export_message.clone();
+                    }
+                    Err(e) => {
+                        self.export_message = self.tr_fmt("Error exporting: {}", &[&e]);
+                        self.status_message = self.export_message.clone();
+                    }
+                }
             }
-            KeyCode::Tab => {
-                self.form_field_index = (self.form_field_index + 1) % 4;
+            Err(e) => {
+                self.export_message = self.tr_fmt("Error serializing: {}", &[&e]);
+                self.status_message = self.export_message.clone();
             }
-            KeyCode::BackTab => {
-                self.form_field_index = if self.form_field_index == 0 {
-                    3
-                } else {
-                    self.form_field_index - 1
-                };
+        }
+    }
+
+    async fn export_summary_xlsx(&mut self) {
+        let Some(user_id) = self.current_user_id else {
+            return;
+        };
+        let transactions = match self.export_transactions().await {
+            Ok(transactions) => transactions,
+            Err(e) => return self.export_failed(e),
+        };
+        let range = self.export_range().unwrap_or(None);
+        let monthly = match xlsx::monthly_summary(&self.pool, Some(user_id), range).await {
+            Ok(monthly) => monthly,
+            Err(e) => return self.export_failed(e),
+        };
+
+        let sheets = xlsx::summary_sheets(
+            &self.accounts,
+            &transactions,
+            &self.categories,
+            &self.transaction_categories,
+            &monthly,
+        );
+        match self.write_export_file("financial_summary.xlsx", xlsx::workbook(&sheets)) {
+            Ok(path) => {
+                self.export_message = self.tr_fmt("Exported summary workbook to {}", &[&path]);
+                self.status_message = self.export_message.clone();
             }
-            KeyCode::Char(c) => match self.form_field_index {
-                0 => self.form_from_currency.push(c),
-                1 => self.form_to_currency.push(c),
-                2 => self.form_rate.push(c),
-                3 => self.form_source.push(c),
-                _ => {}
-            },
-            KeyCode::Backspace => match self.form_field_index {
-                0 => {
-                    self.form_from_currency.pop();
-                }
-                1 => {
-                    self.form_to_currency.pop();
-                }
-                2 => {
-                    self.form_rate.pop();
-                }
-                3 => {
-                    self.form_source.pop();
-                }
-                _ => {}
-            },
-            KeyCode::Enter => {
-                self.submit_exchange_rate().await;
+            Err(e) => {
+                self.export_message = self.tr_fmt("Error exporting: {}", &[&e]);
+                self.status_message = self.export_message.clone();
             }
-            _ => {}
         }
     }
 
-    async fn handle_convert_currency_mode(&mut self, code: KeyCode) {
-        match code {
-            KeyCode::Esc => {
-                self.mode = Mode::Normal;
+    async fn export_statement_pdf(&mut self) {
+        let Some(user_id) = self.current_user_id else {
+            return;
+        };
+        // The export range, otherwise this month
+        let statement = match self.export_range().unwrap_or(None) {
+            Some((start, end)) => {
+                statements::period_statement(&self.pool, user_id, start.date_naive(), end.date_naive()).await
             }
-            KeyCode::Tab => {
-                self.form_field_index = (self.form_field_index + 1) % 3;
+            None => {
+                let today = Utc::now().date_naive();
+                statements::statement(&self.pool, user_id, today.with_day(1).unwrap_or(today)).await
             }
-            KeyCode::BackTab => {
-                self.form_field_index = if self.form_field_index == 0 {
-                    2
+        };
+        let result = match statement {
+            Ok(Some(statement)) => {
+                let name = if statement.is_monthly() {
+                    format!("statement_{}.pdf", statement.start.format("%Y-%m"))
                 } else {
-                    self.form_field_index - 1
+                    format!("statement_{}_{}.pdf", statement.start, statement.end - chrono::Duration::days(1))
                 };
+                self.write_export_file(&name, statements::render(&statement))
+                    .map(|path| (statement.period_label(), path))
             }
-            KeyCode::Char(c) => match self.form_field_index {
-                0 => self.form_convert_from.push(c),
-                1 => self.form_convert_to.push(c),
-                2 => self.form_convert_amount.push(c),
-                _ => {}
-            },
-            KeyCode::Backspace => match self.form_field_index {
-                0 => {
-                    self.form_convert_from.pop();
-                }
-                1 => {
-                    self.form_convert_to.pop();
-                }
-                2 => {
-                    self.form_convert_amount.pop();
-                }
-                _ => {}
-            },
-            KeyCode::Enter => {
-                self.perform_currency_conversion().await;
-            }
-            _ => {}
-        }
+            Ok(None) => Err(self.tr("User not found").to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        self.export_message = match result {
+            Ok((period, path)) => self.tr_fmt("Exported {} statement to {}", &[&period, &path]),
+            Err(e) => self.tr_fmt("Error exporting: {}", &[&e]),
+        };
+        self.status_message = self.export_message.clone();
     }
 
-    async fn submit_transaction(&mut self) {
-        let account_id = self.form_account_id.parse::<i64>();
-        let amount = self.form_amount.parse::<f64>();
-        let category_id = self.form_category_id.parse::<i64>();
-
-        if account_id.is_err() || amount.is_err() || category_id.is_err() {
-            self.status_message =
-                "Error: Invalid input! Check account ID, amount, and category ID.".to_string();
-            self.mode = Mode::Normal;
+    async fn export_transactions_qif(&mut self) {
+        if self.current_user_id.is_none() {
             return;
         }
-
-        let account_id = account_id.unwrap();
-        let amount = amount.unwrap();
-        let category_id = category_id.unwrap();
-        
-        // Normalize transaction type: accept i/e shortcuts
-        let txn_type = match self.form_type.to_lowercase().as_str() {
-            "i" | "income" => "income",
-            "e" | "expense" | "" => "expense",  // default to expense
-            _ => {
-                self.status_message = "Error: Type must be 'income' (i) or 'expense' (e)".to_string();
-                self.mode = Mode::Normal;
-                return;
-            }
+        let transactions = match self.export_transactions().await {
+            Ok(transactions) => transactions,
+            Err(e) => return self.export_failed(e),
         };
-        
-        let description = if self.form_description.is_empty() {
-            None
-        } else {
-            Some(self.form_description.clone())
+        let result = match self.export_splits(&transactions).await {
+            Ok(splits) => self.write_export_file(
+                "transactions_export.qif",
+                qif::render(&self.accounts, &transactions, &splits, &self.categories),
+            ),
+            Err(e) => Err(e),
         };
+        self.export_message = match result {
+            Ok(path) => self.tr_fmt("Exported {} transactions to {}", &[&transactions.len(), &path]),
+            Err(e) => self.tr_fmt("Error exporting: {}", &[&e]),
+        };
+        self.status_message = self.export_message.clone();
+    }
+
+    async fn process_recurring_transactions(&mut self) {
+        match recurring::process_due_recurring(&self.pool, Utc::now()).await {
+            Ok(result) => {
+                self.load_data().await;
+                self.status_message = self.tr_fmt("Processed {} recurring transactions - {} new transactions created.", &[&result.due, &result.created]);
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error processing recurring transactions: {}", &[&e]);
+            }
+        }
+    }
 
-        let now = chrono::Local::now().with_timezone(&Utc);
+    async fn toggle_recurring_active(&mut self) {
+        let recurring = &self.recurring_transactions[self.selected_index];
+        let new_status = !recurring.is_active;
 
         let result = sqlx::query(
-            "INSERT INTO transactions (account_id, amount, transaction_type, description, transaction_date) VALUES (?, ?, ?, ?, ?)"
+            "UPDATE recurring_transactions SET is_active = ? WHERE id = ?"
         )
-        .bind(account_id)
-        .bind(amount)
-        .bind(txn_type)
-        .bind(&description)
-        .bind(now)
+        .bind(new_status)
+        .bind(recurring.id)
         .execute(&self.pool)
         .await;
 
         match result {
-            Ok(res) => {
-                let transaction_id = res.last_insert_rowid();
-
-                let _ = sqlx::query(
-                    "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)"
-                )
-                .bind(transaction_id)
-                .bind(category_id)
-                .bind(amount)
-                .execute(&self.pool)
-                .await;
+            Ok(_) => {
+                self.status_message = self.tr_fmt("Recurring transaction {} {}", &[&recurring.id, &if new_status { "activated" } else { "paused" }]);
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error updating status: {}", &[&e]);
+            }
+        }
+    }
 
-                let balance_change = if txn_type == "income" {
-                    amount
-                } else {
-                    -amount.abs()
-                };
+    async fn toggle_account_favorite(&mut self) {
+        let account = &self.accounts[self.selected_index];
+        let account_id = account.id;
+        let new_status = !account.is_favorite;
 
-                let _ = sqlx::query(
-                    "UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?",
-                )
-                .bind(balance_change)
-                .bind(account_id)
-                .execute(&self.pool)
-                .await;
+        let result = sqlx::query("UPDATE accounts SET is_favorite = ? WHERE id = ?")
+            .bind(new_status)
+            .bind(account_id)
+            .execute(&self.pool)
+            .await;
 
-                self.status_message =
-                    format!("Transaction added successfully! ID: {}", transaction_id);
+        match result {
+            Ok(_) => {
+                self.status_message = self.tr_fmt("Account {} {}", &[&account.name, &if new_status { "starred" } else { "unstarred" }]);
                 self.load_data().await;
+                // Keep the cursor on the same account after it moves
+                if let Some(pos) = self.accounts.iter().position(|a| a.id == account_id) {
+                    self.selected_index = pos;
+                }
             }
             Err(e) => {
-                self.status_message = format!("Error adding transaction: {}", e);
+                self.status_message = self.tr_fmt("Error updating account: {}", &[&e]);
             }
         }
-
-        self.mode = Mode::Normal;
     }
 
-    async fn submit_exchange_rate(&mut self) {
-        let rate = self.form_rate.parse::<f64>();
-
-        if self.form_from_currency.is_empty() || self.form_to_currency.is_empty() || rate.is_err() {
-            self.status_message = "Error: Invalid input! Check currencies and rate.".to_string();
-            self.mode = Mode::Normal;
+    /// Pin the selected manual rate so it is used over newer quotes, or unpin it.
+    async fn toggle_rate_pin(&mut self) {
+        let rate = &self.exchange_rates[self.selected_index];
+        if rate.source != "manual" {
+            self.status_message = self.tr("Only manual rates can be pinned").to_string();
             return;
         }
+        let (rate_id, new_status) = (rate.id, !rate.pinned);
+        let label = format!("{} → {}", rate.from_currency, rate.to_currency);
 
-        let rate = rate.unwrap();
-        let from_currency = &self.form_from_currency;
-        let to_currency = &self.form_to_currency;
-        let source = &self.form_source;
+        match fx::set_pinned(&self.pool, rate_id, new_status).await {
+            Ok(_) => {
+                self.status_message = self.tr_fmt("Rate {} {}", &[&label, &if new_status { "pinned" } else { "unpinned" }]);
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error updating rate: {}", &[&e]);
+            }
+        }
+    }
 
-        let result = sqlx::query(
-            "INSERT INTO exchange_rates (from_currency, to_currency, rate, rate_date, source) VALUES (?, ?, ?, datetime('now'), ?)"
-        )
-        .bind(from_currency)
-        .bind(to_currency)
-        .bind(rate)
-        .bind(source)
-        .execute(&self.pool)
-        .await;
+    /// Re-run every category rule over the user's existing transactions.
+    /// Move the selected category to the next bucket: none -> needs -> wants -> savings -> none
+    async fn cycle_category_bucket(&mut self) {
+        let category = &self.categories[self.selected_index];
+        let next = match category.bucket.as_deref() {
+            None => Some(buckets::BUCKETS[0]),
+            Some(bucket) => buckets::BUCKETS
+                .iter()
+                .position(|b| *b == bucket)
+                .and_then(|i| buckets::BUCKETS.get(i + 1))
+                .copied(),
+        };
+        let (id, name) = (category.id, category.name.clone());
 
+        let result = sqlx::query("UPDATE categories SET bucket = ?, updated_at = datetime('now') WHERE id = ?")
+            .bind(next)
+            .bind(id)
+            .execute(&self.pool)
+            .await;
         match result {
-            Ok(res) => {
-                let rate_id = res.last_insert_rowid();
-                self.status_message = format!("Exchange rate added successfully! ID: {}", rate_id);
+            Ok(_) => {
+                self.status_message = self.tr_fmt("'{}' bucket: {}", &[&name, &next.unwrap_or("none")]);
                 self.load_data().await;
             }
-            Err(e) => {
-                self.status_message = format!("Error adding exchange rate: {}", e);
-            }
+            Err(e) => self.status_message = self.tr_fmt("Error setting bucket: {}", &[&e]),
         }
+    }
 
-        self.mode = Mode::Normal;
+    async fn toggle_category_excluded(&mut self) {
+        let category = &self.categories[self.selected_index];
+        let (id, name, excluded) = (category.id, category.name.clone(), !category.exclude_from_reports);
+        match exclusions::set_category_excluded(&self.pool, id, excluded).await {
+            Ok(_) => {
+                self.status_message = if excluded {
+                    self.tr_fmt("'{}' transactions excluded from reports", &[&name])
+                } else {
+                    self.tr_fmt("'{}' transactions included in reports again", &[&name])
+                };
+                self.load_data().await;
+            }
+            Err(e) => self.status_message = self.tr_fmt("Error updating category: {}", &[&e]),
+        }
     }
 
-    async fn perform_currency_conversion(&mut self) {
-        let amount = self.form_convert_amount.parse::<f64>();
+    async fn apply_category_rules(&mut self) {
+        let Some(user_id) = self.current_user_id else { return };
+        match rules::apply_rules(&self.pool, user_id).await {
+            Ok(updated) => {
+                self.status_message = self.tr_fmt("Applied category rules: {} transactions recategorized", &[&updated]);
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error applying category rules: {}", &[&e]);
+            }
+        }
+    }
 
-        if self.form_convert_from.is_empty() || self.form_convert_to.is_empty() || amount.is_err() {
-            self.form_converted_result = "Error: Invalid input!".to_string();
+    /// Swap the selected account with the one at `target` and persist the new order.
+    async fn move_account(&mut self, target: usize) {
+        let current = self.selected_index;
+        if self.accounts[current].is_favorite != self.accounts[target].is_favorite {
+            self.status_message = self.tr("Favorites are always listed first").to_string();
             return;
         }
 
-        let amount = amount.unwrap();
-        let from = &self.form_convert_from;
-        let to = &self.form_convert_to;
+        self.accounts.swap(current, target);
+        for (position, account) in self.accounts.iter().enumerate() {
+            if let Err(e) = sqlx::query("UPDATE accounts SET sort_order = ? WHERE id = ?")
+                .bind(position as i64)
+                .bind(account.id)
+                .execute(&self.pool)
+                .await
+            {
+                self.status_message = self.tr_fmt("Error reordering accounts: {}", &[&e]);
+                return;
+            }
+        }
 
-        // Query the latest exchange rate
-        let rate: Result<Option<f64>, _> = sqlx::query_scalar(
-            "SELECT rate FROM exchange_rates 
-             WHERE from_currency = ? AND to_currency LIKE ?
-             ORDER BY rate_date DESC 
-             LIMIT 1",
-        )
-        .bind(from)
-        .bind(format!("%({})%", to))
-        .fetch_optional(&self.pool)
-        .await;
+        self.selected_index = target;
+        self.status_message = self.tr_fmt("Moved {}", &[&self.accounts[target].name]);
+        self.load_data().await;
+    }
 
-        match rate {
-            Ok(Some(rate)) => {
-                let converted = amount * rate;
-                self.form_converted_result = format!(
-                    "{:.2} {} = {:.2} {} (rate: {:.6})",
-                    amount, from, converted, to, rate
-                );
-                self.status_message = "Conversion successful!".to_string();
+    async fn handle_account_group_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Char(c) => self.form_account_group.push(c),
+            KeyCode::Backspace => {
+                self.form_account_group.pop();
             }
-            Ok(None) => {
-                self.form_converted_result = format!("No rate found from {} to {}", from, to);
+            KeyCode::Enter => self.set_account_group().await,
+            _ => {}
+        }
+    }
+
+    /// Put the selected account in the typed group, creating it if it's new,
+    /// or take it out of its group when the name is empty
+    async fn set_account_group(&mut self) {
+        let (Some(user_id), Some(account)) = (self.current_user_id, self.accounts.get(self.selected_index)) else {
+            return;
+        };
+        let account_id = account.id;
+        let account_name = account.name.clone();
+        let name = self.form_account_group.trim().to_string();
+        let group = if name.is_empty() {
+            None
+        } else {
+            match account_groups::create_group(&self.pool, user_id, &name).await {
+                Ok(group) => Some(group),
+                Err(e) => {
+                    self.status_message = self.tr_fmt("Error creating group: {}", &[&e]);
+                    return;
+                }
             }
-            Err(e) => {
-                self.form_converted_result = format!("Database error: {}", e);
+        };
+
+        match account_groups::set_account_group(&self.pool, account_id, group.as_ref().map(|g| g.id)).await {
+            Ok(_) => {
+                self.status_message = match group {
+                    Some(group) => self.tr_fmt("{} is now in {}", &[&account_name, &group.name]),
+                    None => self.tr_fmt("{} is no longer in a group", &[&account_name]),
+                };
+                self.mode = Mode::Normal;
+                self.load_data().await;
+                // The grouped view may have moved the account
+                if let Some(position) = self.accounts.iter().position(|a| a.id == account_id) {
+                    self.selected_index = position;
+                }
             }
+            Err(e) => self.status_message = self.tr_fmt("Error updating account: {}", &[&e]),
         }
     }
 
-    async fn handle_delete_mode(&mut self, code: KeyCode) {
+    async fn handle_account_currency_mode(&mut self, code: KeyCode) {
+        let field = if self.form_field_index == 0 {
+            &mut self.form_new_currency
+        } else {
+            &mut self.form_currency_rate
+        };
         match code {
-            KeyCode::Char('y') | KeyCode::Char('Y') => {
-                if self.current_screen == Screen::Transactions
-                    && self.selected_index < self.transactions.len()
-                {
-                    let transaction = &self.transactions[self.selected_index];
-                    let transaction_id = transaction.id;
-                    let account_id = transaction.account_id;
-                    let amount = transaction.amount;
-                    let transaction_type = transaction.transaction_type.clone();
-
-                    // First update account balance (reverse the transaction effect)
-                    let balance_adjustment = if transaction_type == "income" {
-                        -amount  // Subtract income that was added
-                    } else {
-                        amount.abs()  // Add back expense that was subtracted
-                    };
+            KeyCode::Esc => self.mode = Mode::Normal,
+            // The rate only matters when there's history to convert
+            KeyCode::Tab if self.form_account_transactions > 0 => {
+                self.form_field_index = (self.form_field_index + 1) % 2
+            }
+            KeyCode::Char(c) => field.push(c),
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            KeyCode::Enter => self.change_account_currency().await,
+            _ => {}
+        }
+    }
 
-                    let balance_result = sqlx::query(
-                        "UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?"
-                    )
-                        .bind(balance_adjustment)
-                        .bind(account_id)
-                        .execute(&self.pool)
-                        .await;
+    /// Switch the selected account to the typed currency. Without transactions
+    /// it's only re-labelled; with them, everything is converted at the typed
+    /// rate (or the latest FX rate), as PUT /accounts/{id}?currency_mode=convert
+    /// does, since re-labelling would misstate every past amount.
+    async fn change_account_currency(&mut self) {
+        let Some(account) = self.accounts.get(self.selected_index).cloned() else {
+            return;
+        };
+        let currency = self.form_new_currency.trim().to_uppercase();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            self.status_message = self.tr("Enter a 3-letter currency code").to_string();
+            return;
+        }
+        if currency == account.currency {
+            self.status_message = self.tr_fmt("{} is already in {}", &[&account.name, &currency]);
+            return;
+        }
 
-                    if let Err(e) = balance_result {
-                        self.status_message = format!("Error updating account balance: {}", e);
-                        self.mode = Mode::Normal;
+        let result = if self.form_account_transactions == 0 {
+            sqlx::query("UPDATE accounts SET currency = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+                .bind(&currency)
+                .bind(account.id)
+                .execute(&self.pool)
+                .await
+                .map(|_| self.tr_fmt("{} is now in {}", &[&account.name, &currency]))
+        } else {
+            let typed = self.form_currency_rate.trim();
+            let rate = if typed.is_empty() {
+                match fx::conversion_rate(&self.pool, &account.currency, &currency, None).await {
+                    Ok(Some(rate)) => rate,
+                    Ok(None) => {
+                        self.status_message = self.tr_fmt("No exchange rate from {} to {}; type one in the Rate field", &[&account.currency, &currency]);
                         return;
                     }
-
-                    // Then delete the transaction
-                    let result = sqlx::query("DELETE FROM transactions WHERE id = ?")
-                        .bind(transaction_id)
-                        .execute(&self.pool)
-                        .await;
-
-                    match result {
-                        Ok(_) => {
-                            self.status_message =
-                                format!("Transaction {} deleted, balance updated!", transaction_id);
-                            self.load_data().await;
-                            self.selected_index = 0;
-                        }
-                        Err(e) => {
-                            self.status_message = format!("Error deleting transaction: {}", e);
-                        }
+                    Err(e) => {
+                        self.status_message = self.tr_fmt("Error looking up the rate: {}", &[&e]);
+                        return;
                     }
-                } else if self.current_screen == Screen::Accounts
-                    && self.selected_index < self.accounts.len()
-                {
-                    let account = &self.accounts[self.selected_index];
-                    let account_id = account.id;
-                    let account_name = account.name.clone();
-
-                    // First delete all transaction_categories for this account's transactions
-                    let _ = sqlx::query(
-                        "DELETE FROM transaction_categories WHERE transaction_id IN (SELECT id FROM transactions WHERE account_id = ?)"
+                }
+            } else {
+                match typed.parse::<f64>() {
+                    Ok(rate) if rate > 0.0 => rate,
+                    _ => {
+                        self.status_message = self.tr("Rate must be a positive number").to_string();
+                        return;
+                    }
+                }
+            };
+            accounts::convert_currency(&self.pool, &account, &currency, rate)
+                .await
+                .map(|converted| {
+                    self.tr_fmt(
+                        "Converted {} to {} at {}: balance {} {}, {} transactions keep their {} amounts as originals",
+                        &[
+                            &account.name,
+                            &currency,
+                            &format!("{:.4}", rate),
+                            &format!("{:.2}", converted.current_balance),
+                            &currency,
+                            &self.form_account_transactions,
+                            &account.currency,
+                        ],
                     )
-                        .bind(account_id)
-                        .execute(&self.pool)
-                        .await;
-
-                    // Then delete all transactions for this account
-                    let txn_result = sqlx::query("DELETE FROM transactions WHERE account_id = ?")
-                        .bind(account_id)
-                        .execute(&self.pool)
-                        .await;
-
-                    let txn_deleted = txn_result.map(|r| r.rows_affected()).unwrap_or(0);
-
-                    // Finally delete the account
-                    let result = sqlx::query("DELETE FROM accounts WHERE id = ?")
-                        .bind(account_id)
-                        .execute(&self.pool)
-                        .await;
+                })
+        };
 
-                    match result {
-                        Ok(_) => {
-                            if txn_deleted > 0 {
-                                self.status_message = format!("Account '{}' and {} transactions deleted!", account_name, txn_deleted);
-                            } else {
-                                self.status_message = format!("Account '{}' deleted!", account_name);
-                            }
-                            self.load_data().await;
-                            self.selected_index = 0;
-                        }
-                        Err(e) => {
-                            self.status_message = format!("Error deleting account: {}", e);
-                        }
-                    }
-                    self.mode = Mode::Normal;
-                } else if self.current_screen == Screen::Categories
-                    && self.selected_index < self.categories.len()
-                {
-                    let category = &self.categories[self.selected_index];
-                    let category_id = category.id;
-                    let category_name = category.name.clone();
+        match result {
+            Ok(message) => {
+                self.status_message = message;
+                self.mode = Mode::Normal;
+                self.load_data().await;
+            }
+            Err(e) => self.status_message = self.tr_fmt("Error updating account: {}", &[&e]),
+        }
+    }
 
-                    // Check if category is linked to transactions
-                    let link_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM transaction_categories WHERE category_id = ?")
-                        .bind(category_id)
-                        .fetch_one(&self.pool)
-                        .await
-                        .unwrap_or((0,));
+    fn render_account_currency_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 6,
+            y: area.y + area.height.saturating_sub(9) / 2,
+            width: area.width * 2 / 3,
+            height: 9.min(area.height),
+        };
 
-                    if link_count.0 > 0 {
-                        self.status_message = format!("Cannot delete '{}': used by {} transactions.", category_name, link_count.0);
-                        self.mode = Mode::Normal;
-                        return;
-                    }
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
 
-                    let result = sqlx::query("DELETE FROM categories WHERE id = ?")
-                        .bind(category_id)
-                        .execute(&self.pool)
-                        .await;
+        let Some(account) = self.accounts.get(self.selected_index) else {
+            return;
+        };
+        let field_style = |index: usize| {
+            if self.form_field_index == index {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        let cursor = |index: usize| if self.form_field_index == index { "_" } else { "" };
+        let target = match self.form_new_currency.trim() {
+            "" => "?".to_string(),
+            typed => typed.to_uppercase(),
+        };
+        let consequence = if self.form_account_transactions == 0 {
+            self.tr("No transactions yet: the currency is just changed").to_string()
+        } else {
+            self.tr_fmt(
+                "{} transactions: balances and amounts are converted, the {} ones kept as originals",
+                &[&self.form_account_transactions, &account.currency],
+            )
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(self.tr("Account: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{} ({})", account.name, account.currency),
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("New currency: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}{}", self.form_new_currency, cursor(0)), field_style(0)),
+            ]),
+        ];
+        if self.form_account_transactions > 0 {
+            lines.push(Line::from(vec![
+                Span::styled(self.tr("Rate: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}{}", self.form_currency_rate, cursor(1)), field_style(1)),
+                Span::styled(
+                    self.tr_fmt(" {} per {}, empty for the latest FX rate", &[&target, &account.currency]),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(consequence, Style::default().fg(Color::DarkGray))));
 
-                    match result {
-                        Ok(_) => {
-                            self.status_message = format!("Category '{}' deleted!", category_name);
-                            self.load_data().await;
-                            self.selected_index = 0;
-                        }
-                        Err(e) => {
-                            self.status_message = format!("Error deleting category: {}", e);
-                        }
-                    }
-                    self.mode = Mode::Normal;
-                } else if self.current_screen == Screen::ExchangeRates
-                    && self.selected_index < self.exchange_rates.len()
-                {
-                    let rate_id = self.exchange_rates[self.selected_index].id;
+        let form = Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Account Currency")));
+        frame.render_widget(form, popup_area);
+    }
 
-                    let result = sqlx::query("DELETE FROM exchange_rates WHERE id = ?")
-                        .bind(rate_id)
-                        .execute(&self.pool)
-                        .await;
+    fn render_account_group_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(7) / 2,
+            width: area.width / 2,
+            height: 7.min(area.height),
+        };
 
-                    match result {
-                        Ok(_) => {
-                            self.status_message =
-                                format!("Exchange rate {} deleted successfully!", rate_id);
-                            self.load_data().await;
-                            self.selected_index = 0;
-                        }
-                        Err(e) => {
-                            self.status_message = format!("Error deleting exchange rate: {}", e);
-                        }
-                    }
-                } else if self.current_screen == Screen::RecurringTransactions
-                    && self.selected_index < self.recurring_transactions.len()
-                {
-                    let recurring_id = self.recurring_transactions[self.selected_index].id;
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
 
-                    let result = sqlx::query("DELETE FROM recurring_transactions WHERE id = ?")
-                        .bind(recurring_id)
-                        .execute(&self.pool)
-                        .await;
+        let account = self
+            .accounts
+            .get(self.selected_index)
+            .map(|a| a.name.as_str())
+            .unwrap_or("-");
+        let existing: Vec<&str> = self.account_groups.iter().map(|g| g.name.as_str()).collect();
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(self.tr("Account: "), Style::default().fg(Color::Gray)),
+                Span::styled(account, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Group: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}_", self.form_account_group), Style::default().fg(Color::Yellow)),
+            ]),
+            Line::from(Span::styled(
+                if existing.is_empty() {
+                    self.tr("No groups yet").to_string()
+                } else {
+                    self.tr_fmt("Existing: {}", &[&existing.join(", ")])
+                },
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
 
-                    match result {
-                        Ok(_) => {
-                            self.status_message =
-                                format!("Recurring transaction {} deleted successfully!", recurring_id);
-                            self.load_data().await;
-                            self.selected_index = 0;
-                        }
-                        Err(e) => {
-                            self.status_message = format!("Error deleting recurring transaction: {}", e);
-                        }
-                    }
-                }
+        let form = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Account Group")));
+        frame.render_widget(form, popup_area);
+    }
+
+    async fn handle_details_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                // Clear account-specific view currency when exiting details
+                self.account_view_currency = None;
                 self.mode = Mode::Normal;
             }
-            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                self.mode = Mode::Normal;
+            KeyCode::Char('v') if self.current_screen == Screen::Accounts => {
+                // Open currency conversion selector when viewing account details
+                self.mode = Mode::SelectViewCurrency;
+            }
+            KeyCode::Char('n')
+                if self.current_screen == Screen::Transactions
+                    && self.selected_transaction().is_some() =>
+            {
+                // Edit notes/reference of the selected transaction
+                let Some(t) = self.selected_transaction().cloned() else { return };
+                self.form_notes = t.notes.clone().unwrap_or_default();
+                self.form_reference = t.reference.clone().unwrap_or_default();
+                self.form_field_index = 0;
+                self.mode = Mode::EditTransactionNotes;
+            }
+            KeyCode::Char('T')
+                if self.current_screen == Screen::Transactions
+                    && self.selected_transaction().is_some() =>
+            {
+                self.form_tag.clear();
+                self.mode = Mode::EditTags;
+            }
+            KeyCode::Char('E')
+                if self.current_screen == Screen::Transactions
+                    && self.selected_transaction().is_some() =>
+            {
+                self.toggle_transaction_excluded().await;
             }
             _ => {}
         }
     }
 
-    async fn handle_add_recurring_mode(&mut self, code: KeyCode) {
+    async fn toggle_transaction_excluded(&mut self) {
+        let Some(t) = self.selected_transaction() else { return };
+        let (id, excluded) = (t.id, !t.exclude_from_reports);
+        match exclusions::set_transaction_excluded(&self.pool, id, excluded).await {
+            Ok(_) => {
+                self.status_message = if excluded {
+                    self.tr_fmt("Transaction {} excluded from reports", &[&id])
+                } else {
+                    self.tr_fmt("Transaction {} included in reports again", &[&id])
+                };
+                self.load_data().await;
+            }
+            Err(e) => self.status_message = self.tr_fmt("Error updating transaction: {}", &[&e]),
+        }
+    }
+
+    fn render_edit_notes_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let field_style = |index: usize| {
+            if self.form_field_index == index {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+
+        let txn_id = self
+            .transactions
+            .get(self.selected_index)
+            .map(|t| t.id.to_string())
+            .unwrap_or_default();
+
+        let form_text = vec![
+            Line::from(Span::styled(
+                self.tr_fmt("Edit Notes - Transaction {}", &[&txn_id]),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Reference: "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_reference, field_style(0)),
+            ]),
+            Line::from(vec![Span::styled(
+                self.tr("  (check number or bank reference)"),
+                Style::default().fg(Color::DarkGray),
+            )]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Notes: "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_notes, field_style(1)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                self.help("Tab: Next field | Enter: Save | Esc: Cancel"),
+                Style::default().fg(Color::Cyan),
+            )),
+        ];
+
+        let form = Paragraph::new(form_text)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Edit Notes")))
+            .alignment(Alignment::Left)
+            .wrap(ratatui::widgets::Wrap { trim: false });
+        frame.render_widget(form, area);
+    }
+
+    async fn handle_edit_notes_mode(&mut self, code: KeyCode) {
         match code {
             KeyCode::Esc => {
-                self.mode = Mode::Normal;
-            }
-            KeyCode::Tab => {
-                self.form_field_index = (self.form_field_index + 1) % 6;
+                self.mode = Mode::ViewDetails;
             }
-            KeyCode::BackTab => {
-                self.form_field_index = if self.form_field_index == 0 {
-                    5
-                } else {
-                    self.form_field_index - 1
-                };
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.form_field_index = (self.form_field_index + 1) % 2;
             }
             KeyCode::Char(c) => match self.form_field_index {
-                0 => self.form_account_id.push(c),
-                1 => self.form_amount.push(c),
-                2 => self.form_type.push(c),
-                3 => self.form_description.push(c),
-                4 => self.form_category_id.push(c),
-                5 => self.form_recurring_frequency.push(c),
+                0 => self.form_reference.push(c),
+                1 => self.form_notes.push(c),
                 _ => {}
             },
             KeyCode::Backspace => match self.form_field_index {
-                0 => { self.form_account_id.pop(); }
-                1 => { self.form_amount.pop(); }
-                2 => { self.form_type.pop(); }
-                3 => { self.form_description.pop(); }
-                4 => { self.form_category_id.pop(); }
-                5 => { self.form_recurring_frequency.pop(); }
+                0 => {
+                    self.form_reference.pop();
+                }
+                1 => {
+                    self.form_notes.pop();
+                }
                 _ => {}
             },
             KeyCode::Enter => {
-                self.submit_recurring_transaction().await;
+                self.submit_transaction_notes().await;
             }
             _ => {}
         }
     }
 
-    async fn submit_recurring_transaction(&mut self) {
-        let account_id = self.form_account_id.parse::<i64>();
-        let amount = self.form_amount.parse::<f64>();
-        let category_id = self.form_category_id.parse::<i64>().ok();
+    async fn handle_edit_tags_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.mode = Mode::ViewDetails,
+            KeyCode::Char(c) => self.form_tag.push(c),
+            KeyCode::Backspace => {
+                self.form_tag.pop();
+            }
+            KeyCode::Enter => self.toggle_transaction_tag().await,
+            _ => {}
+        }
+    }
 
-        if account_id.is_err() || amount.is_err() {
-            self.status_message = "Error: Invalid input! Check account ID and amount.".to_string();
+    /// Put the typed tag on the selected transaction, or take it off if the
+    /// transaction already has it. Stays in the tag form for the next one.
+    async fn toggle_transaction_tag(&mut self) {
+        let (Some(user_id), Some(txn_id)) = (self.current_user_id, self.selected_transaction().map(|t| t.id)) else {
             self.mode = Mode::Normal;
             return;
+        };
+        let name = match tags::normalize_tag(&self.form_tag) {
+            Ok(name) => name,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error: {}", &[&e]);
+                return;
+            }
+        };
+        let tag = match tags::create_tag(&self.pool, user_id, &name).await {
+            Ok(tag) => tag,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error creating tag: {}", &[&e]);
+                return;
+            }
+        };
+
+        let tagged = self.transaction_tags.get(&txn_id).is_some_and(|names| names.contains(&name));
+        let result = if tagged {
+            tags::remove_tag(&self.pool, txn_id, tag.id).await.map(|_| "removed")
+        } else {
+            tags::add_tag(&self.pool, txn_id, tag.id).await.map(|_| "added")
+        };
+        match result {
+            Ok(action) => {
+                self.status_message = self.tr_fmt("Tag #{} {}", &[&name, &action]);
+                self.form_tag.clear();
+                if let Ok(tags) = tags::tags_by_transaction(&self.pool, user_id).await {
+                    self.transaction_tags = tags;
+                }
+            }
+            Err(e) => self.status_message = self.tr_fmt("Error updating tags: {}", &[&e]),
         }
+    }
 
-        let account_id = account_id.unwrap();
-        let amount = amount.unwrap();
-        
-        // Normalize transaction type: accept i/e shortcuts
-        let txn_type = match self.form_type.to_lowercase().as_str() {
-            "i" | "income" => "income",
-            "e" | "expense" | "" => "expense",
-            _ => {
-                self.status_message = "Error: Type must be 'income' (i) or 'expense' (e)".to_string();
+    fn render_tag_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(6) / 2,
+            width: area.width / 2,
+            height: 6.min(area.height),
+        };
+
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+        let current = self
+            .selected_transaction()
+            .and_then(|t| self.transaction_tags.get(&t.id))
+            .map(|names| names.iter().map(|n| format!("#{}", n)).collect::<Vec<_>>().join(" "))
+            .unwrap_or_else(|| self.tr("None").to_string());
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(self.tr("Tags: "), Style::default().fg(Color::Gray)),
+                Span::styled(current, Style::default().fg(Color::Magenta)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Tag: #"), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}_", self.form_tag), Style::default().fg(Color::Yellow)),
+            ]),
+        ];
+
+        let form = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Transaction Tags")));
+        frame.render_widget(form, popup_area);
+    }
+
+    async fn submit_transaction_notes(&mut self) {
+        let txn_id = match self.selected_transaction() {
+            Some(t) => t.id,
+            None => {
                 self.mode = Mode::Normal;
                 return;
             }
         };
-        
-        let description = if self.form_description.is_empty() {
+
+        let notes = if self.form_notes.trim().is_empty() {
             None
         } else {
-            Some(self.form_description.clone())
+            Some(self.form_notes.trim().to_string())
         };
-        
-        // Normalize frequency: accept shortcuts d/w/m/y
-        let frequency = match self.form_recurring_frequency.to_lowercase().as_str() {
-            "d" | "daily" => "daily",
-            "w" | "weekly" => "weekly",
-            "m" | "monthly" | "" => "monthly",
-            "y" | "yearly" => "yearly",
-            _ => {
-                self.status_message = "Error: Frequency must be daily(d)/weekly(w)/monthly(m)/yearly(y)".to_string();
-                self.mode = Mode::Normal;
-                return;
-            }
+        let reference = if self.form_reference.trim().is_empty() {
+            None
+        } else {
+            Some(self.form_reference.trim().to_string())
         };
 
-        let now = chrono::Utc::now();
         let result = sqlx::query(
-            "INSERT INTO recurring_transactions 
-             (account_id, category_id, amount, transaction_type, description, frequency, start_date, next_occurrence, is_active) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1)"
+            "UPDATE transactions SET notes = ?, reference = ?, updated_at = datetime('now') WHERE id = ?",
         )
-        .bind(account_id)
-        .bind(category_id)
-        .bind(amount)
-        .bind(txn_type)
-        .bind(&description)
-        .bind(frequency)
-        .bind(now)
-        .bind(now)
+        .bind(&notes)
+        .bind(&reference)
+        .bind(txn_id)
         .execute(&self.pool)
         .await;
 
-        match result {
-            Ok(res) => {
-                let recurring_id = res.last_insert_rowid();
-                self.status_message = format!("Recurring transaction added successfully! ID: {}", recurring_id);
-                self.load_data().await;
-            }
-            Err(e) => {
-                self.status_message = format!("Error adding recurring transaction: {}", e);
-            }
-        }
+        match result {
+            Ok(_) => {
+                self.status_message = self.tr_fmt("Transaction {} notes updated!", &[&txn_id]);
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error updating notes: {}", &[&e]);
+            }
+        }
+
+        self.mode = Mode::ViewDetails;
+    }
+
+    /// Category picker for the selected transaction, starting at its current category
+    async fn open_category_picker(&mut self) {
+        if self.categories.is_empty() {
+            self.status_message = self.tr("Add a category before recategorizing transactions").to_string();
+            return;
+        }
+
+        let Some(txn_id) = self.selected_transaction().map(|t| t.id) else { return };
+        let current: Option<i64> = sqlx::query_scalar(
+            "SELECT category_id FROM transaction_categories WHERE transaction_id = ? ORDER BY amount DESC LIMIT 1",
+        )
+        .bind(txn_id)
+        .fetch_optional(&self.pool)
+        .await
+        .unwrap_or(None);
+
+        self.category_picker_index = current
+            .and_then(|id| self.categories.iter().position(|c| c.id == id))
+            .unwrap_or(0);
+        self.mode = Mode::Recategorize;
+    }
+
+    async fn handle_recategorize_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Up => {
+                self.category_picker_index = self.category_picker_index.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                self.category_picker_index =
+                    (self.category_picker_index + 1).min(self.categories.len().saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                self.recategorize_selected_transaction().await;
+                self.mode = Mode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    /// Book the whole selected transaction to the highlighted category, replacing its splits
+    async fn recategorize_selected_transaction(&mut self) {
+        let (Some(txn), Some(category)) = (
+            self.selected_transaction(),
+            self.categories.get(self.category_picker_index),
+        ) else {
+            return;
+        };
+        let (txn_id, amount) = (txn.id, txn.amount.abs());
+        let (category_id, category_name) = (category.id, category.name.clone());
+
+        let result = match &self.remote {
+            Some(remote) => {
+                let data = UpdateTransaction {
+                    categories: Some(vec![CategoryAmount { category_id, amount }]),
+                    ..Default::default()
+                };
+                remote.update_transaction(txn_id, &data).await
+            }
+            None => async {
+                let mut tx = self.pool.begin().await?;
+                sqlx::query("DELETE FROM transaction_categories WHERE transaction_id = ?")
+                    .bind(txn_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    "INSERT INTO transaction_categories (transaction_id, category_id, amount) VALUES (?, ?, ?)",
+                )
+                .bind(txn_id)
+                .bind(category_id)
+                .bind(amount)
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await
+            }
+            .await
+            .map_err(|e| e.to_string()),
+        };
+
+        match result {
+            Ok(_) => {
+                self.status_message = self.tr_fmt("Transaction {} moved to {}", &[&txn_id, &category_name]);
+                self.sync_remote().await;
+                self.load_data().await;
+            }
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error recategorizing transaction: {}", &[&e]);
+            }
+        }
+    }
+
+    /// Set-budget form, prefilled from the selected budget when editing
+    fn open_budget_form(&mut self, row: Option<usize>) {
+        if self.categories.is_empty() {
+            self.status_message = self.tr("Add a category before setting a budget").to_string();
+            return;
+        }
+
+        let selected = row.and_then(|i| self.budget_variance.get(i));
+        self.budget_category_index = selected
+            .and_then(|r| self.categories.iter().position(|c| c.id == r.category_id))
+            .unwrap_or(0);
+        self.form_budget_limit = selected
+            .map(|r| format!("{:.2}", r.budgeted))
+            .unwrap_or_default();
+        self.mode = Mode::SetBudget;
+    }
+
+    async fn handle_set_budget_mode(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Left | KeyCode::Up => {
+                self.budget_category_index = self
+                    .budget_category_index
+                    .checked_sub(1)
+                    .unwrap_or(self.categories.len().saturating_sub(1));
+            }
+            KeyCode::Right | KeyCode::Down => {
+                self.budget_category_index =
+                    (self.budget_category_index + 1) % self.categories.len().max(1);
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
+                self.form_budget_limit.push(c);
+            }
+            KeyCode::Backspace => {
+                self.form_budget_limit.pop();
+            }
+            KeyCode::Enter => {
+                let (Some(user_id), Some(category)) = (
+                    self.current_user_id,
+                    self.categories.get(self.budget_category_index),
+                ) else {
+                    return;
+                };
+                let limit = match self.form_budget_limit.parse::<f64>() {
+                    Ok(limit) if limit > 0.0 => limit,
+                    _ => {
+                        self.status_message = self.tr("Monthly limit must be a positive number").to_string();
+                        return;
+                    }
+                };
+                let category_name = category.name.clone();
+
+                match budgets::set_budget(&self.pool, user_id, category.id, limit).await {
+                    Ok(_) => {
+                        self.status_message =
+                            self.tr_fmt("Budget for {} set to {}/month", &[&category_name, &format!("{:.2}", limit)]);
+                        self.load_data().await;
+                    }
+                    Err(e) => {
+                        self.status_message = self.tr_fmt("Error setting budget: {}", &[&e]);
+                    }
+                }
+                self.mode = Mode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    fn render_budget_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(6) / 2,
+            width: area.width / 2,
+            height: 6.min(area.height),
+        };
+
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+        let category = self
+            .categories
+            .get(self.budget_category_index)
+            .map(|c| c.name.as_str())
+            .unwrap_or("-");
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(self.tr("Category: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("◄ {} ►", category),
+                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(self.tr("Monthly limit: "), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{}_", self.form_budget_limit),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]),
+        ];
+
+        let form = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Set Budget")));
+        frame.render_widget(form, popup_area);
+    }
 
-        self.mode = Mode::Normal;
+    /// Add-goal form, saving up in the first open account unless another is picked
+    fn open_goal_form(&mut self) {
+        if self.accounts.is_empty() {
+            self.status_message = self.tr("Add an account to save the goal in first").to_string();
+            return;
+        }
+        self.goal_field = 0;
+        self.goal_account_index = 0;
+        self.form_goal_name.clear();
+        self.form_goal_target.clear();
+        self.form_goal_date.clear();
+        self.mode = Mode::AddGoal;
     }
 
-    async fn handle_export_mode(&mut self, code: KeyCode) {
+    async fn handle_add_goal_mode(&mut self, code: KeyCode) {
+        let count = self.accounts.len().max(1);
         match code {
-            KeyCode::Esc => {
-                self.mode = Mode::Normal;
-            }
-            KeyCode::Char('1') => {
-                self.export_transactions_csv().await;
-                self.mode = Mode::Normal;
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Tab | KeyCode::Down => self.goal_field = (self.goal_field + 1) % 4,
+            KeyCode::BackTab | KeyCode::Up => self.goal_field = (self.goal_field + 3) % 4,
+            KeyCode::Left if self.goal_field == 3 => {
+                self.goal_account_index = (self.goal_account_index + count - 1) % count;
             }
-            KeyCode::Char('2') => {
-                self.export_transactions_json().await;
-                self.mode = Mode::Normal;
+            KeyCode::Right if self.goal_field == 3 => {
+                self.goal_account_index = (self.goal_account_index + 1) % count;
             }
-            KeyCode::Char('3') => {
-                self.export_accounts_csv().await;
-                self.mode = Mode::Normal;
+            KeyCode::Char(c) => match self.goal_field {
+                0 => self.form_goal_name.push(c),
+                1 if c.is_ascii_digit() || c == '.' => self.form_goal_target.push(c),
+                2 if c.is_ascii_digit() || c == '-' => self.form_goal_date.push(c),
+                _ => {}
+            },
+            KeyCode::Backspace => {
+                match self.goal_field {
+                    0 => self.form_goal_name.pop(),
+                    1 => self.form_goal_target.pop(),
+                    2 => self.form_goal_date.pop(),
+                    _ => None,
+                };
             }
-            KeyCode::Char('4') => {
-                self.export_full_summary().await;
+            KeyCode::Enter => {
+                let (Some(user_id), Some(account)) =
+                    (self.current_user_id, self.accounts.get(self.goal_account_index))
+                else {
+                    return;
+                };
+                let target_date = match self.form_goal_date.trim() {
+                    "" => None,
+                    date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                        Ok(date) => Some(date),
+                        Err(_) => {
+                            self.status_message = self.tr("Target date must be YYYY-MM-DD (or empty)").to_string();
+                            return;
+                        }
+                    },
+                };
+                let data = CreateGoal {
+                    user_id,
+                    name: self.form_goal_name.trim().to_string(),
+                    target_amount: self.form_goal_target.parse().unwrap_or(0.0),
+                    currency: Some(account.currency.clone()),
+                    target_date,
+                    account_ids: vec![account.id],
+                };
+                if let Err(e) = data.validate() {
+                    self.status_message = e;
+                    return;
+                }
+                let currency = account.currency.clone();
+                match goals::create_goal(&self.pool, &data, &currency).await {
+                    Ok(goal) => {
+                        self.status_message = self.tr_fmt("Goal {} added: {} {}", &[&goal.name, &format!("{:.2}", goal.target_amount), &currency]);
+                        self.load_data().await;
+                    }
+                    Err(e) => {
+                        self.status_message = self.tr_fmt("Error adding goal: {}", &[&e]);
+                    }
+                }
                 self.mode = Mode::Normal;
             }
             _ => {}
         }
     }
 
-    async fn export_transactions_csv(&mut self) {
-        let mut csv = String::from("id,account_id,amount,type,description,date\n");
+    fn render_goal_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(8) / 2,
+            width: area.width / 2,
+            height: 8.min(area.height),
+        };
 
-        for t in &self.transactions {
-            csv.push_str(&format!(
-                "{},{},{:.2},{},\"{}\",{}\n",
-                t.id,
-                t.account_id,
-                t.amount,
-                t.transaction_type,
-                t.description.as_deref().unwrap_or("").replace("\"", "\"\""),
-                t.transaction_date.format("%Y-%m-%d %H:%M:%S")
-            ));
-        }
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
 
-        match std::fs::write("transactions_export.csv", &csv) {
-            Ok(_) => {
-                self.export_message = format!("Exported {} transactions to transactions_export.csv", self.transactions.len());
-                self.status_message = self.export_message.clone();
-            }
-            Err(e) => {
-                self.export_message = format!("Error exporting: {}", e);
-                self.status_message = self.export_message.clone();
+        let account = self
+            .accounts
+            .get(self.goal_account_index)
+            .map(|a| format!("{} ({:.2} {})", a.name, a.current_balance, a.currency))
+            .unwrap_or_else(|| "-".to_string());
+        let value_style = |field: usize| {
+            if self.goal_field == field {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
             }
-        }
+        };
+        let cursor = |field: usize| if self.goal_field == field { "_" } else { "" };
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(self.tr("Name:        "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}{}", self.form_goal_name, cursor(0)), value_style(0)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Target:      "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}{}", self.form_goal_target, cursor(1)), value_style(1)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Target date: "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}{}", self.form_goal_date, cursor(2)), value_style(2)),
+                Span::styled(self.tr("  YYYY-MM-DD, optional"), Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Saved in:    "), Style::default().fg(Color::Gray)),
+                Span::styled(format!("◄ {} ►", account), value_style(3)),
+            ]),
+        ];
+
+        let form = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Add Goal")));
+        frame.render_widget(form, popup_area);
     }
 
-    async fn export_transactions_json(&mut self) {
-        match serde_json::to_string_pretty(&self.transactions) {
-            Ok(json) => {
-                match std::fs::write("transactions_export.json", &json) {
-                    Ok(_) => {
-                        self.export_message = format!("Exported {} transactions to transactions_export.json", self.transactions.len());
-                        self.status_message = self.export_message.clone();
-                    }
-                    Err(e) => {
-                        self.export_message = format!("Error exporting: {}", e);
-                        self.status_message = self.export_message.clone();
-                    }
-                }
-            }
-            Err(e) => {
-                self.export_message = format!("Error serializing: {}", e);
-                self.status_message = self.export_message.clone();
-            }
+    /// Add-holding wizard, in the account of the selected holding by default
+    fn open_holding_wizard(&mut self) {
+        let accounts: Vec<(String, String)> = self
+            .accounts
+            .iter()
+            .filter(|a| a.account_type == "investment" && !a.is_archived)
+            .map(|a| (a.id.to_string(), format!("{} [{}]", a.name, a.currency)))
+            .collect();
+        if accounts.is_empty() {
+            self.status_message = self.tr("Add an investment account first").to_string();
+            return;
         }
+        let account = self.selected_holding().map(|h| h.holding.account_id.to_string()).unwrap_or_default();
+        self.wizard = Some(Wizard::new(
+            "Add Holding",
+            vec![Step::new(
+                "Holding",
+                vec![
+                    Field::choice("account", "Account: ", accounts).with_choice(&account),
+                    Field::text("ticker", "Ticker: ").with_hint("e.g. AAPL, VFV.TO"),
+                    Field::text("quantity", "Quantity: "),
+                    Field::text("cost_basis", "Cost Basis: ").with_hint("total paid"),
+                ],
+            )],
+        ));
+        self.mode = Mode::AddHolding;
     }
 
-    async fn export_accounts_csv(&mut self) {
-        let mut csv = String::from("id,user_id,name,type,bank_name,currency,initial_balance,current_balance\n");
-
-        for a in &self.accounts {
-            csv.push_str(&format!(
-                "{},{},\"{}\",{},\"{}\",{},{:.2},{:.2}\n",
-                a.id,
-                a.user_id,
-                a.name.replace("\"", "\"\""),
-                a.account_type,
-                a.bank_name.as_deref().unwrap_or("").replace("\"", "\"\""),
-                a.currency,
-                a.initial_balance,
-                a.current_balance
-            ));
+    /// The holding entered in the holding wizard
+    fn holding_from_wizard(wizard: &Wizard) -> CreateHolding {
+        CreateHolding {
+            ticker: wizard.value("ticker").trim().to_string(),
+            quantity: wizard.value("quantity").trim().parse().unwrap_or(0.0),
+            cost_basis: match wizard.value("cost_basis").trim() {
+                "" => 0.0,
+                cost => cost.parse().unwrap_or(-1.0),
+            },
         }
+    }
 
-        match std::fs::write("accounts_export.csv", &csv) {
-            Ok(_) => {
-                self.export_message = format!("Exported {} accounts to accounts_export.csv", self.accounts.len());
-                self.status_message = self.export_message.clone();
+    async fn handle_add_holding_mode(&mut self, code: KeyCode) {
+        if !self.wizard_key(code, |_, wizard| Self::holding_from_wizard(wizard).validate()) {
+            return;
+        }
+        let Some(wizard) = self.wizard.as_ref() else {
+            return;
+        };
+        let data = Self::holding_from_wizard(wizard);
+        let account_id = wizard.value("account").parse::<i64>().unwrap_or_default();
+        match holdings::create_holding(&self.pool, account_id, &data).await {
+            Ok(holding) => {
+                self.status_message = self.tr_fmt("Added {} x {}", &[&holding.quantity, &holding.ticker]);
+                self.close_wizard();
+                self.load_data().await;
             }
-            Err(e) => {
-                self.export_message = format!("Error exporting: {}", e);
-                self.status_message = self.export_message.clone();
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                self.wizard_failed(self.tr_fmt("The account already holds {}", &[&holdings::normalize_ticker(&data.ticker)]));
             }
+            Err(e) => self.wizard_failed(self.tr_fmt("Error adding holding: {}", &[&e])),
         }
     }
 
-    async fn export_full_summary(&mut self) {
-        use serde_json::json;
+    /// Fetch quotes for every held ticker now
+    async fn update_prices(&mut self) {
+        self.status_message = match prices::update_prices(&self.pool, &prices::Stooq::new()).await {
+            Ok(update) if update.errors.is_empty() => self.tr_fmt("Updated {} quotes", &[&update.saved]),
+            Ok(update) => self.tr_fmt("Updated {} quotes; {}", &[&update.saved, &update.errors.join(", ")]),
+            Err(e) => self.tr_fmt("Error updating prices: {}", &[&e]),
+        };
+        self.load_data().await;
+    }
 
-        let summary = json!({
-            "export_date": chrono::Utc::now().to_rfc3339(),
-            "user_id": self.current_user_id,
-            "accounts": self.accounts,
-            "transactions": self.transactions,
-            "categories": self.categories,
-            "recurring_transactions": self.recurring_transactions,
-        });
+    /// Add-transfer wizard, moving money out of the selected account by default
+    fn open_transfer_form(&mut self) {
+        if self.accounts.len() < 2 {
+            self.status_message = self.tr("A transfer needs at least two accounts").to_string();
+            return;
+        }
+        let from = self.selected_index.min(self.accounts.len() - 1);
+        let to = (from + 1) % self.accounts.len();
+        let accounts: Vec<(String, String)> = self
+            .accounts
+            .iter()
+            .map(|a| (a.id.to_string(), format!("{} ({:.2} {})", a.name, a.current_balance, a.currency)))
+            .collect();
+        self.wizard = Some(Wizard::new(
+            "Add Transfer",
+            vec![
+                Step::new(
+                    "Accounts",
+                    vec![
+                        Field::choice("from", "From: ", accounts.clone()).with_choice(&self.accounts[from].id.to_string()),
+                        Field::choice("to", "To: ", accounts).with_choice(&self.accounts[to].id.to_string()),
+                    ],
+                ),
+                Step::new(
+                    "Amount",
+                    vec![Field::text("amount", "Amount: "), Field::text("description", "Description: ")],
+                ),
+            ],
+        ));
+        self.mode = Mode::AddTransfer;
+    }
 
-        match serde_json::to_string_pretty(&summary) {
-            Ok(json) => {
-                match std::fs::write("financial_summary.json", &json) {
-                    Ok(_) => {
-                        self.export_message = "Exported full financial summary to financial_summary.json".to_string();
-                        self.status_message = self.export_message.clone();
-                    }
-                    Err(e) => {
-                        self.export_message = format!("Error exporting: {}", e);
-                        self.status_message = self.export_message.clone();
-                    }
-                }
-            }
-            Err(e) => {
-                self.export_message = format!("Error serializing: {}", e);
-                self.status_message = self.export_message.clone();
-            }
+    /// The accounts picked in the transfer wizard and the transfer they make
+    fn transfer_from_wizard(&self, wizard: &Wizard) -> Option<(Account, Account, CreateTransfer)> {
+        let account = |key: &str| {
+            let id = wizard.value(key).parse::<i64>().ok()?;
+            self.accounts.iter().find(|a| a.id == id).cloned()
+        };
+        let (from, to) = (account("from")?, account("to")?);
+        let description = wizard.value("description").trim();
+        let data = CreateTransfer {
+            from_account_id: from.id,
+            to_account_id: to.id,
+            amount: wizard.value("amount").trim().parse().unwrap_or(0.0),
+            description: (!description.is_empty()).then(|| description.to_string()),
+            transaction_date: None,
+            fee: None,
+        };
+        Some((from, to, data))
+    }
+
+    /// Why the transfer wizard can't move past its current step, if anything
+    fn check_transfer_step(&self, wizard: &Wizard) -> Result<(), String> {
+        let (from, to, data) = self.transfer_from_wizard(wizard).ok_or(self.tr("Pick two accounts"))?;
+        match wizard.step {
+            0 => transfers::validate_transfer(&from, &to, Utc::now()),
+            _ => data.validate(),
         }
     }
 
-    async fn process_recurring_transactions(&mut self) {
-        match recurring::process_due_recurring(&self.pool).await {
-            Ok(result) => {
-                self.load_data().await;
-                self.status_message = format!(
-                    "Processed {} recurring transactions - {} new transactions created.",
-                    result.due, result.created
-                );
-            }
-            Err(e) => {
-                self.status_message = format!("Error processing recurring transactions: {}", e);
-            }
+    async fn handle_add_transfer_mode(&mut self, code: KeyCode) {
+        if self.wizard_key(code, Self::check_transfer_step) {
+            self.submit_transfer().await;
         }
     }
 
-    async fn toggle_recurring_active(&mut self) {
-        let recurring = &self.recurring_transactions[self.selected_index];
-        let new_status = !recurring.is_active;
+    async fn submit_transfer(&mut self) {
+        let Some((from, to, data)) = self.wizard.as_ref().and_then(|w| self.transfer_from_wizard(w)) else {
+            return;
+        };
 
-        let result = sqlx::query(
-            "UPDATE recurring_transactions SET is_active = ? WHERE id = ?"
-        )
-        .bind(new_status)
-        .bind(recurring.id)
-        .execute(&self.pool)
-        .await;
+        let rate = match fx::conversion_rate(&self.pool, &from.currency, &to.currency, None).await {
+            Ok(Some(rate)) => rate,
+            Ok(None) => {
+                self.wizard_failed(self.tr_fmt("Error: No exchange rate from {} to {}", &[&from.currency, &to.currency]));
+                return;
+            }
+            Err(e) => {
+                self.wizard_failed(self.tr_fmt("Error: {}", &[&e]));
+                return;
+            }
+        };
 
+        let result = match &self.remote {
+            // The server converts at its own rate
+            Some(remote) => remote.create_transfer(&data).await.map(|_| (data.amount, data.amount * rate)),
+            None => transfers::create_transfer(&self.pool, &from, &to, &data, rate)
+                .await
+                .map(|transfer| (transfer.from_transaction.amount, transfer.to_transaction.amount))
+                .map_err(|e| e.to_string()),
+        };
         match result {
-            Ok(_) => {
-                self.status_message = format!(
-                    "Recurring transaction {} {}",
-                    recurring.id,
-                    if new_status { "activated" } else { "paused" }
-                );
+            Ok((sent, received)) => {
+                self.status_message = self.tr_fmt("Transferred {} {} from {} to {} ({} {})", &[&format!("{:.2}", sent), &from.currency, &from.name, &to.name, &format!("{:.2}", received), &to.currency]);
+                self.close_wizard();
+                self.sync_remote().await;
                 self.load_data().await;
             }
-            Err(e) => {
-                self.status_message = format!("Error updating status: {}", e);
-            }
+            Err(e) => self.wizard_failed(self.tr_fmt("Error creating transfer: {}", &[&e])),
         }
     }
 
-    fn handle_details_mode(&mut self, code: KeyCode) {
-        match code {
-            KeyCode::Esc => {
-                // Clear account-specific view currency when exiting details
-                self.account_view_currency = None;
-                self.mode = Mode::Normal;
-            }
-            KeyCode::Char('v') => {
-                // Open currency conversion selector when viewing account details
-                if self.current_screen == Screen::Accounts {
-                    self.mode = Mode::SelectViewCurrency;
-                }
-            }
-            _ => {}
-        }
+    fn render_category_picker(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let height = (self.categories.len() as u16 + 2).min(area.height);
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + area.width / 4,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: area.width / 2,
+            height,
+        };
+
+        frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+        let items: Vec<ListItem> = self
+            .categories
+            .iter()
+            .map(|c| ListItem::new(Line::from(c.name.clone())))
+            .collect();
+
+        let description = self
+            .transactions
+            .get(self.selected_index)
+            .and_then(|t| t.description.clone())
+            .unwrap_or_else(|| "transaction".to_string());
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.tr_fmt("Category for {}", &[&description])),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+            .highlight_symbol("► ");
+
+        // Stateful so the list scrolls to keep the highlighted category visible
+        let mut state = ListState::default().with_selected(Some(self.category_picker_index));
+        frame.render_stateful_widget(list, popup_area, &mut state);
     }
 
     fn handle_currency_filter_mode(&mut self, code: KeyCode) {
@@ -3368,14 +8749,14 @@ impl App {
                 // Show all currencies
                 self.currency_filter = None;
                 self.selected_index = 0;
-                self.status_message = "Showing all currencies".to_string();
+                self.status_message = self.tr("Showing all currencies").to_string();
                 self.mode = Mode::Normal;
             }
             KeyCode::Char(c) if c.is_ascii_digit() => {
                 let num = c.to_digit(10).unwrap() as usize;
                 if num > 0 && num <= self.filter_currencies.len() {
                     let currency = self.filter_currencies[num - 1].clone();
-                    self.status_message = format!("Filtering by {}", currency);
+                    self.status_message = self.tr_fmt("Filtering by {}", &[&currency]);
                     self.currency_filter = Some(currency);
                     self.selected_index = 0;
                     self.mode = Mode::Normal;
@@ -3386,7 +8767,7 @@ impl App {
                 let idx = (c as u8 - b'a') as usize + 9; // 'a' = index 9, 'b' = index 10, etc.
                 if idx < self.filter_currencies.len() {
                     let currency = self.filter_currencies[idx].clone();
-                    self.status_message = format!("Filtering by {}", currency);
+                    self.status_message = self.tr_fmt("Filtering by {}", &[&currency]);
                     self.currency_filter = Some(currency);
                     self.selected_index = 0;
                     self.mode = Mode::Normal;
@@ -3396,6 +8777,61 @@ impl App {
         }
     }
 
+    async fn handle_date_range_mode(&mut self, code: KeyCode) {
+        let now = Utc::now();
+        let range = match code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+                return;
+            }
+            KeyCode::Char('w') => {
+                let start = settings::week_start_date(now.date_naive(), self.user_settings.week_start.as_deref());
+                Some((
+                    start.and_time(chrono::NaiveTime::MIN).and_utc(),
+                    (start + chrono::Duration::days(7)).and_time(chrono::NaiveTime::MIN).and_utc(),
+                ))
+            }
+            KeyCode::Char('t') => budgets::resolve_period("this_month", now),
+            KeyCode::Char('l') => budgets::resolve_period("last_month", now),
+            KeyCode::Char('a') => None,
+            KeyCode::Tab => {
+                self.form_field_index = (self.form_field_index + 1) % 2;
+                return;
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => {
+                match self.form_field_index {
+                    0 => self.form_range_start.push(c),
+                    _ => self.form_range_end.push(c),
+                }
+                return;
+            }
+            KeyCode::Backspace => {
+                match self.form_field_index {
+                    0 => self.form_range_start.pop(),
+                    _ => self.form_range_end.pop(),
+                };
+                return;
+            }
+            KeyCode::Enter => match budgets::parse_date_range(&self.form_range_start, &self.form_range_end) {
+                Ok(range) => Some(range),
+                Err(e) => {
+                    self.status_message = self.tr(e).to_string();
+                    return;
+                }
+            },
+            _ => return,
+        };
+
+        self.transaction_range = range;
+        self.selected_index = 0;
+        self.mode = Mode::Normal;
+        self.load_data().await;
+        self.status_message = match range {
+            Some((start, end)) => self.tr_fmt("Showing transactions from {} to {}", &[&start.format(self.date_pattern()), &(end - chrono::Duration::days(1)).format(self.date_pattern())]),
+            None => self.tr("Showing the latest 100 transactions").to_string(),
+        };
+    }
+
     fn render_view_currency_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         // Make popup larger to show more currencies
         let popup_area = ratatui::layout::Rect {
@@ -3407,10 +8843,14 @@ impl App {
 
         frame.render_widget(ratatui::widgets::Clear, popup_area);
 
-        // Determine if this is for account details or transactions
+        // Determine if this is for account details, the Dashboard's base
+        // currency or transactions
         let is_account_details = self.current_screen == Screen::Accounts;
+        let is_base_currency = matches!(self.current_screen, Screen::Dashboard | Screen::Settings);
         let current_selection = if is_account_details {
             &self.account_view_currency
+        } else if is_base_currency {
+            &self.base_currency
         } else {
             &self.view_in_currency
         };
@@ -3432,9 +8872,9 @@ impl App {
         };
         items.push(ListItem::new(Line::from(vec![
             Span::styled("► ", if is_original_highlighted { Style::default().fg(Color::Yellow) } else { Style::default().fg(Color::DarkGray) }),
-            Span::raw("Original (no conversion)"),
+            Span::raw(if is_base_currency { self.tr("None (sum as recorded)") } else { self.tr("Original (no conversion)") }),
             if is_original_active { 
-                Span::styled(" ✓ ACTIVE", Style::default().fg(Color::Green)) 
+                Span::styled(self.tr(" ✓ ACTIVE"), Style::default().fg(Color::Green)) 
             } else { 
                 Span::raw("") 
             },
@@ -3442,7 +8882,10 @@ impl App {
 
         // Show currencies
         for (i, curr) in self.available_currencies.iter().enumerate() {
-            let is_active = current_selection.as_ref() == Some(curr);
+            let is_active = match current_selection {
+                Some(selected) if is_base_currency => *selected == Self::extract_currency_code(curr),
+                selected => selected.as_ref() == Some(curr),
+            };
             let is_highlighted = self.currency_scroll_offset == i + 1;
             let item_style = if is_highlighted {
                 Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
@@ -3453,7 +8896,7 @@ impl App {
                 Span::styled("► ", if is_highlighted { Style::default().fg(Color::Yellow) } else { Style::default().fg(Color::DarkGray) }),
                 Span::raw(curr.clone()),
                 if is_active { 
-                    Span::styled(" ✓ ACTIVE", Style::default().fg(Color::Green)) 
+                    Span::styled(self.tr(" ✓ ACTIVE"), Style::default().fg(Color::Green)) 
                 } else { 
                     Span::raw("") 
                 },
@@ -3462,18 +8905,24 @@ impl App {
 
         // Show scroll indicator in title
         let scroll_info = if total_currencies > visible_height {
-            format!(" [{}-{}/{}] ↑↓ to scroll", 
-                self.currency_scroll_offset + 1,
-                (self.currency_scroll_offset + visible_height).min(total_currencies + 1),
-                total_currencies + 1)
+            self.tr_fmt(
+                " [{}-{}/{}] ↑↓ to scroll",
+                &[
+                    &(self.currency_scroll_offset + 1),
+                    &(self.currency_scroll_offset + visible_height).min(total_currencies + 1),
+                    &(total_currencies + 1),
+                ],
+            )
         } else {
-            format!(" [{} currencies]", total_currencies)
+            self.tr_fmt(" [{} currencies]", &[&total_currencies])
         };
 
         let title = if is_account_details {
-            format!(" View Account In Currency{} ", scroll_info)
+            self.tr_fmt(" View Account In Currency{} ", &[&scroll_info])
+        } else if is_base_currency {
+            self.tr_fmt(" Base Currency For Totals{} ", &[&scroll_info])
         } else {
-            format!(" View Transactions In Currency{} ", scroll_info)
+            self.tr_fmt(" View Transactions In Currency{} ", &[&scroll_info])
         };
 
         let list = List::new(items)
@@ -3490,7 +8939,7 @@ impl App {
         frame.render_stateful_widget(list, popup_area, &mut state);
     }
 
-    fn handle_view_currency_mode(&mut self, code: KeyCode) {
+    async fn handle_view_currency_mode(&mut self, code: KeyCode) {
         // Determine if we're setting account-specific or global view currency
         let is_account_details = self.current_screen == Screen::Accounts;
         let max_scroll = self.available_currencies.len(); // index 0 = "Original", 1+ = currencies
@@ -3504,10 +8953,8 @@ impl App {
             KeyCode::Up => {
                 self.currency_scroll_offset = self.currency_scroll_offset.saturating_sub(1);
             }
-            KeyCode::Down => {
-                if self.currency_scroll_offset < max_scroll {
-                    self.currency_scroll_offset += 1;
-                }
+            KeyCode::Down if self.currency_scroll_offset < max_scroll => {
+                self.currency_scroll_offset += 1;
             }
             KeyCode::PageUp | KeyCode::Char('[') => {
                 self.currency_scroll_offset = self.currency_scroll_offset.saturating_sub(10);
@@ -3526,58 +8973,203 @@ impl App {
                 let selected_idx = self.currency_scroll_offset;
                 self.currency_scroll_offset = 0;
                 
-                if selected_idx == 0 {
+                if matches!(self.current_screen, Screen::Dashboard | Screen::Settings) {
+                    let currency = selected_idx
+                        .checked_sub(1)
+                        .and_then(|i| self.available_currencies.get(i))
+                        .map(|c| Self::extract_currency_code(c));
+                    self.set_base_currency(currency).await;
+                    self.mode = Mode::Normal;
+                } else if selected_idx == 0 {
                     // "Original (no conversion)" selected
                     if is_account_details {
                         self.account_view_currency = None;
-                        self.status_message = "Account: showing original currency".to_string();
+                        self.status_message = self.tr("Account: showing original currency").to_string();
                         self.mode = Mode::ViewDetails;
                     } else {
                         self.view_in_currency = None;
-                        self.status_message = "Showing original currencies".to_string();
+                        self.refresh_view_rates().await;
+                        self.status_message = self.tr("Showing original currencies").to_string();
                         self.mode = Mode::Normal;
+                        self.save_view_currency().await;
                     }
                 } else if selected_idx <= self.available_currencies.len() {
                     // Currency selected (index 1 = first currency)
                     let currency = self.available_currencies[selected_idx - 1].clone();
                     if is_account_details {
-                        self.status_message = format!("Account: viewing in {}", currency);
+                        self.status_message = self.tr_fmt("Account: viewing in {}", &[&currency]);
                         self.account_view_currency = Some(currency);
                         self.mode = Mode::ViewDetails;
                     } else {
-                        self.status_message = format!("Viewing all amounts in {}", currency);
+                        self.status_message = self.tr_fmt("Viewing all amounts in {}", &[&currency]);
                         self.view_in_currency = Some(currency);
+                        self.refresh_view_rates().await;
                         self.mode = Mode::Normal;
+                        self.save_view_currency().await;
                     }
                 }
             }
-            _ => {}
+            _ => {}
+        }
+    }
+
+    /// Extract 3-letter currency code from strings like "Argentine Peso (ARS)" or "USD"
+    fn extract_currency_code(currency: &str) -> String {
+        currencies::parse_label(currency).0
+    }
+
+    /// Check if two currency strings match (handles both codes and full names)
+    fn currencies_match(a: &str, b: &str) -> bool {
+        let code_a = Self::extract_currency_code(a);
+        let code_b = Self::extract_currency_code(b);
+        code_a == code_b
+    }
+
+    /// Age in days of the oldest rate behind a conversion, when it is past the stale threshold.
+    fn stale_rate_age(&self, from: &str, to: &str) -> Option<i64> {
+        let from_code = Self::extract_currency_code(from);
+        let to_code = Self::extract_currency_code(to);
+        if from_code == to_code {
+            return None;
+        }
+
+        // Same lookup order as get_exchange_rate: direct, reverse, then via an intermediate
+        let pair_date = |a: &str, b: &str| {
+            self.exchange_rates
+                .iter()
+                .find(|r| Self::currencies_match(&r.from_currency, a) && Self::currencies_match(&r.to_currency, b))
+                .or_else(|| {
+                    self.exchange_rates.iter().find(|r| {
+                        Self::currencies_match(&r.from_currency, b) && Self::currencies_match(&r.to_currency, a)
+                    })
+                })
+                .map(|r| r.rate_date)
+        };
+        let oldest = pair_date(&from_code, &to_code).or_else(|| {
+            ["USD", "EUR", "CAD", "GBP"]
+                .iter()
+                .filter(|i| **i != from_code && **i != to_code)
+                .find_map(|i| match (pair_date(&from_code, i), pair_date(i, &to_code)) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    _ => None,
+                })
+        })?;
+
+        let now = Utc::now();
+        if fx::is_stale(oldest, now, fx::stale_after_days()) {
+            Some(fx::age_days(oldest, now))
+        } else {
+            None
+        }
+    }
+
+    /// Work out once, rather than per row and frame, the rates amounts convert
+    /// at into view_in_currency and base_currency. Call when either currency,
+    /// the transactions or the rates change.
+    async fn refresh_view_rates(&mut self) {
+        self.view_rates = match self.view_in_currency.clone() {
+            Some(target) => self.transaction_rates(&target).await,
+            None => HashMap::new(),
+        };
+        self.base_rates = match self.base_currency.clone() {
+            Some(base) => self.transaction_rates(&base).await,
+            None => HashMap::new(),
+        };
+
+        // Balances are today's money, so they convert at the latest rate
+        self.account_base_rates.clear();
+        let Some(base) = self.base_currency.clone() else { return };
+        for account in &self.accounts {
+            let currency = Self::extract_currency_code(&account.currency);
+            if let Ok(Some(rate)) = fx::conversion_rate(&self.pool, &currency, &base, None).await {
+                self.account_base_rates.insert(account.id, rate);
+            }
         }
     }
 
-    /// Extract 3-letter currency code from strings like "Argentine Peso (ARS)" or "USD"
-    fn extract_currency_code(currency: &str) -> String {
-        // Check if it contains parentheses with a code like "(ARS)"
-        if let Some(start) = currency.rfind('(') {
-            if let Some(end) = currency.rfind(')') {
-                if end > start {
-                    let code = &currency[start + 1..end];
-                    // Verify it looks like a currency code (2-4 uppercase letters)
-                    if code.len() >= 2 && code.len() <= 4 && code.chars().all(|c| c.is_ascii_uppercase()) {
-                        return code.to_string();
+    /// Transaction id -> the rate it converts at into `target`: the one quoted
+    /// closest to its date (fx::Converter), or today's, triangulated if need
+    /// be, when the pair has no rate at all
+    async fn transaction_rates(&self, target: &str) -> HashMap<i64, f64> {
+        let target = Self::extract_currency_code(target);
+        let currencies: HashMap<i64, String> = self
+            .accounts
+            .iter()
+            .map(|a| (a.id, Self::extract_currency_code(&a.currency)))
+            .collect();
+
+        let mut converter = fx::Converter::new(&self.pool);
+        let mut rates = HashMap::new();
+        for t in &self.transactions {
+            let Some(currency) = currencies.get(&t.account_id) else { continue };
+            let date = t.transaction_date.date_naive();
+            let rate = match converter.rate(currency, &target, date).await {
+                Ok(Some(rate)) => rate,
+                _ => self.get_exchange_rate(currency, &target),
+            };
+            rates.insert(t.id, rate);
+        }
+        rates
+    }
+
+    /// A transaction's signed amount in `target` at its rate from `rates`
+    /// (see transaction_rates). The base-currency value recorded with the
+    /// transaction beats a looked-up rate.
+    fn amount_in(&self, t: &Transaction, target: &str, rates: &HashMap<i64, f64>) -> Option<f64> {
+        if let Some(base_amount) = t.base_amount.filter(|_| t.base_currency.as_deref() == Some(target)) {
+            return Some(base_amount);
+        }
+        let rate = match rates.get(&t.id) {
+            Some(rate) => *rate,
+            None => {
+                let currency = self.accounts.iter().find(|a| a.id == t.account_id)?.currency.as_str();
+                self.get_exchange_rate(currency, target)
+            }
+        };
+        Some(t.amount * rate)
+    }
+
+    /// A transaction's signed amount in view_in_currency, None when amounts
+    /// show in their own currency
+    fn view_amount(&self, t: &Transaction) -> Option<(f64, &str)> {
+        let target = self.view_in_currency.as_deref()?;
+        Some((self.amount_in(t, target, &self.view_rates)?, target))
+    }
+
+    /// A transaction's signed amount for totals: in base_currency when set,
+    /// else as recorded
+    fn base_amount(&self, t: &Transaction) -> f64 {
+        self.base_currency
+            .as_deref()
+            .and_then(|base| self.amount_in(t, base, &self.base_rates))
+            .unwrap_or(t.amount)
+    }
+
+    /// Sum of the account balances and the currency it's in: base_currency,
+    /// leaving out accounts without a rate into it (their number is the third
+    /// value), or without a base currency the plain sum, in the accounts'
+    /// currency when they all share one
+    fn total_balance(&self) -> (f64, Option<String>, usize) {
+        match &self.base_currency {
+            Some(base) => {
+                let mut total = 0.0;
+                let mut unconverted = 0;
+                for a in &self.accounts {
+                    match self.account_base_rates.get(&a.id) {
+                        Some(rate) => total += a.current_balance * rate,
+                        None => unconverted += 1,
                     }
                 }
+                (total, Some(base.clone()), unconverted)
+            }
+            None => {
+                let total = self.accounts.iter().map(|a| a.current_balance).sum();
+                let mut currencies = self.accounts.iter().map(|a| Self::extract_currency_code(&a.currency));
+                let first = currencies.next();
+                let shared = first.filter(|c| currencies.all(|other| other == *c));
+                (total, shared, 0)
             }
         }
-        // Otherwise return the original string (it's probably already a code)
-        currency.to_string()
-    }
-
-    /// Check if two currency strings match (handles both codes and full names)
-    fn currencies_match(a: &str, b: &str) -> bool {
-        let code_a = Self::extract_currency_code(a);
-        let code_b = Self::extract_currency_code(b);
-        code_a == code_b
     }
 
     fn get_exchange_rate(&self, from: &str, to: &str) -> f64 {
@@ -3642,6 +9234,150 @@ impl App {
         1.0 // Default to 1.0 if no rate found
     }
 
+    /// Interface text in the current language
+    fn tr<'a>(&self, text: &'a str) -> &'a str {
+        i18n::tr(self.language, text)
+    }
+
+    /// A message in the current language with its `{}` placeholders filled
+    fn tr_fmt(&self, text: &str, args: &[&dyn std::fmt::Display]) -> String {
+        i18n::tr_fmt(self.language, text, args)
+    }
+
+    /// A footer help line in the current language
+    fn help(&self, line: &str) -> String {
+        i18n::help_line(self.language, line)
+    }
+
+    /// Switch to the next language and remember it for the user
+    async fn toggle_language(&mut self) {
+        self.language = self.language.next();
+        // The server has no settings call, so a remote session keeps it for this run
+        if let Some(user_id) = self.current_user_id.filter(|_| self.remote.is_none()) {
+            let _ = settings::set(&self.pool, user_id, settings::LANGUAGE, Some(self.language.code())).await;
+        }
+        self.status_message = self.tr_fmt("Language: {}", &[&self.language.name()]);
+    }
+
+    /// Restore the logged-in user's default tab, last view-in currency and language.
+    async fn apply_user_settings(&mut self) {
+        let Some(user_id) = self.current_user_id else { return };
+        if let Ok(user_settings) = settings::user_settings(&self.pool, user_id).await {
+            if let Some(language) = user_settings.language.as_deref().and_then(Language::parse) {
+                self.language = language;
+            }
+            if let Some(tab) = user_settings.default_screen.as_deref().and_then(settings::screen_index) {
+                self.selected_tab = tab;
+            }
+            self.view_in_currency = user_settings.view_currency.clone();
+            self.base_currency = user_settings.base_currency.clone();
+            self.user_settings = user_settings;
+        }
+    }
+
+    async fn save_default_screen(&mut self) {
+        let Some(user_id) = self.current_user_id else { return };
+        let screen = settings::SCREENS[self.selected_tab.min(settings::SCREENS.len() - 1)];
+        self.status_message =
+            match settings::set(&self.pool, user_id, settings::DEFAULT_SCREEN, Some(screen)).await {
+                Ok(()) => {
+                    self.user_settings.default_screen = Some(screen.to_string());
+                    self.tr_fmt("This page now opens after login ({})", &[&screen])
+                }
+                Err(e) => self.tr_fmt("Could not save default page: {}", &[&e]),
+            };
+    }
+
+    /// Save the base_currency setting, which new transactions also record
+    /// their value in, and convert the totals into it
+    async fn set_base_currency(&mut self, currency: Option<String>) {
+        let Some(user_id) = self.current_user_id else { return };
+        if let Err(e) = settings::set(&self.pool, user_id, settings::BASE_CURRENCY, currency.as_deref()).await {
+            self.status_message = self.tr_fmt("Could not save base currency: {}", &[&e]);
+            return;
+        }
+        self.status_message = match &currency {
+            Some(currency) => self.tr_fmt("Totals in {}", &[&currency]),
+            None => self.tr("Totals summed as recorded").to_string(),
+        };
+        self.user_settings.base_currency = currency.clone();
+        self.base_currency = currency;
+        self.refresh_view_rates().await;
+        self.load_report_charts(user_id).await;
+    }
+
+    /// Change the setting highlighted on the Settings tab: the base currency
+    /// opens the currency dialog, the others step to their next value
+    async fn change_setting(&mut self) {
+        let Some(user_id) = self.current_user_id else { return };
+        let current = &self.user_settings;
+        match self.selected_index {
+            0 => self.mode = Mode::SelectViewCurrency,
+            1 => {
+                let index = settings::DATE_FORMATS
+                    .iter()
+                    .position(|(code, _)| current.date_format.as_deref() == Some(*code))
+                    .unwrap_or(0);
+                let (code, _) = settings::DATE_FORMATS[(index + 1) % settings::DATE_FORMATS.len()];
+                self.save_setting(user_id, settings::DATE_FORMAT, Some(code.to_string())).await;
+            }
+            2 => {
+                // None, then each open account in list order
+                let open: Vec<i64> = self.accounts.iter().filter(|a| !a.is_archived).map(|a| a.id).collect();
+                let next = match current.default_account_id.and_then(|id| open.iter().position(|a| *a == id)) {
+                    Some(i) => open.get(i + 1).copied(),
+                    None => open.first().copied(),
+                };
+                self.save_setting(user_id, settings::DEFAULT_ACCOUNT, next.map(|id| id.to_string())).await;
+            }
+            3 => self.toggle_language().await,
+            4 => {
+                let next = match current.week_start.as_deref() {
+                    Some("sunday") => "monday",
+                    _ => "sunday",
+                };
+                self.save_setting(user_id, settings::WEEK_START, Some(next.to_string())).await;
+            }
+            5 => {
+                let enabled = current.weekly_digest.unwrap_or(true);
+                self.save_setting(user_id, settings::WEEKLY_DIGEST, Some((!enabled).to_string())).await;
+            }
+            6 => {
+                let index = current.default_screen.as_deref().and_then(settings::screen_index).unwrap_or(0);
+                let screen = settings::SCREENS[(index + 1) % settings::SCREENS.len()];
+                self.save_setting(user_id, settings::DEFAULT_SCREEN, Some(screen.to_string())).await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Save one setting from the Settings tab and reload them all
+    async fn save_setting(&mut self, user_id: i64, key: &str, value: Option<String>) {
+        if let Err(e) = settings::set(&self.pool, user_id, key, value.as_deref()).await {
+            self.status_message = self.tr_fmt("Could not save setting: {}", &[&e]);
+            return;
+        }
+        if let Ok(user_settings) = settings::user_settings(&self.pool, user_id).await {
+            self.user_settings = user_settings;
+        }
+        self.status_message = self.tr("Setting saved").to_string();
+    }
+
+    /// strftime pattern for dates shown in the TUI, from the date_format setting
+    fn date_pattern(&self) -> &'static str {
+        settings::date_pattern(self.user_settings.date_format.as_deref())
+    }
+
+    async fn save_view_currency(&mut self) {
+        let Some(user_id) = self.current_user_id else { return };
+        let currency = self.view_in_currency.as_deref().map(Self::extract_currency_code);
+        if let Err(e) =
+            settings::set(&self.pool, user_id, settings::VIEW_CURRENCY, currency.as_deref()).await
+        {
+            self.status_message = self.tr_fmt("Could not save view currency: {}", &[&e]);
+        }
+    }
+
     fn update_screen(&mut self) {
         self.current_screen = match self.selected_tab {
             0 => Screen::Dashboard,
@@ -3652,6 +9388,10 @@ impl App {
             5 => Screen::ExchangeRates,
             6 => Screen::Reports,
             7 => Screen::Export,
+            8 => Screen::Budgets,
+            9 => Screen::Settings,
+            10 => Screen::Goals,
+            11 => Screen::Investments,
             _ => Screen::Dashboard,
         };
     }
@@ -3660,39 +9400,174 @@ impl App {
         match self.current_screen {
             Screen::Dashboard => self.transactions.len().min(10),
             Screen::Accounts => self.accounts.len(),
-            Screen::Transactions => {
-                // Account for currency filter
-                if let Some(ref currency) = self.currency_filter {
-                    self.transactions
-                        .iter()
-                        .filter(|t| {
-                            self.accounts
-                                .iter()
-                                .find(|a| a.id == t.account_id)
-                                .map(|a| &a.currency == currency)
-                                .unwrap_or(false)
-                        })
-                        .count()
-                } else {
-                    self.transactions.len()
-                }
-            }
+            Screen::Transactions => self.visible_transactions().len(),
             Screen::Categories => self.categories.len(),
+            Screen::Budgets => self.budget_variance.len(),
             Screen::RecurringTransactions => self.recurring_transactions.len(),
             Screen::ExchangeRates => self.exchange_rates.len(),
+            Screen::Settings => SETTING_ROWS.len(),
+            Screen::Goals => self.goals.len(),
+            Screen::Investments => self.investments.iter().map(|i| i.holdings.len()).sum(),
             _ => 0,
         }
     }
 
+    /// Transactions the Transactions tab lists, after the currency filter and
+    /// the search; `selected_index` points into this list there
+    /// Transactions shown on the Transactions tab. A transfer between two
+    /// accounts is one "A → B" entry: its credit leg is hidden while the debit
+    /// leg is listed.
+    fn visible_transactions(&self) -> Vec<&Transaction> {
+        let visible: Vec<&Transaction> = self
+            .transactions
+            .iter()
+            .filter(|t| match &self.currency_filter {
+                // Match the original currency, or the account currency this transaction posted in
+                Some(currency) => {
+                    t.original_currency.as_ref() == Some(currency)
+                        || self
+                            .accounts
+                            .iter()
+                            .find(|a| a.id == t.account_id)
+                            .map(|a| &a.currency == currency)
+                            .unwrap_or(false)
+                }
+                None => true,
+            })
+            .filter(|t| self.matches_search(t))
+            .collect();
+
+        let debit_legs: Vec<i64> = visible
+            .iter()
+            .filter(|t| t.transaction_type != "income")
+            .filter_map(|t| t.transfer_group_id)
+            .collect();
+        visible
+            .into_iter()
+            .filter(|t| {
+                t.transaction_type != "income"
+                    || !t.transfer_group_id.is_some_and(|group_id| debit_legs.contains(&group_id))
+            })
+            .collect()
+    }
+
+    /// "Checking → Savings" for either leg of a transfer between two accounts
+    fn transfer_label(&self, t: &Transaction) -> Option<String> {
+        let (from, to) = self.transfers.get(&t.transfer_group_id?)?;
+        let name = |id: &i64| {
+            self.accounts
+                .iter()
+                .find(|a| a.id == *id)
+                .map(|a| a.name.clone())
+                .unwrap_or_else(|| self.tr_fmt("Account {}", &[&id]))
+        };
+        Some(format!("{} → {}", name(from), name(to)))
+    }
+
+    /// Whether the transaction is in the summaries: not excluded itself or by one of its categories
+    fn counts_in_reports(&self, t: &Transaction) -> bool {
+        let category_ids: Vec<i64> = self
+            .transaction_splits
+            .get(&t.id)
+            .map(|splits| splits.iter().map(|s| s.category_id).collect())
+            .unwrap_or_default();
+        exclusions::counts_in_reports(t, &category_ids, &self.excluded_categories)
+    }
+
+    /// Transaction under the cursor on the Transactions tab
+    fn selected_transaction(&self) -> Option<&Transaction> {
+        self.visible_transactions().get(self.selected_index).copied()
+    }
+
+    fn matches_search(&self, t: &Transaction) -> bool {
+        let query = self.transaction_search.trim().to_ascii_lowercase();
+        query.is_empty()
+            || t.description.as_deref().unwrap_or("").to_ascii_lowercase().contains(&query)
+            || self.amount_matches_search(t)
+            || self.matching_categories(t).is_some()
+            || self
+                .transaction_tags
+                .get(&t.id)
+                .is_some_and(|names| names.iter().any(|n| n.contains(query.trim_start_matches('#'))))
+    }
+
+    /// Whether the search is a number found in the transaction's amount, e.g.
+    /// "4.5" or "$12" against 124.50
+    fn amount_matches_search(&self, t: &Transaction) -> bool {
+        let query = self.transaction_search.trim().trim_start_matches('$');
+        !query.is_empty()
+            && query.parse::<f64>().is_ok()
+            && format!("{:.2}", t.amount.abs()).contains(query)
+    }
+
+    /// The transaction's category names, when the search matches one of them
+    fn matching_categories(&self, t: &Transaction) -> Option<&str> {
+        let query = self.transaction_search.trim().to_ascii_lowercase();
+        self.transaction_categories
+            .get(&t.id)
+            .map(|names| names.as_str())
+            .filter(|names| !query.is_empty() && names.to_ascii_lowercase().contains(&query))
+    }
+
     fn clear_transaction_form(&mut self) {
         self.form_account_id.clear();
         self.form_amount.clear();
         self.form_type = String::from("expense");
         self.form_description.clear();
         self.form_category_id.clear();
+        self.form_fee.clear();
+        self.form_fee_category_id.clear();
+        self.form_merchant = None;
+        self.form_date = None;
         self.form_field_index = 0;
     }
 
+    /// One-line summary of a template, e.g. "Coffee – $4.50 – Dining – Card A"
+    fn template_label(&self, template: &TransactionTemplate) -> String {
+        let account = self.accounts.iter().find(|a| a.id == template.account_id);
+        let currency = account.map(|a| a.currency.as_str()).unwrap_or("");
+        let mut label = format!(
+            "{} – {}{:.2}",
+            template.name,
+            if currency == "USD" { "$" } else { "" },
+            template.amount
+        );
+        if currency != "USD" && !currency.is_empty() {
+            label.push_str(&format!(" {}", currency));
+        }
+        if let Some(category) = template
+            .category_id
+            .and_then(|id| self.categories.iter().find(|c| c.id == id))
+        {
+            label.push_str(&format!(" – {}", category.name));
+        }
+        if let Some(account) = account {
+            label.push_str(&format!(" – {}", account.name));
+        }
+        label
+    }
+
+    /// Pre-fill the add-transaction form from a template
+    fn apply_template(&mut self, index: usize) {
+        let Some(template) = self.templates.get(index).cloned() else {
+            return;
+        };
+
+        self.form_account_id = template.account_id.to_string();
+        self.form_amount = format!("{:.2}", template.amount);
+        self.form_type = template.transaction_type.clone();
+        self.form_description = template
+            .description
+            .clone()
+            .unwrap_or_else(|| template.name.clone());
+        self.form_category_id = template
+            .category_id
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+        self.form_merchant = template.merchant.clone();
+        self.status_message = self.tr_fmt("Template '{}' applied - Enter to submit", &[&template.name]);
+    }
+
     fn clear_exchange_rate_form(&mut self) {
         self.form_from_currency.clear();
         self.form_to_currency.clear();
@@ -3709,16 +9584,6 @@ impl App {
         self.form_field_index = 0;
     }
 
-    fn clear_recurring_form(&mut self) {
-        self.form_account_id.clear();
-        self.form_amount.clear();
-        self.form_type = String::from("expense");
-        self.form_description.clear();
-        self.form_category_id.clear();
-        self.form_recurring_frequency = String::from("monthly");
-        self.form_field_index = 0;
-    }
-
     fn clear_account_form(&mut self) {
         self.form_account_name.clear();
         self.form_account_bank.clear();
@@ -3743,26 +9608,27 @@ impl App {
             "r" | "credit" => "credit",
             "i" | "investment" => "investment",
             "h" | "cash" => "cash",
+            "l" | "loan" => "loan",
             _ => &self.form_account_type,
         };
 
         let form_text = vec![
-            Line::from(Span::styled("Add New Account", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(self.tr("Add New Account"), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Name: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Name: "), Style::default().fg(Color::Gray)),
                 Span::styled(&self.form_account_name,
                     if self.form_field_index == 0 { Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED) }
                     else { Style::default().fg(Color::White) }),
             ]),
             Line::from(vec![
-                Span::styled("Bank (optional): ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Bank (optional): "), Style::default().fg(Color::Gray)),
                 Span::styled(&self.form_account_bank,
                     if self.form_field_index == 1 { Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED) }
                     else { Style::default().fg(Color::White) }),
             ]),
             Line::from(vec![
-                Span::styled("Type: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Type: "), Style::default().fg(Color::Gray)),
                 Span::styled(&self.form_account_type,
                     if self.form_field_index == 2 { Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED) }
                     else { Style::default().fg(Color::White) }),
@@ -3779,33 +9645,35 @@ impl App {
                 Span::styled("i", Style::default().fg(Color::Green)),
                 Span::styled("=investment ", Style::default().fg(Color::DarkGray)),
                 Span::styled("h", Style::default().fg(Color::Green)),
-                Span::styled("=cash", Style::default().fg(Color::DarkGray)),
+                Span::styled("=cash ", Style::default().fg(Color::DarkGray)),
+                Span::styled("l", Style::default().fg(Color::Green)),
+                Span::styled("=loan", Style::default().fg(Color::DarkGray)),
             ]),
             Line::from(vec![
-                Span::styled("Currency: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Currency: "), Style::default().fg(Color::Gray)),
                 Span::styled(&self.form_account_currency,
                     if self.form_field_index == 3 { Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED) }
                     else { Style::default().fg(Color::Green) }),
             ]),
             Line::from(vec![
-                Span::styled("Initial Balance: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("Initial Balance: "), Style::default().fg(Color::Gray)),
                 Span::styled(&self.form_account_balance,
                     if self.form_field_index == 4 { Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED) }
                     else { Style::default().fg(Color::White) }),
                 Span::styled(format!(" {}", self.form_account_currency), Style::default().fg(Color::Green)),
             ]),
             Line::from(""),
-            Line::from(Span::styled("Tab: Next | Enter: Submit | Esc: Cancel", Style::default().fg(Color::Cyan))),
+            Line::from(Span::styled(self.help("Tab: Next | Enter: Submit | Esc: Cancel"), Style::default().fg(Color::Cyan))),
         ];
 
         let form = Paragraph::new(form_text)
-            .block(Block::default().borders(Borders::ALL).title("Add Account"))
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Add Account")))
             .alignment(Alignment::Left);
         frame.render_widget(form, chunks[0]);
 
         // Right panel: Currency and Type options
         let mut help_lines = vec![
-            Line::from(Span::styled("Available Currencies:", Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(self.tr("Available Currencies:"), Style::default().fg(Color::Yellow))),
             Line::from(""),
         ];
         for (i, curr) in currencies.iter().enumerate() {
@@ -3817,7 +9685,7 @@ impl App {
             if (i + 1) % 4 == 0 { help_lines.push(Line::from("")); }
         }
         help_lines.push(Line::from(""));
-        help_lines.push(Line::from(Span::styled("Account Types:", Style::default().fg(Color::Yellow))));
+        help_lines.push(Line::from(Span::styled(self.tr("Account Types:"), Style::default().fg(Color::Yellow))));
         help_lines.push(Line::from(""));
         let account_types = [("c", "checking"), ("s", "savings"), ("r", "credit"), ("i", "investment"), ("h", "cash")];
         for (shortcut, name) in &account_types {
@@ -3830,25 +9698,37 @@ impl App {
         }
 
         let help = Paragraph::new(help_lines)
-            .block(Block::default().borders(Borders::ALL).title("Options"))
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Options")))
             .alignment(Alignment::Left);
         frame.render_widget(help, chunks[1]);
     }
 
     async fn handle_add_account_mode(&mut self, code: KeyCode) {
         match code {
+            KeyCode::Esc if self.onboarding.is_some() => { self.finish_onboarding().await; }
             KeyCode::Esc => { self.mode = Mode::Normal; }
             KeyCode::Tab => { self.form_field_index = (self.form_field_index + 1) % 5; }
             KeyCode::BackTab => {
                 self.form_field_index = if self.form_field_index == 0 { 4 } else { self.form_field_index - 1 };
             }
-            KeyCode::Enter => { self.submit_account().await; }
+            KeyCode::Enter => {
+                self.submit_account().await;
+                if self.onboarding == Some(OnboardingStep::CreateAccount) {
+                    if self.accounts.is_empty() {
+                        // Keep the wizard on the form so the error can be fixed
+                        self.mode = Mode::AddAccount;
+                    } else {
+                        self.onboarding = Some(OnboardingStep::SeedCategories);
+                        self.mode = Mode::Onboarding;
+                    }
+                }
+            }
             KeyCode::Char(c) => match self.form_field_index {
                 0 => self.form_account_name.push(c),
                 1 => self.form_account_bank.push(c),
                 2 => self.form_account_type.push(c),
                 3 => self.form_account_currency.push(c.to_ascii_uppercase()),
-                4 => if c.is_ascii_digit() || c == '.' || c == '-' { self.form_account_balance.push(c); }
+                4 if c.is_ascii_digit() || c == '.' || c == '-' => self.form_account_balance.push(c),
                 _ => {}
             },
             KeyCode::Backspace => match self.form_field_index {
@@ -3865,7 +9745,7 @@ impl App {
 
     async fn submit_account(&mut self) {
         if self.form_account_name.trim().is_empty() {
-            self.status_message = "Error: Account name is required!".to_string();
+            self.status_message = self.tr("Error: Account name is required!").to_string();
             self.mode = Mode::Normal;
             return;
         }
@@ -3877,15 +9757,16 @@ impl App {
         let account_type = match self.form_account_type.to_lowercase().as_str() {
             "c" | "checking" => "checking",
             "s" | "savings" => "savings",
-            "r" | "credit" => "credit",
+            "r" | "credit" | "credit_card" => "credit_card",
             "i" | "investment" => "investment",
             "h" | "cash" => "cash",
+            "l" | "loan" => "loan",
             _ => "checking",
         };
 
         let currency = self.form_account_currency.to_uppercase();
         if currency.is_empty() {
-            self.status_message = "Error: Currency is required!".to_string();
+            self.status_message = self.tr("Error: Currency is required!").to_string();
             self.mode = Mode::Normal;
             return;
         }
@@ -3893,33 +9774,52 @@ impl App {
         let user_id = match self.current_user_id {
             Some(id) => id,
             None => {
-                self.status_message = "Error: No user logged in!".to_string();
+                self.status_message = self.tr("Error: No user logged in!").to_string();
                 self.mode = Mode::Normal;
                 return;
             }
         };
 
-        let result = sqlx::query(
-            "INSERT INTO accounts (user_id, name, account_type, currency, current_balance, bank_name) VALUES (?, ?, ?, ?, ?, ?)"
-        )
-        .bind(user_id)
-        .bind(&self.form_account_name)
-        .bind(account_type)
-        .bind(&currency)
-        .bind(balance)
-        .bind(&bank_name)
-        .execute(&self.pool)
-        .await;
+        let result = match &self.remote {
+            Some(remote) => {
+                let data = CreateAccount {
+                    user_id,
+                    name: self.form_account_name.clone(),
+                    account_type: account_type.to_string(),
+                    bank_name,
+                    currency: Some(currency.clone()),
+                    initial_balance: Some(balance),
+                    default_category_id: None,
+                    credit_limit: None,
+                    opened_on: None,
+                };
+                remote.create_account(&data).await
+            }
+            None => sqlx::query(
+                "INSERT INTO accounts (user_id, name, account_type, currency, initial_balance, current_balance, bank_name, opened_on) VALUES (?, ?, ?, ?, ?, ?, ?, date('now'))"
+            )
+            .bind(user_id)
+            .bind(&self.form_account_name)
+            .bind(account_type)
+            .bind(&currency)
+            .bind(balance)
+            .bind(balance)
+            .bind(&bank_name)
+            .execute(&self.pool)
+            .await
+            .map(|res| res.last_insert_rowid())
+            .map_err(|e| e.to_string()),
+        };
 
         match result {
-            Ok(res) => {
-                let account_id = res.last_insert_rowid();
-                self.status_message = format!("Account '{}' created! ID: {} [{}]", self.form_account_name, account_id, currency);
+            Ok(account_id) => {
+                self.status_message = self.tr_fmt("Account '{}' created! ID: {} [{}]", &[&self.form_account_name, &account_id, &currency]);
+                self.sync_remote().await;
                 self.load_data().await;
                 self.mode = Mode::Normal;
             }
             Err(e) => {
-                self.status_message = format!("Error creating account: {}", e);
+                self.status_message = self.tr_fmt("Error creating account: {}", &[&e]);
                 self.mode = Mode::Normal;
             }
         }
@@ -3928,43 +9828,91 @@ impl App {
     fn clear_category_form(&mut self) {
         self.form_category_name.clear();
         self.form_category_type = String::from("expense");
+        self.form_category_parent.clear();
+        self.editing_category = None;
         self.form_field_index = 0;
     }
 
     fn render_add_category_form(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let field_style = |index: usize| {
+            if self.form_field_index == index {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        let parent_name = self
+            .form_category_parent
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.categories.iter().find(|c| c.id == id))
+            .map(|c| format!("  ({})", c.name))
+            .unwrap_or_default();
+        let title = if self.editing_category.is_some() { self.tr("Edit Category") } else { self.tr("Add New Category") };
+
         let form_text = vec![
-            Line::from(Span::styled("Add New Category", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(title, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Name: ", Style::default().fg(Color::Gray)),
-                Span::styled(&self.form_category_name,
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)),
+                Span::styled(self.tr("Name: "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_category_name, field_style(0)),
+            ]),
+            Line::from(vec![
+                Span::styled(self.tr("Parent ID (optional): "), Style::default().fg(Color::Gray)),
+                Span::styled(&self.form_category_parent, field_style(1)),
+                Span::styled(parent_name, Style::default().fg(Color::DarkGray)),
             ]),
             Line::from(""),
-            Line::from(Span::styled("Enter: Submit | Esc: Cancel", Style::default().fg(Color::Cyan))),
+            Line::from(Span::styled(self.help("Tab: Next field | Enter: Submit | Esc: Cancel"), Style::default().fg(Color::Cyan))),
             Line::from(""),
-            Line::from(Span::styled("Examples: Salary, Groceries, Rent, Entertainment, Subscriptions...", Style::default().fg(Color::DarkGray))),
+            Line::from(Span::styled(self.tr("Examples: Salary, Groceries, Rent, Entertainment, Subscriptions..."), Style::default().fg(Color::DarkGray))),
+            Line::from(Span::styled(self.tr("A parent nests the category under it, e.g. Food > Groceries"), Style::default().fg(Color::DarkGray))),
         ];
 
         let form = Paragraph::new(form_text)
-            .block(Block::default().borders(Borders::ALL).title("Add Category"))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .alignment(Alignment::Left);
         frame.render_widget(form, area);
     }
 
     async fn handle_add_category_mode(&mut self, code: KeyCode) {
         match code {
-            KeyCode::Esc => { self.mode = Mode::Normal; }
+            KeyCode::Esc => {
+                self.editing_category = None;
+                self.mode = Mode::Normal;
+            }
             KeyCode::Enter => { self.submit_category().await; }
-            KeyCode::Char(c) => { self.form_category_name.push(c); }
-            KeyCode::Backspace => { self.form_category_name.pop(); }
+            KeyCode::Tab => { self.form_field_index = (self.form_field_index + 1) % 2; }
+            KeyCode::Char(c) if self.form_field_index == 0 => { self.form_category_name.push(c); }
+            KeyCode::Char(c) if c.is_ascii_digit() => { self.form_category_parent.push(c); }
+            KeyCode::Backspace if self.form_field_index == 0 => { self.form_category_name.pop(); }
+            KeyCode::Backspace => { self.form_category_parent.pop(); }
             _ => {}
         }
     }
 
+    /// The parent typed into the category form, checked the way the API checks it
+    fn category_form_parent(&self, user_id: i64) -> Result<Option<i64>, String> {
+        let input = self.form_category_parent.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+        let parent = input
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.categories.iter().find(|c| c.id == id))
+            .ok_or_else(|| self.tr_fmt("No category with ID {}", &[&input]))?;
+        let category_id = self.editing_category.as_ref().map(|c| c.id);
+        let has_sub_categories = category_id
+            .is_some_and(|id| self.categories.iter().any(|c| c.parent_category_id == Some(id)));
+        categories::validate_parent(user_id, category_id, parent, has_sub_categories)?;
+        Ok(Some(parent.id))
+    }
+
     async fn submit_category(&mut self) {
         if self.form_category_name.trim().is_empty() {
-            self.status_message = "Error: Category name is required!".to_string();
+            self.status_message = self.tr("Error: Category name is required!").to_string();
             self.mode = Mode::Normal;
             return;
         }
@@ -3972,34 +9920,208 @@ impl App {
         let user_id = match self.current_user_id {
             Some(id) => id,
             None => {
-                self.status_message = "Error: No user logged in!".to_string();
+                self.status_message = self.tr("Error: No user logged in!").to_string();
                 self.mode = Mode::Normal;
                 return;
             }
         };
 
-        let result = sqlx::query(
-            "INSERT INTO categories (user_id, name) VALUES (?, ?)"
-        )
-        .bind(user_id)
-        .bind(&self.form_category_name)
-        .execute(&self.pool)
-        .await;
+        let parent_id = match self.category_form_parent(user_id) {
+            Ok(parent_id) => parent_id,
+            Err(e) => {
+                // Stay in the form so the parent can be fixed
+                self.status_message = self.tr_fmt("Error: {}", &[&e]);
+                return;
+            }
+        };
+
+        let result = match self.editing_category.take() {
+            Some(category) => sqlx::query(
+                "UPDATE categories SET name = ?, parent_category_id = ?, updated_at = datetime('now') WHERE id = ?"
+            )
+            .bind(&self.form_category_name)
+            .bind(parent_id)
+            .bind(category.id)
+            .execute(&self.pool)
+            .await
+            .map(|_| self.tr_fmt("Category '{}' updated", &[&self.form_category_name]))
+            .map_err(|e| e.to_string()),
+            None => match &self.remote {
+                Some(remote) => {
+                    let data = CreateCategory {
+                        user_id,
+                        name: self.form_category_name.clone(),
+                        bucket: None,
+                        parent_category_id: parent_id,
+                    };
+                    remote.create_category(&data).await
+                }
+                None => sqlx::query("INSERT INTO categories (user_id, name, parent_category_id) VALUES (?, ?, ?)")
+                    .bind(user_id)
+                    .bind(&self.form_category_name)
+                    .bind(parent_id)
+                    .execute(&self.pool)
+                    .await
+                    .map(|res| res.last_insert_rowid())
+                    .map_err(|e| e.to_string()),
+            }
+            .map(|id| self.tr_fmt("Category '{}' created! ID: {}", &[&self.form_category_name, &id])),
+        };
 
         match result {
-            Ok(res) => {
-                let category_id = res.last_insert_rowid();
-                self.status_message = format!("Category '{}' created! ID: {}", self.form_category_name, category_id);
+            Ok(message) => {
+                self.status_message = message;
+                self.sync_remote().await;
                 self.load_data().await;
                 self.mode = Mode::Normal;
             }
             Err(e) => {
-                self.status_message = format!("Error creating category: {}", e);
+                self.status_message = self.tr_fmt("Error saving category: {}", &[&e]);
                 self.mode = Mode::Normal;
             }
         }
     }
 
+    fn render_onboarding(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(step) = self.onboarding else { return };
+
+        let (title, lines) = match step {
+            OnboardingStep::SeedCategories => (
+                self.tr("Add starter categories?"),
+                vec![
+                    self.tr("Creates a basic set of income and expense categories:").to_string(),
+                    seed::DEFAULT_CATEGORIES.join(", "),
+                    String::new(),
+                    self.tr("You can add or delete categories later on the Categories tab.").to_string(),
+                ],
+            ),
+            OnboardingStep::ScrapeRates => (
+                self.tr("Download exchange rates?"),
+                vec![
+                    self.tr_fmt(
+                        "Fetches today's rates for {} from x-rates.com.",
+                        &[&self.onboarding_rate_currencies().join(", ")],
+                    ),
+                    self.tr("Needed to convert between currencies; requires an internet connection.").to_string(),
+                ],
+            ),
+            OnboardingStep::CreateUser | OnboardingStep::CreateAccount => return,
+        };
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                self.tr_fmt("Setup {}/4: {}", &[&step.number(), &title]),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        text.extend(lines.into_iter().map(|l| Line::from(Span::styled(l, Style::default().fg(Color::White)))));
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            self.help("y: Yes | n: Skip | Esc: Finish setup"),
+            Style::default().fg(Color::Cyan),
+        )));
+
+        let prompt = Paragraph::new(text)
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Getting Started")))
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Left);
+        frame.render_widget(prompt, area);
+    }
+
+    async fn handle_onboarding_mode(&mut self, code: KeyCode) {
+        match (self.onboarding, code) {
+            (_, KeyCode::Esc) => self.finish_onboarding().await,
+            (Some(OnboardingStep::SeedCategories), KeyCode::Char('y')) => {
+                let user_id = self.current_user_id.unwrap_or_default();
+                self.status_message = match seed::create_default_categories(&self.pool, user_id).await {
+                    Ok(count) => self.tr_fmt("Added {} categories", &[&count]),
+                    Err(e) => self.tr_fmt("Error adding categories: {}", &[&e]),
+                };
+                self.onboarding = Some(OnboardingStep::ScrapeRates);
+            }
+            (Some(OnboardingStep::SeedCategories), KeyCode::Char('n')) => {
+                self.onboarding = Some(OnboardingStep::ScrapeRates);
+            }
+            (Some(OnboardingStep::ScrapeRates), KeyCode::Char('y')) => {
+                let summary = self.scrape_onboarding_rates().await;
+                self.finish_onboarding().await;
+                self.status_message = summary;
+            }
+            (Some(OnboardingStep::ScrapeRates), KeyCode::Char('n')) => {
+                self.finish_onboarding().await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Base currencies to scrape: the new accounts' currencies plus FX_SCRAPE_CURRENCIES.
+    fn onboarding_rate_currencies(&self) -> Vec<String> {
+        exchange_scraper::merge_base_currencies(
+            self.accounts.iter().map(|a| a.currency.clone()).collect(),
+            &exchange_scraper::configured_currencies(),
+        )
+    }
+
+    async fn scrape_onboarding_rates(&mut self) -> String {
+        let providers = match rate_providers::RateProviders::configured(None, false) {
+            Ok(providers) => providers,
+            Err(e) => return self.tr_fmt("Setup complete - no exchange rates fetched: {}", &[&e]),
+        };
+        let currencies = self.onboarding_rate_currencies();
+        let started_at = Utc::now();
+        let timer = Instant::now();
+        let mut saved = 0;
+        let mut failed = Vec::new();
+        let mut errors = Vec::new();
+        let mut fetched_all = Vec::new();
+
+        for currency in &currencies {
+            let result = match providers.fetch(currency).await {
+                Ok(fetched) => {
+                    let stored = rate_providers::save_rates(&self.pool, &fetched, Utc::now()).await;
+                    fetched_all.push(fetched);
+                    stored.map(|results| rate_providers::saved_count(&results)).map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(count) => saved += count,
+                Err(e) => {
+                    errors.push(format!("{}: {}", currency, e));
+                    failed.push(currency.clone());
+                }
+            }
+        }
+
+        let metrics = exchange_scraper::ScrapeRunMetrics {
+            provider: rate_providers::providers_used(providers.primary(), &fetched_all),
+            currencies,
+            started_at,
+            duration: timer.elapsed(),
+            rates_saved: saved,
+            currencies_skipped: 0,
+            errors,
+        };
+        let _ = exchange_scraper::record_scrape_run(&self.pool, &metrics).await;
+
+        if failed.is_empty() {
+            self.tr_fmt("Setup complete - saved {} exchange rates", &[&saved])
+        } else {
+            self.tr_fmt(
+                "Setup complete - saved {} exchange rates; could not fetch {} (try 'scrape_rates' later)",
+                &[&saved, &failed.join(", ")],
+            )
+        }
+    }
+
+    async fn finish_onboarding(&mut self) {
+        self.onboarding = None;
+        self.mode = Mode::Normal;
+        self.load_data().await;
+        self.status_message = self.tr("Setup complete").to_string();
+    }
+
     fn clear_user_form(&mut self) {
         self.form_user_username.clear();
         self.form_user_email.clear();
@@ -4018,9 +10140,12 @@ impl App {
 
         // Title
         let title = Paragraph::new(vec![
-            Line::from(Span::styled("Personal Finance Tracker", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(self.tr("Personal Finance Tracker"), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
             Line::from(""),
-            Line::from(Span::styled("Create New User", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(
+                if self.onboarding.is_some() { self.tr("Welcome! Setup 1/4: Create your user") } else { self.tr("Create New User") },
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
         ])
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
@@ -4030,29 +10155,29 @@ impl App {
         let form_text = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("  Username: ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("  Username: "), Style::default().fg(Color::Gray)),
                 Span::styled(&self.form_user_username,
                     if self.form_field_index == 0 { Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED) }
                     else { Style::default().fg(Color::White) }),
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("  Email:    ", Style::default().fg(Color::Gray)),
+                Span::styled(self.tr("  Email:    "), Style::default().fg(Color::Gray)),
                 Span::styled(&self.form_user_email,
                     if self.form_field_index == 1 { Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED) }
                     else { Style::default().fg(Color::White) }),
             ]),
             Line::from(""),
             Line::from(""),
-            Line::from(Span::styled("  (Password will be set to 'password123' by default)", Style::default().fg(Color::DarkGray))),
+            Line::from(Span::styled(self.tr_fmt("  (Password will be set to '{}' by default)", &[&auth::DEFAULT_PASSWORD]), Style::default().fg(Color::DarkGray))),
         ];
 
         let form = Paragraph::new(form_text)
-            .block(Block::default().borders(Borders::ALL).title("New User Details"));
+            .block(Block::default().borders(Borders::ALL).title(self.tr("New User Details")));
         frame.render_widget(form, chunks[1]);
 
         // Instructions
-        let instructions = Paragraph::new("Tab: Next field | Enter: Create | Esc: Cancel")
+        let instructions = Paragraph::new(self.help("Tab: Next field | Enter: Create | Esc: Cancel"))
             .style(Style::default().fg(Color::Cyan))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
@@ -4061,7 +10186,10 @@ impl App {
 
     async fn handle_add_user_mode(&mut self, code: KeyCode) {
         match code {
-            KeyCode::Esc => { self.mode = Mode::Normal; }
+            KeyCode::Esc => {
+                self.onboarding = None;
+                self.mode = Mode::Normal;
+            }
             KeyCode::Tab => { self.form_field_index = (self.form_field_index + 1) % 2; }
             KeyCode::BackTab => {
                 self.form_field_index = if self.form_field_index == 0 { 1 } else { 0 };
@@ -4083,43 +10211,54 @@ impl App {
 
     async fn submit_user(&mut self) {
         if self.form_user_username.trim().is_empty() {
-            self.status_message = "Error: Username is required!".to_string();
+            self.status_message = self.tr("Error: Username is required!").to_string();
             return;
         }
 
         if self.form_user_email.trim().is_empty() {
-            self.status_message = "Error: Email is required!".to_string();
+            self.status_message = self.tr("Error: Email is required!").to_string();
             return;
         }
 
-        // Use a simple default password hash (in production, this should be properly hashed)
-        let default_password_hash = "$argon2id$v=19$m=19456,t=2,p=1$defaulthash";
+        let default_password_hash = match auth::hash_password(auth::DEFAULT_PASSWORD) {
+            Ok(hash) => hash,
+            Err(e) => {
+                self.status_message = self.tr_fmt("Error: {}", &[&e]);
+                return;
+            }
+        };
 
         let result = sqlx::query(
             "INSERT INTO users (username, email, password_hash) VALUES (?, ?, ?)"
         )
         .bind(&self.form_user_username)
         .bind(&self.form_user_email)
-        .bind(default_password_hash)
+        .bind(&default_password_hash)
         .execute(&self.pool)
         .await;
 
         match result {
             Ok(res) => {
                 let user_id = res.last_insert_rowid();
-                self.status_message = format!("User '{}' created! ID: {}", self.form_user_username, user_id);
+                self.status_message = self.tr_fmt("User '{}' created! ID: {}", &[&self.form_user_username, &user_id]);
                 // Reload users list
                 self.users = sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY username")
                     .fetch_all(&self.pool)
                     .await
                     .unwrap_or_default();
                 self.mode = Mode::Normal;
+
+                if self.onboarding == Some(OnboardingStep::CreateUser) {
+                    if let Some(index) = self.users.iter().position(|u| u.id == user_id) {
+                        self.log_in(index).await;
+                    }
+                }
             }
             Err(e) => {
                 if e.to_string().contains("UNIQUE") {
-                    self.status_message = "Error: Username or email already exists!".to_string();
+                    self.status_message = self.tr("Error: Username or email already exists!").to_string();
                 } else {
-                    self.status_message = format!("Error creating user: {}", e);
+                    self.status_message = self.tr_fmt("Error creating user: {}", &[&e]);
                 }
             }
         }
@@ -4137,9 +10276,9 @@ impl App {
 
         // Title
         let title = Paragraph::new(vec![
-            Line::from(Span::styled("Personal Finance Tracker", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(self.tr("Personal Finance Tracker"), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
             Line::from(""),
-            Line::from(Span::styled("Delete User", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(self.tr("Delete User"), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))),
         ])
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
@@ -4154,26 +10293,26 @@ impl App {
 
         let dialog_text = vec![
             Line::from(""),
-            Line::from(Span::styled("⚠ DELETE USER?", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(self.tr("⚠ DELETE USER?"), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))),
             Line::from(""),
-            Line::from(format!("  Username: {}", user.username)),
-            Line::from(format!("  Email: {}", user.email)),
-            Line::from(format!("  ID: {}", user.id)),
+            Line::from(self.tr_fmt("  Username: {}", &[&user.username])),
+            Line::from(self.tr_fmt("  Email: {}", &[&user.email])),
+            Line::from(self.tr_fmt("  ID: {}", &[&user.id])),
             Line::from(""),
-            Line::from(Span::styled("  ⚠ WARNING: This will PERMANENTLY delete:", Style::default().fg(Color::Yellow))),
-            Line::from(Span::styled("    - All accounts for this user", Style::default().fg(Color::Yellow))),
-            Line::from(Span::styled("    - All transactions for this user", Style::default().fg(Color::Yellow))),
-            Line::from(Span::styled("    - All categories for this user", Style::default().fg(Color::Yellow))),
-            Line::from(Span::styled("    - All recurring transactions", Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(self.tr("  ⚠ WARNING: This will PERMANENTLY delete:"), Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(self.tr("    - All accounts for this user"), Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(self.tr("    - All transactions for this user"), Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(self.tr("    - All categories for this user"), Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(self.tr("    - All recurring transactions"), Style::default().fg(Color::Yellow))),
             Line::from(""),
         ];
 
         let dialog = Paragraph::new(dialog_text)
-            .block(Block::default().borders(Borders::ALL).title("Confirm Delete"));
+            .block(Block::default().borders(Borders::ALL).title(self.tr("Confirm Delete")));
         frame.render_widget(dialog, chunks[1]);
 
         // Instructions
-        let instructions = Paragraph::new("y: Confirm Delete | n/Esc: Cancel")
+        let instructions = Paragraph::new(self.help("y: Confirm Delete | n/Esc: Cancel"))
             .style(Style::default().fg(Color::Cyan))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
@@ -4189,39 +10328,45 @@ impl App {
                     let username = user.username.clone();
 
                     // Delete in order: transaction_categories, transactions, recurring_transactions, categories, accounts, user
-                    // 1. Delete transaction_categories for all user's transactions
-                    let _ = sqlx::query(
-                        "DELETE FROM transaction_categories WHERE transaction_id IN 
-                         (SELECT id FROM transactions WHERE account_id IN 
-                          (SELECT id FROM accounts WHERE user_id = ?))"
-                    ).bind(user_id).execute(&self.pool).await;
-
-                    // 2. Delete all transactions for user's accounts
-                    let _ = sqlx::query(
-                        "DELETE FROM transactions WHERE account_id IN (SELECT id FROM accounts WHERE user_id = ?)"
-                    ).bind(user_id).execute(&self.pool).await;
-
-                    // 3. Delete recurring transactions
-                    let _ = sqlx::query("DELETE FROM recurring_transactions WHERE account_id IN (SELECT id FROM accounts WHERE user_id = ?)")
-                        .bind(user_id).execute(&self.pool).await;
-
-                    // 4. Delete categories
-                    let _ = sqlx::query("DELETE FROM categories WHERE user_id = ?")
-                        .bind(user_id).execute(&self.pool).await;
-
-                    // 5. Delete accounts
-                    let _ = sqlx::query("DELETE FROM accounts WHERE user_id = ?")
-                        .bind(user_id).execute(&self.pool).await;
-
-                    // 6. Delete user
-                    let result = sqlx::query("DELETE FROM users WHERE id = ?")
-                        .bind(user_id)
-                        .execute(&self.pool)
-                        .await;
+                    // in one transaction, so a failure part way leaves the user as it was
+                    let result = async {
+                        let mut tx = self.pool.begin().await?;
+                        // 1. Delete transaction_categories for all user's transactions
+                        sqlx::query(
+                            "DELETE FROM transaction_categories WHERE transaction_id IN 
+                             (SELECT id FROM transactions WHERE account_id IN 
+                              (SELECT id FROM accounts WHERE user_id = ?))"
+                        ).bind(user_id).execute(&mut *tx).await?;
+
+                        // 2. Delete all transactions for user's accounts
+                        sqlx::query(
+                            "DELETE FROM transactions WHERE account_id IN (SELECT id FROM accounts WHERE user_id = ?)"
+                        ).bind(user_id).execute(&mut *tx).await?;
+
+                        // 3. Delete recurring transactions
+                        sqlx::query("DELETE FROM recurring_transactions WHERE account_id IN (SELECT id FROM accounts WHERE user_id = ?)")
+                            .bind(user_id).execute(&mut *tx).await?;
+
+                        // 4. Delete categories
+                        sqlx::query("DELETE FROM categories WHERE user_id = ?")
+                            .bind(user_id).execute(&mut *tx).await?;
+
+                        // 5. Delete accounts
+                        sqlx::query("DELETE FROM accounts WHERE user_id = ?")
+                            .bind(user_id).execute(&mut *tx).await?;
+
+                        // 6. Delete user
+                        sqlx::query("DELETE FROM users WHERE id = ?")
+                            .bind(user_id)
+                            .execute(&mut *tx)
+                            .await?;
+                        tx.commit().await
+                    }
+                    .await;
 
                     match result {
                         Ok(_) => {
-                            self.status_message = format!("User '{}' and all data deleted!", username);
+                            self.status_message = self.tr_fmt("User '{}' and all data deleted!", &[&username]);
                             // Reload users list
                             self.users = sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY username")
                                 .fetch_all(&self.pool)
@@ -4230,7 +10375,7 @@ impl App {
                             self.selected_index = 0;
                         }
                         Err(e) => {
-                            self.status_message = format!("Error deleting user: {}", e);
+                            self.status_message = self.tr_fmt("Error deleting user: {}", &[&e]);
                         }
                     }
                 }
@@ -0,0 +1,267 @@
+// undo.rs
+// Snapshots of rows about to be deleted, so the TUI can put them back. A
+// snapshot follows the schema's foreign keys: rows that would cascade with
+// the deleted ones are saved too, and references that ON DELETE SET NULL
+// would clear are remembered, so restoring an account brings back its
+// transactions, their splits, tags and attachments, and any pointers to them.
+
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+
+/// Deletions the TUI keeps for undo; older ones can no longer be restored
+pub const LIMIT: usize = 20;
+
+/// How far foreign keys are followed (account -> transaction -> split)
+const MAX_DEPTH: usize = 4;
+
+/// Saved rows of one table, as a JSON array of objects
+#[derive(Debug, Clone)]
+struct TableRows {
+    table: String,
+    columns: Vec<String>,
+    rows: String,
+}
+
+/// Values of `table.column` that deleting the rows sets to NULL, as a JSON
+/// array of [rowid, value] pairs
+#[derive(Debug, Clone)]
+struct ClearedReferences {
+    table: String,
+    column: String,
+    values: String,
+}
+
+/// Everything one deletion removes, enough to restore it
+#[derive(Debug, Clone)]
+pub struct Deletion {
+    pub label: String,
+    tables: Vec<TableRows>,
+    cleared: Vec<ClearedReferences>,
+    balance_changes: Vec<(i64, f64)>, // (account id, amount to add back)
+    pub files: Vec<PathBuf>,          // Kept on disk until the deletion can no longer be undone
+}
+
+impl Deletion {
+    /// Add `amount` back to the account's current balance on restore
+    pub fn restore_balance(&mut self, account_id: i64, amount: f64) {
+        self.balance_changes.push((account_id, amount));
+    }
+
+    /// Number of rows saved across all tables
+    pub fn row_count(&self) -> usize {
+        self.tables
+            .iter()
+            .filter_map(|t| serde_json::from_str::<Vec<serde_json::Value>>(&t.rows).ok())
+            .map(|rows| rows.len())
+            .sum()
+    }
+}
+
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+async fn columns(pool: &SqlitePool, table: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT name FROM pragma_table_info(?) ORDER BY cid")
+        .bind(table)
+        .fetch_all(pool)
+        .await
+}
+
+/// (table, column, referenced column, on_delete) of every foreign key pointing at `table`
+async fn references_to(pool: &SqlitePool, table: &str) -> Result<Vec<(String, String, String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT m.name, f.\"from\", COALESCE(f.\"to\", 'id'), f.on_delete
+         FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) f
+         WHERE m.type = 'table' AND f.\"table\" = ?
+         ORDER BY m.name, f.\"from\"",
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await
+}
+
+/// Save the rows of `table` matching `condition`, then whatever depends on them
+fn save<'a>(
+    pool: &'a SqlitePool,
+    deletion: &'a mut Deletion,
+    table: String,
+    condition: String,
+    depth: usize,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), sqlx::Error>> + Send + 'a>> {
+    Box::pin(async move {
+        let columns = columns(pool, &table).await?;
+        let fields: Vec<String> = columns.iter().map(|c| format!("'{}', {}", c.replace('\'', "''"), quoted(c))).collect();
+        let rows: String = sqlx::query_scalar(&format!(
+            "SELECT json_group_array(json_object({})) FROM {} WHERE {}",
+            fields.join(", "),
+            quoted(&table),
+            condition
+        ))
+        .fetch_one(pool)
+        .await?;
+        if rows == "[]" {
+            return Ok(());
+        }
+        deletion.tables.push(TableRows { table: table.clone(), columns, rows });
+        if depth >= MAX_DEPTH {
+            return Ok(());
+        }
+
+        for (child, column, parent_column, on_delete) in references_to(pool, &table).await? {
+            let matching = format!(
+                "{} IN (SELECT {} FROM {} WHERE {})",
+                quoted(&column),
+                quoted(&parent_column),
+                quoted(&table),
+                condition
+            );
+            match on_delete.as_str() {
+                "CASCADE" => save(pool, deletion, child, matching, depth + 1).await?,
+                "SET NULL" => {
+                    let values: String = sqlx::query_scalar(&format!(
+                        "SELECT json_group_array(json_array(rowid, {})) FROM {} WHERE {}",
+                        quoted(&column),
+                        quoted(&child),
+                        matching
+                    ))
+                    .fetch_one(pool)
+                    .await?;
+                    if values != "[]" {
+                        deletion.cleared.push(ClearedReferences { table: child, column, values });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Snapshot the `table` rows with these ids, and everything that would go
+/// or be unlinked with them, before deleting them
+pub async fn capture(pool: &SqlitePool, label: &str, table: &str, ids: &[i64]) -> Result<Deletion, sqlx::Error> {
+    let mut deletion = Deletion {
+        label: label.to_string(),
+        tables: Vec::new(),
+        cleared: Vec::new(),
+        balance_changes: Vec::new(),
+        files: Vec::new(),
+    };
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    save(pool, &mut deletion, table.to_string(), format!("id IN ({})", ids.join(", ")), 0).await?;
+    Ok(deletion)
+}
+
+/// Put the deleted rows back with their original ids. Fails, changing
+/// nothing, if one of the ids has been taken since.
+pub async fn restore(pool: &SqlitePool, deletion: &Deletion) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    // Rows come back parents first, but a row can point at one restored later
+    sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut *tx).await?;
+
+    for saved in &deletion.tables {
+        let names: Vec<String> = saved.columns.iter().map(|c| quoted(c)).collect();
+        let values: Vec<String> = saved
+            .columns
+            .iter()
+            .map(|c| format!("json_extract(value, '$.\"{}\"')", c.replace('\'', "''").replace('"', "\\\"")))
+            .collect();
+        sqlx::query(&format!(
+            "INSERT INTO {} ({}) SELECT {} FROM json_each(?)",
+            quoted(&saved.table),
+            names.join(", "),
+            values.join(", ")
+        ))
+        .bind(&saved.rows)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for cleared in &deletion.cleared {
+        sqlx::query(&format!(
+            "UPDATE {table} SET {column} = (
+                 SELECT json_extract(value, '$[1]') FROM json_each(?1) WHERE json_extract(value, '$[0]') = {table}.rowid
+             )
+             WHERE rowid IN (SELECT json_extract(value, '$[0]') FROM json_each(?1))",
+            table = quoted(&cleared.table),
+            column = quoted(&cleared.column)
+        ))
+        .bind(&cleared.values)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (account_id, amount) in &deletion.balance_changes {
+        sqlx::query("UPDATE accounts SET current_balance = current_balance + ? WHERE id = ?")
+            .bind(amount)
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await
+}
+
+/// Remove the files a deletion kept for undo, once it can't be undone anymore
+pub fn discard(deletion: Deletion) {
+    for file in deletion.files {
+        let _ = std::fs::remove_file(file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{reimbursements, tags, testing};
+    use crate::models::Transaction;
+
+    #[tokio::test]
+    async fn test_deleted_account_comes_back_with_its_transactions() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let checking = testing::account(user.id).balance(500.0).insert(&pool).await;
+        let savings = testing::account(user.id).name("Savings").insert(&pool).await;
+        let food = testing::category(&pool, user.id, "Food").await;
+        let lunch = testing::txn(checking.id).amount(12.5).category(food.id).insert(&pool).await;
+        let tag = tags::create_tag(&pool, user.id, "work").await.unwrap();
+        tags::add_tag(&pool, lunch.id, tag.id).await.unwrap();
+        // A reimbursement in another account points at the lunch (ON DELETE SET NULL)
+        let payback = testing::txn(savings.id).amount(12.5).income().insert(&pool).await;
+        reimbursements::link(&pool, lunch.id, payback.id).await.unwrap();
+
+        let deletion = capture(&pool, "Account 'Checking'", "accounts", &[checking.id]).await.unwrap();
+        assert_eq!(deletion.row_count(), 4); // account, transaction, split, tag
+        sqlx::query("DELETE FROM accounts WHERE id = ?").bind(checking.id).execute(&pool).await.unwrap();
+
+        let unlinked: Transaction = sqlx::query_as("SELECT * FROM transactions WHERE id = ?")
+            .bind(payback.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(unlinked.reimburses_transaction_id, None);
+
+        restore(&pool, &deletion).await.unwrap();
+        let restored: Transaction = sqlx::query_as("SELECT * FROM transactions WHERE id = ?")
+            .bind(lunch.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((restored.amount, restored.transaction_date), (lunch.amount, lunch.transaction_date));
+        assert_eq!(tags::tags_for_transaction(&pool, lunch.id).await.unwrap(), ["work"]);
+        let relinked: Option<i64> = sqlx::query_scalar("SELECT reimburses_transaction_id FROM transactions WHERE id = ?")
+            .bind(payback.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(relinked, Some(lunch.id));
+        let balance: f64 = sqlx::query_scalar("SELECT current_balance FROM accounts WHERE id = ?")
+            .bind(checking.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(balance, 500.0);
+
+        // Restoring twice would reuse the ids, so it fails without changing anything
+        assert!(restore(&pool, &deletion).await.is_err());
+    }
+}
@@ -0,0 +1,100 @@
+// web_assets.rs
+// Optional static web frontend served next to the API: files from WEB_DIR
+// (default ./web), with index.html as the fallback for client-side routes
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+use crate::models::ApiResponse;
+
+/// Root directory of the frontend; None when it doesn't exist (serving disabled).
+pub fn web_dir() -> Option<PathBuf> {
+    let dir = PathBuf::from(env::var("WEB_DIR").unwrap_or_else(|_| "./web".to_string()));
+    dir.is_dir().then_some(dir)
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("html") | Some("htm") => "text/html; charset=utf-8",
+        Some("js") | Some("mjs") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") | Some("map") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("ico") => "image/x-icon",
+        Some("webp") => "image/webp",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// File under `root` for a request path. Paths that try to leave the root
+/// ("..", absolute segments) resolve to nothing; "/" maps to index.html.
+pub fn resolve(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let relative = Path::new(request_path.trim_start_matches('/'));
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return None;
+    }
+
+    let path = root.join(relative);
+    if path.is_dir() {
+        Some(path.join("index.html"))
+    } else {
+        Some(path)
+    }
+}
+
+/// Routes without a file extension are client-side routes of the SPA.
+fn is_spa_route(request_path: &str) -> bool {
+    Path::new(request_path).extension().is_none()
+}
+
+/// Default service: serve the requested file, or index.html for SPA routes.
+pub async fn serve(req: HttpRequest, root: web::Data<PathBuf>) -> HttpResponse {
+    let not_found = || HttpResponse::NotFound().json(ApiResponse::<()>::error("Not found".into()));
+    if req.method() != actix_web::http::Method::GET && req.method() != actix_web::http::Method::HEAD {
+        return not_found();
+    }
+
+    let request_path = req.path();
+    let Some(path) = resolve(&root, request_path) else {
+        return not_found();
+    };
+
+    let (path, contents) = match tokio::fs::read(&path).await {
+        Ok(contents) => (path, contents),
+        Err(_) if is_spa_route(request_path) => {
+            let index = root.join("index.html");
+            match tokio::fs::read(&index).await {
+                Ok(contents) => (index, contents),
+                Err(_) => return not_found(),
+            }
+        }
+        Err(_) => return not_found(),
+    };
+
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, content_type(&path)))
+        .body(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_stays_inside_root() {
+        let root = Path::new("/srv/web");
+
+        assert_eq!(resolve(root, "/app.js"), Some(PathBuf::from("/srv/web/app.js")));
+        assert_eq!(resolve(root, "/assets/logo.svg"), Some(PathBuf::from("/srv/web/assets/logo.svg")));
+        assert_eq!(resolve(root, "/../secret.db"), None);
+        assert_eq!(resolve(root, "/assets/../../etc/passwd"), None);
+        assert_eq!(content_type(Path::new("app.JS")), "text/javascript; charset=utf-8");
+    }
+}
@@ -0,0 +1,215 @@
+use chrono::{Duration, Utc};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::models::WebhookDelivery;
+
+/// Give up on a delivery after this many failed attempts.
+const MAX_ATTEMPTS: i64 = 8;
+/// Delay before the first retry; doubled after every failed attempt.
+const BASE_BACKOFF_SECS: i64 = 30;
+/// Upper bound for the retry delay.
+const MAX_BACKOFF_SECS: i64 = 6 * 60 * 60;
+/// Maximum number of outbox rows handled per dispatcher run.
+const BATCH_SIZE: i64 = 50;
+
+/// Result from one dispatcher run over the outbox.
+pub struct DispatchResult {
+    pub attempted: usize,
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+/// Write an event into the outbox for every active webhook of the user subscribed to it.
+///
+/// Takes a connection rather than the pool so callers can pass their open
+/// transaction (`&mut *tx`), making the outbox row commit or roll back
+/// together with the domain change that produced the event.
+pub async fn enqueue_event(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    event_type: &str,
+    payload: &serde_json::Value,
+) -> Result<u64, sqlx::Error> {
+    let body = serde_json::json!({
+        "event": event_type,
+        "occurred_at": Utc::now(),
+        "data": payload,
+    })
+    .to_string();
+
+    let result = sqlx::query(
+        "INSERT INTO event_outbox (webhook_id, event_type, payload)
+         SELECT id, ?, ? FROM webhooks
+         WHERE user_id = ? AND is_active = 1
+         AND (event_types = '*' OR (',' || REPLACE(event_types, ' ', '') || ',') LIKE ('%,' || ? || ',%'))",
+    )
+    .bind(event_type)
+    .bind(&body)
+    .bind(user_id)
+    .bind(event_type)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Write an event into the outbox for one webhook, whatever it subscribes to.
+/// Used for scheduled deliveries such as monthly reports, where the caller
+/// has already picked the webhooks.
+pub async fn enqueue_for_webhook(
+    conn: &mut SqliteConnection,
+    webhook_id: i64,
+    event_type: &str,
+    payload: &serde_json::Value,
+) -> Result<u64, sqlx::Error> {
+    let body = serde_json::json!({
+        "event": event_type,
+        "occurred_at": Utc::now(),
+        "data": payload,
+    })
+    .to_string();
+
+    let result = sqlx::query("INSERT INTO event_outbox (webhook_id, event_type, payload) VALUES (?, ?, ?)")
+        .bind(webhook_id)
+        .bind(event_type)
+        .bind(&body)
+        .execute(conn)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Deliver all pending outbox rows whose next attempt is due:
+/// - POST the payload to the webhook URL
+/// - mark 2xx responses as delivered
+/// - reschedule other outcomes with exponential backoff, or mark failed after MAX_ATTEMPTS
+pub async fn dispatch_pending(
+    pool: &SqlitePool,
+    client: &reqwest::Client,
+) -> Result<DispatchResult, sqlx::Error> {
+    use sqlx::Row;
+
+    let now = Utc::now();
+
+    let rows = sqlx::query(
+        "SELECT o.id, o.event_type, o.payload, o.attempts, w.url
+         FROM event_outbox o
+         JOIN webhooks w ON o.webhook_id = w.id
+         WHERE o.status = 'pending' AND o.next_attempt_at <= ? AND w.is_active = 1
+         ORDER BY o.next_attempt_at ASC
+         LIMIT ?",
+    )
+    .bind(now)
+    .bind(BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let mut delivered_count = 0;
+    let mut failed_count = 0;
+
+    for row in &rows {
+        let id: i64 = row.get("id");
+        let event_type: String = row.get("event_type");
+        let payload: String = row.get("payload");
+        let attempts: i64 = row.get::<i64, _>("attempts") + 1;
+        let url: String = row.get("url");
+
+        let response = client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Event", &event_type)
+            .header("X-Webhook-Delivery", id.to_string())
+            .body(payload)
+            .send()
+            .await;
+
+        let (status_code, error) = match response {
+            Ok(resp) if resp.status().is_success() => (Some(resp.status().as_u16() as i64), None),
+            Ok(resp) => (
+                Some(resp.status().as_u16() as i64),
+                Some(format!("Webhook responded with HTTP {}", resp.status())),
+            ),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        match error {
+            None => {
+                sqlx::query(
+                    "UPDATE event_outbox SET status = 'delivered', attempts = ?, last_response_status = ?,
+                     last_error = NULL, delivered_at = ? WHERE id = ?",
+                )
+                .bind(attempts)
+                .bind(status_code)
+                .bind(Utc::now())
+                .bind(id)
+                .execute(pool)
+                .await?;
+                delivered_count += 1;
+            }
+            Some(error) => {
+                let status = if attempts >= MAX_ATTEMPTS { "failed" } else { "pending" };
+                let next_attempt = Utc::now() + calculate_backoff(attempts);
+
+                sqlx::query(
+                    "UPDATE event_outbox SET status = ?, attempts = ?, last_response_status = ?,
+                     last_error = ?, next_attempt_at = ? WHERE id = ?",
+                )
+                .bind(status)
+                .bind(attempts)
+                .bind(status_code)
+                .bind(&error)
+                .bind(next_attempt)
+                .bind(id)
+                .execute(pool)
+                .await?;
+
+                if status == "failed" {
+                    failed_count += 1;
+                }
+            }
+        }
+    }
+
+    Ok(DispatchResult {
+        attempted: rows.len(),
+        delivered: delivered_count,
+        failed: failed_count,
+    })
+}
+
+/// Fetch the delivery history of a webhook, newest first.
+pub async fn list_deliveries(
+    pool: &SqlitePool,
+    webhook_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    sqlx::query_as::<_, WebhookDelivery>(
+        "SELECT * FROM event_outbox WHERE webhook_id = ? ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?",
+    )
+    .bind(webhook_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+/// Delay before the next attempt after `attempts` failed deliveries.
+fn calculate_backoff(attempts: i64) -> Duration {
+    let exponent = (attempts - 1).clamp(0, 20) as u32;
+    let secs = BASE_BACKOFF_SECS.saturating_mul(2_i64.pow(exponent));
+    Duration::seconds(secs.min(MAX_BACKOFF_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(calculate_backoff(1), Duration::seconds(30));
+        assert_eq!(calculate_backoff(2), Duration::seconds(60));
+        assert_eq!(calculate_backoff(3), Duration::seconds(120));
+        assert_eq!(calculate_backoff(30), Duration::seconds(MAX_BACKOFF_SECS));
+    }
+}
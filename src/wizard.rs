@@ -0,0 +1,272 @@
+// wizard.rs
+// Multi-step forms for the TUI: one step of fields at a time under a step
+// indicator. Enter asks to move on, which the owner grants with advance()
+// once the step validates (or refuses with fail()); Esc goes back a step and
+// cancels from the first one.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::i18n::{self, Language};
+
+/// A typed value, or one of a list of options picked with ←/→
+pub struct Field {
+    pub key: &'static str,
+    pub label: &'static str, // As in "Amount: "
+    pub hint: Option<String>,
+    pub text: String,
+    options: Vec<(String, String)>, // (value, label); empty for typed fields
+    choice: usize,
+}
+
+impl Field {
+    pub fn text(key: &'static str, label: &'static str) -> Self {
+        Field { key, label, hint: None, text: String::new(), options: Vec::new(), choice: 0 }
+    }
+
+    pub fn choice(key: &'static str, label: &'static str, options: Vec<(String, String)>) -> Self {
+        Field { key, label, hint: None, text: String::new(), options, choice: 0 }
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Preselect the option with this value, if there is one
+    pub fn with_choice(mut self, value: &str) -> Self {
+        if let Some(index) = self.options.iter().position(|(v, _)| v == value) {
+            self.choice = index;
+        }
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// The typed text, or the value of the picked option
+    pub fn value(&self) -> &str {
+        match self.options.get(self.choice) {
+            Some((value, _)) => value,
+            None => &self.text,
+        }
+    }
+}
+
+pub struct Step {
+    pub title: &'static str,
+    pub fields: Vec<Field>,
+}
+
+impl Step {
+    pub fn new(title: &'static str, fields: Vec<Field>) -> Self {
+        Step { title, fields }
+    }
+}
+
+/// What a key press asks of the wizard's owner
+#[derive(Debug, PartialEq)]
+pub enum WizardEvent {
+    Pending,
+    Cancel,
+    Next,   // Validate the current step, then advance()
+    Finish, // Validate the last step, then submit
+}
+
+pub struct Wizard {
+    pub title: &'static str,
+    pub steps: Vec<Step>,
+    pub step: usize,
+    pub field: usize, // Focused field of the current step
+    pub error: Option<String>,
+}
+
+impl Wizard {
+    pub fn new(title: &'static str, steps: Vec<Step>) -> Self {
+        Wizard { title, steps, step: 0, field: 0, error: None }
+    }
+
+    fn find(&self, key: &str) -> Option<&Field> {
+        self.steps.iter().flat_map(|s| &s.fields).find(|f| f.key == key)
+    }
+
+    /// Value of the field with this key, on any step
+    pub fn value(&self, key: &str) -> &str {
+        self.find(key).map(Field::value).unwrap_or_default()
+    }
+
+    pub fn is_last_step(&self) -> bool {
+        self.step + 1 >= self.steps.len()
+    }
+
+    pub fn advance(&mut self) {
+        if !self.is_last_step() {
+            self.step += 1;
+            self.field = 0;
+        }
+        self.error = None;
+    }
+
+    /// Stay on the step and show why
+    pub fn fail(&mut self, error: impl Into<String>) {
+        self.error = Some(error.into());
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> WizardEvent {
+        let count = self.steps[self.step].fields.len().max(1);
+        let last = self.is_last_step();
+        let field = self.steps[self.step].fields.get_mut(self.field);
+        match code {
+            KeyCode::Esc if self.step == 0 => return WizardEvent::Cancel,
+            KeyCode::Esc => {
+                self.step -= 1;
+                self.field = 0;
+                self.error = None;
+            }
+            KeyCode::Enter if last => return WizardEvent::Finish,
+            KeyCode::Enter => return WizardEvent::Next,
+            KeyCode::Tab | KeyCode::Down => self.field = (self.field + 1) % count,
+            KeyCode::BackTab | KeyCode::Up => self.field = (self.field + count - 1) % count,
+            KeyCode::Left | KeyCode::Right => {
+                if let Some(field) = field.filter(|f| !f.options.is_empty()) {
+                    let n = field.options.len();
+                    field.choice = if code == KeyCode::Left { (field.choice + n - 1) % n } else { (field.choice + 1) % n };
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(field) = field.filter(|f| f.options.is_empty()) {
+                    field.text.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(field) = field.filter(|f| f.options.is_empty()) {
+                    field.text.pop();
+                }
+            }
+            _ => {}
+        }
+        WizardEvent::Pending
+    }
+
+    /// Footer help for the current step
+    pub fn help(&self) -> &'static str {
+        match (self.step == 0, self.is_last_step()) {
+            (true, true) => "Tab/↑↓: Field | ←/→: Choose | Enter: Finish | Esc: Cancel",
+            (true, false) => "Tab/↑↓: Field | ←/→: Choose | Enter: Next | Esc: Cancel",
+            (false, true) => "Tab/↑↓: Field | ←/→: Choose | Enter: Finish | Esc: Back",
+            (false, false) => "Tab/↑↓: Field | ←/→: Choose | Enter: Next | Esc: Back",
+        }
+    }
+
+    /// Draw the current step as a popup centered in `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, language: Language) {
+        let tr = |text| i18n::tr(language, text);
+        let mut indicator = vec![Span::styled(
+            format!("{} {}/{}  ", tr("Step"), self.step + 1, self.steps.len()),
+            Style::default().fg(Color::Gray),
+        )];
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                indicator.push(Span::styled(" › ", Style::default().fg(Color::DarkGray)));
+            }
+            let (mark, style) = if i < self.step {
+                ("✓", Style::default().fg(Color::Green))
+            } else if i == self.step {
+                ("●", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            } else {
+                ("○", Style::default().fg(Color::DarkGray))
+            };
+            indicator.push(Span::styled(format!("{} {}", mark, tr(step.title)), style));
+        }
+
+        let mut lines = vec![Line::from(indicator), Line::from("")];
+        for (i, field) in self.steps[self.step].fields.iter().enumerate() {
+            let focused = i == self.field;
+            let style = if focused {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let value = match field.options.get(field.choice) {
+                Some((_, label)) => format!("◄ {} ►", tr(label)),
+                None if focused => format!("{}_", field.text),
+                None => field.text.clone(),
+            };
+            let mut spans = vec![
+                Span::styled(tr(field.label), Style::default().fg(Color::Gray)),
+                Span::styled(value, style),
+            ];
+            if let Some(hint) = &field.hint {
+                spans.push(Span::styled(format!("  {}", tr(hint)), Style::default().fg(Color::DarkGray)));
+            }
+            lines.push(Line::from(spans));
+        }
+        if let Some(error) = &self.error {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))));
+        }
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + area.width / 6,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: area.width * 2 / 3,
+            height,
+        };
+        frame.render_widget(Clear, popup);
+        let form = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(tr(self.title)));
+        frame.render_widget(form, popup);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wizard_steps_forward_only_when_advanced() {
+        let options = vec![("1".to_string(), "Checking".to_string()), ("2".to_string(), "Savings".to_string())];
+        let mut wizard = Wizard::new(
+            "Add Transfer",
+            vec![
+                Step::new("Accounts", vec![Field::choice("from", "From: ", options).with_choice("2")]),
+                Step::new("Amount", vec![Field::text("amount", "Amount: "), Field::text("note", "Description: ")]),
+            ],
+        );
+        assert_eq!(wizard.value("from"), "2");
+        assert_eq!(wizard.handle_key(KeyCode::Right), WizardEvent::Pending);
+        assert_eq!(wizard.value("from"), "1");
+        // Typing into a choice does nothing
+        wizard.handle_key(KeyCode::Char('x'));
+        assert_eq!(wizard.value("from"), "1");
+
+        // Next waits for the owner
+        assert_eq!(wizard.handle_key(KeyCode::Enter), WizardEvent::Next);
+        assert_eq!(wizard.step, 0);
+        wizard.fail("Pick two different accounts");
+        wizard.advance();
+        assert_eq!((wizard.step, wizard.error.as_deref()), (1, None));
+
+        for c in "12.5".chars() {
+            wizard.handle_key(KeyCode::Char(c));
+        }
+        wizard.handle_key(KeyCode::Backspace);
+        wizard.handle_key(KeyCode::Up);
+        assert_eq!((wizard.value("amount"), wizard.field), ("12.", 1));
+        assert_eq!(wizard.handle_key(KeyCode::Enter), WizardEvent::Finish);
+
+        // Back keeps what was entered, then cancels from the first step
+        assert_eq!(wizard.handle_key(KeyCode::Esc), WizardEvent::Pending);
+        assert_eq!((wizard.step, wizard.value("from")), (0, "1"));
+        assert_eq!(wizard.handle_key(KeyCode::Esc), WizardEvent::Cancel);
+        assert_eq!(wizard.value("missing"), "");
+    }
+}
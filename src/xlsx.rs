@@ -0,0 +1,513 @@
+// xlsx.rs
+// Excel workbooks for the financial summary export: accounts, transactions,
+// categories and a monthly summary, each on its own sheet with a bold frozen
+// header row, two-decimal amounts and real dates. An .xlsx file is a zip of
+// XML parts; entries are stored uncompressed, which every reader accepts.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::exclusions;
+use crate::models::{Account, Category, MonthlySummary, Transaction};
+
+pub const CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+// Cell formats, as indexes into cellXfs in styles.xml
+const STYLE_HEADER: u8 = 1;
+const STYLE_AMOUNT: u8 = 2;
+const STYLE_DATE: u8 = 3;
+
+/// Widest a column gets, in characters
+const MAX_COLUMN_WIDTH: usize = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Amount(f64),
+    Date(NaiveDate),
+    Empty,
+}
+
+impl Cell {
+    fn text(value: impl Into<String>) -> Cell {
+        Cell::Text(value.into())
+    }
+
+    fn optional(value: Option<&str>) -> Cell {
+        value.map_or(Cell::Empty, Cell::text)
+    }
+
+    /// Characters the value takes on screen, for the column width
+    fn width(&self) -> usize {
+        match self {
+            Cell::Text(s) => s.chars().count(),
+            Cell::Number(n) => n.to_string().len(),
+            Cell::Amount(n) => format!("{:.2}", n).len() + 2,
+            Cell::Date(_) => 10,
+            Cell::Empty => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    pub name: String,
+    pub header: Vec<&'static str>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .chars()
+        // XML 1.0 has no way to write most control characters
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Column letters for a 0-based index: A..Z, AA..
+fn column_name(mut index: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.insert(0, (b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            return name;
+        }
+        index = index / 26 - 1;
+    }
+}
+
+/// Excel's serial day number (days since 1899-12-30)
+fn date_serial(date: NaiveDate) -> i64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap_or_default();
+    (date - epoch).num_days()
+}
+
+/// Sheet names are at most 31 characters and can't contain []:*?/\
+fn sheet_name(name: &str) -> String {
+    let name: String = name.chars().filter(|c| !"[]:*?/\\".contains(*c)).take(31).collect();
+    if name.is_empty() {
+        "Sheet".to_string()
+    } else {
+        name
+    }
+}
+
+fn sheet_xml(sheet: &Sheet) -> String {
+    let mut widths: Vec<usize> = sheet.header.iter().map(|h| h.chars().count()).collect();
+    for row in &sheet.rows {
+        for (i, cell) in row.iter().enumerate() {
+            if i >= widths.len() {
+                widths.push(0);
+            }
+            widths[i] = widths[i].max(cell.width());
+        }
+    }
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+         <sheetViews><sheetView workbookViewId=\"0\">\
+         <pane ySplit=\"1\" topLeftCell=\"A2\" activePane=\"bottomLeft\" state=\"frozen\"/>\
+         </sheetView></sheetViews>",
+    );
+    if !widths.is_empty() {
+        xml.push_str("<cols>");
+        for (i, width) in widths.iter().enumerate() {
+            xml.push_str(&format!(
+                "<col min=\"{0}\" max=\"{0}\" width=\"{1}\" customWidth=\"1\"/>",
+                i + 1,
+                (width + 2).min(MAX_COLUMN_WIDTH)
+            ));
+        }
+        xml.push_str("</cols>");
+    }
+
+    xml.push_str("<sheetData>");
+    let header: Vec<Cell> = sheet.header.iter().map(|h| Cell::text(*h)).collect();
+    for (r, row) in std::iter::once(&header).chain(&sheet.rows).enumerate() {
+        xml.push_str(&format!("<row r=\"{}\">", r + 1));
+        for (c, cell) in row.iter().enumerate() {
+            let at = format!("{}{}", column_name(c), r + 1);
+            let style = if r == 0 { STYLE_HEADER } else { 0 };
+            match cell {
+                Cell::Text(s) => xml.push_str(&format!(
+                    "<c r=\"{}\" s=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                    at,
+                    style,
+                    xml_escape(s)
+                )),
+                Cell::Number(n) if n.is_finite() => xml.push_str(&format!("<c r=\"{}\"><v>{}</v></c>", at, n)),
+                Cell::Amount(n) if n.is_finite() => {
+                    xml.push_str(&format!("<c r=\"{}\" s=\"{}\"><v>{}</v></c>", at, STYLE_AMOUNT, n))
+                }
+                Cell::Date(d) => {
+                    xml.push_str(&format!("<c r=\"{}\" s=\"{}\"><v>{}</v></c>", at, STYLE_DATE, date_serial(*d)))
+                }
+                _ => {}
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+const STYLES_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+<numFmts count=\"1\"><numFmt numFmtId=\"164\" formatCode=\"yyyy-mm-dd\"/></numFmts>\
+<fonts count=\"2\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font><font><b/><sz val=\"11\"/><name val=\"Calibri\"/></font></fonts>\
+<fills count=\"2\"><fill><patternFill patternType=\"none\"/></fill><fill><patternFill patternType=\"gray125\"/></fill></fills>\
+<borders count=\"1\"><border><left/><right/><top/><bottom/><diagonal/></border></borders>\
+<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+<cellXfs count=\"4\">\
+<xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+<xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/>\
+<xf numFmtId=\"4\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+<xf numFmtId=\"164\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+</cellXfs>\
+<cellStyles count=\"1\"><cellStyle name=\"Normal\" xfId=\"0\" builtinId=\"0\"/></cellStyles>\
+</styleSheet>";
+
+/// Zip the parts, stored uncompressed
+fn zip(entries: &[(String, Vec<u8>)]) -> Vec<u8> {
+    // 1980-01-01 00:00, the earliest DOS timestamp
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = (1 << 5) | 1;
+
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in entries {
+        let offset = out.len() as u32;
+        let crc = crc32fast::hash(data);
+        let mut header = Vec::new();
+        header.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        header.extend_from_slice(&0u16.to_le_bytes()); // flags
+        header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        header.extend_from_slice(&DOS_TIME.to_le_bytes());
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+
+        out.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        central.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        central.extend_from_slice(&header);
+        central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    out
+}
+
+/// The .xlsx file holding these sheets, in order
+pub fn workbook(sheets: &[Sheet]) -> Vec<u8> {
+    let mut content_types = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+         <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+         <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+         <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+         <Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>",
+    );
+    let mut workbook = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+         xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>",
+    );
+    let mut workbook_rels = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    );
+    let mut parts = Vec::new();
+    for (i, sheet) in sheets.iter().enumerate() {
+        let n = i + 1;
+        content_types.push_str(&format!(
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+            n
+        ));
+        workbook.push_str(&format!(
+            "<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
+            xml_escape(&sheet_name(&sheet.name)),
+            n,
+            n
+        ));
+        workbook_rels.push_str(&format!(
+            "<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet{}.xml\"/>",
+            n, n
+        ));
+        parts.push((format!("xl/worksheets/sheet{}.xml", n), sheet_xml(sheet).into_bytes()));
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str(&format!(
+        "<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/></Relationships>",
+        sheets.len() + 1
+    ));
+
+    let root_rels = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+         <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+         </Relationships>";
+
+    let mut entries = vec![
+        ("[Content_Types].xml".to_string(), content_types.into_bytes()),
+        ("_rels/.rels".to_string(), root_rels.as_bytes().to_vec()),
+        ("xl/workbook.xml".to_string(), workbook.into_bytes()),
+        ("xl/_rels/workbook.xml.rels".to_string(), workbook_rels.into_bytes()),
+        ("xl/styles.xml".to_string(), STYLES_XML.as_bytes().to_vec()),
+    ];
+    entries.extend(parts);
+    zip(&entries)
+}
+
+/// Accounts, transactions, categories and monthly summary sheets.
+/// `transaction_categories` maps transaction ids to their category names.
+pub fn summary_sheets(
+    accounts: &[Account],
+    transactions: &[Transaction],
+    categories: &[Category],
+    transaction_categories: &HashMap<i64, String>,
+    monthly: &[MonthlySummary],
+) -> Vec<Sheet> {
+    let account_names: HashMap<i64, (&str, &str)> =
+        accounts.iter().map(|a| (a.id, (a.name.as_str(), a.currency.as_str()))).collect();
+    let category_names: HashMap<i64, &str> = categories.iter().map(|c| (c.id, c.name.as_str())).collect();
+
+    let accounts_sheet = Sheet {
+        name: "Accounts".to_string(),
+        header: vec!["ID", "Name", "Type", "Bank", "Currency", "Initial Balance", "Current Balance", "Created"],
+        rows: accounts
+            .iter()
+            .map(|a| {
+                vec![
+                    Cell::Number(a.id as f64),
+                    Cell::text(&a.name),
+                    Cell::text(&a.account_type),
+                    Cell::optional(a.bank_name.as_deref()),
+                    Cell::text(&a.currency),
+                    Cell::Amount(a.initial_balance),
+                    Cell::Amount(a.current_balance),
+                    Cell::Date(a.created_at.date_naive()),
+                ]
+            })
+            .collect(),
+    };
+
+    let transactions_sheet = Sheet {
+        name: "Transactions".to_string(),
+        header: vec![
+            "ID", "Date", "Account", "Type", "Status", "Description", "Merchant", "Categories", "Amount", "Currency",
+            "Notes", "Reference",
+        ],
+        rows: transactions
+            .iter()
+            .map(|t| {
+                let (account, currency) = account_names.get(&t.account_id).copied().unwrap_or(("", ""));
+                vec![
+                    Cell::Number(t.id as f64),
+                    Cell::Date(t.transaction_date.date_naive()),
+                    Cell::text(account),
+                    Cell::text(&t.transaction_type),
+                    Cell::text(&t.status),
+                    Cell::optional(t.description.as_deref()),
+                    Cell::optional(t.merchant.as_deref()),
+                    Cell::optional(transaction_categories.get(&t.id).map(String::as_str)),
+                    Cell::Amount(t.amount),
+                    Cell::text(currency),
+                    Cell::optional(t.notes.as_deref()),
+                    Cell::optional(t.reference.as_deref()),
+                ]
+            })
+            .collect(),
+    };
+
+    let categories_sheet = Sheet {
+        name: "Categories".to_string(),
+        header: vec!["ID", "Name", "Parent", "Excluded from Reports"],
+        rows: categories
+            .iter()
+            .map(|c| {
+                vec![
+                    Cell::Number(c.id as f64),
+                    Cell::text(&c.name),
+                    Cell::optional(c.parent_category_id.and_then(|id| category_names.get(&id).copied())),
+                    Cell::text(if c.exclude_from_reports { "Yes" } else { "No" }),
+                ]
+            })
+            .collect(),
+    };
+
+    let monthly_sheet = Sheet {
+        name: "Monthly Summary".to_string(),
+        header: vec!["Month", "Income", "Expenses", "Net Change", "Transactions"],
+        rows: monthly
+            .iter()
+            .map(|m| {
+                vec![
+                    Cell::text(&m.month),
+                    Cell::Amount(m.total_income),
+                    Cell::Amount(m.total_expense),
+                    Cell::Amount(m.net_change),
+                    Cell::Number(m.transaction_count as f64),
+                ]
+            })
+            .collect(),
+    };
+
+    vec![accounts_sheet, transactions_sheet, categories_sheet, monthly_sheet]
+}
+
+/// Income, spending and net change per month, oldest first, counted like
+/// GET /analytics/monthly-summary; every user's when `user_id` is None, over
+/// [start, end) when a range is given
+pub async fn monthly_summary(
+    pool: &SqlitePool,
+    user_id: Option<i64>,
+    range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Result<Vec<MonthlySummary>, sqlx::Error> {
+    let mut filter = match user_id {
+        Some(user_id) => format!("AND account_id IN (SELECT id FROM accounts WHERE user_id = {})", user_id),
+        None => String::new(),
+    };
+    if let Some((start, end)) = range {
+        filter.push_str(&format!(
+            " AND datetime(transaction_date) >= datetime('{}') AND datetime(transaction_date) < datetime('{}')",
+            start.to_rfc3339(),
+            end.to_rfc3339()
+        ));
+    }
+    let sql = format!(
+        "SELECT strftime('%Y-%m', transaction_date) as month,
+                SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE 0.0 END) as total_income,
+                SUM(CASE WHEN transaction_type = 'expense' THEN ABS(amount) ELSE 0.0 END) as total_expense,
+                SUM(CASE WHEN transaction_type = 'income' THEN amount ELSE -ABS(amount) END) as net_change,
+                COUNT(*) as transaction_count
+         FROM transactions t
+         WHERE transfer_group_id IS NULL AND {} {}
+         GROUP BY strftime('%Y-%m', transaction_date)
+         ORDER BY month",
+        exclusions::COUNTED,
+        filter
+    );
+    sqlx::query_as::<_, MonthlySummary>(&sql).fetch_all(pool).await
+}
+
+/// Category names of each transaction, comma-separated, largest split first
+pub async fn transaction_categories(pool: &SqlitePool, user_id: Option<i64>) -> Result<HashMap<i64, String>, sqlx::Error> {
+    let rows: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT tc.transaction_id, c.name FROM transaction_categories tc
+         JOIN categories c ON c.id = tc.category_id
+         WHERE ? IS NULL OR c.user_id = ?
+         ORDER BY tc.transaction_id, tc.amount DESC",
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut names: HashMap<i64, String> = HashMap::new();
+    for (transaction_id, name) in rows {
+        names
+            .entry(transaction_id)
+            .and_modify(|names| {
+                names.push_str(", ");
+                names.push_str(&name);
+            })
+            .or_insert(name);
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn read_u16(data: &[u8], at: usize) -> usize {
+        u16::from_le_bytes([data[at], data[at + 1]]) as usize
+    }
+
+    fn read_u32(data: &[u8], at: usize) -> usize {
+        u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]) as usize
+    }
+
+    /// Names and contents of the stored entries, read through the central directory
+    fn unzip(data: &[u8]) -> HashMap<String, String> {
+        let end = data.len() - 22;
+        assert_eq!(read_u32(data, end), 0x06054b50);
+        let (count, mut at) = (read_u16(data, end + 10), read_u32(data, end + 16));
+        let mut entries = HashMap::new();
+        for _ in 0..count {
+            assert_eq!(read_u32(data, at), 0x02014b50);
+            let (crc, size, name_len) = (read_u32(data, at + 16), read_u32(data, at + 24), read_u16(data, at + 28));
+            let local = read_u32(data, at + 42);
+            let name = String::from_utf8(data[at + 46..at + 46 + name_len].to_vec()).unwrap();
+            let start = local + 30 + name_len;
+            let contents = &data[start..start + size];
+            assert_eq!(crc32fast::hash(contents) as usize, crc, "{}", name);
+            entries.insert(name, String::from_utf8(contents.to_vec()).unwrap());
+            at += 46 + name_len;
+        }
+        entries
+    }
+
+    #[tokio::test]
+    async fn test_summary_workbook_has_a_sheet_per_part() {
+        let pool = testing::pool().await;
+        let user = testing::user().insert(&pool).await;
+        let account = testing::account(user.id).name("Chequing & Savings").balance(100.0).insert(&pool).await;
+        let food = testing::category(&pool, user.id, "Food").await;
+        testing::txn(account.id).amount(12.5).category(food.id).description("Lunch <team>").insert(&pool).await;
+
+        let accounts = vec![account];
+        let transactions: Vec<Transaction> = sqlx::query_as("SELECT * FROM transactions").fetch_all(&pool).await.unwrap();
+        let categories = vec![food];
+        let names = transaction_categories(&pool, Some(user.id)).await.unwrap();
+        let monthly = monthly_summary(&pool, Some(user.id), None).await.unwrap();
+        assert_eq!((monthly.len(), monthly[0].total_expense), (1, 12.5));
+
+        let file = workbook(&summary_sheets(&accounts, &transactions, &categories, &names, &monthly));
+        let entries = unzip(&file);
+        assert_eq!(entries.len(), 9);
+        let workbook = &entries["xl/workbook.xml"];
+        for name in ["Accounts", "Transactions", "Categories", "Monthly Summary"] {
+            assert!(workbook.contains(&format!("name=\"{}\"", name)), "{}", name);
+        }
+        assert!(entries["xl/worksheets/sheet1.xml"].contains("Chequing &amp; Savings"));
+        let transactions_xml = &entries["xl/worksheets/sheet2.xml"];
+        assert!(transactions_xml.contains("Lunch &lt;team&gt;"));
+        assert!(transactions_xml.contains("<t xml:space=\"preserve\">Food</t>"));
+        assert!(transactions_xml.contains(&format!("s=\"{}\"><v>12.5</v>", STYLE_AMOUNT)));
+
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(date_serial(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()), 45292);
+    }
+}